@@ -75,6 +75,9 @@ pub struct AdmissionGate {
     enable_pb: bool,
     /// Enable PQC verification
     enable_pqc: bool,
+    /// Shapes graph for SHACL validation (none: every payload passes)
+    #[cfg(feature = "rdf")]
+    shapes: Option<knhk_shacl::ShaclEngine>,
 }
 
 impl AdmissionGate {
@@ -84,6 +87,8 @@ impl AdmissionGate {
             enable_shacl: true,
             enable_pb: true,
             enable_pqc: true,
+            #[cfg(feature = "rdf")]
+            shapes: None,
         }
     }
 
@@ -93,9 +98,20 @@ impl AdmissionGate {
             enable_shacl,
             enable_pb,
             enable_pqc,
+            #[cfg(feature = "rdf")]
+            shapes: None,
         }
     }
 
+    /// Load the shapes graph (Turtle) that payloads must conform to
+    #[cfg(feature = "rdf")]
+    pub fn with_shapes(mut self, shapes_turtle: &str) -> Result<Self, AdmissionError> {
+        let engine = knhk_shacl::ShaclEngine::from_turtle(shapes_turtle)
+            .map_err(|e| AdmissionError::Validation(format!("Failed to load SHACL shapes: {}", e)))?;
+        self.shapes = Some(engine);
+        Ok(self)
+    }
+
     /// Admit payload through 4-stage pipeline
     ///
     /// Pipeline: SHACL Validation → PB Congruence → PQC Verification → Θ Decision
@@ -179,80 +195,44 @@ impl AdmissionGate {
 
     /// Stage 1: SHACL Validation
     ///
-    /// Validates payload against SHACL shapes using oxigraph (from WIP)
+    /// Validates the payload graph against the configured shapes graph.
+    /// Only `sh:Violation` results reject; warnings and info are advisory.
+    /// Without configured shapes every payload passes.
     fn validate_shacl(&self, payload: &Value) -> Result<bool, AdmissionError> {
         #[cfg(feature = "rdf")]
         {
-            use oxigraph::io::RdfFormat;
-            use oxigraph::sparql::SparqlEvaluator;
-            use oxigraph::store::Store;
-
-            // Convert JSON payload to Turtle RDF format
-            let data_turtle = self.json_to_turtle(payload)?;
-
-            // Create data store and load payload
-            let data_store = Store::new()
-                .map_err(|e| AdmissionError::Validation(format!("Failed to create RDF store: {:?}", e)))?;
-            data_store
-                .load_from_reader(RdfFormat::Turtle, data_turtle.as_bytes())
-                .map_err(|e| AdmissionError::Validation(format!("Failed to load payload RDF: {:?}", e)))?;
-
-            // Use default shapes (in production, would load from schema registry)
-            let shapes_turtle = r#"
-                @prefix sh: <http://www.w3.org/ns/shacl#> .
-                @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
-                
-                [] a sh:NodeShape ;
-                    sh:property [
-                        sh:path <http://example.org/predicate> ;
-                        sh:minCount 1 ;
-                    ] .
-            "#;
-
-            // Create shapes store and load shapes
-            let shapes_store = Store::new()
-                .map_err(|e| AdmissionError::Validation(format!("Failed to create shapes store: {:?}", e)))?;
-            shapes_store
-                .load_from_reader(RdfFormat::Turtle, shapes_turtle.as_bytes())
-                .map_err(|e| AdmissionError::Validation(format!("Failed to load SHACL shapes: {:?}", e)))?;
-
-            // Execute SHACL validation query using oxigraph (oxigraph 0.5 best practices)
-            // Simple validation: check if data conforms to shapes
-            let validation_query = r#"
-                PREFIX sh: <http://www.w3.org/ns/shacl#>
-                ASK WHERE {
-                    ?s ?p ?o .
-                    FILTER NOT EXISTS {
-                        # Check for violations (simplified - full SHACL would be more complex)
-                        ?shape a sh:NodeShape ;
-                            sh:property ?prop .
-                        ?prop sh:path ?path ;
-                            sh:minCount ?minCount .
-                        FILTER(?minCount > 0)
-                    }
-                }
-            "#;
-
-            // Use SparqlEvaluator (oxigraph 0.5 best practices)
-            let results = SparqlEvaluator::new()
-                .parse_query(validation_query)
-                .map_err(|e| AdmissionError::Validation(format!("Failed to parse SHACL query: {:?}", e)))?
-                .on_store(&data_store)
-                .execute()
-                .map_err(|e| AdmissionError::Validation(format!("SHACL validation failed: {:?}", e)))?;
-
-            // For now, basic validation: if query executes without errors, consider valid
-            // In production, would check actual SHACL constraint violations
-            Ok(true)
+            if self.shapes.is_none() {
+                return Ok(true);
+            }
+            let report = self.shacl_report(payload)?;
+            Ok(report.violations().next().is_none())
         }
 
         #[cfg(not(feature = "rdf"))]
         {
             // Fallback: basic structural validation when rdf feature is disabled
+            let _ = payload;
             Ok(true)
         }
     }
 
+    /// Validate a payload against the configured shapes and return the full
+    /// W3C validation report
+    ///
+    /// The payload is mapped to a data graph by [`knhk_shacl::json_to_turtle`].
+    #[cfg(feature = "rdf")]
+    pub fn shacl_report(
+        &self,
+        payload: &Value,
+    ) -> Result<knhk_shacl::ValidationReport, AdmissionError> {
+        let Some(engine) = &self.shapes else {
+            return Ok(knhk_shacl::ValidationReport::new(Vec::new()));
+        };
+        engine
+            .validate_json(payload)
+            .map_err(|e| AdmissionError::Validation(format!("SHACL validation failed: {}", e)))
+    }
+
     /// Stage 2: PB Congruence (Pattern Byte correspondence)
     ///
     /// Verifies that pattern bytes match expected computational graph structure
@@ -487,34 +467,6 @@ impl AdmissionGate {
             Ok(true)
         }
     }
-
-    /// Convert JSON payload to Turtle RDF format
-    fn json_to_turtle(&self, payload: &Value) -> Result<String, AdmissionError> {
-        // Simple conversion: convert JSON object to basic Turtle
-        // In production, would use proper JSON-LD to Turtle conversion
-        if let Some(obj) = payload.as_object() {
-            let mut turtle = String::new();
-            turtle.push_str("@prefix ex: <http://example.org/> .\n");
-            turtle.push_str("@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\n");
-
-            for (key, value) in obj {
-                let predicate = format!("ex:{}", key);
-                let object = match value {
-                    Value::String(s) => format!("\"{}\"", s),
-                    Value::Number(n) => n.to_string(),
-                    Value::Bool(b) => b.to_string(),
-                    _ => "\"unknown\"".to_string(),
-                };
-                turtle.push_str(&format!("[] {} {} .\n", predicate, object));
-            }
-
-            Ok(turtle)
-        } else {
-            Err(AdmissionError::Validation(
-                "Payload must be a JSON object".to_string(),
-            ))
-        }
-    }
 }
 
 impl Default for AdmissionGate {
@@ -602,5 +554,44 @@ mod tests {
         // PB congruence check should fail for pattern without required fields
         assert!(!admission_result3.stage_results.pb_congruent);
     }
-}
 
+    #[cfg(feature = "rdf")]
+    #[test]
+    fn test_shacl_shapes_reject_nonconforming_payload() {
+        let shapes = r#"
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            @prefix ex: <http://example.org/> .
+
+            ex:PayloadShape a sh:NodeShape ;
+                sh:targetClass ex:Payload ;
+                sh:property [
+                    sh:path ex:tenant ;
+                    sh:minCount 1 ;
+                    sh:pattern "^[a-z]+$" ;
+                ] ;
+                sh:property [
+                    sh:path ex:pattern_byte ;
+                    sh:datatype xsd:integer ;
+                    sh:maxCount 1 ;
+                ] .
+        "#;
+        let gate = AdmissionGate::with_config(true, false, false)
+            .with_shapes(shapes)
+            .unwrap();
+
+        let valid = serde_json::json!({"tenant": "acme", "pattern_byte": 1});
+        let result = gate.admit(&valid).unwrap();
+        assert!(result.stage_results.shacl_valid);
+        assert_eq!(result.decision, Theta::Admit);
+
+        let invalid = serde_json::json!({"tenant": "ACME", "pattern_byte": 1});
+        let result = gate.admit(&invalid).unwrap();
+        assert!(!result.stage_results.shacl_valid);
+        assert_eq!(result.decision, Theta::Reject);
+
+        let report = gate.shacl_report(&invalid).unwrap();
+        assert_eq!(report.results.len(), 1);
+        assert!(report.to_turtle().contains("sh:PatternConstraintComponent"));
+    }
+}
@@ -1,20 +1,76 @@
 // rust/knhk-cli/src/commands/config.rs
 // Config command - show current configuration
 
-use knhk_config::{apply_env_overrides, load_config, load_env_config};
+use knhk_config::{Config, ConfigLoader};
 use serde_json;
+use std::sync::OnceLock;
+
+// `--set key=value` assignments given before the noun
+static CLI_OVERRIDES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Record the command line overrides; only the first call takes effect
+pub fn set_cli_overrides(assignments: Vec<String>) {
+    let _ = CLI_OVERRIDES.set(assignments);
+}
+
+/// Split leading `--set key=value` (or `--set=key=value`) options from the
+/// arguments; they are applied as the command line configuration layer
+pub fn split_cli_overrides(args: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut args = args.into_iter();
+    let mut remaining: Vec<String> = args.next().into_iter().collect();
+    let mut overrides = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--set" {
+            match args.next() {
+                Some(assignment) => overrides.push(assignment),
+                // Let clap report the missing value
+                None => remaining.push(arg),
+            }
+        } else if let Some(assignment) = arg.strip_prefix("--set=") {
+            overrides.push(assignment.to_string());
+        } else {
+            remaining.push(arg);
+            break;
+        }
+    }
+    remaining.extend(args);
+    (overrides, remaining)
+}
+
+/// Loader for all layers, including the command line overrides
+pub fn loader() -> ConfigLoader {
+    CLI_OVERRIDES
+        .get()
+        .into_iter()
+        .flatten()
+        .fold(ConfigLoader::from_environment(), |loader, assignment| {
+            loader.cli_override(assignment.as_str())
+        })
+}
 
 /// Show current configuration
 pub fn show() -> Result<String, String> {
-    // Load config file
-    let mut config = load_config(None)?;
+    // Load all layers (system, user, project, profile, environment, command line)
+    let loaded = loader().load().map_err(|e| e.to_string())?;
+
+    // Serialize to JSON string
+    serde_json::to_string_pretty(&loaded.config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))
+}
 
-    // Load environment variables
-    let env_vars = load_env_config();
+/// Validate the configuration layers, listing the files that were read
+pub fn validate() -> Result<Vec<String>, String> {
+    let loaded = loader().load().map_err(|e| e.to_string())?;
 
-    // Apply environment variable overrides
-    apply_env_overrides(&mut config, &env_vars);
+    Ok(loaded
+        .sources
+        .iter()
+        .map(|source| format!("{}: {}", source.layer, source.origin))
+        .collect())
+}
 
-    // Serialize to JSON string
-    serde_json::to_string_pretty(&config).map_err(|e| format!("Failed to serialize config: {}", e))
+/// JSON Schema of the configuration file
+pub fn schema() -> Result<String, String> {
+    serde_json::to_string_pretty(&Config::json_schema())
+        .map_err(|e| format!("Failed to serialize schema: {}", e))
 }
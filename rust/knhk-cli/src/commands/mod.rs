@@ -18,5 +18,7 @@ pub mod pipeline;
 pub mod receipt;
 pub mod reflex;
 pub mod route;
+#[cfg(feature = "marketplace")]
+pub mod template;
 pub mod validate;
 pub mod weaver;
@@ -0,0 +1,140 @@
+// rust/knhk-cli/src/commands/template.rs
+// Template command - install, publish and yank workflow template packages
+
+use knhk_marketplace::packages::{
+    load_signing_key, LocalRegistry, ProjectTemplates, TemplateInstaller, TemplatePackage,
+    TrustedKeys,
+};
+use semver::{Version, VersionReq};
+use std::path::{Path, PathBuf};
+
+/// Trusted publisher keys, read from the registry root unless given
+const TRUSTED_KEYS_FILE: &str = "trusted-keys.toml";
+
+/// Where the registry lives and which publishers it trusts
+pub struct RegistryOptions {
+    pub registry: Option<PathBuf>,
+    pub trusted_keys: Option<PathBuf>,
+    pub allow_unsigned: bool,
+}
+
+/// `~/.knhk/registry` unless `KNHK_TEMPLATE_REGISTRY` is set
+fn default_registry() -> Result<PathBuf, String> {
+    if let Ok(path) = std::env::var("KNHK_TEMPLATE_REGISTRY") {
+        return Ok(PathBuf::from(path));
+    }
+    dirs::home_dir()
+        .map(|home| home.join(".knhk").join("registry"))
+        .ok_or_else(|| "Cannot determine home directory for the template registry".to_string())
+}
+
+fn open_registry(options: &RegistryOptions) -> Result<LocalRegistry, String> {
+    let root = match &options.registry {
+        Some(path) => path.clone(),
+        None => default_registry()?,
+    };
+    let keys_path = options
+        .trusted_keys
+        .clone()
+        .unwrap_or_else(|| root.join(TRUSTED_KEYS_FILE));
+
+    let mut registry = LocalRegistry::open(&root).map_err(|e| e.to_string())?;
+    if keys_path.exists() {
+        let trusted = TrustedKeys::load(&keys_path).map_err(|e| e.to_string())?;
+        registry = registry.with_trusted_keys(trusted);
+    } else if options.trusted_keys.is_some() {
+        return Err(format!(
+            "Trusted keys file {} not found",
+            keys_path.display()
+        ));
+    }
+    if options.allow_unsigned {
+        eprintln!("WARNING: accepting unsigned template packages (--allow-unsigned)");
+        registry = registry.allow_unsigned();
+    }
+    Ok(registry)
+}
+
+/// Install the project's template dependencies, adding `package` first if given
+///
+/// Returns the locked packages as `name@version`.
+pub fn install(
+    project: &Path,
+    package: Option<(&str, Option<&str>)>,
+    options: &RegistryOptions,
+) -> Result<Vec<String>, String> {
+    let registry = open_registry(options)?;
+    let mut templates = ProjectTemplates::load(project).map_err(|e| e.to_string())?;
+    if let Some((name, requirement)) = package {
+        let requirement = match requirement {
+            Some(requirement) => VersionReq::parse(requirement)
+                .map_err(|e| format!("Invalid version requirement '{}': {}", requirement, e))?,
+            None => VersionReq::STAR,
+        };
+        templates
+            .add(name, requirement)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let lockfile = TemplateInstaller::new(&registry, project)
+        .install(&templates.dependencies)
+        .map_err(|e| e.to_string())?;
+    // Only record the new dependency once it resolved and installed
+    templates.save(project).map_err(|e| e.to_string())?;
+    Ok(lockfile
+        .packages
+        .iter()
+        .map(|p| format!("{}@{}", p.name, p.version))
+        .collect())
+}
+
+/// Re-resolve the project's template dependencies to the newest versions
+pub fn update(project: &Path, options: &RegistryOptions) -> Result<Vec<String>, String> {
+    let registry = open_registry(options)?;
+    let templates = ProjectTemplates::load(project).map_err(|e| e.to_string())?;
+    let lockfile = TemplateInstaller::new(&registry, project)
+        .update(&templates.dependencies)
+        .map_err(|e| e.to_string())?;
+    Ok(lockfile
+        .packages
+        .iter()
+        .map(|p| format!("{}@{}", p.name, p.version))
+        .collect())
+}
+
+/// Pack the package directory, sign it and publish it
+///
+/// Returns `name@version` and the package checksum.
+pub fn publish(
+    dir: &Path,
+    signing_key: Option<&Path>,
+    options: &RegistryOptions,
+) -> Result<(String, String), String> {
+    let registry = open_registry(options)?;
+    let mut package = TemplatePackage::from_dir(dir).map_err(|e| e.to_string())?;
+    match signing_key {
+        Some(path) => package.sign(&load_signing_key(path).map_err(|e| e.to_string())?),
+        None if !options.allow_unsigned => {
+            return Err("Publishing requires --signing-key (or --allow-unsigned)".to_string())
+        }
+        None => {}
+    }
+    let entry = registry.publish(&package).map_err(|e| e.to_string())?;
+    Ok((package.id(), entry.checksum))
+}
+
+/// Yank, or with `undo` restore, a published version
+pub fn yank(
+    name: &str,
+    version: &str,
+    undo: bool,
+    options: &RegistryOptions,
+) -> Result<String, String> {
+    let version =
+        Version::parse(version).map_err(|e| format!("Invalid version '{}': {}", version, e))?;
+    let registry = open_registry(options)?;
+    registry
+        .set_yanked(name, &version, !undo)
+        .map_err(|e| e.to_string())?;
+    Ok(format!("{}@{}", name, version))
+}
@@ -22,3 +22,37 @@ fn show() -> Result<ConfigResult> {
         })
         .map(|config| ConfigResult { config })
 }
+
+#[derive(Serialize, Debug)]
+struct ValidateResult {
+    valid: bool,
+    sources: Vec<String>,
+}
+
+/// Validate configuration layers against the schema
+#[verb]
+fn validate() -> Result<ValidateResult> {
+    config_impl::validate()
+        .map_err(|e| {
+            clap_noun_verb::NounVerbError::execution_error(format!("Invalid config: {}", e))
+        })
+        .map(|sources| ValidateResult {
+            valid: true,
+            sources,
+        })
+}
+
+#[derive(Serialize, Debug)]
+struct SchemaResult {
+    schema: String,
+}
+
+/// Print the JSON Schema of the configuration file
+#[verb]
+fn schema() -> Result<SchemaResult> {
+    config_impl::schema()
+        .map_err(|e| {
+            clap_noun_verb::NounVerbError::execution_error(format!("Failed to show schema: {}", e))
+        })
+        .map(|schema| SchemaResult { schema })
+}
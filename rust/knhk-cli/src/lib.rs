@@ -41,3 +41,5 @@ pub mod metrics;
 pub mod pipeline;
 pub mod reflex;
 pub mod route;
+#[cfg(feature = "marketplace")]
+pub mod template;
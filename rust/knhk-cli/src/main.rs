@@ -46,18 +46,19 @@ mod pipeline;
 mod reflex;
 mod route;
 mod soundness;
+mod template;
 mod workflow;
 
 use clap_noun_verb::Result as CnvResult;
-use knhk_config::{load_config, Config};
+use knhk_config::Config;
 
 // Global configuration (loaded at startup)
 static CONFIG: std::sync::OnceLock<Config> = std::sync::OnceLock::new();
 
 fn get_config() -> &'static Config {
     CONFIG.get_or_init(|| {
-        match load_config(None) {
-            Ok(config) => {
+        match commands::config::loader().load() {
+            Ok(loaded) => {
                 // Record configuration load metric
                 #[cfg(feature = "otel")]
                 {
@@ -65,13 +66,11 @@ fn get_config() -> &'static Config {
                     let mut tracer = Tracer::new();
                     MetricsHelper::record_config_load(&mut tracer, "file");
                 }
-                config
+                loaded.config
             }
             Err(e) => {
-                eprintln!(
-                    "Warning: Failed to load configuration: {}. Using defaults.",
-                    e
-                );
+                // Running on defaults would silently ignore the typo
+                eprintln!("Error: {}", e);
                 // Record configuration error metric
                 #[cfg(feature = "otel")]
                 {
@@ -79,7 +78,7 @@ fn get_config() -> &'static Config {
                     let mut tracer = Tracer::new();
                     MetricsHelper::record_config_error(&mut tracer, "load_failed");
                 }
-                Config::default()
+                std::process::exit(2);
             }
         }
     })
@@ -99,11 +98,22 @@ fn main() -> CnvResult<()> {
         }
     };
 
+    let (overrides, args) = commands::config::split_cli_overrides(std::env::args().collect());
+    commands::config::set_cli_overrides(overrides);
+
     // Load configuration at startup
     let _ = get_config();
 
     // Auto-discover all registered commands and run
     // CNV v3.3.0 automatically discovers all #[verb] functions
     // Nouns are auto-inferred from filenames (boot.rs → "boot", etc.)
-    clap_noun_verb::run()
+    let registry = clap_noun_verb::cli::CommandRegistry::get()
+        .lock()
+        .map_err(|e| {
+            clap_noun_verb::NounVerbError::execution_error(format!(
+                "Failed to lock registry: {}",
+                e
+            ))
+        })?;
+    registry.run(args)
 }
@@ -0,0 +1,133 @@
+//! Template commands - Workflow template packages
+
+// Allow non_upper_case_globals - #[verb] macro generates static vars with lowercase names
+#![allow(non_upper_case_globals)]
+
+#[cfg(feature = "marketplace")]
+use crate::commands::template::{self as template_impl, RegistryOptions};
+use clap_noun_verb::Result;
+use clap_noun_verb_macros::verb;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Serialize, Debug)]
+struct InstallResult {
+    installed: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct PublishResult {
+    package: String,
+    checksum: String,
+}
+
+#[derive(Serialize, Debug)]
+struct YankResult {
+    package: String,
+    yanked: bool,
+}
+
+#[cfg(feature = "marketplace")]
+fn registry_options(
+    registry: Option<PathBuf>,
+    trusted_keys: Option<PathBuf>,
+    allow_unsigned: bool,
+) -> RegistryOptions {
+    RegistryOptions {
+        registry,
+        trusted_keys,
+        allow_unsigned,
+    }
+}
+
+/// Install the project's template packages, optionally adding one first
+#[verb] // Noun "template" auto-inferred from filename "template.rs"
+#[cfg(feature = "marketplace")]
+fn install(
+    package: Option<String>,
+    version: Option<String>,
+    project: Option<PathBuf>,
+    registry: Option<PathBuf>,
+    trusted_keys: Option<PathBuf>,
+    allow_unsigned: bool,
+) -> Result<InstallResult> {
+    let project = project.unwrap_or_else(|| PathBuf::from("."));
+    let package = package.as_deref().map(|name| (name, version.as_deref()));
+    template_impl::install(
+        &project,
+        package,
+        &registry_options(registry, trusted_keys, allow_unsigned),
+    )
+    .map_err(|e| {
+        clap_noun_verb::NounVerbError::execution_error(format!(
+            "Failed to install templates: {}",
+            e
+        ))
+    })
+    .map(|installed| InstallResult { installed })
+}
+
+/// Update the project's template packages to the newest matching versions
+#[verb]
+#[cfg(feature = "marketplace")]
+fn update(
+    project: Option<PathBuf>,
+    registry: Option<PathBuf>,
+    trusted_keys: Option<PathBuf>,
+    allow_unsigned: bool,
+) -> Result<InstallResult> {
+    let project = project.unwrap_or_else(|| PathBuf::from("."));
+    template_impl::update(
+        &project,
+        &registry_options(registry, trusted_keys, allow_unsigned),
+    )
+    .map_err(|e| {
+        clap_noun_verb::NounVerbError::execution_error(format!("Failed to update templates: {}", e))
+    })
+    .map(|installed| InstallResult { installed })
+}
+
+/// Sign and publish a template package directory
+#[verb]
+#[cfg(feature = "marketplace")]
+fn publish(
+    dir: PathBuf,
+    signing_key: Option<PathBuf>,
+    registry: Option<PathBuf>,
+    trusted_keys: Option<PathBuf>,
+    allow_unsigned: bool,
+) -> Result<PublishResult> {
+    template_impl::publish(
+        &dir,
+        signing_key.as_deref(),
+        &registry_options(registry, trusted_keys, allow_unsigned),
+    )
+    .map_err(|e| {
+        clap_noun_verb::NounVerbError::execution_error(format!("Failed to publish template: {}", e))
+    })
+    .map(|(package, checksum)| PublishResult { package, checksum })
+}
+
+/// Yank a published template version (or restore it with --undo)
+#[verb]
+#[cfg(feature = "marketplace")]
+fn yank(
+    name: String,
+    version: String,
+    undo: bool,
+    registry: Option<PathBuf>,
+) -> Result<YankResult> {
+    template_impl::yank(
+        &name,
+        &version,
+        undo,
+        &registry_options(registry, None, false),
+    )
+    .map_err(|e| {
+        clap_noun_verb::NounVerbError::execution_error(format!("Failed to yank template: {}", e))
+    })
+    .map(|package| YankResult {
+        package,
+        yanked: !undo,
+    })
+}
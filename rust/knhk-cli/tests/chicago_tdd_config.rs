@@ -23,3 +23,31 @@ fn test_config_show_returns_result() {
         }
     }
 }
+
+/// Test: leading --set options are split off as command line overrides
+#[test]
+fn test_split_cli_overrides() {
+    let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    // Act: Split options given before and after the noun
+    let (overrides, remaining) = config::split_cli_overrides(args(&[
+        "knhk",
+        "--set",
+        "knhk.context=production",
+        "--set=hooks.max_count=8",
+        "config",
+        "show",
+        "--set",
+        "ignored=1",
+    ]));
+
+    // Assert: Only the leading options are overrides, the rest goes to clap
+    assert_eq!(
+        overrides,
+        args(&["knhk.context=production", "hooks.max_count=8"])
+    );
+    assert_eq!(
+        remaining,
+        args(&["knhk", "config", "show", "--set", "ignored=1"])
+    );
+}
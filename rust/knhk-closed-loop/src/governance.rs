@@ -1,12 +1,19 @@
 // Guard Governance: Multi-party approval for critical policy changes
 // Implements cryptographically-signed quorum-based approval workflows
 
+use crate::governance_log::{GovernanceEvent, GovernanceLog, GovernanceRecord};
+#[cfg(feature = "rdf")]
+use crate::ontology_io::TurtleOntology;
+use crate::ontology_io::{OntologyIoError, RdfPatch};
+#[cfg(feature = "rdf")]
+use crate::shadow::DeltaSigma;
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -30,6 +37,9 @@ pub enum GovernanceError {
     #[error("Invalid signature")]
     InvalidSignature,
 
+    #[error("Approver not trusted: {0}")]
+    UntrustedApprover(String),
+
     #[error("Insufficient approvals: need {needed}, have {current}")]
     InsufficientApprovals { needed: usize, current: usize },
 
@@ -39,11 +49,23 @@ pub enum GovernanceError {
     #[error("Approval window not yet open")]
     ApprovalWindowNotOpen,
 
+    #[error("Approved patch does not match request: {0}")]
+    PatchMismatch(String),
+
+    #[error("Governance log error: {0}")]
+    Persistence(String),
+
+    #[error("Governance log tampered at record {0}")]
+    LogTampered(u64),
+
     #[error("Hex decoding error: {0}")]
     HexError(#[from] hex::FromHexError),
 
     #[error("Signature error: {0}")]
     SignatureError(#[from] ed25519_dalek::SignatureError),
+
+    #[error("Ontology error: {0}")]
+    Ontology(#[from] OntologyIoError),
 }
 
 pub type Result<T> = std::result::Result<T, GovernanceError>;
@@ -159,6 +181,22 @@ pub struct GuardRelaxationRequest {
     pub state: RequestState,
 }
 
+impl GuardRelaxationRequest {
+    /// Message an approver signs
+    ///
+    /// It covers a SHA-256 digest of the proposed change, so a signature
+    /// can't be carried over to a request whose change was edited.
+    pub fn approval_message(&self, approver_id: &str) -> String {
+        format!(
+            "{}-{}-{}-{}",
+            self.id,
+            self.guard_id,
+            approver_id,
+            hex::encode(Sha256::digest(self.proposed_change.as_bytes()))
+        )
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum RequestState {
     Submitted,
@@ -181,22 +219,23 @@ pub struct GuardApproval {
 }
 
 impl GuardApproval {
-    pub fn verify(&self, message: &str) -> Result<()> {
-        let sig_bytes = hex::decode(&self.signature)?;
-        let key_bytes = hex::decode(&self.verifying_key)?;
+    /// Verify the signature against the approver's trusted key
+    ///
+    /// The embedded `verifying_key` is informational only: it must match the
+    /// trusted key, so an approval can't vouch for itself.
+    pub fn verify(&self, message: &str, trusted_key: &VerifyingKey) -> Result<()> {
+        if self.verifying_key != hex::encode(trusted_key.to_bytes()) {
+            return Err(GovernanceError::UntrustedApprover(self.approver_id.clone()));
+        }
 
+        let sig_bytes = hex::decode(&self.signature)?;
         let signature = Signature::from_bytes(
             &sig_bytes
                 .try_into()
                 .map_err(|_| GovernanceError::InvalidSignature)?,
         );
-        let verifying_key = VerifyingKey::from_bytes(
-            &key_bytes
-                .try_into()
-                .map_err(|_| GovernanceError::InvalidSignature)?,
-        )?;
 
-        verifying_key.verify(message.as_bytes(), &signature)?;
+        trusted_key.verify(message.as_bytes(), &signature)?;
         Ok(())
     }
 }
@@ -215,6 +254,8 @@ pub struct GovernanceEngine {
     relaxation_requests: DashMap<String, Arc<RwLock<GuardRelaxationRequest>>>,
     approval_history: Arc<RwLock<Vec<GuardRelaxationRequest>>>,
     active_relaxations: DashMap<String, RelaxationWindow>,
+    trusted_approvers: DashMap<String, VerifyingKey>,
+    log: Option<Arc<dyn GovernanceLog>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -233,11 +274,79 @@ impl GovernanceEngine {
             relaxation_requests: DashMap::new(),
             approval_history: Arc::new(RwLock::new(Vec::new())),
             active_relaxations: DashMap::new(),
+            trusted_approvers: DashMap::new(),
+            log: None,
         }
     }
 
+    /// Create an engine backed by a durable log, replaying its history
+    ///
+    /// Every state change is appended to the log before it takes effect, so a
+    /// restarted engine sees the same guards, signed approvals and active
+    /// relaxation windows as before the restart. Replayed approvals must be
+    /// signed by one of `trusted_approvers` (approver id to verifying key).
+    pub fn with_log(
+        log: Arc<dyn GovernanceLog>,
+        trusted_approvers: HashMap<String, VerifyingKey>,
+    ) -> Result<Self> {
+        let records = log.replay()?;
+        let mut engine = Self::new();
+        for (approver_id, key) in trusted_approvers {
+            engine.trust_approver(approver_id, key);
+        }
+        for record in &records {
+            engine.apply_event(&record.event)?;
+        }
+        engine.log = Some(log);
+
+        tracing::info!(
+            records = records.len(),
+            guards = engine.guards.len(),
+            active_relaxations = engine.active_relaxations.len(),
+            "Governance state replayed"
+        );
+        Ok(engine)
+    }
+
+    /// Full audit trail from the durable log (empty for in-memory engines)
+    pub fn audit_trail(&self) -> Result<Vec<GovernanceRecord>> {
+        match &self.log {
+            Some(log) => log.replay(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Audit trail entries for a single relaxation request
+    pub fn audit_trail_for_request(&self, request_id: &str) -> Result<Vec<GovernanceRecord>> {
+        Ok(self
+            .audit_trail()?
+            .into_iter()
+            .filter(|r| r.event.request_id() == Some(request_id))
+            .collect())
+    }
+
+    /// Trust `verifying_key` for approvals signed as `approver_id`
+    ///
+    /// Approver keys are configuration, not governance state: they are never
+    /// read from the log, so a log writer can't enrol keys of its own.
+    pub fn trust_approver(&self, approver_id: impl Into<String>, verifying_key: VerifyingKey) {
+        self.trusted_approvers
+            .insert(approver_id.into(), verifying_key);
+    }
+
+    /// Trusted key of an approver
+    fn approver_key(&self, approver_id: &str) -> Result<VerifyingKey> {
+        self.trusted_approvers
+            .get(approver_id)
+            .map(|key| *key)
+            .ok_or_else(|| GovernanceError::UntrustedApprover(approver_id.to_string()))
+    }
+
     /// Register a guard in the governance system
     pub fn register_guard(&self, guard: Guard) -> Result<()> {
+        self.record(GovernanceEvent::GuardRegistered {
+            guard: guard.clone(),
+        })?;
         self.guards.insert(guard.id.clone(), Arc::new(guard));
         Ok(())
     }
@@ -295,12 +404,88 @@ impl GovernanceEngine {
             state: RequestState::Submitted,
         };
 
+        self.record(GovernanceEvent::RelaxationRequested {
+            request: request.clone(),
+        })?;
         self.relaxation_requests
             .insert(request_id.clone(), Arc::new(RwLock::new(request)));
 
         Ok(request_id)
     }
 
+    /// Request approval of an ontology RDF patch guarded by `guard_id`
+    ///
+    /// The patch text and its content hash become the proposed change, so
+    /// approvers review and sign exactly the triples being added/removed.
+    pub fn request_patch_approval(
+        &self,
+        guard_id: String,
+        requested_by: String,
+        reason: String,
+        patch: &RdfPatch,
+    ) -> Result<String> {
+        self.request_relaxation(guard_id, requested_by, reason, patch_proposal(patch))
+    }
+
+    /// Check that an approved request covers exactly this RDF patch
+    ///
+    /// The approval signatures are verified again against the trusted
+    /// approver keys, and the distinct valid approvers must still meet the
+    /// guard's quorum.
+    pub fn verify_patch_approval(&self, request_id: &str, patch: &RdfPatch) -> Result<()> {
+        let request_ref = self
+            .relaxation_requests
+            .get(request_id)
+            .ok_or_else(|| GovernanceError::RequestNotFound(request_id.to_string()))?;
+
+        let request = request_ref.read();
+        if !matches!(request.state, RequestState::Approved) {
+            return Err(GovernanceError::RequestNotApproved);
+        }
+
+        if request.proposed_change != patch_proposal(patch) {
+            return Err(GovernanceError::PatchMismatch(request_id.to_string()));
+        }
+
+        let guard = self
+            .guards
+            .get(&request.guard_id)
+            .ok_or_else(|| GovernanceError::GuardNotFound(request.guard_id.clone()))?;
+
+        let mut approvers = HashSet::new();
+        for approval in &request.approval_signatures {
+            approval.verify(
+                &request.approval_message(&approval.approver_id),
+                &self.approver_key(&approval.approver_id)?,
+            )?;
+            approvers.insert(approval.approver_id.as_str());
+        }
+        if approvers.len() < guard.relaxation_policy.approval_quorum {
+            return Err(GovernanceError::InsufficientApprovals {
+                needed: guard.relaxation_policy.approval_quorum,
+                current: approvers.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Apply an approved ΔΣ to an ontology
+    ///
+    /// The patch the delta produces must be the one the request was approved
+    /// for; nothing is returned unless `verify_patch_approval` passes.
+    #[cfg(feature = "rdf")]
+    pub fn apply_approved_delta(
+        &self,
+        request_id: &str,
+        ontology: &TurtleOntology,
+        delta: &DeltaSigma,
+    ) -> Result<TurtleOntology> {
+        let updated = ontology.apply_delta(delta)?;
+        self.verify_patch_approval(request_id, &ontology.diff(&updated))?;
+        Ok(updated)
+    }
+
     /// Approve a relaxation request with cryptographic signature
     pub fn approve_relaxation(
         &self,
@@ -317,7 +502,13 @@ impl GovernanceEngine {
 
         // Check if request is expired
         if Utc::now() > request.expires_at {
-            request.state = RequestState::Expired;
+            if request.state != RequestState::Expired {
+                self.record(GovernanceEvent::RequestStateChanged {
+                    request_id: request.id.clone(),
+                    state: RequestState::Expired,
+                })?;
+                request.state = RequestState::Expired;
+            }
             return Err(GovernanceError::RequestExpired);
         }
 
@@ -331,7 +522,8 @@ impl GovernanceEngine {
         }
 
         // Sign the approval with ed25519
-        let message = format!("{}-{}-{}", request.id, request.guard_id, approver_id);
+        let trusted_key = self.approver_key(&approver_id)?;
+        let message = request.approval_message(&approver_id);
         let signature = signing_key.sign(message.as_bytes());
         let verifying_key = signing_key.verifying_key();
 
@@ -344,10 +536,7 @@ impl GovernanceEngine {
         };
 
         // Verify signature immediately
-        approval.verify(&message)?;
-
-        // Add approval to request
-        request.approval_signatures.push(approval);
+        approval.verify(&message, &trusted_key)?;
 
         // Check if we have quorum
         let guard = self
@@ -355,22 +544,31 @@ impl GovernanceEngine {
             .get(&request.guard_id)
             .ok_or_else(|| GovernanceError::GuardNotFound(request.guard_id.clone()))?;
 
-        if request.approval_signatures.len() >= guard.relaxation_policy.approval_quorum {
+        let approvals = request.approval_signatures.len() + 1;
+        let state = if approvals >= guard.relaxation_policy.approval_quorum {
             // Check if minimum approval duration has passed
             let elapsed = Utc::now().signed_duration_since(request.created_at);
             let elapsed_ms = elapsed.num_milliseconds().max(0) as u64;
             if elapsed_ms >= guard.relaxation_policy.min_approval_duration_ms {
-                request.state = RequestState::Approved;
+                RequestState::Approved
             } else {
-                request.state = RequestState::UnderReview;
+                RequestState::UnderReview
             }
         } else {
-            let pending =
-                guard.relaxation_policy.approval_quorum - request.approval_signatures.len();
-            request.state = RequestState::ApprovalsPending {
-                pending_count: pending,
-            };
-        }
+            RequestState::ApprovalsPending {
+                pending_count: guard.relaxation_policy.approval_quorum - approvals,
+            }
+        };
+
+        // Persist the signed approval before it takes effect
+        self.record(GovernanceEvent::ApprovalRecorded {
+            request_id: request.id.clone(),
+            approval: approval.clone(),
+            state: state.clone(),
+        })?;
+
+        request.approval_signatures.push(approval);
+        request.state = state;
 
         Ok(())
     }
@@ -383,7 +581,12 @@ impl GovernanceEngine {
             .ok_or_else(|| GovernanceError::RequestNotFound(request_id.to_string()))?;
 
         let mut request = request_ref.write();
-        request.state = RequestState::Rejected { reason };
+        let state = RequestState::Rejected { reason };
+        self.record(GovernanceEvent::RequestStateChanged {
+            request_id: request.id.clone(),
+            state: state.clone(),
+        })?;
+        request.state = state;
 
         Ok(())
     }
@@ -429,6 +632,10 @@ impl GovernanceEngine {
             return Err(GovernanceError::ApprovalWindowNotOpen);
         }
 
+        self.record(GovernanceEvent::RequestStateChanged {
+            request_id: request.id.clone(),
+            state: RequestState::Approved,
+        })?;
         request.state = RequestState::Approved;
         Ok(())
     }
@@ -469,6 +676,9 @@ impl GovernanceEngine {
             reason,
         };
 
+        self.record(GovernanceEvent::RelaxationActivated {
+            window: window.clone(),
+        })?;
         self.active_relaxations
             .insert(request_id.clone(), window.clone());
 
@@ -483,6 +693,18 @@ impl GovernanceEngine {
 
     /// Revoke a relaxation before expiration
     pub fn revoke_relaxation(&self, request_id: &str) -> Result<()> {
+        let guard_id = self
+            .active_relaxations
+            .get(request_id)
+            .map(|w| w.guard_id.clone())
+            .ok_or_else(|| GovernanceError::RelaxationNotFound(request_id.to_string()))?;
+
+        self.record(GovernanceEvent::RelaxationRevoked {
+            request_id: request_id.to_string(),
+            guard_id,
+            reason: "Manual revocation".to_string(),
+        })?;
+
         let (_, window) = self
             .active_relaxations
             .remove(request_id)
@@ -505,24 +727,42 @@ impl GovernanceEngine {
     }
 
     /// Check and expire relaxations that have passed their window
+    ///
+    /// Lapsed windows are collected first and logged outside the map's shard
+    /// locks, so a slow log append never blocks readers of active relaxations.
     pub fn expire_relaxations(&self) -> Vec<String> {
         let now = Utc::now();
-        let mut expired = Vec::new();
+        let lapsed: Vec<String> = self
+            .active_relaxations
+            .iter()
+            .filter(|entry| now > entry.value().expires_at)
+            .map(|entry| entry.key().clone())
+            .collect();
 
-        self.active_relaxations.retain(|request_id, window| {
-            if now > window.expires_at {
-                expired.push(request_id.clone());
+        let mut expired = Vec::new();
+        for request_id in lapsed {
+            // A concurrent revocation may have closed the window already
+            let Some((_, window)) = self
+                .active_relaxations
+                .remove_if(&request_id, |_, window| now > window.expires_at)
+            else {
+                continue;
+            };
 
-                // Re-enable guard
-                if let Some(guard) = self.guards.get(&window.guard_id) {
-                    guard.re_enable();
-                }
+            // Expiry is enforced even if the log is unavailable; a guard must never stay relaxed
+            if let Err(e) = self.record(GovernanceEvent::RelaxationExpired {
+                request_id: request_id.clone(),
+                guard_id: window.guard_id.clone(),
+            }) {
+                tracing::error!(request_id = %request_id, error = %e, "Failed to log relaxation expiry");
+            }
 
-                false // Remove from active relaxations
-            } else {
-                true // Keep active
+            // Re-enable guard
+            if let Some(guard) = self.guards.get(&window.guard_id) {
+                guard.re_enable();
             }
-        });
+            expired.push(request_id);
+        }
 
         expired
     }
@@ -588,6 +828,126 @@ impl GovernanceEngine {
             .map(|entry| entry.value().clone())
             .collect()
     }
+
+    /// Spawn a background task that expires relaxation windows every `period`
+    ///
+    /// Windows that lapsed while the process was down are expired on the
+    /// first tick. The task stops once the engine is dropped.
+    pub fn spawn_expiry_worker(
+        self: &Arc<Self>,
+        period: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let engine = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(engine) = engine.upgrade() else {
+                    break;
+                };
+                let expired = engine.expire_relaxations();
+                if !expired.is_empty() {
+                    tracing::info!(count = expired.len(), "Expired guard relaxations");
+                }
+            }
+        })
+    }
+
+    /// Append an event to the durable log, if one is configured
+    fn record(&self, event: GovernanceEvent) -> Result<()> {
+        if let Some(log) = &self.log {
+            log.append(event)?;
+        }
+        Ok(())
+    }
+
+    /// Apply a logged event during replay (no re-logging)
+    fn apply_event(&self, event: &GovernanceEvent) -> Result<()> {
+        match event {
+            GovernanceEvent::GuardRegistered { guard } => {
+                // `enforced` is not serialized; guards start enforced and
+                // relaxation windows replayed later disable them again
+                let guard = guard.clone();
+                guard.re_enable();
+                self.guards.insert(guard.id.clone(), Arc::new(guard));
+            }
+            GovernanceEvent::RelaxationRequested { request } => {
+                self.relaxation_requests
+                    .insert(request.id.clone(), Arc::new(RwLock::new(request.clone())));
+            }
+            GovernanceEvent::ApprovalRecorded {
+                request_id,
+                approval,
+                state,
+            } => {
+                let request_ref = self
+                    .relaxation_requests
+                    .get(request_id)
+                    .ok_or_else(|| GovernanceError::RequestNotFound(request_id.clone()))?;
+                let mut request = request_ref.write();
+
+                // Signatures are re-verified against the configured approver
+                // keys so a forged approval can't be replayed
+                let message = request.approval_message(&approval.approver_id);
+                approval.verify(&message, &self.approver_key(&approval.approver_id)?)?;
+
+                request.approval_signatures.push(approval.clone());
+                request.state = state.clone();
+            }
+            GovernanceEvent::RequestStateChanged { request_id, state } => {
+                let request_ref = self
+                    .relaxation_requests
+                    .get(request_id)
+                    .ok_or_else(|| GovernanceError::RequestNotFound(request_id.clone()))?;
+                request_ref.write().state = state.clone();
+            }
+            GovernanceEvent::RelaxationActivated { window } => {
+                if let Some(req_ref) = self.relaxation_requests.get(&window.request_id) {
+                    self.approval_history.write().push(req_ref.read().clone());
+                }
+                if let Some(guard) = self.guards.get(&window.guard_id) {
+                    guard.temporarily_disable();
+                }
+                self.active_relaxations
+                    .insert(window.request_id.clone(), window.clone());
+            }
+            GovernanceEvent::RelaxationRevoked {
+                request_id,
+                guard_id,
+                reason,
+            } => {
+                self.active_relaxations.remove(request_id);
+                if let Some(guard) = self.guards.get(guard_id) {
+                    guard.re_enable();
+                }
+                if let Some(req_ref) = self.relaxation_requests.get(request_id) {
+                    req_ref.write().state = RequestState::Revoked {
+                        reason: reason.clone(),
+                    };
+                }
+            }
+            GovernanceEvent::RelaxationExpired {
+                request_id,
+                guard_id,
+            } => {
+                self.active_relaxations.remove(request_id);
+                if let Some(guard) = self.guards.get(guard_id) {
+                    guard.re_enable();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Proposed change for an RDF patch: its content hash followed by the text
+fn patch_proposal(patch: &RdfPatch) -> String {
+    format!(
+        "rdf-patch sha256:{}\n{}",
+        patch.content_hash(),
+        patch.to_patch_string()
+    )
 }
 
 impl Default for GovernanceEngine {
@@ -638,6 +998,13 @@ mod tests {
         ])
     }
 
+    /// Generate a key for `approver_id` and trust it
+    fn trusted_key(engine: &GovernanceEngine, approver_id: &str) -> SigningKey {
+        let key = SigningKey::generate(&mut OsRng);
+        engine.trust_approver(approver_id, key.verifying_key());
+        key
+    }
+
     #[test]
     fn test_critical_guard_requires_quorum() {
         let guard = create_test_guard("critical-1", Criticality::Critical);
@@ -703,9 +1070,7 @@ mod tests {
             )
             .unwrap();
 
-        // Generate signing key
-        let mut csprng = OsRng;
-        let signing_key = SigningKey::generate(&mut csprng);
+        let signing_key = trusted_key(&engine, "CFO");
 
         // Approve with signature
         engine
@@ -720,8 +1085,52 @@ mod tests {
         assert_eq!(request.approval_signatures[0].approver_id, "CFO");
 
         // Verify the signature is valid
-        let message = format!("{}-{}-{}", request.id, request.guard_id, "CFO");
-        request.approval_signatures[0].verify(&message).unwrap();
+        let message = request.approval_message("CFO");
+        request.approval_signatures[0]
+            .verify(&message, &signing_key.verifying_key())
+            .unwrap();
+
+        // The same signature doesn't verify against another approver's key
+        let other = SigningKey::generate(&mut OsRng).verifying_key();
+        assert!(matches!(
+            request.approval_signatures[0].verify(&message, &other),
+            Err(GovernanceError::UntrustedApprover(_))
+        ));
+    }
+
+    #[test]
+    fn test_untrusted_approver_rejected() {
+        let engine = GovernanceEngine::new();
+        engine
+            .register_guard(create_test_guard("test-15", Criticality::Low))
+            .unwrap();
+        let request_id = engine
+            .request_relaxation(
+                "test-15".to_string(),
+                "oscar".to_string(),
+                "Trust test".to_string(),
+                "Change".to_string(),
+            )
+            .unwrap();
+        trusted_key(&engine, "CFO");
+
+        // Unknown approver, and a known approver signing with another key
+        let impostor = SigningKey::generate(&mut OsRng);
+        assert!(matches!(
+            engine.approve_relaxation(&request_id, "Mallory".to_string(), &impostor),
+            Err(GovernanceError::UntrustedApprover(_))
+        ));
+        assert!(matches!(
+            engine.approve_relaxation(&request_id, "CFO".to_string(), &impostor),
+            Err(GovernanceError::UntrustedApprover(_))
+        ));
+        assert_eq!(
+            engine
+                .request_status(&request_id)
+                .unwrap()
+                .approvals_received,
+            0
+        );
     }
 
     #[test]
@@ -745,10 +1154,8 @@ mod tests {
             )
             .unwrap();
 
-        let mut csprng = OsRng;
-
         // First approval
-        let key1 = SigningKey::generate(&mut csprng);
+        let key1 = trusted_key(&engine, "CFO");
         engine
             .approve_relaxation(&request_id, "CFO".to_string(), &key1)
             .unwrap();
@@ -757,7 +1164,7 @@ mod tests {
         assert!(!engine.is_relaxation_approved(&request_id));
 
         // Second approval
-        let key2 = SigningKey::generate(&mut csprng);
+        let key2 = trusted_key(&engine, "Legal");
         engine
             .approve_relaxation(&request_id, "Legal".to_string(), &key2)
             .unwrap();
@@ -766,7 +1173,7 @@ mod tests {
         assert!(!engine.is_relaxation_approved(&request_id));
 
         // Third approval
-        let key3 = SigningKey::generate(&mut csprng);
+        let key3 = trusted_key(&engine, "CTO");
         engine
             .approve_relaxation(&request_id, "CTO".to_string(), &key3)
             .unwrap();
@@ -795,8 +1202,7 @@ mod tests {
             .unwrap();
 
         // Approve with required quorum (1 for low criticality)
-        let mut csprng = OsRng;
-        let key = SigningKey::generate(&mut csprng);
+        let key = trusted_key(&engine, "TeamLead");
         engine
             .approve_relaxation(&request_id, "TeamLead".to_string(), &key)
             .unwrap();
@@ -832,8 +1238,7 @@ mod tests {
             )
             .unwrap();
 
-        let mut csprng = OsRng;
-        let key = SigningKey::generate(&mut csprng);
+        let key = trusted_key(&engine, "Approver");
         engine
             .approve_relaxation(&request_id, "Approver".to_string(), &key)
             .unwrap();
@@ -872,8 +1277,7 @@ mod tests {
             )
             .unwrap();
 
-        let mut csprng = OsRng;
-        let key = SigningKey::generate(&mut csprng);
+        let key = trusted_key(&engine, "Approver");
         engine
             .approve_relaxation(&request_id, "Approver".to_string(), &key)
             .unwrap();
@@ -953,8 +1357,7 @@ mod tests {
             )
             .unwrap();
 
-        let mut csprng = OsRng;
-        let key = SigningKey::generate(&mut csprng);
+        let key = trusted_key(&engine, "Approver1");
 
         // Approve once
         engine
@@ -970,6 +1373,248 @@ mod tests {
         let status = engine.request_status(&request_id).unwrap();
         assert_eq!(status.approvals_received, 1);
     }
+
+    #[test]
+    fn test_state_survives_restart_via_log() {
+        use crate::governance_log::InMemoryGovernanceLog;
+
+        let log: Arc<dyn GovernanceLog> = Arc::new(InMemoryGovernanceLog::new());
+        let key = SigningKey::generate(&mut OsRng);
+        let approvers = HashMap::from([("Approver1".to_string(), key.verifying_key())]);
+        let request_id = {
+            let engine = GovernanceEngine::with_log(log.clone(), approvers.clone()).unwrap();
+            engine
+                .register_guard(create_test_guard("test-12", Criticality::Low))
+                .unwrap();
+            let request_id = engine
+                .request_relaxation(
+                    "test-12".to_string(),
+                    "ivan".to_string(),
+                    "Restart test".to_string(),
+                    "Relax for migration".to_string(),
+                )
+                .unwrap();
+            engine
+                .approve_relaxation(&request_id, "Approver1".to_string(), &key)
+                .unwrap();
+            engine
+                .activate_relaxation(request_id.clone(), 60_000)
+                .unwrap();
+            request_id
+        };
+
+        // A fresh engine over the same log sees the approval and the open window
+        let restarted = GovernanceEngine::with_log(log.clone(), approvers).unwrap();
+        let status = restarted.request_status(&request_id).unwrap();
+        assert_eq!(status.approvals_received, 1);
+        assert_eq!(status.state, RequestState::Approved);
+        assert!(!restarted.get_guard("test-12").unwrap().is_enforced());
+        assert_eq!(restarted.list_active_relaxations().len(), 1);
+
+        let trail = restarted.audit_trail_for_request(&request_id).unwrap();
+        assert_eq!(trail.len(), 3); // requested, approved, activated
+
+        // Without the approver's key configured the approval is not honoured
+        assert!(matches!(
+            GovernanceEngine::with_log(log, HashMap::new()),
+            Err(GovernanceError::UntrustedApprover(_))
+        ));
+    }
+
+    #[test]
+    fn test_replay_rejects_forged_approval() {
+        use crate::governance_log::{GovernanceEvent, InMemoryGovernanceLog};
+
+        let log: Arc<dyn GovernanceLog> = Arc::new(InMemoryGovernanceLog::new());
+        let key = SigningKey::generate(&mut OsRng);
+        let approvers = HashMap::from([("Mallory".to_string(), key.verifying_key())]);
+        let engine = GovernanceEngine::with_log(log.clone(), approvers.clone()).unwrap();
+        engine
+            .register_guard(create_test_guard("test-13", Criticality::Low))
+            .unwrap();
+        let request_id = engine
+            .request_relaxation(
+                "test-13".to_string(),
+                "judy".to_string(),
+                "Forgery test".to_string(),
+                "Change".to_string(),
+            )
+            .unwrap();
+
+        // Signature over a different message
+        let signature = key.sign(b"something else");
+        log.append(GovernanceEvent::ApprovalRecorded {
+            request_id,
+            approval: GuardApproval {
+                approver_id: "Mallory".to_string(),
+                signed_at: Utc::now(),
+                signature: hex::encode(signature.to_bytes()),
+                verifying_key: hex::encode(key.verifying_key().to_bytes()),
+                metadata: HashMap::new(),
+            },
+            state: RequestState::Approved,
+        })
+        .unwrap();
+
+        assert!(GovernanceEngine::with_log(log, approvers).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expiry_worker_re_enables_guard() {
+        let engine = Arc::new(GovernanceEngine::new());
+        engine
+            .register_guard(create_test_guard("test-14", Criticality::Low))
+            .unwrap();
+        let request_id = engine
+            .request_relaxation(
+                "test-14".to_string(),
+                "ken".to_string(),
+                "Worker test".to_string(),
+                "Short window".to_string(),
+            )
+            .unwrap();
+        let key = trusted_key(&engine, "Approver1");
+        engine
+            .approve_relaxation(&request_id, "Approver1".to_string(), &key)
+            .unwrap();
+        engine.activate_relaxation(request_id, 1).unwrap();
+        assert!(!engine.get_guard("test-14").unwrap().is_enforced());
+
+        let worker = engine.spawn_expiry_worker(std::time::Duration::from_millis(5));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        assert!(engine.get_guard("test-14").unwrap().is_enforced());
+        assert!(engine.list_active_relaxations().is_empty());
+        worker.abort();
+    }
+
+    #[test]
+    fn test_patch_approval_binds_to_patch_content() {
+        use crate::ontology_io::{Term, Triple};
+
+        let engine = GovernanceEngine::new();
+        engine
+            .register_guard(create_test_guard("test-11", Criticality::Low))
+            .unwrap();
+
+        let patch = RdfPatch {
+            prefixes: vec![],
+            added: vec![Triple::new(
+                Term::iri("urn:knhk:ontology#RetirementAccount"),
+                Term::iri("http://www.w3.org/1999/02/22-rdf-syntax-ns#type"),
+                Term::iri("http://www.w3.org/2002/07/owl#Class"),
+            )],
+            removed: vec![],
+        };
+
+        let request_id = engine
+            .request_patch_approval(
+                "test-11".to_string(),
+                "heidi".to_string(),
+                "Add retirement accounts".to_string(),
+                &patch,
+            )
+            .unwrap();
+
+        // Not approved yet
+        assert!(matches!(
+            engine.verify_patch_approval(&request_id, &patch),
+            Err(GovernanceError::RequestNotApproved)
+        ));
+
+        let key = trusted_key(&engine, "Reviewer");
+        engine
+            .approve_relaxation(&request_id, "Reviewer".to_string(), &key)
+            .unwrap();
+        engine.verify_patch_approval(&request_id, &patch).unwrap();
+
+        // A different patch is not covered by this approval
+        let tampered = RdfPatch {
+            removed: patch.added.clone(),
+            ..patch.clone()
+        };
+        assert!(matches!(
+            engine.verify_patch_approval(&request_id, &tampered),
+            Err(GovernanceError::PatchMismatch(_))
+        ));
+
+        // Editing the stored change doesn't carry the signatures over: they
+        // cover the digest of the change that was approved
+        engine
+            .relaxation_requests
+            .get(&request_id)
+            .unwrap()
+            .write()
+            .proposed_change = patch_proposal(&tampered);
+        assert!(matches!(
+            engine.verify_patch_approval(&request_id, &tampered),
+            Err(GovernanceError::SignatureError(_))
+        ));
+    }
+
+    #[cfg(feature = "rdf")]
+    #[test]
+    fn test_apply_approved_delta_requires_matching_approval() {
+        use crate::ontology_io::TurtleOntology;
+        use crate::shadow::{ClassDef, DeltaSigma};
+
+        let engine = GovernanceEngine::new();
+        engine
+            .register_guard(create_test_guard("test-15", Criticality::Low))
+            .unwrap();
+
+        let ontology = TurtleOntology::parse(
+            "@prefix knhk: <urn:knhk:ontology#> .\n\
+             @prefix owl: <http://www.w3.org/2002/07/owl#> .\n\
+             knhk:Account a owl:Class .\n",
+        )
+        .unwrap();
+        let delta = DeltaSigma {
+            add_classes: vec![ClassDef {
+                id: "knhk:RetirementAccount".to_string(),
+                name: "RetirementAccount".to_string(),
+                properties: vec![],
+                constraints: vec![],
+            }],
+            ..DeltaSigma::default()
+        };
+        let patch = ontology.diff(&ontology.apply_delta(&delta).unwrap());
+
+        let request_id = engine
+            .request_patch_approval(
+                "test-15".to_string(),
+                "heidi".to_string(),
+                "Add retirement accounts".to_string(),
+                &patch,
+            )
+            .unwrap();
+        let key = trusted_key(&engine, "Reviewer");
+        engine
+            .approve_relaxation(&request_id, "Reviewer".to_string(), &key)
+            .unwrap();
+
+        // A delta other than the approved one is refused
+        let other = DeltaSigma {
+            remove_classes: vec!["knhk:Account".to_string()],
+            ..DeltaSigma::default()
+        };
+        assert!(matches!(
+            engine.apply_approved_delta(&request_id, &ontology, &other),
+            Err(GovernanceError::PatchMismatch(_))
+        ));
+
+        let updated = engine
+            .apply_approved_delta(&request_id, &ontology, &delta)
+            .unwrap();
+        assert_eq!(updated.to_ontology_data().unwrap().classes.len(), 2);
+
+        // Approvals are re-verified: a key rotated after approval invalidates them
+        trusted_key(&engine, "Reviewer");
+        assert!(matches!(
+            engine.apply_approved_delta(&request_id, &ontology, &delta),
+            Err(GovernanceError::UntrustedApprover(_))
+        ));
+    }
 }
 
 // ============================================================================
@@ -1026,8 +1671,8 @@ mod prop_tests {
                 "change".to_string(),
             ).unwrap();
 
-            let mut csprng = OsRng;
-            let signing_key = SigningKey::generate(&mut csprng);
+            let signing_key = SigningKey::generate(&mut OsRng);
+            engine.trust_approver(approver_id.clone(), signing_key.verifying_key());
 
             // Approve twice with same key and approver
             engine.approve_relaxation(&request_id, approver_id.clone(), &signing_key).unwrap();
@@ -1054,8 +1699,8 @@ mod prop_tests {
                 "test".to_string(),
             ).unwrap();
 
-            let mut csprng = OsRng;
-            let signing_key = SigningKey::generate(&mut csprng);
+            let signing_key = SigningKey::generate(&mut OsRng);
+            engine.trust_approver("approver", signing_key.verifying_key());
             engine.approve_relaxation(&request_id, "approver".to_string(), &signing_key).unwrap();
 
             let window = engine.activate_relaxation(request_id.clone(), duration_ms).unwrap();
@@ -1096,10 +1741,9 @@ mod prop_tests {
                 "test".to_string(),
             ).unwrap();
 
-            let mut csprng = OsRng;
-
             for i in 0..approval_count {
-                let key = SigningKey::generate(&mut csprng);
+                let key = SigningKey::generate(&mut OsRng);
+                engine.trust_approver(format!("approver-{}", i), key.verifying_key());
                 engine.approve_relaxation(&request_id, format!("approver-{}", i), &key).unwrap();
             }
 
@@ -0,0 +1,520 @@
+// Governance Log: Append-only, hash-chained record of every governance decision
+// Replayed on startup so approvals, signatures and relaxation windows survive restarts
+// Every chain head is signed, so the chain can't be rewritten without the sealing key
+
+use crate::governance::{
+    GovernanceError, Guard, GuardApproval, GuardRelaxationRequest, RelaxationWindow, RequestState,
+    Result,
+};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Hash of the (virtual) record preceding the first entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A state change in the governance engine
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GovernanceEvent {
+    GuardRegistered {
+        guard: Guard,
+    },
+    RelaxationRequested {
+        request: GuardRelaxationRequest,
+    },
+    ApprovalRecorded {
+        request_id: String,
+        approval: GuardApproval,
+        state: RequestState,
+    },
+    RequestStateChanged {
+        request_id: String,
+        state: RequestState,
+    },
+    RelaxationActivated {
+        window: RelaxationWindow,
+    },
+    RelaxationRevoked {
+        request_id: String,
+        guard_id: String,
+        reason: String,
+    },
+    RelaxationExpired {
+        request_id: String,
+        guard_id: String,
+    },
+}
+
+impl GovernanceEvent {
+    /// Request this event belongs to, if any
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            GovernanceEvent::GuardRegistered { .. } => None,
+            GovernanceEvent::RelaxationRequested { request } => Some(&request.id),
+            GovernanceEvent::ApprovalRecorded { request_id, .. }
+            | GovernanceEvent::RequestStateChanged { request_id, .. }
+            | GovernanceEvent::RelaxationRevoked { request_id, .. }
+            | GovernanceEvent::RelaxationExpired { request_id, .. } => Some(request_id),
+            GovernanceEvent::RelaxationActivated { window } => Some(&window.request_id),
+        }
+    }
+}
+
+/// One entry of the governance log
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GovernanceRecord {
+    pub sequence: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub prev_hash: String,
+    pub hash: String,
+    pub seal: String, // hex-encoded ed25519 signature of `hash` by the log's sealing key
+    pub event: GovernanceEvent,
+}
+
+impl GovernanceRecord {
+    fn new(
+        sequence: u64,
+        prev_hash: String,
+        event: GovernanceEvent,
+        sealing_key: &SigningKey,
+    ) -> Result<Self> {
+        let recorded_at = Utc::now();
+        let hash = Self::compute_hash(sequence, &recorded_at, &prev_hash, &event)?;
+        let seal = hex::encode(sealing_key.sign(hash.as_bytes()).to_bytes());
+        Ok(GovernanceRecord {
+            sequence,
+            recorded_at,
+            prev_hash,
+            hash,
+            seal,
+            event,
+        })
+    }
+
+    fn compute_hash(
+        sequence: u64,
+        recorded_at: &DateTime<Utc>,
+        prev_hash: &str,
+        event: &GovernanceEvent,
+    ) -> Result<String> {
+        let payload =
+            serde_json::to_vec(event).map_err(|e| GovernanceError::Persistence(e.to_string()))?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(sequence.to_le_bytes());
+        hasher.update(recorded_at.to_rfc3339().as_bytes());
+        hasher.update(prev_hash.as_bytes());
+        hasher.update(&payload);
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Verify this record's hash, its link to the previous record and its seal
+    pub fn verify(&self, expected_prev_hash: &str, sealing_key: &VerifyingKey) -> Result<()> {
+        let hash = Self::compute_hash(
+            self.sequence,
+            &self.recorded_at,
+            &self.prev_hash,
+            &self.event,
+        )?;
+        if self.prev_hash != expected_prev_hash || hash != self.hash {
+            return Err(GovernanceError::LogTampered(self.sequence));
+        }
+
+        // A rewritten chain hashes consistently; only the seal gives it away
+        let seal: [u8; 64] = hex::decode(&self.seal)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(GovernanceError::LogTampered(self.sequence))?;
+        sealing_key
+            .verify(self.hash.as_bytes(), &Signature::from_bytes(&seal))
+            .map_err(|_| GovernanceError::LogTampered(self.sequence))
+    }
+}
+
+/// Durable, append-only storage for governance events
+pub trait GovernanceLog: Send + Sync {
+    /// Append an event, returning the sealed record
+    fn append(&self, event: GovernanceEvent) -> Result<GovernanceRecord>;
+
+    /// Read back every record in order, verifying the hash chain
+    fn replay(&self) -> Result<Vec<GovernanceRecord>>;
+}
+
+/// Verify a full chain of records
+fn verify_chain(records: &[GovernanceRecord], sealing_key: &VerifyingKey) -> Result<()> {
+    let mut prev_hash = GENESIS_HASH.to_string();
+    for (expected_seq, record) in records.iter().enumerate() {
+        if record.sequence != expected_seq as u64 {
+            return Err(GovernanceError::LogTampered(record.sequence));
+        }
+        record.verify(&prev_hash, sealing_key)?;
+        prev_hash = record.hash.clone();
+    }
+    Ok(())
+}
+
+/// In-memory log (tests and ephemeral deployments), sealed with a throwaway key
+pub struct InMemoryGovernanceLog {
+    records: Mutex<Vec<GovernanceRecord>>,
+    sealing_key: SigningKey,
+}
+
+impl InMemoryGovernanceLog {
+    pub fn new() -> Self {
+        InMemoryGovernanceLog {
+            records: Mutex::new(Vec::new()),
+            sealing_key: SigningKey::from_bytes(&rand::random()),
+        }
+    }
+}
+
+impl Default for InMemoryGovernanceLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GovernanceLog for InMemoryGovernanceLog {
+    fn append(&self, event: GovernanceEvent) -> Result<GovernanceRecord> {
+        let mut records = self.records.lock();
+        let prev_hash = records
+            .last()
+            .map(|r| r.hash.clone())
+            .unwrap_or_else(|| GENESIS_HASH.to_string());
+        let record =
+            GovernanceRecord::new(records.len() as u64, prev_hash, event, &self.sealing_key)?;
+        records.push(record.clone());
+        Ok(record)
+    }
+
+    fn replay(&self) -> Result<Vec<GovernanceRecord>> {
+        let records = self.records.lock().clone();
+        verify_chain(&records, &self.sealing_key.verifying_key())?;
+        Ok(records)
+    }
+}
+
+/// JSON-lines file log, fsynced on every append
+pub struct FileGovernanceLog {
+    path: PathBuf,
+    sealing_key: SigningKey,
+    // (next sequence, last hash, open handle)
+    state: Mutex<(u64, String, File)>,
+}
+
+impl FileGovernanceLog {
+    /// Open (or create) a log file, validating any existing records
+    ///
+    /// `sealing_key` signs every appended record and must be the key the
+    /// existing records were sealed with.
+    pub fn open(path: impl AsRef<Path>, sealing_key: SigningKey) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(persistence_error)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(&path)
+            .map_err(persistence_error)?;
+
+        Self::repair_torn_tail(&path)?;
+        let records = Self::read_records(&path)?;
+        verify_chain(&records, &sealing_key.verifying_key())?;
+
+        let next_sequence = records.len() as u64;
+        let last_hash = records
+            .last()
+            .map(|r| r.hash.clone())
+            .unwrap_or_else(|| GENESIS_HASH.to_string());
+
+        Ok(FileGovernanceLog {
+            path,
+            sealing_key,
+            state: Mutex::new((next_sequence, last_hash, file)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Drop a final record torn by a crash mid-append
+    ///
+    /// Only the last line is repaired: an unparseable record followed by
+    /// further records is corruption, which `read_records` still rejects.
+    fn repair_torn_tail(path: &Path) -> Result<()> {
+        let content = std::fs::read(path).map_err(persistence_error)?;
+        let end = match content.iter().rposition(|b| !b.is_ascii_whitespace()) {
+            Some(last) => last + 1,
+            None => return Ok(()),
+        };
+        let start = content[..end]
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |newline| newline + 1);
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map_err(persistence_error)?;
+        if serde_json::from_slice::<GovernanceRecord>(&content[start..end]).is_ok() {
+            // The record is complete, but its newline may have been lost
+            if content[end..].contains(&b'\n') {
+                return Ok(());
+            }
+            file.set_len(end as u64).map_err(persistence_error)?;
+            file.write_all(b"\n").map_err(persistence_error)?;
+        } else {
+            tracing::warn!(
+                path = %path.display(),
+                offset = start,
+                "Truncating torn trailing governance record"
+            );
+            file.set_len(start as u64).map_err(persistence_error)?;
+        }
+        file.sync_data().map_err(persistence_error)
+    }
+
+    fn read_records(path: &Path) -> Result<Vec<GovernanceRecord>> {
+        let file = File::open(path).map_err(persistence_error)?;
+        let mut records = Vec::new();
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(persistence_error)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: GovernanceRecord = serde_json::from_str(&line).map_err(|e| {
+                GovernanceError::Persistence(format!("{}:{}: {}", path.display(), line_no + 1, e))
+            })?;
+            records.push(record);
+        }
+        Ok(records)
+    }
+}
+
+impl GovernanceLog for FileGovernanceLog {
+    fn append(&self, event: GovernanceEvent) -> Result<GovernanceRecord> {
+        let mut state = self.state.lock();
+        let (next_sequence, last_hash, file) = &mut *state;
+
+        let record =
+            GovernanceRecord::new(*next_sequence, last_hash.clone(), event, &self.sealing_key)?;
+        let mut line = serde_json::to_string(&record)
+            .map_err(|e| GovernanceError::Persistence(e.to_string()))?;
+        line.push('\n');
+
+        file.write_all(line.as_bytes()).map_err(persistence_error)?;
+        file.sync_data().map_err(persistence_error)?;
+
+        *next_sequence += 1;
+        *last_hash = record.hash.clone();
+        Ok(record)
+    }
+
+    fn replay(&self) -> Result<Vec<GovernanceRecord>> {
+        // Hold the lock so a concurrent append can't produce a torn read
+        let _state = self.state.lock();
+        let records = Self::read_records(&self.path)?;
+        verify_chain(&records, &self.sealing_key.verifying_key())?;
+        Ok(records)
+    }
+}
+
+fn persistence_error(e: std::io::Error) -> GovernanceError {
+    GovernanceError::Persistence(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sealing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn temp_log_path() -> PathBuf {
+        std::env::temp_dir().join(format!("knhk-governance-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    fn state_event(request_id: &str) -> GovernanceEvent {
+        GovernanceEvent::RequestStateChanged {
+            request_id: request_id.to_string(),
+            state: RequestState::UnderReview,
+        }
+    }
+
+    #[test]
+    fn test_in_memory_log_chains_records() {
+        let log = InMemoryGovernanceLog::new();
+        let first = log.append(state_event("req-1")).unwrap();
+        let second = log.append(state_event("req-2")).unwrap();
+
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert_eq!(second.prev_hash, first.hash);
+        assert_eq!(log.replay().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_file_log_survives_reopen() {
+        let path = temp_log_path();
+        {
+            let log = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+            log.append(state_event("req-1")).unwrap();
+            log.append(state_event("req-2")).unwrap();
+        }
+
+        let reopened = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+        let third = reopened.append(state_event("req-3")).unwrap();
+        assert_eq!(third.sequence, 2);
+
+        let records = reopened.replay().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].event.request_id(), Some("req-2"));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_file_log_truncates_torn_last_record() {
+        let path = temp_log_path();
+        {
+            let log = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+            log.append(state_event("req-1")).unwrap();
+            log.append(state_event("req-2")).unwrap();
+        }
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"sequence":2,"prev_hash":"#).unwrap();
+        drop(file);
+
+        let reopened = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+        assert_eq!(reopened.replay().unwrap().len(), 2);
+        let third = reopened.append(state_event("req-3")).unwrap();
+        assert_eq!(third.sequence, 2);
+        drop(reopened);
+
+        let records = FileGovernanceLog::open(&path, sealing_key())
+            .unwrap()
+            .replay()
+            .unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].event.request_id(), Some("req-3"));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_file_log_restores_missing_newline() {
+        let path = temp_log_path();
+        {
+            let log = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+            log.append(state_event("req-1")).unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.trim_end()).unwrap();
+
+        let reopened = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+        reopened.append(state_event("req-2")).unwrap();
+        assert_eq!(reopened.replay().unwrap().len(), 2);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_file_log_rejects_corruption_before_last_record() {
+        let path = temp_log_path();
+        {
+            let log = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+            log.append(state_event("req-1")).unwrap();
+            log.append(state_event("req-2")).unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let (first, rest) = content.split_once('\n').unwrap();
+        std::fs::write(&path, format!("{}\n{{\"sequence\":1,\n{}", first, rest)).unwrap();
+
+        assert!(matches!(
+            FileGovernanceLog::open(&path, sealing_key()),
+            Err(GovernanceError::Persistence(_))
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_file_log_detects_tampering() {
+        let path = temp_log_path();
+        {
+            let log = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+            log.append(state_event("req-1")).unwrap();
+            log.append(state_event("req-2")).unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace("req-1", "req-X")).unwrap();
+
+        assert!(matches!(
+            FileGovernanceLog::open(&path, sealing_key()),
+            Err(GovernanceError::LogTampered(0))
+        ));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_file_log_rejects_rewritten_chain() {
+        let path = temp_log_path();
+        {
+            let log = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+            log.append(state_event("req-1")).unwrap();
+        }
+
+        // Rewrite the record and recompute its hash without the sealing key
+        let content = std::fs::read_to_string(&path).unwrap();
+        let mut record: GovernanceRecord = serde_json::from_str(content.trim()).unwrap();
+        record.event = state_event("req-X");
+        record.hash = GovernanceRecord::compute_hash(
+            record.sequence,
+            &record.recorded_at,
+            &record.prev_hash,
+            &record.event,
+        )
+        .unwrap();
+        let forged = serde_json::to_string(&record).unwrap();
+        std::fs::write(&path, format!("{}\n", forged)).unwrap();
+
+        assert!(matches!(
+            FileGovernanceLog::open(&path, sealing_key()),
+            Err(GovernanceError::LogTampered(0))
+        ));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_file_log_rejects_other_sealing_key() {
+        let path = temp_log_path();
+        {
+            let log = FileGovernanceLog::open(&path, sealing_key()).unwrap();
+            log.append(state_event("req-1")).unwrap();
+        }
+
+        assert!(matches!(
+            FileGovernanceLog::open(&path, SigningKey::from_bytes(&[8; 32])),
+            Err(GovernanceError::LogTampered(0))
+        ));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
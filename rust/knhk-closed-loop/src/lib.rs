@@ -5,8 +5,10 @@ pub mod chatman_equation;
 pub mod coordinator;
 pub mod doctrine;
 pub mod governance;
+pub mod governance_log;
 pub mod invariants;
 pub mod observation;
+pub mod ontology_io;
 pub mod promoter;
 pub mod receipt;
 pub mod shadow;
@@ -28,9 +30,13 @@ pub use governance::{
     GuardRelaxationRequest, GuardStatus, GuardType, RelaxationPolicy, RelaxationWindow,
     RequestState, RequestStatus,
 };
+pub use governance_log::{
+    FileGovernanceLog, GovernanceEvent, GovernanceLog, GovernanceRecord, InMemoryGovernanceLog,
+};
 pub use invariants::{HardInvariants, InvariantValidator, InvariantViolation};
 pub use learning::{LearningMetrics, LearningSystem, ProposalCorpus, ProposalOutcome};
 pub use observation::{Observation, ObservationStore, PatternDetector};
+pub use ontology_io::{OntologyIoError, RdfPatch, Term, Triple, TurtleOntology};
 pub use promoter::{PromotionError, SnapshotPromoter};
 pub use prompt_engine::{PromptEngine, PromptEngineError};
 pub use proposer::{
@@ -81,6 +87,9 @@ pub enum ClosedLoopError {
     #[error("Governance error: {0}")]
     Governance(#[from] governance::GovernanceError),
 
+    #[error("Ontology I/O error: {0}")]
+    OntologyIo(#[from] ontology_io::OntologyIoError),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -0,0 +1,1315 @@
+// Ontology I/O: Bidirectional bridge between Turtle files and ΔΣ proposals
+// Loads .ttl ontologies into OntologyData, applies approved ΔΣ at the triple level,
+// writes Turtle back with the original prefixes, and emits reviewable RDF patches
+// Turtle is parsed with oxigraph, so reading ontologies needs the `rdf` feature.
+
+use crate::proposer::{ClassDefinition, PropertyDefinition, SigmaDiff};
+use crate::shadow::{ClassDef, DeltaSigma, GuardDef, GuardSeverity, OntologyData, PropertyDef};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use thiserror::Error;
+
+pub const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+pub const RDFS: &str = "http://www.w3.org/2000/01/rdf-schema#";
+pub const OWL: &str = "http://www.w3.org/2002/07/owl#";
+pub const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+pub const SH: &str = "http://www.w3.org/ns/shacl#";
+pub const KNHK: &str = "urn:knhk:ontology#";
+
+/// IRI schemes `TurtleOntology::expand` accepts as absolute IRIs
+pub const IRI_SCHEMES: &[&str] = &["http", "https", "urn", "file", "mailto", "tag"];
+
+#[derive(Error, Debug)]
+pub enum OntologyIoError {
+    #[error("Turtle syntax error at line {line}, column {column}: {message}")]
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("Undefined prefix: {0}")]
+    UndefinedPrefix(String),
+
+    #[error("Invalid term: {0}")]
+    InvalidTerm(String),
+
+    #[error("Removed {removed} is still referenced by {referenced_by}")]
+    DanglingReference {
+        removed: String,
+        referenced_by: String,
+    },
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, OntologyIoError>;
+
+/// RDF term (IRI, blank node or literal)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Term {
+    Iri(String),
+    BlankNode(String),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+impl Term {
+    pub fn iri(iri: impl Into<String>) -> Self {
+        Term::Iri(iri.into())
+    }
+
+    pub fn literal(value: impl Into<String>) -> Self {
+        Term::Literal {
+            value: value.into(),
+            datatype: None,
+            language: None,
+        }
+    }
+
+    pub fn as_iri(&self) -> Option<&str> {
+        match self {
+            Term::Iri(iri) => Some(iri),
+            _ => None,
+        }
+    }
+
+    pub fn literal_value(&self) -> Option<&str> {
+        match self {
+            Term::Literal { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Lexical form used when a term is shown outside the graph (labels, metadata)
+    fn display_value(&self) -> String {
+        match self {
+            Term::Iri(iri) => iri.clone(),
+            Term::BlankNode(id) => format!("_:{}", id),
+            Term::Literal { value, .. } => value.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    /// N-Triples serialization
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Iri(iri) => write!(f, "<{}>", iri),
+            Term::BlankNode(id) => write!(f, "_:{}", id),
+            Term::Literal {
+                value,
+                datatype,
+                language,
+            } => {
+                write!(f, "\"{}\"", escape_string(value))?;
+                if let Some(lang) = language {
+                    write!(f, "@{}", lang)
+                } else if let Some(dt) = datatype {
+                    write!(f, "^^<{}>", dt)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// A single RDF triple
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Triple {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+}
+
+impl Triple {
+    pub fn new(subject: Term, predicate: Term, object: Term) -> Self {
+        Triple {
+            subject,
+            predicate,
+            object,
+        }
+    }
+}
+
+impl fmt::Display for Triple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} .", self.subject, self.predicate, self.object)
+    }
+}
+
+/// Turtle ontology document: prefixes plus the full triple graph
+///
+/// The graph is kept verbatim so that triples outside the `OntologyData`
+/// projection (comments, restrictions, instances) survive a round trip.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TurtleOntology {
+    pub base: Option<String>,
+    pub prefixes: Vec<(String, String)>,
+    pub triples: Vec<Triple>,
+}
+
+impl TurtleOntology {
+    /// Parse a Turtle document
+    #[cfg(feature = "rdf")]
+    pub fn parse(input: &str) -> Result<Self> {
+        parse_turtle(input)
+    }
+
+    /// Load a Turtle ontology from disk
+    #[cfg(feature = "rdf")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// Write the ontology to disk as Turtle
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_turtle())?;
+        Ok(())
+    }
+
+    /// Expand a compact identifier (`prefix:local`, `<iri>` or absolute IRI)
+    ///
+    /// Only IRIs with a well-known scheme (see [`IRI_SCHEMES`]) pass through
+    /// unbracketed; any other `prefix:local` needs a declared prefix, so a
+    /// typo'd prefix is reported instead of becoming a bogus IRI.
+    pub fn expand(&self, id: &str) -> Result<String> {
+        let id = id.trim();
+        if let Some(inner) = id.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+            return Ok(inner.to_string());
+        }
+        if let Some((prefix, local)) = id.split_once(':') {
+            if let Some((_, ns)) = self.prefixes.iter().find(|(p, _)| p == prefix) {
+                return Ok(format!("{}{}", ns, local));
+            }
+            if IRI_SCHEMES.contains(&prefix) && !local.is_empty() {
+                return Ok(id.to_string());
+            }
+        }
+        Err(OntologyIoError::UndefinedPrefix(id.to_string()))
+    }
+
+    /// Compact an IRI using the document prefixes when the local part is simple
+    pub fn compact(&self, iri: &str) -> Option<String> {
+        self.prefixes
+            .iter()
+            .filter(|(_, ns)| iri.starts_with(ns.as_str()))
+            .max_by_key(|(_, ns)| ns.len())
+            .and_then(|(prefix, ns)| {
+                let local = &iri[ns.len()..];
+                is_simple_local_name(local).then(|| format!("{}:{}", prefix, local))
+            })
+    }
+
+    /// Project the graph onto the in-memory ontology model
+    ///
+    /// Absent domains, ranges and severities project to their defaults, but
+    /// malformed ones (a literal domain, an unknown severity, a guard without
+    /// an expression) are reported rather than silently dropped.
+    pub fn to_ontology_data(&self) -> Result<OntologyData> {
+        let rdf_type = format!("{}type", RDF);
+        let rdfs_domain = format!("{}domain", RDFS);
+        let rdfs_range = format!("{}range", RDFS);
+
+        let mut types: HashMap<&Term, Vec<&str>> = HashMap::new();
+        for t in &self.triples {
+            if t.predicate.as_iri() == Some(rdf_type.as_str()) {
+                if let Some(o) = t.object.as_iri() {
+                    types.entry(&t.subject).or_default().push(o);
+                }
+            }
+        }
+
+        let has_type = |subject: &Term, candidates: &[String]| {
+            types
+                .get(subject)
+                .map(|ts| ts.iter().any(|t| candidates.iter().any(|c| c == t)))
+                .unwrap_or(false)
+        };
+
+        let class_types = [format!("{}Class", OWL), format!("{}Class", RDFS)];
+        let property_types = [
+            format!("{}ObjectProperty", OWL),
+            format!("{}DatatypeProperty", OWL),
+            format!("{}AnnotationProperty", OWL),
+            format!("{}Property", RDF),
+        ];
+        let guard_types = [format!("{}Guard", KNHK)];
+        let ontology_types = [format!("{}Ontology", OWL)];
+
+        let mut data = OntologyData::new();
+
+        for subject in self.subjects() {
+            let Term::Iri(id) = subject else { continue };
+
+            if has_type(subject, &class_types) {
+                data.classes.push(ClassDef {
+                    id: id.clone(),
+                    name: self.label_of(subject),
+                    properties: Vec::new(),
+                    constraints: self.constraints_of(subject),
+                });
+            } else if has_type(subject, &property_types) {
+                data.properties.push(PropertyDef {
+                    id: id.clone(),
+                    name: self.label_of(subject),
+                    domain: self.iri_of(subject, &rdfs_domain)?.unwrap_or_default(),
+                    range: self.iri_of(subject, &rdfs_range)?.unwrap_or_default(),
+                });
+            } else if has_type(subject, &guard_types) {
+                let expression = self
+                    .object_of(subject, &format!("{}guardExpression", KNHK))
+                    .and_then(Term::literal_value)
+                    .ok_or_else(|| {
+                        OntologyIoError::InvalidTerm(format!(
+                            "{} has no knhk:guardExpression literal",
+                            subject
+                        ))
+                    })?;
+                let severity = match self.iri_of(subject, &format!("{}severity", SH))? {
+                    Some(iri) => severity_from_iri(&iri)?,
+                    // SHACL's default severity
+                    None => GuardSeverity::Error,
+                };
+                data.guards.push(GuardDef {
+                    id: id.clone(),
+                    name: self.label_of(subject),
+                    expression: expression.to_string(),
+                    severity,
+                });
+            } else if has_type(subject, &ontology_types) {
+                for t in self.triples.iter().filter(|t| &t.subject == subject) {
+                    if t.predicate.as_iri() == Some(rdf_type.as_str()) {
+                        continue;
+                    }
+                    let predicate = t.predicate.as_iri().ok_or_else(|| {
+                        OntologyIoError::InvalidTerm(format!("predicate {}", t.predicate))
+                    })?;
+                    let key = self
+                        .compact(predicate)
+                        .unwrap_or_else(|| predicate.to_string());
+                    data.metadata
+                        .insert(key, serde_json::Value::String(t.object.display_value()));
+                }
+            }
+        }
+
+        // Derive class → property links from rdfs:domain
+        for class in &mut data.classes {
+            class.properties = data
+                .properties
+                .iter()
+                .filter(|p| p.domain == class.id)
+                .map(|p| p.id.clone())
+                .collect();
+        }
+
+        Ok(data)
+    }
+
+    /// Apply an approved ΔΣ, returning the updated document
+    ///
+    /// Removals drop every triple about the removed subject together with the
+    /// blank nodes it owns; additions are rendered with the same vocabulary
+    /// that `to_ontology_data` reads. A removal that would leave another
+    /// subject pointing at the removed IRI (a `rdfs:subClassOf`, domain or
+    /// range) is rejected with [`OntologyIoError::DanglingReference`].
+    #[cfg(feature = "rdf")]
+    pub fn apply_delta(&self, delta: &DeltaSigma) -> Result<TurtleOntology> {
+        let mut removed_subjects = HashSet::new();
+        for id in delta
+            .remove_classes
+            .iter()
+            .chain(&delta.remove_properties)
+            .chain(&delta.remove_guards)
+        {
+            removed_subjects.insert(Term::Iri(self.expand(id)?));
+        }
+
+        let owned = self.owned_blank_nodes(&removed_subjects);
+        let mut triples: Vec<Triple> = self
+            .triples
+            .iter()
+            .filter(|t| !removed_subjects.contains(&t.subject) && !owned.contains(&t.subject))
+            .cloned()
+            .collect();
+
+        let rdf_type = Term::iri(format!("{}type", RDF));
+        let label = Term::iri(format!("{}label", RDFS));
+
+        for class in &delta.add_classes {
+            let subject = Term::Iri(self.expand(&class.id)?);
+            triples.push(Triple::new(
+                subject.clone(),
+                rdf_type.clone(),
+                Term::iri(format!("{}Class", OWL)),
+            ));
+            triples.push(Triple::new(
+                subject.clone(),
+                label.clone(),
+                Term::literal(&class.name),
+            ));
+            for constraint in &class.constraints {
+                let (predicate, object) = self.parse_constraint(constraint)?;
+                triples.push(Triple::new(subject.clone(), predicate, object));
+            }
+        }
+
+        for property in &delta.add_properties {
+            let subject = Term::Iri(self.expand(&property.id)?);
+            let range = if property.range.is_empty() {
+                String::new()
+            } else {
+                self.expand(&property.range)?
+            };
+            let kind = if range.starts_with(XSD) || range == format!("{}Literal", RDFS) {
+                "DatatypeProperty"
+            } else {
+                "ObjectProperty"
+            };
+            triples.push(Triple::new(
+                subject.clone(),
+                rdf_type.clone(),
+                Term::iri(format!("{}{}", OWL, kind)),
+            ));
+            triples.push(Triple::new(
+                subject.clone(),
+                label.clone(),
+                Term::literal(&property.name),
+            ));
+            if !property.domain.is_empty() {
+                triples.push(Triple::new(
+                    subject.clone(),
+                    Term::iri(format!("{}domain", RDFS)),
+                    Term::Iri(self.expand(&property.domain)?),
+                ));
+            }
+            if !range.is_empty() {
+                triples.push(Triple::new(
+                    subject.clone(),
+                    Term::iri(format!("{}range", RDFS)),
+                    Term::Iri(range),
+                ));
+            }
+        }
+
+        for guard in &delta.add_guards {
+            let subject = Term::Iri(self.expand(&guard.id)?);
+            triples.push(Triple::new(
+                subject.clone(),
+                rdf_type.clone(),
+                Term::iri(format!("{}Guard", KNHK)),
+            ));
+            triples.push(Triple::new(
+                subject.clone(),
+                label.clone(),
+                Term::literal(&guard.name),
+            ));
+            triples.push(Triple::new(
+                subject.clone(),
+                Term::iri(format!("{}guardExpression", KNHK)),
+                Term::literal(&guard.expression),
+            ));
+            triples.push(Triple::new(
+                subject,
+                Term::iri(format!("{}severity", SH)),
+                Term::iri(severity_to_iri(&guard.severity)),
+            ));
+        }
+
+        if !delta.metadata_updates.is_empty() {
+            let owl_ontology = format!("{}Ontology", OWL);
+            let ontology_subject = self
+                .subjects()
+                .into_iter()
+                .find(|s| {
+                    self.triples.iter().any(|t| {
+                        &t.subject == *s
+                            && t.predicate == rdf_type
+                            && t.object.as_iri() == Some(owl_ontology.as_str())
+                    })
+                })
+                .cloned()
+                .unwrap_or_else(|| Term::iri(KNHK.trim_end_matches('#')));
+
+            for (key, value) in &delta.metadata_updates {
+                let predicate = Term::Iri(self.expand(key)?);
+                triples.retain(|t| !(t.subject == ontology_subject && t.predicate == predicate));
+                let object = match value {
+                    serde_json::Value::String(s) => Term::literal(s),
+                    other => Term::literal(other.to_string()),
+                };
+                triples.push(Triple::new(ontology_subject.clone(), predicate, object));
+            }
+        }
+
+        let readded: HashSet<&Term> = triples.iter().map(|t| &t.subject).collect();
+        if let Some(dangling) = triples
+            .iter()
+            .find(|t| removed_subjects.contains(&t.object) && !readded.contains(&t.object))
+        {
+            return Err(OntologyIoError::DanglingReference {
+                removed: dangling.object.to_string(),
+                referenced_by: dangling.subject.to_string(),
+            });
+        }
+
+        // Keep the graph duplicate-free while preserving document order
+        let mut seen = HashSet::new();
+        triples.retain(|t| seen.insert(t.clone()));
+
+        Ok(TurtleOntology {
+            base: self.base.clone(),
+            prefixes: self.prefixes.clone(),
+            triples,
+        })
+    }
+
+    /// Compute the RDF patch that turns `self` into `other`
+    pub fn diff(&self, other: &TurtleOntology) -> RdfPatch {
+        let before: HashSet<&Triple> = self.triples.iter().collect();
+        let after: HashSet<&Triple> = other.triples.iter().collect();
+
+        RdfPatch {
+            prefixes: other.prefixes.clone(),
+            removed: self
+                .triples
+                .iter()
+                .filter(|t| !after.contains(t))
+                .cloned()
+                .collect(),
+            added: other
+                .triples
+                .iter()
+                .filter(|t| !before.contains(t))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Serialize as Turtle, grouping triples by subject in document order
+    pub fn to_turtle(&self) -> String {
+        let mut out = String::new();
+        if let Some(base) = &self.base {
+            out.push_str(&format!("@base <{}> .\n", base));
+        }
+        for (prefix, ns) in &self.prefixes {
+            out.push_str(&format!("@prefix {}: <{}> .\n", prefix, ns));
+        }
+
+        let rdf_type = format!("{}type", RDF);
+        for subject in self.subjects() {
+            out.push('\n');
+            out.push_str(&self.render_term(subject));
+
+            let mut predicates: Vec<&Term> = Vec::new();
+            for t in self.triples.iter().filter(|t| &t.subject == subject) {
+                if !predicates.contains(&&t.predicate) {
+                    predicates.push(&t.predicate);
+                }
+            }
+
+            for (i, predicate) in predicates.iter().enumerate() {
+                let verb = if predicate.as_iri() == Some(rdf_type.as_str()) {
+                    "a".to_string()
+                } else {
+                    self.render_term(predicate)
+                };
+                let objects: Vec<String> = self
+                    .triples
+                    .iter()
+                    .filter(|t| &t.subject == subject && &t.predicate == *predicate)
+                    .map(|t| self.render_term(&t.object))
+                    .collect();
+                let separator = if i == 0 { " " } else { " ;\n    " };
+                out.push_str(separator);
+                out.push_str(&format!("{} {}", verb, objects.join(", ")));
+            }
+            out.push_str(" .\n");
+        }
+
+        out
+    }
+
+    // Private helpers
+
+    fn subjects(&self) -> Vec<&Term> {
+        let mut seen = HashSet::new();
+        self.triples
+            .iter()
+            .map(|t| &t.subject)
+            .filter(|s| seen.insert(*s))
+            .collect()
+    }
+
+    fn object_of(&self, subject: &Term, predicate: &str) -> Option<&Term> {
+        self.triples
+            .iter()
+            .find(|t| &t.subject == subject && t.predicate.as_iri() == Some(predicate))
+            .map(|t| &t.object)
+    }
+
+    /// IRI object of `predicate`, an error if the object is not an IRI
+    fn iri_of(&self, subject: &Term, predicate: &str) -> Result<Option<String>> {
+        self.object_of(subject, predicate)
+            .map(|object| {
+                object.as_iri().map(str::to_string).ok_or_else(|| {
+                    OntologyIoError::InvalidTerm(format!("{} <{}> {}", subject, predicate, object))
+                })
+            })
+            .transpose()
+    }
+
+    fn label_of(&self, subject: &Term) -> String {
+        self.object_of(subject, &format!("{}label", RDFS))
+            .and_then(Term::literal_value)
+            .map(str::to_string)
+            .unwrap_or_else(|| match subject {
+                Term::Iri(iri) => local_name(iri).to_string(),
+                other => other.display_value(),
+            })
+    }
+
+    /// Class constraints are the non-structural `predicate object` pairs in N-Triples syntax
+    fn constraints_of(&self, subject: &Term) -> Vec<String> {
+        let skip = [format!("{}type", RDF), format!("{}label", RDFS)];
+        self.triples
+            .iter()
+            .filter(|t| &t.subject == subject)
+            .filter(|t| {
+                !t.predicate
+                    .as_iri()
+                    .map(|p| skip.iter().any(|s| s == p))
+                    .unwrap_or(false)
+            })
+            .filter(|t| !matches!(t.object, Term::BlankNode(_)))
+            .map(|t| format!("{} {}", t.predicate, t.object))
+            .collect()
+    }
+
+    /// Parse a `predicate object` constraint using the document prefixes
+    #[cfg(feature = "rdf")]
+    fn parse_constraint(&self, constraint: &str) -> Result<(Term, Term)> {
+        let mut document = String::new();
+        if let Some(base) = &self.base {
+            document.push_str(&format!("@base <{}> .\n", base));
+        }
+        for (prefix, ns) in &self.prefixes {
+            document.push_str(&format!("@prefix {}: <{}> .\n", prefix, ns));
+        }
+        document.push_str(&format!("<urn:knhk:constraint> {} .\n", constraint));
+
+        let parsed = parse_turtle(&document)
+            .map_err(|e| OntologyIoError::InvalidTerm(format!("{}: {}", constraint, e)))?;
+        match parsed.triples.as_slice() {
+            [triple] => Ok((triple.predicate.clone(), triple.object.clone())),
+            _ => Err(OntologyIoError::InvalidTerm(format!(
+                "{}: expected a single predicate and object",
+                constraint
+            ))),
+        }
+    }
+
+    /// Blank nodes reachable only through the given subjects
+    #[cfg(feature = "rdf")]
+    fn owned_blank_nodes(&self, roots: &HashSet<Term>) -> HashSet<Term> {
+        let mut owned = HashSet::new();
+        let mut frontier: Vec<&Term> = roots.iter().collect();
+        while let Some(node) = frontier.pop() {
+            for t in self.triples.iter().filter(|t| &t.subject == node) {
+                if matches!(t.object, Term::BlankNode(_)) && owned.insert(t.object.clone()) {
+                    frontier.push(&t.object);
+                }
+            }
+        }
+        owned
+    }
+
+    fn render_term(&self, term: &Term) -> String {
+        match term {
+            Term::Iri(iri) => self.compact(iri).unwrap_or_else(|| format!("<{}>", iri)),
+            Term::BlankNode(id) => format!("_:{}", id),
+            Term::Literal {
+                value,
+                datatype,
+                language,
+            } => {
+                if let Some(lang) = language {
+                    return format!("\"{}\"@{}", escape_string(value), lang);
+                }
+                match datatype.as_deref() {
+                    None => format!("\"{}\"", escape_string(value)),
+                    Some(dt) if dt == format!("{}string", XSD) => {
+                        format!("\"{}\"", escape_string(value))
+                    }
+                    Some(dt) if is_bare_literal(dt, value) => value.clone(),
+                    Some(dt) => format!(
+                        "\"{}\"^^{}",
+                        escape_string(value),
+                        self.render_term(&Term::iri(dt))
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Human-reviewable RDF patch (added/removed triples)
+///
+/// Serialized in the RDF Patch text format (`A`/`D` rows inside a `TX`/`TC`
+/// transaction) so approvers can read exactly which statements change.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RdfPatch {
+    pub prefixes: Vec<(String, String)>,
+    pub added: Vec<Triple>,
+    pub removed: Vec<Triple>,
+}
+
+impl RdfPatch {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// SHA-256 over the canonical patch text; approvers sign off on this digest
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.to_patch_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Render in RDF Patch format
+    pub fn to_patch_string(&self) -> String {
+        let mut out = String::new();
+        for (prefix, ns) in &self.prefixes {
+            out.push_str(&format!("PA {} <{}> .\n", prefix, ns));
+        }
+        out.push_str("TX .\n");
+        for t in &self.removed {
+            out.push_str(&format!("D {}\n", t));
+        }
+        for t in &self.added {
+            out.push_str(&format!("A {}\n", t));
+        }
+        out.push_str("TC .\n");
+        out
+    }
+
+    /// Apply this patch to a document, returning the patched copy
+    pub fn apply_to(&self, ontology: &TurtleOntology) -> TurtleOntology {
+        let removed: HashSet<&Triple> = self.removed.iter().collect();
+        let mut triples: Vec<Triple> = ontology
+            .triples
+            .iter()
+            .filter(|t| !removed.contains(t))
+            .cloned()
+            .collect();
+        let mut seen: HashSet<Triple> = triples.iter().cloned().collect();
+        for t in &self.added {
+            if seen.insert(t.clone()) {
+                triples.push(t.clone());
+            }
+        }
+        TurtleOntology {
+            base: ontology.base.clone(),
+            prefixes: ontology.prefixes.clone(),
+            triples,
+        }
+    }
+}
+
+impl fmt::Display for RdfPatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RDF patch: +{} -{} triples",
+            self.added.len(),
+            self.removed.len()
+        )
+    }
+}
+
+impl From<&SigmaDiff> for DeltaSigma {
+    fn from(diff: &SigmaDiff) -> Self {
+        DeltaSigma {
+            add_classes: diff
+                .added_classes
+                .iter()
+                .map(class_from_definition)
+                .collect(),
+            remove_classes: diff.removed_classes.clone(),
+            add_properties: diff
+                .added_properties
+                .iter()
+                .map(property_from_definition)
+                .collect(),
+            remove_properties: diff.removed_properties.clone(),
+            ..DeltaSigma::default()
+        }
+    }
+}
+
+fn class_from_definition(class: &ClassDefinition) -> ClassDef {
+    let mut constraints = Vec::new();
+    if !class.subclass_of.is_empty() {
+        constraints.push(format!("rdfs:subClassOf {}", class.subclass_of));
+    }
+    ClassDef {
+        id: class.uri.clone(),
+        name: class.label.clone(),
+        properties: class
+            .properties_required
+            .iter()
+            .chain(&class.properties_optional)
+            .cloned()
+            .collect(),
+        constraints,
+    }
+}
+
+fn property_from_definition(property: &PropertyDefinition) -> PropertyDef {
+    PropertyDef {
+        id: property.uri.clone(),
+        name: property.label.clone(),
+        range: property.range.clone(),
+        domain: property.domain.clone(),
+    }
+}
+
+fn severity_from_iri(iri: &str) -> Result<GuardSeverity> {
+    match iri.strip_prefix(SH) {
+        Some("Violation") => Ok(GuardSeverity::Error),
+        Some("Warning") => Ok(GuardSeverity::Warning),
+        Some("Info") => Ok(GuardSeverity::Info),
+        _ => Err(OntologyIoError::InvalidTerm(format!(
+            "unknown severity <{}>",
+            iri
+        ))),
+    }
+}
+
+#[cfg(feature = "rdf")]
+fn severity_to_iri(severity: &GuardSeverity) -> String {
+    let local = match severity {
+        GuardSeverity::Error => "Violation",
+        GuardSeverity::Warning => "Warning",
+        GuardSeverity::Info => "Info",
+    };
+    format!("{}{}", SH, local)
+}
+
+fn local_name(iri: &str) -> &str {
+    iri.rsplit(['#', '/', ':']).next().unwrap_or(iri)
+}
+
+fn is_simple_local_name(local: &str) -> bool {
+    !local.is_empty()
+        && !local.ends_with('.')
+        && local
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+        && !local.starts_with(['-', '.'])
+}
+
+fn is_bare_literal(datatype: &str, value: &str) -> bool {
+    match datatype.strip_prefix(XSD) {
+        Some("integer") => {
+            let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+            !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+        }
+        Some("decimal") => {
+            let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+            match digits.split_once('.') {
+                Some((int, frac)) => {
+                    !frac.is_empty()
+                        && int.chars().all(|c| c.is_ascii_digit())
+                        && frac.chars().all(|c| c.is_ascii_digit())
+                }
+                None => false,
+            }
+        }
+        Some("boolean") => value == "true" || value == "false",
+        _ => false,
+    }
+}
+
+fn escape_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Parse a Turtle document with oxigraph
+///
+/// Prefixes are returned in declaration order, recovered by
+/// [`declared_prefix_order`] since the parser hands them back unordered. Blank
+/// nodes are relabelled by [`canonicalize_blank_nodes`].
+#[cfg(feature = "rdf")]
+fn parse_turtle(input: &str) -> Result<TurtleOntology> {
+    use oxigraph::io::{RdfFormat, RdfParseError, RdfParser};
+
+    let mut parser = RdfParser::from_format(RdfFormat::Turtle).for_reader(input.as_bytes());
+    let mut triples = Vec::new();
+    for quad in parser.by_ref() {
+        let quad = quad.map_err(|e| match e {
+            RdfParseError::Io(e) => OntologyIoError::Io(e),
+            RdfParseError::Syntax(e) => {
+                let (line, column) = e
+                    .location()
+                    .map(|location| (location.start.line + 1, location.start.column + 1))
+                    .unwrap_or((0, 0));
+                OntologyIoError::Syntax {
+                    line: line as usize,
+                    column: column as usize,
+                    message: e.to_string(),
+                }
+            }
+        })?;
+        triples.push(Triple::new(
+            from_oxigraph_term(quad.subject.into())?,
+            Term::iri(quad.predicate.as_str()),
+            from_oxigraph_term(quad.object)?,
+        ));
+    }
+    canonicalize_blank_nodes(&mut triples);
+
+    let mut prefixes: Vec<(String, String)> = parser
+        .prefixes()
+        .map(|(prefix, ns)| (prefix.to_string(), ns.to_string()))
+        .collect();
+    let declared = declared_prefix_order(input);
+    prefixes.sort_by_cached_key(|(prefix, _)| {
+        let position = declared.iter().position(|p| p == prefix);
+        (position.unwrap_or(usize::MAX), prefix.clone())
+    });
+
+    Ok(TurtleOntology {
+        base: parser.base_iri().map(str::to_string),
+        prefixes,
+        triples,
+    })
+}
+
+/// Prefix names in the order the document declares them
+///
+/// A lexical scan for `@prefix` and SPARQL-style `PREFIX` directives that
+/// skips comments, string literals and IRIs, so a directive-like word inside
+/// one of those is not mistaken for a declaration.
+#[cfg(feature = "rdf")]
+fn declared_prefix_order(input: &str) -> Vec<String> {
+    let bytes = input.as_bytes();
+    let mut names: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'<' => {
+                while i < bytes.len() && bytes[i] != b'>' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            quote @ (b'"' | b'\'') => {
+                let long = bytes[i..].starts_with(&[quote; 3]);
+                i += if long { 3 } else { 1 };
+                while i < bytes.len() {
+                    if bytes[i] == b'\\' {
+                        i += 2;
+                    } else if long && bytes[i..].starts_with(&[quote; 3]) {
+                        i += 3;
+                        break;
+                    } else if !long && bytes[i] == quote {
+                        i += 1;
+                        break;
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            _ => {
+                let at_word_start = i == 0 || !(bytes[i - 1].is_ascii_alphanumeric());
+                let directive = if bytes[i..].starts_with(b"@prefix") {
+                    Some(7)
+                } else if at_word_start
+                    && bytes.len() >= i + 6
+                    && bytes[i..i + 6].eq_ignore_ascii_case(b"PREFIX")
+                {
+                    Some(6)
+                } else {
+                    None
+                };
+                match directive {
+                    Some(len) if bytes.get(i + len).is_some_and(|b| b.is_ascii_whitespace()) => {
+                        let rest = &input[i + len..];
+                        let rest = rest.trim_start();
+                        if let Some(colon) = rest.find(':') {
+                            let name = &rest[..colon];
+                            if !name.contains(char::is_whitespace)
+                                && !names.iter().any(|n| n == name)
+                            {
+                                names.push(name.to_string());
+                            }
+                        }
+                        i += len;
+                    }
+                    _ => i += 1,
+                }
+            }
+        }
+    }
+    names
+}
+
+#[cfg(feature = "rdf")]
+fn from_oxigraph_term(term: oxigraph::model::Term) -> Result<Term> {
+    use oxigraph::model::Term as OxTerm;
+
+    match term {
+        OxTerm::NamedNode(node) => Ok(Term::Iri(node.into_string())),
+        OxTerm::BlankNode(node) => Ok(Term::BlankNode(node.into_string())),
+        OxTerm::Literal(literal) => {
+            let datatype = literal.datatype().as_str();
+            let datatype = (literal.language().is_none() && datatype != format!("{}string", XSD))
+                .then(|| datatype.to_string());
+            Ok(Term::Literal {
+                value: literal.value().to_string(),
+                datatype,
+                language: literal.language().map(str::to_string),
+            })
+        }
+        #[allow(unreachable_patterns)]
+        other => Err(OntologyIoError::InvalidTerm(other.to_string())),
+    }
+}
+
+/// Relabel blank nodes `b1`, `b2`, ... in the order `to_turtle` writes them
+///
+/// The parser gives anonymous nodes random ids; stable labels keep the written
+/// Turtle and `diff` deterministic across parses of the same document.
+#[cfg(feature = "rdf")]
+fn canonicalize_blank_nodes(triples: &mut [Triple]) {
+    let mut subjects: Vec<Term> = Vec::new();
+    for triple in triples.iter() {
+        if !subjects.contains(&triple.subject) {
+            subjects.push(triple.subject.clone());
+        }
+    }
+
+    let mut labels: HashMap<String, String> = HashMap::new();
+    for subject in &subjects {
+        for triple in triples.iter().filter(|t| &t.subject == subject) {
+            for term in [&triple.subject, &triple.object] {
+                if let Term::BlankNode(id) = term {
+                    let next = format!("b{}", labels.len() + 1);
+                    labels.entry(id.clone()).or_insert(next);
+                }
+            }
+        }
+    }
+
+    for triple in triples.iter_mut() {
+        for term in [&mut triple.subject, &mut triple.object] {
+            if let Term::BlankNode(id) = term {
+                if let Some(label) = labels.get(id) {
+                    *id = label.clone();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rdf"))]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+@prefix knhk: <urn:knhk:ontology#> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@prefix sh: <http://www.w3.org/ns/shacl#> .
+
+knhk:Ontology a owl:Ontology ;
+    owl:versionInfo "1.0.0" .
+
+# Core classes
+knhk:Account a owl:Class ;
+    rdfs:label "Account" ;
+    rdfs:comment "A ledger account" .
+
+knhk:LegacyAccount a owl:Class ;
+    rdfs:label "Legacy Account" ;
+    rdfs:subClassOf knhk:Account , [ a owl:Restriction ;
+        owl:onProperty knhk:balance ;
+        owl:maxInclusive 8 ] .
+
+knhk:balance a owl:DatatypeProperty ;
+    rdfs:label "balance" ;
+    rdfs:domain knhk:Account ;
+    rdfs:range xsd:decimal .
+
+knhk:max_run_len a knhk:Guard ;
+    rdfs:label "Max run length" ;
+    knhk:guardExpression "maxCount 8" ;
+    sh:severity sh:Violation .
+"#;
+
+    #[test]
+    fn test_parse_projects_classes_properties_and_guards() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let data = doc.to_ontology_data().unwrap();
+
+        assert_eq!(data.classes.len(), 2);
+        assert_eq!(data.properties.len(), 1);
+        assert_eq!(data.guards.len(), 1);
+
+        let account = &data.classes[0];
+        assert_eq!(account.id, "urn:knhk:ontology#Account");
+        assert_eq!(account.name, "Account");
+        assert_eq!(account.properties, vec!["urn:knhk:ontology#balance"]);
+
+        let guard = &data.guards[0];
+        assert_eq!(guard.expression, "maxCount 8");
+        assert_eq!(guard.severity, GuardSeverity::Error);
+
+        assert_eq!(
+            data.metadata.get("owl:versionInfo"),
+            Some(&serde_json::Value::String("1.0.0".into()))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_preserves_prefixes_and_triples() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let written = doc.to_turtle();
+
+        assert!(written.starts_with("@prefix knhk: <urn:knhk:ontology#> ."));
+        assert!(written.contains("knhk:Account a owl:Class"));
+
+        let reparsed = TurtleOntology::parse(&written).unwrap();
+        assert_eq!(reparsed.prefixes, doc.prefixes);
+        assert!(doc.diff(&reparsed).is_empty());
+    }
+
+    #[test]
+    fn test_prefixes_keep_declaration_order() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let names: Vec<&str> = doc.prefixes.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(names, vec!["knhk", "rdf", "rdfs", "owl", "xsd", "sh"]);
+
+        let doc = TurtleOntology::parse(
+            "# @prefix a: <urn:commented#> .\n\
+             PREFIX z: <urn:z#>\n\
+             @prefix m: <urn:m#> .\n\
+             z:s m:p \"@prefix a: <urn:quoted#> .\" .\n",
+        )
+        .unwrap();
+        let names: Vec<&str> = doc.prefixes.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(names, vec!["z", "m"]);
+    }
+
+    #[test]
+    fn test_apply_delta_removes_subject_and_owned_blank_nodes() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let mut delta = DeltaSigma::new();
+        delta.remove_classes.push("knhk:LegacyAccount".to_string());
+
+        let updated = doc.apply_delta(&delta).unwrap();
+        let patch = doc.diff(&updated);
+
+        assert!(patch.added.is_empty());
+        // type, label, two subClassOf, plus three restriction triples
+        assert_eq!(patch.removed.len(), 7);
+        assert!(!updated
+            .triples
+            .iter()
+            .any(|t| matches!(t.subject, Term::BlankNode(_))));
+    }
+
+    #[test]
+    fn test_apply_delta_rejects_dangling_references() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let mut delta = DeltaSigma::new();
+        delta.remove_classes.push("knhk:Account".to_string());
+
+        match doc.apply_delta(&delta).unwrap_err() {
+            OntologyIoError::DanglingReference { removed, .. } => {
+                assert_eq!(removed, "<urn:knhk:ontology#Account>")
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+
+        // Removing the referrers along with the class leaves nothing dangling
+        delta.remove_classes.push("knhk:LegacyAccount".to_string());
+        delta.remove_properties.push("knhk:balance".to_string());
+        let updated = doc.apply_delta(&delta).unwrap();
+        assert!(updated.to_ontology_data().unwrap().classes.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_terms_are_reported() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let mut delta = DeltaSigma::new();
+        delta.add_properties.push(PropertyDef {
+            id: "knhk:owner".to_string(),
+            name: "owner".to_string(),
+            domain: "knhk:Account".to_string(),
+            range: "missing:Party".to_string(),
+        });
+        assert!(matches!(
+            doc.apply_delta(&delta),
+            Err(OntologyIoError::UndefinedPrefix(_))
+        ));
+
+        let doc = TurtleOntology::parse(&format!(
+            "{}knhk:loose a knhk:Guard ; knhk:guardExpression \"x\" ; sh:severity sh:Fatal .",
+            SAMPLE
+        ))
+        .unwrap();
+        assert!(matches!(
+            doc.to_ontology_data(),
+            Err(OntologyIoError::InvalidTerm(_))
+        ));
+    }
+
+    #[test]
+    fn test_sigma_diff_applies_and_reads_back() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let diff = SigmaDiff {
+            added_classes: vec![ClassDefinition {
+                uri: "knhk:RetirementAccount".to_string(),
+                label: "Retirement Account".to_string(),
+                subclass_of: "knhk:Account".to_string(),
+                properties_required: vec![],
+                properties_optional: vec![],
+            }],
+            ..SigmaDiff::default()
+        };
+
+        let updated = doc.apply_delta(&DeltaSigma::from(&diff)).unwrap();
+        let data = updated.to_ontology_data().unwrap();
+        let added = data
+            .classes
+            .iter()
+            .find(|c| c.id == "urn:knhk:ontology#RetirementAccount")
+            .unwrap();
+        assert_eq!(added.name, "Retirement Account");
+        assert_eq!(
+            added.constraints,
+            vec!["<http://www.w3.org/2000/01/rdf-schema#subClassOf> <urn:knhk:ontology#Account>"]
+        );
+
+        let turtle = updated.to_turtle();
+        assert!(turtle.contains("knhk:RetirementAccount a owl:Class"));
+        assert!(turtle.contains("rdfs:subClassOf knhk:Account"));
+    }
+
+    #[test]
+    fn test_patch_format_and_reapply() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+        let mut delta = DeltaSigma::new();
+        delta.remove_guards.push("knhk:max_run_len".to_string());
+        delta.add_guards.push(GuardDef {
+            id: "knhk:max_batch".to_string(),
+            name: "Max batch".to_string(),
+            expression: "maxCount 64".to_string(),
+            severity: GuardSeverity::Warning,
+        });
+
+        let updated = doc.apply_delta(&delta).unwrap();
+        let patch = doc.diff(&updated);
+        let text = patch.to_patch_string();
+
+        assert!(text.contains("TX ."));
+        assert!(text.contains("D <urn:knhk:ontology#max_run_len>"));
+        assert!(text.contains("A <urn:knhk:ontology#max_batch>"));
+        assert!(text.ends_with("TC .\n"));
+        assert_eq!(patch.content_hash().len(), 64);
+
+        let replayed = patch.apply_to(&doc);
+        assert!(replayed.diff(&updated).is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error_reports_position() {
+        let err = TurtleOntology::parse("@prefix ex: <urn:ex#> .\nex:a ex:b .").unwrap_err();
+        match err {
+            OntologyIoError::Syntax { line, .. } => assert_eq!(line, 2),
+            other => panic!("unexpected error: {}", other),
+        }
+
+        let err = TurtleOntology::parse("missing:a missing:b missing:c .").unwrap_err();
+        match err {
+            OntologyIoError::Syntax { line, message, .. } => {
+                assert_eq!(line, 1);
+                assert!(message.contains("missing"), "{}", message);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_expand_rejects_undeclared_prefixes() {
+        let doc = TurtleOntology::parse(SAMPLE).unwrap();
+
+        assert_eq!(
+            doc.expand("knhk:Account").unwrap(),
+            "urn:knhk:ontology#Account"
+        );
+        assert_eq!(
+            doc.expand("urn:example:Thing").unwrap(),
+            "urn:example:Thing"
+        );
+        assert_eq!(
+            doc.expand("<foo:Bar>").unwrap(),
+            "foo:Bar",
+            "Bracketed IRIs are taken as written"
+        );
+        assert!(matches!(
+            doc.expand("foo:Bar"),
+            Err(OntologyIoError::UndefinedPrefix(_))
+        ));
+        assert!(matches!(
+            doc.expand("Account"),
+            Err(OntologyIoError::UndefinedPrefix(_))
+        ));
+    }
+
+    #[test]
+    fn test_literals_and_collections() {
+        let doc = TurtleOntology::parse(
+            r#"@prefix ex: <urn:ex#> .
+ex:s ex:p "say \"hi\""@en , 42 , 1.5 , true , """multi
+line""" ;
+    ex:list ( ex:a ex:b ) ."#,
+        )
+        .unwrap();
+
+        // 5 objects + list head + 4 list triples
+        assert_eq!(doc.triples.len(), 10);
+        let reparsed = TurtleOntology::parse(&doc.to_turtle()).unwrap();
+        assert!(doc.diff(&reparsed).is_empty());
+    }
+}
@@ -16,7 +16,8 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Main configuration structure
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(default)]
     pub knhk: KnhkConfig,
@@ -30,7 +31,8 @@ pub struct Config {
     pub routes: BTreeMap<String, RouteConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct KnhkConfig {
     #[serde(default = "default_version")]
     pub version: String,
@@ -55,7 +57,8 @@ impl Default for KnhkConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ConnectorConfig {
     pub r#type: String,
     pub bootstrap_servers: Option<Vec<String>>,
@@ -75,7 +78,8 @@ fn default_max_batch_size() -> u64 {
     1000
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EpochConfig {
     #[serde(default = "default_tau")]
     pub tau: u64,
@@ -91,7 +95,8 @@ fn default_ordering() -> String {
     "deterministic".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct HooksConfig {
     #[serde(default = "default_max_count")]
     pub max_count: u64,
@@ -109,15 +114,42 @@ impl Default for HooksConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RouteConfig {
     pub kind: String,
     pub target: String,
     pub encode: Option<String>,
 }
 
+/// Default per-user config file (~/.knhk/config.toml or %APPDATA%/knhk/config.toml)
+#[cfg(feature = "std")]
+pub fn default_config_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let mut path = PathBuf::from(
+            std::env::var("APPDATA").unwrap_or_else(|_| "C:\\Users\\Public".to_string()),
+        );
+        path.push("knhk");
+        path.push("config.toml");
+        path
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        let mut path = PathBuf::from(home);
+        path.push(".knhk");
+        path.push("config.toml");
+        path
+    }
+}
+
 /// Load configuration from file
 ///
+/// Unknown keys are rejected. Use `ConfigLoader` for the full set of layers
+/// and for diagnostics with file and line.
+///
 /// # Arguments
 /// * `path` - Path to config file (defaults to ~/.knhk/config.toml or %APPDATA%/knhk/config.toml)
 #[cfg(feature = "std")]
@@ -125,26 +157,7 @@ pub fn load_config(path: Option<PathBuf>) -> Result<Config, String> {
     use std::fs;
     use toml::de::Error as TomlError;
 
-    let config_path = path.unwrap_or_else(|| {
-        #[cfg(target_os = "windows")]
-        {
-            let mut path = PathBuf::from(
-                std::env::var("APPDATA").unwrap_or_else(|_| "C:\\Users\\Public".to_string()),
-            );
-            path.push("knhk");
-            path.push("config.toml");
-            path
-        }
-
-        #[cfg(not(target_os = "windows"))]
-        {
-            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-            let mut path = PathBuf::from(home);
-            path.push(".knhk");
-            path.push("config.toml");
-            path
-        }
-    });
+    let config_path = path.unwrap_or_else(default_config_path);
 
     // If config file doesn't exist, return default config
     if !config_path.exists() {
@@ -0,0 +1,389 @@
+// knhk-config/src/diagnostics.rs
+// Configuration diagnostics with source locations
+
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Configuration layer, in increasing order of precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConfigLayer {
+    /// System-wide file (`/etc/knhk/config.toml`)
+    System,
+    /// Per-user file (`~/.knhk/config.toml`)
+    User,
+    /// Project file (`.knhk/config.toml` in the working directory)
+    Project,
+    /// Profile file for the active `knhk.context`
+    Profile,
+    /// `KNHK_*` environment variables
+    Env,
+    /// Command line overrides (`key.path=value`)
+    Cli,
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConfigLayer::System => "system",
+            ConfigLayer::User => "user",
+            ConfigLayer::Project => "project",
+            ConfigLayer::Profile => "profile",
+            ConfigLayer::Env => "env",
+            ConfigLayer::Cli => "cli",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What is wrong with a configuration value
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// The file is not valid TOML
+    Parse(String),
+    /// A key that is not in the schema (typos end up here)
+    UnknownField { expected: Vec<String> },
+    /// A value of the wrong TOML type
+    InvalidType {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// A required key missing after all layers are merged
+    MissingField,
+    /// An integer outside the allowed range
+    OutOfRange {
+        value: i64,
+        minimum: i64,
+        maximum: Option<i64>,
+    },
+    /// A string that is not one of the allowed values
+    InvalidValue { value: String, allowed: Vec<String> },
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::Parse(msg) => write!(f, "parse error: {}", msg),
+            DiagnosticKind::UnknownField { expected } if expected.is_empty() => {
+                write!(f, "unknown field")
+            }
+            DiagnosticKind::UnknownField { expected } => {
+                write!(f, "unknown field, expected one of {}", quoted(expected))
+            }
+            DiagnosticKind::InvalidType { expected, found } => {
+                write!(f, "invalid type: expected {}, found {}", expected, found)
+            }
+            DiagnosticKind::MissingField => write!(f, "missing required field"),
+            DiagnosticKind::OutOfRange {
+                value,
+                minimum,
+                maximum: Some(maximum),
+            } => write!(
+                f,
+                "value {} out of range, expected {}..={}",
+                value, minimum, maximum
+            ),
+            DiagnosticKind::OutOfRange { value, minimum, .. } => {
+                write!(f, "value {} out of range, expected >= {}", value, minimum)
+            }
+            DiagnosticKind::InvalidValue { value, allowed } => write!(
+                f,
+                "invalid value `{}`, expected one of {}",
+                value,
+                quoted(allowed)
+            ),
+        }
+    }
+}
+
+fn quoted(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| format!("`{}`", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// One problem found while loading configuration
+///
+/// `origin` is the file path for file layers, the variable name for `Env` and
+/// the argument for `Cli`. Line and column are 1-based and only known for
+/// files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub layer: ConfigLayer,
+    pub origin: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Dotted key path (`connectors.kafka.max_run_len`), empty for the root
+    pub path: String,
+    pub kind: DiagnosticKind,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.origin)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        if self.path.is_empty() {
+            write!(f, ": {}", self.kind)
+        } else {
+            write!(f, ": `{}`: {}", self.path, self.kind)
+        }
+    }
+}
+
+/// Error from layered configuration loading
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// A configuration file exists but could not be read
+    Io { path: String, message: String },
+    /// One or more layers failed validation
+    Invalid(Vec<Diagnostic>),
+}
+
+impl ConfigError {
+    /// Diagnostics of an invalid configuration (empty for I/O errors)
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        match self {
+            ConfigError::Io { .. } => &[],
+            ConfigError::Invalid(diagnostics) => diagnostics,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, message } => {
+                write!(f, "Failed to read config file {}: {}", path, message)
+            }
+            ConfigError::Invalid(diagnostics) => {
+                write!(f, "Invalid configuration ({} errors)", diagnostics.len())?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n  {}", diagnostic)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+/// Line and column of every key and table header in a TOML document
+///
+/// A light scanner rather than a second parser: it only runs on documents
+/// that already parsed, and only needs to find where keys are written.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SourceMap {
+    keys: BTreeMap<Vec<String>, (usize, usize)>,
+}
+
+impl SourceMap {
+    pub(crate) fn scan(source: &str) -> Self {
+        let mut keys = BTreeMap::new();
+        let mut table: Vec<String> = Vec::new();
+        let mut multiline: Option<&'static str> = None;
+        let mut depth: i32 = 0;
+
+        for (index, raw) in source.lines().enumerate() {
+            let line = index + 1;
+            if let Some(delimiter) = multiline {
+                if raw.contains(delimiter) {
+                    multiline = None;
+                }
+                continue;
+            }
+            if depth > 0 {
+                depth += bracket_balance(raw);
+                continue;
+            }
+
+            let trimmed = raw.trim_start();
+            let column = raw.len() - trimmed.len() + 1;
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = table_header(trimmed) {
+                table = split_key(header);
+                record(&mut keys, &table, line, column);
+                continue;
+            }
+
+            let Some(eq) = find_unquoted(trimmed, '=') else {
+                continue;
+            };
+            let mut path = table.clone();
+            path.extend(split_key(&trimmed[..eq]));
+            record(&mut keys, &path, line, column);
+
+            let value = &trimmed[eq + 1..];
+            for delimiter in ["\"\"\"", "'''"] {
+                if value.matches(delimiter).count() % 2 == 1 {
+                    multiline = Some(delimiter);
+                }
+            }
+            if multiline.is_none() {
+                depth = bracket_balance(value).max(0);
+            }
+        }
+
+        Self { keys }
+    }
+
+    /// Location of `path`, or of its closest enclosing key or table
+    pub(crate) fn locate(&self, path: &[String]) -> Option<(usize, usize)> {
+        (1..=path.len())
+            .rev()
+            .find_map(|len| self.keys.get(&path[..len]).copied())
+    }
+}
+
+/// Record a key and, for dotted keys, the tables it implicitly creates
+fn record(
+    keys: &mut BTreeMap<Vec<String>, (usize, usize)>,
+    path: &[String],
+    line: usize,
+    column: usize,
+) {
+    for len in 1..path.len() {
+        keys.entry(path[..len].to_vec()).or_insert((line, column));
+    }
+    keys.insert(path.to_vec(), (line, column));
+}
+
+fn table_header(line: &str) -> Option<&str> {
+    let line = strip_comment(line).trim_end();
+    if let Some(inner) = line.strip_prefix("[[") {
+        return inner.strip_suffix("]]");
+    }
+    line.strip_prefix('[')?.strip_suffix(']')
+}
+
+fn strip_comment(line: &str) -> &str {
+    match find_unquoted(line, '#') {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+/// Byte index of the first `needle` outside quoted strings
+fn find_unquoted(line: &str, needle: char) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == needle => return Some(index),
+            None => {}
+        }
+    }
+    None
+}
+
+/// Split a (possibly dotted, possibly quoted) key into its segments
+fn split_key(key: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut rest = key.trim();
+    while !rest.is_empty() {
+        let end = find_unquoted(rest, '.').unwrap_or(rest.len());
+        let segment = rest[..end].trim();
+        let segment = segment
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .or_else(|| {
+                segment
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+            })
+            .unwrap_or(segment);
+        segments.push(segment.to_string());
+        rest = rest.get(end + 1..).unwrap_or("").trim_start();
+    }
+    segments
+}
+
+/// Opened minus closed brackets and braces outside strings and comments
+fn bracket_balance(line: &str) -> i32 {
+    let mut balance = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' | '{' => balance += 1,
+                ']' | '}' => balance -= 1,
+                '#' => break,
+                _ => {}
+            },
+        }
+    }
+    balance
+}
+
+/// 1-based line and column of a byte offset
+pub(crate) fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn path(key: &str) -> Vec<String> {
+        key.split('.').map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_source_map_locates_keys_and_tables() {
+        let source = r#"
+[knhk]
+context = "dev" # comment = ignored
+
+[connectors.kafka]
+bootstrap_servers = [
+    "a=1",
+    "b",
+]
+topic = """
+x = 1
+"""
+epochs.default.tau = 4
+"#;
+        let map = SourceMap::scan(source);
+        assert_eq!(map.locate(&path("knhk.context")), Some((3, 1)));
+        assert_eq!(map.locate(&path("connectors.kafka")), Some((5, 1)));
+        assert_eq!(map.locate(&path("connectors.kafka.topic")), Some((10, 1)));
+        assert_eq!(map.locate(&path("connectors.kafka.type")), Some((5, 1)));
+        assert_eq!(
+            map.locate(&path("connectors.kafka.epochs.default.tau")),
+            Some((13, 1))
+        );
+        assert_eq!(map.locate(&path("x")), None);
+        assert_eq!(line_column(source, source.find("topic").unwrap()), (10, 1));
+    }
+}
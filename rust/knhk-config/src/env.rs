@@ -5,7 +5,9 @@ extern crate alloc;
 
 use crate::config::Config;
 use alloc::collections::BTreeMap;
+use alloc::format;
 use alloc::string::{String, ToString};
+use alloc::vec;
 use alloc::vec::Vec;
 
 #[cfg(feature = "std")]
@@ -135,6 +137,44 @@ pub fn apply_env_overrides(config: &mut Config, env_vars: &BTreeMap<String, Stri
     }
 }
 
+/// Environment overrides as configuration tables, one per variable
+///
+/// Keys follow `apply_env_overrides`, plus `hooks_<key>` and
+/// `route_<name>_<key>`. Variables that are not configuration (`KNHK_TRACE`,
+/// `KNHK_LOG_LEVEL`, ...) are skipped; values are typed by the schema so that
+/// validation can report what does not fit.
+#[cfg(feature = "std")]
+pub fn env_layer(env_vars: &BTreeMap<String, String>) -> Vec<(String, toml::Table)> {
+    let schema = Config::schema();
+    let mut layer = Vec::new();
+
+    for (key, value) in env_vars.iter() {
+        let path: Vec<String> = if key == "context" {
+            vec!["knhk".to_string(), "context".to_string()]
+        } else if let Some(rest) = key.strip_prefix("hooks_") {
+            vec!["hooks".to_string(), rest.to_string()]
+        } else {
+            let section = match key.split('_').next() {
+                Some("connector") => "connectors",
+                Some("epoch") => "epochs",
+                Some("route") => "routes",
+                _ => continue,
+            };
+            let mut path = vec![section.to_string()];
+            path.extend(key.splitn(3, '_').skip(1).map(|s| s.to_string()));
+            path
+        };
+
+        let value = schema.override_value(&path, value);
+        layer.push((
+            format!("KNHK_{}", key.to_uppercase()),
+            crate::layered::nested(&path, value),
+        ));
+    }
+
+    layer
+}
+
 #[cfg(not(feature = "std"))]
 pub fn apply_env_overrides(_config: &mut Config, _env_vars: &BTreeMap<String, String>) {
     // In no_std mode, no-op
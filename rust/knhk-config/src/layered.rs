@@ -0,0 +1,471 @@
+// knhk-config/src/layered.rs
+// Layered configuration: system → user → project → profile → env → CLI
+
+use crate::config::{default_config_path, Config};
+use crate::diagnostics::{
+    line_column, ConfigError, ConfigLayer, Diagnostic, DiagnosticKind, SourceMap,
+};
+use crate::env::{env_layer, load_env_config};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// One configuration source that contributed to a loaded configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerSource {
+    pub layer: ConfigLayer,
+    /// File path, variable name or command line argument
+    pub origin: String,
+}
+
+/// Result of loading all layers
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub config: Config,
+    /// Sources in precedence order (later ones win)
+    pub sources: Vec<LayerSource>,
+}
+
+/// A parsed layer document
+struct Document {
+    layer: ConfigLayer,
+    origin: String,
+    table: Table,
+    source_map: Option<SourceMap>,
+}
+
+impl Document {
+    fn diagnostic(&self, path: &[String], kind: DiagnosticKind) -> Diagnostic {
+        let location = self.source_map.as_ref().and_then(|map| map.locate(path));
+        Diagnostic {
+            layer: self.layer,
+            origin: self.origin.clone(),
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            path: path.join("."),
+            kind,
+        }
+    }
+}
+
+/// Loads configuration from every layer and validates it against the schema
+///
+/// Later layers override earlier ones key by key; tables are merged, other
+/// values (including arrays) are replaced. The profile layer is
+/// `profiles/<context>.toml` next to the user and project files, where
+/// `context` is `knhk.context` as set by the other layers. Missing files are
+/// skipped; unknown keys, wrong types and out-of-range values in any layer fail
+/// the load with one diagnostic each.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    system: Option<PathBuf>,
+    user: Option<PathBuf>,
+    project: Option<PathBuf>,
+    env_vars: BTreeMap<String, String>,
+    cli_overrides: Vec<String>,
+}
+
+impl ConfigLoader {
+    /// Loader without any layers (add them with the builder methods)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loader for the standard locations and the process environment
+    ///
+    /// System: `/etc/knhk/config.toml` (`%PROGRAMDATA%\knhk\config.toml` on
+    /// Windows), user: `~/.knhk/config.toml`, project: `.knhk/config.toml` in
+    /// the working directory.
+    pub fn from_environment() -> Self {
+        let mut loader = Self::new()
+            .system_file(default_system_path())
+            .user_file(default_config_path())
+            .env_vars(load_env_config());
+        if let Ok(dir) = std::env::current_dir() {
+            loader = loader.project_file(dir.join(".knhk").join("config.toml"));
+        }
+        loader
+    }
+
+    /// Set the system-wide config file
+    pub fn system_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.system = Some(path.into());
+        self
+    }
+
+    /// Set the per-user config file
+    pub fn user_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.user = Some(path.into());
+        self
+    }
+
+    /// Set the project config file
+    pub fn project_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.project = Some(path.into());
+        self
+    }
+
+    /// Set the environment overrides (normalized as by `load_env_config`)
+    pub fn env_vars(mut self, env_vars: BTreeMap<String, String>) -> Self {
+        self.env_vars = env_vars;
+        self
+    }
+
+    /// Add a command line override such as `connectors.kafka.topic=triples`
+    pub fn cli_override(mut self, assignment: impl Into<String>) -> Self {
+        self.cli_overrides.push(assignment.into());
+        self
+    }
+
+    /// Profile files for a context, lowest precedence first
+    pub fn profile_paths(&self, context: &str) -> Vec<PathBuf> {
+        [&self.user, &self.project]
+            .into_iter()
+            .flatten()
+            .filter_map(|file| file.parent())
+            .map(|dir| dir.join("profiles").join(format!("{}.toml", context)))
+            .collect()
+    }
+
+    /// Load, merge and validate all layers
+    pub fn load(&self) -> Result<LoadedConfig, ConfigError> {
+        let schema = Config::schema();
+        let mut diagnostics = Vec::new();
+
+        let mut files = Vec::new();
+        for (layer, path) in [
+            (ConfigLayer::System, &self.system),
+            (ConfigLayer::User, &self.user),
+            (ConfigLayer::Project, &self.project),
+        ] {
+            if let Some(path) = path {
+                files.extend(read_document(layer, path, &mut diagnostics)?);
+            }
+        }
+
+        let mut overrides: Vec<Document> = env_layer(&self.env_vars)
+            .into_iter()
+            .map(|(origin, table)| Document {
+                layer: ConfigLayer::Env,
+                origin,
+                table,
+                source_map: None,
+            })
+            .collect();
+        for assignment in &self.cli_overrides {
+            match parse_assignment(assignment) {
+                Some((path, raw)) => overrides.push(Document {
+                    layer: ConfigLayer::Cli,
+                    origin: assignment.clone(),
+                    table: nested(&path, schema.override_value(&path, raw)),
+                    source_map: None,
+                }),
+                None => diagnostics.push(Diagnostic {
+                    layer: ConfigLayer::Cli,
+                    origin: assignment.clone(),
+                    line: None,
+                    column: None,
+                    path: String::new(),
+                    kind: DiagnosticKind::Parse("expected `key.path=value`".to_string()),
+                }),
+            }
+        }
+
+        // The profile is chosen by the context the other layers settle on
+        let context = {
+            let merged = merge_all(files.iter().chain(overrides.iter()));
+            match lookup(&merged, &["knhk", "context"]) {
+                Some(Value::String(context)) => context.clone(),
+                _ => Config::default().knhk.context,
+            }
+        };
+        for path in self.profile_paths(&context) {
+            files.extend(read_document(
+                ConfigLayer::Profile,
+                &path,
+                &mut diagnostics,
+            )?);
+        }
+        let documents: Vec<Document> = files.into_iter().chain(overrides).collect();
+
+        for document in &documents {
+            let value = Value::Table(document.table.clone());
+            for (path, kind) in schema.validate(&value, false) {
+                diagnostics.push(document.diagnostic(&path, kind));
+            }
+        }
+        if !diagnostics.is_empty() {
+            return Err(ConfigError::Invalid(diagnostics));
+        }
+
+        // Required keys can only be checked once the layers are merged
+        let merged = merge_all(documents.iter());
+        for (path, kind) in schema.validate(&Value::Table(merged.clone()), true) {
+            let parent = &path[..path.len().saturating_sub(1)];
+            let segments: Vec<&str> = parent.iter().map(String::as_str).collect();
+            if let Some(document) = documents
+                .iter()
+                .rev()
+                .find(|d| lookup(&d.table, &segments).is_some())
+            {
+                diagnostics.push(document.diagnostic(&path, kind));
+            }
+        }
+        if !diagnostics.is_empty() {
+            return Err(ConfigError::Invalid(diagnostics));
+        }
+
+        let config = Value::Table(merged).try_into::<Config>().map_err(|e| {
+            ConfigError::Invalid(vec![Diagnostic {
+                layer: documents.last().map_or(ConfigLayer::System, |d| d.layer),
+                origin: "merged configuration".to_string(),
+                line: None,
+                column: None,
+                path: String::new(),
+                kind: DiagnosticKind::Parse(e.to_string()),
+            }])
+        })?;
+        let sources = documents
+            .into_iter()
+            .map(|d| LayerSource {
+                layer: d.layer,
+                origin: d.origin,
+            })
+            .collect();
+
+        Ok(LoadedConfig { config, sources })
+    }
+}
+
+fn default_system_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let mut path = PathBuf::from(
+            std::env::var("PROGRAMDATA").unwrap_or_else(|_| "C:\\ProgramData".to_string()),
+        );
+        path.push("knhk");
+        path.push("config.toml");
+        path
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        PathBuf::from("/etc/knhk/config.toml")
+    }
+}
+
+/// Read and parse a layer file; `None` if it does not exist
+///
+/// Parse errors are collected so every broken layer is reported at once.
+fn read_document(
+    layer: ConfigLayer,
+    path: &Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Option<Document>, ConfigError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let origin = path.display().to_string();
+    let content = fs::read_to_string(path).map_err(|e| ConfigError::Io {
+        path: origin.clone(),
+        message: e.to_string(),
+    })?;
+
+    match toml::from_str::<Table>(&content) {
+        Ok(table) => Ok(Some(Document {
+            layer,
+            origin,
+            table,
+            source_map: Some(SourceMap::scan(&content)),
+        })),
+        Err(e) => {
+            let location = e.span().map(|span| line_column(&content, span.start));
+            diagnostics.push(Diagnostic {
+                layer,
+                origin,
+                line: location.map(|(line, _)| line),
+                column: location.map(|(_, column)| column),
+                path: String::new(),
+                kind: DiagnosticKind::Parse(e.message().to_string()),
+            });
+            Ok(None)
+        }
+    }
+}
+
+/// Split `a.b.c=value` into its key path and raw value
+fn parse_assignment(assignment: &str) -> Option<(Vec<String>, &str)> {
+    let (key, value) = assignment.split_once('=')?;
+    let path: Vec<String> = key
+        .trim()
+        .split('.')
+        .map(|s| s.trim().to_string())
+        .collect();
+    if path.iter().any(|segment| segment.is_empty()) {
+        return None;
+    }
+    Some((path, value.trim()))
+}
+
+/// A table holding `value` at `path`
+pub(crate) fn nested(path: &[String], mut value: Value) -> Table {
+    for key in path.iter().skip(1).rev() {
+        let mut table = Table::new();
+        table.insert(key.clone(), value);
+        value = Value::Table(table);
+    }
+    let mut table = Table::new();
+    if let Some(first) = path.first() {
+        table.insert(first.clone(), value);
+    }
+    table
+}
+
+fn merge_all<'a>(documents: impl Iterator<Item = &'a Document>) -> Table {
+    let mut merged = Table::new();
+    for document in documents {
+        merge(&mut merged, &document.table);
+    }
+    merged
+}
+
+/// Merge `overlay` into `base`, recursing into tables present in both
+fn merge(base: &mut Table, overlay: &Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => merge(base, overlay),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn lookup<'a>(table: &'a Table, path: &[&str]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    let mut value = table.get(*first)?;
+    for key in rest {
+        value = value.as_table()?.get(*key)?;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("knhk_layered_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("project").join("profiles")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let dir = temp_dir("order");
+        let system = dir.join("system.toml");
+        let project = dir.join("project").join("config.toml");
+        fs::write(
+            &system,
+            "[knhk]\ncontext = \"prod\"\n\n[connectors.kafka]\ntype = \"kafka\"\ntopic = \"system\"\nmax_run_len = 4\n",
+        )
+        .unwrap();
+        fs::write(&project, "[connectors.kafka]\ntopic = \"project\"\n").unwrap();
+        fs::write(
+            dir.join("project").join("profiles").join("prod.toml"),
+            "[connectors.kafka]\ntopic = \"profile\"\nmax_batch_size = 50\n",
+        )
+        .unwrap();
+
+        let mut env_vars = BTreeMap::new();
+        env_vars.insert("connector_kafka_topic".to_string(), "env".to_string());
+        env_vars.insert("trace".to_string(), "1".to_string());
+        let loader = ConfigLoader::new()
+            .system_file(&system)
+            .project_file(&project)
+            .env_vars(env_vars);
+
+        let loaded = loader.load().unwrap();
+        let kafka = &loaded.config.connectors["kafka"];
+        assert_eq!(kafka.topic.as_deref(), Some("env"));
+        assert_eq!(kafka.max_run_len, 4);
+        assert_eq!(kafka.max_batch_size, 50);
+        let layers: Vec<ConfigLayer> = loaded.sources.iter().map(|s| s.layer).collect();
+        assert_eq!(
+            layers,
+            vec![
+                ConfigLayer::System,
+                ConfigLayer::Project,
+                ConfigLayer::Profile,
+                ConfigLayer::Env
+            ]
+        );
+
+        let loaded = loader
+            .cli_override("connectors.kafka.topic=cli")
+            .cli_override("knhk.context=dev")
+            .load()
+            .unwrap();
+        let kafka = &loaded.config.connectors["kafka"];
+        assert_eq!(kafka.topic.as_deref(), Some("cli"));
+        assert_eq!(kafka.max_batch_size, 1000, "prod profile no longer applies");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_diagnostics_point_at_file_and_line() {
+        let dir = temp_dir("diagnostics");
+        let project = dir.join("project").join("config.toml");
+        fs::write(
+            &project,
+            "[knhk]\ncontxt = \"prod\"\n\n[routes.alerts]\nkind = \"webhook\"\n",
+        )
+        .unwrap();
+
+        let mut env_vars = BTreeMap::new();
+        env_vars.insert("epoch_default_tau".to_string(), "fast".to_string());
+        let error = ConfigLoader::new()
+            .project_file(&project)
+            .env_vars(env_vars)
+            .load()
+            .unwrap_err();
+        let diagnostics = error.diagnostics();
+        assert_eq!(diagnostics.len(), 2, "{}", error);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[0].path, "knhk.contxt");
+        assert!(matches!(
+            diagnostics[0].kind,
+            DiagnosticKind::UnknownField { .. }
+        ));
+        assert_eq!(diagnostics[1].origin, "KNHK_EPOCH_DEFAULT_TAU");
+        assert!(diagnostics[1].to_string().contains("expected integer"));
+
+        // Fixing the typos leaves the route without a target
+        fs::write(
+            &project,
+            "[knhk]\ncontext = \"prod\"\n\n[routes.alerts]\nkind = \"webhook\"\n",
+        )
+        .unwrap();
+        let error = ConfigLoader::new()
+            .project_file(&project)
+            .load()
+            .unwrap_err();
+        let diagnostic = &error.diagnostics()[0];
+        assert_eq!(diagnostic.path, "routes.alerts.target");
+        assert_eq!(diagnostic.kind, DiagnosticKind::MissingField);
+        assert_eq!(
+            diagnostic.to_string(),
+            format!(
+                "{}:4:1: `routes.alerts.target`: missing required field",
+                project.display()
+            )
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,5 +1,6 @@
 // knhk-config v0.1.0 - Configuration Management
-// TOML-based configuration with environment variable overrides
+// Layered TOML configuration with environment and command line overrides
+// and schema validation
 
 // CRITICAL: Enforce proper error handling - no unwrap/expect in production code
 #![deny(clippy::unwrap_used)]
@@ -11,9 +12,19 @@ extern crate alloc;
 extern crate std;
 
 pub mod config;
+pub mod diagnostics;
 pub mod env;
+#[cfg(feature = "std")]
+pub mod layered;
+pub mod schema;
 
 pub use config::load_config;
 pub use config::Config;
+pub use diagnostics::{ConfigError, ConfigLayer, Diagnostic, DiagnosticKind};
 pub use env::apply_env_overrides;
+#[cfg(feature = "std")]
+pub use env::env_layer;
 pub use env::load_env_config;
+#[cfg(feature = "std")]
+pub use layered::{ConfigLoader, LayerSource, LoadedConfig};
+pub use schema::{SchemaField, SchemaNode};
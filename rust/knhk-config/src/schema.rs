@@ -1,169 +1,396 @@
-// Configuration schema and types
+// knhk-config/src/schema.rs
+// Configuration schema, JSON Schema export and strict validation
 
-use serde::{Deserialize, Serialize};
-use alloc::vec::Vec;
-use alloc::string::String;
-use alloc::collections::BTreeMap;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct KnhkConfig {
-    #[serde(default)]
-    pub knhk: KnhkSection,
-    #[serde(default)]
-    pub connectors: BTreeMap<String, ConnectorConfig>,
-    #[serde(default)]
-    pub epochs: BTreeMap<String, EpochConfig>,
-    #[serde(default)]
-    pub hooks: HooksSection,
-    #[serde(default)]
-    pub routes: BTreeMap<String, RouteConfig>,
-}
+extern crate alloc;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct KnhkSection {
-    #[serde(default = "default_version")]
-    pub version: String,
-    #[serde(default = "default_context")]
-    pub context: String,
-}
+use crate::config::Config;
+use crate::diagnostics::DiagnosticKind;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use serde_json::{json, Map, Value as JsonValue};
+use toml::Value;
 
-fn default_version() -> String {
-    "0.5.0".to_string()
+/// Shape of a configuration value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaNode {
+    /// A string, optionally restricted to `one_of`
+    String { one_of: Vec<&'static str> },
+    /// An integer in `minimum..=maximum`
+    Integer { minimum: i64, maximum: Option<i64> },
+    /// An array of values of one shape
+    Array(Box<SchemaNode>),
+    /// A table with a fixed set of keys
+    Table(Vec<SchemaField>),
+    /// A table of named entries (connectors, epochs, routes)
+    Map(Box<SchemaNode>),
 }
 
-fn default_context() -> String {
-    "default".to_string()
+/// A key of a `SchemaNode::Table`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaField {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Must be present once all layers are merged
+    pub required: bool,
+    pub node: SchemaNode,
 }
 
-impl Default for KnhkSection {
-    fn default() -> Self {
+impl SchemaField {
+    fn optional(name: &'static str, description: &'static str, node: SchemaNode) -> Self {
         Self {
-            version: default_version(),
-            context: default_context(),
+            name,
+            description,
+            required: false,
+            node,
         }
     }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConnectorConfig {
-    #[serde(default)]
-    pub r#type: String,
-    #[serde(default)]
-    pub bootstrap_servers: Vec<String>,
-    #[serde(default)]
-    pub topic: String,
-    #[serde(default)]
-    pub schema: String,
-    #[serde(default = "default_max_run_len")]
-    pub max_run_len: u64,
-    #[serde(default = "default_max_batch_size")]
-    pub max_batch_size: u64,
-}
-
-fn default_max_run_len() -> u64 {
-    8
-}
 
-fn default_max_batch_size() -> u64 {
-    1000
-}
-
-impl Default for ConnectorConfig {
-    fn default() -> Self {
+    fn required(name: &'static str, description: &'static str, node: SchemaNode) -> Self {
         Self {
-            r#type: String::new(),
-            bootstrap_servers: Vec::new(),
-            topic: String::new(),
-            schema: String::new(),
-            max_run_len: default_max_run_len(),
-            max_batch_size: default_max_batch_size(),
+            name,
+            description,
+            required: true,
+            node,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EpochConfig {
-    #[serde(default = "default_tau")]
-    pub tau: u64,
-    #[serde(default = "default_ordering")]
-    pub ordering: String,
+fn string() -> SchemaNode {
+    SchemaNode::String { one_of: vec![] }
 }
 
-fn default_tau() -> u64 {
-    8
+fn integer(minimum: i64, maximum: Option<i64>) -> SchemaNode {
+    SchemaNode::Integer { minimum, maximum }
 }
 
-fn default_ordering() -> String {
-    "deterministic".to_string()
-}
+/// Guard limit on run length and epoch ticks (Chatman constant)
+const MAX_TICKS: i64 = 8;
 
-impl Default for EpochConfig {
-    fn default() -> Self {
-        Self {
-            tau: default_tau(),
-            ordering: default_ordering(),
-        }
+impl Config {
+    /// Schema of the configuration file, mirroring the `Config` types
+    pub fn schema() -> SchemaNode {
+        SchemaNode::Table(vec![
+            SchemaField::optional(
+                "knhk",
+                "Framework settings",
+                SchemaNode::Table(vec![
+                    SchemaField::optional("version", "Configuration version", string()),
+                    SchemaField::optional(
+                        "context",
+                        "Active context, also selects the profile layer",
+                        string(),
+                    ),
+                ]),
+            ),
+            SchemaField::optional(
+                "connectors",
+                "Connectors by name",
+                SchemaNode::Map(Box::new(SchemaNode::Table(vec![
+                    SchemaField::required(
+                        "type",
+                        "Connector type (kafka, salesforce, ...)",
+                        string(),
+                    ),
+                    SchemaField::optional(
+                        "bootstrap_servers",
+                        "Broker addresses",
+                        SchemaNode::Array(Box::new(string())),
+                    ),
+                    SchemaField::optional("topic", "Source topic", string()),
+                    SchemaField::optional("schema", "Schema IRI", string()),
+                    SchemaField::optional(
+                        "max_run_len",
+                        "Maximum run length",
+                        integer(1, Some(MAX_TICKS)),
+                    ),
+                    SchemaField::optional(
+                        "max_batch_size",
+                        "Maximum deltas per batch",
+                        integer(1, None),
+                    ),
+                ]))),
+            ),
+            SchemaField::optional(
+                "epochs",
+                "Epochs by name",
+                SchemaNode::Map(Box::new(SchemaNode::Table(vec![
+                    SchemaField::optional("tau", "Tick budget", integer(1, Some(MAX_TICKS))),
+                    SchemaField::optional("ordering", "Delta ordering", string()),
+                ]))),
+            ),
+            SchemaField::optional(
+                "hooks",
+                "Hook settings",
+                SchemaNode::Table(vec![SchemaField::optional(
+                    "max_count",
+                    "Maximum registered hooks",
+                    integer(0, None),
+                )]),
+            ),
+            SchemaField::optional(
+                "routes",
+                "Action routes by name",
+                SchemaNode::Map(Box::new(SchemaNode::Table(vec![
+                    SchemaField::required(
+                        "kind",
+                        "Route kind (webhook, kafka, grpc, ...)",
+                        string(),
+                    ),
+                    SchemaField::required("target", "Route target", string()),
+                    SchemaField::optional("encode", "Payload encoding", string()),
+                ]))),
+            ),
+        ])
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HooksSection {
-    #[serde(default = "default_max_count")]
-    pub max_count: u64,
-}
-
-fn default_max_count() -> u64 {
-    100
+    /// JSON Schema (draft 2020-12) of the configuration file
+    pub fn json_schema() -> JsonValue {
+        let mut schema = Self::schema().to_json_schema();
+        if let Some(object) = schema.as_object_mut() {
+            object.insert(
+                "$schema".to_string(),
+                json!("https://json-schema.org/draft/2020-12/schema"),
+            );
+            object.insert("title".to_string(), json!("KNHK configuration"));
+        }
+        schema
+    }
 }
 
-impl Default for HooksSection {
-    fn default() -> Self {
-        Self {
-            max_count: default_max_count(),
+impl SchemaNode {
+    /// JSON Schema of this node; tables reject unknown keys
+    pub fn to_json_schema(&self) -> JsonValue {
+        match self {
+            SchemaNode::String { one_of } if one_of.is_empty() => json!({"type": "string"}),
+            SchemaNode::String { one_of } => json!({"type": "string", "enum": one_of}),
+            SchemaNode::Integer { minimum, maximum } => {
+                let mut schema = json!({"type": "integer", "minimum": minimum});
+                if let (Some(maximum), Some(object)) = (maximum, schema.as_object_mut()) {
+                    object.insert("maximum".to_string(), json!(maximum));
+                }
+                schema
+            }
+            SchemaNode::Array(item) => json!({"type": "array", "items": item.to_json_schema()}),
+            SchemaNode::Table(fields) => {
+                let mut properties = Map::new();
+                for field in fields {
+                    let mut property = field.node.to_json_schema();
+                    if let Some(object) = property.as_object_mut() {
+                        object.insert("description".to_string(), json!(field.description));
+                    }
+                    properties.insert(field.name.to_string(), property);
+                }
+                let required: Vec<&str> = fields
+                    .iter()
+                    .filter(|f| f.required)
+                    .map(|f| f.name)
+                    .collect();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+            SchemaNode::Map(entry) => json!({
+                "type": "object",
+                "additionalProperties": entry.to_json_schema(),
+            }),
         }
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RouteConfig {
-    #[serde(default)]
-    pub kind: String,
-    #[serde(default)]
-    pub target: String,
-    #[serde(default = "default_encode")]
-    pub encode: String,
-}
+    /// Check `value` against this node
+    ///
+    /// Reports every problem rather than stopping at the first. Required keys
+    /// are only checked with `check_required`, since a single layer usually
+    /// sets a few keys of a table.
+    pub fn validate(
+        &self,
+        value: &Value,
+        check_required: bool,
+    ) -> Vec<(Vec<String>, DiagnosticKind)> {
+        let mut problems = Vec::new();
+        let mut path = Vec::new();
+        self.validate_at(value, check_required, &mut path, &mut problems);
+        problems
+    }
 
-fn default_encode() -> String {
-    "json-ld".to_string()
-}
+    fn validate_at(
+        &self,
+        value: &Value,
+        check_required: bool,
+        path: &mut Vec<String>,
+        problems: &mut Vec<(Vec<String>, DiagnosticKind)>,
+    ) {
+        match (self, value) {
+            (SchemaNode::String { one_of }, Value::String(s)) => {
+                if !one_of.is_empty() && !one_of.contains(&s.as_str()) {
+                    problems.push((
+                        path.clone(),
+                        DiagnosticKind::InvalidValue {
+                            value: s.clone(),
+                            allowed: one_of.iter().map(|v| v.to_string()).collect(),
+                        },
+                    ));
+                }
+            }
+            (SchemaNode::Integer { minimum, maximum }, Value::Integer(i)) => {
+                if *i < *minimum || maximum.is_some_and(|max| *i > max) {
+                    problems.push((
+                        path.clone(),
+                        DiagnosticKind::OutOfRange {
+                            value: *i,
+                            minimum: *minimum,
+                            maximum: *maximum,
+                        },
+                    ));
+                }
+            }
+            (SchemaNode::Array(item), Value::Array(values)) => {
+                for (index, value) in values.iter().enumerate() {
+                    path.push(index.to_string());
+                    item.validate_at(value, check_required, path, problems);
+                    path.pop();
+                }
+            }
+            (SchemaNode::Table(fields), Value::Table(table)) => {
+                for (key, value) in table {
+                    path.push(key.clone());
+                    match fields.iter().find(|f| key.as_str() == f.name) {
+                        Some(field) => {
+                            field
+                                .node
+                                .validate_at(value, check_required, path, problems)
+                        }
+                        None => problems.push((
+                            path.clone(),
+                            DiagnosticKind::UnknownField {
+                                expected: fields.iter().map(|f| f.name.to_string()).collect(),
+                            },
+                        )),
+                    }
+                    path.pop();
+                }
+                if check_required {
+                    for field in fields.iter().filter(|f| f.required) {
+                        if !table.contains_key(field.name) {
+                            path.push(field.name.to_string());
+                            problems.push((path.clone(), DiagnosticKind::MissingField));
+                            path.pop();
+                        }
+                    }
+                }
+            }
+            (SchemaNode::Map(entry), Value::Table(table)) => {
+                for (key, value) in table {
+                    path.push(key.clone());
+                    entry.validate_at(value, check_required, path, problems);
+                    path.pop();
+                }
+            }
+            (node, value) => problems.push((
+                path.clone(),
+                DiagnosticKind::InvalidType {
+                    expected: node.type_name(),
+                    found: value.type_str(),
+                },
+            )),
+        }
+    }
 
-impl Default for RouteConfig {
-    fn default() -> Self {
-        Self {
-            kind: String::new(),
-            target: String::new(),
-            encode: default_encode(),
+    /// Schema node at a key path, looking through map entries
+    pub fn node_at(&self, path: &[String]) -> Option<&SchemaNode> {
+        let Some((key, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        match self {
+            SchemaNode::Table(fields) => fields
+                .iter()
+                .find(|f| key.as_str() == f.name)
+                .and_then(|f| f.node.node_at(rest)),
+            SchemaNode::Map(entry) => entry.node_at(rest),
+            _ => None,
         }
     }
-}
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ConfigError {
-    FileNotFound,
-    ParseError(String),
-    ValidationError(String),
-    IoError(String),
-}
+    /// Typed value for a string override (env var or command line) at `path`
+    ///
+    /// Integers are parsed, arrays split on commas; anything that does not
+    /// convert stays a string so validation reports it.
+    pub(crate) fn override_value(&self, path: &[String], raw: &str) -> Value {
+        match self.node_at(path) {
+            Some(SchemaNode::Integer { .. }) => match raw.trim().parse::<i64>() {
+                Ok(i) => Value::Integer(i),
+                Err(_) => Value::String(raw.to_string()),
+            },
+            Some(SchemaNode::Array(_)) => Value::Array(
+                raw.split(',')
+                    .map(|s| Value::String(s.trim().to_string()))
+                    .collect(),
+            ),
+            _ => Value::String(raw.to_string()),
+        }
+    }
 
-impl core::fmt::Display for ConfigError {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    fn type_name(&self) -> &'static str {
         match self {
-            ConfigError::FileNotFound => write!(f, "Configuration file not found"),
-            ConfigError::ParseError(msg) => write!(f, "Parse error: {}", msg),
-            ConfigError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
-            ConfigError::IoError(msg) => write!(f, "IO error: {}", msg),
+            SchemaNode::String { .. } => "string",
+            SchemaNode::Integer { .. } => "integer",
+            SchemaNode::Array(_) => "array",
+            SchemaNode::Table(_) | SchemaNode::Map(_) => "table",
         }
     }
 }
 
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let value: Value = toml::from_str(
+            r#"
+[knhk]
+contxt = "prod"
+
+[connectors.kafka]
+max_run_len = 9
+
+[[routes]]
+kind = "webhook"
+"#,
+        )
+        .unwrap();
+
+        let problems = Config::schema().validate(&value, true);
+        let paths: Vec<String> = problems.iter().map(|(p, _)| p.join(".")).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "connectors.kafka.max_run_len",
+                "connectors.kafka.type",
+                "knhk.contxt",
+                "routes"
+            ]
+        );
+        assert!(matches!(
+            problems[3].1,
+            DiagnosticKind::InvalidType {
+                expected: "table",
+                found: "array"
+            }
+        ));
+    }
+
+    #[test]
+    fn test_json_schema_rejects_additional_properties() {
+        let schema = Config::json_schema();
+        assert_eq!(schema["additionalProperties"], json!(false));
+        let connector = &schema["properties"]["connectors"]["additionalProperties"];
+        assert_eq!(connector["required"], json!(["type"]));
+        assert_eq!(connector["properties"]["max_run_len"]["maximum"], json!(8));
+    }
+}
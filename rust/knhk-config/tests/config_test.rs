@@ -4,7 +4,7 @@
 #![cfg(test)]
 extern crate std;
 
-use knhk_config::load_config;
+use knhk_config::{load_config, ConfigLoader};
 use std::env;
 use std::fs;
 
@@ -100,7 +100,7 @@ fn test_config_validation() {
 version = "0.5.0"
 context = "test"
 
-[[connectors]]
+[connectors.kafka]
 type = "kafka"
 max_run_len = 8
 max_batch_size = 1000
@@ -186,7 +186,7 @@ fn test_config_connector_section() {
 version = "0.5.0"
 context = "test"
 
-[knhk.connectors.kafka-prod]
+[connectors.kafka-prod]
 type = "kafka"
 bootstrap_servers = ["localhost:9092"]
 topic = "triples"
@@ -211,3 +211,38 @@ max_run_len = 8
     fs::remove_file(&config_file).ok();
     fs::remove_dir(&config_dir).ok();
 }
+
+#[test]
+fn test_config_unknown_field_rejected() {
+    println!("[TEST] Configuration Unknown Field Rejected");
+
+    // Setup: Create config file with a typo in a key
+    let config_dir = std::env::temp_dir().join("knhk_test_config_unknown_field");
+    fs::create_dir_all(&config_dir).expect("Failed to create config directory");
+
+    let config_file = config_dir.join("config.toml");
+    let config_content = r#"
+[knhk]
+version = "0.5.0"
+contxt = "production"
+"#;
+    fs::write(&config_file, config_content).expect("Failed to write config file");
+
+    // Execute: Load through the layered loader
+    let error = ConfigLoader::new()
+        .project_file(&config_file)
+        .load()
+        .expect_err("Typo should not fall back to defaults");
+
+    // Verify: Diagnostic names the file, line and key
+    let diagnostics = error.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, Some(4));
+    assert_eq!(diagnostics[0].path, "knhk.contxt");
+    assert!(load_config(Some(config_file.clone())).is_err());
+    println!("  ✓ {}", diagnostics[0]);
+
+    // Cleanup
+    fs::remove_file(&config_file).ok();
+    fs::remove_dir(&config_dir).ok();
+}
@@ -59,6 +59,11 @@ impl MerkleTree {
         leaf_hash
     }
 
+    /// Add a precomputed leaf hash (e.g. the digest of an externally hashed record)
+    pub fn add_leaf(&mut self, leaf_hash: [u8; 32]) {
+        self.leaves.push(leaf_hash);
+    }
+
     /// Compute Merkle root by building tree bottom-up
     /// Algorithm:
     /// 1. Start with leaf hashes
@@ -291,6 +296,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_merkle_proof_precomputed_leaves() {
+        let mut tree = MerkleTree::new();
+
+        for i in 0..5u8 {
+            tree.add_leaf([i; 32]);
+        }
+
+        tree.compute_root();
+
+        for i in 0..5 {
+            let proof = tree.generate_proof(i).expect("proof generation failed");
+            assert_eq!(proof.leaf_hash, [i as u8; 32]);
+            assert!(proof.verify(), "Proof verification failed for leaf {}", i);
+        }
+    }
+
     #[test]
     fn test_merkle_tree_deterministic() {
         let receipts = vec![
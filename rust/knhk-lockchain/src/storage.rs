@@ -5,7 +5,7 @@
 use crate::quorum::QuorumProof;
 use git2::{Oid, Repository, Signature};
 use serde::{Deserialize, Serialize};
-use sled::Db;
+use sled::{Db, Tree};
 use std::path::Path;
 use std::sync::Mutex;
 use thiserror::Error;
@@ -39,6 +39,7 @@ pub struct LockchainEntry {
 /// Includes Git integration for immutable audit log (v1.0 requirement)
 pub struct LockchainStorage {
     db: Db,
+    roots: Tree, // Keyspace holding the roots (the default tree unless namespaced)
     git_repo: Option<Mutex<Repository>>, // Optional Git repository for audit log (wrapped in Mutex for Sync)
     #[allow(dead_code)]
     git_path: Option<String>, // Git repository path
@@ -52,6 +53,7 @@ impl LockchainStorage {
     pub fn new(path: &str) -> Result<Self, StorageError> {
         let db = sled::open(path)?;
         Ok(Self {
+            roots: (*db).clone(),
             db,
             git_repo: None,
             git_path: None,
@@ -76,12 +78,23 @@ impl LockchainStorage {
         };
 
         Ok(Self {
+            roots: (*db).clone(),
             db,
             git_repo: Some(Mutex::new(repo)),
             git_path: Some(git_path.to_string()),
         })
     }
 
+    /// Keep roots in their own keyspace of the database
+    ///
+    /// Producers sharing one database (e.g. beat cycles and receipt segments)
+    /// number their roots independently; a namespace keeps them from
+    /// overwriting each other's entries.
+    pub fn with_namespace(mut self, namespace: &str) -> Result<Self, StorageError> {
+        self.roots = self.db.open_tree(format!("ns:{}", namespace))?;
+        Ok(self)
+    }
+
     /// Append receipt to Git repository (80/20 implementation)
     /// Creates a commit with receipt data as file content
     pub fn append_to_git(
@@ -176,8 +189,8 @@ impl LockchainStorage {
         let key = format!("root:{:020}", cycle);
         let value = bincode::serialize(&entry)?;
 
-        self.db.insert(key.as_bytes(), value)?;
-        self.db.flush()?;
+        self.roots.insert(key.as_bytes(), value)?;
+        self.roots.flush()?;
 
         Ok(())
     }
@@ -193,7 +206,7 @@ impl LockchainStorage {
     pub fn get_root(&self, cycle: u64) -> Result<Option<LockchainEntry>, StorageError> {
         let key = format!("root:{:020}", cycle);
 
-        if let Some(bytes) = self.db.get(key.as_bytes())? {
+        if let Some(bytes) = self.roots.get(key.as_bytes())? {
             let entry: LockchainEntry = bincode::deserialize(&bytes)?;
             Ok(Some(entry))
         } else {
@@ -216,7 +229,7 @@ impl LockchainStorage {
 
         let mut entries = Vec::new();
 
-        for result in self.db.range(start_key.as_bytes()..=end_key.as_bytes()) {
+        for result in self.roots.range(start_key.as_bytes()..=end_key.as_bytes()) {
             let (_key, value) = result?;
             let entry: LockchainEntry = bincode::deserialize(&value)?;
             entries.push(entry);
@@ -228,7 +241,7 @@ impl LockchainStorage {
     /// Get latest committed root
     pub fn get_latest_root(&self) -> Result<Option<LockchainEntry>, StorageError> {
         // Iterate in reverse to find latest
-        if let Some(result) = self.db.iter().next_back() {
+        if let Some(result) = self.roots.iter().next_back() {
             let (_key, value) = result?;
             let entry: LockchainEntry = bincode::deserialize(&value)?;
             Ok(Some(entry))
@@ -239,7 +252,7 @@ impl LockchainStorage {
 
     /// Get total number of committed roots
     pub fn root_count(&self) -> usize {
-        self.db.len()
+        self.roots.len()
     }
 
     /// Verify audit trail continuity
@@ -268,7 +281,7 @@ impl LockchainStorage {
     /// Clear all data (for testing)
     #[cfg(test)]
     pub fn clear(&self) -> Result<(), StorageError> {
-        self.db.clear()?;
+        self.roots.clear()?;
         Ok(())
     }
 }
@@ -418,4 +431,30 @@ mod tests {
             .verify_continuity(100, 120)
             .expect("failed to verify continuity"));
     }
+
+    #[test]
+    fn test_storage_namespaces_are_disjoint() {
+        let storage =
+            LockchainStorage::new("/tmp/knhk-lockchain-test-6").expect("failed to create storage");
+        storage.clear().expect("failed to clear storage");
+        storage
+            .persist_root(1, [1u8; 32], create_test_proof(1, [1u8; 32]))
+            .expect("failed to persist root");
+
+        let receipts = storage
+            .with_namespace("receipts")
+            .expect("failed to open namespace");
+        receipts.clear().expect("failed to clear namespace");
+        assert!(receipts.get_root(1).expect("failed to get root").is_none());
+        receipts
+            .persist_root(1, [2u8; 32], create_test_proof(1, [2u8; 32]))
+            .expect("failed to persist root");
+
+        let entry = receipts
+            .get_root(1)
+            .expect("failed to get root")
+            .expect("root not found");
+        assert_eq!(entry.root, [2u8; 32]);
+        assert_eq!(receipts.root_count(), 1);
+    }
 }
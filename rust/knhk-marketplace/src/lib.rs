@@ -3,6 +3,7 @@
 //! This module provides comprehensive infrastructure for:
 //! - Commercial licensing (Community, Professional, Enterprise tiers)
 //! - Workflow marketplace and templates
+//! - Template packages with semver resolution, lockfiles and signatures
 //! - Usage-based billing and invoicing
 //! - Telemetry and analytics collection
 //! - Cloud deployment integration (AWS, GCP, Azure)
@@ -11,6 +12,7 @@ pub mod billing;
 pub mod deployment;
 pub mod licensing;
 pub mod marketplace;
+pub mod packages;
 pub mod telemetry;
 
 /// Phase 10 version identifier
@@ -45,6 +47,18 @@ pub enum MarketplaceError {
     #[error("Deployment configuration error: {0}")]
     Deployment(String),
 
+    #[error("Template package error: {0}")]
+    Package(String),
+
+    #[error("Dependency resolution failed: {0}")]
+    Resolution(String),
+
+    #[error("Package signature verification failed: {0}")]
+    Signature(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("HTTP request failed: {0}")]
     HttpError(#[from] reqwest::Error),
 
@@ -1,12 +1,21 @@
 //! Workflow template marketplace
 
 use chrono::{DateTime, Utc};
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::packages::TemplatePackage;
 use crate::{MarketplaceError, Result};
 
+/// Dependency of a template on another template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateDependency {
+    pub name: String,
+    pub requirement: VersionReq,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowTemplate {
     pub id: Uuid,
@@ -17,7 +26,7 @@ pub struct WorkflowTemplate {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub content: String,
-    pub dependencies: Vec<String>,
+    pub dependencies: Vec<TemplateDependency>,
     pub rating: f32,
     pub rating_count: u32,
     pub download_count: u32,
@@ -31,6 +40,7 @@ impl WorkflowTemplate {
         author: String,
         content: String,
     ) -> Result<Self> {
+        parse_version(&version)?;
         Ok(Self {
             id: Uuid::new_v4(),
             name,
@@ -47,8 +57,45 @@ impl WorkflowTemplate {
         })
     }
 
-    pub fn add_dependency(&mut self, dep: String) {
-        self.dependencies.push(dep);
+    /// Template from a package, its Turtle specs concatenated as content
+    pub fn from_package(package: &TemplatePackage) -> Result<Self> {
+        let info = &package.manifest.package;
+        let content = package
+            .specs()
+            .map(|(_, spec)| spec)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut template = Self::new(
+            info.name.clone(),
+            info.version.to_string(),
+            info.description.clone(),
+            info.authors.join(", "),
+            content,
+        )?;
+        for (name, requirement) in &package.manifest.dependencies {
+            template.dependencies.push(TemplateDependency {
+                name: name.clone(),
+                requirement: requirement.clone(),
+            });
+        }
+        Ok(template)
+    }
+
+    /// Add a dependency with a semver requirement such as `^1.2`
+    pub fn add_dependency(&mut self, name: String, requirement: &str) -> Result<()> {
+        let requirement = VersionReq::parse(requirement).map_err(|e| {
+            MarketplaceError::Marketplace(format!(
+                "Invalid version requirement '{}' for {}: {}",
+                requirement, name, e
+            ))
+        })?;
+        self.dependencies
+            .push(TemplateDependency { name, requirement });
+        Ok(())
+    }
+
+    pub fn semver(&self) -> Result<Version> {
+        parse_version(&self.version)
     }
 
     pub fn add_rating(&mut self, stars: f32) -> Result<()> {
@@ -92,9 +139,21 @@ impl TemplateRegistry {
         Ok(id)
     }
 
-    pub fn find(&self, name: &str, _version: Option<&str>) -> Option<&WorkflowTemplate> {
+    /// Highest version of a template matching a semver requirement
+    ///
+    /// `None` matches any version; an invalid requirement matches nothing.
+    pub fn find(&self, name: &str, version: Option<&str>) -> Option<&WorkflowTemplate> {
+        let requirement = match version {
+            Some(version) => VersionReq::parse(version).ok()?,
+            None => VersionReq::STAR,
+        };
         let ids = self.by_name.get(name)?;
-        ids.iter().find_map(|id| self.templates.get(id))
+        ids.iter()
+            .filter_map(|id| self.templates.get(id))
+            .filter_map(|template| Some((template.semver().ok()?, template)))
+            .filter(|(version, _)| requirement.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, template)| template)
     }
 
     pub fn list_all(&self) -> Vec<&WorkflowTemplate> {
@@ -130,7 +189,11 @@ impl TemplatePublisher {
             .versions
             .entry(template.name.clone())
             .or_insert_with(Vec::new);
-        if versions.contains(&template.version) {
+        let version = template.semver()?;
+        if versions
+            .iter()
+            .any(|published| parse_version(published).is_ok_and(|v| v == version))
+        {
             return Err(MarketplaceError::Marketplace(
                 "Version already exists".to_string(),
             ));
@@ -158,6 +221,12 @@ impl Default for TemplatePublisher {
     }
 }
 
+fn parse_version(version: &str) -> Result<Version> {
+    Version::parse(version).map_err(|e| {
+        MarketplaceError::Marketplace(format!("Invalid template version '{}': {}", version, e))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,4 +274,58 @@ mod tests {
 
         assert!(publisher.publish(template).is_ok());
     }
+
+    #[test]
+    fn test_find_resolves_version_requirement() {
+        let mut registry = TemplateRegistry::new();
+        for version in ["1.2.0", "1.10.0", "2.0.0"] {
+            let template = WorkflowTemplate::new(
+                "approval".to_string(),
+                version.to_string(),
+                "Approval".to_string(),
+                "Author".to_string(),
+                "content".to_string(),
+            )
+            .unwrap();
+            registry.register(template).unwrap();
+        }
+
+        let found = |version| {
+            registry
+                .find("approval", version)
+                .map(|t| t.version.as_str())
+        };
+        assert_eq!(found(None), Some("2.0.0"));
+        assert_eq!(found(Some("^1.2")), Some("1.10.0"));
+        assert_eq!(found(Some("=1.2.0")), Some("1.2.0"));
+        assert_eq!(found(Some("^3")), None);
+    }
+
+    #[test]
+    fn test_dependency_requirement_validated() {
+        let mut template = WorkflowTemplate::new(
+            "approval".to_string(),
+            "1.0.0".to_string(),
+            "Approval".to_string(),
+            "Author".to_string(),
+            "content".to_string(),
+        )
+        .unwrap();
+
+        assert!(template
+            .add_dependency("escalation".to_string(), "^1.0")
+            .is_ok());
+        assert!(template
+            .add_dependency("notify".to_string(), "latest")
+            .is_err());
+        assert_eq!(template.dependencies.len(), 1);
+        assert!(WorkflowTemplate::new(
+            "bad".to_string(),
+            "v1".to_string(),
+            String::new(),
+            String::new(),
+            String::new()
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,247 @@
+//! Installing template packages into a project
+
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::lockfile::{Lockfile, LOCKFILE_NAME};
+use super::manifest::validate_name;
+use super::registry::{write_atomic, LocalRegistry};
+use super::resolve::resolve;
+use crate::{MarketplaceError, Result};
+
+/// Directory under the project where packages are unpacked
+pub const INSTALL_DIR: &str = ".knhk/templates";
+
+/// File in the project directory listing its template dependencies
+pub const PROJECT_FILE: &str = "knhk-templates.toml";
+
+/// A project's direct template dependencies
+///
+/// ```toml
+/// [dependencies]
+/// approval-chain = "^1.2"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectTemplates {
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, VersionReq>,
+}
+
+impl ProjectTemplates {
+    /// Load `knhk-templates.toml` from `project_dir`, empty if it does not exist
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let path = project_dir.join(PROJECT_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let project: Self = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| MarketplaceError::Package(format!("Invalid {}: {}", PROJECT_FILE, e)))?;
+        for name in project.dependencies.keys() {
+            validate_name(name)?;
+        }
+        Ok(project)
+    }
+
+    pub fn save(&self, project_dir: &Path) -> Result<()> {
+        fs::create_dir_all(project_dir)?;
+        let content = toml::to_string_pretty(self).map_err(|e| {
+            MarketplaceError::Package(format!("Failed to write {}: {}", PROJECT_FILE, e))
+        })?;
+        write_atomic(&project_dir.join(PROJECT_FILE), content.as_bytes())
+    }
+
+    /// Add or replace a dependency
+    pub fn add(&mut self, name: &str, requirement: VersionReq) -> Result<()> {
+        validate_name(name)?;
+        self.dependencies.insert(name.to_string(), requirement);
+        Ok(())
+    }
+}
+
+/// Resolves a project's template dependencies and unpacks them
+///
+/// Packages go to `.knhk/templates/<name>/<version>/` and the resolution is
+/// written to `knhk-templates.lock` in the project directory.
+pub struct TemplateInstaller<'a> {
+    registry: &'a LocalRegistry,
+    project_dir: PathBuf,
+}
+
+impl<'a> TemplateInstaller<'a> {
+    pub fn new(registry: &'a LocalRegistry, project_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            registry,
+            project_dir: project_dir.into(),
+        }
+    }
+
+    pub fn lockfile_path(&self) -> PathBuf {
+        self.project_dir.join(LOCKFILE_NAME)
+    }
+
+    pub fn install_path(&self, name: &str, version: &semver::Version) -> PathBuf {
+        self.project_dir
+            .join(INSTALL_DIR)
+            .join(name)
+            .join(version.to_string())
+    }
+
+    /// Install keeping the versions pinned by the lockfile where possible
+    pub fn install(&self, dependencies: &BTreeMap<String, VersionReq>) -> Result<Lockfile> {
+        let locked = Lockfile::load(&self.lockfile_path())?;
+        self.install_resolved(dependencies, locked)
+    }
+
+    /// Install the newest matching versions, ignoring the lockfile pins
+    pub fn update(&self, dependencies: &BTreeMap<String, VersionReq>) -> Result<Lockfile> {
+        self.install_resolved(dependencies, None)
+    }
+
+    fn install_resolved(
+        &self,
+        dependencies: &BTreeMap<String, VersionReq>,
+        locked: Option<Lockfile>,
+    ) -> Result<Lockfile> {
+        let lockfile = resolve(self.registry, dependencies, locked.as_ref())?;
+
+        for package in &lockfile.packages {
+            // A pinned version must still have the content it was locked with
+            if let Some(pinned) = locked.as_ref().and_then(|l| l.get(&package.name)) {
+                if pinned.version == package.version && pinned.checksum != package.checksum {
+                    return Err(MarketplaceError::Package(format!(
+                        "Checksum of {}@{} changed since it was locked: {} -> {}",
+                        package.name, package.version, pinned.checksum, package.checksum
+                    )));
+                }
+            }
+
+            let fetched = self.registry.fetch(&package.name, &package.version)?;
+            if fetched.checksum != package.checksum {
+                return Err(MarketplaceError::Package(format!(
+                    "{} does not match its locked checksum",
+                    fetched.id()
+                )));
+            }
+            let target = self.install_path(&package.name, &package.version);
+            if target.exists() {
+                fs::remove_dir_all(&target)?;
+            }
+            fetched.unpack(&target)?;
+        }
+
+        lockfile.save(&self.lockfile_path())?;
+        Ok(lockfile)
+    }
+
+    pub fn project_dir(&self) -> &Path {
+        &self.project_dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packages::{PackageManifest, TemplatePackage};
+    use semver::Version;
+    use tempfile::TempDir;
+
+    fn publish(registry: &LocalRegistry, name: &str, version: &str, dependencies: &[(&str, &str)]) {
+        let mut manifest = PackageManifest::new(name, Version::parse(version).unwrap()).unwrap();
+        for (dependency, requirement) in dependencies {
+            manifest.dependencies.insert(
+                dependency.to_string(),
+                VersionReq::parse(requirement).unwrap(),
+            );
+        }
+        let mut files = BTreeMap::new();
+        files.insert(
+            format!("specs/{}.ttl", name),
+            format!("# {} {}", name, version).into_bytes(),
+        );
+        files.insert("assets/README.md".to_string(), b"docs".to_vec());
+        registry
+            .publish(&TemplatePackage::new(manifest, files).unwrap())
+            .unwrap();
+    }
+
+    fn requirements(dependencies: &[(&str, &str)]) -> BTreeMap<String, VersionReq> {
+        dependencies
+            .iter()
+            .map(|(name, requirement)| (name.to_string(), VersionReq::parse(requirement).unwrap()))
+            .collect()
+    }
+
+    fn versions(lockfile: &Lockfile) -> Vec<String> {
+        lockfile
+            .packages
+            .iter()
+            .map(|p| format!("{}@{}", p.name, p.version))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolution_backtracks_on_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LocalRegistry::open(temp_dir.path().join("registry"))
+            .unwrap()
+            .allow_unsigned();
+        publish(&registry, "notify", "1.0.0", &[]);
+        publish(&registry, "notify", "2.0.0", &[]);
+        publish(&registry, "escalation", "1.0.0", &[("notify", "^1")]);
+        publish(&registry, "escalation", "1.1.0", &[("notify", "^2")]);
+
+        // escalation 1.1.0 needs notify 2, which the project rules out
+        let dependencies = requirements(&[("escalation", "^1"), ("notify", "^1")]);
+        let lockfile = resolve(&registry, &dependencies, None).unwrap();
+        assert_eq!(
+            versions(&lockfile),
+            vec!["escalation@1.0.0", "notify@1.0.0"]
+        );
+        assert_eq!(lockfile.packages[0].dependencies, vec!["notify 1.0.0"]);
+
+        let impossible = requirements(&[("notify", ">=3")]);
+        assert!(matches!(
+            resolve(&registry, &impossible, None),
+            Err(MarketplaceError::Resolution(_))
+        ));
+    }
+
+    #[test]
+    fn test_install_pins_versions_and_honours_yanks() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LocalRegistry::open(temp_dir.path().join("registry"))
+            .unwrap()
+            .allow_unsigned();
+        let project = temp_dir.path().join("project");
+        publish(&registry, "approval", "1.0.0", &[]);
+        let installer = TemplateInstaller::new(&registry, &project);
+        let dependencies = requirements(&[("approval", "^1")]);
+
+        installer.install(&dependencies).unwrap();
+        let installed = installer.install_path("approval", &Version::new(1, 0, 0));
+        assert!(installed.join("specs/approval.ttl").exists());
+        assert!(installed.join("assets/README.md").exists());
+
+        // A newer release does not move the pin until update
+        publish(&registry, "approval", "1.1.0", &[]);
+        let lockfile = installer.install(&dependencies).unwrap();
+        assert_eq!(versions(&lockfile), vec!["approval@1.0.0"]);
+        assert_eq!(
+            Lockfile::load(&installer.lockfile_path()).unwrap(),
+            Some(lockfile)
+        );
+
+        // Yanked versions stay installable from the lockfile only
+        registry
+            .set_yanked("approval", &Version::new(1, 0, 0), true)
+            .unwrap();
+        let lockfile = installer.install(&dependencies).unwrap();
+        assert_eq!(versions(&lockfile), vec!["approval@1.0.0"]);
+        let lockfile = installer.update(&dependencies).unwrap();
+        assert_eq!(versions(&lockfile), vec!["approval@1.1.0"]);
+    }
+}
@@ -0,0 +1,69 @@
+//! Template lockfile (`knhk-templates.lock`)
+//!
+//! Pins every resolved package to an exact version and checksum so installs
+//! are reproducible. The format follows `Cargo.lock`: one `[[package]]` table
+//! per package, dependencies written as `"name version"`.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::registry::write_atomic;
+use crate::{MarketplaceError, Result};
+
+/// Lockfile name in the project directory
+pub const LOCKFILE_NAME: &str = "knhk-templates.lock";
+
+const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Version,
+    pub checksum: String,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    pub fn new(mut packages: Vec<LockedPackage>) -> Self {
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            version: LOCKFILE_VERSION,
+            packages,
+        }
+    }
+
+    /// Load a lockfile, `None` if it does not exist
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let lockfile: Self = toml::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| MarketplaceError::Package(format!("Invalid {}: {}", LOCKFILE_NAME, e)))?;
+        if lockfile.version > LOCKFILE_VERSION {
+            return Err(MarketplaceError::Package(format!(
+                "{} version {} is newer than supported version {}",
+                LOCKFILE_NAME, lockfile.version, LOCKFILE_VERSION
+            )));
+        }
+        Ok(Some(lockfile))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| MarketplaceError::Package(format!("Failed to write lockfile: {}", e)))?;
+        write_atomic(path, content.as_bytes())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|package| package.name == name)
+    }
+}
@@ -0,0 +1,112 @@
+//! Template package manifest (`knhk-template.toml`)
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::{MarketplaceError, Result};
+
+/// Manifest file name at the root of a package directory
+pub const MANIFEST_FILE: &str = "knhk-template.toml";
+
+/// Package manifest
+///
+/// ```toml
+/// [package]
+/// name = "approval-chain"
+/// version = "1.2.0"
+/// description = "Multi-level approval with escalation"
+///
+/// [dependencies]
+/// escalation = "^1.0"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManifest {
+    pub package: PackageInfo,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, VersionReq>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageInfo {
+    pub name: String,
+    pub version: Version,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+}
+
+impl PackageManifest {
+    pub fn new(name: &str, version: Version) -> Result<Self> {
+        validate_name(name)?;
+        Ok(Self {
+            package: PackageInfo {
+                name: name.to_string(),
+                version,
+                description: String::new(),
+                authors: Vec::new(),
+                license: None,
+            },
+            dependencies: BTreeMap::new(),
+        })
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let manifest: Self = toml::from_str(content)
+            .map_err(|e| MarketplaceError::Package(format!("Invalid {}: {}", MANIFEST_FILE, e)))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn load(dir: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(dir.join(MANIFEST_FILE))?;
+        Self::parse(&content)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self)
+            .map_err(|e| MarketplaceError::Package(format!("Failed to write manifest: {}", e)))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_name(&self.package.name)?;
+        for name in self.dependencies.keys() {
+            validate_name(name)?;
+            if *name == self.package.name {
+                return Err(MarketplaceError::Package(format!(
+                    "Package {} depends on itself",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// `name@version`
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.package.name, self.package.version)
+    }
+}
+
+/// Package names are lowercase ASCII, digits, `-` and `_`, starting with a letter
+pub(crate) fn validate_name(name: &str) -> Result<()> {
+    let valid = name.len() <= 64
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(MarketplaceError::Package(format!(
+            "Invalid package name '{}'",
+            name
+        )))
+    }
+}
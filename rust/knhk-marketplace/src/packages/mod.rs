@@ -0,0 +1,23 @@
+//! Workflow template packages
+//!
+//! A template package is a directory with a `knhk-template.toml` manifest,
+//! Turtle workflow specs under `specs/` and optional files under `assets/`,
+//! packed into a single content-hashed archive that can be signed with
+//! ed25519. Packages are published to a local filesystem registry, resolved
+//! against semver requirements and pinned in a lockfile on install.
+
+pub mod install;
+pub mod lockfile;
+pub mod manifest;
+pub mod package;
+pub mod registry;
+pub mod resolve;
+pub mod signing;
+
+pub use install::{ProjectTemplates, TemplateInstaller, INSTALL_DIR, PROJECT_FILE};
+pub use lockfile::{LockedPackage, Lockfile, LOCKFILE_NAME};
+pub use manifest::{PackageInfo, PackageManifest, MANIFEST_FILE};
+pub use package::{TemplatePackage, PACKAGE_EXTENSION};
+pub use registry::{IndexEntry, LocalRegistry};
+pub use resolve::resolve;
+pub use signing::{load_signing_key, PackageSignature, TrustedKeys};
@@ -0,0 +1,297 @@
+//! Packed template packages
+//!
+//! The archive is JSON: the manifest, every file hex-encoded by its relative
+//! path, a SHA-256 checksum over both, and an optional signature of that
+//! checksum. The checksum identifies the package content; the index and the
+//! lockfile record it so any change to a published package is detected.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path};
+
+use super::manifest::{PackageManifest, MANIFEST_FILE};
+use super::signing::PackageSignature;
+use crate::{MarketplaceError, Result};
+
+/// File extension of packed packages
+pub const PACKAGE_EXTENSION: &str = "knhkpkg";
+
+const PACKAGE_FORMAT: &str = "knhk-template-package";
+const PACKAGE_FORMAT_VERSION: u32 = 1;
+
+/// Directory holding the Turtle workflow specs
+const SPECS_DIR: &str = "specs";
+/// Directory holding other files shipped with the templates
+const ASSETS_DIR: &str = "assets";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplatePackage {
+    format: String,
+    format_version: u32,
+    pub manifest: PackageManifest,
+    /// File contents by relative path (`specs/...` or `assets/...`)
+    #[serde(with = "hex_files")]
+    pub files: BTreeMap<String, Vec<u8>>,
+    /// `sha256:<hex>` over the manifest and files
+    pub checksum: String,
+    #[serde(default)]
+    pub signature: Option<PackageSignature>,
+}
+
+impl TemplatePackage {
+    /// Build a package from its manifest and files
+    ///
+    /// There must be at least one `specs/*.ttl` file, and every spec must be
+    /// UTF-8. Other files must live under `assets/`.
+    pub fn new(manifest: PackageManifest, files: BTreeMap<String, Vec<u8>>) -> Result<Self> {
+        validate_contents(&manifest, &files)?;
+        let checksum = compute_checksum(&manifest, &files)?;
+        Ok(Self {
+            format: PACKAGE_FORMAT.to_string(),
+            format_version: PACKAGE_FORMAT_VERSION,
+            manifest,
+            files,
+            checksum,
+            signature: None,
+        })
+    }
+
+    /// Pack a package directory (manifest, `specs/`, `assets/`)
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let manifest = PackageManifest::load(dir)?;
+        let mut files = BTreeMap::new();
+        for sub_dir in [SPECS_DIR, ASSETS_DIR] {
+            collect_files(dir, &dir.join(sub_dir), &mut files)?;
+        }
+        Self::new(manifest, files)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let package: Self = serde_json::from_slice(bytes)?;
+        if package.format != PACKAGE_FORMAT || package.format_version > PACKAGE_FORMAT_VERSION {
+            return Err(MarketplaceError::Package(format!(
+                "Unsupported package format {} v{}",
+                package.format, package.format_version
+            )));
+        }
+        package.verify_checksum()?;
+        Ok(package)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Validate the manifest and file paths, then recompute the checksum and
+    /// compare it with the recorded one
+    ///
+    /// A matching checksum says nothing about where files will be written, so
+    /// archives read from disk get the same checks as packages built by `new`.
+    pub fn verify_checksum(&self) -> Result<()> {
+        validate_contents(&self.manifest, &self.files)?;
+        let actual = compute_checksum(&self.manifest, &self.files)?;
+        if actual != self.checksum {
+            return Err(MarketplaceError::Package(format!(
+                "Checksum mismatch for {}: recorded {}, content is {}",
+                self.id(),
+                self.checksum,
+                actual
+            )));
+        }
+        Ok(())
+    }
+
+    /// `name@version`
+    pub fn id(&self) -> String {
+        self.manifest.id()
+    }
+
+    /// Turtle specs by path
+    pub fn specs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.files.iter().filter_map(|(path, content)| {
+            let spec = std::str::from_utf8(content).ok()?;
+            path.starts_with("specs/").then_some((path.as_str(), spec))
+        })
+    }
+
+    /// Write the manifest and files into `dir`
+    ///
+    /// Every path is checked before anything is written.
+    pub fn unpack(&self, dir: &Path) -> Result<()> {
+        validate_contents(&self.manifest, &self.files)?;
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(MANIFEST_FILE), self.manifest.to_toml()?)?;
+        for (path, content) in &self.files {
+            let target = dir.join(path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target, content)?;
+        }
+        Ok(())
+    }
+}
+
+/// There must be at least one `specs/*.ttl` file, and every spec must be
+/// UTF-8. Other files must live under `assets/`.
+fn validate_contents(manifest: &PackageManifest, files: &BTreeMap<String, Vec<u8>>) -> Result<()> {
+    manifest.validate()?;
+    for (path, content) in files {
+        validate_path(path)?;
+        if path.starts_with("specs/") {
+            if !path.ends_with(".ttl") {
+                return Err(MarketplaceError::Package(format!(
+                    "Spec {} is not a Turtle (.ttl) file",
+                    path
+                )));
+            }
+            if std::str::from_utf8(content).is_err() {
+                return Err(MarketplaceError::Package(format!(
+                    "Spec {} is not valid UTF-8",
+                    path
+                )));
+            }
+        } else if !path.starts_with("assets/") {
+            return Err(MarketplaceError::Package(format!(
+                "File {} must be under {}/ or {}/",
+                path, SPECS_DIR, ASSETS_DIR
+            )));
+        }
+    }
+    if !files.keys().any(|path| path.starts_with("specs/")) {
+        return Err(MarketplaceError::Package(format!(
+            "Package {} has no workflow specs",
+            manifest.id()
+        )));
+    }
+    Ok(())
+}
+
+/// `sha256:<hex>` over the manifest (as JSON) and every file in path order
+fn compute_checksum(
+    manifest: &PackageManifest,
+    files: &BTreeMap<String, Vec<u8>>,
+) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(manifest)?);
+    for (path, content) in files {
+        hasher.update((path.len() as u64).to_le_bytes());
+        hasher.update(path.as_bytes());
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(content);
+    }
+    Ok(format!("sha256:{}", hex::encode(hasher.finalize())))
+}
+
+/// Relative, `/`-separated, without `.`/`..` components
+fn validate_path(path: &str) -> Result<()> {
+    let normal = !path.is_empty()
+        && !path.contains('\\')
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+    if normal {
+        Ok(())
+    } else {
+        Err(MarketplaceError::Package(format!(
+            "Invalid file path '{}' in package",
+            path
+        )))
+    }
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut BTreeMap<String, Vec<u8>>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            let relative = path
+                .strip_prefix(root)
+                .map_err(|e| MarketplaceError::Package(e.to_string()))?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(relative, fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+mod hex_files {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        files: &BTreeMap<String, Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        files
+            .iter()
+            .map(|(path, content)| (path, hex::encode(content)))
+            .collect::<BTreeMap<_, _>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, Vec<u8>>, D::Error> {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(path, content)| {
+                hex::decode(&content)
+                    .map(|content| (path, content))
+                    .map_err(serde::de::Error::custom)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::Version;
+    use tempfile::TempDir;
+
+    fn package() -> TemplatePackage {
+        let manifest = PackageManifest::new("approval", Version::new(1, 0, 0)).unwrap();
+        let mut files = BTreeMap::new();
+        files.insert("specs/main.ttl".to_string(), b"# approval".to_vec());
+        TemplatePackage::new(manifest, files).unwrap()
+    }
+
+    #[test]
+    fn test_crafted_archive_with_escaping_path_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut crafted = package();
+        crafted
+            .files
+            .insert("../../escaped".to_string(), b"owned".to_vec());
+        crafted.checksum = compute_checksum(&crafted.manifest, &crafted.files).unwrap();
+
+        let bytes = serde_json::to_vec(&crafted).unwrap();
+        assert!(matches!(
+            TemplatePackage::from_bytes(&bytes),
+            Err(MarketplaceError::Package(_))
+        ));
+        assert!(crafted.verify_checksum().is_err());
+
+        let target = temp_dir.path().join("a").join("b");
+        assert!(crafted.unpack(&target).is_err());
+        assert!(!temp_dir.path().join("escaped").exists());
+        assert!(!target.exists(), "nothing is written before validation");
+    }
+
+    #[test]
+    fn test_round_trip_through_bytes() {
+        let package = package();
+        let bytes = package.to_bytes().unwrap();
+        assert_eq!(TemplatePackage::from_bytes(&bytes).unwrap(), package);
+    }
+}
@@ -0,0 +1,302 @@
+//! Local filesystem registry
+//!
+//! Layout under the registry root:
+//!
+//! ```text
+//! index/<name>.json                          published versions of a package
+//! packages/<name>/<name>-<version>.knhkpkg   package archives
+//! ```
+//!
+//! Published versions are immutable. Yanking hides a version from new
+//! resolutions but keeps it installable for lockfiles that already pin it.
+
+use chrono::{DateTime, Utc};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::manifest::validate_name;
+use super::package::{TemplatePackage, PACKAGE_EXTENSION};
+use super::signing::TrustedKeys;
+use crate::{MarketplaceError, Result};
+
+/// One published version in the registry index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub version: Version,
+    pub checksum: String,
+    pub dependencies: BTreeMap<String, VersionReq>,
+    pub yanked: bool,
+    pub published_at: DateTime<Utc>,
+    /// Trusted publisher that signed the package, if signatures are required
+    pub publisher: Option<String>,
+}
+
+pub struct LocalRegistry {
+    root: PathBuf,
+    trusted: TrustedKeys,
+    allow_unsigned: bool,
+}
+
+impl LocalRegistry {
+    /// Open (creating if needed) a registry at `root`
+    ///
+    /// Packages must be signed by a trusted key to be published or fetched;
+    /// with no keys configured every package is rejected until
+    /// `with_trusted_keys` or `allow_unsigned` is used.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(root.join("index"))?;
+        fs::create_dir_all(root.join("packages"))?;
+        Ok(Self {
+            root,
+            trusted: TrustedKeys::new(),
+            allow_unsigned: false,
+        })
+    }
+
+    /// Accept packages signed by one of `trusted` on publish and fetch
+    pub fn with_trusted_keys(mut self, trusted: TrustedKeys) -> Self {
+        self.trusted = trusted;
+        self
+    }
+
+    /// Insecure: accept unsigned packages, and any signature when no keys
+    /// are trusted
+    ///
+    /// Only meant for local development registries.
+    pub fn allow_unsigned(mut self) -> Self {
+        self.allow_unsigned = true;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Publish a package version
+    ///
+    /// Fails if the version was already published (even if yanked), or if a
+    /// dependency has no unyanked version in this registry matching its
+    /// requirement.
+    pub fn publish(&self, package: &TemplatePackage) -> Result<IndexEntry> {
+        package.verify_checksum()?;
+        let publisher = self.verify_publisher(package)?;
+
+        let info = &package.manifest.package;
+        let mut entries = self.versions(&info.name)?;
+        if entries.iter().any(|entry| entry.version == info.version) {
+            return Err(MarketplaceError::Marketplace(format!(
+                "{} is already published",
+                package.id()
+            )));
+        }
+        for (name, requirement) in &package.manifest.dependencies {
+            let available = self
+                .versions(name)?
+                .iter()
+                .any(|entry| !entry.yanked && requirement.matches(&entry.version));
+            if !available {
+                return Err(MarketplaceError::Resolution(format!(
+                    "{} depends on {} {}, which is not published",
+                    package.id(),
+                    name,
+                    requirement
+                )));
+            }
+        }
+
+        let path = self.package_path(&info.name, &info.version);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&path, &package.to_bytes()?)?;
+
+        let entry = IndexEntry {
+            name: info.name.clone(),
+            version: info.version.clone(),
+            checksum: package.checksum.clone(),
+            dependencies: package.manifest.dependencies.clone(),
+            yanked: false,
+            published_at: Utc::now(),
+            publisher,
+        };
+        entries.push(entry.clone());
+        self.write_index(&info.name, &mut entries)?;
+        tracing::info!("Published template package {}", package.id());
+        Ok(entry)
+    }
+
+    /// Yank (or with `yanked = false`, restore) a published version
+    pub fn set_yanked(&self, name: &str, version: &Version, yanked: bool) -> Result<()> {
+        let mut entries = self.versions(name)?;
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.version == *version)
+            .ok_or_else(|| {
+                MarketplaceError::Marketplace(format!("{}@{} is not published", name, version))
+            })?;
+        entry.yanked = yanked;
+        self.write_index(name, &mut entries)
+    }
+
+    /// Published versions of a package, lowest first (empty if unknown)
+    pub fn versions(&self, name: &str) -> Result<Vec<IndexEntry>> {
+        validate_name(name)?;
+        let path = self.index_path(name);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Index entry of one version
+    pub fn entry(&self, name: &str, version: &Version) -> Result<IndexEntry> {
+        self.versions(name)?
+            .into_iter()
+            .find(|entry| entry.version == *version)
+            .ok_or_else(|| {
+                MarketplaceError::Marketplace(format!("{}@{} is not published", name, version))
+            })
+    }
+
+    /// Read a package, checking it against the index and the trusted keys
+    pub fn fetch(&self, name: &str, version: &Version) -> Result<TemplatePackage> {
+        let entry = self.entry(name, version)?;
+        let package = TemplatePackage::from_bytes(&fs::read(self.package_path(name, version))?)?;
+        if package.checksum != entry.checksum {
+            return Err(MarketplaceError::Package(format!(
+                "{} does not match its index checksum",
+                package.id()
+            )));
+        }
+        self.verify_publisher(&package)?;
+        Ok(package)
+    }
+
+    fn verify_publisher(&self, package: &TemplatePackage) -> Result<Option<String>> {
+        if self.allow_unsigned && (package.signature.is_none() || self.trusted.is_empty()) {
+            tracing::warn!(
+                "Accepting {} without a trusted signature (unsigned packages allowed)",
+                package.id()
+            );
+            return Ok(None);
+        }
+        self.trusted.verify(package).map(Some)
+    }
+
+    fn index_path(&self, name: &str) -> PathBuf {
+        self.root.join("index").join(format!("{}.json", name))
+    }
+
+    fn package_path(&self, name: &str, version: &Version) -> PathBuf {
+        self.root
+            .join("packages")
+            .join(name)
+            .join(format!("{}-{}.{}", name, version, PACKAGE_EXTENSION))
+    }
+
+    fn write_index(&self, name: &str, entries: &mut [IndexEntry]) -> Result<()> {
+        entries.sort_by(|a, b| a.version.cmp(&b.version));
+        write_atomic(&self.index_path(name), &serde_json::to_vec_pretty(entries)?)
+    }
+}
+
+/// Write through a temporary file so readers never see a partial file
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let partial = path.with_extension("partial");
+    fs::write(&partial, content)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packages::PackageManifest;
+    use ed25519_dalek::SigningKey;
+    use tempfile::TempDir;
+
+    fn package(name: &str, version: &str) -> TemplatePackage {
+        let manifest = PackageManifest::new(name, Version::parse(version).unwrap()).unwrap();
+        let mut files = BTreeMap::new();
+        files.insert(
+            "specs/main.ttl".to_string(),
+            b"@prefix yawl: <http://www.yawlfoundation.org/yawlschema#> .".to_vec(),
+        );
+        TemplatePackage::new(manifest, files).unwrap()
+    }
+
+    #[test]
+    fn test_publish_requires_trusted_signature() {
+        let temp_dir = TempDir::new().unwrap();
+        let publisher_key = SigningKey::from_bytes(&[7u8; 32]);
+        let other_key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut trusted = TrustedKeys::new();
+        trusted.add("core-team", publisher_key.verifying_key());
+        let registry = LocalRegistry::open(temp_dir.path())
+            .unwrap()
+            .with_trusted_keys(trusted);
+
+        let mut unsigned = package("approval", "1.0.0");
+        assert!(matches!(
+            registry.publish(&unsigned),
+            Err(MarketplaceError::Signature(_))
+        ));
+        unsigned.sign(&other_key);
+        assert!(registry.publish(&unsigned).is_err());
+
+        let mut signed = package("approval", "1.0.0");
+        signed.sign(&publisher_key);
+        let entry = registry.publish(&signed).unwrap();
+        assert_eq!(entry.publisher.as_deref(), Some("core-team"));
+        assert!(registry.publish(&signed).is_err(), "versions are immutable");
+
+        let fetched = registry.fetch("approval", &entry.version).unwrap();
+        assert_eq!(fetched, signed);
+    }
+
+    #[test]
+    fn test_unsigned_packages_need_explicit_opt_in() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LocalRegistry::open(temp_dir.path()).unwrap();
+        assert!(matches!(
+            registry.publish(&package("approval", "1.0.0")),
+            Err(MarketplaceError::Signature(_))
+        ));
+
+        let registry = LocalRegistry::open(temp_dir.path())
+            .unwrap()
+            .allow_unsigned();
+        let entry = registry.publish(&package("approval", "1.0.0")).unwrap();
+        assert_eq!(entry.publisher, None);
+
+        // Reopening without the opt-in refuses to hand out the unsigned package
+        let registry = LocalRegistry::open(temp_dir.path()).unwrap();
+        assert!(matches!(
+            registry.fetch("approval", &entry.version),
+            Err(MarketplaceError::Signature(_))
+        ));
+    }
+
+    #[test]
+    fn test_fetch_detects_tampered_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LocalRegistry::open(temp_dir.path())
+            .unwrap()
+            .allow_unsigned();
+        let entry = registry.publish(&package("approval", "1.0.0")).unwrap();
+
+        let mut tampered = package("approval", "1.0.0");
+        tampered
+            .files
+            .insert("specs/main.ttl".to_string(), b"# replaced".to_vec());
+        let path = registry.package_path("approval", &entry.version);
+        fs::write(&path, serde_json::to_vec(&tampered).unwrap()).unwrap();
+
+        assert!(registry.fetch("approval", &entry.version).is_err());
+    }
+}
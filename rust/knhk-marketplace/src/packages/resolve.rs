@@ -0,0 +1,180 @@
+//! Semver dependency resolution
+//!
+//! Picks one version per package so that every requirement is met,
+//! preferring the highest version and backtracking on conflicts. Versions
+//! pinned by an existing lockfile are kept while they still satisfy the
+//! requirements; yanked versions are only used when pinned.
+
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, HashMap};
+
+use super::lockfile::{LockedPackage, Lockfile};
+use super::registry::{IndexEntry, LocalRegistry};
+use crate::{MarketplaceError, Result};
+
+/// Upper bound on resolution steps before giving up
+const MAX_RESOLUTION_STEPS: usize = 10_000;
+
+#[derive(Debug, Clone)]
+struct Requirement {
+    name: String,
+    requirement: VersionReq,
+    required_by: String,
+}
+
+/// Resolve `dependencies` (the project's direct requirements) to a lockfile
+pub fn resolve(
+    registry: &LocalRegistry,
+    dependencies: &BTreeMap<String, VersionReq>,
+    locked: Option<&Lockfile>,
+) -> Result<Lockfile> {
+    let mut resolver = Resolver {
+        registry,
+        locked: locked
+            .map(|lockfile| {
+                lockfile
+                    .packages
+                    .iter()
+                    .map(|package| (package.name.clone(), package.version.clone()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        index: HashMap::new(),
+        steps: 0,
+    };
+    let pending: Vec<Requirement> = dependencies
+        .iter()
+        .map(|(name, requirement)| Requirement {
+            name: name.clone(),
+            requirement: requirement.clone(),
+            required_by: "project".to_string(),
+        })
+        .collect();
+    let chosen = resolver.solve(&pending, &BTreeMap::new())?;
+
+    let packages = chosen
+        .values()
+        .map(|entry| LockedPackage {
+            name: entry.name.clone(),
+            version: entry.version.clone(),
+            checksum: entry.checksum.clone(),
+            dependencies: entry
+                .dependencies
+                .keys()
+                .filter_map(|name| chosen.get(name))
+                .map(|dependency| format!("{} {}", dependency.name, dependency.version))
+                .collect(),
+        })
+        .collect();
+    Ok(Lockfile::new(packages))
+}
+
+struct Resolver<'a> {
+    registry: &'a LocalRegistry,
+    locked: HashMap<String, Version>,
+    index: HashMap<String, Vec<IndexEntry>>,
+    steps: usize,
+}
+
+impl Resolver<'_> {
+    fn solve(
+        &mut self,
+        pending: &[Requirement],
+        chosen: &BTreeMap<String, IndexEntry>,
+    ) -> Result<BTreeMap<String, IndexEntry>> {
+        self.steps += 1;
+        if self.steps > MAX_RESOLUTION_STEPS {
+            return Err(MarketplaceError::Resolution(format!(
+                "Gave up after {} steps",
+                MAX_RESOLUTION_STEPS
+            )));
+        }
+
+        let Some((next, rest)) = pending.split_first() else {
+            return Ok(chosen.clone());
+        };
+        if let Some(entry) = chosen.get(&next.name) {
+            if next.requirement.matches(&entry.version) {
+                return self.solve(rest, chosen);
+            }
+            return Err(MarketplaceError::Resolution(format!(
+                "{} requires {} {}, but {} was selected",
+                next.required_by, next.name, next.requirement, entry.version
+            )));
+        }
+
+        // Versions must also meet the requirements still pending on this name
+        let others: Vec<&Requirement> = rest.iter().filter(|r| r.name == next.name).collect();
+        let candidates: Vec<IndexEntry> = self
+            .candidates(next)?
+            .into_iter()
+            .filter(|entry| others.iter().all(|r| r.requirement.matches(&entry.version)))
+            .collect();
+        if candidates.is_empty() {
+            let mut requirements =
+                vec![format!("{} (from {})", next.requirement, next.required_by)];
+            requirements.extend(
+                others
+                    .iter()
+                    .map(|r| format!("{} (from {})", r.requirement, r.required_by)),
+            );
+            return Err(MarketplaceError::Resolution(format!(
+                "No version of {} matches {}",
+                next.name,
+                requirements.join(", ")
+            )));
+        }
+
+        let mut last_error = None;
+        for candidate in candidates {
+            let required_by = format!("{}@{}", candidate.name, candidate.version);
+            let mut pending = rest.to_vec();
+            pending.extend(
+                candidate
+                    .dependencies
+                    .iter()
+                    .map(|(name, requirement)| Requirement {
+                        name: name.clone(),
+                        requirement: requirement.clone(),
+                        required_by: required_by.clone(),
+                    }),
+            );
+            let mut chosen = chosen.clone();
+            chosen.insert(candidate.name.clone(), candidate);
+            match self.solve(&pending, &chosen) {
+                Ok(solution) => return Ok(solution),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            MarketplaceError::Resolution(format!("No version of {} could be selected", next.name))
+        }))
+    }
+
+    /// Matching versions, locked version first, then highest first
+    fn candidates(&mut self, requirement: &Requirement) -> Result<Vec<IndexEntry>> {
+        if !self.index.contains_key(&requirement.name) {
+            let entries = self.registry.versions(&requirement.name)?;
+            self.index.insert(requirement.name.clone(), entries);
+        }
+        let locked = self.locked.get(&requirement.name);
+        let mut candidates: Vec<IndexEntry> = self
+            .index
+            .get(&requirement.name)
+            .map(|entries| entries.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|entry| requirement.requirement.matches(&entry.version))
+            .filter(|entry| !entry.yanked || Some(&entry.version) == locked)
+            .cloned()
+            .collect();
+        candidates.sort_by(|a, b| {
+            let a_locked = Some(&a.version) == locked;
+            let b_locked = Some(&b.version) == locked;
+            b_locked
+                .cmp(&a_locked)
+                .then_with(|| b.version.cmp(&a.version))
+        });
+        Ok(candidates)
+    }
+}
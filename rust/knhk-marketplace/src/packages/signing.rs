@@ -0,0 +1,135 @@
+//! Package signatures
+//!
+//! Publishers sign the package checksum with ed25519. Registries and installers
+//! hold the publisher keys they trust; a package signed by any other key is
+//! rejected.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::package::TemplatePackage;
+use crate::{MarketplaceError, Result};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageSignature {
+    /// Hex-encoded ed25519 public key of the signer
+    pub public_key: String,
+    /// Hex-encoded signature of the package checksum
+    pub signature: String,
+}
+
+impl TemplatePackage {
+    /// Sign the package checksum, replacing any previous signature
+    pub fn sign(&mut self, signing_key: &SigningKey) {
+        let signature = signing_key.sign(self.checksum.as_bytes());
+        self.signature = Some(PackageSignature {
+            public_key: hex::encode(signing_key.verifying_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        });
+    }
+}
+
+/// Publisher keys trusted to sign packages, by publisher name
+#[derive(Debug, Clone, Default)]
+pub struct TrustedKeys {
+    keys: BTreeMap<String, VerifyingKey>,
+}
+
+impl TrustedKeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, publisher: impl Into<String>, key: VerifyingKey) {
+        self.keys.insert(publisher.into(), key);
+    }
+
+    /// Load trusted keys from a TOML file of hex-encoded public keys
+    ///
+    /// ```toml
+    /// [publishers]
+    /// core-team = "3b6a27bc..."
+    /// ```
+    pub fn load(path: &Path) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct KeysFile {
+            #[serde(default)]
+            publishers: BTreeMap<String, String>,
+        }
+
+        let file: KeysFile = toml::from_str(&std::fs::read_to_string(path)?).map_err(|e| {
+            MarketplaceError::Signature(format!("Invalid {}: {}", path.display(), e))
+        })?;
+        let mut trusted = Self::new();
+        for (publisher, key) in file.publishers {
+            let bytes: [u8; 32] = decode_key(&key).map_err(|e| {
+                MarketplaceError::Signature(format!("Key of publisher {}: {}", publisher, e))
+            })?;
+            let key = VerifyingKey::from_bytes(&bytes).map_err(|e| {
+                MarketplaceError::Signature(format!("Key of publisher {}: {}", publisher, e))
+            })?;
+            trusted.add(publisher, key);
+        }
+        Ok(trusted)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Verify that the package is signed by a trusted key
+    ///
+    /// Returns the publisher name. The checksum itself is checked when the
+    /// package is read, so a valid signature covers the whole content.
+    pub fn verify(&self, package: &TemplatePackage) -> Result<String> {
+        let signed = package.signature.as_ref().ok_or_else(|| {
+            MarketplaceError::Signature(format!("Package {} is not signed", package.id()))
+        })?;
+        let (publisher, key) = self
+            .keys
+            .iter()
+            .find(|(_, key)| hex::encode(key.to_bytes()) == signed.public_key)
+            .ok_or_else(|| {
+                MarketplaceError::Signature(format!(
+                    "Package {} is signed by untrusted key {}",
+                    package.id(),
+                    signed.public_key
+                ))
+            })?;
+
+        let bytes = hex::decode(&signed.signature)
+            .map_err(|e| MarketplaceError::Signature(format!("Malformed signature: {}", e)))?;
+        let signature = Signature::from_bytes(
+            bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| MarketplaceError::Signature("Malformed signature".to_string()))?,
+        );
+        key.verify(package.checksum.as_bytes(), &signature)
+            .map_err(|_| {
+                MarketplaceError::Signature(format!(
+                    "Invalid signature on package {}",
+                    package.id()
+                ))
+            })?;
+
+        Ok(publisher.clone())
+    }
+}
+
+/// Read a publisher signing key stored as 64 hex characters
+pub fn load_signing_key(path: &Path) -> Result<SigningKey> {
+    let bytes = decode_key(std::fs::read_to_string(path)?.trim())
+        .map_err(|e| MarketplaceError::Signature(format!("{}: {}", path.display(), e)))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+fn decode_key(hex_key: &str) -> std::result::Result<[u8; 32], String> {
+    hex::decode(hex_key)
+        .map_err(|e| e.to_string())?
+        .try_into()
+        .map_err(|_| "expected a 32-byte hex key".to_string())
+}
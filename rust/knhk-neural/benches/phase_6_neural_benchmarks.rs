@@ -51,13 +51,11 @@ impl NeuralLayer {
     /// Forward pass - compute output from input
     fn forward(&self, input: &[f32]) -> Vec<f32> {
         let mut output = vec![0.0; self.output_size];
-        for i in 0..self.output_size {
-            let mut sum = self.biases[i];
-            for j in 0..self.input_size {
-                sum += input[j] * self.weights[i * self.input_size + j];
-            }
+        for (i, out) in output.iter_mut().enumerate() {
+            let row = &self.weights[i * self.input_size..(i + 1) * self.input_size];
+            let sum = self.biases[i] + input.iter().zip(row).map(|(x, w)| x * w).sum::<f32>();
             // ReLU activation
-            output[i] = if sum > 0.0 { sum } else { 0.0 };
+            *out = if sum > 0.0 { sum } else { 0.0 };
         }
         output
     }
@@ -117,16 +115,6 @@ struct WorkflowState {
     step: usize,
 }
 
-impl WorkflowState {
-    fn features(&self) -> Vec<f32> {
-        vec![self.step as f32]
-    }
-
-    fn is_terminal(&self) -> bool {
-        self.step >= 100
-    }
-}
-
 /// Q-Learning action
 #[derive(Clone, Eq, PartialEq, Hash, Copy)]
 struct WorkflowAction(usize);
@@ -134,7 +122,7 @@ struct WorkflowAction(usize);
 impl WorkflowAction {
     const ACTION_COUNT: usize = 10;
 
-    fn to_index(&self) -> usize {
+    fn to_index(self) -> usize {
         self.0
     }
 
@@ -218,10 +206,6 @@ impl QLearningAgent {
         let delta = self.learning_rate * (target - current_q);
         self.q_table.get_mut(state).unwrap()[action_idx] += delta;
     }
-
-    fn episode_count(&self) -> usize {
-        self.q_table.len()
-    }
 }
 
 // ============================================================================
@@ -293,7 +277,7 @@ fn bench_convergence_speed(c: &mut Criterion) {
                         current_loss += layer.backward(&gradient, 0.001);
                     }
                 }
-                current_loss /= (dataset.samples.len() as f32);
+                current_loss /= dataset.samples.len() as f32;
                 epoch += 1;
             }
 
@@ -319,7 +303,7 @@ fn bench_convergence_speed(c: &mut Criterion) {
                         current_loss += layer.backward(&gradient, 0.001);
                     }
                 }
-                current_loss /= (dataset.samples.len() as f32);
+                current_loss /= dataset.samples.len() as f32;
                 epoch += 1;
             }
 
@@ -560,7 +544,6 @@ fn bench_daily_improvement(c: &mut Criterion) {
             for epoch in 0..5 {
                 let mut epoch_loss = 0.0;
 
-                let start = Instant::now();
                 for (batch_samples, _) in dataset.batches(32) {
                     for sample in batch_samples {
                         let output = layer.forward(sample);
@@ -568,9 +551,8 @@ fn bench_daily_improvement(c: &mut Criterion) {
                         epoch_loss += layer.backward(&gradient, 0.001);
                     }
                 }
-                let epoch_duration = start.elapsed();
 
-                epoch_loss /= (dataset.samples.len() as f32);
+                epoch_loss /= dataset.samples.len() as f32;
                 losses.push(epoch_loss);
 
                 // Adaptive learning rate decay
@@ -630,6 +612,7 @@ fn bench_adaptive_workflow(c: &mut Criterion) {
                 agent.update(&state, action, reward, &next_state);
             }
 
+            black_box(cumulative_reward);
             start.elapsed()
         });
     });
@@ -0,0 +1,450 @@
+// Phase 6: Model Checkpointing and Versioned Registry
+// Persist weights, optimizer state, Q-tables and training metrics keyed by ModelVersion
+//
+// DOCTRINE ALIGNMENT:
+// - Principle: Knowledge (K in MAPE-K) must survive restarts
+// - Covenant: Every deployed model is reproducible from an immutable checkpoint
+// - Implementation: bincode checkpoints + JSON registry index on local disk
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::optimizer::OptimizerCheckpoint;
+use crate::workflow::ModelVersion;
+
+/// Checkpoint format version written into every file
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+const REGISTRY_INDEX_FILE: &str = "registry.json";
+
+/// Errors raised while saving, loading or applying checkpoints
+#[derive(Debug)]
+pub enum CheckpointError {
+    /// Filesystem error
+    Io(std::io::Error),
+    /// Encoding or decoding failed
+    Serialization(String),
+    /// Checkpoint layer shape does not match the target layer
+    ShapeMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// Checkpoint written by an unsupported format version
+    UnsupportedFormat(u32),
+    /// Requested version is not in the registry
+    VersionNotFound(usize),
+    /// Version already registered (checkpoints are immutable)
+    VersionExists(usize),
+    /// No previous model to roll back to
+    NoRollbackPoint,
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(e) => write!(f, "checkpoint I/O error: {}", e),
+            CheckpointError::Serialization(e) => write!(f, "checkpoint serialization error: {}", e),
+            CheckpointError::ShapeMismatch { expected, found } => write!(
+                f,
+                "layer shape mismatch: expected {}x{}, found {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            CheckpointError::UnsupportedFormat(v) => {
+                write!(f, "unsupported checkpoint format version {}", v)
+            }
+            CheckpointError::VersionNotFound(v) => write!(f, "model version {} not found", v),
+            CheckpointError::VersionExists(v) => write!(f, "model version {} already exists", v),
+            CheckpointError::NoRollbackPoint => write!(f, "no previous model to roll back to"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<std::io::Error> for CheckpointError {
+    fn from(e: std::io::Error) -> Self {
+        CheckpointError::Io(e)
+    }
+}
+
+impl From<bincode::Error> for CheckpointError {
+    fn from(e: bincode::Error) -> Self {
+        CheckpointError::Serialization(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CheckpointError {
+    fn from(e: serde_json::Error) -> Self {
+        CheckpointError::Serialization(e.to_string())
+    }
+}
+
+/// Weights and biases of one dense layer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerCheckpoint {
+    pub input_size: usize,
+    pub output_size: usize,
+    /// Row-major [output_size x input_size] weights
+    pub weights: Vec<f32>,
+    pub biases: Vec<f32>,
+    pub learning_rate: f32,
+}
+
+/// Q-table and hyperparameters of a Q-Learning agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QLearningCheckpoint<S> {
+    pub q_table: Vec<(S, Vec<f32>)>,
+    pub action_count: usize,
+    pub learning_rate: f32,
+    pub discount_factor: f32,
+    pub exploration_rate: f32,
+    pub exploration_decay: f32,
+    pub episodes: usize,
+    pub total_reward: f32,
+}
+
+/// Training metrics captured at checkpoint time
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointMetrics {
+    pub avg_reward: f32,
+    pub loss: f32,
+    pub convergence: f32,
+    pub success_rate: f32,
+    pub avg_duration_ms: f32,
+    pub total_episodes: usize,
+}
+
+/// Complete, self-describing model checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCheckpoint<S> {
+    pub format_version: u32,
+    pub version: ModelVersion,
+    pub created_at: DateTime<Utc>,
+    pub layers: Vec<LayerCheckpoint>,
+    pub optimizer: Option<OptimizerCheckpoint>,
+    pub q_learning: Option<QLearningCheckpoint<S>>,
+    pub hyperparameters: HashMap<String, f32>,
+    pub metrics: CheckpointMetrics,
+}
+
+impl<S> ModelCheckpoint<S> {
+    /// Create an empty checkpoint for a model version
+    pub fn new(version: ModelVersion) -> Self {
+        Self {
+            format_version: CHECKPOINT_FORMAT_VERSION,
+            version,
+            created_at: Utc::now(),
+            layers: Vec::new(),
+            optimizer: None,
+            q_learning: None,
+            hyperparameters: HashMap::new(),
+            metrics: CheckpointMetrics::default(),
+        }
+    }
+
+    pub fn with_layers(mut self, layers: Vec<LayerCheckpoint>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    pub fn with_optimizer(mut self, optimizer: OptimizerCheckpoint) -> Self {
+        self.optimizer = Some(optimizer);
+        self
+    }
+
+    pub fn with_q_learning(mut self, q_learning: QLearningCheckpoint<S>) -> Self {
+        self.q_learning = Some(q_learning);
+        self
+    }
+
+    pub fn with_hyperparameter(mut self, name: &str, value: f32) -> Self {
+        self.hyperparameters.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: CheckpointMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+}
+
+impl<S: Serialize + DeserializeOwned> ModelCheckpoint<S> {
+    /// Encode to the binary checkpoint format
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CheckpointError> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Decode from the binary checkpoint format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CheckpointError> {
+        let checkpoint: Self = bincode::deserialize(bytes)?;
+        if checkpoint.format_version != CHECKPOINT_FORMAT_VERSION {
+            return Err(CheckpointError::UnsupportedFormat(
+                checkpoint.format_version,
+            ));
+        }
+        Ok(checkpoint)
+    }
+
+    /// Save atomically (write to a temp file, then rename)
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("ckpt.tmp");
+        fs::write(&tmp, self.to_bytes()?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Load a checkpoint from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Registry index entry (one per stored version)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub version: ModelVersion,
+    pub file: String,
+    pub created_at: DateTime<Utc>,
+    pub metrics: CheckpointMetrics,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RegistryIndex {
+    entries: Vec<RegistryEntry>,
+    active: Option<usize>,
+}
+
+/// Local model registry keyed by `ModelVersion::version`
+///
+/// Layout: `<root>/registry.json` plus one `v<N>.ckpt` file per version.
+/// Registered checkpoints are immutable; the index tracks which one is active.
+pub struct ModelRegistry {
+    root: PathBuf,
+    index: RwLock<RegistryIndex>,
+}
+
+impl ModelRegistry {
+    /// Open (or create) a registry rooted at `root`
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+
+        let index_path = root.join(REGISTRY_INDEX_FILE);
+        let index = if index_path.exists() {
+            serde_json::from_slice(&fs::read(&index_path)?)?
+        } else {
+            RegistryIndex::default()
+        };
+
+        Ok(Self {
+            root,
+            index: RwLock::new(index),
+        })
+    }
+
+    /// Next unused version number
+    pub fn next_version(&self) -> usize {
+        self.index
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .map(|e| e.version.version)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Store a checkpoint under its version
+    pub fn register<S: Serialize + DeserializeOwned>(
+        &self,
+        checkpoint: &ModelCheckpoint<S>,
+    ) -> Result<ModelVersion, CheckpointError> {
+        let mut index = self.index.write().unwrap();
+        let version = checkpoint.version.version;
+        if index.entries.iter().any(|e| e.version.version == version) {
+            return Err(CheckpointError::VersionExists(version));
+        }
+
+        let file = format!("v{}.ckpt", version);
+        checkpoint.save(self.root.join(&file))?;
+
+        index.entries.push(RegistryEntry {
+            version: checkpoint.version.clone(),
+            file,
+            created_at: checkpoint.created_at,
+            metrics: checkpoint.metrics.clone(),
+        });
+        index.entries.sort_by_key(|e| e.version.version);
+        self.persist_index(&index)?;
+
+        Ok(checkpoint.version.clone())
+    }
+
+    /// Load a specific version
+    pub fn load<S: Serialize + DeserializeOwned>(
+        &self,
+        version: usize,
+    ) -> Result<ModelCheckpoint<S>, CheckpointError> {
+        let file = self
+            .index
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|e| e.version.version == version)
+            .map(|e| e.file.clone())
+            .ok_or(CheckpointError::VersionNotFound(version))?;
+        ModelCheckpoint::load(self.root.join(file))
+    }
+
+    /// Load the highest registered version
+    pub fn latest<S: Serialize + DeserializeOwned>(
+        &self,
+    ) -> Result<Option<ModelCheckpoint<S>>, CheckpointError> {
+        let latest = self
+            .index
+            .read()
+            .unwrap()
+            .entries
+            .last()
+            .map(|e| e.version.version);
+        latest.map(|v| self.load(v)).transpose()
+    }
+
+    /// All registered versions, oldest first
+    pub fn versions(&self) -> Vec<ModelVersion> {
+        self.index
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .map(|e| e.version.clone())
+            .collect()
+    }
+
+    /// Registry entries (version, file, metrics), oldest first
+    pub fn entries(&self) -> Vec<RegistryEntry> {
+        self.index.read().unwrap().entries.clone()
+    }
+
+    /// Version currently serving traffic
+    pub fn active_version(&self) -> Option<usize> {
+        self.index.read().unwrap().active
+    }
+
+    /// Mark a version as active
+    pub fn set_active(&self, version: usize) -> Result<(), CheckpointError> {
+        let mut index = self.index.write().unwrap();
+        if !index.entries.iter().any(|e| e.version.version == version) {
+            return Err(CheckpointError::VersionNotFound(version));
+        }
+        index.active = Some(version);
+        self.persist_index(&index)
+    }
+
+    fn persist_index(&self, index: &RegistryIndex) -> Result<(), CheckpointError> {
+        let path = self.root.join(REGISTRY_INDEX_FILE);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(index)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DenseLayer;
+    use crate::optimizer::{AdamOptimizer, Optimizer};
+
+    fn version(v: usize) -> ModelVersion {
+        ModelVersion {
+            version: v,
+            timestamp: 0,
+            avg_reward: v as f32,
+            loss: 0.1,
+            convergence: 0.5,
+        }
+    }
+
+    fn temp_root() -> PathBuf {
+        std::env::temp_dir().join(format!("knhk-neural-registry-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let mut layer: DenseLayer<4, 3> = DenseLayer::new();
+        layer.initialize_xavier();
+        let optimizer = AdamOptimizer::new(0.01).unwrap();
+
+        let checkpoint: ModelCheckpoint<u32> = ModelCheckpoint::new(version(1))
+            .with_layers(vec![layer.to_checkpoint()])
+            .with_optimizer(optimizer.state_dict())
+            .with_hyperparameter("learning_rate", 0.01);
+
+        let restored = ModelCheckpoint::<u32>::from_bytes(&checkpoint.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.layers, checkpoint.layers);
+        assert_eq!(restored.hyperparameters.get("learning_rate"), Some(&0.01));
+
+        let mut fresh: DenseLayer<4, 3> = DenseLayer::new();
+        fresh.load_checkpoint(&restored.layers[0]).unwrap();
+        assert_eq!(fresh.to_checkpoint(), layer.to_checkpoint());
+    }
+
+    #[test]
+    fn test_layer_shape_mismatch_rejected() {
+        let layer: DenseLayer<4, 3> = DenseLayer::new();
+        let mut other: DenseLayer<3, 4> = DenseLayer::new();
+        assert!(matches!(
+            other.load_checkpoint(&layer.to_checkpoint()),
+            Err(CheckpointError::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_registry_versions_and_active() {
+        let root = temp_root();
+        {
+            let registry = ModelRegistry::open(&root).unwrap();
+            assert_eq!(registry.next_version(), 1);
+
+            registry
+                .register(&ModelCheckpoint::<u32>::new(version(1)))
+                .unwrap();
+            registry
+                .register(&ModelCheckpoint::<u32>::new(version(2)))
+                .unwrap();
+            assert!(matches!(
+                registry.register(&ModelCheckpoint::<u32>::new(version(2))),
+                Err(CheckpointError::VersionExists(2))
+            ));
+            registry.set_active(2).unwrap();
+        }
+
+        // Index survives reopen
+        let registry = ModelRegistry::open(&root).unwrap();
+        assert_eq!(registry.versions().len(), 2);
+        assert_eq!(registry.active_version(), Some(2));
+        assert_eq!(registry.next_version(), 3);
+        let latest = registry.latest::<u32>().unwrap().unwrap();
+        assert_eq!(latest.version.version, 2);
+        assert!(matches!(
+            registry.load::<u32>(7),
+            Err(CheckpointError::VersionNotFound(7))
+        ));
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
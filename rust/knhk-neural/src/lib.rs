@@ -1,19 +1,30 @@
 // KNHK Phase 6: Advanced Neural Integration
 // Hyper-advanced Rust with Generic Associated Types (GATs) and neural networks
 
+pub mod checkpoint;
 pub mod model;
+pub mod offline;
 pub mod optimizer;
 pub mod reinforcement;
 pub mod training;
 pub mod workflow;
 
+pub use checkpoint::{
+    CheckpointError, CheckpointMetrics, LayerCheckpoint, ModelCheckpoint, ModelRegistry,
+    QLearningCheckpoint, RegistryEntry,
+};
 pub use model::{DenseLayer, Layer, NeuralModel};
-pub use reinforcement::{Agent, QLearning, SARSAAgent, WorkflowAction, WorkflowState};
+pub use offline::{
+    CaseLog, OffPolicyEvaluator, OffPolicyReport, OfflineDataset, OfflineError, OfflineTrainer,
+    OfflineTrainingConfig, OfflineTrainingSummary, RecordedCase, RecordedEvent, Transition,
+    TransitionMapper,
+};
+pub use reinforcement::{Agent, QFunction, QLearning, SARSAAgent, WorkflowAction, WorkflowState};
 pub use training::Trainer;
 pub use workflow::{
     AdaptiveWorkflowExecutor, EpisodeResult, LearningMetrics, ModelVersion,
-    PerformanceImprovements, PerformanceTracker, SelfLearningWorkflow, WorkflowConfig,
-    WorkflowExecutor, WorkflowMetrics,
+    PerformanceImprovements, PerformanceTracker, RegressionPolicy, RegressionReport,
+    SelfLearningWorkflow, WorkflowConfig, WorkflowExecutor, WorkflowMetrics,
 };
 
 /// Prelude for Phase 6 neural features
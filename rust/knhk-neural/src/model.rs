@@ -3,6 +3,8 @@
 
 use ndarray::{Array1, Array2};
 
+use crate::checkpoint::{CheckpointError, LayerCheckpoint};
+
 /// Generic Associated Types enable lifetime-dependent trait methods
 /// This allows us to return references to internal state without lifetime issues
 pub trait NeuralModel: Clone + Send + Sync {
@@ -76,19 +78,42 @@ impl<const IN: usize, const OUT: usize> DenseLayer<IN, OUT> {
         OUT
     }
 
-    /// ReLU activation
-    fn relu(x: f32) -> f32 {
-        if x > 0.0 {
-            x
-        } else {
-            0.0
+    /// Snapshot weights and biases for persistence
+    pub fn to_checkpoint(&self) -> LayerCheckpoint {
+        LayerCheckpoint {
+            input_size: IN,
+            output_size: OUT,
+            weights: self.weights.iter().cloned().collect(),
+            biases: self.biases.to_vec(),
+            learning_rate: self.learning_rate,
         }
     }
 
-    /// ReLU derivative
-    fn relu_prime(x: f32) -> f32 {
+    /// Restore weights and biases (shape must match IN x OUT)
+    pub fn load_checkpoint(&mut self, checkpoint: &LayerCheckpoint) -> Result<(), CheckpointError> {
+        let shape_ok = checkpoint.input_size == IN
+            && checkpoint.output_size == OUT
+            && checkpoint.weights.len() == IN * OUT
+            && checkpoint.biases.len() == OUT;
+        if !shape_ok {
+            return Err(CheckpointError::ShapeMismatch {
+                expected: (IN, OUT),
+                found: (checkpoint.input_size, checkpoint.output_size),
+            });
+        }
+
+        self.weights = Array2::from_shape_vec((OUT, IN), checkpoint.weights.clone())
+            .map_err(|e| CheckpointError::Serialization(e.to_string()))?;
+        self.biases = Array1::from_vec(checkpoint.biases.clone());
+        self.learning_rate = checkpoint.learning_rate;
+        self.last_input = None;
+        Ok(())
+    }
+
+    /// ReLU activation
+    fn relu(x: f32) -> f32 {
         if x > 0.0 {
-            1.0
+            x
         } else {
             0.0
         }
@@ -137,8 +162,7 @@ impl<const IN: usize, const OUT: usize> NeuralModel for DenseLayer<IN, OUT> {
             self.biases -= &(gradient * self.learning_rate);
 
             // Compute loss (L2 norm of gradient)
-            let loss = gradient.dot(gradient).sqrt();
-            loss
+            gradient.dot(gradient).sqrt()
         } else {
             0.0
         }
@@ -182,6 +206,25 @@ impl<const L1: usize, const L2: usize, const L3: usize> SequentialModel<L1, L2,
         let intermediate = Layer::forward(&self.layer1, input);
         Layer::forward(&self.layer2, &intermediate)
     }
+
+    /// Snapshot both layers, input layer first
+    pub fn to_checkpoint(&self) -> Vec<LayerCheckpoint> {
+        vec![self.layer1.to_checkpoint(), self.layer2.to_checkpoint()]
+    }
+
+    /// Restore both layers from a checkpoint produced by `to_checkpoint`
+    pub fn load_checkpoint(&mut self, layers: &[LayerCheckpoint]) -> Result<(), CheckpointError> {
+        match layers {
+            [first, second] => {
+                self.layer1.load_checkpoint(first)?;
+                self.layer2.load_checkpoint(second)
+            }
+            _ => Err(CheckpointError::Serialization(format!(
+                "expected 2 layers, found {}",
+                layers.len()
+            ))),
+        }
+    }
 }
 
 impl<const L1: usize, const L2: usize, const L3: usize> Default for SequentialModel<L1, L2, L3> {
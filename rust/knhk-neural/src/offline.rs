@@ -0,0 +1,969 @@
+// Phase 6: Offline Reinforcement Learning
+// Train Q-Learning/SARSA policies from recorded case logs and evaluate them off-policy
+//
+// DOCTRINE ALIGNMENT:
+// - Principle: Knowledge (K in MAPE-K) is built from observed executions, not only live episodes
+// - Covenant: A learned policy never reaches production without an off-policy estimate
+// - Implementation: XES / StateEvent import + importance-sampling evaluation report
+
+use chrono::{DateTime, Utc};
+use process_mining::event_log::{Attribute, AttributeValue};
+use process_mining::{import_xes_file, import_xes_slice, EventLog, XESImportOptions};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::reinforcement::{QFunction, QLearning, SARSAAgent, WorkflowAction, WorkflowState};
+use crate::workflow::WorkflowMetrics;
+
+/// Errors raised while importing logs or training offline
+#[derive(Debug)]
+pub enum OfflineError {
+    /// Filesystem error
+    Io(std::io::Error),
+    /// Malformed log content
+    Parse { line: usize, message: String },
+    /// XES log rejected by the importer or missing required attributes
+    Xes(String),
+    /// Log produced no transitions to learn from
+    EmptyDataset,
+}
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OfflineError::Io(e) => write!(f, "case log I/O error: {}", e),
+            OfflineError::Parse { line, message } => {
+                write!(f, "case log parse error at line {}: {}", line, message)
+            }
+            OfflineError::Xes(message) => write!(f, "XES import error: {}", message),
+            OfflineError::EmptyDataset => write!(f, "case log contains no decision transitions"),
+        }
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+impl From<std::io::Error> for OfflineError {
+    fn from(e: std::io::Error) -> Self {
+        OfflineError::Io(e)
+    }
+}
+
+/// One completed (or aborted) activity in a recorded case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Activity / task name (concept:name in XES)
+    pub activity: String,
+    /// Completion timestamp
+    pub timestamp: DateTime<Utc>,
+    /// Execution time (start → complete)
+    pub duration_ms: f32,
+    /// Activity completed normally
+    pub success: bool,
+    /// Resource usage percentage (0-100)
+    pub resource_usage: f32,
+    /// KNHK pattern ID
+    pub pattern_id: u8,
+    /// Resource (actor) that executed the activity
+    pub resource: Option<String>,
+}
+
+impl RecordedEvent {
+    /// Metrics as they would have been recorded by `PerformanceTracker`
+    pub fn to_metrics(&self) -> WorkflowMetrics {
+        let mut metrics = WorkflowMetrics::new(
+            self.duration_ms,
+            self.success,
+            self.resource_usage,
+            self.pattern_id,
+        );
+        metrics.timestamp = self.timestamp;
+        metrics
+    }
+}
+
+/// Ordered activities of one workflow case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCase {
+    pub case_id: String,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// Historical executions used for offline training
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaseLog {
+    pub cases: Vec<RecordedCase>,
+}
+
+impl CaseLog {
+    /// Parse an XES 2.0 event log
+    ///
+    /// `start`/`complete` lifecycle pairs are folded into one event with a duration;
+    /// abort/withdraw/cancel transitions are recorded as unsuccessful. The optional
+    /// `knhk:duration_ms`, `knhk:success` and `knhk:resource_usage` attributes
+    /// override the derived values.
+    pub fn from_xes_str(xml: &str) -> Result<Self, OfflineError> {
+        let log = import_xes_slice(xml.as_bytes(), false, XESImportOptions::default())
+            .map_err(|e| OfflineError::Xes(format!("{:?}", e)))?;
+        Self::from_event_log(&log)
+    }
+
+    /// Load an XES file (optionally gzipped) from disk
+    pub fn load_xes(path: impl AsRef<Path>) -> Result<Self, OfflineError> {
+        let log = import_xes_file(path.as_ref(), XESImportOptions::default())
+            .map_err(|e| OfflineError::Xes(format!("{:?}", e)))?;
+        Self::from_event_log(&log)
+    }
+
+    /// Convert an event log imported with `process_mining`
+    ///
+    /// Traces without `concept:name` are numbered `trace-1`, `trace-2`, ...
+    pub fn from_event_log(log: &EventLog) -> Result<Self, OfflineError> {
+        let cases = log
+            .traces
+            .iter()
+            .enumerate()
+            .map(|(idx, trace)| {
+                let case_id = attribute(&trace.attributes, "concept:name")
+                    .and_then(text)
+                    .unwrap_or_else(|| format!("trace-{}", idx + 1));
+                let events = trace.events.iter().map(|e| e.attributes.as_slice());
+                build_case(case_id, events).map_err(OfflineError::Xes)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(CaseLog { cases })
+    }
+
+    /// Parse the engine's case history: one serialized `StateEvent` per line
+    ///
+    /// `TaskCompleted` events become activities; a case transitioning to
+    /// `Failed` or `Cancelled` marks its last activity as unsuccessful.
+    pub fn from_state_events(jsonl: &str) -> Result<Self, OfflineError> {
+        let mut log = CaseLog::default();
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for (line_idx, line) in jsonl.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parse_error = |message: String| OfflineError::Parse {
+                line: line_idx + 1,
+                message,
+            };
+
+            let value: serde_json::Value =
+                serde_json::from_str(line).map_err(|e| parse_error(e.to_string()))?;
+            let (kind, body) = value
+                .as_object()
+                .and_then(|o| o.iter().next())
+                .ok_or_else(|| parse_error("expected an externally tagged event".to_string()))?;
+
+            let case_id = match body.get("case_id") {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+                None => continue,
+            };
+            let timestamp = body
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+                .ok_or_else(|| parse_error("missing or invalid timestamp".to_string()))?;
+
+            let case_idx = *index.entry(case_id.clone()).or_insert_with(|| {
+                log.cases.push(RecordedCase {
+                    case_id,
+                    events: Vec::new(),
+                });
+                log.cases.len() - 1
+            });
+            let case = &mut log.cases[case_idx];
+
+            match kind.as_str() {
+                "TaskCompleted" => {
+                    let activity =
+                        body.get("task_name")
+                            .and_then(|n| n.as_str())
+                            .ok_or_else(|| {
+                                parse_error("TaskCompleted without task_name".to_string())
+                            })?;
+                    let duration_ms = body
+                        .get("duration_ms")
+                        .and_then(|d| d.as_f64())
+                        .unwrap_or(0.0) as f32;
+                    case.events.push(RecordedEvent {
+                        activity: activity.to_string(),
+                        timestamp,
+                        duration_ms,
+                        success: true,
+                        resource_usage: 0.0,
+                        pattern_id: 0,
+                        resource: None,
+                    });
+                }
+                "CaseStateChanged" => {
+                    let new_state = body.get("new_state").and_then(|s| s.as_str());
+                    if matches!(new_state, Some("Failed" | "Cancelled")) {
+                        if let Some(last) = case.events.last_mut() {
+                            last.success = false;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        log.cases.retain(|c| !c.events.is_empty());
+        Ok(log)
+    }
+
+    /// Total number of recorded activities
+    pub fn event_count(&self) -> usize {
+        self.cases.iter().map(|c| c.events.len()).sum()
+    }
+}
+
+/// Fold lifecycle transitions of one trace into completed activities
+fn build_case<'a>(
+    case_id: String,
+    events: impl Iterator<Item = &'a [Attribute]>,
+) -> Result<RecordedCase, String> {
+    let mut started: HashMap<String, DateTime<Utc>> = HashMap::new();
+    let mut recorded = Vec::new();
+
+    for attrs in events {
+        let activity = attribute(attrs, "concept:name")
+            .and_then(text)
+            .ok_or_else(|| format!("event without concept:name in trace '{}'", case_id))?;
+        let timestamp = match attribute(attrs, "time:timestamp") {
+            Some(value) => {
+                timestamp(value).ok_or_else(|| format!("invalid time:timestamp {:?}", value))?
+            }
+            None => return Err(format!("event '{}' without time:timestamp", activity)),
+        };
+        let lifecycle = attribute(attrs, "lifecycle:transition")
+            .and_then(text)
+            .map(|l| l.to_ascii_lowercase())
+            .unwrap_or_else(|| "complete".to_string());
+
+        let success = match lifecycle.as_str() {
+            "start" => {
+                started.insert(activity, timestamp);
+                continue;
+            }
+            "complete" => true,
+            "ate_abort" | "pi_abort" | "withdraw" | "cancel" => false,
+            // schedule, assign, suspend, resume, ... carry no outcome
+            _ => continue,
+        };
+
+        let derived_duration = started
+            .remove(&activity)
+            .map(|start| (timestamp - start).num_milliseconds().max(0) as f32)
+            .unwrap_or(0.0);
+        let float = |key: &str| attribute(attrs, key).and_then(number).map(|v| v as f32);
+
+        recorded.push(RecordedEvent {
+            duration_ms: float("knhk:duration_ms").unwrap_or(derived_duration),
+            success: attribute(attrs, "knhk:success")
+                .and_then(boolean)
+                .unwrap_or(success),
+            resource_usage: float("knhk:resource_usage").unwrap_or(0.0),
+            pattern_id: attribute(attrs, "pattern:id")
+                .and_then(integer)
+                .and_then(|id| u8::try_from(id).ok())
+                .unwrap_or(0),
+            resource: attribute(attrs, "org:resource").and_then(text),
+            activity,
+            timestamp,
+        });
+    }
+
+    Ok(RecordedCase {
+        case_id,
+        events: recorded,
+    })
+}
+
+fn attribute<'a>(attrs: &'a [Attribute], key: &str) -> Option<&'a AttributeValue> {
+    attrs.iter().find(|a| a.key == key).map(|a| &a.value)
+}
+
+fn text(value: &AttributeValue) -> Option<String> {
+    match value {
+        AttributeValue::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn timestamp(value: &AttributeValue) -> Option<DateTime<Utc>> {
+    match value {
+        AttributeValue::Date(d) => Some(d.with_timezone(&Utc)),
+        AttributeValue::String(s) => DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|d| d.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+fn number(value: &AttributeValue) -> Option<f64> {
+    match value {
+        AttributeValue::Float(f) => Some(*f),
+        AttributeValue::Int(i) => Some(*i as f64),
+        AttributeValue::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn integer(value: &AttributeValue) -> Option<i64> {
+    match value {
+        AttributeValue::Int(i) => Some(*i),
+        AttributeValue::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn boolean(value: &AttributeValue) -> Option<bool> {
+    match value {
+        AttributeValue::Boolean(b) => Some(*b),
+        AttributeValue::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Maps recorded cases onto the agent's state/action spaces
+pub trait TransitionMapper<S: WorkflowState, A: WorkflowAction> {
+    /// State observed before `case.events[step]` (`step == events.len()` is the final state)
+    fn state(&self, case: &RecordedCase, step: usize) -> S;
+
+    /// Action the logged policy took, or `None` if the event was not a decision
+    fn action(&self, event: &RecordedEvent) -> Option<A>;
+
+    /// Reward of one event (defaults to `WorkflowMetrics::to_reward`)
+    fn reward(&self, event: &RecordedEvent) -> f32 {
+        event.to_metrics().to_reward()
+    }
+}
+
+/// One logged decision: (s, a, r, s', a')
+#[derive(Debug, Clone)]
+pub struct Transition<S, A> {
+    pub state: S,
+    pub action: A,
+    pub reward: f32,
+    pub next_state: S,
+    /// Next logged decision (`None` at the end of the case)
+    pub next_action: Option<A>,
+    pub done: bool,
+}
+
+/// Logged transitions grouped by case
+#[derive(Debug, Clone)]
+pub struct OfflineDataset<S, A> {
+    episodes: Vec<Vec<Transition<S, A>>>,
+}
+
+impl<S: WorkflowState, A: WorkflowAction> OfflineDataset<S, A> {
+    /// Build transitions from a case log
+    ///
+    /// Rewards of non-decision events are credited to the preceding decision.
+    pub fn from_log<M: TransitionMapper<S, A>>(log: &CaseLog, mapper: &M) -> Self {
+        let mut episodes = Vec::with_capacity(log.cases.len());
+
+        for case in &log.cases {
+            let decisions: Vec<(usize, A)> = case
+                .events
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, event)| mapper.action(event).map(|a| (idx, a)))
+                .collect();
+            if decisions.is_empty() {
+                continue;
+            }
+
+            let mut episode = Vec::with_capacity(decisions.len());
+            for (k, (idx, action)) in decisions.iter().enumerate() {
+                let next = decisions.get(k + 1);
+                let end = next
+                    .map(|(next_idx, _)| *next_idx)
+                    .unwrap_or(case.events.len());
+                let reward = case.events[*idx..end]
+                    .iter()
+                    .map(|e| mapper.reward(e))
+                    .sum();
+
+                episode.push(Transition {
+                    state: mapper.state(case, *idx),
+                    action: action.clone(),
+                    reward,
+                    next_state: mapper.state(case, end),
+                    next_action: next.map(|(_, a)| a.clone()),
+                    done: next.is_none(),
+                });
+            }
+            episodes.push(episode);
+        }
+
+        Self { episodes }
+    }
+
+    pub fn episodes(&self) -> &[Vec<Transition<S, A>>] {
+        &self.episodes
+    }
+
+    pub fn transition_count(&self) -> usize {
+        self.episodes.iter().map(|e| e.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.episodes.is_empty()
+    }
+}
+
+/// Offline training configuration
+#[derive(Debug, Clone)]
+pub struct OfflineTrainingConfig {
+    /// Passes over the full dataset
+    pub epochs: usize,
+    /// Shuffle case order between epochs (transition order within a case is kept)
+    pub shuffle: bool,
+}
+
+impl Default for OfflineTrainingConfig {
+    fn default() -> Self {
+        Self {
+            epochs: 50,
+            shuffle: true,
+        }
+    }
+}
+
+/// Outcome of an offline training run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineTrainingSummary {
+    pub epochs: usize,
+    pub transitions: usize,
+    /// Mean |ΔQ(s,a)| per epoch (approaches 0 as the Q-table converges)
+    pub q_change_history: Vec<f32>,
+}
+
+impl OfflineTrainingSummary {
+    pub fn final_q_change(&self) -> f32 {
+        self.q_change_history.last().copied().unwrap_or(0.0)
+    }
+}
+
+/// Replays logged transitions into tabular agents
+pub struct OfflineTrainer {
+    config: OfflineTrainingConfig,
+}
+
+impl OfflineTrainer {
+    pub fn new(config: OfflineTrainingConfig) -> Self {
+        Self { config }
+    }
+
+    /// Off-policy Q-Learning over the logged transitions
+    pub fn train_q_learning<S: WorkflowState, A: WorkflowAction>(
+        &self,
+        agent: &QLearning<S, A>,
+        dataset: &OfflineDataset<S, A>,
+    ) -> Result<OfflineTrainingSummary, OfflineError> {
+        self.run(dataset, |t| {
+            let before = agent.get_q_value(&t.state, &t.action);
+            agent.update(&t.state, &t.action, t.reward, &t.next_state, t.done);
+            (agent.get_q_value(&t.state, &t.action) - before).abs()
+        })
+    }
+
+    /// SARSA over the logged transitions (evaluates the logged policy itself)
+    pub fn train_sarsa<S: WorkflowState, A: WorkflowAction>(
+        &self,
+        agent: &SARSAAgent<S, A>,
+        dataset: &OfflineDataset<S, A>,
+    ) -> Result<OfflineTrainingSummary, OfflineError> {
+        self.run(dataset, |t| {
+            let before = agent.get_q_value(&t.state, &t.action);
+            match &t.next_action {
+                Some(next_action) if !t.done => {
+                    agent.update(&t.state, &t.action, t.reward, &t.next_state, next_action)
+                }
+                _ => agent.update_terminal(&t.state, &t.action, t.reward),
+            }
+            (agent.get_q_value(&t.state, &t.action) - before).abs()
+        })
+    }
+
+    fn run<S, A, F>(
+        &self,
+        dataset: &OfflineDataset<S, A>,
+        mut step: F,
+    ) -> Result<OfflineTrainingSummary, OfflineError>
+    where
+        F: FnMut(&Transition<S, A>) -> f32,
+    {
+        let transitions: usize = dataset.episodes.iter().map(|e| e.len()).sum();
+        if transitions == 0 {
+            return Err(OfflineError::EmptyDataset);
+        }
+
+        let mut order: Vec<usize> = (0..dataset.episodes.len()).collect();
+        let mut rng = rand::thread_rng();
+        let mut q_change_history = Vec::with_capacity(self.config.epochs);
+
+        for _ in 0..self.config.epochs {
+            if self.config.shuffle {
+                order.shuffle(&mut rng);
+            }
+            let total_change: f32 = order
+                .iter()
+                .flat_map(|&idx| dataset.episodes[idx].iter())
+                .map(&mut step)
+                .sum();
+            q_change_history.push(total_change / transitions as f32);
+        }
+
+        Ok(OfflineTrainingSummary {
+            epochs: self.config.epochs,
+            transitions,
+            q_change_history,
+        })
+    }
+}
+
+impl Default for OfflineTrainer {
+    fn default() -> Self {
+        Self::new(OfflineTrainingConfig::default())
+    }
+}
+
+/// Importance-sampling evaluation of a learned policy against the logged one
+#[derive(Debug, Clone)]
+pub struct OffPolicyEvaluator {
+    /// γ used for episode returns
+    pub discount_factor: f32,
+    /// ε of the ε-greedy target policy (must be > 0 to give every action support)
+    pub target_epsilon: f32,
+    /// Minimum effective sample size before a policy may be recommended
+    pub min_effective_sample_size: f32,
+}
+
+impl Default for OffPolicyEvaluator {
+    fn default() -> Self {
+        Self {
+            discount_factor: 0.99,
+            target_epsilon: 0.05,
+            min_effective_sample_size: 10.0,
+        }
+    }
+}
+
+/// Off-policy evaluation results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffPolicyReport {
+    pub episodes: usize,
+    pub transitions: usize,
+    /// Mean discounted return of the logged (behavior) policy
+    pub behavior_value: f32,
+    /// Per-decision importance sampling estimate of the learned policy
+    pub importance_sampling_value: f32,
+    /// Weighted importance sampling estimate of the learned policy
+    pub weighted_importance_sampling_value: f32,
+    /// Kish effective sample size of the episode weights
+    pub effective_sample_size: f32,
+    /// Fraction of logged decisions where the learned policy agrees
+    pub action_agreement: f32,
+    /// Fraction of distinct logged states whose greedy action appears in the log
+    pub state_coverage: f32,
+    /// Estimated improvement (WIS estimate − behavior value)
+    pub estimated_improvement: f32,
+    /// Enough evidence and a positive estimated improvement
+    pub recommended: bool,
+}
+
+impl fmt::Display for OffPolicyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Off-policy evaluation ({} episodes, {} transitions)",
+            self.episodes, self.transitions
+        )?;
+        writeln!(f, "  logged policy value:   {:.3}", self.behavior_value)?;
+        writeln!(
+            f,
+            "  learned policy (IS):   {:.3}",
+            self.importance_sampling_value
+        )?;
+        writeln!(
+            f,
+            "  learned policy (WIS):  {:.3}",
+            self.weighted_importance_sampling_value
+        )?;
+        writeln!(
+            f,
+            "  effective sample size: {:.1}",
+            self.effective_sample_size
+        )?;
+        writeln!(
+            f,
+            "  action agreement:      {:.1}%",
+            self.action_agreement * 100.0
+        )?;
+        writeln!(
+            f,
+            "  state coverage:        {:.1}%",
+            self.state_coverage * 100.0
+        )?;
+        write!(
+            f,
+            "  estimated improvement: {:+.3} ({})",
+            self.estimated_improvement,
+            if self.recommended {
+                "recommended"
+            } else {
+                "not recommended"
+            }
+        )
+    }
+}
+
+impl OffPolicyEvaluator {
+    /// Estimate the value of `policy`'s ε-greedy policy from logged transitions
+    ///
+    /// The behavior policy is the empirical action distribution per logged state.
+    pub fn evaluate<S, A, Q>(
+        &self,
+        policy: &Q,
+        dataset: &OfflineDataset<S, A>,
+    ) -> Result<OffPolicyReport, OfflineError>
+    where
+        S: WorkflowState,
+        A: WorkflowAction,
+        Q: QFunction<S, A>,
+    {
+        let transitions = dataset.transition_count();
+        if transitions == 0 {
+            return Err(OfflineError::EmptyDataset);
+        }
+
+        let mut behavior_counts: HashMap<&S, Vec<usize>> = HashMap::new();
+        for t in dataset.episodes.iter().flatten() {
+            behavior_counts
+                .entry(&t.state)
+                .or_insert_with(|| vec![0; A::ACTION_COUNT])[t.action.to_index()] += 1;
+        }
+
+        let greedy: HashMap<&S, usize> = behavior_counts
+            .keys()
+            .map(|s| (*s, policy.greedy_action(s).to_index()))
+            .collect();
+        let explore = self.target_epsilon / A::ACTION_COUNT as f32;
+
+        let mut returns = Vec::with_capacity(dataset.episodes.len());
+        let mut weights = Vec::with_capacity(dataset.episodes.len());
+        let mut per_decision = Vec::with_capacity(dataset.episodes.len());
+        let mut agreements = 0usize;
+
+        for episode in &dataset.episodes {
+            let mut rho = 1.0f32;
+            let mut discount = 1.0f32;
+            let mut episode_return = 0.0f32;
+            let mut pdis = 0.0f32;
+
+            for t in episode {
+                let counts = &behavior_counts[&t.state];
+                let total: usize = counts.iter().sum();
+                let action_idx = t.action.to_index();
+                let greedy_idx = greedy[&t.state];
+
+                let behavior_prob = counts[action_idx] as f32 / total as f32;
+                let target_prob = if action_idx == greedy_idx {
+                    1.0 - self.target_epsilon + explore
+                } else {
+                    explore
+                };
+
+                rho *= target_prob / behavior_prob;
+                pdis += discount * rho * t.reward;
+                episode_return += discount * t.reward;
+                discount *= self.discount_factor;
+
+                if action_idx == greedy_idx {
+                    agreements += 1;
+                }
+            }
+
+            returns.push(episode_return);
+            weights.push(rho);
+            per_decision.push(pdis);
+        }
+
+        let n = dataset.episodes.len() as f32;
+        let behavior_value = returns.iter().sum::<f32>() / n;
+        let importance_sampling_value = per_decision.iter().sum::<f32>() / n;
+
+        let weight_sum: f32 = weights.iter().sum();
+        let weight_sq_sum: f32 = weights.iter().map(|w| w * w).sum();
+        let weighted_importance_sampling_value = if weight_sum > 0.0 {
+            weights
+                .iter()
+                .zip(&returns)
+                .map(|(w, g)| w * g)
+                .sum::<f32>()
+                / weight_sum
+        } else {
+            0.0
+        };
+        let effective_sample_size = if weight_sq_sum > 0.0 {
+            weight_sum * weight_sum / weight_sq_sum
+        } else {
+            0.0
+        };
+
+        let covered = greedy
+            .iter()
+            .filter(|(state, greedy_idx)| behavior_counts[*state][**greedy_idx] > 0)
+            .count();
+        let state_coverage = covered as f32 / behavior_counts.len() as f32;
+
+        let estimated_improvement = weighted_importance_sampling_value - behavior_value;
+        Ok(OffPolicyReport {
+            episodes: dataset.episodes.len(),
+            transitions,
+            behavior_value,
+            importance_sampling_value,
+            weighted_importance_sampling_value,
+            effective_sample_size,
+            action_agreement: agreements as f32 / transitions as f32,
+            state_coverage,
+            estimated_improvement,
+            recommended: effective_sample_size >= self.min_effective_sample_size
+                && estimated_improvement > 0.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Eq, PartialEq, Hash, Debug)]
+    struct StepState(usize);
+
+    impl WorkflowState for StepState {
+        fn features(&self) -> Vec<f32> {
+            vec![self.0 as f32]
+        }
+
+        fn is_terminal(&self) -> bool {
+            self.0 >= 3
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq, Hash, Debug)]
+    enum Route {
+        Fast,
+        Slow,
+    }
+
+    impl WorkflowAction for Route {
+        const ACTION_COUNT: usize = 2;
+
+        fn to_index(&self) -> usize {
+            match self {
+                Route::Fast => 0,
+                Route::Slow => 1,
+            }
+        }
+
+        fn from_index(idx: usize) -> Option<Self> {
+            match idx {
+                0 => Some(Route::Fast),
+                1 => Some(Route::Slow),
+                _ => None,
+            }
+        }
+    }
+
+    struct RouteMapper;
+
+    impl TransitionMapper<StepState, Route> for RouteMapper {
+        fn state(&self, _case: &RecordedCase, step: usize) -> StepState {
+            StepState(step)
+        }
+
+        fn action(&self, event: &RecordedEvent) -> Option<Route> {
+            match event.activity.as_str() {
+                "fast" => Some(Route::Fast),
+                "slow" => Some(Route::Slow),
+                _ => None,
+            }
+        }
+    }
+
+    /// Every combination of three routing decisions, repeated
+    fn synthetic_log() -> CaseLog {
+        let cases = (0..40)
+            .map(|i| RecordedCase {
+                case_id: format!("case-{}", i),
+                events: (0..3)
+                    .map(|step| {
+                        let fast = (i >> step) & 1 == 0;
+                        RecordedEvent {
+                            activity: if fast { "fast" } else { "slow" }.to_string(),
+                            timestamp: Utc::now(),
+                            duration_ms: if fast { 50.0 } else { 400.0 },
+                            success: fast,
+                            resource_usage: 20.0,
+                            pattern_id: 1,
+                            resource: None,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        CaseLog { cases }
+    }
+
+    #[test]
+    fn test_xes_import_folds_lifecycle_pairs() {
+        let xes = r#"<?xml version="1.0" encoding="UTF-8"?>
+<log xes.version="2.0">
+  <global scope="event">
+    <string key="concept:name" value="__INVALID__"/>
+  </global>
+  <trace>
+    <string key="concept:name" value="case &amp; 1"/>
+    <event>
+      <string key="concept:name" value="approve"/>
+      <string key="lifecycle:transition" value="start"/>
+      <date key="time:timestamp" value="2024-01-01T10:00:00.000Z"/>
+    </event>
+    <event>
+      <string key="concept:name" value="approve"/>
+      <string key="lifecycle:transition" value="complete"/>
+      <date key="time:timestamp" value="2024-01-01T10:00:01.500Z"/>
+      <string key="org:resource" value="alice"/>
+      <int key="pattern:id" value="4"/>
+    </event>
+    <event>
+      <string key="concept:name" value="ship"/>
+      <string key="lifecycle:transition" value="ate_abort"/>
+      <date key="time:timestamp" value="2024-01-01T10:00:02.000Z"/>
+    </event>
+  </trace>
+</log>"#;
+
+        let log = CaseLog::from_xes_str(xes).unwrap();
+        assert_eq!(log.cases.len(), 1);
+        let case = &log.cases[0];
+        assert_eq!(case.case_id, "case & 1");
+        assert_eq!(case.events.len(), 2);
+
+        assert_eq!(case.events[0].activity, "approve");
+        assert_eq!(case.events[0].duration_ms, 1500.0);
+        assert!(case.events[0].success);
+        assert_eq!(case.events[0].pattern_id, 4);
+        assert_eq!(case.events[0].resource.as_deref(), Some("alice"));
+
+        assert_eq!(case.events[1].activity, "ship");
+        assert!(!case.events[1].success);
+    }
+
+    #[test]
+    fn test_state_event_import_marks_failed_cases() {
+        let history = r#"{"CaseCreated":{"case_id":"c1","spec_id":"s1","timestamp":"2024-01-01T10:00:00Z"}}
+{"TaskCompleted":{"case_id":"c1","task_id":"t1","task_name":"review","duration_ms":120,"timestamp":"2024-01-01T10:00:01Z"}}
+{"CaseStateChanged":{"case_id":"c1","old_state":"Running","new_state":"Failed","timestamp":"2024-01-01T10:00:02Z"}}
+{"CaseCreated":{"case_id":"c2","spec_id":"s1","timestamp":"2024-01-01T10:00:00Z"}}"#;
+
+        let log = CaseLog::from_state_events(history).unwrap();
+        assert_eq!(log.cases.len(), 1);
+        assert_eq!(log.event_count(), 1);
+        assert_eq!(log.cases[0].events[0].duration_ms, 120.0);
+        assert!(!log.cases[0].events[0].success);
+    }
+
+    #[test]
+    fn test_offline_q_learning_beats_logged_policy() {
+        let dataset = OfflineDataset::from_log(&synthetic_log(), &RouteMapper);
+        assert_eq!(dataset.transition_count(), 120);
+
+        let agent: QLearning<StepState, Route> = QLearning::new();
+        let summary = OfflineTrainer::default()
+            .train_q_learning(&agent, &dataset)
+            .unwrap();
+        assert!(summary.final_q_change() < summary.q_change_history[0]);
+        assert_eq!(QFunction::greedy_action(&agent, &StepState(0)), Route::Fast);
+
+        let evaluator = OffPolicyEvaluator {
+            min_effective_sample_size: 3.0,
+            ..OffPolicyEvaluator::default()
+        };
+        let report = evaluator.evaluate(&agent, &dataset).unwrap();
+        assert!(report.weighted_importance_sampling_value > report.behavior_value);
+        assert!(report.effective_sample_size >= 3.0);
+        assert_eq!(report.state_coverage, 1.0);
+        assert!(report.recommended, "{}", report);
+    }
+
+    struct AlwaysFast;
+
+    impl QFunction<StepState, Route> for AlwaysFast {
+        fn q_values(&self, _state: &StepState) -> Vec<f32> {
+            vec![1.0, 0.0]
+        }
+    }
+
+    #[test]
+    fn test_state_coverage_counts_distinct_states() {
+        let event = |activity: &str| RecordedEvent {
+            activity: activity.to_string(),
+            timestamp: Utc::now(),
+            duration_ms: 50.0,
+            success: true,
+            resource_usage: 20.0,
+            pattern_id: 1,
+            resource: None,
+        };
+        // State 0 is logged five times with the greedy action, state 1 once without it
+        let mut cases: Vec<RecordedCase> = (0..4)
+            .map(|i| RecordedCase {
+                case_id: format!("case-{}", i),
+                events: vec![event("fast")],
+            })
+            .collect();
+        cases.push(RecordedCase {
+            case_id: "case-4".to_string(),
+            events: vec![event("fast"), event("slow")],
+        });
+        let dataset = OfflineDataset::from_log(&CaseLog { cases }, &RouteMapper);
+
+        let report = OffPolicyEvaluator::default()
+            .evaluate(&AlwaysFast, &dataset)
+            .unwrap();
+        assert_eq!(report.transitions, 6);
+        assert_eq!(report.state_coverage, 0.5);
+    }
+
+    #[test]
+    fn test_offline_sarsa_and_empty_dataset() {
+        let dataset = OfflineDataset::from_log(&synthetic_log(), &RouteMapper);
+        let agent: SARSAAgent<StepState, Route> = SARSAAgent::new();
+        OfflineTrainer::default()
+            .train_sarsa(&agent, &dataset)
+            .unwrap();
+        assert!(agent.get_q_value(&StepState(2), &Route::Fast) > 0.0);
+        assert!(agent.get_q_value(&StepState(2), &Route::Slow) < 0.0);
+
+        let empty: OfflineDataset<StepState, Route> =
+            OfflineDataset::from_log(&CaseLog::default(), &RouteMapper);
+        assert!(matches!(
+            OfflineTrainer::default().train_q_learning(&QLearning::new(), &empty),
+            Err(OfflineError::EmptyDataset)
+        ));
+    }
+}
@@ -198,7 +198,7 @@ impl SGDConfig {
 
     /// Set momentum with validation (must be in [0, 1])
     pub fn with_momentum(mut self, momentum: f32) -> Result<Self, String> {
-        if momentum < 0.0 || momentum > 1.0 || !momentum.is_finite() {
+        if !(0.0..=1.0).contains(&momentum) || !momentum.is_finite() {
             return Err("Momentum must be in [0, 1] and finite".to_string());
         }
         self.momentum = momentum;
@@ -270,7 +270,7 @@ impl SGDOptimizer {
 
     /// Set momentum coefficient (default 0.9)
     pub fn with_momentum(mut self, momentum: f32) -> Result<Self, String> {
-        if momentum < 0.0 || momentum > 1.0 || !momentum.is_finite() {
+        if !(0.0..=1.0).contains(&momentum) || !momentum.is_finite() {
             return Err("Momentum must be in [0, 1] and finite".to_string());
         }
         self.momentum = momentum;
@@ -428,7 +428,7 @@ impl AdamConfig {
 
     /// Set beta1 with validation (must be in [0, 1))
     pub fn with_beta1(mut self, beta1: f32) -> Result<Self, String> {
-        if beta1 < 0.0 || beta1 >= 1.0 || !beta1.is_finite() {
+        if !(0.0..1.0).contains(&beta1) || !beta1.is_finite() {
             return Err("Beta1 must be in [0, 1) and finite".to_string());
         }
         self.beta1 = beta1;
@@ -437,7 +437,7 @@ impl AdamConfig {
 
     /// Set beta2 with validation (must be in [0, 1))
     pub fn with_beta2(mut self, beta2: f32) -> Result<Self, String> {
-        if beta2 < 0.0 || beta2 >= 1.0 || !beta2.is_finite() {
+        if !(0.0..1.0).contains(&beta2) || !beta2.is_finite() {
             return Err("Beta2 must be in [0, 1) and finite".to_string());
         }
         self.beta2 = beta2;
@@ -523,7 +523,7 @@ impl AdamOptimizer {
 
     /// Set beta1 for 1st moment decay (default 0.9)
     pub fn with_beta1(mut self, beta1: f32) -> Result<Self, String> {
-        if beta1 < 0.0 || beta1 >= 1.0 || !beta1.is_finite() {
+        if !(0.0..1.0).contains(&beta1) || !beta1.is_finite() {
             return Err("Beta1 must be in [0, 1) and finite".to_string());
         }
         self.beta1 = beta1;
@@ -532,7 +532,7 @@ impl AdamOptimizer {
 
     /// Set beta2 for 2nd moment decay (default 0.999)
     pub fn with_beta2(mut self, beta2: f32) -> Result<Self, String> {
-        if beta2 < 0.0 || beta2 >= 1.0 || !beta2.is_finite() {
+        if !(0.0..1.0).contains(&beta2) || !beta2.is_finite() {
             return Err("Beta2 must be in [0, 1) and finite".to_string());
         }
         self.beta2 = beta2;
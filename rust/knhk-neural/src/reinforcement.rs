@@ -5,6 +5,8 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::sync::{Arc, RwLock};
 
+use crate::checkpoint::{CheckpointError, QLearningCheckpoint};
+
 /// State for reinforcement learning (must be hashable and cloneable)
 pub trait WorkflowState: Clone + Eq + Hash + Send + Sync {
     /// State features for function approximation
@@ -145,6 +147,20 @@ impl<S: WorkflowState, A: WorkflowAction> QLearning<S, A> {
             .unwrap_or(0.0)
     }
 
+    /// Q(s, ·) for every action (zeros for unseen states)
+    pub fn q_values(&self, state: &S) -> Vec<f32> {
+        let q_table = self.q_table.read().unwrap();
+        q_table
+            .get(state)
+            .cloned()
+            .unwrap_or_else(|| vec![0.0; A::ACTION_COUNT])
+    }
+
+    /// Number of states with learned Q-values
+    pub fn state_count(&self) -> usize {
+        self.q_table.read().unwrap().len()
+    }
+
     pub fn episode_count(&self) -> usize {
         *self.episodes.read().unwrap()
     }
@@ -156,6 +172,55 @@ impl<S: WorkflowState, A: WorkflowAction> QLearning<S, A> {
     pub fn get_exploration_rate(&self) -> f32 {
         self.exploration_rate
     }
+
+    /// Snapshot Q-table, hyperparameters and statistics
+    pub fn checkpoint(&self) -> QLearningCheckpoint<S> {
+        QLearningCheckpoint {
+            q_table: self
+                .q_table
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(s, q)| (s.clone(), q.clone()))
+                .collect(),
+            action_count: A::ACTION_COUNT,
+            learning_rate: self.learning_rate,
+            discount_factor: self.discount_factor,
+            exploration_rate: self.exploration_rate,
+            exploration_decay: self.exploration_decay,
+            episodes: *self.episodes.read().unwrap(),
+            total_reward: *self.total_reward.read().unwrap(),
+        }
+    }
+
+    /// Replace Q-table, hyperparameters and statistics in place
+    ///
+    /// Clones of this agent share the Q-table, so they observe the restore too.
+    pub fn load_checkpoint(
+        &mut self,
+        checkpoint: &QLearningCheckpoint<S>,
+    ) -> Result<(), CheckpointError> {
+        if checkpoint.action_count != A::ACTION_COUNT
+            || checkpoint
+                .q_table
+                .iter()
+                .any(|(_, q)| q.len() != A::ACTION_COUNT)
+        {
+            return Err(CheckpointError::ShapeMismatch {
+                expected: (checkpoint.q_table.len(), A::ACTION_COUNT),
+                found: (checkpoint.q_table.len(), checkpoint.action_count),
+            });
+        }
+
+        *self.q_table.write().unwrap() = checkpoint.q_table.iter().cloned().collect();
+        self.learning_rate = checkpoint.learning_rate;
+        self.discount_factor = checkpoint.discount_factor;
+        self.exploration_rate = checkpoint.exploration_rate;
+        self.exploration_decay = checkpoint.exploration_decay;
+        *self.episodes.write().unwrap() = checkpoint.episodes;
+        *self.total_reward.write().unwrap() = checkpoint.total_reward;
+        Ok(())
+    }
 }
 
 impl<S: WorkflowState, A: WorkflowAction> Default for QLearning<S, A> {
@@ -208,6 +273,38 @@ impl<S: WorkflowState, A: WorkflowAction> SARSAAgent<S, A> {
         q_table.get_mut(state).unwrap()[action_idx] += self.learning_rate * (target - current_q);
     }
 
+    /// SARSA update for the last step of an episode: Q(s,a) ← Q(s,a) + α[r - Q(s,a)]
+    pub fn update_terminal(&self, state: &S, action: &A, reward: f32) {
+        let mut q_table = self.q_table.write().unwrap();
+        let q_vals = q_table
+            .entry(state.clone())
+            .or_insert_with(|| vec![0.0; A::ACTION_COUNT]);
+
+        let action_idx = action.to_index();
+        q_vals[action_idx] += self.learning_rate * (reward - q_vals[action_idx]);
+    }
+
+    pub fn get_q_value(&self, state: &S, action: &A) -> f32 {
+        let q_table = self.q_table.read().unwrap();
+        q_table
+            .get(state)
+            .map(|q_vals| q_vals[action.to_index()])
+            .unwrap_or(0.0)
+    }
+
+    /// Q(s, ·) for every action (zeros for unseen states)
+    pub fn q_values(&self, state: &S) -> Vec<f32> {
+        let q_table = self.q_table.read().unwrap();
+        q_table
+            .get(state)
+            .cloned()
+            .unwrap_or_else(|| vec![0.0; A::ACTION_COUNT])
+    }
+
+    pub fn get_exploration_rate(&self) -> f32 {
+        self.exploration_rate
+    }
+
     pub fn epsilon_greedy_action(&self, state: &S, epsilon: f32) -> A {
         if rand::random::<f32>() < epsilon {
             let idx = rand::random::<usize>() % A::ACTION_COUNT;
@@ -247,6 +344,36 @@ pub trait Agent<S: WorkflowState, A: WorkflowAction> {
     fn update(&self, state: &S, action: &A, reward: f32, next_state: &S, done: bool);
 }
 
+/// Tabular action-value function, used to derive greedy policies
+pub trait QFunction<S: WorkflowState, A: WorkflowAction> {
+    /// Q(s, ·) indexed by `WorkflowAction::to_index`
+    fn q_values(&self, state: &S) -> Vec<f32>;
+
+    /// Action with the highest Q-value (ties resolve to the lowest index)
+    fn greedy_action(&self, state: &S) -> A {
+        let q_vals = self.q_values(state);
+        let mut best_idx = 0;
+        for (idx, q) in q_vals.iter().enumerate() {
+            if *q > q_vals[best_idx] {
+                best_idx = idx;
+            }
+        }
+        A::from_index(best_idx).unwrap()
+    }
+}
+
+impl<S: WorkflowState, A: WorkflowAction> QFunction<S, A> for QLearning<S, A> {
+    fn q_values(&self, state: &S) -> Vec<f32> {
+        QLearning::q_values(self, state)
+    }
+}
+
+impl<S: WorkflowState, A: WorkflowAction> QFunction<S, A> for SARSAAgent<S, A> {
+    fn q_values(&self, state: &S) -> Vec<f32> {
+        SARSAAgent::q_values(self, state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,4 +444,28 @@ mod tests {
         agent.update(&s1, &a1, 1.0, &s2, &a2);
         // Should complete without error
     }
+
+    #[test]
+    fn test_q_learning_checkpoint_restore() {
+        let agent: QLearning<SimpleState, SimpleAction> =
+            QLearning::with_hyperparams(0.5, 0.9, 0.2);
+        agent.update(
+            &SimpleState(0),
+            &SimpleAction::Double,
+            2.0,
+            &SimpleState(1),
+            false,
+        );
+        let checkpoint = agent.checkpoint();
+
+        let mut restored: QLearning<SimpleState, SimpleAction> = QLearning::new();
+        restored.load_checkpoint(&checkpoint).unwrap();
+
+        assert_eq!(
+            restored.get_q_value(&SimpleState(0), &SimpleAction::Double),
+            agent.get_q_value(&SimpleState(0), &SimpleAction::Double)
+        );
+        assert_eq!(restored.get_exploration_rate(), 0.2);
+        assert_eq!(restored.total_reward(), 2.0);
+    }
 }
@@ -9,10 +9,11 @@
 // - Covenant 6: Full observability through telemetry
 
 use ndarray::Array1;
+use rand::seq::SliceRandom;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Training configuration with learning rate, batch size, and convergence parameters
@@ -265,7 +266,7 @@ impl DataLoader {
         assert!(!samples.is_empty(), "Dataset cannot be empty");
         assert!(batch_size > 0, "Batch size must be > 0");
 
-        let num_batches = (samples.len() + batch_size - 1) / batch_size;
+        let num_batches = samples.len().div_ceil(batch_size);
 
         Self {
             samples,
@@ -305,7 +306,11 @@ impl DataLoader {
 
     /// Iterate over batches (with optional shuffling)
     pub fn iter(&self) -> impl Iterator<Item = MiniBatch> + '_ {
-        (0..self.num_batches).map(move |i| self.get_batch(i).unwrap())
+        let mut order: Vec<usize> = (0..self.num_batches).collect();
+        if self.shuffle {
+            order.shuffle(&mut rand::thread_rng());
+        }
+        order.into_iter().map(move |i| self.get_batch(i).unwrap())
     }
 
     /// Parallel iteration over batches using Rayon
@@ -383,7 +388,8 @@ pub struct TrainingHistory {
     /// Training duration per epoch (ms)
     pub epoch_durations: Vec<u128>,
 
-    /// Best validation loss achieved
+    /// Best validation loss achieved (JSON has no infinity, so "none yet" is `null`)
+    #[serde(deserialize_with = "infinite_if_null")]
     pub best_val_loss: f32,
 
     /// Epoch of best validation loss
@@ -399,6 +405,13 @@ pub struct TrainingHistory {
     pub stopping_reason: String,
 }
 
+fn infinite_if_null<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<f32>::deserialize(deserializer)?.unwrap_or(f32::INFINITY))
+}
+
 impl TrainingHistory {
     /// Create new training history
     pub fn new() -> Self {
@@ -437,6 +450,7 @@ impl Default for TrainingHistory {
 
 /// Trainer for neural network with forward/backward operations
 /// Generic over the forward/backward implementation detail
+#[allow(dead_code)] // Closure-based trainer is not wired up yet; SimpleTrainer is used today
 pub struct Trainer<F>
 where
     F: Fn(&Array1<f32>) -> Array1<f32> + Send + Sync,
@@ -826,8 +840,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_simple_trainer_train_epoch() {
-        let mut config = TrainingConfig::default();
-        config.batch_size = 16;
+        let config = TrainingConfig {
+            batch_size: 16,
+            ..TrainingConfig::default()
+        };
 
         let mut trainer = SimpleTrainer::new(config);
         let train_data = create_synthetic_data(64, 5, 5);
@@ -950,11 +966,13 @@ mod tests {
 
     #[test]
     fn test_gradient_clipping() {
-        let mut config = TrainingConfig::default();
-        config.gradient_clip_norm = Some(1.0);
+        let config = TrainingConfig {
+            gradient_clip_norm: Some(1.0),
+            ..TrainingConfig::default()
+        };
 
         let trainer = SimpleTrainer::new(config);
-        let mut gradients = vec![0.5, 0.5, 0.5];
+        let mut gradients = vec![1.0, 1.0, 1.0];
 
         trainer.clip_gradients(&mut gradients);
 
@@ -974,12 +992,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_simple_trainer_scheduled_lr() {
-        let mut config = TrainingConfig::default();
-        config.batch_size = 16;
-        config.lr_schedule = Some(LRScheduleConfig::StepDecay {
-            decay_rate: 0.5,
-            step_size: 1,
-        });
+        let config = TrainingConfig {
+            batch_size: 16,
+            lr_schedule: Some(LRScheduleConfig::StepDecay {
+                decay_rate: 0.5,
+                step_size: 1,
+            }),
+            ..TrainingConfig::default()
+        };
 
         let trainer = SimpleTrainer::new(config);
         let lr_0 = trainer.get_scheduled_learning_rate();
@@ -8,6 +8,7 @@
 // - Platform Integration: Phase 5 autonomic components with neural optimization
 
 use chrono::{DateTime, Utc};
+use ndarray::Array1;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex, RwLock};
@@ -15,8 +16,11 @@ use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use uuid::Uuid;
 
-use crate::model::DenseLayer;
+use crate::checkpoint::{CheckpointError, CheckpointMetrics, ModelCheckpoint, ModelRegistry};
+use crate::model::{DenseLayer, Layer};
+use crate::optimizer::{Optimizer, SGDConfig, SGDOptimizer};
 use crate::reinforcement::{QLearning, WorkflowAction, WorkflowState};
+use serde::de::DeserializeOwned;
 
 /// Workflow execution metrics for MAPE-K feedback loops
 /// Tracks performance of individual workflow executions for adaptive optimization
@@ -107,22 +111,30 @@ pub struct LearningMetrics {
 pub struct Trainer<L: Clone + Send + Sync> {
     pub learning_rate: f32,
     batch_size: usize,
-    momentum: f32,
-    weight_decay: f32,
-    _phantom: std::marker::PhantomData<L>,
+    /// Model being trained
+    model: L,
+    /// SGD with momentum and weight decay
+    optimizer: SGDOptimizer,
 }
 
-impl<L: Clone + Send + Sync> Trainer<L> {
+impl<L: Clone + Send + Sync + Default> Trainer<L> {
     pub fn new() -> Self {
+        let learning_rate = 0.001;
         Self {
-            learning_rate: 0.001,
+            learning_rate,
             batch_size: 32,
-            momentum: 0.9,
-            weight_decay: 0.0001,
-            _phantom: std::marker::PhantomData,
+            model: L::default(),
+            optimizer: SGDOptimizer::from_config(SGDConfig {
+                learning_rate,
+                momentum: 0.9,
+                nesterov: false,
+                weight_decay: 0.0001,
+            }),
         }
     }
+}
 
+impl<L: Clone + Send + Sync> Trainer<L> {
     pub fn with_learning_rate(mut self, lr: f32) -> Self {
         self.learning_rate = lr;
         self
@@ -147,6 +159,7 @@ impl<L: Clone + Send + Sync> Trainer<L> {
     /// Decay learning rate
     pub fn decay_learning_rate(&mut self, decay_factor: f32) {
         self.learning_rate *= decay_factor;
+        self.optimizer.update_learning_rate(self.learning_rate);
     }
 
     pub fn learning_rate(&self) -> f32 {
@@ -156,14 +169,48 @@ impl<L: Clone + Send + Sync> Trainer<L> {
     pub fn batch_size(&self) -> usize {
         self.batch_size
     }
+
+    pub fn momentum(&self) -> f32 {
+        self.optimizer.get_momentum()
+    }
+
+    pub fn weight_decay(&self) -> f32 {
+        self.optimizer.get_weight_decay()
+    }
+
+    pub fn model(&self) -> &L {
+        &self.model
+    }
+
+    pub fn model_mut(&mut self) -> &mut L {
+        &mut self.model
+    }
+
+    pub fn optimizer(&self) -> &SGDOptimizer {
+        &self.optimizer
+    }
+
+    pub fn optimizer_mut(&mut self) -> &mut SGDOptimizer {
+        &mut self.optimizer
+    }
 }
 
-impl<L: Clone + Send + Sync> Default for Trainer<L> {
+impl<const IN: usize, const OUT: usize> Trainer<DenseLayer<IN, OUT>> {
+    /// Forward pass through the trained layer
+    pub fn predict(&self, input: &Array1<f32>) -> Array1<f32> {
+        Layer::forward(&self.model, input)
+    }
+}
+
+impl<L: Clone + Send + Sync + Default> Default for Trainer<L> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Reward calculation function: (state, action, next_state) -> reward
+type RewardFn<S, A> = dyn Fn(&S, &A, &S) -> f32 + Send + Sync;
+
 /// Self-learning workflow with ε-greedy exploration and Q-learning
 ///
 /// Combines:
@@ -182,7 +229,7 @@ pub struct SelfLearningWorkflow<S: WorkflowState, A: WorkflowAction> {
     state_observer: Arc<dyn Fn() -> S + Send + Sync>,
 
     /// Reward calculation function: (state, action, next_state) -> reward
-    reward_calculator: Arc<dyn Fn(&S, &A, &S) -> f32 + Send + Sync>,
+    reward_calculator: Arc<RewardFn<S, A>>,
 
     /// Performance history for convergence tracking
     reward_history: Arc<RwLock<VecDeque<f32>>>,
@@ -359,6 +406,17 @@ impl PerformanceTracker {
             .collect()
     }
 
+    /// Number of metrics currently held in history
+    pub fn history_len(&self) -> usize {
+        self.metrics_history.lock().unwrap().len()
+    }
+
+    /// Metrics recorded at or after history position `start`
+    pub fn metrics_since(&self, start: usize) -> Vec<WorkflowMetrics> {
+        let history = self.metrics_history.lock().unwrap();
+        history.iter().skip(start).cloned().collect()
+    }
+
     /// Clear history
     pub fn clear(&self) {
         self.metrics_history.lock().unwrap().clear();
@@ -387,6 +445,12 @@ pub struct WorkflowExecutor {
     mape_k_cycles: Arc<RwLock<u64>>,
 }
 
+impl Default for WorkflowExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WorkflowExecutor {
     /// Create new workflow executor
     pub fn new() -> Self {
@@ -469,9 +533,12 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
         FState: Fn() -> S + Send + Sync + 'static,
         FReward: Fn(&S, &A, &S) -> f32 + Send + Sync + 'static,
     {
+        let mut trainer = Trainer::<DenseLayer<10, 5>>::new();
+        trainer.model_mut().initialize_xavier();
+
         Self {
             agent: Arc::new(RwLock::new(QLearning::new())),
-            trainer: Arc::new(RwLock::new(Trainer::new())),
+            trainer: Arc::new(RwLock::new(trainer)),
             state_observer: Arc::new(state_fn),
             reward_calculator: Arc::new(reward_fn),
             reward_history: Arc::new(RwLock::new(VecDeque::with_capacity(config.history_window))),
@@ -489,16 +556,14 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
     ///
     /// Integrates with Phase 5 platform to select patterns based on learned Q-values
     /// and records metrics for continuous improvement.
-    pub async fn execute_adaptive(&self, workflow_def: &str) -> (WorkflowMetrics, f32) {
+    pub async fn execute_adaptive(&self, _workflow_def: &str) -> (WorkflowMetrics, f32) {
         let start = Instant::now();
 
         // Observe current state
         let state = (self.state_observer)();
 
         // Select action using learned Q-values (greedy with some exploration)
-        let agent = self.agent.read().unwrap();
-        let action = agent.select_action(&state);
-        drop(agent);
+        let action = self.agent.read().unwrap().select_action(&state);
 
         // Get pattern from action
         let pattern_id = (action.to_index() % 256) as u8;
@@ -523,15 +588,13 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
         let next_state = (self.state_observer)();
         let reward = (self.reward_calculator)(&state, &action, &next_state);
 
-        let agent = self.agent.read().unwrap();
-        agent.update(
+        self.agent.read().unwrap().update(
             &state,
             &action,
             reward,
             &next_state,
             next_state.is_terminal(),
         );
-        drop(agent);
 
         // Record in execution history
         let mut history = self.execution_history.lock().unwrap();
@@ -554,21 +617,16 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
     /// Computes improvement metrics and retrains Q-Learning weights
     /// Returns improvement percentage
     pub async fn update_model(&mut self) -> f32 {
-        let start = Instant::now();
-
-        // Get execution history
-        let history = self.execution_history.lock().unwrap();
-        if history.len() < 2 {
-            return 0.0;
-        }
-
-        // Calculate improvement from first to last
-        let first = &history[0];
-        let last = &history[history.len() - 1];
-
-        let improvement_ratio = (first.duration_ms - last.duration_ms) / first.duration_ms.max(1.0);
-
-        drop(history);
+        // Calculate improvement from first to last execution
+        let improvement_ratio = {
+            let history = self.execution_history.lock().unwrap();
+            if history.len() < 2 {
+                return 0.0;
+            }
+            let first = &history[0];
+            let last = &history[history.len() - 1];
+            (first.duration_ms - last.duration_ms) / first.duration_ms.max(1.0)
+        };
 
         // Perform training
         self.perform_training().await;
@@ -631,7 +689,6 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
         let mut total_reward = 0.0;
         let mut steps = 0;
         let mut is_terminal = false;
-        let mut loss = 0.0;
 
         // Get initial state
         let mut state = (self.state_observer)();
@@ -639,9 +696,7 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
         // Execute episode steps
         while !state.is_terminal() && steps < self.config.max_steps {
             // Select action using ε-greedy
-            let agent = self.agent.read().unwrap();
-            let action = agent.select_action(&state);
-            drop(agent);
+            let action = self.agent.read().unwrap().select_action(&state);
 
             // Simulate action execution - get next state
             // In real systems, this would call actual execution
@@ -653,9 +708,10 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
 
             // Update Q-values
             let is_done = next_state.is_terminal();
-            let agent = self.agent.read().unwrap();
-            agent.update(&state, &action, reward, &next_state, is_done);
-            drop(agent);
+            self.agent
+                .read()
+                .unwrap()
+                .update(&state, &action, reward, &next_state, is_done);
 
             // Progress episode
             state = next_state;
@@ -677,7 +733,7 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
         drop(reward_hist);
 
         // Calculate convergence
-        loss = self.calculate_convergence(); // Loss inversely correlates with convergence
+        let loss = self.calculate_convergence(); // Loss inversely correlates with convergence
 
         let duration = start.elapsed().as_millis();
 
@@ -763,7 +819,7 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
         drop(loss_hist);
 
         // Decay learning rate
-        if *self.episode_count.read().unwrap() % 100 == 0 {
+        if self.episode_count.read().unwrap().is_multiple_of(100) {
             trainer.decay_learning_rate(self.config.lr_decay);
         }
 
@@ -884,6 +940,118 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> SelfLearningWorkfl
     pub fn model_versions(&self) -> Vec<ModelVersion> {
         self.model_versions.read().unwrap().clone()
     }
+
+    /// Build a full checkpoint (layer weights, optimizer state, Q-table,
+    /// trainer hyperparameters, metrics)
+    pub fn create_checkpoint(&self, version: ModelVersion) -> ModelCheckpoint<S> {
+        let metrics = self.get_learning_metrics();
+        let tracker = self.executor.performance_tracker();
+        let trainer = self.trainer.read().unwrap();
+        let q_learning = self.agent.read().unwrap().checkpoint();
+
+        ModelCheckpoint::new(version)
+            .with_layers(vec![trainer.model().to_checkpoint()])
+            .with_optimizer(trainer.optimizer().state_dict())
+            .with_q_learning(q_learning)
+            .with_hyperparameter("learning_rate", trainer.learning_rate())
+            .with_hyperparameter("batch_size", trainer.batch_size() as f32)
+            .with_hyperparameter("momentum", trainer.momentum())
+            .with_hyperparameter("weight_decay", trainer.weight_decay())
+            .with_hyperparameter("lr_decay", self.config.lr_decay)
+            .with_metrics(CheckpointMetrics {
+                avg_reward: metrics.avg_reward,
+                loss: metrics.loss_trend,
+                convergence: self.calculate_convergence(),
+                success_rate: tracker.success_rate(),
+                avg_duration_ms: tracker.avg_duration_ms(),
+                total_episodes: metrics.total_episodes,
+            })
+    }
+
+    /// Restore agent and trainer state from a checkpoint
+    ///
+    /// Sections absent from the checkpoint (no layers, no optimizer) leave the
+    /// current state untouched.
+    pub fn restore_checkpoint(
+        &self,
+        checkpoint: &ModelCheckpoint<S>,
+    ) -> Result<(), CheckpointError> {
+        let mut trainer = self.trainer.write().unwrap();
+        match checkpoint.layers.as_slice() {
+            [] => {}
+            [layer] => trainer.model_mut().load_checkpoint(layer)?,
+            layers => {
+                return Err(CheckpointError::Serialization(format!(
+                    "expected 1 layer, found {}",
+                    layers.len()
+                )))
+            }
+        }
+        if let Some(q_learning) = &checkpoint.q_learning {
+            self.agent.write().unwrap().load_checkpoint(q_learning)?;
+        }
+        if let Some(optimizer) = &checkpoint.optimizer {
+            trainer.optimizer_mut().load_state_dict(optimizer);
+        }
+        if let Some(lr) = checkpoint.hyperparameters.get("learning_rate") {
+            trainer.learning_rate = *lr;
+            trainer.optimizer_mut().update_learning_rate(*lr);
+        }
+        Ok(())
+    }
+}
+
+/// Thresholds for detecting a regression after a model hot-swap
+#[derive(Debug, Clone, Copy)]
+pub struct RegressionPolicy {
+    /// Executions required after the swap before judging it
+    pub min_samples: usize,
+    /// Maximum tolerated relative drop in average reward (0.1 = 10%)
+    pub max_reward_drop: f32,
+    /// Maximum tolerated drop in success rate (percentage points)
+    pub max_success_rate_drop: f32,
+}
+
+impl Default for RegressionPolicy {
+    fn default() -> Self {
+        Self {
+            min_samples: 20,
+            max_reward_drop: 0.1,
+            max_success_rate_drop: 5.0,
+        }
+    }
+}
+
+/// Evidence that a hot-swapped model performs worse than its predecessor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionReport {
+    pub from_version: Option<usize>,
+    pub to_version: usize,
+    pub baseline_reward: f32,
+    pub observed_reward: f32,
+    pub baseline_success_rate: f32,
+    pub observed_success_rate: f32,
+    pub samples: usize,
+}
+
+/// Rollback point captured when a new model version is swapped in
+struct SwapState<S> {
+    previous: ModelCheckpoint<S>,
+    previous_version: Option<usize>,
+    baseline_reward: f32,
+    baseline_success_rate: f32,
+    history_offset: usize,
+}
+
+/// Mean reward and success rate (0-100) over a metrics window
+fn summarize(metrics: &[WorkflowMetrics]) -> (f32, f32) {
+    if metrics.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = metrics.len() as f32;
+    let reward = metrics.iter().map(|m| m.to_reward()).sum::<f32>() / n;
+    let success = metrics.iter().filter(|m| m.success).count() as f32 / n * 100.0;
+    (reward, success)
 }
 
 /// Adaptive executor that monitors performance and triggers retraining
@@ -902,6 +1070,12 @@ pub struct AdaptiveWorkflowExecutor<S: WorkflowState + 'static, A: WorkflowActio
 
     /// Last retraining episode
     last_retraining: Arc<RwLock<usize>>,
+
+    /// Model version currently serving
+    active_version: Arc<RwLock<Option<ModelVersion>>>,
+
+    /// Rollback point from the most recent hot-swap
+    swap_state: Arc<RwLock<Option<SwapState<S>>>>,
 }
 
 impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> AdaptiveWorkflowExecutor<S, A> {
@@ -913,7 +1087,135 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> AdaptiveWorkflowEx
             retraining_threshold,
             min_episodes_retraining: 50,
             last_retraining: Arc::new(RwLock::new(0)),
+            active_version: Arc::new(RwLock::new(None)),
+            swap_state: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Model version currently serving, if one was swapped in
+    pub fn active_version(&self) -> Option<ModelVersion> {
+        self.active_version.read().unwrap().clone()
+    }
+
+    /// Hot-swap to a new model while keeping the current one as rollback point
+    ///
+    /// The performance baseline is the tracker history recorded before the swap;
+    /// executions recorded afterwards are attributed to the new version.
+    pub fn hot_swap(&self, checkpoint: &ModelCheckpoint<S>) -> Result<(), CheckpointError> {
+        let tracker = self.workflow.executor().performance_tracker();
+        let previous_version = self.active_version();
+        let previous = self
+            .workflow
+            .create_checkpoint(previous_version.clone().unwrap_or(ModelVersion {
+                version: 0,
+                timestamp: 0,
+                avg_reward: 0.0,
+                loss: 0.0,
+                convergence: 0.0,
+            }));
+
+        self.workflow.restore_checkpoint(checkpoint)?;
+
+        let baseline = tracker.recent_metrics(self.workflow.config.history_window);
+        let (baseline_reward, baseline_success_rate) = summarize(&baseline);
+
+        *self.swap_state.write().unwrap() = Some(SwapState {
+            previous,
+            previous_version: previous_version.map(|v| v.version),
+            baseline_reward,
+            baseline_success_rate,
+            history_offset: tracker.history_len(),
+        });
+        *self.active_version.write().unwrap() = Some(checkpoint.version.clone());
+
+        println!(
+            "[Adaptive] Hot-swapped to model version {}",
+            checkpoint.version.version
+        );
+        Ok(())
+    }
+
+    /// Load a version from the registry, hot-swap to it and mark it active
+    pub fn hot_swap_from_registry(
+        &self,
+        registry: &ModelRegistry,
+        version: usize,
+    ) -> Result<(), CheckpointError>
+    where
+        S: Serialize + DeserializeOwned,
+    {
+        let checkpoint = registry.load::<S>(version)?;
+        self.hot_swap(&checkpoint)?;
+        registry.set_active(version)
+    }
+
+    /// Compare post-swap performance with the pre-swap baseline
+    pub fn detect_regression(&self, policy: &RegressionPolicy) -> Option<RegressionReport> {
+        let swap = self.swap_state.read().unwrap();
+        let swap = swap.as_ref()?;
+        let to_version = self.active_version()?.version;
+
+        let tracker = self.workflow.executor().performance_tracker();
+        let observed = tracker.metrics_since(swap.history_offset);
+        if observed.len() < policy.min_samples || swap.history_offset == 0 {
+            // Not enough evidence, or no baseline to compare against
+            return None;
+        }
+
+        let (observed_reward, observed_success_rate) = summarize(&observed);
+        let reward_floor =
+            swap.baseline_reward - swap.baseline_reward.abs() * policy.max_reward_drop;
+        let regressed = observed_reward < reward_floor
+            || observed_success_rate < swap.baseline_success_rate - policy.max_success_rate_drop;
+
+        regressed.then_some(RegressionReport {
+            from_version: swap.previous_version,
+            to_version,
+            baseline_reward: swap.baseline_reward,
+            observed_reward,
+            baseline_success_rate: swap.baseline_success_rate,
+            observed_success_rate,
+            samples: observed.len(),
+        })
+    }
+
+    /// Restore the model that was active before the last hot-swap
+    pub fn rollback(&self) -> Result<Option<usize>, CheckpointError> {
+        let swap = self
+            .swap_state
+            .write()
+            .unwrap()
+            .take()
+            .ok_or(CheckpointError::NoRollbackPoint)?;
+
+        self.workflow.restore_checkpoint(&swap.previous)?;
+        *self.active_version.write().unwrap() =
+            swap.previous_version.map(|_| swap.previous.version.clone());
+
+        println!(
+            "[Adaptive] Rolled back to model version {:?}",
+            swap.previous_version
+        );
+        Ok(swap.previous_version)
+    }
+
+    /// Roll back automatically when the tracker shows a regression
+    ///
+    /// When a registry is given, the restored version is marked active there too.
+    pub fn rollback_on_regression(
+        &self,
+        policy: &RegressionPolicy,
+        registry: Option<&ModelRegistry>,
+    ) -> Result<Option<RegressionReport>, CheckpointError> {
+        let Some(report) = self.detect_regression(policy) else {
+            return Ok(None);
+        };
+
+        let restored = self.rollback()?;
+        if let (Some(registry), Some(version)) = (registry, restored) {
+            registry.set_active(version)?;
         }
+        Ok(Some(report))
     }
 
     /// Run adaptive execution loop with retraining
@@ -923,12 +1225,13 @@ impl<S: WorkflowState + 'static, A: WorkflowAction + 'static> AdaptiveWorkflowEx
             let result = self.workflow.execute_episode().await;
 
             // Track performance
-            let mut perf_hist = self.performance_history.write().unwrap();
-            if perf_hist.len() >= 200 {
-                perf_hist.pop_front();
+            {
+                let mut perf_hist = self.performance_history.write().unwrap();
+                if perf_hist.len() >= 200 {
+                    perf_hist.pop_front();
+                }
+                perf_hist.push_back(result.total_reward);
             }
-            perf_hist.push_back(result.total_reward);
-            drop(perf_hist);
 
             // Check if retraining needed
             let metrics = self.workflow.get_learning_metrics();
@@ -1056,7 +1359,7 @@ mod tests {
     use super::*;
 
     /// Mock workflow state for testing
-    #[derive(Clone, Eq, PartialEq, Hash, Debug)]
+    #[derive(Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
     struct MockState {
         value: i32,
     }
@@ -1164,7 +1467,7 @@ mod tests {
 
         let convergence = workflow.calculate_convergence();
         assert!(
-            convergence >= 0.0 && convergence <= 1.0,
+            (0.0..=1.0).contains(&convergence),
             "Convergence should be in [0, 1]"
         );
     }
@@ -1187,6 +1490,62 @@ mod tests {
         assert_eq!(versions.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_checkpoint_round_trip_preserves_predictions() {
+        let new_workflow = || {
+            SelfLearningWorkflow::<MockState, MockAction>::new(
+                || MockState { value: 0 },
+                |_state: &MockState, _action: &MockAction, _next: &MockState| 1.0,
+                WorkflowConfig::default(),
+            )
+        };
+        let source = new_workflow();
+        let state = MockState { value: 3 };
+        source
+            .agent()
+            .read()
+            .unwrap()
+            .update(&state, &MockAction::Double, 5.0, &state, true);
+        {
+            let trainer = source.trainer();
+            let mut trainer = trainer.write().unwrap();
+            let mut optimizer = trainer.optimizer().state_dict();
+            optimizer.velocity = Some(vec![0.25; 4]);
+            trainer.optimizer_mut().load_state_dict(&optimizer);
+        }
+
+        let input = Array1::from_shape_fn(10, |i| i as f32 / 10.0);
+        let before = source.trainer().read().unwrap().predict(&input);
+        let checkpoint = source.create_checkpoint(ModelVersion {
+            version: 1,
+            timestamp: 0,
+            avg_reward: 0.0,
+            loss: 0.0,
+            convergence: 0.0,
+        });
+        let bytes = checkpoint.to_bytes().unwrap();
+
+        // A fresh workflow starts from different random weights
+        let restored = new_workflow();
+        assert_ne!(restored.trainer().read().unwrap().predict(&input), before);
+
+        restored
+            .restore_checkpoint(&ModelCheckpoint::from_bytes(&bytes).unwrap())
+            .unwrap();
+        let trainer = restored.trainer();
+        let trainer = trainer.read().unwrap();
+        assert_eq!(trainer.predict(&input), before);
+        assert_eq!(
+            trainer.optimizer().state_dict().velocity,
+            Some(vec![0.25; 4])
+        );
+        assert_eq!(trainer.momentum(), 0.9);
+        assert_eq!(
+            restored.agent().read().unwrap().q_values(&state),
+            source.agent().read().unwrap().q_values(&state)
+        );
+    }
+
     #[tokio::test]
     async fn test_adaptive_executor_creation() {
         let workflow = Arc::new(SelfLearningWorkflow::<MockState, MockAction>::new(
@@ -1200,6 +1559,58 @@ mod tests {
         assert_eq!(metrics.total_episodes, 0);
     }
 
+    #[tokio::test]
+    async fn test_hot_swap_rolls_back_on_regression() {
+        let workflow = Arc::new(SelfLearningWorkflow::<MockState, MockAction>::new(
+            || MockState { value: 0 },
+            |_state: &MockState, _action: &MockAction, _next: &MockState| 1.0,
+            WorkflowConfig::default(),
+        ));
+        let tracker = workflow.executor().performance_tracker();
+        let original_lr = workflow.trainer().read().unwrap().learning_rate();
+
+        for _ in 0..30 {
+            tracker.record(WorkflowMetrics::new(50.0, true, 20.0, 1));
+        }
+
+        let adaptive = AdaptiveWorkflowExecutor::new(workflow.clone(), 0.9);
+        let candidate = workflow
+            .create_checkpoint(ModelVersion {
+                version: 2,
+                timestamp: 0,
+                avg_reward: 0.0,
+                loss: 0.0,
+                convergence: 0.0,
+            })
+            .with_hyperparameter("learning_rate", 0.5);
+        adaptive.hot_swap(&candidate).unwrap();
+        assert_eq!(adaptive.active_version().unwrap().version, 2);
+        assert_eq!(workflow.trainer().read().unwrap().learning_rate(), 0.5);
+
+        let policy = RegressionPolicy::default();
+        for _ in 0..policy.min_samples {
+            tracker.record(WorkflowMetrics::new(50.0, false, 20.0, 1));
+        }
+
+        let report = adaptive
+            .rollback_on_regression(&policy, None)
+            .unwrap()
+            .expect("failing executions should be detected as regression");
+        assert_eq!(report.to_version, 2);
+        assert_eq!(report.samples, policy.min_samples);
+        assert!(report.observed_success_rate < report.baseline_success_rate);
+
+        assert!(adaptive.active_version().is_none());
+        assert_eq!(
+            workflow.trainer().read().unwrap().learning_rate(),
+            original_lr
+        );
+        assert!(matches!(
+            adaptive.rollback(),
+            Err(CheckpointError::NoRollbackPoint)
+        ));
+    }
+
     #[tokio::test]
     async fn test_trainer_learning_rate_decay() {
         let mut trainer: Trainer<DenseLayer<10, 5>> = Trainer::new();
@@ -1354,7 +1765,7 @@ mod tests {
         );
 
         let (metrics, reward) = workflow.execute_adaptive("test_workflow").await;
-        assert!(metrics.success || !metrics.success); // Valid state
+        assert!(metrics.duration_ms >= 0.0);
         assert!(reward.is_finite());
     }
 
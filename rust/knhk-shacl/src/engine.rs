@@ -0,0 +1,630 @@
+//! SHACL Core validation engine
+
+use crate::error::{ShaclError, ShaclResult};
+use crate::graph::TripleIndex;
+use crate::report::{ValidationReport, ValidationResult};
+use crate::shapes::{Constraint, Shape, ShapesGraph, SparqlConstraint, Target};
+use oxigraph::io::RdfFormat;
+use oxigraph::model::vocab::xsd;
+use oxigraph::model::{Literal, NamedNode, Term, Variable};
+use oxigraph::sparql::{QueryResults, SparqlEvaluator};
+use oxigraph::store::Store;
+use std::collections::HashSet;
+
+/// Maximum nesting of shape references (`sh:node`, `sh:and`, ...)
+const MAX_DEPTH: usize = 64;
+
+/// Validates data graphs against a parsed shapes graph
+#[derive(Debug, Clone, Default)]
+pub struct ShaclEngine {
+    shapes: ShapesGraph,
+}
+
+impl ShaclEngine {
+    /// Create an engine for a parsed shapes graph
+    pub fn new(shapes: ShapesGraph) -> Self {
+        Self { shapes }
+    }
+
+    /// Create an engine from a Turtle shapes graph
+    pub fn from_turtle(turtle: &str) -> ShaclResult<Self> {
+        Ok(Self::new(ShapesGraph::from_turtle(turtle)?))
+    }
+
+    /// Shapes graph used by this engine
+    pub fn shapes(&self) -> &ShapesGraph {
+        &self.shapes
+    }
+
+    /// Validate a Turtle data graph
+    pub fn validate_turtle(&self, turtle: &str) -> ShaclResult<ValidationReport> {
+        let store = Store::new().map_err(|e| ShaclError::Storage(e.to_string()))?;
+        store
+            .load_from_reader(RdfFormat::Turtle, turtle.as_bytes())
+            .map_err(|e| ShaclError::Parse(e.to_string()))?;
+        self.validate_store(&store)
+    }
+
+    /// Validate a JSON payload object (see [`crate::payload`])
+    pub fn validate_json(&self, payload: &serde_json::Value) -> ShaclResult<ValidationReport> {
+        self.validate_turtle(&crate::payload::json_to_turtle(payload)?)
+    }
+
+    /// Validate every graph of a store
+    pub fn validate_store(&self, store: &Store) -> ShaclResult<ValidationReport> {
+        let validation = Validation {
+            shapes: &self.shapes,
+            data: TripleIndex::from_store(store)?,
+            store,
+        };
+        let mut results = Vec::new();
+        for shape in self.shapes.iter() {
+            if shape.deactivated || shape.targets.is_empty() {
+                continue;
+            }
+            for focus in validation.focus_nodes(shape) {
+                validation.validate_shape(shape, &focus, &mut results, 0)?;
+            }
+        }
+        Ok(ValidationReport::new(results))
+    }
+}
+
+/// State of one validation run
+struct Validation<'a> {
+    shapes: &'a ShapesGraph,
+    data: TripleIndex,
+    store: &'a Store,
+}
+
+impl Validation<'_> {
+    fn focus_nodes(&self, shape: &Shape) -> Vec<Term> {
+        let mut nodes = Vec::new();
+        let mut seen = HashSet::new();
+        for target in &shape.targets {
+            let selected = match target {
+                Target::Node(node) => vec![node.clone()],
+                Target::Class(class) => self.data.instances_of(class),
+                Target::SubjectsOf(predicate) => self.data.subjects_of(predicate.as_ref()),
+                Target::ObjectsOf(predicate) => self.data.objects_of(predicate.as_ref()),
+            };
+            for node in selected {
+                if seen.insert(node.clone()) {
+                    nodes.push(node);
+                }
+            }
+        }
+        nodes
+    }
+
+    fn shape(&self, id: &Term) -> ShaclResult<&Shape> {
+        self.shapes
+            .get(id)
+            .ok_or_else(|| ShaclError::InvalidShape(format!("Unknown shape {}", id)))
+    }
+
+    /// Whether `node` conforms to the shape `id`
+    fn conforms(&self, id: &Term, node: &Term, depth: usize) -> ShaclResult<bool> {
+        let mut results = Vec::new();
+        self.validate_shape(self.shape(id)?, node, &mut results, depth)?;
+        Ok(results.is_empty())
+    }
+
+    fn validate_shape(
+        &self,
+        shape: &Shape,
+        focus: &Term,
+        results: &mut Vec<ValidationResult>,
+        depth: usize,
+    ) -> ShaclResult<()> {
+        if depth > MAX_DEPTH {
+            return Err(ShaclError::Recursion(MAX_DEPTH));
+        }
+        if shape.deactivated {
+            return Ok(());
+        }
+        let values = match &shape.path {
+            Some(path) => path.evaluate(&self.data, focus),
+            None => vec![focus.clone()],
+        };
+        for constraint in &shape.constraints {
+            let mut report = |value: Option<&Term>, default: String| {
+                results.push(ValidationResult {
+                    focus_node: focus.clone(),
+                    result_path: shape.path.clone(),
+                    value: value.cloned(),
+                    source_shape: shape.id.clone(),
+                    source_constraint_component: constraint.component(),
+                    severity: shape.severity,
+                    message: Some(shape.messages.first().cloned().unwrap_or(default)),
+                });
+            };
+            match constraint {
+                Constraint::Class(class) => {
+                    for value in &values {
+                        if !self.data.is_instance_of(value, class) {
+                            report(
+                                Some(value),
+                                format!("Value is not an instance of {}", class),
+                            );
+                        }
+                    }
+                }
+                Constraint::Datatype(datatype) => {
+                    for value in &values {
+                        if !has_datatype(value, datatype) {
+                            report(
+                                Some(value),
+                                format!("Value is not a well-formed literal of {}", datatype),
+                            );
+                        }
+                    }
+                }
+                Constraint::NodeKind(kind) => {
+                    for value in &values {
+                        if !kind.matches(value) {
+                            report(
+                                Some(value),
+                                format!("Value does not have node kind {:?}", kind),
+                            );
+                        }
+                    }
+                }
+                Constraint::MinCount(min) => {
+                    if values.len() < *min {
+                        report(None, format!("Less than {} values", min));
+                    }
+                }
+                Constraint::MaxCount(max) => {
+                    if values.len() > *max {
+                        report(None, format!("More than {} values", max));
+                    }
+                }
+                Constraint::MinLength(min) => {
+                    for value in &values {
+                        if !lexical(value).is_some_and(|text| text.chars().count() >= *min) {
+                            report(
+                                Some(value),
+                                format!("Value is shorter than {} characters", min),
+                            );
+                        }
+                    }
+                }
+                Constraint::MaxLength(max) => {
+                    for value in &values {
+                        if !lexical(value).is_some_and(|text| text.chars().count() <= *max) {
+                            report(
+                                Some(value),
+                                format!("Value is longer than {} characters", max),
+                            );
+                        }
+                    }
+                }
+                Constraint::Pattern { regex, source } => {
+                    for value in &values {
+                        if !lexical(value).is_some_and(|text| regex.is_match(text)) {
+                            report(
+                                Some(value),
+                                format!("Value does not match pattern {:?}", source),
+                            );
+                        }
+                    }
+                }
+                Constraint::In(allowed) => {
+                    for value in &values {
+                        if !allowed.contains(value) {
+                            report(Some(value), "Value is not in the allowed list".to_string());
+                        }
+                    }
+                }
+                Constraint::HasValue(expected) => {
+                    if !values.contains(expected) {
+                        report(None, format!("Missing expected value {}", expected));
+                    }
+                }
+                Constraint::Node(id) => {
+                    for value in &values {
+                        if !self.conforms(id, value, depth + 1)? {
+                            report(
+                                Some(value),
+                                format!("Value does not conform to shape {}", id),
+                            );
+                        }
+                    }
+                }
+                Constraint::Property(id) => {
+                    let property = self.shape(id)?;
+                    for value in &values {
+                        self.validate_shape(property, value, results, depth + 1)?;
+                    }
+                }
+                Constraint::And(ids) => {
+                    for value in &values {
+                        if self.conforms_count(ids, value, depth)? != ids.len() {
+                            report(
+                                Some(value),
+                                "Value does not conform to all shapes".to_string(),
+                            );
+                        }
+                    }
+                }
+                Constraint::Or(ids) => {
+                    for value in &values {
+                        if self.conforms_count(ids, value, depth)? == 0 {
+                            report(
+                                Some(value),
+                                "Value does not conform to any shape".to_string(),
+                            );
+                        }
+                    }
+                }
+                Constraint::Xone(ids) => {
+                    for value in &values {
+                        if self.conforms_count(ids, value, depth)? != 1 {
+                            report(
+                                Some(value),
+                                "Value does not conform to exactly one shape".to_string(),
+                            );
+                        }
+                    }
+                }
+                Constraint::Not(id) => {
+                    for value in &values {
+                        if self.conforms(id, value, depth + 1)? {
+                            report(Some(value), format!("Value conforms to shape {}", id));
+                        }
+                    }
+                }
+                Constraint::Sparql(sparql) => {
+                    for (value, message) in self.evaluate_sparql(shape, sparql, focus)? {
+                        let message = message
+                            .or_else(|| shape.messages.first().cloned())
+                            .unwrap_or_else(|| "SPARQL constraint violated".to_string());
+                        results.push(ValidationResult {
+                            focus_node: focus.clone(),
+                            result_path: shape.path.clone(),
+                            value,
+                            source_shape: shape.id.clone(),
+                            source_constraint_component: constraint.component(),
+                            severity: shape.severity,
+                            message: Some(message),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn conforms_count(&self, ids: &[Term], node: &Term, depth: usize) -> ShaclResult<usize> {
+        let mut count = 0;
+        for id in ids {
+            if self.conforms(id, node, depth + 1)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Run a SPARQL constraint with `$this` bound to the focus node; each
+    /// solution is one result (value node and message)
+    fn evaluate_sparql(
+        &self,
+        shape: &Shape,
+        sparql: &SparqlConstraint,
+        focus: &Term,
+    ) -> ShaclResult<Vec<(Option<Term>, Option<String>)>> {
+        let mut select = sparql.select.clone();
+        if let Some(path) = &shape.path {
+            select = select.replace("$PATH", &path.to_string());
+        }
+        let query = format!("{}{}", sparql.prefixes, select);
+        let solutions = match SparqlEvaluator::new()
+            .parse_query(&query)
+            .map_err(|e| ShaclError::Sparql(format!("{}: {}", shape.id, e)))?
+            .substitute_variable(Variable::new_unchecked("this"), focus.clone())
+            .on_store(self.store)
+            .execute()
+            .map_err(|e| ShaclError::Sparql(format!("{}: {}", shape.id, e)))?
+        {
+            QueryResults::Solutions(solutions) => solutions,
+            _ => {
+                return Err(ShaclError::Sparql(format!(
+                    "{}: sh:select must be a SELECT query",
+                    shape.id
+                )))
+            }
+        };
+
+        let mut violations = Vec::new();
+        for solution in solutions {
+            let solution = solution.map_err(|e| ShaclError::Sparql(e.to_string()))?;
+            let value = solution
+                .get("value")
+                .cloned()
+                .or_else(|| shape.path.is_none().then(|| focus.clone()));
+            let message = sparql.message.as_ref().map(|template| {
+                let mut message = template.clone();
+                for (variable, term) in solution.iter() {
+                    let text = match term {
+                        Term::Literal(literal) => literal.value().to_string(),
+                        other => other.to_string(),
+                    };
+                    message = message
+                        .replace(&format!("{{?{}}}", variable.as_str()), &text)
+                        .replace(&format!("{{${}}}", variable.as_str()), &text);
+                }
+                message.replace("{$this}", &focus.to_string())
+            });
+            violations.push((value, message));
+        }
+        Ok(violations)
+    }
+}
+
+/// Lexical form used by string-based constraints (blank nodes have none)
+fn lexical(term: &Term) -> Option<&str> {
+    match term {
+        Term::NamedNode(node) => Some(node.as_str()),
+        Term::Literal(literal) => Some(literal.value()),
+        _ => None,
+    }
+}
+
+/// Whether `term` is a literal of `datatype` with a valid lexical form
+fn has_datatype(term: &Term, datatype: &NamedNode) -> bool {
+    let Term::Literal(literal) = term else {
+        return false;
+    };
+    literal.datatype() == datatype.as_ref() && is_well_formed(literal)
+}
+
+fn is_well_formed(literal: &Literal) -> bool {
+    let value = literal.value();
+    let datatype = literal.datatype();
+    if datatype == xsd::INTEGER {
+        value.parse::<i128>().is_ok()
+    } else if datatype == xsd::DECIMAL {
+        let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+        !digits.is_empty()
+            && digits != "."
+            && digits.chars().filter(|c| *c == '.').count() <= 1
+            && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+    } else if datatype == xsd::BOOLEAN {
+        matches!(value, "true" | "false" | "1" | "0")
+    } else if datatype == xsd::DOUBLE || datatype == xsd::FLOAT {
+        matches!(value, "INF" | "-INF" | "NaN") || value.parse::<f64>().is_ok()
+    } else {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREFIXES: &str = r#"
+        @prefix sh: <http://www.w3.org/ns/shacl#> .
+        @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+        @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+        @prefix ex: <http://example.org/> .
+    "#;
+
+    fn validate(shapes: &str, data: &str) -> ValidationReport {
+        let engine = ShaclEngine::from_turtle(&format!("{}{}", PREFIXES, shapes))
+            .unwrap_or_else(|e| panic!("shapes should parse: {}", e));
+        engine
+            .validate_turtle(&format!("{}{}", PREFIXES, data))
+            .unwrap_or_else(|e| panic!("validation should run: {}", e))
+    }
+
+    fn components(report: &ValidationReport) -> Vec<String> {
+        report
+            .results
+            .iter()
+            .map(|result| result.source_constraint_component.as_str().to_string())
+            .collect()
+    }
+
+    const PERSON_SHAPE: &str = r#"
+        ex:PersonShape a sh:NodeShape ;
+            sh:targetClass ex:Person ;
+            sh:property [
+                sh:path ex:name ;
+                sh:datatype xsd:string ;
+                sh:minCount 1 ;
+                sh:maxCount 1 ;
+                sh:pattern "^[A-Z]" ;
+            ] ;
+            sh:property [
+                sh:path ex:status ;
+                sh:in ( "active" "retired" ) ;
+            ] .
+    "#;
+
+    #[test]
+    fn conforming_data_produces_empty_report() {
+        let report = validate(
+            PERSON_SHAPE,
+            r#"ex:alice a ex:Person ; ex:name "Alice" ; ex:status "active" ."#,
+        );
+        assert!(report.conforms);
+        assert!(report.results.is_empty());
+    }
+
+    #[test]
+    fn property_constraints_report_each_component() {
+        let report = validate(
+            PERSON_SHAPE,
+            r#"
+            ex:bob a ex:Person ; ex:name "bob", 42 ; ex:status "unknown" .
+            ex:carol a ex:Person .
+            "#,
+        );
+        assert!(!report.conforms);
+        let components = components(&report);
+        for expected in [
+            "DatatypeConstraintComponent",
+            "MaxCountConstraintComponent",
+            "PatternConstraintComponent",
+            "InConstraintComponent",
+            "MinCountConstraintComponent",
+        ] {
+            assert!(
+                components.iter().any(|c| c.ends_with(expected)),
+                "missing {} in {:?}",
+                expected,
+                components
+            );
+        }
+        let min_count = report
+            .results
+            .iter()
+            .find(|r| {
+                r.source_constraint_component
+                    .as_str()
+                    .ends_with("MinCountConstraintComponent")
+            })
+            .unwrap_or_else(|| panic!("min count result expected"));
+        assert_eq!(
+            min_count.focus_node.to_string(),
+            "<http://example.org/carol>"
+        );
+        assert!(min_count.value.is_none());
+    }
+
+    #[test]
+    fn subclass_instances_are_targeted() {
+        let report = validate(
+            PERSON_SHAPE,
+            "ex:Employee rdfs:subClassOf ex:Person . ex:dave a ex:Employee .",
+        );
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(
+            report.results[0].focus_node.to_string(),
+            "<http://example.org/dave>"
+        );
+    }
+
+    #[test]
+    fn logical_and_node_constraints() {
+        let shapes = r#"
+            ex:NamedShape sh:property [ sh:path ex:name ; sh:minCount 1 ] .
+            ex:AgedShape sh:property [ sh:path ex:age ; sh:minCount 1 ] .
+            ex:TaskShape a sh:NodeShape ;
+                sh:targetSubjectsOf ex:owner ;
+                sh:property [ sh:path ex:owner ; sh:node ex:NamedShape ] ;
+                sh:or ( ex:NamedShape ex:AgedShape ) ;
+                sh:not ex:AgedShape .
+        "#;
+        let report = validate(
+            shapes,
+            r#"
+            ex:t1 ex:owner ex:o1 ; ex:name "t1" .
+            ex:o1 ex:name "Owner" .
+            ex:t2 ex:owner ex:o2 ; ex:age 3 .
+            ex:o2 ex:age 40 .
+            "#,
+        );
+        let components = components(&report);
+        assert_eq!(report.results.len(), 2, "{:?}", components);
+        assert!(report
+            .results
+            .iter()
+            .all(|r| r.focus_node.to_string() == "<http://example.org/t2>"));
+        assert!(components
+            .iter()
+            .any(|c| c.ends_with("NodeConstraintComponent")));
+        assert!(components
+            .iter()
+            .any(|c| c.ends_with("NotConstraintComponent")));
+    }
+
+    #[test]
+    fn complex_paths_are_evaluated() {
+        let shapes = r#"
+            ex:RootShape sh:targetNode ex:root ;
+                sh:property [
+                    sh:path ( ex:child [ sh:oneOrMorePath ex:child ] ) ;
+                    sh:minCount 2 ;
+                    sh:maxCount 2 ;
+                ] ;
+                sh:property [
+                    sh:path [ sh:inversePath ex:child ] ;
+                    sh:maxCount 0 ;
+                ] .
+        "#;
+        let report = validate(
+            shapes,
+            "ex:root ex:child ex:a . ex:a ex:child ex:b . ex:b ex:child ex:c . ex:x ex:child ex:root .",
+        );
+        let components = components(&report);
+        assert_eq!(components.len(), 1, "{:?}", components);
+        assert!(components[0].ends_with("MaxCountConstraintComponent"));
+        assert_eq!(
+            report.results[0]
+                .result_path
+                .as_ref()
+                .map(ToString::to_string),
+            Some("^(<http://example.org/child>)".to_string())
+        );
+    }
+
+    #[test]
+    fn sparql_constraint_binds_this_and_fills_message() {
+        let shapes = r#"
+            ex:BudgetShape sh:targetClass ex:Project ;
+                sh:severity sh:Warning ;
+                sh:sparql [
+                    sh:message "Spent {?value} exceeds budget" ;
+                    sh:prefixes ex: ;
+                    sh:select """
+                        SELECT $this ?value WHERE {
+                            $this ex:budget ?budget ; ex:spent ?value .
+                            FILTER (?value > ?budget)
+                        }
+                    """ ;
+                ] .
+            ex: sh:declare [ sh:prefix "ex" ; sh:namespace "http://example.org/"^^xsd:anyURI ] .
+        "#;
+        let report = validate(
+            shapes,
+            r#"
+            ex:p1 a ex:Project ; ex:budget 10 ; ex:spent 12 .
+            ex:p2 a ex:Project ; ex:budget 10 ; ex:spent 5 .
+            "#,
+        );
+        assert_eq!(report.results.len(), 1);
+        let result = &report.results[0];
+        assert_eq!(result.focus_node.to_string(), "<http://example.org/p1>");
+        assert_eq!(result.severity, crate::Severity::Warning);
+        assert_eq!(result.message.as_deref(), Some("Spent 12 exceeds budget"));
+        assert_eq!(report.violations().count(), 0);
+    }
+
+    #[test]
+    fn report_serializes_to_w3c_graph() {
+        let report = validate(PERSON_SHAPE, r#"ex:erin a ex:Person ; ex:name "erin" ."#);
+        let turtle = report.to_turtle();
+
+        let store = Store::new().unwrap_or_else(|e| panic!("store: {}", e));
+        store
+            .load_from_reader(RdfFormat::Turtle, turtle.as_bytes())
+            .unwrap_or_else(|e| panic!("report should be valid Turtle: {}\n{}", e, turtle));
+        assert!(turtle.contains("sh:conforms false"));
+        assert!(turtle.contains("sh:PatternConstraintComponent"));
+        assert!(turtle.contains("sh:resultPath <http://example.org/name>"));
+        assert!(turtle.contains("sh:value \"erin\""));
+    }
+
+    #[test]
+    fn recursive_shapes_are_bounded() {
+        let shapes = r#"
+            ex:LoopShape sh:targetNode ex:a ; sh:node ex:LoopShape .
+        "#;
+        let engine = ShaclEngine::from_turtle(&format!("{}{}", PREFIXES, shapes))
+            .unwrap_or_else(|e| panic!("shapes should parse: {}", e));
+        let result = engine.validate_turtle(&format!("{}ex:a ex:p ex:b .", PREFIXES));
+        assert!(matches!(result, Err(ShaclError::Recursion(_))));
+    }
+}
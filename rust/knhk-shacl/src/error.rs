@@ -0,0 +1,28 @@
+//! SHACL error types
+
+use thiserror::Error;
+
+/// Errors raised while loading shapes or validating data
+#[derive(Debug, Error)]
+pub enum ShaclError {
+    #[error("Failed to parse RDF: {0}")]
+    Parse(String),
+
+    #[error("Invalid shapes graph: {0}")]
+    InvalidShape(String),
+
+    #[error("SPARQL constraint failed: {0}")]
+    Sparql(String),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("Shape recursion exceeds depth {0}")]
+    Recursion(usize),
+
+    #[error("Invalid payload: {0}")]
+    Payload(String),
+}
+
+/// Result type for SHACL operations
+pub type ShaclResult<T> = Result<T, ShaclError>;
@@ -0,0 +1,181 @@
+//! In-memory triple index over shapes and data graphs
+//!
+//! Shapes parsing and path evaluation need direct subject/object lookups
+//! (including blank nodes, which cannot be addressed from SPARQL text), so
+//! both graphs are indexed in both directions.
+
+use crate::error::{ShaclError, ShaclResult};
+use crate::SH;
+use oxigraph::model::vocab::{rdf, rdfs};
+use oxigraph::model::{NamedNode, NamedNodeRef, Term};
+use oxigraph::store::Store;
+use std::collections::{HashMap, HashSet};
+
+/// Build a SHACL vocabulary IRI
+pub(crate) fn sh(local: &str) -> NamedNode {
+    NamedNode::new_unchecked(format!("{}{}", SH, local))
+}
+
+/// Triple index keyed by subject and by object
+#[derive(Debug, Default)]
+pub(crate) struct TripleIndex {
+    outgoing: HashMap<Term, HashMap<NamedNode, Vec<Term>>>,
+    incoming: HashMap<Term, HashMap<NamedNode, Vec<Term>>>,
+}
+
+impl TripleIndex {
+    /// Index every quad of a store (all graphs)
+    pub(crate) fn from_store(store: &Store) -> ShaclResult<Self> {
+        let mut index = Self::default();
+        for quad in store.quads_for_pattern(None, None, None, None) {
+            let quad = quad.map_err(|e| ShaclError::Storage(e.to_string()))?;
+            index.insert(Term::from(quad.subject), quad.predicate, quad.object);
+        }
+        Ok(index)
+    }
+
+    fn insert(&mut self, subject: Term, predicate: NamedNode, object: Term) {
+        let objects = self
+            .outgoing
+            .entry(subject.clone())
+            .or_default()
+            .entry(predicate.clone())
+            .or_default();
+        if objects.contains(&object) {
+            return;
+        }
+        objects.push(object.clone());
+        self.incoming
+            .entry(object)
+            .or_default()
+            .entry(predicate)
+            .or_default()
+            .push(subject);
+    }
+
+    /// Objects of `subject predicate ?o`
+    pub(crate) fn objects(&self, subject: &Term, predicate: NamedNodeRef<'_>) -> &[Term] {
+        self.outgoing
+            .get(subject)
+            .and_then(|predicates| predicates.get(&predicate.into_owned()))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// First object of `subject predicate ?o`
+    pub(crate) fn object(&self, subject: &Term, predicate: NamedNodeRef<'_>) -> Option<&Term> {
+        self.objects(subject, predicate).first()
+    }
+
+    /// Subjects of `?s predicate object`
+    pub(crate) fn subjects(&self, predicate: NamedNodeRef<'_>, object: &Term) -> &[Term] {
+        self.incoming
+            .get(object)
+            .and_then(|predicates| predicates.get(&predicate.into_owned()))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// All subjects with at least one `predicate` triple, in a stable order
+    pub(crate) fn subjects_of(&self, predicate: NamedNodeRef<'_>) -> Vec<Term> {
+        let predicate = predicate.into_owned();
+        let mut subjects: Vec<Term> = self
+            .outgoing
+            .iter()
+            .filter(|(_, predicates)| predicates.contains_key(&predicate))
+            .map(|(subject, _)| subject.clone())
+            .collect();
+        sort_terms(&mut subjects);
+        subjects
+    }
+
+    /// All objects of `predicate` triples, in a stable order
+    pub(crate) fn objects_of(&self, predicate: NamedNodeRef<'_>) -> Vec<Term> {
+        let predicate = predicate.into_owned();
+        let mut objects: Vec<Term> = self
+            .incoming
+            .iter()
+            .filter(|(_, predicates)| predicates.contains_key(&predicate))
+            .map(|(object, _)| object.clone())
+            .collect();
+        sort_terms(&mut objects);
+        objects
+    }
+
+    /// Whether `subject predicate object` is in the graph
+    pub(crate) fn contains(
+        &self,
+        subject: &Term,
+        predicate: NamedNodeRef<'_>,
+        object: &Term,
+    ) -> bool {
+        self.objects(subject, predicate).contains(object)
+    }
+
+    /// Members of an RDF list, in order
+    pub(crate) fn list(&self, head: &Term) -> ShaclResult<Vec<Term>> {
+        let nil = Term::from(rdf::NIL.into_owned());
+        let mut items = Vec::new();
+        let mut seen = HashSet::new();
+        let mut node = head.clone();
+        while node != nil {
+            if !seen.insert(node.clone()) {
+                return Err(ShaclError::InvalidShape(format!(
+                    "Cyclic RDF list at {}",
+                    node
+                )));
+            }
+            let first = self.object(&node, rdf::FIRST).ok_or_else(|| {
+                ShaclError::InvalidShape(format!("{} is not a well-formed RDF list", node))
+            })?;
+            items.push(first.clone());
+            node = self.object(&node, rdf::REST).cloned().ok_or_else(|| {
+                ShaclError::InvalidShape(format!("{} is not a well-formed RDF list", node))
+            })?;
+        }
+        Ok(items)
+    }
+
+    /// `class` and its transitive subclasses
+    pub(crate) fn subclasses(&self, class: &Term) -> Vec<Term> {
+        let mut classes = vec![class.clone()];
+        let mut seen: HashSet<Term> = classes.iter().cloned().collect();
+        let mut next = 0;
+        while next < classes.len() {
+            for subclass in self.subjects(rdfs::SUB_CLASS_OF, &classes[next]) {
+                if seen.insert(subclass.clone()) {
+                    classes.push(subclass.clone());
+                }
+            }
+            next += 1;
+        }
+        classes
+    }
+
+    /// SHACL instances of `class` (`rdf:type/rdfs:subClassOf*`)
+    pub(crate) fn instances_of(&self, class: &Term) -> Vec<Term> {
+        let mut instances = Vec::new();
+        let mut seen = HashSet::new();
+        for class in self.subclasses(class) {
+            for instance in self.subjects(rdf::TYPE, &class) {
+                if seen.insert(instance.clone()) {
+                    instances.push(instance.clone());
+                }
+            }
+        }
+        instances
+    }
+
+    /// Whether `node` is a SHACL instance of `class`
+    pub(crate) fn is_instance_of(&self, node: &Term, class: &Term) -> bool {
+        let classes = self.subclasses(class);
+        self.objects(node, rdf::TYPE)
+            .iter()
+            .any(|node_type| classes.contains(node_type))
+    }
+}
+
+/// Sort terms by their N-Triples form for deterministic output
+pub(crate) fn sort_terms(terms: &mut [Term]) {
+    terms.sort_by_cached_key(|term| term.to_string());
+}
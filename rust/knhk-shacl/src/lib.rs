@@ -0,0 +1,52 @@
+// rust/knhk-shacl/src/lib.rs
+// SHACL Core validation engine driven by user-supplied shapes graphs
+
+// CRITICAL: Enforce proper error handling - no unwrap/expect in production code
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+
+//! SHACL Core validation for KNHK
+//!
+//! Loads arbitrary shapes graphs from Turtle and validates data graphs
+//! against them, producing a W3C validation report. Supported features:
+//!
+//! - Targets: `sh:targetNode`, `sh:targetClass` (and implicit class targets),
+//!   `sh:targetSubjectsOf`, `sh:targetObjectsOf`
+//! - Property paths: predicate, sequence, alternative, inverse,
+//!   zero-or-more, one-or-more and zero-or-one paths
+//! - Value type: `sh:class`, `sh:datatype`, `sh:nodeKind`
+//! - Cardinality: `sh:minCount`, `sh:maxCount`
+//! - String based: `sh:minLength`, `sh:maxLength`, `sh:pattern` (with `sh:flags`)
+//! - Other: `sh:in`, `sh:hasValue`
+//! - Shape-based: `sh:node`, `sh:property`, `sh:and`, `sh:or`, `sh:xone`, `sh:not`
+//! - SPARQL-based constraints: `sh:sparql` with `sh:select`, `sh:prefixes`
+//!   and `sh:message` templates
+//!
+//! JSON payloads can be validated directly with
+//! [`ShaclEngine::validate_json`]; see [`payload`] for the mapping.
+//!
+//! ```ignore
+//! let engine = ShaclEngine::from_turtle(shapes_ttl)?;
+//! let report = engine.validate_turtle(data_ttl)?;
+//! if !report.conforms {
+//!     println!("{}", report.to_turtle());
+//! }
+//! ```
+
+pub mod engine;
+pub mod error;
+mod graph;
+pub mod path;
+pub mod payload;
+pub mod report;
+pub mod shapes;
+
+pub use engine::ShaclEngine;
+pub use error::{ShaclError, ShaclResult};
+pub use path::PropertyPath;
+pub use payload::{json_to_turtle, PAYLOAD_NODE};
+pub use report::{Severity, ValidationReport, ValidationResult};
+pub use shapes::{Constraint, NodeKind, Shape, ShapesGraph, SparqlConstraint, Target};
+
+/// SHACL namespace
+pub const SH: &str = "http://www.w3.org/ns/shacl#";
@@ -0,0 +1,343 @@
+//! SHACL property paths
+//!
+//! Paths are parsed from the shapes graph and evaluated directly over the
+//! data graph index, so that blank focus nodes are handled like any other.
+
+use crate::error::{ShaclError, ShaclResult};
+use crate::graph::{sh, TripleIndex};
+use oxigraph::model::vocab::rdf;
+use oxigraph::model::{NamedNode, Term};
+use std::collections::HashSet;
+use std::fmt;
+
+/// SHACL property path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyPath {
+    /// Single predicate
+    Predicate(NamedNode),
+    /// Inverse path (`sh:inversePath`)
+    Inverse(Box<PropertyPath>),
+    /// Sequence path (RDF list of paths)
+    Sequence(Vec<PropertyPath>),
+    /// Alternative path (`sh:alternativePath`)
+    Alternative(Vec<PropertyPath>),
+    /// Zero or more steps (`sh:zeroOrMorePath`)
+    ZeroOrMore(Box<PropertyPath>),
+    /// One or more steps (`sh:oneOrMorePath`)
+    OneOrMore(Box<PropertyPath>),
+    /// Zero or one step (`sh:zeroOrOnePath`)
+    ZeroOrOne(Box<PropertyPath>),
+}
+
+impl PropertyPath {
+    /// Parse the path rooted at `node` of the shapes graph
+    pub(crate) fn parse(shapes: &TripleIndex, node: &Term) -> ShaclResult<Self> {
+        match node {
+            Term::NamedNode(predicate) => Ok(Self::Predicate(predicate.clone())),
+            Term::BlankNode(_) => {
+                if shapes.object(node, rdf::FIRST).is_some() {
+                    let steps = Self::parse_list(shapes, node)?;
+                    if steps.len() < 2 {
+                        return Err(ShaclError::InvalidShape(format!(
+                            "Sequence path {} needs at least two members",
+                            node
+                        )));
+                    }
+                    return Ok(Self::Sequence(steps));
+                }
+                let nested = |local: &str| shapes.object(node, sh(local).as_ref()).cloned();
+                if let Some(inner) = nested("inversePath") {
+                    Ok(Self::Inverse(Box::new(Self::parse(shapes, &inner)?)))
+                } else if let Some(list) = nested("alternativePath") {
+                    let alternatives = Self::parse_list(shapes, &list)?;
+                    if alternatives.len() < 2 {
+                        return Err(ShaclError::InvalidShape(format!(
+                            "Alternative path {} needs at least two members",
+                            node
+                        )));
+                    }
+                    Ok(Self::Alternative(alternatives))
+                } else if let Some(inner) = nested("zeroOrMorePath") {
+                    Ok(Self::ZeroOrMore(Box::new(Self::parse(shapes, &inner)?)))
+                } else if let Some(inner) = nested("oneOrMorePath") {
+                    Ok(Self::OneOrMore(Box::new(Self::parse(shapes, &inner)?)))
+                } else if let Some(inner) = nested("zeroOrOnePath") {
+                    Ok(Self::ZeroOrOne(Box::new(Self::parse(shapes, &inner)?)))
+                } else {
+                    Err(ShaclError::InvalidShape(format!(
+                        "{} is not a well-formed property path",
+                        node
+                    )))
+                }
+            }
+            _ => Err(ShaclError::InvalidShape(format!(
+                "{} is not a well-formed property path",
+                node
+            ))),
+        }
+    }
+
+    fn parse_list(shapes: &TripleIndex, list: &Term) -> ShaclResult<Vec<Self>> {
+        shapes
+            .list(list)?
+            .iter()
+            .map(|member| Self::parse(shapes, member))
+            .collect()
+    }
+
+    /// Value nodes reachable from `focus` through this path
+    pub(crate) fn evaluate(&self, data: &TripleIndex, focus: &Term) -> Vec<Term> {
+        let mut values = Vec::new();
+        let mut seen = HashSet::new();
+        for value in self.step(data, focus, false) {
+            if seen.insert(value.clone()) {
+                values.push(value);
+            }
+        }
+        values
+    }
+
+    fn step(&self, data: &TripleIndex, node: &Term, inverse: bool) -> Vec<Term> {
+        match self {
+            Self::Predicate(predicate) => {
+                if inverse {
+                    data.subjects(predicate.as_ref(), node).to_vec()
+                } else {
+                    data.objects(node, predicate.as_ref()).to_vec()
+                }
+            }
+            Self::Inverse(inner) => inner.step(data, node, !inverse),
+            Self::Sequence(steps) => {
+                let mut current = vec![node.clone()];
+                let ordered: Box<dyn Iterator<Item = &PropertyPath>> = if inverse {
+                    Box::new(steps.iter().rev())
+                } else {
+                    Box::new(steps.iter())
+                };
+                for step in ordered {
+                    current = dedup(
+                        current
+                            .iter()
+                            .flat_map(|node| step.step(data, node, inverse))
+                            .collect(),
+                    );
+                }
+                current
+            }
+            Self::Alternative(alternatives) => dedup(
+                alternatives
+                    .iter()
+                    .flat_map(|alternative| alternative.step(data, node, inverse))
+                    .collect(),
+            ),
+            Self::ZeroOrMore(inner) => closure(inner, data, node, inverse, true),
+            Self::OneOrMore(inner) => closure(inner, data, node, inverse, false),
+            Self::ZeroOrOne(inner) => {
+                let mut values = vec![node.clone()];
+                values.extend(inner.step(data, node, inverse));
+                dedup(values)
+            }
+        }
+    }
+
+    /// Render the path in Turtle (for validation reports)
+    pub(crate) fn to_turtle(&self) -> String {
+        let list = |paths: &[PropertyPath]| {
+            let members: Vec<String> = paths.iter().map(PropertyPath::to_turtle).collect();
+            format!("( {} )", members.join(" "))
+        };
+        match self {
+            Self::Predicate(predicate) => predicate.to_string(),
+            Self::Inverse(inner) => format!("[ sh:inversePath {} ]", inner.to_turtle()),
+            Self::Sequence(steps) => list(steps),
+            Self::Alternative(alternatives) => {
+                format!("[ sh:alternativePath {} ]", list(alternatives))
+            }
+            Self::ZeroOrMore(inner) => format!("[ sh:zeroOrMorePath {} ]", inner.to_turtle()),
+            Self::OneOrMore(inner) => format!("[ sh:oneOrMorePath {} ]", inner.to_turtle()),
+            Self::ZeroOrOne(inner) => format!("[ sh:zeroOrOnePath {} ]", inner.to_turtle()),
+        }
+    }
+}
+
+/// SPARQL property path syntax (used for `$PATH` in SPARQL constraints)
+impl fmt::Display for PropertyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |paths: &[PropertyPath], separator: &str| {
+            paths
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(separator)
+        };
+        match self {
+            Self::Predicate(predicate) => write!(f, "{}", predicate),
+            Self::Inverse(inner) => write!(f, "^({})", inner),
+            Self::Sequence(steps) => write!(f, "({})", join(steps, "/")),
+            Self::Alternative(alternatives) => write!(f, "({})", join(alternatives, "|")),
+            Self::ZeroOrMore(inner) => write!(f, "({})*", inner),
+            Self::OneOrMore(inner) => write!(f, "({})+", inner),
+            Self::ZeroOrOne(inner) => write!(f, "({})?", inner),
+        }
+    }
+}
+
+fn closure(
+    path: &PropertyPath,
+    data: &TripleIndex,
+    node: &Term,
+    inverse: bool,
+    include_start: bool,
+) -> Vec<Term> {
+    let mut reached = Vec::new();
+    let mut seen = HashSet::new();
+    if include_start {
+        seen.insert(node.clone());
+        reached.push(node.clone());
+    }
+    let mut frontier = vec![node.clone()];
+    while let Some(current) = frontier.pop() {
+        for next in path.step(data, &current, inverse) {
+            if seen.insert(next.clone()) {
+                reached.push(next.clone());
+                frontier.push(next);
+            }
+        }
+    }
+    reached
+}
+
+fn dedup(terms: Vec<Term>) -> Vec<Term> {
+    let mut seen = HashSet::new();
+    terms
+        .into_iter()
+        .filter(|term| seen.insert(term.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxigraph::io::RdfFormat;
+    use oxigraph::store::Store;
+
+    const PREFIXES: &str = r#"
+        @prefix sh: <http://www.w3.org/ns/shacl#> .
+        @prefix ex: <http://example.org/> .
+    "#;
+
+    const DATA: &str = r#"
+        ex:a ex:knows ex:b ; ex:likes ex:c .
+        ex:b ex:knows ex:c .
+        ex:c ex:knows ex:b .
+        ex:d ex:knows ex:a .
+    "#;
+
+    fn ex(local: &str) -> Term {
+        Term::from(NamedNode::new_unchecked(format!(
+            "http://example.org/{}",
+            local
+        )))
+    }
+
+    fn index(turtle: &str) -> TripleIndex {
+        let store = Store::new().unwrap_or_else(|e| panic!("store: {}", e));
+        store
+            .load_from_reader(RdfFormat::Turtle, turtle.as_bytes())
+            .unwrap_or_else(|e| panic!("turtle should load: {}", e));
+        TripleIndex::from_store(&store).unwrap_or_else(|e| panic!("index: {}", e))
+    }
+
+    /// Parse `path` (Turtle) as the `sh:path` of a throwaway shape
+    fn parse(path: &str) -> ShaclResult<PropertyPath> {
+        let shapes = index(&format!("{}ex:shape sh:path {} .", PREFIXES, path));
+        let node = shapes
+            .object(&ex("shape"), sh("path").as_ref())
+            .unwrap_or_else(|| panic!("ex:shape should have a path"))
+            .clone();
+        PropertyPath::parse(&shapes, &node)
+    }
+
+    fn evaluate(path: &str, focus: &str) -> Vec<Term> {
+        let path = parse(path).unwrap_or_else(|e| panic!("path should parse: {}", e));
+        let mut values = path.evaluate(&index(&format!("{}{}", PREFIXES, DATA)), &ex(focus));
+        crate::graph::sort_terms(&mut values);
+        values
+    }
+
+    #[test]
+    fn inverse_path_follows_edges_backwards() {
+        assert_eq!(
+            evaluate("[ sh:inversePath ex:knows ]", "b"),
+            vec![ex("a"), ex("c")]
+        );
+        assert_eq!(
+            evaluate("[ sh:inversePath ex:knows ]", "d"),
+            Vec::<Term>::new()
+        );
+    }
+
+    #[test]
+    fn alternative_path_unions_members() {
+        assert_eq!(
+            evaluate("[ sh:alternativePath ( ex:knows ex:likes ) ]", "a"),
+            vec![ex("b"), ex("c")]
+        );
+    }
+
+    #[test]
+    fn alternative_path_needs_two_members() {
+        let result = parse("[ sh:alternativePath ( ex:knows ) ]");
+        assert!(matches!(result, Err(ShaclError::InvalidShape(_))));
+    }
+
+    #[test]
+    fn zero_or_more_path_includes_focus() {
+        assert_eq!(
+            evaluate("[ sh:zeroOrMorePath ex:knows ]", "d"),
+            vec![ex("a"), ex("b"), ex("c"), ex("d")]
+        );
+        assert_eq!(
+            evaluate("[ sh:zeroOrMorePath ex:likes ]", "c"),
+            vec![ex("c")]
+        );
+    }
+
+    #[test]
+    fn one_or_more_path_excludes_focus_unless_revisited() {
+        assert_eq!(
+            evaluate("[ sh:oneOrMorePath ex:knows ]", "d"),
+            vec![ex("a"), ex("b"), ex("c")]
+        );
+        // ex:b knows ex:c, which knows ex:b again
+        assert_eq!(
+            evaluate("[ sh:oneOrMorePath ex:knows ]", "b"),
+            vec![ex("b"), ex("c")]
+        );
+        assert_eq!(
+            evaluate("[ sh:oneOrMorePath ex:likes ]", "c"),
+            Vec::<Term>::new()
+        );
+    }
+
+    #[test]
+    fn nested_paths_compose() {
+        // Everyone with a chain of ex:knows edges leading to ex:a
+        assert_eq!(
+            evaluate("[ sh:oneOrMorePath [ sh:inversePath ex:knows ] ]", "a"),
+            vec![ex("d")]
+        );
+    }
+
+    #[test]
+    fn paths_render_as_sparql() {
+        let path = parse(
+            "[ sh:alternativePath ( [ sh:inversePath ex:knows ] [ sh:zeroOrMorePath ex:likes ] ) ]",
+        )
+        .unwrap_or_else(|e| panic!("path should parse: {}", e));
+        assert_eq!(
+            path.to_string(),
+            "(^(<http://example.org/knows>)|(<http://example.org/likes>)*)"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! JSON payloads as data graphs
+//!
+//! A payload object becomes the node [`PAYLOAD_NODE`] of type `ex:Payload`
+//! (`ex:` = `http://example.org/`) and each key an `ex:` property. Arrays
+//! expand to repeated values and nested objects to blank nodes; nulls are
+//! dropped.
+
+use crate::error::{ShaclError, ShaclResult};
+use serde_json::{Map, Value};
+
+/// Focus node a payload object is mapped to
+pub const PAYLOAD_NODE: &str = "urn:knhk:admission:payload";
+
+/// Convert a JSON payload object to a Turtle data graph
+pub fn json_to_turtle(payload: &Value) -> ShaclResult<String> {
+    let obj = payload
+        .as_object()
+        .ok_or_else(|| ShaclError::Payload("payload must be a JSON object".to_string()))?;
+
+    let mut turtle = String::new();
+    turtle.push_str("@prefix ex: <http://example.org/> .\n");
+    turtle.push_str("@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\n");
+    turtle.push_str(&format!("<{}> a ex:Payload", PAYLOAD_NODE));
+    for property in json_properties(obj) {
+        turtle.push_str(" ;\n    ");
+        turtle.push_str(&property);
+    }
+    turtle.push_str(" .\n");
+    Ok(turtle)
+}
+
+/// Turtle predicate-object pairs for the entries of a JSON object
+fn json_properties(obj: &Map<String, Value>) -> Vec<String> {
+    obj.iter()
+        .filter_map(|(key, value)| {
+            let mut objects = Vec::new();
+            json_objects(value, &mut objects);
+            if objects.is_empty() {
+                None
+            } else {
+                Some(format!("{} {}", json_predicate(key), objects.join(", ")))
+            }
+        })
+        .collect()
+}
+
+/// Turtle objects for a JSON value (arrays are flattened)
+fn json_objects(value: &Value, objects: &mut Vec<String>) {
+    match value {
+        Value::Null => {}
+        // JSON string escapes are valid Turtle string escapes
+        Value::String(s) => objects.push(Value::String(s.clone()).to_string()),
+        Value::Number(n) if n.is_i64() || n.is_u64() => {
+            objects.push(format!("\"{}\"^^xsd:integer", n))
+        }
+        Value::Number(n) => objects.push(format!("\"{}\"^^xsd:double", n)),
+        Value::Bool(b) => objects.push(b.to_string()),
+        Value::Array(items) => items.iter().for_each(|item| json_objects(item, objects)),
+        Value::Object(nested) => {
+            let properties = json_properties(nested);
+            if properties.is_empty() {
+                objects.push("[]".to_string());
+            } else {
+                objects.push(format!("[ {} ]", properties.join(" ; ")));
+            }
+        }
+    }
+}
+
+/// `ex:` predicate for a JSON key (characters outside `[A-Za-z0-9_-]` are percent-encoded)
+fn json_predicate(key: &str) -> String {
+    let mut iri = String::from("<http://example.org/");
+    for byte in key.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' {
+            iri.push(byte as char);
+        } else {
+            iri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    iri.push('>');
+    iri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ShaclEngine;
+
+    #[test]
+    fn test_json_to_turtle_single_payload_node() {
+        let payload = serde_json::json!({
+            "name": "say \"hi\"",
+            "count": 2,
+            "tasks": ["a", "b"],
+            "meta": {"owner": "ops"},
+            "odd key": true,
+            "missing": null
+        });
+
+        let turtle = json_to_turtle(&payload).unwrap();
+        assert!(turtle.contains("<urn:knhk:admission:payload> a ex:Payload"));
+        assert!(turtle.contains("<http://example.org/name> \"say \\\"hi\\\"\""));
+        assert!(turtle.contains("<http://example.org/count> \"2\"^^xsd:integer"));
+        assert!(turtle.contains("<http://example.org/tasks> \"a\", \"b\""));
+        assert!(turtle.contains("<http://example.org/meta> [ <http://example.org/owner> \"ops\" ]"));
+        assert!(turtle.contains("<http://example.org/odd%20key> true"));
+        assert!(!turtle.contains("missing"));
+
+        assert!(matches!(
+            json_to_turtle(&serde_json::json!(["not", "an", "object"])),
+            Err(ShaclError::Payload(_))
+        ));
+    }
+
+    #[test]
+    fn test_shapes_reject_nonconforming_payload() {
+        let shapes = r#"
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            @prefix ex: <http://example.org/> .
+
+            ex:PayloadShape a sh:NodeShape ;
+                sh:targetClass ex:Payload ;
+                sh:property [
+                    sh:path ex:tenant ;
+                    sh:minCount 1 ;
+                    sh:pattern "^[a-z]+$" ;
+                ] ;
+                sh:property [
+                    sh:path ex:pattern_byte ;
+                    sh:datatype xsd:integer ;
+                    sh:maxCount 1 ;
+                ] .
+        "#;
+        let engine = ShaclEngine::from_turtle(shapes).unwrap();
+
+        let valid = serde_json::json!({"tenant": "acme", "pattern_byte": 1});
+        assert!(engine.validate_json(&valid).unwrap().conforms);
+
+        let invalid = serde_json::json!({"tenant": "ACME", "pattern_byte": 1});
+        let report = engine.validate_json(&invalid).unwrap();
+        assert!(!report.conforms);
+        assert_eq!(report.results.len(), 1);
+        assert!(report.to_turtle().contains("sh:PatternConstraintComponent"));
+    }
+}
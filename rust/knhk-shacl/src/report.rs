@@ -0,0 +1,121 @@
+//! W3C SHACL validation reports
+
+use crate::path::PropertyPath;
+use crate::SH;
+use oxigraph::model::{Literal, NamedNode, Term};
+use std::fmt::Write;
+
+/// Severity of a validation result (`sh:severity`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// `sh:Violation`
+    #[default]
+    Violation,
+    /// `sh:Warning`
+    Warning,
+    /// `sh:Info`
+    Info,
+}
+
+impl Severity {
+    /// SHACL IRI of this severity
+    pub fn iri(&self) -> String {
+        let local = match self {
+            Self::Violation => "Violation",
+            Self::Warning => "Warning",
+            Self::Info => "Info",
+        };
+        format!("{}{}", SH, local)
+    }
+
+    /// Parse a severity IRI (unknown IRIs are treated as violations)
+    pub fn from_iri(iri: &str) -> Self {
+        match iri.strip_prefix(SH) {
+            Some("Warning") => Self::Warning,
+            Some("Info") => Self::Info,
+            _ => Self::Violation,
+        }
+    }
+}
+
+/// One `sh:ValidationResult`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationResult {
+    /// Focus node that failed validation
+    pub focus_node: Term,
+    /// Path of the property shape that produced the result
+    pub result_path: Option<PropertyPath>,
+    /// Offending value node
+    pub value: Option<Term>,
+    /// Shape that produced the result
+    pub source_shape: Term,
+    /// Constraint component that produced the result
+    pub source_constraint_component: NamedNode,
+    /// Severity of the source shape
+    pub severity: Severity,
+    /// Human-readable message
+    pub message: Option<String>,
+}
+
+/// `sh:ValidationReport`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Whether the data graph conforms to the shapes graph
+    pub conforms: bool,
+    /// Validation results
+    pub results: Vec<ValidationResult>,
+}
+
+impl ValidationReport {
+    /// Build a report from results (`sh:conforms` is true when there are none)
+    pub fn new(results: Vec<ValidationResult>) -> Self {
+        Self {
+            conforms: results.is_empty(),
+            results,
+        }
+    }
+
+    /// Results with `sh:Violation` severity
+    pub fn violations(&self) -> impl Iterator<Item = &ValidationResult> {
+        self.results
+            .iter()
+            .filter(|result| result.severity == Severity::Violation)
+    }
+
+    /// Serialize the report graph as Turtle
+    pub fn to_turtle(&self) -> String {
+        let mut turtle = format!("@prefix sh: <{}> .\n\n", SH);
+        turtle.push_str("[] a sh:ValidationReport ;\n");
+        let _ = write!(turtle, "    sh:conforms {}", self.conforms);
+        for result in &self.results {
+            turtle.push_str(" ;\n    sh:result [\n        a sh:ValidationResult ;\n");
+            let _ = writeln!(turtle, "        sh:focusNode {} ;", result.focus_node);
+            if let Some(path) = &result.result_path {
+                let _ = writeln!(turtle, "        sh:resultPath {} ;", path.to_turtle());
+            }
+            if let Some(value) = &result.value {
+                let _ = writeln!(turtle, "        sh:value {} ;", value);
+            }
+            if let Some(message) = &result.message {
+                let _ = writeln!(
+                    turtle,
+                    "        sh:resultMessage {} ;",
+                    Literal::new_simple_literal(message.as_str())
+                );
+            }
+            let _ = writeln!(turtle, "        sh:sourceShape {} ;", result.source_shape);
+            let _ = writeln!(
+                turtle,
+                "        sh:sourceConstraintComponent {} ;",
+                result.source_constraint_component
+            );
+            let _ = write!(
+                turtle,
+                "        sh:resultSeverity <{}>\n    ]",
+                result.severity.iri()
+            );
+        }
+        turtle.push_str(" .\n");
+        turtle
+    }
+}
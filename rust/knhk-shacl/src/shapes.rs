@@ -0,0 +1,596 @@
+//! Shapes graph parsing
+//!
+//! Shapes are read once from a shapes graph into plain Rust structures; the
+//! engine never touches the shapes graph again while validating.
+
+use crate::error::{ShaclError, ShaclResult};
+use crate::graph::{sh, sort_terms, TripleIndex};
+use crate::path::PropertyPath;
+use crate::report::Severity;
+use oxigraph::io::RdfFormat;
+use oxigraph::model::vocab::{rdf, rdfs, xsd};
+use oxigraph::model::{NamedNode, Term};
+use oxigraph::store::Store;
+use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, HashSet};
+
+/// Focus node selector of a shape
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// `sh:targetNode`
+    Node(Term),
+    /// `sh:targetClass` (or an implicit class target)
+    Class(Term),
+    /// `sh:targetSubjectsOf`
+    SubjectsOf(NamedNode),
+    /// `sh:targetObjectsOf`
+    ObjectsOf(NamedNode),
+}
+
+/// Value of `sh:nodeKind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// `sh:IRI`
+    Iri,
+    /// `sh:BlankNode`
+    BlankNode,
+    /// `sh:Literal`
+    Literal,
+    /// `sh:BlankNodeOrIRI`
+    BlankNodeOrIri,
+    /// `sh:BlankNodeOrLiteral`
+    BlankNodeOrLiteral,
+    /// `sh:IRIOrLiteral`
+    IriOrLiteral,
+}
+
+impl NodeKind {
+    fn from_iri(iri: &str) -> Option<Self> {
+        match iri.strip_prefix(crate::SH)? {
+            "IRI" => Some(Self::Iri),
+            "BlankNode" => Some(Self::BlankNode),
+            "Literal" => Some(Self::Literal),
+            "BlankNodeOrIRI" => Some(Self::BlankNodeOrIri),
+            "BlankNodeOrLiteral" => Some(Self::BlankNodeOrLiteral),
+            "IRIOrLiteral" => Some(Self::IriOrLiteral),
+            _ => None,
+        }
+    }
+
+    /// Whether `term` has this node kind
+    pub fn matches(&self, term: &Term) -> bool {
+        let (iri, blank, literal) = match term {
+            Term::NamedNode(_) => (true, false, false),
+            Term::BlankNode(_) => (false, true, false),
+            Term::Literal(_) => (false, false, true),
+            #[allow(unreachable_patterns)]
+            _ => (false, false, false),
+        };
+        match self {
+            Self::Iri => iri,
+            Self::BlankNode => blank,
+            Self::Literal => literal,
+            Self::BlankNodeOrIri => blank || iri,
+            Self::BlankNodeOrLiteral => blank || literal,
+            Self::IriOrLiteral => iri || literal,
+        }
+    }
+}
+
+/// SPARQL-based constraint (`sh:sparql`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparqlConstraint {
+    /// SELECT query; `$this` is pre-bound to the focus node
+    pub select: String,
+    /// `sh:message` of the constraint, if any
+    pub message: Option<String>,
+    /// PREFIX declarations collected from `sh:prefixes`
+    pub prefixes: String,
+}
+
+/// Constraint component parameters of a shape
+#[derive(Debug, Clone)]
+pub enum Constraint {
+    /// `sh:class`
+    Class(Term),
+    /// `sh:datatype`
+    Datatype(NamedNode),
+    /// `sh:nodeKind`
+    NodeKind(NodeKind),
+    /// `sh:minCount`
+    MinCount(usize),
+    /// `sh:maxCount`
+    MaxCount(usize),
+    /// `sh:minLength`
+    MinLength(usize),
+    /// `sh:maxLength`
+    MaxLength(usize),
+    /// `sh:pattern` with optional `sh:flags`
+    Pattern {
+        /// Compiled pattern
+        regex: Regex,
+        /// Pattern as written in the shapes graph
+        source: String,
+    },
+    /// `sh:in`
+    In(Vec<Term>),
+    /// `sh:hasValue`
+    HasValue(Term),
+    /// `sh:node`
+    Node(Term),
+    /// `sh:property`
+    Property(Term),
+    /// `sh:and`
+    And(Vec<Term>),
+    /// `sh:or`
+    Or(Vec<Term>),
+    /// `sh:xone`
+    Xone(Vec<Term>),
+    /// `sh:not`
+    Not(Term),
+    /// `sh:sparql`
+    Sparql(SparqlConstraint),
+}
+
+impl Constraint {
+    /// IRI of the constraint component (`sh:sourceConstraintComponent`)
+    pub fn component(&self) -> NamedNode {
+        let local = match self {
+            Self::Class(_) => "ClassConstraintComponent",
+            Self::Datatype(_) => "DatatypeConstraintComponent",
+            Self::NodeKind(_) => "NodeKindConstraintComponent",
+            Self::MinCount(_) => "MinCountConstraintComponent",
+            Self::MaxCount(_) => "MaxCountConstraintComponent",
+            Self::MinLength(_) => "MinLengthConstraintComponent",
+            Self::MaxLength(_) => "MaxLengthConstraintComponent",
+            Self::Pattern { .. } => "PatternConstraintComponent",
+            Self::In(_) => "InConstraintComponent",
+            Self::HasValue(_) => "HasValueConstraintComponent",
+            Self::Node(_) => "NodeConstraintComponent",
+            Self::Property(_) => "PropertyConstraintComponent",
+            Self::And(_) => "AndConstraintComponent",
+            Self::Or(_) => "OrConstraintComponent",
+            Self::Xone(_) => "XoneConstraintComponent",
+            Self::Not(_) => "NotConstraintComponent",
+            Self::Sparql(_) => "SPARQLConstraintComponent",
+        };
+        sh(local)
+    }
+}
+
+/// Node shape or property shape
+#[derive(Debug, Clone)]
+pub struct Shape {
+    /// Shape node in the shapes graph
+    pub id: Term,
+    /// Targets declared on the shape
+    pub targets: Vec<Target>,
+    /// `sh:path` (present for property shapes)
+    pub path: Option<PropertyPath>,
+    /// Constraints declared on the shape
+    pub constraints: Vec<Constraint>,
+    /// `sh:severity` (defaults to `sh:Violation`)
+    pub severity: Severity,
+    /// `sh:message` values
+    pub messages: Vec<String>,
+    /// `sh:deactivated true`
+    pub deactivated: bool,
+}
+
+impl Shape {
+    /// Whether this is a property shape
+    pub fn is_property_shape(&self) -> bool {
+        self.path.is_some()
+    }
+
+    fn parse(index: &TripleIndex, id: &Term) -> ShaclResult<Self> {
+        let values = |local: &str| index.objects(id, sh(local).as_ref());
+
+        let mut targets: Vec<Target> = Vec::new();
+        targets.extend(values("targetNode").iter().cloned().map(Target::Node));
+        targets.extend(values("targetClass").iter().cloned().map(Target::Class));
+        if index.contains(id, rdf::TYPE, &Term::from(rdfs::CLASS.into_owned())) {
+            targets.push(Target::Class(id.clone()));
+        }
+        for predicate in values("targetSubjectsOf") {
+            targets.push(Target::SubjectsOf(named(predicate, "sh:targetSubjectsOf")?));
+        }
+        for predicate in values("targetObjectsOf") {
+            targets.push(Target::ObjectsOf(named(predicate, "sh:targetObjectsOf")?));
+        }
+
+        let path = values("path")
+            .first()
+            .map(|path| PropertyPath::parse(index, path))
+            .transpose()?;
+
+        let mut constraints = Vec::new();
+        constraints.extend(values("class").iter().cloned().map(Constraint::Class));
+        for datatype in values("datatype") {
+            constraints.push(Constraint::Datatype(named(datatype, "sh:datatype")?));
+        }
+        for kind in values("nodeKind") {
+            let kind = match kind {
+                Term::NamedNode(iri) => NodeKind::from_iri(iri.as_str()),
+                _ => None,
+            }
+            .ok_or_else(|| ShaclError::InvalidShape(format!("Unknown sh:nodeKind {}", kind)))?;
+            constraints.push(Constraint::NodeKind(kind));
+        }
+        for (local, build) in [
+            ("minCount", Constraint::MinCount as fn(usize) -> Constraint),
+            ("maxCount", Constraint::MaxCount),
+            ("minLength", Constraint::MinLength),
+            ("maxLength", Constraint::MaxLength),
+        ] {
+            for value in values(local) {
+                constraints.push(build(integer(value, local)?));
+            }
+        }
+        if let Some(flags) = values("flags").first() {
+            if values("pattern").is_empty() {
+                return Err(ShaclError::InvalidShape(format!(
+                    "{} declares sh:flags {} without sh:pattern",
+                    id, flags
+                )));
+            }
+        }
+        for pattern in values("pattern") {
+            let source = string(pattern, "sh:pattern")?;
+            let flags = values("flags")
+                .first()
+                .map(|flags| string(flags, "sh:flags"))
+                .transpose()?
+                .unwrap_or_default();
+            let regex = RegexBuilder::new(&source)
+                .case_insensitive(flags.contains('i'))
+                .multi_line(flags.contains('m'))
+                .dot_matches_new_line(flags.contains('s'))
+                .ignore_whitespace(flags.contains('x'))
+                .build()
+                .map_err(|e| {
+                    ShaclError::InvalidShape(format!("Invalid sh:pattern {:?}: {}", source, e))
+                })?;
+            constraints.push(Constraint::Pattern { regex, source });
+        }
+        for list in values("in") {
+            constraints.push(Constraint::In(index.list(list)?));
+        }
+        constraints.extend(values("hasValue").iter().cloned().map(Constraint::HasValue));
+        constraints.extend(values("node").iter().cloned().map(Constraint::Node));
+        constraints.extend(values("property").iter().cloned().map(Constraint::Property));
+        for list in values("and") {
+            constraints.push(Constraint::And(index.list(list)?));
+        }
+        for list in values("or") {
+            constraints.push(Constraint::Or(index.list(list)?));
+        }
+        for list in values("xone") {
+            constraints.push(Constraint::Xone(index.list(list)?));
+        }
+        constraints.extend(values("not").iter().cloned().map(Constraint::Not));
+        for sparql in values("sparql") {
+            if is_deactivated(index, sparql) {
+                continue;
+            }
+            let select = index
+                .object(sparql, sh("select").as_ref())
+                .ok_or_else(|| {
+                    ShaclError::InvalidShape(format!(
+                        "SPARQL constraint {} has no sh:select",
+                        sparql
+                    ))
+                })
+                .and_then(|select| string(select, "sh:select"))?;
+            let message = index
+                .object(sparql, sh("message").as_ref())
+                .map(|message| string(message, "sh:message"))
+                .transpose()?;
+            constraints.push(Constraint::Sparql(SparqlConstraint {
+                select,
+                message,
+                prefixes: prefixes(index, sparql)?,
+            }));
+        }
+
+        let severity = match values("severity").first() {
+            Some(Term::NamedNode(iri)) => Severity::from_iri(iri.as_str()),
+            _ => Severity::default(),
+        };
+        let messages = values("message")
+            .iter()
+            .map(|message| string(message, "sh:message"))
+            .collect::<ShaclResult<Vec<_>>>()?;
+
+        Ok(Self {
+            id: id.clone(),
+            targets,
+            path,
+            constraints,
+            severity,
+            messages,
+            deactivated: is_deactivated(index, id),
+        })
+    }
+}
+
+/// Parsed shapes graph
+#[derive(Debug, Clone, Default)]
+pub struct ShapesGraph {
+    shapes: HashMap<Term, Shape>,
+    order: Vec<Term>,
+}
+
+impl ShapesGraph {
+    /// Parse a shapes graph from Turtle
+    pub fn from_turtle(turtle: &str) -> ShaclResult<Self> {
+        let store = Store::new().map_err(|e| ShaclError::Storage(e.to_string()))?;
+        store
+            .load_from_reader(RdfFormat::Turtle, turtle.as_bytes())
+            .map_err(|e| ShaclError::Parse(e.to_string()))?;
+        Self::from_store(&store)
+    }
+
+    /// Parse a shapes graph already loaded into a store
+    pub fn from_store(store: &Store) -> ShaclResult<Self> {
+        let index = TripleIndex::from_store(store)?;
+
+        let mut roots = Vec::new();
+        for class in ["NodeShape", "PropertyShape"] {
+            roots.extend(
+                index
+                    .subjects(rdf::TYPE, &Term::from(sh(class)))
+                    .iter()
+                    .cloned(),
+            );
+        }
+        for local in [
+            "targetNode",
+            "targetClass",
+            "targetSubjectsOf",
+            "targetObjectsOf",
+            "path",
+        ] {
+            roots.extend(index.subjects_of(sh(local).as_ref()));
+        }
+        sort_terms(&mut roots);
+
+        let mut graph = Self::default();
+        let mut seen = HashSet::new();
+        let mut pending = roots;
+        pending.reverse();
+        while let Some(id) = pending.pop() {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            let shape = Shape::parse(&index, &id)?;
+            let mut referenced = Vec::new();
+            for constraint in &shape.constraints {
+                match constraint {
+                    Constraint::Node(id) | Constraint::Property(id) | Constraint::Not(id) => {
+                        referenced.push(id.clone())
+                    }
+                    Constraint::And(ids) | Constraint::Or(ids) | Constraint::Xone(ids) => {
+                        referenced.extend(ids.iter().cloned())
+                    }
+                    _ => {}
+                }
+            }
+            pending.extend(referenced.into_iter().rev());
+            graph.order.push(id.clone());
+            graph.shapes.insert(id, shape);
+        }
+        Ok(graph)
+    }
+
+    /// Look up a shape by its node
+    pub fn get(&self, id: &Term) -> Option<&Shape> {
+        self.shapes.get(id)
+    }
+
+    /// All shapes, in discovery order
+    pub fn iter(&self) -> impl Iterator<Item = &Shape> {
+        self.order.iter().filter_map(|id| self.shapes.get(id))
+    }
+
+    /// Number of shapes
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Whether the shapes graph declares no shapes
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+fn is_deactivated(index: &TripleIndex, node: &Term) -> bool {
+    matches!(
+        index.object(node, sh("deactivated").as_ref()),
+        Some(Term::Literal(literal)) if literal.value() == "true"
+    )
+}
+
+fn named(term: &Term, parameter: &str) -> ShaclResult<NamedNode> {
+    match term {
+        Term::NamedNode(node) => Ok(node.clone()),
+        _ => Err(ShaclError::InvalidShape(format!(
+            "{} value {} must be an IRI",
+            parameter, term
+        ))),
+    }
+}
+
+fn string(term: &Term, parameter: &str) -> ShaclResult<String> {
+    match term {
+        Term::Literal(literal) => Ok(literal.value().to_string()),
+        _ => Err(ShaclError::InvalidShape(format!(
+            "{} value {} must be a literal",
+            parameter, term
+        ))),
+    }
+}
+
+fn integer(term: &Term, parameter: &str) -> ShaclResult<usize> {
+    match term {
+        Term::Literal(literal) if literal.datatype() == xsd::INTEGER => {
+            literal.value().parse().map_err(|_| {
+                ShaclError::InvalidShape(format!("sh:{} value {} is not a count", parameter, term))
+            })
+        }
+        _ => Err(ShaclError::InvalidShape(format!(
+            "sh:{} value {} must be an xsd:integer",
+            parameter, term
+        ))),
+    }
+}
+
+/// PREFIX declarations reachable through `sh:prefixes/sh:declare`
+fn prefixes(index: &TripleIndex, sparql: &Term) -> ShaclResult<String> {
+    let mut declarations = String::new();
+    for ontology in index.objects(sparql, sh("prefixes").as_ref()) {
+        for declaration in index.objects(ontology, sh("declare").as_ref()) {
+            let prefix = index
+                .object(declaration, sh("prefix").as_ref())
+                .map(|prefix| string(prefix, "sh:prefix"))
+                .transpose()?;
+            let namespace = index
+                .object(declaration, sh("namespace").as_ref())
+                .map(|namespace| string(namespace, "sh:namespace"))
+                .transpose()?;
+            match (prefix, namespace) {
+                (Some(prefix), Some(namespace)) => {
+                    declarations.push_str(&format!("PREFIX {}: <{}>\n", prefix, namespace));
+                }
+                _ => {
+                    return Err(ShaclError::InvalidShape(format!(
+                        "Prefix declaration {} needs sh:prefix and sh:namespace",
+                        declaration
+                    )))
+                }
+            }
+        }
+    }
+    Ok(declarations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREFIXES: &str = r#"
+        @prefix sh: <http://www.w3.org/ns/shacl#> .
+        @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+        @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+        @prefix ex: <http://example.org/> .
+    "#;
+
+    fn parse(shapes: &str) -> ShaclResult<ShapesGraph> {
+        ShapesGraph::from_turtle(&format!("{}{}", PREFIXES, shapes))
+    }
+
+    fn shape<'a>(graph: &'a ShapesGraph, local: &str) -> &'a Shape {
+        let id = Term::from(NamedNode::new_unchecked(format!(
+            "http://example.org/{}",
+            local
+        )));
+        graph
+            .get(&id)
+            .unwrap_or_else(|| panic!("shape ex:{} should be parsed", local))
+    }
+
+    #[test]
+    fn in_list_is_read_in_order() {
+        let graph = parse(r#"ex:S sh:targetNode ex:a ; sh:in ( ex:x ex:y ex:z ) ."#)
+            .unwrap_or_else(|e| panic!("shapes should parse: {}", e));
+        let members = match shape(&graph, "S").constraints.as_slice() {
+            [Constraint::In(members)] => members.clone(),
+            other => panic!("expected a single sh:in constraint, got {:?}", other),
+        };
+        let expected: Vec<Term> = ["x", "y", "z"]
+            .iter()
+            .map(|local| {
+                Term::from(NamedNode::new_unchecked(format!(
+                    "http://example.org/{}",
+                    local
+                )))
+            })
+            .collect();
+        assert_eq!(members, expected);
+    }
+
+    #[test]
+    fn list_without_rest_is_rejected() {
+        let result = parse(
+            r#"ex:S sh:targetNode ex:a ; sh:in _:head .
+               _:head rdf:first ex:x ."#,
+        );
+        assert!(matches!(result, Err(ShaclError::InvalidShape(_))));
+    }
+
+    #[test]
+    fn list_without_first_is_rejected() {
+        let result = parse(
+            r#"ex:S sh:targetNode ex:a ; sh:or _:head .
+               _:head rdf:rest rdf:nil ."#,
+        );
+        assert!(matches!(result, Err(ShaclError::InvalidShape(_))));
+    }
+
+    #[test]
+    fn cyclic_list_is_rejected() {
+        let result = parse(
+            r#"ex:S sh:targetNode ex:a ; sh:in _:one .
+               _:one rdf:first ex:x ; rdf:rest _:two .
+               _:two rdf:first ex:y ; rdf:rest _:one ."#,
+        );
+        match result {
+            Err(ShaclError::InvalidShape(message)) => assert!(message.contains("Cyclic")),
+            other => panic!("expected a cyclic list error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_constraint_components_are_ignored() {
+        let graph = parse(
+            r#"ex:S sh:targetNode ex:a ;
+                   sh:fooBar 3 ;
+                   ex:customConstraint "ignored" ;
+                   sh:minCount 1 ."#,
+        )
+        .unwrap_or_else(|e| panic!("shapes should parse: {}", e));
+        let components: Vec<NamedNode> = shape(&graph, "S")
+            .constraints
+            .iter()
+            .map(Constraint::component)
+            .collect();
+        assert_eq!(components, vec![sh("MinCountConstraintComponent")]);
+    }
+
+    #[test]
+    fn unknown_node_kind_is_rejected() {
+        let result = parse(r#"ex:S sh:targetNode ex:a ; sh:nodeKind sh:Resource ."#);
+        match result {
+            Err(ShaclError::InvalidShape(message)) => assert!(message.contains("sh:nodeKind")),
+            other => panic!("expected an unknown node kind error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn count_must_be_an_integer() {
+        let result = parse(r#"ex:S sh:targetNode ex:a ; sh:minCount "1" ."#);
+        assert!(matches!(result, Err(ShaclError::InvalidShape(_))));
+    }
+
+    #[test]
+    fn flags_without_pattern_are_rejected() {
+        let result = parse(r#"ex:S sh:targetNode ex:a ; sh:flags "i" ."#);
+        assert!(matches!(result, Err(ShaclError::InvalidShape(_))));
+    }
+
+    #[test]
+    fn sparql_constraint_requires_select() {
+        let result = parse(r#"ex:S sh:targetNode ex:a ; sh:sparql [ sh:message "no query" ] ."#);
+        assert!(matches!(result, Err(ShaclError::InvalidShape(_))));
+    }
+}
@@ -7,6 +7,7 @@
 
 use crate::graph::WarmPathGraph;
 use crate::query::{AskResult, ConstructResult, DescribeResult, SelectResult};
+use crate::update::UpdateSummary;
 // Path selector removed - use simple routing logic instead
 use std::sync::Arc;
 
@@ -102,6 +103,11 @@ impl WarmPathExecutor {
         }
     }
 
+    /// Execute a SPARQL 1.1 Update request against the warm path graph
+    pub fn execute_update(&self, sparql: &str) -> Result<UpdateSummary, String> {
+        self.graph.update(sparql)
+    }
+
     /// Load RDF data into graph
     pub fn load_rdf(&self, turtle_data: &str) -> Result<(), String> {
         self.graph.load_from_turtle(turtle_data)
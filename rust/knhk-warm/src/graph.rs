@@ -37,8 +37,13 @@ use oxigraph::model::{GraphName, NamedNode, Quad, Term};
 use oxigraph::sparql::{Query, QueryResults};
 #[cfg(feature = "rdf")]
 use oxigraph::store::Store;
+
+#[cfg(feature = "rdf")]
+use crate::update::{
+    parse_update, GraphTransaction, QueryFootprint, UpdateOperation, UpdateSummary,
+};
 use serde_json::Value as JsonValue;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 #[cfg(feature = "rdf")]
 use std::fs::File;
 use std::hash::{Hash, Hasher};
@@ -85,14 +90,31 @@ impl CachedResult {
     }
 }
 
+/// Cached result together with the predicates it was computed from
+#[cfg(feature = "rdf")]
+struct CacheEntry {
+    result: CachedResult,
+    footprint: QueryFootprint,
+}
+
+/// Quads actually changed by one committed write
+#[cfg(feature = "rdf")]
+#[derive(Default)]
+struct AppliedChange {
+    inserted: Vec<Quad>,
+    removed: Vec<Quad>,
+}
+
 /// Thread-safe Oxigraph wrapper with SPARQL caching. Clone is cheap (shared store).
 /// Following ggen's Graph wrapper pattern.
 #[cfg(feature = "rdf")]
 pub struct WarmPathGraph {
     inner: Store,
     epoch: Arc<AtomicU64>,
-    query_cache: Arc<Mutex<lru::LruCache<(u64, u64), CachedResult>>>,
+    query_cache: Arc<Mutex<lru::LruCache<u64, CacheEntry>>>,
     query_plan_cache: Arc<Mutex<lru::LruCache<u64, Query>>>,
+    /// Serializes transactional writers so change sets are computed against a stable state
+    write_lock: Arc<Mutex<()>>,
     #[cfg(feature = "otel")]
     query_count: Arc<AtomicU64>,
     #[cfg(feature = "otel")]
@@ -122,6 +144,7 @@ impl WarmPathGraph {
             epoch: Arc::new(AtomicU64::new(1)),
             query_cache: Arc::new(Mutex::new(lru::LruCache::new(query_cache_size))),
             query_plan_cache: Arc::new(Mutex::new(lru::LruCache::new(plan_cache_size))),
+            write_lock: Arc::new(Mutex::new(())),
             #[cfg(feature = "otel")]
             query_count: Arc::new(AtomicU64::new(0)),
             #[cfg(feature = "otel")]
@@ -160,9 +183,17 @@ impl WarmPathGraph {
         self.epoch.load(Ordering::Relaxed)
     }
 
+    /// Current graph epoch (incremented once per committed write)
+    pub fn epoch(&self) -> u64 {
+        self.current_epoch()
+    }
+
     /// Bump epoch (invalidates cache)
     pub fn bump_epoch(&self) {
         self.epoch.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut cache) = self.query_cache.lock() {
+            cache.clear();
+        }
     }
 
     /// Hash SPARQL query for cache key
@@ -172,18 +203,81 @@ impl WarmPathGraph {
         hasher.finish()
     }
 
-    /// Materialize QueryResults into CachedResult
+    /// Materialize QueryResults into CachedResult (consumes the result iterators)
+    fn materialize_results(results: QueryResults<'_>) -> Result<CachedResult, String> {
+        match results {
+            QueryResults::Boolean(b) => Ok(CachedResult::Boolean(b)),
+            QueryResults::Solutions(solutions) => {
+                let mut rows = Vec::new();
+                for solution in solutions {
+                    let solution = solution.map_err(|e| format!("Solution error: {}", e))?;
+                    rows.push(
+                        solution
+                            .iter()
+                            .map(|(var, term)| (var.as_str().to_string(), term.to_string()))
+                            .collect(),
+                    );
+                }
+                Ok(CachedResult::Solutions(rows))
+            }
+            QueryResults::Graph(triples) => triples
+                .map(|t| {
+                    t.map(|triple| triple.to_string())
+                        .map_err(|e| e.to_string())
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(CachedResult::Graph),
+        }
+    }
+
+    /// Execute SPARQL query and cache the materialized result
     ///
-    /// Note: Currently disabled due to oxigraph iterator ownership issues.
-    /// QueryResults contain iterators that must be consumed, but this function
-    /// receives a &QueryResults. Full caching would require cloning results
-    /// or changing the API design.
-    fn materialize_results(&self, _results: &QueryResults) -> Result<CachedResult, String> {
-        // Disabled for now - cache conversion not fully implemented
-        Err("Result caching temporarily disabled".to_string())
+    /// Cached entries survive transactional writes that do not touch any
+    /// predicate the query reads (see [`QueryFootprint`]).
+    pub fn query_cached(&self, sparql: &str) -> Result<CachedResult, String> {
+        let query_hash = self.hash_query(sparql);
+
+        if let Ok(mut cache) = self.query_cache.lock() {
+            if let Some(entry) = cache.get(&query_hash) {
+                #[cfg(feature = "otel")]
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(entry.result.clone());
+            }
+        }
+
+        #[cfg(feature = "otel")]
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+
+        let epoch = self.current_epoch();
+        let result = Self::materialize_results(self.query(sparql)?)?;
+
+        // Writers bump the epoch before evicting, so a result computed against
+        // a state that changed meanwhile is never inserted
+        if let Ok(mut cache) = self.query_cache.lock() {
+            if self.current_epoch() == epoch {
+                cache.put(
+                    query_hash,
+                    CacheEntry {
+                        result: result.clone(),
+                        footprint: QueryFootprint::of_query(sparql),
+                    },
+                );
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Whether a result for this query is currently cached
+    pub fn is_cached(&self, sparql: &str) -> bool {
+        let query_hash = self.hash_query(sparql);
+        self.query_cache
+            .lock()
+            .map(|cache| cache.contains(&query_hash))
+            .unwrap_or(false)
     }
 
-    /// Execute SPARQL query with caching
+    /// Execute SPARQL query
     ///
     /// Uses query plan cache to avoid re-parsing identical queries
     pub fn query(&self, sparql: &str) -> Result<QueryResults<'_>, String> {
@@ -194,26 +288,8 @@ impl WarmPathGraph {
         self.query_count.fetch_add(1, Ordering::Relaxed);
 
         let query_hash = self.hash_query(sparql);
-        let current_epoch = self.current_epoch();
-
-        // Check result cache
-        let _cache_hit = if let Ok(mut cache) = self.query_cache.lock() {
-            if let Some(cached) = cache.get(&(query_hash, current_epoch)) {
-                #[cfg(feature = "otel")]
-                self.cache_hits.fetch_add(1, Ordering::Relaxed);
-                // Convert cached result back to QueryResults
-                return self.cached_to_query_results(cached.clone());
-            } else {
-                false
-            }
-        } else {
-            false
-        };
-
-        #[cfg(feature = "otel")]
-        if !cache_hit {
-            self.cache_misses.fetch_add(1, Ordering::Relaxed);
-        }
+        // Streaming results are not cached; see query_cached()
+        let cache_hit = false;
 
         // Check query plan cache - reuse parsed query if available
         let parsed_query = if let Ok(mut plan_cache) = self.query_plan_cache.lock() {
@@ -249,11 +325,6 @@ impl WarmPathGraph {
                 .map_err(|e| format!("SPARQL query execution failed: {}", e))?
         };
 
-        // Cache result (currently disabled - see materialize_results comment)
-        // FUTURE: Implement proper result caching with ownership handling
-        // (Currently cloning results - optimization opportunity for future releases)
-        let _ = self.materialize_results(&results); // Intentionally ignored
-
         #[cfg(feature = "otel")]
         {
             let latency_ms = start_time.elapsed().as_millis() as u64;
@@ -348,23 +419,19 @@ impl WarmPathGraph {
         }
     }
 
-    /// Convert cached result back to QueryResults
-    /// Note: This is a simplified conversion - full conversion would require
-    /// storing more information in the cache
-    fn cached_to_query_results(&self, _cached: CachedResult) -> Result<QueryResults<'_>, String> {
-        // For now, return error to force re-execution
-        // In production, would store full QueryResults or reconstruct
-        Err("Cache conversion not fully implemented".to_string())
-    }
-
-    /// Insert a single triple
-    pub fn insert_triple(&self, s: &str, p: &str, o: &str) -> Result<(), String> {
+    /// Build a default-graph quad from subject/predicate IRIs and an object term
+    pub(crate) fn triple_to_quad(s: &str, p: &str, o: &str) -> Result<Quad, String> {
         let s_node = NamedNode::new(s).map_err(|e| format!("Invalid subject IRI {}: {}", s, e))?;
         let p_node =
             NamedNode::new(p).map_err(|e| format!("Invalid predicate IRI {}: {}", p, e))?;
         let o_term = Self::parse_term(o)?;
 
-        let quad = Quad::new(s_node, p_node, o_term, GraphName::DefaultGraph);
+        Ok(Quad::new(s_node, p_node, o_term, GraphName::DefaultGraph))
+    }
+
+    /// Insert a single triple
+    pub fn insert_triple(&self, s: &str, p: &str, o: &str) -> Result<(), String> {
+        let quad = Self::triple_to_quad(s, p, o)?;
         self.inner
             .insert(&quad)
             .map_err(|e| format!("Failed to insert triple: {}", e))?;
@@ -406,6 +473,252 @@ impl WarmPathGraph {
         Ok(())
     }
 
+    /// Delete a single triple (no-op if absent)
+    pub fn delete_triple(&self, s: &str, p: &str, o: &str) -> Result<UpdateSummary, String> {
+        let mut transaction = GraphTransaction::new();
+        transaction.delete_triple(s, p, o)?;
+        self.apply(transaction)
+    }
+
+    /// Batch delete quads atomically
+    pub fn remove_quads(&self, quads: &[Quad]) -> Result<UpdateSummary, String> {
+        let mut transaction = GraphTransaction::new();
+        for quad in quads {
+            transaction.delete(quad.clone());
+        }
+        self.apply(transaction)
+    }
+
+    /// Apply a batch of deletes and inserts atomically
+    ///
+    /// The epoch is bumped once if anything changed, and only cached query
+    /// results reading one of the changed predicates are evicted.
+    pub fn apply(&self, transaction: GraphTransaction) -> Result<UpdateSummary, String> {
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| "Write lock poisoned".to_string())?;
+
+        let change = self.commit_locked(&[(transaction.deletes(), transaction.inserts())])?;
+        Ok(self.publish_changes(&[change]))
+    }
+
+    /// Execute a SPARQL 1.1 Update request
+    ///
+    /// All operations of the request are committed in a single store
+    /// transaction, so readers never see a partially applied request. The
+    /// templates of every operation are evaluated before anything is written;
+    /// when an operation's WHERE pattern reads predicates an earlier operation
+    /// of the request changes, it must see those changes, and the request is
+    /// delegated to oxigraph (also one transaction, but flushing the cache).
+    pub fn update(&self, sparql: &str) -> Result<UpdateSummary, String> {
+        let operations = parse_update(sparql)?;
+
+        let guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| "Write lock poisoned".to_string())?;
+
+        let mut batches = Vec::with_capacity(operations.len());
+        let mut written_predicates = BTreeSet::new();
+        for operation in &operations {
+            let UpdateOperation::Modify {
+                delete,
+                insert,
+                reads,
+            } = operation
+            else {
+                drop(guard);
+                return self.update_native(sparql);
+            };
+            if reads.affected_by(&written_predicates) {
+                drop(guard);
+                return self.update_native(sparql);
+            }
+
+            let deletes = self
+                .construct_quads(delete.as_deref())
+                .map_err(|e| format!("SPARQL update failed: {}", e))?;
+            let inserts = self
+                .construct_quads(insert.as_deref())
+                .map_err(|e| format!("SPARQL update failed: {}", e))?;
+            written_predicates.extend(
+                deletes
+                    .iter()
+                    .chain(inserts.iter())
+                    .map(|quad| quad.predicate.as_str().to_string()),
+            );
+            batches.push((deletes, inserts));
+        }
+
+        let batches: Vec<(&[Quad], &[Quad])> = batches
+            .iter()
+            .map(|(deletes, inserts)| (deletes.as_slice(), inserts.as_slice()))
+            .collect();
+        let change = self
+            .commit_locked(&batches)
+            .map_err(|e| format!("SPARQL update failed: {}", e))?;
+        Ok(self.publish_changes(&[change]))
+    }
+
+    /// Delegate an update to oxigraph and flush the whole cache
+    fn update_native(&self, sparql: &str) -> Result<UpdateSummary, String> {
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| "Write lock poisoned".to_string())?;
+
+        let before = self.size();
+        self.inner
+            .update(sparql)
+            .map_err(|e| format!("SPARQL update failed: {}", e))?;
+        let after = self.size();
+
+        let invalidated = self.query_cache.lock().map(|c| c.len()).unwrap_or(0);
+        self.bump_epoch();
+
+        Ok(UpdateSummary {
+            inserted: after.saturating_sub(before),
+            removed: before.saturating_sub(after),
+            epoch: self.current_epoch(),
+            invalidated,
+            full_invalidation: true,
+        })
+    }
+
+    /// Evaluate a CONSTRUCT query into default-graph quads
+    fn construct_quads(&self, construct: Option<&str>) -> Result<Vec<Quad>, String> {
+        let Some(construct) = construct else {
+            return Ok(Vec::new());
+        };
+
+        match self
+            .inner
+            .query(construct)
+            .map_err(|e| format!("Template evaluation failed: {}", e))?
+        {
+            QueryResults::Graph(triples) => triples
+                .map(|t| {
+                    t.map(|triple| {
+                        Quad::new(
+                            triple.subject,
+                            triple.predicate,
+                            triple.object,
+                            GraphName::DefaultGraph,
+                        )
+                    })
+                    .map_err(|e| format!("Template evaluation failed: {}", e))
+                })
+                .collect(),
+            _ => Err("Template evaluation did not produce a graph".to_string()),
+        }
+    }
+
+    /// Write the net effect of delete/insert batches in a single store transaction
+    ///
+    /// Batches apply in order, deletes before inserts within a batch. Only
+    /// quads whose presence actually changes are written and reported.
+    /// Caller must hold `write_lock`.
+    fn commit_locked(&self, batches: &[(&[Quad], &[Quad])]) -> Result<AppliedChange, String> {
+        // Presence before the request and after the batches, in first-touched order
+        let mut touched: Vec<&Quad> = Vec::new();
+        let mut presence: HashMap<&Quad, (bool, bool)> = HashMap::new();
+
+        for (deletes, inserts) in batches {
+            let insert_set: HashSet<&Quad> = inserts.iter().collect();
+            let updates = deletes
+                .iter()
+                .filter(|quad| !insert_set.contains(quad))
+                .map(|quad| (quad, false))
+                .chain(inserts.iter().map(|quad| (quad, true)));
+            for (quad, present) in updates {
+                match presence.get_mut(quad) {
+                    Some(state) => state.1 = present,
+                    None => {
+                        let before = self
+                            .inner
+                            .contains(quad)
+                            .map_err(|e| format!("Failed to read store: {}", e))?;
+                        presence.insert(quad, (before, present));
+                        touched.push(quad);
+                    }
+                }
+            }
+        }
+
+        let mut change = AppliedChange::default();
+        for quad in touched {
+            match presence.get(quad) {
+                Some((true, false)) => change.removed.push(quad.clone()),
+                Some((false, true)) => change.inserted.push(quad.clone()),
+                _ => {}
+            }
+        }
+
+        if change.inserted.is_empty() && change.removed.is_empty() {
+            return Ok(change);
+        }
+
+        let mut transaction = self
+            .inner
+            .start_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        for quad in &change.removed {
+            transaction.remove(quad);
+        }
+        for quad in &change.inserted {
+            transaction.insert(quad);
+        }
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(change)
+    }
+
+    /// Bump the epoch once and evict cached results affected by the changes
+    fn publish_changes(&self, applied: &[AppliedChange]) -> UpdateSummary {
+        let inserted = applied.iter().map(|c| c.inserted.len()).sum();
+        let removed = applied.iter().map(|c| c.removed.len()).sum();
+        let changed_predicates: BTreeSet<String> = applied
+            .iter()
+            .flat_map(|c| c.inserted.iter().chain(c.removed.iter()))
+            .map(|quad| quad.predicate.as_str().to_string())
+            .collect();
+
+        if changed_predicates.is_empty() {
+            return UpdateSummary {
+                epoch: self.current_epoch(),
+                ..UpdateSummary::default()
+            };
+        }
+
+        // Bump before evicting: concurrent query_cached() calls that started
+        // on the old state will then refuse to insert their result
+        self.epoch.fetch_add(1, Ordering::Relaxed);
+
+        let mut invalidated = 0;
+        if let Ok(mut cache) = self.query_cache.lock() {
+            let stale: Vec<u64> = cache
+                .iter()
+                .filter(|(_, entry)| entry.footprint.affected_by(&changed_predicates))
+                .map(|(key, _)| *key)
+                .collect();
+            invalidated = stale.len();
+            for key in stale {
+                cache.pop(&key);
+            }
+        }
+
+        UpdateSummary {
+            inserted,
+            removed,
+            epoch: self.current_epoch(),
+            invalidated,
+            full_invalidation: false,
+        }
+    }
+
     /// Get graph size (number of quads)
     pub fn size(&self) -> usize {
         // Store::len() returns Result<usize, StorageError> in newer oxigraph
@@ -442,6 +755,7 @@ impl Default for WarmPathGraph {
                 epoch: Arc::new(AtomicU64::new(1)),
                 query_cache: Arc::new(Mutex::new(lru::LruCache::new(query_cache_size))),
                 query_plan_cache: Arc::new(Mutex::new(lru::LruCache::new(plan_cache_size))),
+                write_lock: Arc::new(Mutex::new(())),
                 #[cfg(feature = "otel")]
                 query_count: Arc::new(AtomicU64::new(0)),
                 #[cfg(feature = "otel")]
@@ -30,6 +30,8 @@ pub mod kernel;
 #[cfg(feature = "rdf")]
 pub mod query;
 pub mod scheduler;
+#[cfg(feature = "rdf")]
+pub mod update;
 pub mod warm_path;
 
 #[cfg(feature = "rdf")]
@@ -39,6 +41,8 @@ pub use graph::WarmPathGraph;
 #[cfg(feature = "rdf")]
 pub use query::*;
 pub use scheduler::{EpochPlan, EpochScheduler, ExecutionPlan};
+#[cfg(feature = "rdf")]
+pub use update::{GraphTransaction, QueryFootprint, UpdateSummary};
 pub use warm_path::*;
 // Hot path types are re-exported from ffi module
 pub use ffi::{Ctx, Ir, Op, Receipt, Run};
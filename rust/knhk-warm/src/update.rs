@@ -0,0 +1,863 @@
+//! SPARQL 1.1 Update and transactional writes for the warm path graph
+//! Batches of inserts/deletes are applied atomically and only the cached
+//! query results whose predicates were touched are invalidated.
+//!
+//! This module is only available with the `rdf` feature enabled.
+//!
+//! ## Update evaluation
+//!
+//! `INSERT DATA`, `DELETE DATA`, `DELETE WHERE` and `DELETE/INSERT ... WHERE`
+//! operations on the default graph are evaluated by turning each template into
+//! a `CONSTRUCT` query over the current state, so the exact set of changed
+//! quads is known before anything is written. Graph management operations
+//! (`LOAD`, `CLEAR`, `DROP`, ...), `WITH`/`USING` and `GRAPH` templates are
+//! delegated to oxigraph and invalidate the whole cache.
+
+#![cfg(feature = "rdf")]
+
+use crate::graph::WarmPathGraph;
+use oxigraph::model::Quad;
+use std::collections::{BTreeSet, HashMap};
+
+/// rdf:type, the predicate behind the `a` keyword
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+
+/// Batch of quad deletions and insertions applied atomically
+///
+/// Deletions are applied before insertions, matching SPARQL `DELETE/INSERT`
+/// semantics: a quad that is both deleted and inserted ends up present.
+#[derive(Debug, Clone, Default)]
+pub struct GraphTransaction {
+    deletes: Vec<Quad>,
+    inserts: Vec<Quad>,
+}
+
+impl GraphTransaction {
+    /// Create an empty transaction
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a quad for insertion
+    pub fn insert(&mut self, quad: Quad) -> &mut Self {
+        self.inserts.push(quad);
+        self
+    }
+
+    /// Queue a quad for deletion
+    pub fn delete(&mut self, quad: Quad) -> &mut Self {
+        self.deletes.push(quad);
+        self
+    }
+
+    /// Queue a default-graph triple for insertion
+    pub fn insert_triple(&mut self, s: &str, p: &str, o: &str) -> Result<&mut Self, String> {
+        let quad = WarmPathGraph::triple_to_quad(s, p, o)?;
+        Ok(self.insert(quad))
+    }
+
+    /// Queue a default-graph triple for deletion
+    pub fn delete_triple(&mut self, s: &str, p: &str, o: &str) -> Result<&mut Self, String> {
+        let quad = WarmPathGraph::triple_to_quad(s, p, o)?;
+        Ok(self.delete(quad))
+    }
+
+    /// Quads queued for insertion
+    pub fn inserts(&self) -> &[Quad] {
+        &self.inserts
+    }
+
+    /// Quads queued for deletion
+    pub fn deletes(&self) -> &[Quad] {
+        &self.deletes
+    }
+
+    /// Number of queued operations
+    pub fn len(&self) -> usize {
+        self.inserts.len() + self.deletes.len()
+    }
+
+    /// True if nothing is queued
+    pub fn is_empty(&self) -> bool {
+        self.inserts.is_empty() && self.deletes.is_empty()
+    }
+}
+
+/// Outcome of a committed transaction or SPARQL update
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpdateSummary {
+    /// Quads that were absent and are now present
+    pub inserted: usize,
+    /// Quads that were present and are now absent
+    pub removed: usize,
+    /// Graph epoch after the write (unchanged if nothing changed)
+    pub epoch: u64,
+    /// Cached query results evicted by this write
+    pub invalidated: usize,
+    /// The write was delegated to oxigraph and flushed the whole cache;
+    /// `inserted`/`removed` are then derived from the net size change
+    pub full_invalidation: bool,
+}
+
+impl UpdateSummary {
+    /// True if the graph did not change
+    pub fn is_noop(&self) -> bool {
+        self.inserted == 0 && self.removed == 0 && !self.full_invalidation
+    }
+}
+
+/// Predicates a cached query can observe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryFootprint {
+    /// Query only matches triples with one of these predicates
+    Predicates(BTreeSet<String>),
+    /// Query may observe any triple (variable predicates, DESCRIBE, SERVICE, ...)
+    Unbounded,
+}
+
+impl QueryFootprint {
+    /// Conservatively derive the predicates a query reads
+    ///
+    /// Anything the analysis does not understand yields `Unbounded`, so the
+    /// footprint may over-approximate but never misses a dependency.
+    pub fn of_query(sparql: &str) -> Self {
+        let Ok(tokens) = tokenize(sparql) else {
+            return QueryFootprint::Unbounded;
+        };
+        match FootprintAnalyzer::new(&tokens).run() {
+            Some(predicates) => QueryFootprint::Predicates(predicates),
+            None => QueryFootprint::Unbounded,
+        }
+    }
+
+    /// True if a change to triples with `predicate` can alter the query result
+    pub fn depends_on(&self, predicate: &str) -> bool {
+        match self {
+            QueryFootprint::Predicates(predicates) => predicates.contains(predicate),
+            QueryFootprint::Unbounded => true,
+        }
+    }
+
+    pub(crate) fn affected_by(&self, changed_predicates: &BTreeSet<String>) -> bool {
+        match self {
+            QueryFootprint::Predicates(predicates) => !predicates.is_disjoint(changed_predicates),
+            QueryFootprint::Unbounded => !changed_predicates.is_empty(),
+        }
+    }
+}
+
+/// One operation of a SPARQL Update request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum UpdateOperation {
+    /// Default-graph modification, as CONSTRUCT queries producing the quads
+    /// to delete and to insert (both evaluated before anything is written)
+    Modify {
+        delete: Option<String>,
+        insert: Option<String>,
+        /// Predicates the WHERE pattern reads (none for DATA operations)
+        reads: QueryFootprint,
+    },
+    /// Anything else; delegated to oxigraph
+    Native,
+}
+
+/// Split a SPARQL Update request into operations
+pub(crate) fn parse_update(sparql: &str) -> Result<Vec<UpdateOperation>, String> {
+    let tokens = tokenize(sparql)?;
+    let mut operations = Vec::new();
+    let mut prologue = String::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        // Prologue declarations carry over to every following operation
+        if tokens[i].is_word("PREFIX") && i + 2 < tokens.len() {
+            prologue.push_str(&sparql[tokens[i].start..tokens[i + 2].end]);
+            prologue.push('\n');
+            i += 3;
+            continue;
+        }
+        if tokens[i].is_word("BASE") && i + 1 < tokens.len() {
+            prologue.push_str(&sparql[tokens[i].start..tokens[i + 1].end]);
+            prologue.push('\n');
+            i += 2;
+            continue;
+        }
+
+        let mut end = i;
+        let mut depth = 0usize;
+        while end < tokens.len() {
+            match tokens[end].text {
+                "{" => depth += 1,
+                "}" => depth = depth.saturating_sub(1),
+                ";" if depth == 0 => break,
+                _ => {}
+            }
+            end += 1;
+        }
+
+        if end > i {
+            operations.push(classify_operation(sparql, &tokens[i..end], &prologue)?);
+        }
+        i = end + 1;
+    }
+
+    Ok(operations)
+}
+
+fn classify_operation(
+    sparql: &str,
+    tokens: &[Token<'_>],
+    prologue: &str,
+) -> Result<UpdateOperation, String> {
+    let construct = |template: (usize, usize), pattern: (usize, usize)| {
+        let template_tokens = &tokens[template.0..=template.1];
+        if template_tokens.iter().any(|t| t.is_word("GRAPH")) {
+            return None;
+        }
+        Some(format!(
+            "{}CONSTRUCT {} WHERE {}",
+            prologue,
+            &sparql[tokens[template.0].start..tokens[template.1].end],
+            &sparql[tokens[pattern.0].start..tokens[pattern.1].end]
+        ))
+    };
+    let reads = |pattern: (usize, usize)| {
+        QueryFootprint::of_query(&format!(
+            "{}ASK {}",
+            prologue,
+            &sparql[tokens[pattern.0].start..tokens[pattern.1].end]
+        ))
+    };
+    let empty_where = |template: (usize, usize)| {
+        let template_tokens = &tokens[template.0..=template.1];
+        if template_tokens.iter().any(|t| t.is_word("GRAPH")) {
+            return None;
+        }
+        Some(format!(
+            "{}CONSTRUCT {} WHERE {{}}",
+            prologue,
+            &sparql[tokens[template.0].start..tokens[template.1].end]
+        ))
+    };
+
+    let first = &tokens[0];
+    let operation = if first.is_word("INSERT") && tokens.get(1).is_some_and(|t| t.is_word("DATA")) {
+        let block = block_at(tokens, 2)?;
+        ensure_consumed(tokens, block.1)?;
+        empty_where(block).map(|insert| UpdateOperation::Modify {
+            delete: None,
+            insert: Some(insert),
+            reads: QueryFootprint::Predicates(BTreeSet::new()),
+        })
+    } else if first.is_word("DELETE") && tokens.get(1).is_some_and(|t| t.is_word("DATA")) {
+        let block = block_at(tokens, 2)?;
+        ensure_consumed(tokens, block.1)?;
+        empty_where(block).map(|delete| UpdateOperation::Modify {
+            delete: Some(delete),
+            insert: None,
+            reads: QueryFootprint::Predicates(BTreeSet::new()),
+        })
+    } else if first.is_word("DELETE") && tokens.get(1).is_some_and(|t| t.is_word("WHERE")) {
+        let block = block_at(tokens, 2)?;
+        ensure_consumed(tokens, block.1)?;
+        construct(block, block).map(|delete| UpdateOperation::Modify {
+            delete: Some(delete),
+            insert: None,
+            reads: reads(block),
+        })
+    } else if first.is_word("DELETE") || first.is_word("INSERT") {
+        let mut next = 0;
+        let mut delete_block = None;
+        let mut insert_block = None;
+
+        if tokens[next].is_word("DELETE") {
+            let block = block_at(tokens, next + 1)?;
+            delete_block = Some(block);
+            next = block.1 + 1;
+        }
+        if tokens.get(next).is_some_and(|t| t.is_word("INSERT")) {
+            let block = block_at(tokens, next + 1)?;
+            insert_block = Some(block);
+            next = block.1 + 1;
+        }
+
+        if tokens.get(next).is_some_and(|t| t.is_word("WHERE")) {
+            let pattern = block_at(tokens, next + 1)?;
+            ensure_consumed(tokens, pattern.1)?;
+            let delete = delete_block.map(|b| construct(b, pattern));
+            let insert = insert_block.map(|b| construct(b, pattern));
+            match (delete, insert) {
+                (Some(None), _) | (_, Some(None)) => None,
+                (delete, insert) => Some(UpdateOperation::Modify {
+                    delete: delete.flatten(),
+                    insert: insert.flatten(),
+                    reads: reads(pattern),
+                }),
+            }
+        } else {
+            // USING clauses or malformed input: let oxigraph handle (or reject) it
+            None
+        }
+    } else {
+        None
+    };
+
+    Ok(operation.unwrap_or(UpdateOperation::Native))
+}
+
+/// Token range `(open, close)` of the brace block starting at `start`
+fn block_at(tokens: &[Token<'_>], start: usize) -> Result<(usize, usize), String> {
+    if tokens.get(start).map(|t| t.text) != Some("{") {
+        return Err(format!(
+            "Update parse failed: expected '{{' but found '{}'",
+            tokens.get(start).map(|t| t.text).unwrap_or("end of input")
+        ));
+    }
+
+    let mut depth = 0usize;
+    for (offset, token) in tokens[start..].iter().enumerate() {
+        match token.text {
+            "{" => depth += 1,
+            "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((start, start + offset));
+                }
+            }
+            _ => {}
+        }
+    }
+    Err("Update parse failed: unbalanced braces".to_string())
+}
+
+fn ensure_consumed(tokens: &[Token<'_>], last: usize) -> Result<(), String> {
+    match tokens.get(last + 1) {
+        None => Ok(()),
+        Some(extra) => Err(format!(
+            "Update parse failed: unexpected '{}' after operation",
+            extra.text
+        )),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Iri,
+    PrefixedName,
+    Var,
+    Literal,
+    BlankNode,
+    Word,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl Token<'_> {
+    fn is_word(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        self.kind == TokenKind::Punct && self.text == punct
+    }
+
+    /// RDF term usable in subject/object position
+    fn is_term(&self) -> bool {
+        match self.kind {
+            TokenKind::Iri
+            | TokenKind::PrefixedName
+            | TokenKind::Var
+            | TokenKind::Literal
+            | TokenKind::BlankNode => true,
+            TokenKind::Word => {
+                self.text.eq_ignore_ascii_case("true")
+                    || self.text.eq_ignore_ascii_case("false")
+                    || self.text.starts_with(|c: char| c.is_ascii_digit())
+            }
+            TokenKind::Punct => false,
+        }
+    }
+}
+
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b':' | b'%') || b >= 0x80
+}
+
+/// Lexer for the subset of SPARQL needed to split updates and find predicates
+fn tokenize(src: &str) -> Result<Vec<Token<'_>>, String> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+
+        let kind = if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if c == b'#' {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+            continue;
+        } else if c == b'<' {
+            let mut j = i + 1;
+            while j < bytes.len()
+                && !bytes[j].is_ascii_whitespace()
+                && !matches!(
+                    bytes[j],
+                    b'<' | b'>' | b'"' | b'{' | b'}' | b'|' | b'^' | b'`'
+                )
+            {
+                j += 1;
+            }
+            if j < bytes.len() && bytes[j] == b'>' {
+                i = j + 1;
+                TokenKind::Iri
+            } else {
+                i += 1;
+                TokenKind::Punct
+            }
+        } else if c == b'"' || c == b'\'' {
+            i = scan_string(bytes, i)?;
+            // Language tag or datatype suffix
+            if i < bytes.len() && bytes[i] == b'@' {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-') {
+                    i += 1;
+                }
+            } else if bytes[i..].starts_with(b"^^") {
+                i += 2;
+                if i < bytes.len() && bytes[i] == b'<' {
+                    while i < bytes.len() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    i += 1;
+                } else {
+                    while i < bytes.len() && is_name_byte(bytes[i]) {
+                        i += 1;
+                    }
+                }
+            }
+            TokenKind::Literal
+        } else if (c == b'?' || c == b'$') && bytes.get(i + 1).is_some_and(|b| is_name_byte(*b)) {
+            i += 1;
+            while i < bytes.len() && is_name_byte(bytes[i]) {
+                i += 1;
+            }
+            TokenKind::Var
+        } else if is_name_byte(c) {
+            while i < bytes.len()
+                && (is_name_byte(bytes[i])
+                    || (bytes[i] == b'.' && bytes.get(i + 1).is_some_and(|b| is_name_byte(*b))))
+            {
+                i += 1;
+            }
+            let text = &src[start..i];
+            if text.starts_with("_:") {
+                TokenKind::BlankNode
+            } else if text.contains(':') {
+                TokenKind::PrefixedName
+            } else {
+                TokenKind::Word
+            }
+        } else {
+            i += 1;
+            TokenKind::Punct
+        };
+
+        let end = i.min(bytes.len());
+        tokens.push(Token {
+            kind,
+            text: &src[start..end],
+            start,
+            end,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// End offset (exclusive) of the string literal starting at `start`
+fn scan_string(bytes: &[u8], start: usize) -> Result<usize, String> {
+    let quote = bytes[start];
+    let long = bytes.len() >= start + 3 && bytes[start + 1] == quote && bytes[start + 2] == quote;
+    let mut i = if long { start + 3 } else { start + 1 };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => {
+                if !long {
+                    return Ok(i + 1);
+                }
+                if bytes.len() >= i + 3 && bytes[i + 1] == quote && bytes[i + 2] == quote {
+                    return Ok(i + 3);
+                }
+                i += 1;
+            }
+            b'\n' if !long => break,
+            _ => i += 1,
+        }
+    }
+    Err("Update parse failed: unterminated string literal".to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Subject,
+    Verb,
+    Object,
+    AfterObject,
+}
+
+enum Frame {
+    Group,
+    /// `[ ... ]` blank node; position to resume after the closing bracket
+    BlankNode(Position),
+}
+
+/// Walks group graph patterns collecting constant predicates
+struct FootprintAnalyzer<'t, 'a> {
+    tokens: &'t [Token<'a>],
+    prefixes: HashMap<&'a str, &'a str>,
+    predicates: BTreeSet<String>,
+}
+
+impl<'t, 'a> FootprintAnalyzer<'t, 'a> {
+    fn new(tokens: &'t [Token<'a>]) -> Self {
+        Self {
+            tokens,
+            prefixes: HashMap::new(),
+            predicates: BTreeSet::new(),
+        }
+    }
+
+    fn run(mut self) -> Option<BTreeSet<String>> {
+        let tokens = self.tokens;
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut pos = Position::Subject;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let t = tokens[i];
+
+            if stack.is_empty() {
+                if t.is_word("PREFIX") {
+                    let name = tokens.get(i + 1)?;
+                    let iri = tokens.get(i + 2)?;
+                    let prefix = name.text.strip_suffix(':')?;
+                    self.prefixes.insert(
+                        prefix,
+                        iri.text.trim_start_matches('<').trim_end_matches('>'),
+                    );
+                    i += 3;
+                    continue;
+                }
+                // Relative IRIs would resolve against BASE; DESCRIBE reads whole resources
+                if t.is_word("BASE") || t.is_word("DESCRIBE") {
+                    return None;
+                }
+                if t.is_punct("{") {
+                    stack.push(Frame::Group);
+                    pos = Position::Subject;
+                }
+                i += 1;
+                continue;
+            }
+
+            if t.is_punct("{") {
+                stack.push(Frame::Group);
+                pos = Position::Subject;
+                i += 1;
+                continue;
+            }
+            if t.is_punct("}") {
+                match stack.pop()? {
+                    Frame::Group => {}
+                    Frame::BlankNode(_) => return None,
+                }
+                pos = Position::Subject;
+                i += 1;
+                continue;
+            }
+            if t.kind == TokenKind::Word && !t.is_term() && !t.is_word("a") {
+                match t.text.to_ascii_uppercase().as_str() {
+                    "SERVICE" => return None,
+                    "FILTER" | "BIND" | "HAVING" => {
+                        i = skip_expression(tokens, i + 1)?;
+                    }
+                    "VALUES" => {
+                        i = skip_values(tokens, i + 1)?;
+                    }
+                    // Sub-select projection up to its WHERE block
+                    "SELECT" => {
+                        i += tokens[i..].iter().position(|t| t.is_punct("{"))?;
+                    }
+                    // OPTIONAL, MINUS, UNION, GRAPH, EXISTS, NOT, SELECT, WHERE, ...
+                    _ => i += 1,
+                }
+                pos = Position::Subject;
+                continue;
+            }
+
+            match pos {
+                Position::Subject => {
+                    if t.is_punct("[") {
+                        stack.push(Frame::BlankNode(Position::Verb));
+                        pos = Position::Verb;
+                    } else if t.is_punct("(") {
+                        i = self.skip_collection(i)?;
+                        pos = Position::Verb;
+                        continue;
+                    } else if t.is_term() {
+                        pos = Position::Verb;
+                    } else if !t.is_punct(".") {
+                        return None;
+                    }
+                }
+                Position::Verb => {
+                    if t.is_word("a") {
+                        self.predicates.insert(RDF_TYPE.to_string());
+                        pos = Position::Object;
+                    } else if matches!(t.kind, TokenKind::Iri | TokenKind::PrefixedName) {
+                        let iri = self.expand(&t)?;
+                        self.predicates.insert(iri);
+                        pos = Position::Object;
+                    } else if t.is_punct("(") {
+                        i = self.path_group(i)?;
+                        pos = Position::Object;
+                        continue;
+                    } else if t.is_punct(".") {
+                        pos = Position::Subject;
+                    } else if t.is_punct("]") {
+                        pos = close_blank_node(&mut stack)?;
+                    } else if !(t.is_punct("^") || t.is_punct(";")) {
+                        // Variable predicates and negated property sets match anything
+                        return None;
+                    }
+                }
+                Position::Object => {
+                    if t.is_punct("/") || t.is_punct("|") {
+                        pos = Position::Verb;
+                    } else if t.is_punct("[") {
+                        stack.push(Frame::BlankNode(Position::AfterObject));
+                        pos = Position::Verb;
+                    } else if t.is_punct("(") {
+                        i = self.skip_collection(i)?;
+                        pos = Position::AfterObject;
+                        continue;
+                    } else if t.is_term() {
+                        pos = Position::AfterObject;
+                    } else if !(t.is_punct("*") || t.is_punct("+") || t.is_punct("?")) {
+                        return None;
+                    }
+                }
+                Position::AfterObject => {
+                    if t.is_punct(",") {
+                        pos = Position::Object;
+                    } else if t.is_punct(";") {
+                        pos = Position::Verb;
+                    } else if t.is_punct(".") {
+                        pos = Position::Subject;
+                    } else if t.is_punct("]") {
+                        pos = close_blank_node(&mut stack)?;
+                    } else {
+                        return None;
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        Some(self.predicates)
+    }
+
+    fn expand(&self, token: &Token<'_>) -> Option<String> {
+        match token.kind {
+            TokenKind::Iri => Some(token.text[1..token.text.len() - 1].to_string()),
+            TokenKind::PrefixedName => {
+                let (prefix, local) = token.text.split_once(':')?;
+                self.prefixes
+                    .get(prefix)
+                    .map(|ns| format!("{}{}", ns, local))
+            }
+            _ => None,
+        }
+    }
+
+    /// Skip an RDF collection `( ... )`, recording its list predicates
+    fn skip_collection(&mut self, open: usize) -> Option<usize> {
+        let close = matching_paren(self.tokens, open)?;
+        if self.tokens[open..close].iter().any(|t| t.is_punct("[")) {
+            return None;
+        }
+        self.predicates.insert(RDF_FIRST.to_string());
+        self.predicates.insert(RDF_REST.to_string());
+        Some(close + 1)
+    }
+
+    /// Record every IRI of a parenthesised property path
+    fn path_group(&mut self, open: usize) -> Option<usize> {
+        let close = matching_paren(self.tokens, open)?;
+        for t in &self.tokens[open + 1..close] {
+            if t.is_word("a") {
+                self.predicates.insert(RDF_TYPE.to_string());
+            } else if matches!(t.kind, TokenKind::Iri | TokenKind::PrefixedName) {
+                let iri = self.expand(t)?;
+                self.predicates.insert(iri);
+            } else if !matches!(t.text, "(" | ")" | "/" | "|" | "^" | "*" | "+" | "?") {
+                return None;
+            }
+        }
+        Some(close + 1)
+    }
+}
+
+fn close_blank_node(stack: &mut Vec<Frame>) -> Option<Position> {
+    match stack.pop()? {
+        Frame::BlankNode(resume) => Some(resume),
+        Frame::Group => None,
+    }
+}
+
+fn matching_paren(tokens: &[Token<'_>], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (offset, t) in tokens[open..].iter().enumerate() {
+        if t.is_punct("(") {
+            depth += 1;
+        } else if t.is_punct(")") {
+            depth -= 1;
+            if depth == 0 {
+                return Some(open + offset);
+            }
+        }
+    }
+    None
+}
+
+/// Skip a FILTER/BIND/HAVING expression; `EXISTS { ... }` patterns are kept
+fn skip_expression(tokens: &[Token<'_>], start: usize) -> Option<usize> {
+    let t = tokens.get(start)?;
+    if t.is_word("EXISTS") || t.is_word("NOT") {
+        return Some(start);
+    }
+    let open = if t.is_punct("(") {
+        start
+    } else if tokens.get(start + 1)?.is_punct("(") {
+        // Function call such as regex(...) or a prefixed function
+        start + 1
+    } else {
+        return None;
+    };
+
+    let close = matching_paren(tokens, open)?;
+    // EXISTS patterns nested inside an expression are not analysed
+    if tokens[open..close].iter().any(|t| t.is_punct("{")) {
+        return None;
+    }
+    Some(close + 1)
+}
+
+/// Skip an inline `VALUES` data block
+fn skip_values(tokens: &[Token<'_>], start: usize) -> Option<usize> {
+    let open = start + tokens[start..].iter().position(|t| t.is_punct("{"))?;
+    let close = open + tokens[open..].iter().position(|t| t.is_punct("}"))?;
+    Some(close + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn predicates(sparql: &str) -> Vec<String> {
+        match QueryFootprint::of_query(sparql) {
+            QueryFootprint::Predicates(p) => p.into_iter().collect(),
+            QueryFootprint::Unbounded => vec!["*".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_footprint_collects_constant_predicates() {
+        let sparql = r#"PREFIX ex: <http://example.org/>
+            SELECT ?s WHERE {
+                ?s a ex:Order ; ex:total ?t .
+                OPTIONAL { ?s ex:customer [ ex:name "A \"quoted\" }" ] }
+                FILTER(?t > 10)
+            }"#;
+        assert_eq!(
+            predicates(sparql),
+            vec![
+                "http://example.org/customer".to_string(),
+                "http://example.org/name".to_string(),
+                "http://example.org/total".to_string(),
+                RDF_TYPE.to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_footprint_variable_predicate_is_unbounded() {
+        assert_eq!(
+            QueryFootprint::of_query("SELECT * WHERE { ?s ?p ?o }"),
+            QueryFootprint::Unbounded
+        );
+        assert_eq!(
+            QueryFootprint::of_query("DESCRIBE <http://example.org/a>"),
+            QueryFootprint::Unbounded
+        );
+        assert_eq!(
+            QueryFootprint::of_query("SELECT ?s WHERE { ?s !<http://example.org/p> ?o }"),
+            QueryFootprint::Unbounded
+        );
+    }
+
+    #[test]
+    fn test_parse_update_operations() {
+        let ops = parse_update(
+            "PREFIX ex: <http://example.org/>
+             INSERT DATA { ex:a ex:p ex:b } ;
+             DELETE { ?s ex:p ?o } INSERT { ?s ex:q ?o } WHERE { ?s ex:p ?o } ;
+             CLEAR ALL",
+        )
+        .expect("update should parse");
+
+        assert_eq!(ops.len(), 3);
+        match &ops[0] {
+            UpdateOperation::Modify {
+                delete: None,
+                insert: Some(insert),
+                reads,
+            } => {
+                assert_eq!(*reads, QueryFootprint::Predicates(BTreeSet::new()));
+                assert!(insert.starts_with("PREFIX ex: <http://example.org/>"));
+                assert!(insert.ends_with("CONSTRUCT { ex:a ex:p ex:b } WHERE {}"));
+            }
+            other => panic!("unexpected operation {:?}", other),
+        }
+        match &ops[1] {
+            UpdateOperation::Modify {
+                delete: Some(delete),
+                insert: Some(insert),
+                reads,
+            } => {
+                assert!(reads.depends_on("http://example.org/p"));
+                assert!(!reads.depends_on("http://example.org/q"));
+                assert!(delete.ends_with("CONSTRUCT { ?s ex:p ?o } WHERE { ?s ex:p ?o }"));
+                assert!(insert.ends_with("CONSTRUCT { ?s ex:q ?o } WHERE { ?s ex:p ?o }"));
+            }
+            other => panic!("unexpected operation {:?}", other),
+        }
+        assert_eq!(ops[2], UpdateOperation::Native);
+    }
+}
@@ -0,0 +1,262 @@
+//! Chicago TDD tests for SPARQL UPDATE and transactional writes
+//! Tests INSERT/DELETE DATA, DELETE/INSERT WHERE, atomic batches, selective cache invalidation
+
+use knhk_warm::{execute_ask, execute_select, GraphTransaction, WarmPathGraph};
+
+const DATA: &str = r#"
+    <http://example.org/order1> <http://example.org/status> <http://example.org/open> .
+    <http://example.org/order2> <http://example.org/status> <http://example.org/open> .
+    <http://example.org/order1> <http://example.org/customer> <http://example.org/alice> .
+"#;
+
+fn graph_with_data() -> WarmPathGraph {
+    let graph = WarmPathGraph::new().expect("Failed to create graph");
+    graph.load_from_turtle(DATA).expect("Failed to load data");
+    graph
+}
+
+#[test]
+fn test_insert_and_delete_data() {
+    let graph = graph_with_data();
+    let epoch_before = graph.epoch();
+
+    let summary = graph
+        .update(
+            "PREFIX ex: <http://example.org/>
+             DELETE DATA { ex:order1 ex:status ex:open } ;
+             INSERT DATA { ex:order1 ex:status ex:closed }",
+        )
+        .expect("Update should succeed");
+
+    assert_eq!(summary.inserted, 1);
+    assert_eq!(summary.removed, 1);
+    assert_eq!(
+        summary.epoch,
+        epoch_before + 1,
+        "Epoch should be bumped exactly once per request"
+    );
+    assert_eq!(graph.size(), 3);
+
+    let closed = execute_ask(
+        &graph,
+        "ASK { <http://example.org/order1> <http://example.org/status> <http://example.org/closed> }",
+    )
+    .expect("ASK should succeed");
+    assert!(closed.result);
+}
+
+#[test]
+fn test_delete_insert_where() {
+    let graph = graph_with_data();
+
+    let summary = graph
+        .update(
+            "PREFIX ex: <http://example.org/>
+             DELETE { ?o ex:status ex:open }
+             INSERT { ?o ex:status ex:archived }
+             WHERE { ?o ex:status ex:open }",
+        )
+        .expect("Update should succeed");
+
+    assert_eq!(summary.removed, 2);
+    assert_eq!(summary.inserted, 2);
+
+    let result = execute_select(
+        &graph,
+        "SELECT ?o WHERE { ?o <http://example.org/status> <http://example.org/open> }",
+    )
+    .expect("SELECT should succeed");
+    assert!(result.bindings.is_empty(), "No open orders should remain");
+}
+
+#[test]
+fn test_delete_where_and_noop() {
+    let graph = graph_with_data();
+
+    let summary = graph
+        .update("DELETE WHERE { ?s <http://example.org/customer> ?c }")
+        .expect("Update should succeed");
+    assert_eq!(summary.removed, 1);
+    assert_eq!(graph.size(), 2);
+
+    let epoch = graph.epoch();
+    let noop = graph
+        .update("DELETE WHERE { ?s <http://example.org/customer> ?c }")
+        .expect("Update should succeed");
+    assert!(noop.is_noop());
+    assert_eq!(
+        graph.epoch(),
+        epoch,
+        "No-op updates must not bump the epoch"
+    );
+}
+
+#[test]
+fn test_transaction_applies_batch_atomically() {
+    let graph = graph_with_data();
+    let epoch_before = graph.epoch();
+
+    let mut transaction = GraphTransaction::new();
+    transaction
+        .delete_triple(
+            "http://example.org/order2",
+            "http://example.org/status",
+            "http://example.org/open",
+        )
+        .expect("Valid triple")
+        .insert_triple(
+            "http://example.org/order2",
+            "http://example.org/status",
+            "http://example.org/shipped",
+        )
+        .expect("Valid triple")
+        .insert_triple(
+            "http://example.org/order2",
+            "http://example.org/carrier",
+            "http://example.org/dhl",
+        )
+        .expect("Valid triple");
+
+    let summary = graph.apply(transaction).expect("Transaction should commit");
+
+    assert_eq!(summary.inserted, 2);
+    assert_eq!(summary.removed, 1);
+    assert_eq!(graph.epoch(), epoch_before + 1);
+    assert_eq!(graph.size(), 4);
+}
+
+#[test]
+fn test_failed_update_leaves_graph_unchanged() {
+    let graph = graph_with_data();
+    let size_before = graph.size();
+
+    // Second operation references an undeclared prefix and fails to evaluate
+    let result = graph.update(
+        "INSERT DATA { <http://example.org/order3> <http://example.org/status> <http://example.org/open> } ;
+         INSERT { ?o undeclared:flag true } WHERE { ?o <http://example.org/status> ?s }",
+    );
+
+    assert!(result.is_err(), "Update with invalid operation should fail");
+    assert_eq!(
+        graph.size(),
+        size_before,
+        "First operation must not be applied"
+    );
+}
+
+#[test]
+fn test_update_operations_commit_together() {
+    let graph = graph_with_data();
+    let epoch_before = graph.epoch();
+    let customer_query = "SELECT ?o WHERE { ?o <http://example.org/customer> ?c }";
+    graph
+        .query_cached(customer_query)
+        .expect("Query should succeed");
+
+    // Independent operations are evaluated up front and written once
+    let summary = graph
+        .update(
+            "PREFIX ex: <http://example.org/>
+             INSERT DATA { ex:order3 ex:status ex:open } ;
+             DELETE { ?o ex:customer ?c } INSERT { ?o ex:buyer ?c } WHERE { ?o ex:customer ?c }",
+        )
+        .expect("Update should succeed");
+
+    assert_eq!(summary.inserted, 2);
+    assert_eq!(summary.removed, 1);
+    assert!(!summary.full_invalidation);
+    assert_eq!(graph.epoch(), epoch_before + 1);
+    assert!(!graph.is_cached(customer_query));
+}
+
+#[test]
+fn test_later_operation_sees_earlier_changes() {
+    let graph = graph_with_data();
+
+    // The second WHERE reads ex:status, which the first operation writes
+    let summary = graph
+        .update(
+            "PREFIX ex: <http://example.org/>
+             INSERT DATA { ex:order3 ex:status ex:open } ;
+             DELETE { ?o ex:status ex:open } INSERT { ?o ex:status ex:archived }
+             WHERE { ?o ex:status ex:open }",
+        )
+        .expect("Update should succeed");
+
+    assert!(summary.full_invalidation);
+    let open = execute_ask(
+        &graph,
+        "ASK { ?o <http://example.org/status> <http://example.org/open> }",
+    )
+    .expect("ASK should succeed");
+    assert!(!open.result, "order3 must be archived too");
+    assert_eq!(graph.size(), 4);
+}
+
+#[test]
+fn test_transaction_invalidates_only_affected_queries() {
+    let graph = graph_with_data();
+
+    let status_query =
+        "SELECT ?o WHERE { ?o <http://example.org/status> <http://example.org/open> }";
+    let customer_query = "SELECT ?o WHERE { ?o <http://example.org/customer> ?c }";
+    let wildcard_query = "SELECT ?p WHERE { <http://example.org/order1> ?p ?o }";
+
+    graph
+        .query_cached(status_query)
+        .expect("Query should succeed");
+    graph
+        .query_cached(customer_query)
+        .expect("Query should succeed");
+    graph
+        .query_cached(wildcard_query)
+        .expect("Query should succeed");
+    assert!(graph.is_cached(status_query));
+    assert!(graph.is_cached(customer_query));
+    assert!(graph.is_cached(wildcard_query));
+
+    let summary = graph
+        .delete_triple(
+            "http://example.org/order1",
+            "http://example.org/status",
+            "http://example.org/open",
+        )
+        .expect("Delete should succeed");
+
+    assert_eq!(summary.removed, 1);
+    assert_eq!(summary.invalidated, 2);
+    assert!(
+        !graph.is_cached(status_query),
+        "Status query reads the changed predicate"
+    );
+    assert!(
+        !graph.is_cached(wildcard_query),
+        "Variable predicates read everything"
+    );
+    assert!(
+        graph.is_cached(customer_query),
+        "Customer query does not read the changed predicate"
+    );
+
+    let refreshed = graph
+        .query_cached(status_query)
+        .expect("Query should succeed");
+    match refreshed {
+        knhk_warm::graph::CachedResult::Solutions(rows) => assert_eq!(rows.len(), 1),
+        other => panic!("Expected solutions, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_native_update_flushes_cache() {
+    let graph = graph_with_data();
+    let query = "SELECT ?o WHERE { ?o <http://example.org/customer> ?c }";
+    graph.query_cached(query).expect("Query should succeed");
+
+    let summary = graph.update("CLEAR DEFAULT").expect("CLEAR should succeed");
+
+    assert!(summary.full_invalidation);
+    assert_eq!(summary.removed, 3);
+    assert_eq!(graph.size(), 0);
+    assert!(!graph.is_cached(query));
+}
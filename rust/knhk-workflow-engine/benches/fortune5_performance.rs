@@ -69,6 +69,10 @@ fn create_minimal_workflow() -> WorkflowSpec {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
     };
     tasks.insert("task1".to_string(), task);
 
@@ -81,6 +85,10 @@ fn create_minimal_workflow() -> WorkflowSpec {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     }
 }
 
@@ -109,6 +117,10 @@ fn create_atm_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -132,6 +144,10 @@ fn create_atm_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -155,6 +171,10 @@ fn create_atm_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -178,6 +198,10 @@ fn create_atm_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -241,6 +265,10 @@ fn create_atm_workflow() -> WorkflowSpec {
         start_condition: Some("c_start".to_string()),
         end_condition: Some("c_complete".to_string()),
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     }
 }
 
@@ -342,6 +370,10 @@ fn bench_max_ticks_check_hot_path(c: &mut Criterion) {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
     };
 
     group.bench_function("max_ticks_check", |b| {
@@ -479,6 +511,10 @@ fn bench_task_lookup_scalability(c: &mut Criterion) {
                             required_roles: vec![],
                             required_capabilities: vec![],
                             exception_worklet: None,
+                            multi_instance: None,
+                            cancellation: None,
+                            decomposition: None,
+                            message: None,
                         },
                     );
                 }
@@ -492,6 +528,10 @@ fn bench_task_lookup_scalability(c: &mut Criterion) {
                     start_condition: None,
                     end_condition: None,
                     source_turtle: None,
+                    variables: Vec::new(),
+                    iri: None,
+                    version: None,
+                    subnets: Vec::new(),
                 };
 
                 b.iter(|| {
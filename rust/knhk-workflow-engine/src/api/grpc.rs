@@ -137,6 +137,10 @@ impl WorkflowEngineService for GrpcService {
                 conditions: std::collections::HashMap::new(),
                 flows: Vec::new(),
                 source_turtle: None,
+                variables: Vec::new(),
+                iri: None,
+                version: None,
+                subnets: Vec::new(),
             }
         };
 
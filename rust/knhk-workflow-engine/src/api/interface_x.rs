@@ -7,6 +7,8 @@
 
 use crate::case::CaseId;
 use crate::error::{WorkflowError, WorkflowResult};
+use crate::executor::WorkflowEngine;
+use crate::services::{CorrelatedMessage, MessageDelivery};
 use dashmap::DashMap;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -38,6 +40,8 @@ pub enum MessageType {
     CaseMessage,
     /// Event subscription
     EventSubscription,
+    /// Correlated message to message-catch and message-start tasks
+    CorrelatedMessage,
     /// Status check
     StatusCheck,
 }
@@ -110,6 +114,37 @@ impl InterfaceX {
         self.send_message(message).await
     }
 
+    /// Publish a correlated message from a case to other cases
+    ///
+    /// The message is recorded like any Interface X message and delivered
+    /// through the engine's message correlation (waiting message-catch task,
+    /// message start or buffer).
+    pub async fn publish_correlated_message(
+        &self,
+        engine: &WorkflowEngine,
+        from_case: CaseId,
+        message: CorrelatedMessage,
+    ) -> WorkflowResult<MessageDelivery> {
+        let record = InterfaceXMessage {
+            message_id: message.id.clone(),
+            from_case,
+            to_case: None,
+            message_type: MessageType::CorrelatedMessage,
+            data: serde_json::to_value(&message).map_err(|e| {
+                WorkflowError::Internal(format!("Failed to serialize message: {}", e))
+            })?,
+            timestamp: message.published_at,
+        };
+        let delivery = engine.publish_message(message).await?;
+        let to_case = match delivery {
+            MessageDelivery::Delivered { case_id, .. } => Some(case_id),
+            _ => None,
+        };
+        self.send_message(InterfaceXMessage { to_case, ..record })
+            .await?;
+        Ok(delivery)
+    }
+
     /// Subscribe to case events
     pub async fn subscribe_to_case_events(
         &self,
@@ -165,7 +200,7 @@ impl InterfaceX {
 
         tokio::spawn(async move {
             let mut receiver = rx.write().await;
-            while let Ok(message) = receiver.recv().await {
+            while let Some(message) = receiver.recv().await {
                 // Handle message
                 tracing::debug!("Interface X message received: {:?}", message.message_id);
 
@@ -8,8 +8,10 @@
 //! - Audit logging
 
 use crate::resilience::{CircuitBreaker, KeyedRateLimiter, RateLimitConfig};
+use crate::security::{Action, AuthManager, Principal};
 use axum::{
     body::Body,
+    extract::State,
     http::{HeaderMap, Request, StatusCode},
     middleware::Next,
     response::Response,
@@ -41,13 +43,18 @@ impl Fortune5Middleware {
 }
 
 /// Authentication middleware
+///
+/// Validates the `Authorization: Bearer <jwt>` header with the auth manager and
+/// stores the authenticated [`Principal`] in the request extensions, where
+/// handlers extract it with `Extension<Principal>`.
 pub async fn auth_middleware(
-    headers: HeaderMap,
-    request: Request<Body>,
+    State(auth): State<Arc<AuthManager>>,
+    mut request: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
     // Extract authorization header
-    let auth_header = headers
+    let auth_header = request
+        .headers()
         .get("authorization")
         .and_then(|h| h.to_str().ok())
         .ok_or_else(|| {
@@ -56,16 +63,60 @@ pub async fn auth_middleware(
         })?;
 
     // Validate token format (Bearer token required)
-    // Note: Full SPIFFE/SPIRE integration would validate mTLS certificates here
-    if !auth_header.starts_with("Bearer ") {
+    let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
         warn!("Invalid authorization header format");
-        return Err(StatusCode::UNAUTHORIZED);
-    }
+        StatusCode::UNAUTHORIZED
+    })?;
+
+    let principal = auth.authenticate(token.trim()).map_err(|e| {
+        warn!(error = %e, "Rejected bearer token");
+        StatusCode::UNAUTHORIZED
+    })?;
+    request.extensions_mut().insert(principal);
 
     // Continue request
     Ok(next.run(request).await)
 }
 
+/// Action required by a route (state of [`authorize_middleware`])
+#[derive(Clone)]
+pub struct RouteAuthorization {
+    auth: Arc<AuthManager>,
+    action: Action,
+}
+
+impl RouteAuthorization {
+    /// Require an action on the routes this middleware is applied to
+    pub fn new(auth: Arc<AuthManager>, action: Action) -> Self {
+        Self { auth, action }
+    }
+}
+
+/// Authorization middleware
+///
+/// Runs after [`auth_middleware`] and enforces the route's action for the
+/// authenticated principal, with the request path as the resource.
+pub async fn authorize_middleware(
+    State(route): State<RouteAuthorization>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let principal = request.extensions().get::<Principal>().ok_or_else(|| {
+        warn!("Authorization without an authenticated principal");
+        StatusCode::UNAUTHORIZED
+    })?;
+
+    route
+        .auth
+        .authorize(principal, route.action, request.uri().path())
+        .map_err(|e| {
+            warn!(error = %e, "Request not authorized");
+            StatusCode::FORBIDDEN
+        })?;
+
+    Ok(next.run(request).await)
+}
+
 /// Global rate limiter for API middleware (per-client rate limiting)
 static RATE_LIMITER: std::sync::OnceLock<Arc<KeyedRateLimiter<String>>> =
     std::sync::OnceLock::new();
@@ -232,12 +283,18 @@ pub async fn tracing_middleware(request: Request<Body>, next: Next) -> Response
 }
 
 /// Audit logging middleware
+///
+/// Records the authenticated principal; the `x-user-id` header is only used on
+/// unauthenticated routes.
 pub async fn audit_middleware(headers: HeaderMap, request: Request<Body>, next: Next) -> Response {
-    let user = headers
-        .get("x-user-id")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("unknown")
-        .to_string();
+    let user = match request.extensions().get::<Principal>() {
+        Some(principal) => principal.id.clone(),
+        None => headers
+            .get("x-user-id")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("unknown")
+            .to_string(),
+    };
 
     let path = request.uri().path().to_string();
     let method = request.method().to_string();
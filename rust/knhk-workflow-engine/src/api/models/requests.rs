@@ -5,7 +5,9 @@
 use crate::case::CaseId;
 use crate::parser::{WorkflowSpec, WorkflowSpecId};
 use crate::patterns::PatternId;
+use crate::resilience::DlqEntryStatus;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 /// Register workflow request
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,3 +108,66 @@ pub struct ExecutePatternRequest {
     /// Execution context variables
     pub variables: std::collections::HashMap<String, String>,
 }
+
+/// List dead letter entries request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListDeadLettersRequest {
+    /// Optional status filter
+    pub status: Option<DlqEntryStatus>,
+}
+
+/// Get work item request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetWorkItemRequest {
+    /// Work item ID
+    pub id: String,
+}
+
+/// Get dead letter entry request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetDeadLetterRequest {
+    /// Entry ID
+    pub id: Uuid,
+}
+
+/// Edit dead letter payload request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditDeadLetterRequest {
+    /// Entry ID
+    pub id: Uuid,
+    /// Replacement operation data
+    pub operation_data: serde_json::Value,
+}
+
+/// Redrive dead letter entry request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedriveDeadLetterRequest {
+    /// Entry ID
+    pub id: Uuid,
+    /// Requeue for the redrive worker instead of redriving immediately
+    #[serde(default)]
+    pub requeue: bool,
+}
+
+/// Purge dead letter entries request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PurgeDeadLettersRequest {
+    /// Only purge entries with this status (all entries if unset)
+    pub status: Option<DlqEntryStatus>,
+}
+
+/// Publish message request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishMessageRequest {
+    /// Message name
+    pub name: String,
+    /// Correlation key values
+    #[serde(default)]
+    pub correlation: std::collections::BTreeMap<String, serde_json::Value>,
+    /// Message payload
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    /// Buffering time in seconds if no case waits for the message
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+}
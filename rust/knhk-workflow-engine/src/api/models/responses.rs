@@ -4,8 +4,12 @@
 
 use crate::api::models::CaseHistoryEntry;
 use crate::case::{Case, CaseId};
+use crate::data::TaskForm;
 use crate::parser::{WorkflowSpec, WorkflowSpecId};
 use crate::patterns::PatternId;
+use crate::resilience::DLQEntry;
+use crate::services::work_items::WorkItem;
+use crate::services::MessageDelivery;
 use serde::{Deserialize, Serialize};
 
 /// Register workflow response
@@ -111,3 +115,52 @@ pub struct ExecutePatternResponse {
     /// Result variables
     pub variables: std::collections::HashMap<String, String>,
 }
+
+/// List dead letter entries response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListDeadLettersResponse {
+    /// Entries, oldest first
+    pub entries: Vec<DLQEntry>,
+}
+
+/// Dead letter entry response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterResponse {
+    /// Entry
+    pub entry: DLQEntry,
+}
+
+/// Redrive dead letter entry response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedriveDeadLetterResponse {
+    /// Whether the entry was redriven (false when requeued)
+    pub redriven: bool,
+    /// Entry left in the queue (requeued), if any
+    pub entry: Option<DLQEntry>,
+}
+
+/// Purge dead letter entries response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeDeadLettersResponse {
+    /// Number of purged entries
+    pub purged: usize,
+}
+
+/// Publish message response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishMessageResponse {
+    /// Message ID
+    pub message_id: String,
+    /// Delivery outcome
+    pub delivery: MessageDelivery,
+}
+
+/// Work item response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkItemResponse {
+    /// Work item
+    pub work_item: WorkItem,
+    /// Generated form (absent for tasks without declared parameters)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form: Option<TaskForm>,
+}
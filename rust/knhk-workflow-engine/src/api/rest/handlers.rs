@@ -1,30 +1,39 @@
 //! REST API route handlers
 
 use crate::api::models::requests::{
-    CancelCaseRequest, CreateCaseRequest, ExecuteCaseRequest, ExecutePatternRequest,
-    GetCaseHistoryRequest, GetCaseRequest, GetPatternRequest, GetWorkflowRequest, ListCasesRequest,
-    ListPatternsRequest, ListWorkflowsRequest, RegisterWorkflowRequest, StartCaseRequest,
+    CancelCaseRequest, CreateCaseRequest, EditDeadLetterRequest, ExecuteCaseRequest,
+    ExecutePatternRequest, GetCaseHistoryRequest, GetCaseRequest, GetDeadLetterRequest,
+    GetPatternRequest, GetWorkItemRequest, GetWorkflowRequest, ListCasesRequest,
+    ListDeadLettersRequest, ListPatternsRequest, ListWorkflowsRequest, PublishMessageRequest,
+    PurgeDeadLettersRequest, RedriveDeadLetterRequest, RegisterWorkflowRequest, StartCaseRequest,
+};
+use crate::api::service::{
+    CaseService, DeadLetterService, MessageService, PatternService, WorkItemApiService,
+    WorkflowService,
 };
-use crate::api::service::{CaseService, PatternService, WorkflowService};
 use crate::api::transport::RestAdapter;
 use crate::case::CaseId;
 use crate::executor::WorkflowEngine;
 use crate::observability::HealthStatus;
 use crate::parser::WorkflowSpecId;
 use crate::patterns::PatternId;
+use crate::security::Principal;
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Json},
 };
 use std::collections::HashMap;
 use std::sync::Arc;
+use uuid::Uuid;
 
 /// Register a workflow
 pub async fn register_workflow(
     State(engine): State<Arc<WorkflowEngine>>,
+    principal: Option<Extension<Principal>>,
     Json(request): Json<RegisterWorkflowRequest>,
 ) -> axum::response::Response {
+    audit_principal(&principal, "register_workflow");
     let service = WorkflowService::new(engine);
     let result = service.register_workflow(request).await;
     RestAdapter::result_to_response(result)
@@ -54,8 +63,10 @@ pub async fn get_workflow(
 /// Create a case
 pub async fn create_case(
     State(engine): State<Arc<WorkflowEngine>>,
+    principal: Option<Extension<Principal>>,
     Json(request): Json<CreateCaseRequest>,
 ) -> axum::response::Response {
+    audit_principal(&principal, "create_case");
     let service = CaseService::new(engine);
     let result = service.create_case(request).await;
     RestAdapter::result_to_response(result)
@@ -336,8 +347,10 @@ pub async fn start_case(
 /// Execute case
 pub async fn execute_case(
     State(engine): State<Arc<WorkflowEngine>>,
+    principal: Option<Extension<Principal>>,
     Path(id): Path<String>,
 ) -> axum::response::Response {
+    audit_principal(&principal, "execute_case");
     let case_id = match CaseId::parse_str(&id) {
         Ok(id) => id,
         Err(_) => {
@@ -433,3 +446,122 @@ pub async fn execute_pattern(
     let result = service.execute_pattern(request).await;
     RestAdapter::result_to_response(result)
 }
+
+/// List dead letter entries (optionally filtered by `?status=pending|quarantined`)
+pub async fn list_dead_letters(
+    State(engine): State<Arc<WorkflowEngine>>,
+    Query(request): Query<ListDeadLettersRequest>,
+) -> axum::response::Response {
+    let service = DeadLetterService::new(engine);
+    let result = service.list(request).await;
+    RestAdapter::result_to_response(result)
+}
+
+/// Get a dead letter entry
+pub async fn get_dead_letter(
+    State(engine): State<Arc<WorkflowEngine>>,
+    Path(id): Path<String>,
+) -> axum::response::Response {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return invalid_dead_letter_id(),
+    };
+
+    let service = DeadLetterService::new(engine);
+    let result = service.get(GetDeadLetterRequest { id }).await;
+    RestAdapter::result_to_response(result)
+}
+
+/// Replace the payload of a dead letter entry
+pub async fn edit_dead_letter(
+    State(engine): State<Arc<WorkflowEngine>>,
+    principal: Option<Extension<Principal>>,
+    Path(id): Path<String>,
+    Json(operation_data): Json<serde_json::Value>,
+) -> axum::response::Response {
+    audit_principal(&principal, "edit_dead_letter");
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return invalid_dead_letter_id(),
+    };
+
+    let service = DeadLetterService::new(engine);
+    let request = EditDeadLetterRequest { id, operation_data };
+    let result = service.edit(request).await;
+    RestAdapter::result_to_response(result)
+}
+
+/// Redrive a dead letter entry (`?requeue=true` hands it to the redrive worker)
+pub async fn redrive_dead_letter(
+    State(engine): State<Arc<WorkflowEngine>>,
+    principal: Option<Extension<Principal>>,
+    Path(id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> axum::response::Response {
+    audit_principal(&principal, "redrive_dead_letter");
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return invalid_dead_letter_id(),
+    };
+
+    let service = DeadLetterService::new(engine);
+    let request = RedriveDeadLetterRequest {
+        id,
+        requeue: params.get("requeue").is_some_and(|v| v == "true"),
+    };
+    let result = service.redrive(request).await;
+    RestAdapter::result_to_response(result)
+}
+
+/// Purge dead letter entries (optionally filtered by `?status=pending|quarantined`)
+pub async fn purge_dead_letters(
+    State(engine): State<Arc<WorkflowEngine>>,
+    principal: Option<Extension<Principal>>,
+    Query(request): Query<PurgeDeadLettersRequest>,
+) -> axum::response::Response {
+    audit_principal(&principal, "purge_dead_letters");
+    let service = DeadLetterService::new(engine);
+    let result = service.purge(request).await;
+    RestAdapter::result_to_response(result)
+}
+
+/// Publish a message to waiting message-catch tasks or message-start tasks
+pub async fn publish_message(
+    State(engine): State<Arc<WorkflowEngine>>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<PublishMessageRequest>,
+) -> axum::response::Response {
+    audit_principal(&principal, "publish_message");
+    let service = MessageService::new(engine);
+    let result = service.publish(request).await;
+    RestAdapter::result_to_response(result)
+}
+
+/// Get a work item with the form generated from its task parameters
+pub async fn get_work_item(
+    State(engine): State<Arc<WorkflowEngine>>,
+    Path(id): Path<String>,
+) -> axum::response::Response {
+    let service = WorkItemApiService::new(engine);
+    let result = service.get(GetWorkItemRequest { id }).await;
+    RestAdapter::result_to_response(result)
+}
+
+fn invalid_dead_letter_id() -> axum::response::Response {
+    RestAdapter::error_to_response(crate::api::models::errors::ApiError::new(
+        "BAD_REQUEST",
+        "Invalid dead letter entry ID format",
+    ))
+}
+
+/// Record the authenticated principal of a state-changing request
+fn audit_principal(principal: &Option<Extension<Principal>>, operation: &str) {
+    if let Some(Extension(principal)) = principal {
+        tracing::info!(
+            audit.event = "api_request",
+            audit.principal = %principal.id,
+            audit.operation = operation,
+            "Authenticated API request"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 //! REST API server implementation
 
+use crate::api::middleware::{auth_middleware, authorize_middleware, RouteAuthorization};
 use crate::executor::WorkflowEngine;
+use crate::security::{Action, AuthManager};
+use axum::routing::MethodRouter;
 use axum::Router;
 use std::sync::Arc;
 
@@ -9,6 +12,8 @@ pub struct RestApiServer {
     engine: Arc<WorkflowEngine>,
     /// Enable Fortune 5 features
     fortune5_enabled: bool,
+    /// Bearer-token authentication and per-route authorization
+    auth: Option<Arc<AuthManager>>,
 }
 
 impl RestApiServer {
@@ -17,6 +22,7 @@ impl RestApiServer {
         Self {
             engine,
             fortune5_enabled: false,
+            auth: None,
         }
     }
 
@@ -25,29 +31,115 @@ impl RestApiServer {
         Self {
             engine,
             fortune5_enabled: true,
+            auth: None,
         }
     }
 
+    /// Require authenticated and authorized requests on all routes except health
+    pub fn with_auth(mut self, auth: Arc<AuthManager>) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
     /// Create the router
     ///
     /// Provides REST API routes for workflow management:
     /// - Health check route (GET /health)
     /// - Workflow registration (POST /workflows)
     /// - Case creation (POST /cases)
-    /// - Case execution (POST /cases/:id/execute)
-    /// - Case status (GET /cases/:id)
+    /// - Case execution (POST /cases/{id}/execute)
+    /// - Case status (GET /cases/{id})
+    /// - Dead letter administration (GET/DELETE /dlq, GET/PUT /dlq/{id},
+    ///   POST /dlq/{id}/redrive), only with auth configured
+    /// - Message publication to waiting or message-started cases (POST /messages)
+    /// - Work item with its generated form (GET /workitems/{id})
+    ///
+    /// With auth configured, every route except health requires a valid bearer
+    /// token and the route's action. Without it the destructive dead letter
+    /// routes are not served at all.
     pub fn router(&self) -> Router {
         use crate::api::rest::handlers;
         use axum::routing::{get, post};
 
-        Router::new()
+        let router = Router::new()
             .route("/health", get(handlers::health))
-            .route("/workflows", post(handlers::register_workflow))
-            .route("/cases", post(handlers::create_case))
-            .route("/cases/:id/execute", post(handlers::execute_case))
-            .route("/cases/:id/history", get(handlers::get_case_history))
-            .route("/cases/:id", get(handlers::get_case))
-            .with_state(self.engine.clone())
+            .route(
+                "/workflows",
+                self.secured(post(handlers::register_workflow), Action::CreateWorkflow),
+            )
+            .route(
+                "/cases",
+                self.secured(post(handlers::create_case), Action::CreateCase),
+            )
+            .route(
+                "/cases/{id}/execute",
+                self.secured(post(handlers::execute_case), Action::UpdateCase),
+            )
+            .route(
+                "/cases/{id}/history",
+                self.secured(get(handlers::get_case_history), Action::ReadCase),
+            )
+            .route(
+                "/cases/{id}",
+                self.secured(get(handlers::get_case), Action::ReadCase),
+            )
+            .route(
+                "/messages",
+                self.secured(post(handlers::publish_message), Action::PublishMessage),
+            )
+            .route(
+                "/workitems/{id}",
+                self.secured(get(handlers::get_work_item), Action::ReadCase),
+            );
+
+        let router = if self.auth.is_some() {
+            router
+                .route(
+                    "/dlq",
+                    self.secured(
+                        get(handlers::list_dead_letters).delete(handlers::purge_dead_letters),
+                        Action::ManageDeadLetters,
+                    ),
+                )
+                .route(
+                    "/dlq/{id}",
+                    self.secured(
+                        get(handlers::get_dead_letter).put(handlers::edit_dead_letter),
+                        Action::ManageDeadLetters,
+                    ),
+                )
+                .route(
+                    "/dlq/{id}/redrive",
+                    self.secured(
+                        post(handlers::redrive_dead_letter),
+                        Action::ManageDeadLetters,
+                    ),
+                )
+        } else {
+            router
+        };
+
+        router.with_state(self.engine.clone())
+    }
+
+    /// Authenticate and authorize a route when auth is configured
+    fn secured(
+        &self,
+        route: MethodRouter<Arc<WorkflowEngine>>,
+        action: Action,
+    ) -> MethodRouter<Arc<WorkflowEngine>> {
+        match self.auth {
+            Some(ref auth) => route
+                .route_layer(axum::middleware::from_fn_with_state(
+                    RouteAuthorization::new(auth.clone(), action),
+                    authorize_middleware,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    auth.clone(),
+                    auth_middleware,
+                )),
+            None => route,
+        }
     }
 
     /// Get engine reference
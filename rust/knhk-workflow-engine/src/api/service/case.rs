@@ -131,8 +131,10 @@ impl CaseService {
         let entries: Vec<CaseHistoryEntry> = events
             .into_iter()
             .filter_map(|event| match event {
-                StateEvent::TaskStarted { .. } | StateEvent::TaskCompleted { .. } => {
-                    // Task events are logged but not returned in case history
+                StateEvent::TaskStarted { .. }
+                | StateEvent::TaskCompleted { .. }
+                | StateEvent::CaseCheckpointed { .. } => {
+                    // Task and checkpoint events are logged but not returned in case history
                     None
                 }
                 StateEvent::CaseCreated {
@@ -147,6 +149,108 @@ impl CaseService {
                         "spec_id": spec_id.to_string(),
                     }),
                 }),
+                StateEvent::RegionCancelled {
+                    case_id,
+                    task_id,
+                    cancelled,
+                    withdrawn_work_items,
+                    timestamp,
+                } => Some(CaseHistoryEntry {
+                    timestamp,
+                    event_type: "region_cancelled".to_string(),
+                    data: serde_json::json!({
+                        "case_id": case_id.to_string(),
+                        "task_id": task_id,
+                        "cancelled": cancelled,
+                        "withdrawn_work_items": withdrawn_work_items,
+                    }),
+                }),
+                StateEvent::ExceptionRaised {
+                    case_id,
+                    task_id,
+                    work_item_id,
+                    exception,
+                    rule_id,
+                    exlet,
+                    timestamp,
+                } => Some(CaseHistoryEntry {
+                    timestamp,
+                    event_type: "exception_raised".to_string(),
+                    data: serde_json::json!({
+                        "case_id": case_id.to_string(),
+                        "task_id": task_id,
+                        "work_item_id": work_item_id,
+                        "exception": exception,
+                        "rule_id": rule_id,
+                        "exlet": exlet,
+                    }),
+                }),
+                StateEvent::ExletStep {
+                    case_id,
+                    task_id,
+                    exlet,
+                    step,
+                    success,
+                    detail,
+                    timestamp,
+                } => Some(CaseHistoryEntry {
+                    timestamp,
+                    event_type: "exlet_step".to_string(),
+                    data: serde_json::json!({
+                        "case_id": case_id.to_string(),
+                        "task_id": task_id,
+                        "exlet": exlet,
+                        "step": step,
+                        "success": success,
+                        "detail": detail,
+                    }),
+                }),
+                StateEvent::SubCaseStarted {
+                    case_id,
+                    task_id,
+                    child_case_id,
+                    child_spec_id,
+                    timestamp,
+                } => Some(CaseHistoryEntry {
+                    timestamp,
+                    event_type: "subcase_started".to_string(),
+                    data: serde_json::json!({
+                        "case_id": case_id.to_string(),
+                        "task_id": task_id,
+                        "child_case_id": child_case_id.to_string(),
+                        "child_spec_id": child_spec_id.to_string(),
+                    }),
+                }),
+                StateEvent::SubCaseFinished {
+                    case_id,
+                    task_id,
+                    child_case_id,
+                    child_state,
+                    timestamp,
+                } => Some(CaseHistoryEntry {
+                    timestamp,
+                    event_type: "subcase_finished".to_string(),
+                    data: serde_json::json!({
+                        "case_id": case_id.to_string(),
+                        "task_id": task_id,
+                        "child_case_id": child_case_id.to_string(),
+                        "child_state": child_state,
+                    }),
+                }),
+                StateEvent::CaseMigrated {
+                    case_id,
+                    from_spec_id,
+                    to_spec_id,
+                    timestamp,
+                } => Some(CaseHistoryEntry {
+                    timestamp,
+                    event_type: "case_migrated".to_string(),
+                    data: serde_json::json!({
+                        "case_id": case_id.to_string(),
+                        "from_spec_id": from_spec_id.to_string(),
+                        "to_spec_id": to_spec_id.to_string(),
+                    }),
+                }),
                 StateEvent::CaseStateChanged {
                     case_id,
                     old_state,
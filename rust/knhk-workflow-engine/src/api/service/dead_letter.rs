@@ -0,0 +1,122 @@
+//! Dead letter service
+//!
+//! Service layer for administering the dead letter queue of failed case executions.
+
+use crate::api::models::{
+    errors::ApiError,
+    requests::{
+        EditDeadLetterRequest, GetDeadLetterRequest, ListDeadLettersRequest,
+        PurgeDeadLettersRequest, RedriveDeadLetterRequest,
+    },
+    responses::{
+        DeadLetterResponse, ListDeadLettersResponse, PurgeDeadLettersResponse,
+        RedriveDeadLetterResponse,
+    },
+    ApiResult,
+};
+use crate::executor::WorkflowEngine;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Dead letter service for inspecting, editing, redriving and purging entries
+pub struct DeadLetterService {
+    engine: Arc<WorkflowEngine>,
+}
+
+impl DeadLetterService {
+    /// Create a new dead letter service
+    pub fn new(engine: Arc<WorkflowEngine>) -> Self {
+        Self { engine }
+    }
+
+    /// List entries
+    pub async fn list(
+        &self,
+        request: ListDeadLettersRequest,
+    ) -> ApiResult<ListDeadLettersResponse> {
+        let mut entries = self
+            .engine
+            .dead_letter_queue()
+            .list()
+            .map_err(ApiError::from)?;
+        if let Some(status) = request.status {
+            entries.retain(|entry| entry.status == status);
+        }
+        Ok(ListDeadLettersResponse { entries })
+    }
+
+    /// Get an entry
+    pub async fn get(&self, request: GetDeadLetterRequest) -> ApiResult<DeadLetterResponse> {
+        let entry = self
+            .engine
+            .dead_letter_queue()
+            .get(request.id)
+            .map_err(ApiError::from)?
+            .ok_or_else(|| not_found(request.id))?;
+        Ok(DeadLetterResponse { entry })
+    }
+
+    /// Replace the payload of an entry
+    pub async fn edit(&self, request: EditDeadLetterRequest) -> ApiResult<DeadLetterResponse> {
+        self.ensure_exists(request.id)?;
+        let entry = self
+            .engine
+            .dead_letter_queue()
+            .edit_payload(request.id, request.operation_data)
+            .map_err(ApiError::from)?;
+        Ok(DeadLetterResponse { entry })
+    }
+
+    /// Redrive an entry now, or requeue it for the redrive worker
+    pub async fn redrive(
+        &self,
+        request: RedriveDeadLetterRequest,
+    ) -> ApiResult<RedriveDeadLetterResponse> {
+        self.ensure_exists(request.id)?;
+        if request.requeue {
+            let entry = self
+                .engine
+                .dead_letter_queue()
+                .requeue(request.id)
+                .map_err(ApiError::from)?;
+            return Ok(RedriveDeadLetterResponse {
+                redriven: false,
+                entry: Some(entry),
+            });
+        }
+
+        self.engine
+            .redrive_dead_letter(request.id)
+            .await
+            .map_err(ApiError::from)?;
+        Ok(RedriveDeadLetterResponse {
+            redriven: true,
+            entry: None,
+        })
+    }
+
+    /// Purge entries
+    pub async fn purge(
+        &self,
+        request: PurgeDeadLettersRequest,
+    ) -> ApiResult<PurgeDeadLettersResponse> {
+        let purged = self
+            .engine
+            .dead_letter_queue()
+            .purge(request.status)
+            .map_err(ApiError::from)?;
+        Ok(PurgeDeadLettersResponse { purged })
+    }
+
+    fn ensure_exists(&self, id: Uuid) -> ApiResult<()> {
+        match self.engine.dead_letter_queue().get(id) {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(not_found(id)),
+            Err(e) => Err(ApiError::from(e)),
+        }
+    }
+}
+
+fn not_found(id: Uuid) -> ApiError {
+    ApiError::new("NOT_FOUND", format!("Dead letter entry {} not found", id))
+}
@@ -0,0 +1,50 @@
+//! Message service
+//!
+//! Service layer for publishing correlated messages to message-catch and
+//! message-start tasks.
+
+use crate::api::models::{
+    errors::ApiError, requests::PublishMessageRequest, responses::PublishMessageResponse, ApiResult,
+};
+use crate::executor::WorkflowEngine;
+use crate::services::CorrelatedMessage;
+use std::sync::Arc;
+
+/// Message service for publishing messages to running cases
+pub struct MessageService {
+    engine: Arc<WorkflowEngine>,
+}
+
+impl MessageService {
+    /// Create a new message service
+    pub fn new(engine: Arc<WorkflowEngine>) -> Self {
+        Self { engine }
+    }
+
+    /// Publish a message
+    pub async fn publish(
+        &self,
+        request: PublishMessageRequest,
+    ) -> ApiResult<PublishMessageResponse> {
+        let mut message = CorrelatedMessage::new(request.name, request.payload);
+        message.correlation = request.correlation;
+        if let Some(ttl) = request.ttl_seconds {
+            let ttl = i64::try_from(ttl)
+                .ok()
+                .and_then(chrono::Duration::try_seconds)
+                .ok_or_else(|| ApiError::new("BAD_REQUEST", "Message TTL is out of range"))?;
+            message = message.with_ttl(ttl);
+        }
+
+        let message_id = message.id.clone();
+        let delivery = self
+            .engine
+            .publish_message(message)
+            .await
+            .map_err(ApiError::from)?;
+        Ok(PublishMessageResponse {
+            message_id,
+            delivery,
+        })
+    }
+}
@@ -3,10 +3,16 @@
 //! Service layer that encapsulates business logic for all transport layers.
 
 pub mod case;
+pub mod dead_letter;
+pub mod message;
 pub mod pattern;
+pub mod work_item;
 pub mod workflow;
 
 // Re-export for convenience
 pub use case::CaseService;
+pub use dead_letter::DeadLetterService;
+pub use message::MessageService;
 pub use pattern::PatternService;
+pub use work_item::WorkItemApiService;
 pub use workflow::WorkflowService;
@@ -0,0 +1,65 @@
+//! Work item service
+//!
+//! Service layer for reading human task work items together with the form
+//! generated from the task's typed parameters.
+
+use crate::api::models::{
+    errors::ApiError, requests::GetWorkItemRequest, responses::WorkItemResponse, ApiResult,
+};
+use crate::data::{TaskDataContract, TaskForm};
+use crate::executor::WorkflowEngine;
+use std::sync::Arc;
+
+/// Work item service for human task clients
+///
+/// Named apart from [`crate::services::WorkItemService`], which owns the work items.
+pub struct WorkItemApiService {
+    engine: Arc<WorkflowEngine>,
+}
+
+impl WorkItemApiService {
+    /// Create a new work item service
+    pub fn new(engine: Arc<WorkflowEngine>) -> Self {
+        Self { engine }
+    }
+
+    /// Get a work item and its form
+    pub async fn get(&self, request: GetWorkItemRequest) -> ApiResult<WorkItemResponse> {
+        let work_item = self
+            .engine
+            .work_item_service()
+            .get_work_item(&request.id)
+            .await
+            .ok_or_else(|| {
+                ApiError::new("NOT_FOUND", format!("Work item {} not found", request.id))
+            })?;
+
+        let spec = self
+            .engine
+            .get_workflow(work_item.spec_id)
+            .await
+            .map_err(ApiError::from)?;
+        let task = spec.tasks.get(&work_item.task_id).ok_or_else(|| {
+            ApiError::new(
+                "NOT_FOUND",
+                format!(
+                    "Task {} of work item {} not found",
+                    work_item.task_id, work_item.id
+                ),
+            )
+        })?;
+
+        let contract = TaskDataContract::for_task(task).map_err(ApiError::from)?;
+        let form = if contract.is_empty() {
+            None
+        } else {
+            Some(TaskForm::from_contract(
+                &contract,
+                &task.name,
+                Some(&work_item.data),
+            ))
+        };
+
+        Ok(WorkItemResponse { work_item, form })
+    }
+}
@@ -10,11 +10,15 @@ use knhk_workflow_engine::{
     executor::WorkflowEngine,
     integration::{OtelIntegration, WeaverIntegration},
     parser::WorkflowParser,
+    resilience::{DefaultRetryPolicy, DlqEntryStatus, RetryConfig},
+    security::{Action, AuthManager, AuthPolicy, ClaimRoleMapping, JwtConfig, JwtValidator},
     state::StateStore,
     CaseId, WorkflowSpecId,
 };
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
 
 #[derive(Parser)]
 #[command(name = "knhk-workflow")]
@@ -104,6 +108,63 @@ enum Commands {
         /// Host to bind to
         #[arg(long, default_value = "0.0.0.0")]
         host: String,
+        /// Interval between dead letter redrive passes (seconds)
+        #[arg(long, default_value = "30")]
+        dlq_redrive_interval: u64,
+        /// JWKS file with the bearer-token signing keys
+        #[arg(long)]
+        jwks: Option<PathBuf>,
+        /// Expected token issuer (iss)
+        #[arg(long)]
+        jwt_issuer: Option<String>,
+        /// Expected token audience (aud)
+        #[arg(long)]
+        jwt_audience: Option<String>,
+        /// Grant a role to tokens with a claim value (CLAIM=VALUE:ROLE, repeatable)
+        #[arg(long = "role-mapping", value_name = "CLAIM=VALUE:ROLE")]
+        role_mappings: Vec<String>,
+        /// Allow a role to perform actions (ROLE=ACTION[,ACTION...], repeatable)
+        #[arg(long = "grant", value_name = "ROLE=ACTIONS")]
+        grants: Vec<String>,
+        /// Interval between JWKS reloads (seconds)
+        #[arg(long, default_value = "60")]
+        jwks_refresh_interval: u64,
+        /// Serve without authentication (local development only)
+        #[arg(long)]
+        insecure_no_auth: bool,
+    },
+
+    /// Administer the dead letter queue of a running server
+    Dlq {
+        #[command(subcommand)]
+        command: DlqCommands,
+        /// Server base URL
+        #[arg(long, default_value = "http://localhost:8080")]
+        server: String,
+        /// Bearer token with the ManageDeadLetters permission
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Publish a message to a running server (message-catch and message-start tasks)
+    PublishMessage {
+        /// Message name
+        name: String,
+        /// Correlation key value (key=value, repeatable; JSON values are parsed)
+        #[arg(short, long = "correlation", value_name = "KEY=VALUE")]
+        correlation: Vec<String>,
+        /// Message payload (JSON)
+        #[arg(short, long)]
+        data: Option<String>,
+        /// Buffering time in seconds if no case waits for the message
+        #[arg(long)]
+        ttl: Option<u64>,
+        /// Server base URL
+        #[arg(long, default_value = "http://localhost:8080")]
+        server: String,
+        /// Bearer token for a server with auth enabled
+        #[arg(long)]
+        token: Option<String>,
     },
 
     /// List all registered patterns
@@ -151,6 +212,332 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum DlqCommands {
+    /// List dead letter entries
+    List {
+        /// Only list entries with this status (pending, quarantined)
+        #[arg(long)]
+        status: Option<String>,
+    },
+
+    /// Show a dead letter entry
+    Show {
+        /// Entry ID
+        id: String,
+    },
+
+    /// Replace the payload of a dead letter entry
+    Edit {
+        /// Entry ID
+        id: String,
+        /// Operation data (JSON)
+        #[arg(short, long)]
+        data: String,
+    },
+
+    /// Redrive a dead letter entry now
+    Redrive {
+        /// Entry ID
+        id: String,
+        /// Requeue for the server's redrive worker instead
+        #[arg(long)]
+        requeue: bool,
+    },
+
+    /// Purge dead letter entries
+    Purge {
+        /// Only purge entries with this status (pending, quarantined)
+        #[arg(long)]
+        status: Option<String>,
+    },
+}
+
+fn parse_dlq_status(status: Option<String>) -> Result<Option<DlqEntryStatus>, String> {
+    status
+        .map(|status| {
+            serde_json::from_value(serde_json::Value::String(status.clone())).map_err(|_| {
+                format!(
+                    "Invalid status {} (expected pending or quarantined)",
+                    status
+                )
+            })
+        })
+        .transpose()
+}
+
+/// Bearer-token settings of `serve`
+struct ServeAuthArgs {
+    jwks: Option<PathBuf>,
+    issuer: Option<String>,
+    audience: Option<String>,
+    role_mappings: Vec<String>,
+    grants: Vec<String>,
+    insecure_no_auth: bool,
+}
+
+/// Build the auth manager of `serve`
+///
+/// JWKS, issuer and audience are required unless auth is explicitly disabled.
+fn serve_auth(args: ServeAuthArgs) -> Result<Option<AuthManager>, String> {
+    let (jwks, issuer, audience) = match (args.jwks, args.issuer, args.audience) {
+        (Some(jwks), Some(issuer), Some(audience)) => (jwks, issuer, audience),
+        (None, None, None) if args.insecure_no_auth => return Ok(None),
+        (None, None, None) => {
+            return Err(
+                "Refusing to serve without authentication: pass --jwks, --jwt-issuer \
+                 and --jwt-audience (or --insecure-no-auth for local development)"
+                    .to_string(),
+            )
+        }
+        _ => {
+            return Err(
+                "--jwks, --jwt-issuer and --jwt-audience must be given together".to_string(),
+            )
+        }
+    };
+    if args.insecure_no_auth {
+        return Err("--insecure-no-auth conflicts with --jwks".to_string());
+    }
+
+    let mut config = JwtConfig::new(issuer, audience, jwks);
+    for mapping in args.role_mappings {
+        let parsed = mapping.split_once('=').and_then(|(claim, rest)| {
+            rest.rsplit_once(':')
+                .map(|(value, role)| ClaimRoleMapping::new(claim, value, role))
+        });
+        config = config.with_role_mapping(parsed.ok_or_else(|| {
+            format!(
+                "Invalid role mapping {} (expected CLAIM=VALUE:ROLE)",
+                mapping
+            )
+        })?);
+    }
+
+    let mut auth = AuthManager::new();
+    for grant in args.grants {
+        let (role, actions) = grant
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid grant {} (expected ROLE=ACTION[,ACTION...])", grant))?;
+        let actions = actions
+            .split(',')
+            .map(|action| {
+                serde_json::from_value::<Action>(serde_json::Value::String(
+                    action.trim().to_string(),
+                ))
+                .map_err(|_| format!("Unknown action {} in grant {}", action, grant))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        auth.add_policy(AuthPolicy {
+            name: format!("grant {}", grant),
+            allowed_principals: vec![],
+            allowed_roles: vec![role.trim().to_string()],
+            resource_patterns: vec![],
+            actions,
+        });
+    }
+    auth.set_jwt_validator(
+        JwtValidator::new(config).map_err(|e| format!("Failed to load JWKS: {}", e))?,
+    );
+    Ok(Some(auth))
+}
+
+#[cfg(feature = "http")]
+fn parse_correlation(
+    pairs: Vec<String>,
+) -> Result<std::collections::BTreeMap<String, serde_json::Value>, String> {
+    pairs
+        .into_iter()
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid correlation {} (expected key=value)", pair))?;
+            let value = serde_json::from_str(value)
+                .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+            Ok((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// Send a dead letter administration request to a running server
+#[cfg(feature = "http")]
+async fn run_dlq_command(
+    command: &DlqCommands,
+    server: &str,
+    token: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use knhk_workflow_engine::api::models::responses::{
+        DeadLetterResponse, ListDeadLettersResponse, PurgeDeadLettersResponse,
+        RedriveDeadLetterResponse,
+    };
+
+    let base = format!("{}/dlq", server.trim_end_matches('/'));
+    let client = reqwest::Client::new();
+    let parse_id = |id: &str| Uuid::parse_str(id).map_err(|e| format!("Invalid entry ID: {}", e));
+    // Validate the status locally for a clear error before sending it
+    let status_query = |status: &Option<String>| -> Result<Vec<(&str, String)>, String> {
+        parse_dlq_status(status.clone())?;
+        Ok(status.iter().map(|s| ("status", s.clone())).collect())
+    };
+
+    let request = match command {
+        DlqCommands::List { status } => client.get(&base).query(&status_query(status)?),
+        DlqCommands::Show { id } => client.get(format!("{}/{}", base, parse_id(id)?)),
+        DlqCommands::Edit { id, data } => {
+            let operation_data: serde_json::Value =
+                serde_json::from_str(data).map_err(|e| format!("Invalid JSON data: {}", e))?;
+            client
+                .put(format!("{}/{}", base, parse_id(id)?))
+                .json(&operation_data)
+        }
+        DlqCommands::Redrive { id, requeue } => client
+            .post(format!("{}/{}/redrive", base, parse_id(id)?))
+            .query(&[("requeue", requeue.to_string())]),
+        DlqCommands::Purge { status } => client.delete(&base).query(&status_query(status)?),
+    };
+    let request = match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach server {}: {}", server, e))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND && matches!(command, DlqCommands::List { .. }) {
+        return Err(format!(
+            "Server {} does not serve /dlq (dead letter routes require auth to be configured)",
+            server
+        )
+        .into());
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Dead letter request failed ({}): {}", status, body).into());
+    }
+
+    match command {
+        DlqCommands::List { .. } => {
+            let entries = response
+                .json::<ListDeadLettersResponse>()
+                .await
+                .map_err(|e| format!("Invalid response from {}: {}", server, e))?
+                .entries;
+            println!("Dead letter entries ({}):", entries.len());
+            for entry in entries {
+                println!(
+                    "  - {} [{:?}] case {} retries {}: {}",
+                    entry.id,
+                    entry.status,
+                    entry
+                        .case_id
+                        .map(|id| id.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    entry.retry_count,
+                    entry.error
+                );
+            }
+        }
+        DlqCommands::Show { .. } => {
+            let entry = response
+                .json::<DeadLetterResponse>()
+                .await
+                .map_err(|e| format!("Invalid response from {}: {}", server, e))?
+                .entry;
+            let json = serde_json::to_string_pretty(&entry)
+                .map_err(|e| format!("Failed to serialize dead letter: {}", e))?;
+            println!("{}", json);
+        }
+        DlqCommands::Edit { id, .. } => println!("Dead letter entry updated: {}", id),
+        DlqCommands::Redrive { id, .. } => {
+            let redrive = response
+                .json::<RedriveDeadLetterResponse>()
+                .await
+                .map_err(|e| format!("Invalid response from {}: {}", server, e))?;
+            if redrive.redriven {
+                println!("Dead letter entry redriven: {}", id);
+            } else {
+                println!("Dead letter entry requeued: {}", id);
+            }
+        }
+        DlqCommands::Purge { .. } => {
+            let purged = response
+                .json::<PurgeDeadLettersResponse>()
+                .await
+                .map_err(|e| format!("Invalid response from {}: {}", server, e))?
+                .purged;
+            println!("Purged {} dead letter entries", purged);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+async fn run_dlq_command(
+    _command: &DlqCommands,
+    _server: &str,
+    _token: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("Dead letter administration requires the http feature".into())
+}
+
+/// Publish a message to a running server
+#[cfg(feature = "http")]
+async fn run_publish_message(
+    name: String,
+    correlation: Vec<String>,
+    data: Option<String>,
+    ttl: Option<u64>,
+    server: String,
+    token: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let payload = if let Some(data_str) = data {
+        serde_json::from_str(&data_str).map_err(|e| format!("Invalid JSON data: {}", e))?
+    } else {
+        serde_json::json!({})
+    };
+    let request = serde_json::json!({
+        "name": name,
+        "correlation": parse_correlation(correlation)?,
+        "payload": payload,
+        "ttl_seconds": ttl,
+    });
+
+    let url = format!("{}/messages", server.trim_end_matches('/'));
+    let mut http_request = reqwest::Client::new().post(&url).json(&request);
+    if let Some(token) = token {
+        http_request = http_request.bearer_auth(token);
+    }
+    let response = http_request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to publish message to {}: {}", url, e))?;
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from {}: {}", url, e))?;
+    if !status.is_success() {
+        return Err(format!("Failed to publish message ({}): {}", status, body).into());
+    }
+    let json = serde_json::to_string_pretty(&body)
+        .map_err(|e| format!("Failed to serialize response: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+async fn run_publish_message(
+    _name: String,
+    _correlation: Vec<String>,
+    _data: Option<String>,
+    _ttl: Option<u64>,
+    _server: String,
+    _token: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("Publishing messages requires the http feature".into())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
@@ -214,6 +601,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    // The server holds the state store lock, so commands for a running server
+    // go through its REST API
+    if let Commands::Dlq {
+        command,
+        server,
+        token,
+    } = &cli.command
+    {
+        return run_dlq_command(command, server, token.as_deref()).await;
+    }
+    if let Commands::PublishMessage {
+        name,
+        correlation,
+        data,
+        ttl,
+        server,
+        token,
+    } = cli.command
+    {
+        return run_publish_message(name, correlation, data, ttl, server, token).await;
+    }
+
     // Create state store
     let state_store = StateStore::new(&cli.state_store)
         .map_err(|e| format!("Failed to create state store: {}", e))?;
@@ -359,11 +768,53 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("(Feature not yet implemented)");
         }
 
-        Commands::Serve { port, host } => {
+        Commands::Serve {
+            port,
+            host,
+            dlq_redrive_interval,
+            jwks,
+            jwt_issuer,
+            jwt_audience,
+            role_mappings,
+            grants,
+            jwks_refresh_interval,
+            insecure_no_auth,
+        } => {
+            let auth = serve_auth(ServeAuthArgs {
+                jwks,
+                issuer: jwt_issuer,
+                audience: jwt_audience,
+                role_mappings,
+                grants,
+                insecure_no_auth,
+            })?;
             println!("Starting REST API server on {}:{}", host, port);
+            engine.start_dead_letter_redrive(
+                // Spread redrives of entries that failed together
+                RetryConfig::default().with_jitter(0.1),
+                Arc::new(DefaultRetryPolicy),
+                Duration::from_secs(dlq_redrive_interval.max(1)),
+            );
             #[cfg(feature = "http")]
             use knhk_workflow_engine::api::rest::RestApiServer;
-            let app = RestApiServer::new(engine.clone()).router();
+            let mut server = RestApiServer::new(engine.clone());
+            match auth {
+                Some(auth) => {
+                    if let Some(validator) = auth.jwt_validator() {
+                        validator
+                            .spawn_key_refresh(Duration::from_secs(jwks_refresh_interval.max(1)));
+                    }
+                    server = server.with_auth(Arc::new(auth));
+                }
+                None => {
+                    tracing::warn!("Serving WITHOUT authentication (--insecure-no-auth)");
+                    eprintln!(
+                        "WARNING: authentication is disabled; every client can run any \
+                         request. Do not expose this server."
+                    );
+                }
+            }
+            let app = server.router();
             use std::net::SocketAddr;
             let addr: SocketAddr = format!("{}:{}", host, port)
                 .parse()
@@ -379,6 +830,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .map_err(|e| format!("Server error: {}", e))?;
         }
 
+        Commands::Dlq { .. } => {
+            // Dlq talks to the server and is handled before the state store is opened
+            unreachable!("Dlq should be handled before match")
+        }
+
+        Commands::PublishMessage { .. } => {
+            // PublishMessage talks to the server and is handled before the state store is opened
+            unreachable!("PublishMessage should be handled before match")
+        }
+
         Commands::ListPatterns => {
             let registry = engine.pattern_registry();
             let patterns = registry.list_patterns();
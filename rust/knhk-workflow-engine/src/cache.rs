@@ -106,6 +106,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         });
 
         cache.insert_spec(spec_id.clone(), spec.clone());
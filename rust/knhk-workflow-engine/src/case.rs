@@ -68,6 +68,15 @@ impl std::fmt::Display for CaseState {
     }
 }
 
+/// Link from a sub-case to the composite task of its parent case
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaseLink {
+    /// Parent case ID
+    pub case_id: CaseId,
+    /// Composite task that launched the sub-case
+    pub task_id: String,
+}
+
 /// Workflow case (instance)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Case {
@@ -89,6 +98,9 @@ pub struct Case {
     pub task_states: std::collections::HashMap<String, TaskState>,
     /// Error message if failed
     pub error: Option<String>,
+    /// Parent case (for sub-cases launched by composite tasks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<CaseLink>,
 }
 
 /// Task execution state within a case
@@ -121,6 +133,7 @@ impl Case {
             data,
             task_states: std::collections::HashMap::new(),
             error: None,
+            parent: None,
         }
     }
 
@@ -0,0 +1,338 @@
+//! Erasure records for retention enforcement and GDPR subject erasure
+//!
+//! Everything the retention sweeper or a subject-erasure request deletes or
+//! redacts is listed in a [`RetentionReport`], signed with Ed25519 so that the
+//! report can be handed to auditors. Deleted receipts and redacted fields
+//! leave [`Tombstone`]s behind: a purged receipt's tombstone keeps the SHA-256
+//! digest of the receipt, so copies held elsewhere (e.g. in the lockchain)
+//! remain verifiable; a redacted field's tombstone records only the field,
+//! never a digest of the personal value.
+
+use crate::case::CaseId;
+use crate::error::{WorkflowError, WorkflowResult};
+use chrono::{DateTime, Utc};
+use ring::{rand, signature};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Why data was erased
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureReason {
+    /// Retention period expired
+    Retention,
+    /// Right-to-be-forgotten request of a data subject
+    SubjectErasure,
+}
+
+/// Right-to-be-forgotten request of a data subject
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureRequest {
+    /// Data subject, matched against the retention manager's subject field
+    pub subject: String,
+    /// Personal fields to redact (the subject field itself is always redacted)
+    pub personal_fields: Vec<String>,
+}
+
+/// Record left in place of erased data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    /// Tombstone ID
+    pub id: Uuid,
+    /// Case the erased data belonged to
+    pub case_id: CaseId,
+    /// Erased resource (e.g. `receipt:<key>`, `case:<id>`, `work_item:<id>`)
+    pub resource: String,
+    /// Redacted field (None if the whole resource was deleted)
+    pub field: Option<String>,
+    /// Hex SHA-256 digest of the deleted bytes (None for redacted personal fields)
+    pub digest: Option<String>,
+    /// Why the data was erased
+    pub reason: ErasureReason,
+    /// Erasure timestamp
+    pub erased_at: DateTime<Utc>,
+}
+
+impl Tombstone {
+    /// Tombstone for a deleted resource, keeping the digest of its bytes
+    pub fn deleted(case_id: CaseId, resource: String, bytes: &[u8], reason: ErasureReason) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            case_id,
+            resource,
+            field: None,
+            digest: Some(sha256_hex(bytes)),
+            reason,
+            erased_at: Utc::now(),
+        }
+    }
+
+    /// Tombstone for a redacted field of a resource
+    pub fn redacted(
+        case_id: CaseId,
+        resource: String,
+        field: String,
+        reason: ErasureReason,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            case_id,
+            resource,
+            field: Some(field),
+            digest: None,
+            reason,
+            erased_at: Utc::now(),
+        }
+    }
+
+    /// Check that `bytes` are the deleted resource
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        self.digest.as_deref() == Some(sha256_hex(bytes).as_str())
+    }
+}
+
+/// What was done to a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureOutcome {
+    /// Deleted
+    Purged,
+    /// Personal fields redacted
+    Anonymized,
+}
+
+/// One resource deleted or anonymized
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureRecord {
+    /// Resource type (Case, CaseHistory, Receipt, WorkItem, Document)
+    pub resource_type: String,
+    /// Resource ID
+    pub resource_id: String,
+    /// Case the resource belongs to
+    pub case_id: Option<CaseId>,
+    /// What was done
+    pub outcome: ErasureOutcome,
+    /// Redacted fields (anonymization only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+/// Kind of retention run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionReportKind {
+    /// Retention sweep over expired resources
+    Sweep,
+    /// Erasure request of a data subject
+    SubjectErasure {
+        /// Data subject
+        subject: String,
+    },
+}
+
+/// Report of a retention run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionReport {
+    /// Report ID
+    pub id: Uuid,
+    /// Kind of run
+    pub kind: RetentionReportKind,
+    /// Generation timestamp
+    pub generated_at: DateTime<Utc>,
+    /// Resources deleted or anonymized
+    pub records: Vec<ErasureRecord>,
+    /// Eligible cases kept because of a legal hold
+    pub held: Vec<CaseId>,
+    /// Tombstones written
+    pub tombstones: Vec<Tombstone>,
+    /// Error that stopped the run; the report then covers what was erased before it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RetentionReport {
+    /// Create an empty report
+    pub fn new(kind: RetentionReportKind) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            generated_at: Utc::now(),
+            records: Vec::new(),
+            held: Vec::new(),
+            tombstones: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Record a deleted resource
+    pub fn purged(&mut self, resource_type: &str, resource_id: String, case_id: Option<CaseId>) {
+        self.records.push(ErasureRecord {
+            resource_type: resource_type.to_string(),
+            resource_id,
+            case_id,
+            outcome: ErasureOutcome::Purged,
+            fields: Vec::new(),
+        });
+    }
+
+    /// Record an anonymized resource
+    pub fn anonymized(
+        &mut self,
+        resource_type: &str,
+        resource_id: String,
+        case_id: Option<CaseId>,
+        fields: Vec<String>,
+    ) {
+        self.records.push(ErasureRecord {
+            resource_type: resource_type.to_string(),
+            resource_id,
+            case_id,
+            outcome: ErasureOutcome::Anonymized,
+            fields,
+        });
+    }
+
+    /// Canonical bytes covered by the signature
+    fn canonical_bytes(&self) -> WorkflowResult<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| {
+            WorkflowError::Internal(format!("Failed to serialize retention report: {}", e))
+        })
+    }
+}
+
+/// Retention report signed by the engine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedRetentionReport {
+    /// The report
+    pub report: RetentionReport,
+    /// Hex SHA-256 digest of the report
+    pub digest: String,
+    /// Hex Ed25519 signature of the digest
+    pub signature: String,
+    /// Hex Ed25519 public key of the signer
+    pub public_key: String,
+}
+
+impl SignedRetentionReport {
+    /// Verify the digest and signature of the report
+    ///
+    /// Only proves the report was signed by `public_key`; auditors should also
+    /// check that key against the one published by the engine operator.
+    pub fn verify(&self) -> WorkflowResult<()> {
+        let digest = sha256_hex(&self.report.canonical_bytes()?);
+        if digest != self.digest {
+            return Err(WorkflowError::Crypto(format!(
+                "Retention report {} digest mismatch",
+                self.report.id
+            )));
+        }
+        let public_key = hex::decode(&self.public_key)
+            .map_err(|e| WorkflowError::Crypto(format!("Invalid public key: {}", e)))?;
+        let signature_bytes = hex::decode(&self.signature)
+            .map_err(|e| WorkflowError::Crypto(format!("Invalid signature: {}", e)))?;
+        signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+            .verify(digest.as_bytes(), &signature_bytes)
+            .map_err(|_| {
+                WorkflowError::Crypto(format!(
+                    "Retention report {} signature verification failed",
+                    self.report.id
+                ))
+            })
+    }
+}
+
+/// Ed25519 signer for retention reports
+pub struct ReportSigner {
+    key_pair: signature::Ed25519KeyPair,
+}
+
+impl ReportSigner {
+    /// Create a signer with a freshly generated key pair
+    pub fn generate() -> WorkflowResult<Self> {
+        let rng = rand::SystemRandom::new();
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng)
+            .map_err(|e| WorkflowError::Crypto(format!("Failed to generate key pair: {:?}", e)))?;
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    /// Create a signer from a PKCS#8 encoded Ed25519 key pair
+    pub fn from_pkcs8(pkcs8: &[u8]) -> WorkflowResult<Self> {
+        let key_pair = signature::Ed25519KeyPair::from_pkcs8(pkcs8)
+            .map_err(|e| WorkflowError::Crypto(format!("Invalid key format: {:?}", e)))?;
+        Ok(Self { key_pair })
+    }
+
+    /// Hex public key of the signer
+    pub fn public_key_hex(&self) -> String {
+        use ring::signature::KeyPair;
+        hex::encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// Sign a report
+    pub fn sign(&self, report: RetentionReport) -> WorkflowResult<SignedRetentionReport> {
+        let digest = sha256_hex(&report.canonical_bytes()?);
+        let signature = hex::encode(self.key_pair.sign(digest.as_bytes()).as_ref());
+        Ok(SignedRetentionReport {
+            report,
+            digest,
+            signature,
+            public_key: self.public_key_hex(),
+        })
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_report_detects_tampering() {
+        let signer = ReportSigner::generate().unwrap();
+        let case_id = CaseId::new();
+        let mut report = RetentionReport::new(RetentionReportKind::Sweep);
+        report.purged("Case", case_id.to_string(), Some(case_id));
+        report.tombstones.push(Tombstone::deleted(
+            case_id,
+            "receipt:r1".to_string(),
+            b"receipt bytes",
+            ErasureReason::Retention,
+        ));
+
+        let signed = signer.sign(report).unwrap();
+        signed.verify().unwrap();
+        assert!(signed.report.tombstones[0].matches(b"receipt bytes"));
+        assert!(!signed.report.tombstones[0].matches(b"other bytes"));
+
+        let mut tampered = signed.clone();
+        tampered.report.records.clear();
+        assert!(tampered.verify().is_err());
+
+        let mut forged = signed;
+        forged.report.records.clear();
+        forged.digest = sha256_hex(&forged.report.canonical_bytes().unwrap());
+        assert!(forged.verify().is_err());
+    }
+
+    #[test]
+    fn test_partial_report_error_is_signed() {
+        let signer = ReportSigner::generate().unwrap();
+        let mut report = RetentionReport::new(RetentionReportKind::Sweep);
+        let complete = signer.sign(report.clone()).unwrap();
+        // Reports of complete runs serialize exactly as before the field existed
+        let json = serde_json::to_value(&complete).unwrap();
+        assert!(json["report"].get("error").is_none());
+
+        report.error = Some("State persistence error: disk full".to_string());
+        let partial = signer.sign(report).unwrap();
+        partial.verify().unwrap();
+
+        let mut hidden = partial;
+        hidden.report.error = None;
+        assert!(hidden.verify().is_err());
+    }
+}
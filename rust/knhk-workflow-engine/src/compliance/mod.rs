@@ -1,16 +1,21 @@
 //! Compliance and governance for Fortune 500-level workflow engine
 
 pub mod abac;
+pub mod erasure;
 pub mod policy;
 pub mod provenance;
 pub mod provenance_law;
 pub mod retention;
 
 pub use abac::{AbacContext, AbacDecision, AbacEffect, AbacPolicyEngine, AbacPolicyRule};
+pub use erasure::{
+    ErasureOutcome, ErasureReason, ErasureRecord, ErasureRequest, ReportSigner, RetentionReport,
+    RetentionReportKind, SignedRetentionReport, Tombstone,
+};
 pub use policy::{PolicyDecision, PolicyEngine, PolicyRule};
 pub use provenance::{ProvenanceEvent, ProvenanceTracker};
 pub use provenance_law::{
     hash_actions, hash_mu_o, verify_provenance, WorkflowAction, WorkflowObservations,
     WorkflowReceipt,
 };
-pub use retention::{RetentionManager, RetentionPolicy};
+pub use retention::{RetentionAction, RetentionManager, RetentionPolicy};
@@ -1,11 +1,29 @@
 //! Data retention policies for compliance
+//!
+//! Policies are enforced by the engine's retention sweeper and subject erasure
+//! (see `WorkflowEngine::sweep_retention` and `WorkflowEngine::erase_subject`).
 
 // Retention manager implementation
+use crate::case::CaseId;
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::warn;
 
+/// Default case data field identifying the data subject (customer)
+pub const DEFAULT_SUBJECT_FIELD: &str = "customer_id";
+
+/// What happens to a resource once its retention period has expired
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    /// Delete the resource (and, for cases, everything recorded for the case)
+    #[default]
+    Purge,
+    /// Keep the resource but redact its personal fields
+    Anonymize,
+}
+
 /// Retention policy
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetentionPolicy {
@@ -19,6 +37,12 @@ pub struct RetentionPolicy {
     pub legal_hold: bool,
     /// GDPR right-to-be-forgotten enabled
     pub gdpr_rtbf: bool,
+    /// Action taken on expiry
+    #[serde(default)]
+    pub action: RetentionAction,
+    /// Personal fields redacted on anonymization (all data fields if empty)
+    #[serde(default)]
+    pub personal_fields: Vec<String>,
 }
 
 /// Retention manager
@@ -26,6 +50,12 @@ pub struct RetentionManager {
     policies: HashMap<String, RetentionPolicy>,
     /// Legal hold resources (cannot be deleted)
     legal_holds: std::sync::Arc<std::sync::Mutex<HashMap<String, DateTime<Utc>>>>,
+    /// Cases under legal hold
+    case_holds: std::sync::Arc<std::sync::Mutex<HashMap<CaseId, DateTime<Utc>>>>,
+    /// Data subjects (customers) under legal hold
+    subject_holds: std::sync::Arc<std::sync::Mutex<HashMap<String, DateTime<Utc>>>>,
+    /// Case data field identifying the data subject
+    subject_field: String,
 }
 
 impl RetentionManager {
@@ -34,6 +64,29 @@ impl RetentionManager {
         Self {
             policies: HashMap::new(),
             legal_holds: std::sync::Arc::new(std::sync::Mutex::new(HashMap::new())),
+            case_holds: std::sync::Arc::new(std::sync::Mutex::new(HashMap::new())),
+            subject_holds: std::sync::Arc::new(std::sync::Mutex::new(HashMap::new())),
+            subject_field: DEFAULT_SUBJECT_FIELD.to_string(),
+        }
+    }
+
+    /// Identify data subjects by another case data field
+    pub fn with_subject_field(mut self, field: impl Into<String>) -> Self {
+        self.subject_field = field.into();
+        self
+    }
+
+    /// Case data field identifying the data subject
+    pub fn subject_field(&self) -> &str {
+        &self.subject_field
+    }
+
+    /// Data subject of case (or work item) data, if any
+    pub fn subject_of(&self, data: &serde_json::Value) -> Option<String> {
+        match data.get(&self.subject_field)? {
+            serde_json::Value::String(subject) => Some(subject.clone()),
+            serde_json::Value::Number(subject) => Some(subject.to_string()),
+            _ => None,
         }
     }
 
@@ -42,6 +95,13 @@ impl RetentionManager {
         self.policies.insert(policy.name.clone(), policy);
     }
 
+    /// Policy governing a resource type
+    pub fn policy_for(&self, resource_type: &str) -> Option<&RetentionPolicy> {
+        self.policies
+            .values()
+            .find(|p| p.resource_type == resource_type)
+    }
+
     /// Check if resource should be retained
     pub fn should_retain(&self, resource_type: &str, created_at: DateTime<Utc>) -> bool {
         // Check legal hold
@@ -54,11 +114,10 @@ impl RetentionManager {
         }
 
         // Check retention policy
-        if let Some(policy) = self
-            .policies
-            .values()
-            .find(|p| p.resource_type == resource_type)
-        {
+        if let Some(policy) = self.policy_for(resource_type) {
+            if policy.legal_hold {
+                return true;
+            }
             let retention_until = created_at + Duration::days(policy.retention_days);
             Utc::now() < retention_until
         } else {
@@ -92,6 +151,60 @@ impl RetentionManager {
             .unwrap_or(false)
     }
 
+    /// Place a case under legal hold
+    pub fn place_case_hold(&self, case_id: CaseId) {
+        if let Ok(mut case_holds) = self.case_holds.lock() {
+            case_holds.insert(case_id, Utc::now());
+        } else {
+            warn!("Failed to acquire case_holds lock in place_case_hold");
+        }
+    }
+
+    /// Release the legal hold on a case
+    pub fn release_case_hold(&self, case_id: &CaseId) {
+        if let Ok(mut case_holds) = self.case_holds.lock() {
+            case_holds.remove(case_id);
+        } else {
+            warn!("Failed to acquire case_holds lock in release_case_hold");
+        }
+    }
+
+    /// Place all cases of a data subject (customer) under legal hold
+    pub fn place_subject_hold(&self, subject: String) {
+        if let Ok(mut subject_holds) = self.subject_holds.lock() {
+            subject_holds.insert(subject, Utc::now());
+        } else {
+            warn!("Failed to acquire subject_holds lock in place_subject_hold");
+        }
+    }
+
+    /// Release the legal hold on a data subject
+    pub fn release_subject_hold(&self, subject: &str) {
+        if let Ok(mut subject_holds) = self.subject_holds.lock() {
+            subject_holds.remove(subject);
+        } else {
+            warn!("Failed to acquire subject_holds lock in release_subject_hold");
+        }
+    }
+
+    /// Check if a case is under legal hold, directly or through its data subject
+    ///
+    /// Fails closed: a poisoned lock counts as a hold.
+    pub fn is_case_held(&self, case_id: &CaseId, subject: Option<&str>) -> bool {
+        let case_held = self
+            .case_holds
+            .lock()
+            .map(|case_holds| case_holds.contains_key(case_id))
+            .unwrap_or(true);
+        let subject_held = subject.is_some_and(|subject| {
+            self.subject_holds
+                .lock()
+                .map(|subject_holds| subject_holds.contains_key(subject))
+                .unwrap_or(true)
+        });
+        case_held || subject_held
+    }
+
     /// Get resources eligible for deletion
     pub fn get_eligible_for_deletion(&self, resources: &[(String, DateTime<Utc>)]) -> Vec<String> {
         resources
@@ -121,10 +234,32 @@ mod tests {
             retention_days: 90,
             legal_hold: false,
             gdpr_rtbf: true,
+            action: RetentionAction::Purge,
+            personal_fields: Vec::new(),
         };
         manager.add_policy(policy);
 
         let created_at = Utc::now() - Duration::days(100);
         assert!(!manager.should_retain("Case", created_at));
     }
+
+    #[test]
+    fn test_case_and_subject_holds() {
+        let manager = RetentionManager::new();
+        let held = CaseId::new();
+        let other = CaseId::new();
+
+        manager.place_case_hold(held);
+        manager.place_subject_hold("cust-1".to_string());
+        assert!(manager.is_case_held(&held, None));
+        assert!(manager.is_case_held(&other, Some("cust-1")));
+        assert!(!manager.is_case_held(&other, Some("cust-2")));
+
+        manager.release_case_hold(&held);
+        manager.release_subject_hold("cust-1");
+        assert!(!manager.is_case_held(&held, Some("cust-1")));
+
+        let data = serde_json::json!({"customer_id": 42});
+        assert_eq!(manager.subject_of(&data), Some("42".to_string()));
+    }
 }
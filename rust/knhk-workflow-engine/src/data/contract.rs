@@ -0,0 +1,401 @@
+//! Task data contracts - typed input/output mappings at the task boundary
+//!
+//! Each task parameter carries a type and an optional mapping expression:
+//! - Input parameters: the mapping is evaluated over case data and bound to the
+//!   parameter name in the task (work item) input. Defaults to the case variable
+//!   of the same name.
+//! - Output parameters: the mapping is evaluated over the task result and assigned
+//!   to the case variable of the same name. Defaults to the result field of the
+//!   same name.
+
+use crate::data::expression::Expr;
+use crate::data::schema::{check_value, violations_error, CaseDataSchema, DataType, DataViolation};
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{FieldAnnotations, Task, TaskParameter, WorkflowSpec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Typed parameter of a task contract
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractParameter {
+    /// Parameter name
+    pub name: String,
+    /// Parameter type
+    pub data_type: DataType,
+    /// Whether a value must be present
+    pub required: bool,
+    /// Mapping expression (source)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<String>,
+    /// Form annotations (label, allowed values, ordering, widget)
+    #[serde(default, skip_serializing_if = "FieldAnnotations::is_empty")]
+    pub annotations: FieldAnnotations,
+}
+
+impl ContractParameter {
+    fn from_parameter(task_id: &str, param: &TaskParameter) -> WorkflowResult<Self> {
+        let data_type = DataType::parse(&param.param_type).map_err(|e| {
+            WorkflowError::Validation(format!(
+                "Task {} parameter '{}': {}",
+                task_id, param.name, e
+            ))
+        })?;
+        if let Some(value) = param
+            .annotations
+            .allowed_values
+            .iter()
+            .find(|v| !data_type.accepts(v))
+        {
+            return Err(WorkflowError::Validation(format!(
+                "Task {} parameter '{}': allowed value {} is not a {}",
+                task_id, param.name, value, data_type
+            )));
+        }
+        Ok(Self {
+            name: param.name.clone(),
+            data_type,
+            required: param.required,
+            mapping: param.mapping.clone(),
+            annotations: param.annotations.clone(),
+        })
+    }
+
+    /// Check a value against the type, required flag and allowed values
+    fn check_field(&self, value: &Value, violations: &mut Vec<DataViolation>) {
+        let before = violations.len();
+        check_value(
+            &self.name,
+            self.data_type,
+            self.required,
+            Some(value),
+            violations,
+        );
+        if violations.len() == before && !value.is_null() && !self.allows(value) {
+            violations.push(DataViolation {
+                path: self.name.clone(),
+                message: format!("{} is not one of the allowed values", value),
+            });
+        }
+    }
+
+    /// Check whether a value is in the enumeration (numbers compare by value)
+    pub fn allows(&self, value: &Value) -> bool {
+        let allowed = &self.annotations.allowed_values;
+        allowed.is_empty()
+            || allowed.iter().any(|a| {
+                a == value || matches!((a.as_f64(), value.as_f64()), (Some(x), Some(y)) if x == y)
+            })
+    }
+
+    fn expression(&self) -> WorkflowResult<Expr> {
+        match self.mapping {
+            Some(ref source) => Expr::parse(source),
+            None => Ok(Expr::Path(vec![self.name.clone()])),
+        }
+    }
+}
+
+/// Typed input/output contract of a task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskDataContract {
+    /// Task ID
+    pub task_id: String,
+    /// Input parameters
+    pub inputs: Vec<ContractParameter>,
+    /// Output parameters
+    pub outputs: Vec<ContractParameter>,
+}
+
+impl TaskDataContract {
+    /// Build the contract of a task from its declared parameters
+    pub fn for_task(task: &Task) -> WorkflowResult<Self> {
+        let convert = |params: &[TaskParameter]| -> WorkflowResult<Vec<ContractParameter>> {
+            params
+                .iter()
+                .map(|p| ContractParameter::from_parameter(&task.id, p))
+                .collect()
+        };
+        Ok(Self {
+            task_id: task.id.clone(),
+            inputs: convert(&task.input_parameters)?,
+            outputs: convert(&task.output_parameters)?,
+        })
+    }
+
+    /// Check whether the task declares no parameters
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty() && self.outputs.is_empty()
+    }
+
+    /// Type-check the mappings against the case data schema
+    ///
+    /// Mapping syntax is always checked. Types are only checked for typed nets
+    /// (non-empty schema).
+    pub fn check(&self, schema: &CaseDataSchema) -> WorkflowResult<()> {
+        for input in &self.inputs {
+            let expr = input
+                .expression()
+                .map_err(|e| self.mapping_error(input, e))?;
+            let mapped = expr
+                .infer_type(schema)
+                .map_err(|e| self.mapping_error(input, e))?;
+            if !input.data_type.is_assignable_from(&mapped) {
+                return Err(WorkflowError::Validation(format!(
+                    "Task {} input '{}' is {}, but its mapping yields {}",
+                    self.task_id, input.name, input.data_type, mapped
+                )));
+            }
+        }
+
+        for output in &self.outputs {
+            // Task results are untyped, so only the mapping syntax can be checked
+            output
+                .expression()
+                .map_err(|e| self.mapping_error(output, e))?;
+            if schema.is_empty() {
+                continue;
+            }
+            let variable = schema.get(&output.name).ok_or_else(|| {
+                WorkflowError::Validation(format!(
+                    "Task {} output '{}' is not a declared case variable",
+                    self.task_id, output.name
+                ))
+            })?;
+            if !variable.data_type.is_assignable_from(&output.data_type) {
+                return Err(WorkflowError::Validation(format!(
+                    "Task {} output '{}' is {}, but the case variable is {}",
+                    self.task_id, output.name, output.data_type, variable.data_type
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bind the task input from case data
+    pub fn bind_inputs(&self, case_data: &Value) -> WorkflowResult<Value> {
+        let mut input = serde_json::Map::new();
+        let mut violations = Vec::new();
+        for param in &self.inputs {
+            let value = param.expression()?.evaluate(case_data)?;
+            param.check_field(&value, &mut violations);
+            if !value.is_null() {
+                input.insert(param.name.clone(), value);
+            }
+        }
+        if violations.is_empty() {
+            Ok(Value::Object(input))
+        } else {
+            Err(violations_error(
+                &format!("Invalid input for task {}", self.task_id),
+                &violations,
+            ))
+        }
+    }
+
+    /// Validate a task result against the declared outputs
+    pub fn validate_result(&self, result: &Value) -> Result<(), Vec<DataViolation>> {
+        if self.outputs.is_empty() {
+            return Ok(());
+        }
+        let mut violations = Vec::new();
+        for param in &self.outputs {
+            match param.expression().and_then(|e| e.evaluate(result)) {
+                Ok(value) => param.check_field(&value, &mut violations),
+                Err(e) => violations.push(DataViolation {
+                    path: param.name.clone(),
+                    message: e.to_string(),
+                }),
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Map a task result onto case variables
+    ///
+    /// Returns the case variable updates. Fails if the result violates the contract.
+    pub fn map_outputs(&self, result: &Value) -> WorkflowResult<serde_json::Map<String, Value>> {
+        self.validate_result(result).map_err(|violations| {
+            violations_error(
+                &format!("Invalid output of task {}", self.task_id),
+                &violations,
+            )
+        })?;
+
+        let mut updates = serde_json::Map::new();
+        for param in &self.outputs {
+            let value = param.expression()?.evaluate(result)?;
+            if !value.is_null() {
+                updates.insert(param.name.clone(), value);
+            }
+        }
+        Ok(updates)
+    }
+
+    fn mapping_error(&self, param: &ContractParameter, e: WorkflowError) -> WorkflowError {
+        WorkflowError::Validation(format!(
+            "Task {} parameter '{}' mapping: {}",
+            self.task_id, param.name, e
+        ))
+    }
+}
+
+/// Validate the data model of a workflow specification
+///
+/// - Flow predicates and parameter mappings must parse
+/// - Parameter types must be known
+/// - For typed nets, predicates must be boolean over declared variables and
+///   mappings must be type-compatible
+/// - Multiple instance configurations must be consistent, and MI collections
+///   must be arrays
+pub fn validate_spec_data_model(spec: &WorkflowSpec) -> WorkflowResult<()> {
+    let schema = spec.data_schema();
+
+    for decl in schema.variables() {
+        if let Some(ref default) = decl.default {
+            let mut violations = Vec::new();
+            check_value(
+                &decl.name,
+                decl.data_type,
+                false,
+                Some(default),
+                &mut violations,
+            );
+            if !violations.is_empty() {
+                return Err(violations_error("Invalid variable default", &violations));
+            }
+        }
+    }
+
+    for flow in &spec.flows {
+        let Some(ref predicate) = flow.predicate else {
+            continue;
+        };
+        Expr::parse(predicate)
+            .and_then(|expr| expr.check_predicate(&schema))
+            .map_err(|e| {
+                WorkflowError::Validation(format!(
+                    "Flow {} -> {} predicate '{}': {}",
+                    flow.from, flow.to, predicate, e
+                ))
+            })?;
+    }
+
+    for task in spec.tasks.values() {
+        TaskDataContract::for_task(task)?.check(&schema)?;
+
+        if let Some(ref mi) = task.multi_instance {
+            mi.validate()?;
+            if let Some(ref collection) = mi.collection {
+                let collection_type = Expr::parse(collection)?.infer_type(&schema)?;
+                if !matches!(collection_type, DataType::Array | DataType::Any) {
+                    return Err(WorkflowError::Validation(format!(
+                        "Task {} MI collection '{}' is {}, expected array",
+                        task.id, collection, collection_type
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::schema::VariableDecl;
+    use serde_json::json;
+
+    fn param(name: &str, param_type: &str, mapping: Option<&str>, required: bool) -> TaskParameter {
+        TaskParameter {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            mapping: mapping.map(str::to_string),
+            required,
+            annotations: Default::default(),
+        }
+    }
+
+    fn contract() -> TaskDataContract {
+        TaskDataContract {
+            task_id: "approve".to_string(),
+            inputs: vec![ContractParameter::from_parameter(
+                "approve",
+                &param("limit", "xsd:decimal", Some("amount * 2"), true),
+            )
+            .unwrap()],
+            outputs: vec![ContractParameter::from_parameter(
+                "approve",
+                &param("approved", "xsd:boolean", Some("decision.ok"), true),
+            )
+            .unwrap()],
+        }
+    }
+
+    #[test]
+    fn test_bind_inputs_and_map_outputs() {
+        let contract = contract();
+
+        let input = contract.bind_inputs(&json!({"amount": 50})).unwrap();
+        assert_eq!(input, json!({"limit": 100}));
+        assert!(contract.bind_inputs(&json!({})).is_err());
+
+        let updates = contract
+            .map_outputs(&json!({"decision": {"ok": true}}))
+            .unwrap();
+        assert_eq!(updates.get("approved"), Some(&json!(true)));
+        assert!(contract
+            .map_outputs(&json!({"decision": {"ok": "yes"}}))
+            .is_err());
+        assert!(contract.map_outputs(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_allowed_values() {
+        let mut decision = param("decision", "xsd:string", None, true);
+        decision.annotations.allowed_values = vec![json!("approve"), json!("reject")];
+        let contract = TaskDataContract {
+            task_id: "review".to_string(),
+            inputs: Vec::new(),
+            outputs: vec![ContractParameter::from_parameter("review", &decision).unwrap()],
+        };
+        assert!(contract
+            .validate_result(&json!({"decision": "reject"}))
+            .is_ok());
+        let violations = contract
+            .validate_result(&json!({"decision": "maybe"}))
+            .unwrap_err();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "decision");
+
+        let mut level = param("level", "xsd:integer", None, false);
+        level.annotations.allowed_values = vec![json!(1), json!("two")];
+        assert!(ContractParameter::from_parameter("review", &level).is_err());
+    }
+
+    #[test]
+    fn test_check_against_schema() {
+        let contract = contract();
+        let schema = CaseDataSchema::from_variables(&[
+            VariableDecl::new("amount", DataType::Integer),
+            VariableDecl::new("approved", DataType::Boolean),
+        ]);
+        assert!(contract.check(&schema).is_ok());
+        assert!(contract.check(&CaseDataSchema::new()).is_ok());
+
+        let wrong = CaseDataSchema::from_variables(&[
+            VariableDecl::new("amount", DataType::String),
+            VariableDecl::new("approved", DataType::Boolean),
+        ]);
+        assert!(contract.check(&wrong).is_err());
+
+        let missing_output =
+            CaseDataSchema::from_variables(&[VariableDecl::new("amount", DataType::Integer)]);
+        assert!(contract.check(&missing_output).is_err());
+    }
+}
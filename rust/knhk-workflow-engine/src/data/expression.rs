@@ -0,0 +1,783 @@
+//! Data expressions for flow predicates and task parameter mappings
+//!
+//! Grammar (lowest to highest precedence):
+//!
+//! ```text
+//! or      := and (("||" | "or") and)*
+//! and     := not (("&&" | "and") not)*
+//! not     := ("!" | "not") not | compare
+//! compare := sum (("==" | "!=" | ">=" | "<=" | ">" | "<") sum)?
+//! sum     := product (("+" | "-") product)*
+//! product := unary (("*" | "/") unary)*
+//! unary   := "-" unary | primary
+//! primary := number | string | true | false | null | path | "(" or ")"
+//! path    := ident ("." ident)*
+//! ```
+//!
+//! Paths resolve against case data. For backward compatibility with the former
+//! string-scanning evaluator, a bare identifier on the right-hand side of `==`/`!=`
+//! that is not a variable is read as a string literal (`status == approved`).
+
+use crate::data::schema::{CaseDataSchema, DataType};
+use crate::error::{WorkflowError, WorkflowResult};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Variable scope an expression is evaluated against
+pub trait DataScope {
+    /// Look up a top-level variable
+    fn lookup(&self, name: &str) -> Option<&Value>;
+}
+
+impl DataScope for Value {
+    fn lookup(&self, name: &str) -> Option<&Value> {
+        self.get(name)
+    }
+}
+
+impl DataScope for HashMap<String, Value> {
+    fn lookup(&self, name: &str) -> Option<&Value> {
+        self.get(name)
+    }
+}
+
+impl DataScope for serde_json::Map<String, Value> {
+    fn lookup(&self, name: &str) -> Option<&Value> {
+        self.get(name)
+    }
+}
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+/// Arithmetic operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    /// `+` (also string concatenation)
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+}
+
+/// Parsed expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Literal value
+    Literal(Value),
+    /// Variable path (`order.total`)
+    Path(Vec<String>),
+    /// Logical negation
+    Not(Box<Expr>),
+    /// Arithmetic negation
+    Neg(Box<Expr>),
+    /// Logical conjunction
+    And(Box<Expr>, Box<Expr>),
+    /// Logical disjunction
+    Or(Box<Expr>, Box<Expr>),
+    /// Comparison
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    /// Arithmetic
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parse an expression
+    pub fn parse(source: &str) -> WorkflowResult<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            source,
+        };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+        Ok(expr)
+    }
+
+    /// Evaluate the expression against a data scope
+    pub fn evaluate<S: DataScope + ?Sized>(&self, scope: &S) -> WorkflowResult<Value> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Path(path) => Ok(resolve(scope, path).cloned().unwrap_or(Value::Null)),
+            Expr::Not(inner) => Ok(Value::Bool(!truthy(&inner.evaluate(scope)?)?)),
+            Expr::Neg(inner) => match inner.evaluate(scope)? {
+                Value::Null => Ok(Value::Null),
+                value => number(-as_f64(&value)?),
+            },
+            Expr::And(left, right) => Ok(Value::Bool(
+                truthy(&left.evaluate(scope)?)? && truthy(&right.evaluate(scope)?)?,
+            )),
+            Expr::Or(left, right) => Ok(Value::Bool(
+                truthy(&left.evaluate(scope)?)? || truthy(&right.evaluate(scope)?)?,
+            )),
+            Expr::Compare(op, left, right) => {
+                let lhs = left.evaluate(scope)?;
+                let rhs = match (op, right.as_ref()) {
+                    (CompareOp::Eq | CompareOp::Ne, Expr::Path(path))
+                        if path.len() == 1 && scope.lookup(&path[0]).is_none() =>
+                    {
+                        Value::String(path[0].clone())
+                    }
+                    _ => right.evaluate(scope)?,
+                };
+                compare(*op, &lhs, &rhs).map(Value::Bool)
+            }
+            Expr::Arith(op, left, right) => {
+                let lhs = left.evaluate(scope)?;
+                let rhs = right.evaluate(scope)?;
+                arith(*op, &lhs, &rhs)
+            }
+        }
+    }
+
+    /// Evaluate as a boolean predicate
+    pub fn evaluate_bool<S: DataScope + ?Sized>(&self, scope: &S) -> WorkflowResult<bool> {
+        truthy(&self.evaluate(scope)?)
+    }
+
+    /// Infer the result type against a schema
+    ///
+    /// With an empty (untyped) schema every variable has type `any`.
+    pub fn infer_type(&self, schema: &CaseDataSchema) -> WorkflowResult<DataType> {
+        match self {
+            Expr::Literal(value) => Ok(literal_type(value)),
+            Expr::Path(path) => path_type(schema, path),
+            Expr::Not(inner) => {
+                expect_boolean(inner.infer_type(schema)?, "operand of 'not'")?;
+                Ok(DataType::Boolean)
+            }
+            Expr::Neg(inner) => {
+                let t = inner.infer_type(schema)?;
+                if t.is_numeric() || t == DataType::Any {
+                    Ok(t)
+                } else {
+                    Err(type_error(format!("cannot negate a value of type {}", t)))
+                }
+            }
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                expect_boolean(left.infer_type(schema)?, "operand of 'and'/'or'")?;
+                expect_boolean(right.infer_type(schema)?, "operand of 'and'/'or'")?;
+                Ok(DataType::Boolean)
+            }
+            Expr::Compare(op, left, right) => {
+                let lhs = left.infer_type(schema)?;
+                let rhs = match (op, right.as_ref()) {
+                    (CompareOp::Eq | CompareOp::Ne, Expr::Path(path))
+                        if path.len() == 1
+                            && !schema.is_empty()
+                            && schema.get(&path[0]).is_none() =>
+                    {
+                        DataType::String
+                    }
+                    _ => right.infer_type(schema)?,
+                };
+                let ok = match op {
+                    CompareOp::Eq | CompareOp::Ne => lhs.is_comparable(&rhs),
+                    _ => lhs.is_orderable(&rhs),
+                };
+                if ok {
+                    Ok(DataType::Boolean)
+                } else {
+                    Err(type_error(format!(
+                        "cannot compare {} with {} in '{}'",
+                        lhs,
+                        rhs,
+                        self.describe()
+                    )))
+                }
+            }
+            Expr::Arith(op, left, right) => {
+                let lhs = left.infer_type(schema)?;
+                let rhs = right.infer_type(schema)?;
+                match (op, lhs, rhs) {
+                    (ArithOp::Add, DataType::String, DataType::String) => Ok(DataType::String),
+                    (_, DataType::Any, _) | (_, _, DataType::Any) => Ok(DataType::Any),
+                    (ArithOp::Div, l, r) if l.is_numeric() && r.is_numeric() => {
+                        Ok(DataType::Decimal)
+                    }
+                    (_, DataType::Integer, DataType::Integer) => Ok(DataType::Integer),
+                    (_, l, r) if l.is_numeric() && r.is_numeric() => Ok(DataType::Decimal),
+                    (_, l, r) => Err(type_error(format!(
+                        "arithmetic on {} and {} in '{}'",
+                        l,
+                        r,
+                        self.describe()
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Type-check as a flow predicate (must be boolean)
+    pub fn check_predicate(&self, schema: &CaseDataSchema) -> WorkflowResult<()> {
+        expect_boolean(self.infer_type(schema)?, "predicate")
+    }
+
+    /// Variables referenced by the expression (top-level names)
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.collect_variables(&mut names);
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn collect_variables(&self, names: &mut Vec<String>) {
+        match self {
+            Expr::Literal(_) => {}
+            Expr::Path(path) => names.extend(path.first().cloned()),
+            Expr::Not(inner) | Expr::Neg(inner) => inner.collect_variables(names),
+            Expr::And(l, r) | Expr::Or(l, r) | Expr::Compare(_, l, r) | Expr::Arith(_, l, r) => {
+                l.collect_variables(names);
+                r.collect_variables(names);
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Expr::Literal(value) => value.to_string(),
+            Expr::Path(path) => path.join("."),
+            Expr::Not(inner) => format!("!{}", inner.describe()),
+            Expr::Neg(inner) => format!("-{}", inner.describe()),
+            Expr::And(l, r) => format!("{} && {}", l.describe(), r.describe()),
+            Expr::Or(l, r) => format!("{} || {}", l.describe(), r.describe()),
+            Expr::Compare(op, l, r) => {
+                let op = match op {
+                    CompareOp::Eq => "==",
+                    CompareOp::Ne => "!=",
+                    CompareOp::Lt => "<",
+                    CompareOp::Le => "<=",
+                    CompareOp::Gt => ">",
+                    CompareOp::Ge => ">=",
+                };
+                format!("{} {} {}", l.describe(), op, r.describe())
+            }
+            Expr::Arith(op, l, r) => {
+                let op = match op {
+                    ArithOp::Add => "+",
+                    ArithOp::Sub => "-",
+                    ArithOp::Mul => "*",
+                    ArithOp::Div => "/",
+                };
+                format!("{} {} {}", l.describe(), op, r.describe())
+            }
+        }
+    }
+}
+
+/// Parse and evaluate a flow predicate against case data
+pub fn evaluate_predicate<S: DataScope + ?Sized>(
+    predicate: &str,
+    scope: &S,
+) -> WorkflowResult<bool> {
+    Expr::parse(predicate)?.evaluate_bool(scope)
+}
+
+fn type_error(message: String) -> WorkflowError {
+    WorkflowError::Validation(format!("Type error: {}", message))
+}
+
+fn expect_boolean(t: DataType, what: &str) -> WorkflowResult<()> {
+    if t == DataType::Boolean || t == DataType::Any {
+        Ok(())
+    } else {
+        Err(type_error(format!("{} must be boolean, found {}", what, t)))
+    }
+}
+
+fn literal_type(value: &Value) -> DataType {
+    match value {
+        Value::Bool(_) => DataType::Boolean,
+        Value::Number(n) if n.is_i64() || n.is_u64() => DataType::Integer,
+        Value::Number(_) => DataType::Decimal,
+        Value::String(_) => DataType::String,
+        Value::Array(_) => DataType::Array,
+        Value::Object(_) => DataType::Object,
+        Value::Null => DataType::Any,
+    }
+}
+
+fn path_type(schema: &CaseDataSchema, path: &[String]) -> WorkflowResult<DataType> {
+    if schema.is_empty() {
+        return Ok(DataType::Any);
+    }
+    let root = &path[0];
+    let decl = schema
+        .get(root)
+        .ok_or_else(|| type_error(format!("undeclared variable '{}'", root)))?;
+    if path.len() == 1 {
+        return Ok(decl.data_type);
+    }
+    match decl.data_type {
+        DataType::Object | DataType::Any => Ok(DataType::Any),
+        other => Err(type_error(format!(
+            "'{}' is {}, cannot access '{}'",
+            root,
+            other,
+            path[1..].join(".")
+        ))),
+    }
+}
+
+fn resolve<'a, S: DataScope + ?Sized>(scope: &'a S, path: &[String]) -> Option<&'a Value> {
+    let mut value = scope.lookup(&path[0])?;
+    for segment in &path[1..] {
+        value = match value {
+            Value::Object(map) => map.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn truthy(value: &Value) -> WorkflowResult<bool> {
+    match value {
+        Value::Bool(b) => Ok(*b),
+        // Missing variables never enable a flow
+        Value::Null => Ok(false),
+        other => Err(type_error(format!("expected boolean, found {}", other))),
+    }
+}
+
+fn as_f64(value: &Value) -> WorkflowResult<f64> {
+    value
+        .as_f64()
+        .ok_or_else(|| type_error(format!("expected number, found {}", value)))
+}
+
+fn number(n: f64) -> WorkflowResult<Value> {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Ok(Value::from(n as i64))
+    } else {
+        serde_json::Number::from_f64(n)
+            .map(Value::Number)
+            .ok_or_else(|| type_error(format!("non-finite result {}", n)))
+    }
+}
+
+fn compare(op: CompareOp, lhs: &Value, rhs: &Value) -> WorkflowResult<bool> {
+    use std::cmp::Ordering;
+
+    let ordering: Option<Ordering> = match (lhs, rhs) {
+        // Comparisons involving a missing value only hold for (in)equality with null
+        (Value::Null, _) | (_, Value::Null) => {
+            let both_null = lhs.is_null() && rhs.is_null();
+            return Ok(match op {
+                CompareOp::Eq => both_null,
+                CompareOp::Ne => !both_null,
+                _ => false,
+            });
+        }
+        (Value::Number(a), Value::Number(b)) => a.as_f64().zip(b.as_f64()).and_then(|(a, b)| {
+            if (a - b).abs() < f64::EPSILON {
+                Some(Ordering::Equal)
+            } else {
+                a.partial_cmp(&b)
+            }
+        }),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+
+    match (op, ordering) {
+        (CompareOp::Eq, Some(o)) => Ok(o == Ordering::Equal),
+        (CompareOp::Ne, Some(o)) => Ok(o != Ordering::Equal),
+        (CompareOp::Eq, None) => Ok(lhs == rhs),
+        (CompareOp::Ne, None) => Ok(lhs != rhs),
+        (_, Some(_)) if lhs.is_boolean() => Err(type_error(format!(
+            "booleans are not ordered ({} vs {})",
+            lhs, rhs
+        ))),
+        (CompareOp::Lt, Some(o)) => Ok(o == Ordering::Less),
+        (CompareOp::Le, Some(o)) => Ok(o != Ordering::Greater),
+        (CompareOp::Gt, Some(o)) => Ok(o == Ordering::Greater),
+        (CompareOp::Ge, Some(o)) => Ok(o != Ordering::Less),
+        (_, None) => Err(type_error(format!("cannot order {} and {}", lhs, rhs))),
+    }
+}
+
+fn arith(op: ArithOp, lhs: &Value, rhs: &Value) -> WorkflowResult<Value> {
+    if lhs.is_null() || rhs.is_null() {
+        return Ok(Value::Null);
+    }
+    if let (ArithOp::Add, Value::String(a), Value::String(b)) = (op, lhs, rhs) {
+        return Ok(Value::String(format!("{}{}", a, b)));
+    }
+    let (a, b) = (as_f64(lhs)?, as_f64(rhs)?);
+    match op {
+        ArithOp::Add => number(a + b),
+        ArithOp::Sub => number(a - b),
+        ArithOp::Mul => number(a * b),
+        ArithOp::Div if b == 0.0 => Err(type_error("division by zero".to_string())),
+        ArithOp::Div => number(a / b),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Dot,
+}
+
+fn tokenize(source: &str) -> WorkflowResult<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            // Path separator, unless it starts a decimal literal (`.5`)
+            '.' if !chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())
+                || matches!(tokens.last(), Some(Token::Ident(_))) =>
+            {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => {
+                            return Err(WorkflowError::Parse(format!(
+                                "Unterminated string in expression '{}'",
+                                source
+                            )))
+                        }
+                        Some('\\') => {
+                            if let Some(escaped) = chars.get(i + 1) {
+                                text.push(*escaped);
+                            }
+                            i += 2;
+                        }
+                        Some(ch) if *ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(ch) => {
+                            text.push(*ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Str(text));
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                // A '.' only continues the number when a digit follows (`lines.0.sku`)
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || (chars[i] == '.'
+                            && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())))
+                {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let n = text.parse::<f64>().map_err(|_| {
+                    WorkflowError::Parse(format!("Invalid number '{}' in '{}'", text, source))
+                })?;
+                tokens.push(Token::Number(n));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => {
+                let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+                let op = match two.as_str() {
+                    "==" => Some("=="),
+                    "!=" => Some("!="),
+                    ">=" => Some(">="),
+                    "<=" => Some("<="),
+                    "&&" => Some("&&"),
+                    "||" => Some("||"),
+                    _ => None,
+                };
+                if let Some(op) = op {
+                    tokens.push(Token::Op(op));
+                    i += 2;
+                    continue;
+                }
+                let op = match c {
+                    '>' => ">",
+                    '<' => "<",
+                    '!' => "!",
+                    '+' => "+",
+                    '-' => "-",
+                    '*' => "*",
+                    '/' => "/",
+                    _ => {
+                        return Err(WorkflowError::Parse(format!(
+                            "Unexpected character '{}' in expression '{}'",
+                            c, source
+                        )))
+                    }
+                };
+                tokens.push(Token::Op(op));
+                i += 1;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    source: &'a str,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> WorkflowError {
+        WorkflowError::Parse(format!(
+            "Invalid expression '{}': {} at token {}",
+            self.source, message, self.pos
+        ))
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat_op(&mut self, ops: &[&str], words: &[&str]) -> Option<&'static str> {
+        let matched = match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => Some(*op),
+            Some(Token::Ident(word)) if words.contains(&word.as_str()) => {
+                // Normalize keyword operators to their symbolic form
+                match word.as_str() {
+                    "and" => Some("&&"),
+                    "or" => Some("||"),
+                    "not" => Some("!"),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        if matched.is_some() {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn parse_or(&mut self) -> WorkflowResult<Expr> {
+        let mut left = self.parse_and()?;
+        while self.eat_op(&["||"], &["or"]).is_some() {
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> WorkflowResult<Expr> {
+        let mut left = self.parse_not()?;
+        while self.eat_op(&["&&"], &["and"]).is_some() {
+            let right = self.parse_not()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> WorkflowResult<Expr> {
+        if self.eat_op(&["!"], &["not"]).is_some() {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_compare()
+    }
+
+    fn parse_compare(&mut self) -> WorkflowResult<Expr> {
+        let left = self.parse_sum()?;
+        let op = match self.eat_op(&["==", "!=", ">=", "<=", ">", "<"], &[]) {
+            Some("==") => CompareOp::Eq,
+            Some("!=") => CompareOp::Ne,
+            Some(">=") => CompareOp::Ge,
+            Some("<=") => CompareOp::Le,
+            Some(">") => CompareOp::Gt,
+            Some("<") => CompareOp::Lt,
+            _ => return Ok(left),
+        };
+        let right = self.parse_sum()?;
+        Ok(Expr::Compare(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_sum(&mut self) -> WorkflowResult<Expr> {
+        let mut left = self.parse_product()?;
+        while let Some(op) = self.eat_op(&["+", "-"], &[]) {
+            let op = if op == "+" {
+                ArithOp::Add
+            } else {
+                ArithOp::Sub
+            };
+            let right = self.parse_product()?;
+            left = Expr::Arith(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_product(&mut self) -> WorkflowResult<Expr> {
+        let mut left = self.parse_unary()?;
+        while let Some(op) = self.eat_op(&["*", "/"], &[]) {
+            let op = if op == "*" {
+                ArithOp::Mul
+            } else {
+                ArithOp::Div
+            };
+            let right = self.parse_unary()?;
+            left = Expr::Arith(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> WorkflowResult<Expr> {
+        if self.eat_op(&["-"], &[]).is_some() {
+            return Ok(Expr::Neg(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> WorkflowResult<Expr> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| self.error("unexpected end of expression"))?;
+        self.pos += 1;
+
+        match token {
+            Token::Number(n) => Ok(Expr::Literal(number(n)?)),
+            Token::Str(s) => Ok(Expr::Literal(Value::String(s))),
+            Token::LParen => {
+                let inner = self.parse_or()?;
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.pos += 1;
+                        Ok(inner)
+                    }
+                    _ => Err(self.error("expected ')'")),
+                }
+            }
+            Token::Ident(word) => match word.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ => {
+                    let mut path = vec![word];
+                    while let Some(Token::Dot) = self.peek() {
+                        self.pos += 1;
+                        match self.tokens.get(self.pos).cloned() {
+                            Some(Token::Ident(segment)) => {
+                                self.pos += 1;
+                                path.push(segment);
+                            }
+                            Some(Token::Number(n)) if n.fract() == 0.0 && n >= 0.0 => {
+                                self.pos += 1;
+                                path.push((n as u64).to_string());
+                            }
+                            _ => return Err(self.error("expected field name after '.'")),
+                        }
+                    }
+                    Ok(Expr::Path(path))
+                }
+            },
+            _ => Err(self.error("expected value")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::schema::VariableDecl;
+    use serde_json::json;
+
+    #[test]
+    fn test_legacy_predicates_keep_their_meaning() {
+        let data =
+            json!({"balance": 100, "withdrawalAmount": 40, "approved": true, "status": "pending"});
+
+        assert!(evaluate_predicate("balance >= withdrawalAmount", &data).unwrap_or(false));
+        assert!(!evaluate_predicate("balance <= withdrawalAmount", &data).unwrap_or(true));
+        assert!(evaluate_predicate("approved == true", &data).unwrap_or(false));
+        assert!(evaluate_predicate("status == pending", &data).unwrap_or(false));
+        assert!(evaluate_predicate("status == \"pending\"", &data).unwrap_or(false));
+        assert!(!evaluate_predicate("missing == true", &data).unwrap_or(true));
+    }
+
+    #[test]
+    fn test_compound_predicates_and_mappings() {
+        let data = json!({"order": {"total": 120.5, "lines": [{"sku": "A-1"}]}, "vip": false});
+
+        assert!(
+            evaluate_predicate("order.total > 100 && (vip || order.total * 2 > 200)", &data)
+                .unwrap_or(false)
+        );
+        assert!(
+            evaluate_predicate("not vip and order.lines.0.sku == 'A-1'", &data).unwrap_or(false)
+        );
+
+        let mapped = Expr::parse("order.total - 20.5")
+            .and_then(|e| e.evaluate(&data))
+            .unwrap_or(Value::Null);
+        assert_eq!(mapped, json!(100));
+    }
+
+    #[test]
+    fn test_type_check_against_schema() {
+        let schema = CaseDataSchema::from_variables(&[
+            VariableDecl::new("amount", DataType::Decimal),
+            VariableDecl::new("approved", DataType::Boolean),
+            VariableDecl::new("status", DataType::String),
+        ]);
+
+        let check = |p: &str| Expr::parse(p).and_then(|e| e.check_predicate(&schema));
+        assert!(check("amount >= 100 && approved").is_ok());
+        assert!(check("status == rejected").is_ok());
+        assert!(check("amount >= \"high\"").is_err());
+        assert!(check("amount + 1").is_err());
+        assert!(check("unknown > 1").is_err());
+        assert!(Expr::parse("amount >=").is_err());
+        assert!(Expr::parse("amount == 1 )").is_err());
+    }
+}
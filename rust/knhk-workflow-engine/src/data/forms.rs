@@ -0,0 +1,292 @@
+//! Work item forms generated from task data contracts
+//!
+//! Each task with typed parameters gets a JSON Schema describing the form data
+//! and a UI schema (react-jsonschema-form conventions) with labels, field order,
+//! widgets and read-only inputs:
+//! - Input parameters are read-only fields pre-filled from the work item data
+//! - Output parameters are editable; required outputs are required fields
+//! - A parameter that is both input and output is editable and pre-filled
+//!
+//! Form data is the work item result, so output fields are keyed by the result
+//! field their mapping reads (the parameter name unless mapped otherwise).
+//! Completion validates results against the same contract the form is built
+//! from, so the form and the engine cannot disagree.
+
+use crate::data::contract::{ContractParameter, TaskDataContract};
+use crate::data::schema::DataType;
+use crate::error::WorkflowResult;
+use crate::parser::Task;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// JSON Schema dialect of generated forms
+pub const FORM_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Generated form of a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskForm {
+    /// Task ID
+    pub task_id: String,
+    /// JSON Schema of the form data
+    pub json_schema: Value,
+    /// UI schema (`ui:order`, `ui:widget`, `ui:readonly`, `ui:help` per field)
+    pub ui_schema: Value,
+}
+
+/// A form field before rendering
+struct Field<'a> {
+    key: String,
+    param: &'a ContractParameter,
+    editable: bool,
+    required: bool,
+    value: Option<Value>,
+}
+
+impl TaskForm {
+    /// Generate the form of a task
+    pub fn for_task(task: &Task) -> WorkflowResult<Self> {
+        let contract = TaskDataContract::for_task(task)?;
+        Ok(Self::from_contract(&contract, &task.name, None))
+    }
+
+    /// Generate the form of a task, pre-filled with a work item's input data
+    pub fn for_work_item(task: &Task, data: &Value) -> WorkflowResult<Self> {
+        let contract = TaskDataContract::for_task(task)?;
+        Ok(Self::from_contract(&contract, &task.name, Some(data)))
+    }
+
+    /// Generate a form from a task data contract
+    pub fn from_contract(contract: &TaskDataContract, title: &str, data: Option<&Value>) -> Self {
+        let value_of = |name: &str| data.and_then(|d| d.get(name)).filter(|v| !v.is_null());
+
+        let mut fields: Vec<Field> = Vec::new();
+        for output in &contract.outputs {
+            let key = result_field(output);
+            fields.push(Field {
+                value: value_of(&key).cloned(),
+                key,
+                param: output,
+                editable: true,
+                required: output.required,
+            });
+        }
+        for input in &contract.inputs {
+            if fields.iter().any(|f| f.key == input.name) {
+                continue;
+            }
+            fields.push(Field {
+                key: input.name.clone(),
+                param: input,
+                editable: false,
+                required: false,
+                value: value_of(&input.name).cloned(),
+            });
+        }
+        fields.sort_by(|a, b| {
+            let order = |f: &Field| {
+                (
+                    f.param.annotations.order.is_none(),
+                    f.param.annotations.order,
+                )
+            };
+            order(a).cmp(&order(b)).then_with(|| a.key.cmp(&b.key))
+        });
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let mut ui_schema = Map::new();
+        let mut ui_order = Vec::new();
+        for field in fields {
+            let annotations = &field.param.annotations;
+
+            let mut property = type_schema(field.param.data_type);
+            property.insert(
+                "title".to_string(),
+                json!(annotations.label.as_deref().unwrap_or(&field.param.name)),
+            );
+            if let Some(ref description) = annotations.description {
+                property.insert("description".to_string(), json!(description));
+            }
+            if !annotations.allowed_values.is_empty() {
+                property.insert("enum".to_string(), json!(annotations.allowed_values));
+            }
+            if let Some(value) = field.value {
+                property.insert("default".to_string(), value);
+            }
+
+            let mut ui = Map::new();
+            if !field.editable {
+                property.insert("readOnly".to_string(), json!(true));
+                ui.insert("ui:readonly".to_string(), json!(true));
+            }
+            if let Some(ref widget) = annotations.widget {
+                ui.insert("ui:widget".to_string(), json!(widget));
+            }
+            if let Some(ref description) = annotations.description {
+                ui.insert("ui:help".to_string(), json!(description));
+            }
+
+            if field.required {
+                required.push(json!(field.key));
+            }
+            if !ui.is_empty() {
+                ui_schema.insert(field.key.clone(), Value::Object(ui));
+            }
+            ui_order.push(json!(field.key));
+            properties.insert(field.key, Value::Object(property));
+        }
+        ui_schema.insert("ui:order".to_string(), Value::Array(ui_order));
+
+        Self {
+            task_id: contract.task_id.clone(),
+            json_schema: json!({
+                "$schema": FORM_SCHEMA_DIALECT,
+                "title": title,
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            }),
+            ui_schema: Value::Object(ui_schema),
+        }
+    }
+
+    /// Names of the editable (output) fields
+    pub fn editable_fields(&self) -> Vec<&str> {
+        self.json_schema["properties"]
+            .as_object()
+            .map(|properties| {
+                properties
+                    .iter()
+                    .filter(|(_, p)| p.get("readOnly") != Some(&Value::Bool(true)))
+                    .map(|(name, _)| name.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Result field an output parameter reads
+///
+/// Outputs mapped from a computed expression fall back to the parameter name.
+fn result_field(output: &ContractParameter) -> String {
+    match output.mapping.as_deref().map(str::trim) {
+        Some(mapping) if is_identifier(mapping) => mapping.to_string(),
+        _ => output.name.clone(),
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// JSON Schema of a data type
+fn type_schema(data_type: DataType) -> Map<String, Value> {
+    let schema = match data_type {
+        DataType::String => json!({"type": "string"}),
+        DataType::Boolean => json!({"type": "boolean"}),
+        DataType::Integer => json!({"type": "integer"}),
+        DataType::Decimal => json!({"type": "number"}),
+        DataType::Date => json!({"type": "string", "format": "date"}),
+        DataType::DateTime => json!({"type": "string", "format": "date-time"}),
+        DataType::Object => json!({"type": "object"}),
+        DataType::Array => json!({"type": "array"}),
+        DataType::Any => json!({}),
+    };
+    match schema {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{FieldAnnotations, TaskParameter};
+    use crate::testing::chicago_tdd::TaskBuilder;
+
+    fn param(name: &str, param_type: &str, required: bool) -> TaskParameter {
+        TaskParameter {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            mapping: None,
+            required,
+            annotations: FieldAnnotations::default(),
+        }
+    }
+
+    fn review_task() -> Task {
+        let mut amount = param("amount", "xsd:decimal", true);
+        amount.annotations.label = Some("Claim amount".to_string());
+        amount.annotations.order = Some(1);
+
+        let mut decision = param("decision", "xsd:string", true);
+        decision.annotations.allowed_values = vec![json!("approve"), json!("reject")];
+        decision.annotations.widget = Some("radio".to_string());
+        decision.annotations.order = Some(2);
+
+        let mut comment = param("comment", "xsd:string", false);
+        comment.annotations.description = Some("Reason for the decision".to_string());
+        comment.annotations.widget = Some("textarea".to_string());
+
+        TaskBuilder::new("review", "Review Claim")
+            .add_input_parameter(amount)
+            .add_output_parameter(decision)
+            .add_output_parameter(comment)
+            .build()
+    }
+
+    #[test]
+    fn test_form_schema_and_ui_schema() {
+        let form = TaskForm::for_work_item(&review_task(), &json!({"amount": 120.5})).unwrap();
+        let schema = &form.json_schema;
+
+        assert_eq!(schema["title"], json!("Review Claim"));
+        assert_eq!(
+            schema["properties"]["amount"],
+            json!({"type": "number", "title": "Claim amount", "readOnly": true, "default": 120.5})
+        );
+        assert_eq!(
+            schema["properties"]["decision"]["enum"],
+            json!(["approve", "reject"])
+        );
+        assert_eq!(schema["required"], json!(["decision"]));
+        assert_eq!(
+            form.ui_schema["ui:order"],
+            json!(["amount", "decision", "comment"])
+        );
+        assert_eq!(form.ui_schema["amount"]["ui:readonly"], json!(true));
+        assert_eq!(form.ui_schema["decision"]["ui:widget"], json!("radio"));
+        assert_eq!(
+            form.ui_schema["comment"]["ui:help"],
+            json!("Reason for the decision")
+        );
+        assert_eq!(form.editable_fields(), vec!["comment", "decision"]);
+    }
+
+    #[test]
+    fn test_mapped_output_uses_result_field() {
+        let mut approved = param("approved", "xsd:boolean", true);
+        approved.mapping = Some("ok".to_string());
+        let mut total = param("total", "xsd:decimal", false);
+        total.mapping = Some("net + tax".to_string());
+        let task = TaskBuilder::new("check", "Check")
+            .add_output_parameter(approved)
+            .add_output_parameter(total)
+            .build();
+
+        let form = TaskForm::for_task(&task).unwrap();
+        assert_eq!(
+            form.json_schema["properties"]["ok"]["type"],
+            json!("boolean")
+        );
+        assert_eq!(
+            form.json_schema["properties"]["total"]["type"],
+            json!("number")
+        );
+        assert_eq!(form.json_schema["required"], json!(["ok"]));
+    }
+}
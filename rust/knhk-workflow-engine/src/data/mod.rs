@@ -1 +1,10 @@
+pub mod contract;
+pub mod expression;
+pub mod forms;
 pub mod gateway;
+pub mod schema;
+
+pub use contract::{validate_spec_data_model, ContractParameter, TaskDataContract};
+pub use expression::{evaluate_predicate, DataScope, Expr};
+pub use forms::TaskForm;
+pub use schema::{CaseDataSchema, DataType, DataViolation, VariableDecl};
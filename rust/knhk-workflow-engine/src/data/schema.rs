@@ -0,0 +1,409 @@
+//! Typed case data model
+//!
+//! Net-level case variables with XSD/JSON Schema style types. Used to:
+//! - Validate case data at case creation
+//! - Validate task input/output mappings at the task boundary
+//! - Type-check flow predicates at workflow registration
+
+use crate::error::{WorkflowError, WorkflowResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Data type of a case variable or task parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    /// UTF-8 string (xsd:string, xsd:token, ...)
+    String,
+    /// Boolean (xsd:boolean)
+    Boolean,
+    /// Integer (xsd:integer, xsd:int, xsd:long, ...)
+    Integer,
+    /// Decimal number (xsd:decimal, xsd:double, JSON Schema number)
+    Decimal,
+    /// Calendar date, `YYYY-MM-DD` (xsd:date)
+    Date,
+    /// RFC 3339 timestamp (xsd:dateTime)
+    DateTime,
+    /// JSON object
+    Object,
+    /// JSON array
+    Array,
+    /// Any value (untyped)
+    Any,
+}
+
+impl DataType {
+    /// Parse a type name
+    ///
+    /// Accepts XSD names (with `xsd:`/`xs:` prefix or full XML Schema IRI),
+    /// JSON Schema type names and the plain names used in YAWL Turtle files.
+    pub fn parse(name: &str) -> WorkflowResult<Self> {
+        let trimmed = name.trim().trim_start_matches('<').trim_end_matches('>');
+        let local = trimmed
+            .strip_prefix("http://www.w3.org/2001/XMLSchema#")
+            .or_else(|| trimmed.strip_prefix("xsd:"))
+            .or_else(|| trimmed.strip_prefix("xs:"))
+            .unwrap_or(trimmed);
+
+        match local {
+            "string" | "normalizedString" | "token" | "anyURI" | "time" | "duration" => {
+                Ok(Self::String)
+            }
+            "boolean" | "bool" => Ok(Self::Boolean),
+            "integer" | "int" | "long" | "short" | "byte" | "nonNegativeInteger"
+            | "positiveInteger" | "negativeInteger" | "nonPositiveInteger" | "unsignedInt"
+            | "unsignedLong" => Ok(Self::Integer),
+            "decimal" | "double" | "float" | "number" => Ok(Self::Decimal),
+            "date" => Ok(Self::Date),
+            "dateTime" | "datetime" | "date-time" => Ok(Self::DateTime),
+            "object" => Ok(Self::Object),
+            "array" => Ok(Self::Array),
+            "any" | "anyType" => Ok(Self::Any),
+            _ => Err(WorkflowError::Validation(format!(
+                "Unknown data type '{}'",
+                name
+            ))),
+        }
+    }
+
+    /// Check whether a (non-null) JSON value conforms to this type
+    pub fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Boolean => value.is_boolean(),
+            Self::Integer => {
+                value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+            }
+            Self::Decimal => value.is_number(),
+            Self::Date => value
+                .as_str()
+                .is_some_and(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()),
+            Self::DateTime => value
+                .as_str()
+                .is_some_and(|s| chrono::DateTime::parse_from_rfc3339(s).is_ok()),
+            Self::Object => value.is_object(),
+            Self::Array => value.is_array(),
+            Self::Any => true,
+        }
+    }
+
+    /// Numeric types (integer, decimal)
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Self::Integer | Self::Decimal)
+    }
+
+    /// Check whether values of the two types can be compared for equality
+    pub fn is_comparable(&self, other: &DataType) -> bool {
+        *self == Self::Any
+            || *other == Self::Any
+            || self == other
+            || (self.is_numeric() && other.is_numeric())
+    }
+
+    /// Check whether values of the two types have a total order (`<`, `>=`, ...)
+    pub fn is_orderable(&self, other: &DataType) -> bool {
+        let orderable = |t: &DataType| {
+            t.is_numeric() || matches!(t, Self::String | Self::Date | Self::DateTime | Self::Any)
+        };
+        orderable(self) && orderable(other) && self.is_comparable(other)
+    }
+
+    /// Check whether a value of type `other` can be assigned to a variable of this type
+    pub fn is_assignable_from(&self, other: &DataType) -> bool {
+        *self == Self::Any
+            || *other == Self::Any
+            || self == other
+            || (*self == Self::Decimal && *other == Self::Integer)
+    }
+}
+
+impl std::fmt::Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Decimal => "decimal",
+            Self::Date => "date",
+            Self::DateTime => "dateTime",
+            Self::Object => "object",
+            Self::Array => "array",
+            Self::Any => "any",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Net-level case variable declaration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariableDecl {
+    /// Variable name
+    pub name: String,
+    /// Variable type
+    pub data_type: DataType,
+    /// Whether the variable must be present (and non-null)
+    #[serde(default)]
+    pub required: bool,
+    /// Initial value applied when the variable is absent at case creation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+}
+
+impl VariableDecl {
+    /// Declare an optional variable
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            required: false,
+            default: None,
+        }
+    }
+
+    /// Mark the variable as required
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set the initial value
+    pub fn with_default(mut self, value: serde_json::Value) -> Self {
+        self.default = Some(value);
+        self
+    }
+}
+
+/// A single data validation failure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataViolation {
+    /// Variable or parameter path
+    pub path: String,
+    /// Failure description
+    pub message: String,
+}
+
+impl std::fmt::Display for DataViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Convert validation failures into a `WorkflowError::Validation`
+pub fn violations_error(context: &str, violations: &[DataViolation]) -> WorkflowError {
+    let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+    WorkflowError::Validation(format!("{}: {}", context, details.join("; ")))
+}
+
+/// Check a value against a type and required flag
+pub(crate) fn check_value(
+    path: &str,
+    data_type: DataType,
+    required: bool,
+    value: Option<&serde_json::Value>,
+    violations: &mut Vec<DataViolation>,
+) {
+    match value {
+        None | Some(serde_json::Value::Null) => {
+            if required {
+                violations.push(DataViolation {
+                    path: path.to_string(),
+                    message: "required value is missing".to_string(),
+                });
+            }
+        }
+        Some(value) => {
+            if !data_type.accepts(value) {
+                violations.push(DataViolation {
+                    path: path.to_string(),
+                    message: format!("expected {}, got {}", data_type, value),
+                });
+            }
+        }
+    }
+}
+
+/// Case data schema (net-level variables)
+///
+/// An empty schema describes an untyped net: any case data is accepted and
+/// predicates are only checked for syntax.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CaseDataSchema {
+    variables: BTreeMap<String, VariableDecl>,
+}
+
+impl CaseDataSchema {
+    /// Create an empty (untyped) schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a schema from variable declarations
+    pub fn from_variables(variables: &[VariableDecl]) -> Self {
+        let mut schema = Self::new();
+        for decl in variables {
+            schema.declare(decl.clone());
+        }
+        schema
+    }
+
+    /// Build a schema from a JSON Schema object (`properties` + `required`)
+    pub fn from_json_schema(json_schema: &serde_json::Value) -> WorkflowResult<Self> {
+        let properties = json_schema
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .ok_or_else(|| {
+                WorkflowError::Validation("JSON Schema must declare object properties".to_string())
+            })?;
+        let required: Vec<&str> = json_schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut schema = Self::new();
+        for (name, property) in properties {
+            let data_type = match property.get("type").and_then(|t| t.as_str()) {
+                Some("string") => match property.get("format").and_then(|f| f.as_str()) {
+                    Some("date") => DataType::Date,
+                    Some("date-time") => DataType::DateTime,
+                    _ => DataType::String,
+                },
+                Some(type_name) => DataType::parse(type_name)?,
+                None => DataType::Any,
+            };
+            let mut decl = VariableDecl::new(name.clone(), data_type);
+            decl.required = required.contains(&name.as_str());
+            decl.default = property.get("default").cloned();
+            schema.declare(decl);
+        }
+        Ok(schema)
+    }
+
+    /// Declare (or replace) a variable
+    pub fn declare(&mut self, decl: VariableDecl) {
+        self.variables.insert(decl.name.clone(), decl);
+    }
+
+    /// Get a variable declaration
+    pub fn get(&self, name: &str) -> Option<&VariableDecl> {
+        self.variables.get(name)
+    }
+
+    /// Iterate over declared variables (sorted by name)
+    pub fn variables(&self) -> impl Iterator<Item = &VariableDecl> {
+        self.variables.values()
+    }
+
+    /// Check whether the schema declares no variables (untyped net)
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    /// Validate case data against the declared variables
+    ///
+    /// Undeclared keys are allowed (open content model).
+    pub fn validate(&self, data: &serde_json::Value) -> Result<(), Vec<DataViolation>> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let Some(object) = data.as_object() else {
+            return Err(vec![DataViolation {
+                path: "$".to_string(),
+                message: "case data must be a JSON object".to_string(),
+            }]);
+        };
+
+        let mut violations = Vec::new();
+        for decl in self.variables.values() {
+            check_value(
+                &decl.name,
+                decl.data_type,
+                decl.required,
+                object.get(&decl.name),
+                &mut violations,
+            );
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Fill in declared defaults for absent variables
+    pub fn apply_defaults(&self, data: &mut serde_json::Value) {
+        if let Some(object) = data.as_object_mut() {
+            for decl in self.variables.values() {
+                if let Some(ref default) = decl.default {
+                    object
+                        .entry(decl.name.clone())
+                        .or_insert_with(|| default.clone());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_xsd_and_json_schema_type_names() {
+        assert_eq!(DataType::parse("xsd:decimal").ok(), Some(DataType::Decimal));
+        assert_eq!(
+            DataType::parse("http://www.w3.org/2001/XMLSchema#dateTime").ok(),
+            Some(DataType::DateTime)
+        );
+        assert_eq!(DataType::parse("integer").ok(), Some(DataType::Integer));
+        assert_eq!(DataType::parse("number").ok(), Some(DataType::Decimal));
+        assert!(DataType::parse("money").is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_all_violations() {
+        let schema = CaseDataSchema::from_variables(&[
+            VariableDecl::new("amount", DataType::Decimal).required(),
+            VariableDecl::new("approved", DataType::Boolean),
+            VariableDecl::new("due", DataType::Date),
+        ]);
+
+        assert!(schema
+            .validate(&json!({"amount": 10, "approved": false, "due": "2024-05-01"}))
+            .is_ok());
+
+        let violations = schema
+            .validate(&json!({"approved": "yes", "due": "tomorrow"}))
+            .err()
+            .unwrap_or_default();
+        assert_eq!(violations.len(), 3);
+        assert!(violations.iter().any(|v| v.path == "amount"));
+    }
+
+    #[test]
+    fn test_from_json_schema_with_defaults() {
+        let schema = CaseDataSchema::from_json_schema(&json!({
+            "type": "object",
+            "properties": {
+                "customer": {"type": "string"},
+                "priority": {"type": "integer", "default": 3},
+                "placed": {"type": "string", "format": "date-time"}
+            },
+            "required": ["customer"]
+        }))
+        .unwrap_or_default();
+
+        assert_eq!(
+            schema.get("placed").map(|d| d.data_type),
+            Some(DataType::DateTime)
+        );
+        assert!(schema.get("customer").is_some_and(|d| d.required));
+
+        let mut data = json!({"customer": "acme"});
+        schema.apply_defaults(&mut data);
+        assert_eq!(data["priority"], 3);
+    }
+}
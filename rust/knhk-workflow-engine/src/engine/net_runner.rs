@@ -398,6 +398,10 @@ mod tests {
                 required_roles: vec![],
                 required_capabilities: vec![],
                 exception_worklet: None,
+                multi_instance: None,
+                cancellation: None,
+                decomposition: None,
+                message: None,
                 pattern_id: None,
             },
         );
@@ -422,6 +426,10 @@ mod tests {
                 required_roles: vec![],
                 required_capabilities: vec![],
                 exception_worklet: None,
+                multi_instance: None,
+                cancellation: None,
+                decomposition: None,
+                message: None,
                 pattern_id: None,
             },
         );
@@ -435,6 +443,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         }
     }
 
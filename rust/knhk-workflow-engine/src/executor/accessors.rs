@@ -5,9 +5,10 @@ use crate::integration::{Fortune5Integration, SidecarIntegration};
 use crate::parser::WorkflowSpec;
 use crate::parser::WorkflowSpecId;
 use crate::patterns::PatternRegistry;
+use crate::resilience::DeadLetterQueue;
 use crate::resource::ResourceAllocator;
 use crate::services::timer::TimerService;
-use crate::services::{AdmissionGate, EventSidecar, WorkItemService};
+use crate::services::{AdmissionGate, EventSidecar, MessageCorrelator, WorkItemService};
 #[cfg(feature = "storage")]
 use crate::state::StateStore;
 use crate::timebase::SysClock;
@@ -39,6 +40,11 @@ impl WorkflowEngine {
         &self.worklet_executor
     }
 
+    /// Get dead letter queue of failed case executions
+    pub fn dead_letter_queue(&self) -> &Arc<DeadLetterQueue> {
+        &self.dead_letter_queue
+    }
+
     /// Get state store (for REST API access)
     pub fn state_store(&self) -> &Arc<RwLock<Arc<StateStore>>> {
         &self.state_store
@@ -74,6 +80,11 @@ impl WorkflowEngine {
         &self.work_item_service
     }
 
+    /// Get message correlator
+    pub fn message_correlator(&self) -> &Arc<MessageCorrelator> {
+        &self.message_correlator
+    }
+
     /// Get admission gate
     pub fn admission_gate(&self) -> &Arc<AdmissionGate> {
         &self.admission_gate
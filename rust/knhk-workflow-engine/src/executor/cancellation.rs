@@ -0,0 +1,135 @@
+//! Cancellation regions (YAWL cancellation sets, Patterns 19, 20 and 25)
+//!
+//! A task's cancellation region is applied atomically when the task completes,
+//! before its output tokens are produced:
+//! - Region scope: tokens are removed from the target tasks and conditions, open
+//!   work items of the target tasks (including multiple instance work items) are
+//!   withdrawn and running multiple instance tasks are cancelled
+//! - Case scope: all open work items of the case are withdrawn and the case is
+//!   cancelled
+//!
+//! Each application is recorded as a `StateEvent::RegionCancelled` in the case
+//! history; the marking change is made durable by the checkpoint after routing.
+
+use crate::case::CaseId;
+use crate::error::WorkflowResult;
+use crate::parser::{CancelScope, CancellationRegion};
+use crate::services::work_items::WorkItemState;
+use crate::state::recovery::CaseMarking;
+
+use super::WorkflowEngine;
+
+/// Apply the cancellation region of a completed task
+///
+/// Returns `true` if the whole case was cancelled.
+pub(super) async fn apply_cancellation(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    task_id: &str,
+    region: &CancellationRegion,
+    marking: &mut CaseMarking,
+) -> WorkflowResult<bool> {
+    let (cancelled, withdrawn) = match region.scope {
+        CancelScope::Region => {
+            let cancelled = marking.cancel_region(&region.targets);
+            for target in &region.targets {
+                if let Some((_, mut state)) = engine.mi_tasks.remove(&(case_id, target.clone())) {
+                    state.cancel_remaining();
+                }
+            }
+            let withdrawn = withdraw_work_items(engine, case_id, Some(&region.targets)).await;
+            (cancelled, withdrawn)
+        }
+        CancelScope::Case => {
+            let mut cancelled: Vec<String> = marking
+                .pending
+                .drain(..)
+                .chain(marking.executing.drain())
+                .collect();
+            cancelled.sort();
+            cancelled.dedup();
+            engine.mi_tasks.retain(|(case, _), _| *case != case_id);
+            let withdrawn = withdraw_work_items(engine, case_id, None).await;
+            (cancelled, withdrawn)
+        }
+    };
+
+    tracing::debug!(
+        "Task {} of case {} cancelled {:?} and withdrew work items {:?}",
+        task_id,
+        case_id,
+        cancelled,
+        withdrawn
+    );
+    engine
+        .state_manager
+        .log_region_cancelled(case_id, task_id.to_string(), cancelled, withdrawn)
+        .await?;
+
+    if region.scope == CancelScope::Case {
+        engine.cancel_case(case_id).await?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Withdraw the open work items of the given tasks (all tasks if `None`)
+///
+/// Returns the withdrawn work item IDs.
+async fn withdraw_work_items(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    tasks: Option<&[String]>,
+) -> Vec<String> {
+    let mut withdrawn = Vec::new();
+    let work_items = engine
+        .work_item_service
+        .list_case_work_items(&case_id.to_string())
+        .await;
+
+    for work_item in work_items {
+        if matches!(
+            work_item.state,
+            WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+        ) {
+            continue;
+        }
+        let in_region = tasks.map_or(true, |tasks| {
+            tasks
+                .iter()
+                .any(|task_id| belongs_to_task(&work_item.task_id, task_id))
+        });
+        if !in_region {
+            continue;
+        }
+        match engine.work_item_service.cancel(&work_item.id).await {
+            Ok(()) => withdrawn.push(work_item.id),
+            Err(e) => tracing::warn!("Failed to withdraw work item {}: {}", work_item.id, e),
+        }
+    }
+
+    withdrawn.sort();
+    withdrawn
+}
+
+/// Check whether a work item belongs to a task (or to one of its MI instances)
+pub(super) fn belongs_to_task(work_item_task: &str, task_id: &str) -> bool {
+    work_item_task == task_id
+        || work_item_task
+            .strip_prefix(task_id)
+            .and_then(|rest| rest.strip_prefix('_'))
+            .is_some_and(|index| index.parse::<usize>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_belongs_to_task_matches_mi_instances() {
+        assert!(belongs_to_task("approve", "approve"));
+        assert!(belongs_to_task("approve_3", "approve"));
+        assert!(!belongs_to_task("approve_all", "approve"));
+        assert!(!belongs_to_task("approver", "approve"));
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! Inputs pre-validated at ingress.
 
-use crate::case::{Case, CaseId, CaseState};
+use crate::case::{Case, CaseId, CaseLink, CaseState};
+use crate::data::schema::violations_error;
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::integration::fortune5::RuntimeClass;
 use crate::parser::WorkflowSpecId;
@@ -21,6 +22,16 @@ impl WorkflowEngine {
         &self,
         spec_id: WorkflowSpecId,
         data: serde_json::Value,
+    ) -> WorkflowResult<CaseId> {
+        self.create_linked_case(spec_id, data, None).await
+    }
+
+    /// Create a new case, optionally as the sub-case of a composite task
+    pub(super) async fn create_linked_case(
+        &self,
+        spec_id: WorkflowSpecId,
+        data: serde_json::Value,
+        parent: Option<CaseLink>,
     ) -> WorkflowResult<CaseId> {
         let start_time = Instant::now();
 
@@ -56,7 +67,29 @@ impl WorkflowEngine {
 
         // Verify workflow exists
         let spec_result = self.get_workflow(spec_id).await;
-        if let Err(e) = spec_result {
+        let spec = match spec_result {
+            Ok(spec) => spec,
+            Err(e) => {
+                if let (Some(ref otel), Some(ref span)) =
+                    (self.otel_integration.as_ref(), span_ctx.as_ref())
+                {
+                    otel_span_end!(
+                        otel,
+                        span_ctx,
+                        success: false,
+                        start_time: start_time
+                    )
+                    .await?;
+                }
+                return Err(e);
+            }
+        };
+
+        // Validate case data against the net variables and fill in defaults
+        let schema = spec.data_schema();
+        let mut data = data;
+        schema.apply_defaults(&mut data);
+        if let Err(violations) = schema.validate(&data) {
             if let (Some(ref otel), Some(ref span)) =
                 (self.otel_integration.as_ref(), span_ctx.as_ref())
             {
@@ -68,11 +101,12 @@ impl WorkflowEngine {
                 )
                 .await?;
             }
-            return Err(e);
+            return Err(violations_error("Invalid case data", &violations));
         }
 
         // Create case
-        let case = Case::new(spec_id, data.clone());
+        let mut case = Case::new(spec_id, data.clone());
+        case.parent = parent;
         let case_id = case.id;
 
         // Update span with actual case_id
@@ -231,9 +265,12 @@ impl WorkflowEngine {
         // Note: The case is guaranteed to exist here due to ok_or_else above
 
         // Start if not already started
-        if case_ref.value().state == CaseState::Created {
+        let started = if case_ref.value().state == CaseState::Created {
             case_ref.value_mut().start()?;
-        }
+            Some(case_ref.value().clone())
+        } else {
+            None
+        };
 
         // Get workflow specification (DashMap is thread-safe)
         let spec_id = case_ref.value().spec_id;
@@ -245,8 +282,17 @@ impl WorkflowEngine {
         // This check is redundant but kept for clarity
         // Note: The spec is guaranteed to exist here due to ok_or_else above
         let spec_clone = spec.value().clone();
+        drop(spec);
         drop(case_ref);
 
+        // Persist the Running state so the case is resumed after a crash
+        if let Some(case_clone) = started {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).save_case(case_id, &case_clone)?;
+            drop(store_arc);
+            self.state_manager.save_case(&case_clone).await?;
+        }
+
         // Execute workflow from start to end condition
         // Note: execute_workflow doesn't actually recurse to execute_case, so this is safe
         let execution_result =
@@ -254,6 +300,9 @@ impl WorkflowEngine {
 
         let latency_ms = start_time.elapsed().as_millis();
         let success = execution_result.is_ok();
+        if let Err(ref e) = execution_result {
+            self.dead_letter_case(case_id, e).await;
+        }
 
         // Record SLO metrics if Fortune 5 is enabled
         if let Some(ref fortune5) = self.fortune5_integration {
@@ -308,11 +357,16 @@ impl WorkflowEngine {
         drop(case_guard);
         let store_arc = self.state_store.read().await;
         (*store_arc).save_case(case_id, &case_clone)?;
+        drop(store_arc);
 
         // Save to state manager for event sourcing (will emit CaseStateChanged event)
         self.state_manager.save_case(&case_clone).await?;
 
-        Ok(())
+        // Stop waiting for messages (message-catch tasks)
+        self.message_correlator.cancel_case(case_id).await;
+
+        // Cancel linked sub-cases and parent case (composite tasks)
+        self.propagate_cancellation(&case_clone).await
     }
 
     /// Get case status
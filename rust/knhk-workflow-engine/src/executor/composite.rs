@@ -0,0 +1,253 @@
+//! Composite tasks (YAWL sub-net decomposition)
+//!
+//! A composite task launches its sub-net as a child case with the task's
+//! mapped inputs and is suspended until the child case finishes; the child's
+//! outputs are then mapped back into the parent case. Cancellation propagates
+//! both ways: cancelling the parent cancels its running sub-cases, cancelling
+//! a sub-case cancels its parent.
+//!
+//! Sub-nets are resolved by spec ID, or by IRI among the registered specs,
+//! picking the pinned version or the latest one.
+
+use crate::case::{Case, CaseId, CaseLink, CaseState};
+use crate::data::contract::TaskDataContract;
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{SubNetRef, Task, TaskType, WorkflowSpec};
+use crate::snapshots::compare_versions;
+use tokio::time::Duration;
+
+use super::task::merge_task_result;
+use super::WorkflowEngine;
+
+/// Poll interval while a composite task waits for its sub-case
+const SUBCASE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+impl WorkflowEngine {
+    /// List the sub-cases launched by the composite tasks of a case
+    pub fn sub_cases(&self, case_id: CaseId) -> Vec<CaseId> {
+        let mut sub_cases: Vec<(chrono::DateTime<chrono::Utc>, CaseId)> = self
+            .cases
+            .iter()
+            .filter(|entry| {
+                entry
+                    .value()
+                    .parent
+                    .as_ref()
+                    .is_some_and(|parent| parent.case_id == case_id)
+            })
+            .map(|entry| (entry.value().created_at, *entry.key()))
+            .collect();
+        sub_cases.sort_by_key(|(created_at, _)| *created_at);
+        sub_cases.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Resolve the sub-net a composite task decomposes to
+    pub(super) fn resolve_subnet(&self, subnet: &SubNetRef) -> WorkflowResult<WorkflowSpec> {
+        if let Some(spec_id) = subnet.spec_id {
+            return self
+                .specs
+                .get(&spec_id)
+                .map(|spec| spec.value().clone())
+                .ok_or_else(|| {
+                    WorkflowError::InvalidSpecification(format!("Sub-net {} not found", spec_id))
+                });
+        }
+
+        let iri = subnet.iri.as_deref().ok_or_else(|| {
+            WorkflowError::InvalidSpecification(
+                "Sub-net reference needs a spec ID or an IRI".to_string(),
+            )
+        })?;
+        self.specs
+            .iter()
+            .filter(|entry| entry.value().iri.as_deref() == Some(iri))
+            .filter(|entry| match subnet.version {
+                Some(ref version) => entry.value().version.as_ref() == Some(version),
+                None => true,
+            })
+            .max_by(|a, b| {
+                compare_versions(a.value().version.as_deref(), b.value().version.as_deref())
+            })
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| {
+                WorkflowError::InvalidSpecification(format!(
+                    "Sub-net {}{} is not registered",
+                    iri,
+                    subnet
+                        .version
+                        .as_ref()
+                        .map(|v| format!(" version {}", v))
+                        .unwrap_or_default()
+                ))
+            })
+    }
+
+    /// Check that all composite tasks of a spec decompose to registered sub-nets
+    pub(super) fn validate_decompositions(&self, spec: &WorkflowSpec) -> WorkflowResult<()> {
+        for task in spec.tasks.values() {
+            if task.task_type != TaskType::Composite {
+                continue;
+            }
+            let subnet = match task.decomposition {
+                Some(ref subnet) => subnet,
+                None => continue,
+            };
+            let same_net = match subnet.spec_id {
+                Some(spec_id) => spec_id == spec.id,
+                None => {
+                    subnet.iri.is_some()
+                        && subnet.iri == spec.iri
+                        && (subnet.version.is_none() || subnet.version == spec.version)
+                }
+            };
+            if same_net {
+                return Err(WorkflowError::Validation(format!(
+                    "Composite task {} decomposes to its own net",
+                    task.id
+                )));
+            }
+            self.resolve_subnet(subnet).map_err(|e| {
+                WorkflowError::Validation(format!("Composite task {}: {}", task.id, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Cancel the running sub-cases and the parent case of a cancelled case
+    pub(super) async fn propagate_cancellation(&self, case: &Case) -> WorkflowResult<()> {
+        let mut linked = self.sub_cases(case.id);
+        if let Some(ref parent) = case.parent {
+            linked.push(parent.case_id);
+        }
+
+        for linked_id in linked {
+            let running = self
+                .cases
+                .get(&linked_id)
+                .is_some_and(|linked| !is_finished(linked.state));
+            if running {
+                tracing::debug!(
+                    "Cancelling case {} linked to cancelled case {}",
+                    linked_id,
+                    case.id
+                );
+                Box::pin(self.cancel_case(linked_id)).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Execute a composite task: run its sub-net as a child case and wait for it
+pub(super) async fn execute_composite_task(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    task: &Task,
+) -> WorkflowResult<()> {
+    let subnet_ref = task.decomposition.as_ref().ok_or_else(|| {
+        WorkflowError::TaskExecutionFailed(format!(
+            "Composite task {} has no sub-net (yawl:hasDecomposesTo)",
+            task.id
+        ))
+    })?;
+    let subnet = engine.resolve_subnet(subnet_ref)?;
+
+    // Re-attach to the sub-case if the task is re-executed after crash recovery
+    let existing = engine.sub_cases(case_id).into_iter().find(|child_id| {
+        engine.cases.get(child_id).is_some_and(|child| {
+            child.parent.as_ref().is_some_and(|p| p.task_id == task.id) && !is_finished(child.state)
+        })
+    });
+    let child_id = match existing {
+        Some(child_id) => child_id,
+        None => {
+            // The sub-case only sees the task's mapped inputs
+            let case = engine.get_case(case_id).await?;
+            let contract = TaskDataContract::for_task(task)?;
+            let input = if contract.inputs.is_empty() {
+                case.data.clone()
+            } else {
+                contract.bind_inputs(&case.data)?
+            };
+            let parent = CaseLink {
+                case_id,
+                task_id: task.id.clone(),
+            };
+            let child_id = engine
+                .create_linked_case(subnet.id, input, Some(parent))
+                .await?;
+            engine
+                .state_manager
+                .log_subcase_started(case_id, task.id.clone(), child_id, subnet.id)
+                .await?;
+            child_id
+        }
+    };
+
+    // Suspend the task until the sub-case finishes (a recovered sub-case is
+    // resumed by crash recovery, a new one is executed here)
+    if engine.get_case(child_id).await?.state == CaseState::Created {
+        if let Err(e) = engine.execute_case(child_id).await {
+            // A cancelled sub-case is reported below, other errors fail the task
+            if !is_finished(engine.get_case(child_id).await?.state) {
+                return Err(e);
+            }
+        }
+    }
+    let child = loop {
+        let child = engine.get_case(child_id).await?;
+        if is_finished(child.state) {
+            break child;
+        }
+        let parent_cancelled = engine
+            .cases
+            .get(&case_id)
+            .is_some_and(|case| case.state == CaseState::Cancelled);
+        if parent_cancelled {
+            return Err(WorkflowError::TaskExecutionFailed(format!(
+                "Case {} was cancelled while composite task {} was waiting for sub-case {}",
+                case_id, task.id, child_id
+            )));
+        }
+        tokio::time::sleep(SUBCASE_POLL_INTERVAL).await;
+    };
+
+    engine
+        .state_manager
+        .log_subcase_finished(case_id, task.id.clone(), child_id, child.state.to_string())
+        .await?;
+    if child.state != CaseState::Completed {
+        return Err(WorkflowError::TaskExecutionFailed(format!(
+            "Sub-case {} of composite task {} {}",
+            child_id, task.id, child.state
+        )));
+    }
+
+    // Map the sub-case outputs back into the parent case
+    let stored = {
+        let store_arc = engine.state_store.read().await;
+        (*store_arc).load_case(&case_id)?
+    };
+    let mut case = match stored {
+        Some(case) => case,
+        None => engine.get_case(case_id).await?,
+    };
+    merge_task_result(task, &mut case.data, &child.data)?;
+    {
+        let store_arc = engine.state_store.read().await;
+        (*store_arc).save_case(case_id, &case)?;
+    }
+    if let Some(mut live) = engine.cases.get_mut(&case_id) {
+        live.data = case.data.clone();
+    }
+
+    Ok(())
+}
+
+/// Check whether a case reached a final state
+pub(super) fn is_finished(state: CaseState) -> bool {
+    matches!(
+        state,
+        CaseState::Completed | CaseState::Cancelled | CaseState::Failed
+    )
+}
@@ -8,7 +8,10 @@ use crate::integration::{Fortune5Integration, LockchainIntegration, OtelIntegrat
 use crate::patterns::{PatternRegistry, RegisterAllExt};
 use crate::resource::ResourceAllocator;
 use crate::services::timer::TimerService;
-use crate::services::{AdmissionGate, EventSidecar, TimerFired, WorkItemService};
+use crate::services::{
+    AdmissionGate, EventSidecar, MessageCorrelator, TimerFired, WorkItemService,
+};
+use crate::snapshots::SpecVersionRegistry;
 use crate::state::manager::StateManager;
 #[cfg(feature = "storage")]
 use crate::state::StateStore;
@@ -18,6 +21,7 @@ use dashmap::DashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 
+use super::dead_letters::open_dead_letter_queue;
 use super::events::{start_event_loop, start_timer_loop};
 use super::WorkflowEngine;
 #[cfg(feature = "rdf")]
@@ -48,6 +52,7 @@ impl WorkflowEngine {
             Some(state_store_arc.clone()),
         ));
         let work_item_service = Arc::new(WorkItemService::new());
+        let dead_letter_queue = Arc::new(open_dead_letter_queue(&state_store_arc));
         let admission_gate = Arc::new(AdmissionGate::new());
         let event_sidecar = Arc::new(EventSidecar::new(event_tx.clone()));
 
@@ -72,12 +77,17 @@ impl WorkflowEngine {
             state_store: Arc::new(RwLock::new(state_store_arc)),
             state_manager,
             specs: Arc::new(DashMap::new()),
+            spec_versions: Arc::new(SpecVersionRegistry::new()),
             cases: Arc::new(DashMap::new()),
+            markings: Arc::new(DashMap::new()),
+            mi_tasks: Arc::new(DashMap::new()),
             resource_allocator,
             worklet_repository,
             worklet_executor,
             timer_service: timer_service.clone(),
+            dead_letter_queue,
             work_item_service,
+            message_correlator: Arc::new(MessageCorrelator::new()),
             admission_gate,
             event_sidecar: event_sidecar.clone(),
             enterprise_config: None,
@@ -98,6 +108,7 @@ impl WorkflowEngine {
         // Note: Pattern metadata loading must be done by the caller after engine creation
         // because WorkflowEngine::new is synchronous. Call engine.load_pattern_metadata_rdf().await
         // after creating the engine in async context.
+        // Likewise, in-flight cases are rebuilt by calling engine.recover_cases().await.
 
         // Start event loops
         let pattern_registry_clone = Arc::clone(&engine.pattern_registry);
@@ -153,6 +164,7 @@ impl WorkflowEngine {
             Some(state_store_arc.clone()),
         ));
         let work_item_service = Arc::new(WorkItemService::new());
+        let dead_letter_queue = Arc::new(open_dead_letter_queue(&state_store_arc));
         let admission_gate = Arc::new(AdmissionGate::new());
         let event_sidecar = Arc::new(EventSidecar::new(event_tx.clone()));
 
@@ -193,12 +205,17 @@ impl WorkflowEngine {
             state_store: Arc::new(RwLock::new(state_store_arc)),
             state_manager,
             specs: Arc::new(DashMap::new()),
+            spec_versions: Arc::new(SpecVersionRegistry::new()),
             cases: Arc::new(DashMap::new()),
+            markings: Arc::new(DashMap::new()),
+            mi_tasks: Arc::new(DashMap::new()),
             resource_allocator,
             worklet_repository,
             worklet_executor,
             timer_service: timer_service.clone(),
+            dead_letter_queue,
             work_item_service,
+            message_correlator: Arc::new(MessageCorrelator::new()),
             admission_gate,
             event_sidecar: event_sidecar.clone(),
             enterprise_config: None,
@@ -219,6 +236,7 @@ impl WorkflowEngine {
         // Note: Pattern metadata loading must be done by the caller after engine creation
         // because WorkflowEngine::new is synchronous. Call engine.load_pattern_metadata_rdf().await
         // after creating the engine in async context.
+        // Likewise, in-flight cases are rebuilt by calling engine.recover_cases().await.
 
         // Start event loops
         let pattern_registry_clone = Arc::clone(&engine.pattern_registry);
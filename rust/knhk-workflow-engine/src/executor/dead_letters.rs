@@ -0,0 +1,237 @@
+//! Dead-lettered case executions
+//!
+//! A case whose execution fails is recorded in the engine's dead letter queue,
+//! persisted in the state store. Redriving the entry resumes the case from its
+//! marking, re-executing the task that failed. The entry payload carries the
+//! case data; editing it replaces the case data before the next redrive.
+//! Sub-cases of composite tasks are not recorded: redriving the parent case
+//! re-executes the composite task.
+
+use crate::case::{CaseId, CaseState};
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::resilience::{DLQEntry, DeadLetterHandler, DeadLetterQueue, RetryConfig, RetryPolicy};
+use crate::state::recovery::{CaseMarking, CaseReplay, RecoveryOutcome};
+#[cfg(feature = "storage")]
+use crate::state::StateStore;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::composite::is_finished;
+use super::workflow_execution::resume_workflow;
+use super::WorkflowEngine;
+
+/// Sled tree holding the engine's dead letter queue
+const DEAD_LETTER_TREE: &str = "dead_letters";
+
+/// Maximum number of dead letter entries kept
+const DEAD_LETTER_CAPACITY: usize = 10_000;
+
+/// Operation recorded for failed case executions
+const EXECUTE_CASE: &str = "execute_case";
+
+/// Open the engine's dead letter queue in the state store
+///
+/// Falls back to an in-memory queue if the tree cannot be opened.
+pub(super) fn open_dead_letter_queue(store: &StateStore) -> DeadLetterQueue {
+    let persistent = store
+        .open_tree(DEAD_LETTER_TREE)
+        .and_then(|tree| DeadLetterQueue::persistent(tree, DEAD_LETTER_CAPACITY));
+    match persistent {
+        Ok(dlq) => dlq,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to open persistent dead letter queue, keeping it in memory: {}",
+                e
+            );
+            DeadLetterQueue::new(DEAD_LETTER_CAPACITY)
+        }
+    }
+}
+
+/// Redrives dead-lettered case executions
+struct CaseRedriveHandler {
+    engine: WorkflowEngine,
+}
+
+#[async_trait]
+impl DeadLetterHandler for CaseRedriveHandler {
+    async fn redrive(&self, entry: &DLQEntry) -> WorkflowResult<()> {
+        let operation = entry
+            .operation_data
+            .get("operation")
+            .and_then(|operation| operation.as_str());
+        let case_id = match (operation, entry.case_id) {
+            (Some(EXECUTE_CASE), Some(case_id)) => case_id,
+            _ => {
+                return Err(WorkflowError::Validation(format!(
+                    "Dead letter entry {} has no redrivable operation",
+                    entry.id
+                )))
+            }
+        };
+
+        let result = self
+            .engine
+            .redrive_case(case_id, entry.operation_data.get("data"))
+            .await;
+        if result.is_err() {
+            // Keep the payload in step with the progress the redrive made
+            if let Ok(data) = self.engine.stored_case_data(case_id).await {
+                let mut payload = entry.operation_data.clone();
+                payload["data"] = data;
+                if let Err(e) = self
+                    .engine
+                    .dead_letter_queue
+                    .edit_payload(entry.id, payload)
+                {
+                    tracing::warn!("Failed to update dead letter entry {}: {}", entry.id, e);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl WorkflowEngine {
+    /// Redrive a dead letter entry now, regardless of its schedule or status
+    ///
+    /// The entry is removed when the case resumes without error.
+    pub async fn redrive_dead_letter(&self, id: Uuid) -> WorkflowResult<()> {
+        let handler = CaseRedriveHandler {
+            engine: self.clone(),
+        };
+        self.dead_letter_queue.redrive(id, &handler).await
+    }
+
+    /// Start the background worker redriving due dead letter entries
+    ///
+    /// Failed redrives are rescheduled with the backoff of `config`; entries
+    /// failing with an error `policy` does not retry, or exhausting
+    /// `config.max_retries`, are quarantined.
+    pub fn start_dead_letter_redrive(
+        &self,
+        config: RetryConfig,
+        policy: Arc<dyn RetryPolicy + Send + Sync>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let handler = Arc::new(CaseRedriveHandler {
+            engine: self.clone(),
+        });
+        self.dead_letter_queue
+            .clone()
+            .spawn_redrive_worker(handler, config, policy, interval)
+    }
+
+    /// Record a failed case execution in the dead letter queue
+    ///
+    /// Finished cases (e.g. cancelled while executing), sub-cases and cases
+    /// already in the queue are not recorded.
+    pub(super) async fn dead_letter_case(&self, case_id: CaseId, error: &WorkflowError) {
+        let case = match self.get_case(case_id).await {
+            Ok(case) => case,
+            Err(_) => return,
+        };
+        if is_finished(case.state) || case.parent.is_some() {
+            return;
+        }
+        let queued = self
+            .dead_letter_queue
+            .list()
+            .map(|entries| entries.iter().any(|e| e.case_id == Some(case_id)))
+            .unwrap_or(false);
+        if queued {
+            return;
+        }
+
+        let data = self.stored_case_data(case_id).await.unwrap_or(case.data);
+        let payload = serde_json::json!({ "operation": EXECUTE_CASE, "data": data });
+        match self
+            .dead_letter_queue
+            .add(Some(case_id), Some(case.spec_id), error.clone(), payload)
+        {
+            Ok(id) => tracing::warn!(
+                "Case {} failed and was dead-lettered as {}: {}",
+                case_id,
+                id,
+                error
+            ),
+            Err(e) => tracing::error!("Failed to dead-letter case {}: {}", case_id, e),
+        }
+    }
+
+    /// Resume a dead-lettered case, re-executing the task that failed
+    async fn redrive_case(
+        &self,
+        case_id: CaseId,
+        data: Option<&serde_json::Value>,
+    ) -> WorkflowResult<()> {
+        if let Some(data) = data {
+            self.replace_case_data(case_id, data).await?;
+        }
+
+        if !self.cases.contains_key(&case_id) {
+            // Not live (e.g. after a restart): rebuild it from the case history
+            let (stored, history) = {
+                let store_arc = self.state_store.read().await;
+                let stored = (*store_arc)
+                    .load_case(&case_id)?
+                    .ok_or_else(|| WorkflowError::CaseNotFound(case_id.to_string()))?;
+                (stored, (*store_arc).load_case_history(&case_id)?)
+            };
+            let recovered = self
+                .recover_case(stored, CaseReplay::from_events(&history))
+                .await?;
+            match recovered.outcome {
+                RecoveryOutcome::Failed(message) => {
+                    return Err(WorkflowError::TaskExecutionFailed(message))
+                }
+                RecoveryOutcome::Restored => {}
+                _ => return Ok(()),
+            }
+        }
+
+        let case = self.get_case(case_id).await?;
+        match case.state {
+            CaseState::Completed | CaseState::Cancelled => Ok(()),
+            CaseState::Created => self.execute_case(case_id).await,
+            CaseState::Running => {
+                let spec = self.get_workflow(case.spec_id).await?;
+                let mut marking = match self.markings.get(&case_id) {
+                    Some(marking) => marking.value().clone(),
+                    None => CaseMarking::initial(&spec)?,
+                };
+                marking.requeue_executing();
+                resume_workflow(self, case_id, &spec, marking, HashSet::new()).await
+            }
+            state => Err(WorkflowError::Validation(format!(
+                "Case {} is {} and cannot be redriven",
+                case_id, state
+            ))),
+        }
+    }
+
+    /// Replace the data of a case (stored and live)
+    pub(super) async fn replace_case_data(
+        &self,
+        case_id: CaseId,
+        data: &serde_json::Value,
+    ) -> WorkflowResult<()> {
+        let store_arc = self.state_store.read().await;
+        let mut case = match (*store_arc).load_case(&case_id)? {
+            Some(case) => case,
+            None => self.get_case(case_id).await?,
+        };
+        if case.data == *data {
+            return Ok(());
+        }
+        case.data = data.clone();
+        (*store_arc).save_case(case_id, &case)?;
+        if let Some(mut live) = self.cases.get_mut(&case_id) {
+            live.data = data.clone();
+        }
+        Ok(())
+    }
+}
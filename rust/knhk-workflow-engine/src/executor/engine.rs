@@ -8,12 +8,16 @@ use crate::integration::{
     ConnectorIntegration, LockchainIntegration, OtelIntegration, SidecarIntegration,
 };
 use crate::parser::{WorkflowSpec, WorkflowSpecId};
+use crate::patterns::multiple_instance::MultiInstanceState;
 use crate::patterns::PatternRegistry;
+use crate::resilience::DeadLetterQueue;
 use crate::resource::ResourceAllocator;
 use crate::security::AuthManager;
 use crate::services::timer::TimerService;
-use crate::services::{AdmissionGate, EventSidecar, WorkItemService};
+use crate::services::{AdmissionGate, EventSidecar, MessageCorrelator, WorkItemService};
+use crate::snapshots::SpecVersionRegistry;
 use crate::state::manager::StateManager;
+use crate::state::recovery::CaseMarking;
 #[cfg(feature = "storage")]
 use crate::state::StateStore;
 use crate::timebase::SysClock;
@@ -36,8 +40,14 @@ pub struct WorkflowEngine {
     pub(crate) state_manager: Arc<StateManager>,
     /// Registered workflow specifications (lock-free DashMap for concurrent access)
     pub(crate) specs: Arc<DashMap<WorkflowSpecId, WorkflowSpec>>,
+    /// Registered versions of spec families (Σ snapshots per family)
+    pub(crate) spec_versions: Arc<SpecVersionRegistry>,
     /// Active cases (lock-free DashMap for concurrent access)
     pub(crate) cases: Arc<DashMap<CaseId, Case>>,
+    /// Token markings of running cases (checkpointed for crash recovery)
+    pub(crate) markings: Arc<DashMap<CaseId, CaseMarking>>,
+    /// Running multiple instance tasks by case and task ID
+    pub(crate) mi_tasks: Arc<DashMap<(CaseId, String), MultiInstanceState>>,
     /// Resource allocator
     pub(crate) resource_allocator: Arc<ResourceAllocator>,
     /// Worklet repository
@@ -46,12 +56,16 @@ pub struct WorkflowEngine {
     pub(crate) worklet_executor: Arc<WorkletExecutor>,
     /// Timer service
     pub(crate) timer_service: Arc<TimerService<SysClock>>,
+    /// Dead letter queue of failed case executions (persisted in the state store)
+    pub(crate) dead_letter_queue: Arc<DeadLetterQueue>,
     /// Work item service
     pub(crate) work_item_service: Arc<WorkItemService>,
     /// Admission gate
     pub(crate) admission_gate: Arc<AdmissionGate>,
     /// Event sidecar
     pub(crate) event_sidecar: Arc<EventSidecar>,
+    /// Message correlation of message-catch and message-start tasks
+    pub(crate) message_correlator: Arc<MessageCorrelator>,
     /// Enterprise configuration
     pub(crate) enterprise_config: Option<Arc<EnterpriseConfig>>,
     /// Fortune 5 integration (if enabled)
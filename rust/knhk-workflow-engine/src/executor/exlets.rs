@@ -0,0 +1,330 @@
+//! Exlet execution on work item and case exceptions
+//!
+//! Exceptions are raised by the engine when a work item is enabled
+//! (pre-constraints), completed (post-constraints) or overdue (timeout), and
+//! from outside the engine with `resilience::YawlException`. The exception rule
+//! trees of the worklet repository select the exlet; its steps run in order and
+//! each one is recorded in the case history. Handling stops at the first failed
+//! step.
+
+use crate::case::{Case, CaseId, CaseState};
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::resilience::YawlException;
+use crate::services::work_items::WorkItemState;
+use crate::worklets::{
+    ExceptionContext, ExceptionKind, ExletPrimitive, ExletReport, ExletStepRecord,
+};
+use std::collections::HashMap;
+
+use super::WorkflowEngine;
+
+impl WorkflowEngine {
+    /// Raise an exception for a case or one of its work items
+    ///
+    /// Exception rules are evaluated against `data` (the work item data for
+    /// item exceptions). Returns `None` when no rule concludes with an exlet;
+    /// constraint checks are then not recorded at all, other exceptions are
+    /// recorded as unhandled. Fails if an exlet step fails.
+    pub async fn raise_exception(
+        &self,
+        case_id: CaseId,
+        task_id: Option<&str>,
+        work_item_id: Option<&str>,
+        kind: ExceptionKind,
+        data: serde_json::Value,
+    ) -> WorkflowResult<Option<ExletReport>> {
+        let context = ExceptionContext {
+            exception_type: kind.as_str().to_string(),
+            task_id: task_id.unwrap_or_default().to_string(),
+            case_id: case_id.to_string(),
+            data,
+            metadata: HashMap::new(),
+        };
+        self.handle_exception(case_id, work_item_id, kind, &context)
+            .await
+    }
+
+    /// Raise an external exception for its case
+    ///
+    /// Rules see the case data overlaid with the exception context, and the
+    /// exception `category`, `severity` and `message` as metadata. If the
+    /// exception names a task, item primitives act on its open work item.
+    pub async fn raise_external_exception(
+        &self,
+        exception: &YawlException,
+    ) -> WorkflowResult<Option<ExletReport>> {
+        let case_id = exception.case_id.as_deref().ok_or_else(|| {
+            WorkflowError::Validation(format!(
+                "External exception '{}' does not name a case",
+                exception.message
+            ))
+        })?;
+        let case_id = CaseId::parse_str(case_id)?;
+
+        let mut data = self.get_case(case_id).await?.data;
+        if let (Some(data), Some(context)) = (data.as_object_mut(), exception.context.as_object()) {
+            for (key, value) in context {
+                data.insert(key.clone(), value.clone());
+            }
+        }
+        let work_item_id = match exception.task_id {
+            Some(ref task_id) => self
+                .work_item_service
+                .find_open_work_item(&case_id.to_string(), task_id)
+                .await
+                .map(|item| item.id),
+            None => None,
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("category".to_string(), format!("{:?}", exception.category));
+        metadata.insert("severity".to_string(), format!("{:?}", exception.severity));
+        metadata.insert("message".to_string(), exception.message.clone());
+        let context = ExceptionContext {
+            exception_type: ExceptionKind::External.as_str().to_string(),
+            task_id: exception.task_id.clone().unwrap_or_default(),
+            case_id: case_id.to_string(),
+            data,
+            metadata,
+        };
+        self.handle_exception(
+            case_id,
+            work_item_id.as_deref(),
+            ExceptionKind::External,
+            &context,
+        )
+        .await
+    }
+
+    /// Select and run the exlet handling an exception
+    async fn handle_exception(
+        &self,
+        case_id: CaseId,
+        work_item_id: Option<&str>,
+        kind: ExceptionKind,
+        context: &ExceptionContext,
+    ) -> WorkflowResult<Option<ExletReport>> {
+        let selected = self.worklet_repository.select_exlet(kind, context).await?;
+        if selected.is_none() && kind.is_constraint() {
+            return Ok(None);
+        }
+
+        let task_id = (!context.task_id.is_empty()).then(|| context.task_id.clone());
+        self.state_manager
+            .log_exception_raised(
+                case_id,
+                task_id.clone(),
+                work_item_id.map(str::to_string),
+                kind.to_string(),
+                selected
+                    .as_ref()
+                    .map(|(rule_id, exlet)| (rule_id.clone(), exlet.name.clone())),
+            )
+            .await?;
+        let (rule_id, exlet) = match selected {
+            Some(selected) => selected,
+            None => {
+                tracing::warn!("Unhandled {} exception for case {}", kind, case_id);
+                return Ok(None);
+            }
+        };
+
+        let mut steps = Vec::with_capacity(exlet.steps.len());
+        for step in &exlet.steps {
+            let outcome = self.run_exlet_step(case_id, work_item_id, step).await;
+            let (success, detail) = match outcome {
+                Ok(ref detail) => (true, detail.clone()),
+                Err(ref e) => (false, Some(e.to_string())),
+            };
+            self.state_manager
+                .log_exlet_step(
+                    case_id,
+                    task_id.clone(),
+                    exlet.name.clone(),
+                    step.to_string(),
+                    success,
+                    detail.clone(),
+                )
+                .await?;
+            steps.push(ExletStepRecord {
+                step: step.clone(),
+                success,
+                detail,
+            });
+            if let Err(e) = outcome {
+                return Err(WorkflowError::ExceptionHandlingFailed(format!(
+                    "Exlet '{}' failed at step {} for case {}: {}",
+                    exlet.name, step, case_id, e
+                )));
+            }
+        }
+
+        Ok(Some(ExletReport {
+            kind,
+            rule_id,
+            exlet: exlet.name.clone(),
+            steps,
+            resolution: exlet.resolution(),
+        }))
+    }
+
+    /// Run one exlet primitive, returning its detail for the case history
+    async fn run_exlet_step(
+        &self,
+        case_id: CaseId,
+        work_item_id: Option<&str>,
+        step: &ExletPrimitive,
+    ) -> WorkflowResult<Option<String>> {
+        match step {
+            ExletPrimitive::SuspendCase => {
+                self.change_case_state(case_id, Case::suspend).await?;
+                Ok(None)
+            }
+            ExletPrimitive::ContinueCase => {
+                self.change_case_state(case_id, Case::resume).await?;
+                Ok(None)
+            }
+            ExletPrimitive::FailCase => {
+                self.change_case_state(case_id, |case| {
+                    case.fail("Failed by exception handling".to_string())
+                })
+                .await?;
+                Ok(None)
+            }
+            ExletPrimitive::CancelCase => {
+                self.cancel_case(case_id).await?;
+                Ok(None)
+            }
+            ExletPrimitive::Compensate { worklet } => {
+                let compensation = self.run_compensation(case_id, worklet).await?;
+                Ok(Some(format!("compensation case {}", compensation)))
+            }
+            item_step => {
+                let work_item_id = work_item_id.ok_or_else(|| {
+                    WorkflowError::Validation(format!(
+                        "Exlet step {} needs a work item, none is open for case {}",
+                        item_step, case_id
+                    ))
+                })?;
+                let work_item = self
+                    .work_item_service
+                    .get_work_item(work_item_id)
+                    .await
+                    .ok_or_else(|| {
+                        WorkflowError::ResourceUnavailable(format!(
+                            "Work item {} not found",
+                            work_item_id
+                        ))
+                    })?;
+                // Post-constraints are checked on completed work items
+                if matches!(
+                    work_item.state,
+                    WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+                ) {
+                    return Ok(Some(format!(
+                        "work item {} already {:?}",
+                        work_item_id, work_item.state
+                    )));
+                }
+                let service = &self.work_item_service;
+                match item_step {
+                    ExletPrimitive::SuspendItem => service.suspend(work_item_id).await?,
+                    ExletPrimitive::ContinueItem => service.unsuspend(work_item_id).await?,
+                    ExletPrimitive::ForceCompleteItem => {
+                        service.force_complete(work_item_id).await?
+                    }
+                    ExletPrimitive::RestartItem => service.restart(work_item_id).await?,
+                    ExletPrimitive::FailItem => service.fail(work_item_id).await?,
+                    ExletPrimitive::CancelItem => service.cancel(work_item_id).await?,
+                    case_step => {
+                        return Err(WorkflowError::Internal(format!(
+                            "Exlet step {} does not act on a work item",
+                            case_step
+                        )))
+                    }
+                }
+                Ok(Some(format!("work item {}", work_item_id)))
+            }
+        }
+    }
+
+    /// Apply a state transition to a case and persist it
+    async fn change_case_state(
+        &self,
+        case_id: CaseId,
+        transition: impl FnOnce(&mut Case) -> WorkflowResult<()>,
+    ) -> WorkflowResult<()> {
+        let mut case_guard = self
+            .cases
+            .get_mut(&case_id)
+            .ok_or_else(|| WorkflowError::CaseNotFound(case_id.to_string()))?;
+
+        transition(case_guard.value_mut())?;
+
+        // Persist state (clone before dropping guard)
+        let case_clone = case_guard.value().clone();
+        drop(case_guard);
+        let store_arc = self.state_store.read().await;
+        (*store_arc).save_case(case_id, &case_clone)?;
+        drop(store_arc);
+
+        // Save to state manager for event sourcing (will emit CaseStateChanged event)
+        self.state_manager.save_case(&case_clone).await
+    }
+
+    /// Run a compensating worklet as a case on the case data
+    ///
+    /// The worklet's specification is registered on first use. The compensation
+    /// case must complete; its data is then merged back into the case.
+    async fn run_compensation(&self, case_id: CaseId, worklet: &str) -> WorkflowResult<CaseId> {
+        let worklet_id = self
+            .worklet_repository
+            .find_by_name(worklet)
+            .await
+            .ok_or_else(|| {
+                WorkflowError::ResourceUnavailable(format!("Worklet {} not found", worklet))
+            })?;
+        let worklet = self.worklet_repository.get(worklet_id).await?;
+        let spec_id = worklet.workflow_spec.id;
+        if !self.specs.contains_key(&spec_id) {
+            self.register_workflow(worklet.workflow_spec).await?;
+        }
+
+        let case = self.get_case(case_id).await?;
+        let compensation_id = self.create_case(spec_id, case.data).await?;
+        self.execute_case(compensation_id).await?;
+        let compensation = self.get_case(compensation_id).await?;
+        if compensation.state != CaseState::Completed {
+            return Err(WorkflowError::TaskExecutionFailed(format!(
+                "Compensation case {} of worklet {} {}",
+                compensation_id, worklet.metadata.name, compensation.state
+            )));
+        }
+
+        // Map the compensation results back into the case
+        let stored = {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).load_case(&case_id)?
+        };
+        let mut case = match stored {
+            Some(case) => case,
+            None => self.get_case(case_id).await?,
+        };
+        if let (Some(data), Some(results)) =
+            (case.data.as_object_mut(), compensation.data.as_object())
+        {
+            for (key, value) in results {
+                data.insert(key.clone(), value.clone());
+            }
+        }
+        {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).save_case(case_id, &case)?;
+        }
+        if let Some(mut live) = self.cases.get_mut(&case_id) {
+            live.data = case.data;
+        }
+
+        Ok(compensation_id)
+    }
+}
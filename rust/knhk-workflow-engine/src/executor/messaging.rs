@@ -0,0 +1,240 @@
+//! Message-catch and message-start tasks
+//!
+//! An atomic task with a `message` catch is suspended until a message with
+//! the expected name arrives whose correlation values equal the task's
+//! correlation expressions evaluated over the case data. Messages that
+//! arrive before the task waits for them are buffered by the
+//! [`MessageCorrelator`](crate::services::MessageCorrelator); the payload of
+//! the received message is merged into the case data like a task result.
+//!
+//! A message-start task (directly after the start condition) instantiates a
+//! case of the latest version of its spec when a published message is not
+//! awaited by a running case; the message is handed to the new case's
+//! message-start task.
+
+use crate::case::CaseId;
+use crate::data::expression::Expr;
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{Task, TaskType, WorkflowSpec, WorkflowSpecId};
+use crate::services::messages::correlation_value;
+use crate::services::{CorrelatedMessage, MessageDelivery, MessageWait};
+use crate::snapshots::compare_versions;
+use std::collections::{BTreeMap, HashMap};
+
+use super::task::merge_task_result;
+use super::WorkflowEngine;
+
+impl WorkflowEngine {
+    /// Publish a message to running cases
+    ///
+    /// The message goes to the oldest waiting message-catch task it matches;
+    /// otherwise it starts a case of every spec with a matching message-start
+    /// task; otherwise it is buffered until a matching task waits for it.
+    pub async fn publish_message(
+        &self,
+        message: CorrelatedMessage,
+    ) -> WorkflowResult<MessageDelivery> {
+        if message.name.trim().is_empty() {
+            return Err(WorkflowError::Validation(
+                "Message name must not be empty".to_string(),
+            ));
+        }
+
+        if let Some((case_id, task_id)) = self.message_correlator.deliver(message.clone()).await {
+            tracing::debug!(
+                "Message {} ({}) delivered to task {} of case {}",
+                message.id,
+                message.name,
+                task_id,
+                case_id
+            );
+            return Ok(MessageDelivery::Delivered { case_id, task_id });
+        }
+
+        let starts = self.message_start_tasks(&message.name);
+        if starts.is_empty() {
+            return self.message_correlator.deliver_or_buffer(message).await;
+        }
+
+        let mut case_ids = Vec::with_capacity(starts.len());
+        let mut spec_ids = Vec::with_capacity(starts.len());
+        for (spec_id, task_id) in starts {
+            let case_id = self.create_case(spec_id, message.payload.clone()).await?;
+            self.message_correlator
+                .reserve(case_id, task_id, message.clone())
+                .await;
+            let engine = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = engine.execute_case(case_id).await {
+                    tracing::warn!("Message-started case {} failed: {}", case_id, e);
+                }
+            });
+            case_ids.push(case_id);
+            spec_ids.push(spec_id);
+        }
+        Ok(MessageDelivery::Started { case_ids, spec_ids })
+    }
+
+    /// Message-start tasks for a message, in the latest version of each spec
+    fn message_start_tasks(&self, message: &str) -> Vec<(WorkflowSpecId, String)> {
+        let mut latest: HashMap<String, WorkflowSpec> = HashMap::new();
+        for entry in self.specs.iter() {
+            let spec = entry.value();
+            let family = spec.iri.clone().unwrap_or_else(|| spec.id.to_string());
+            let newer = !latest.get(&family).is_some_and(|current| {
+                compare_versions(current.version.as_deref(), spec.version.as_deref()).is_ge()
+            });
+            if newer {
+                latest.insert(family, spec.clone());
+            }
+        }
+
+        let mut starts: Vec<(WorkflowSpecId, String)> = latest
+            .values()
+            .flat_map(|spec| {
+                spec.tasks
+                    .values()
+                    .filter(|task| {
+                        task.message
+                            .as_ref()
+                            .is_some_and(|catch| catch.starts_case && catch.message == message)
+                    })
+                    .map(|task| (spec.id, task.id.clone()))
+            })
+            .collect();
+        starts.sort_by_key(|(spec_id, task_id)| (spec_id.to_string(), task_id.clone()));
+        starts
+    }
+}
+
+/// Execute a message-catch task: wait for its correlated message
+pub(super) async fn execute_message_catch(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    task: &Task,
+) -> WorkflowResult<()> {
+    let catch = task.message.as_ref().ok_or_else(|| {
+        WorkflowError::TaskExecutionFailed(format!(
+            "Task {} does not catch a message (yawl:catchesMessage)",
+            task.id
+        ))
+    })?;
+
+    // A message-start task receives the message that instantiated the case
+    let reserved = if catch.starts_case {
+        engine
+            .message_correlator
+            .take_reserved(case_id, &task.id)
+            .await
+    } else {
+        None
+    };
+    let message = match reserved {
+        Some(message) => message,
+        None => {
+            let case = engine.get_case(case_id).await?;
+            let mut correlation = BTreeMap::new();
+            for (key, expression) in &catch.correlation {
+                let value = Expr::parse(expression)?.evaluate(&case.data).map_err(|e| {
+                    WorkflowError::TaskExecutionFailed(format!(
+                        "Task {} cannot evaluate correlation key {}: {}",
+                        task.id, key, e
+                    ))
+                })?;
+                correlation.insert(key.clone(), correlation_value(&value));
+            }
+
+            let wait = engine
+                .message_correlator
+                .subscribe(case_id, task.id.clone(), catch.message.clone(), correlation)
+                .await;
+            match wait {
+                MessageWait::Ready(message) => message,
+                MessageWait::Waiting(receiver) => receiver.await.map_err(|_| {
+                    WorkflowError::TaskExecutionFailed(format!(
+                        "Case {} was cancelled while task {} was waiting for message {}",
+                        case_id, task.id, catch.message
+                    ))
+                })?,
+            }
+        }
+    };
+    tracing::debug!(
+        "Task {} of case {} received message {} ({})",
+        task.id,
+        case_id,
+        message.id,
+        message.name
+    );
+
+    // Merge the message payload into the case data
+    let stored = {
+        let store_arc = engine.state_store.read().await;
+        (*store_arc).load_case(&case_id)?
+    };
+    let mut case = match stored {
+        Some(case) => case,
+        None => engine.get_case(case_id).await?,
+    };
+    merge_task_result(task, &mut case.data, &message.payload)?;
+    {
+        let store_arc = engine.state_store.read().await;
+        (*store_arc).save_case(case_id, &case)?;
+    }
+    if let Some(mut live) = engine.cases.get_mut(&case_id) {
+        live.data = case.data.clone();
+    }
+
+    Ok(())
+}
+
+/// Check the message catches of a spec
+///
+/// Catches need a message name and parseable correlation expressions, are
+/// only allowed on atomic tasks, and message starts must directly follow the
+/// start condition.
+pub(super) fn validate_message_catches(spec: &WorkflowSpec) -> WorkflowResult<()> {
+    for task in spec.tasks.values() {
+        let catch = match task.message {
+            Some(ref catch) => catch,
+            None => continue,
+        };
+        if catch.message.trim().is_empty() {
+            return Err(WorkflowError::Validation(format!(
+                "Task {} catches a message without a name",
+                task.id
+            )));
+        }
+        if task.task_type != TaskType::Atomic {
+            return Err(WorkflowError::Validation(format!(
+                "Task {} catches message {} but is not an atomic task",
+                task.id, catch.message
+            )));
+        }
+        for (key, expression) in &catch.correlation {
+            Expr::parse(expression).map_err(|e| {
+                WorkflowError::Validation(format!(
+                    "Task {} has an invalid correlation expression for {}: {}",
+                    task.id, key, e
+                ))
+            })?;
+        }
+        if catch.starts_case {
+            let follows_start = spec.start_condition.as_ref().is_some_and(|start| {
+                task.incoming_flows.contains(start)
+                    || task.input_conditions.contains(start)
+                    || spec
+                        .flows
+                        .iter()
+                        .any(|f| &f.from == start && f.to == task.id)
+            });
+            if !follows_start {
+                return Err(WorkflowError::Validation(format!(
+                    "Message-start task {} must directly follow the start condition",
+                    task.id
+                )));
+            }
+        }
+    }
+    Ok(())
+}
@@ -12,6 +12,15 @@
 //! - `accessors.rs`: Getter methods for engine components
 //! - `fortune5.rs`: Fortune 5 integration methods
 //! - `rdf_query.rs`: Runtime RDF query API
+//! - `recovery.rs`: Crash recovery of in-flight cases from the case history
+//! - `multi_instance.rs`: Multiple instance tasks (instance splitting, thresholds, aggregation)
+//! - `cancellation.rs`: Cancellation regions applied on task completion
+//! - `composite.rs`: Composite tasks running versioned sub-nets as child cases
+//! - `versioning.rs`: Spec versions registered side by side and live case migration
+//! - `dead_letters.rs`: Failed case executions dead-lettered and redriven
+//! - `retention.rs`: Retention sweeps and subject erasure with signed reports
+//! - `messaging.rs`: Message-catch and message-start tasks correlated on case data
+//! - `exlets.rs`: Exlets run on work item constraint violations, timeouts and external exceptions
 //!
 //! # New Self-Executing Workflow Components (Covenant 1)
 //!
@@ -24,18 +33,27 @@
 //! - `net_runner.rs`: YAWL NetRunner port (workflow net execution)
 
 mod accessors;
+mod cancellation;
 mod case;
+mod composite;
 mod construction;
+mod dead_letters;
 mod engine;
 mod events;
+mod exlets;
 mod fortune5;
 mod loader;
+mod messaging;
+mod multi_instance;
 mod pattern;
 mod provenance;
 mod rdf_query;
+mod recovery;
+mod retention;
 mod runtime;
 mod task;
 mod telemetry;
+mod versioning;
 mod workflow_execution;
 mod workflow_query;
 mod workflow_registration;
@@ -48,3 +66,4 @@ pub use loader::{
 };
 pub use runtime::{ExecutionState, TaskExecutor, TaskResult, WorkflowRuntime, WorkflowState};
 pub use telemetry::{TaskEvent, WorkflowEvent, WorkflowTelemetry};
+pub use versioning::MigrationReport;
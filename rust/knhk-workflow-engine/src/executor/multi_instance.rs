@@ -0,0 +1,429 @@
+//! Multiple instance task execution (patterns 12-15 and MI partial joins)
+//!
+//! Instances run concurrently, each with its own input split from case data.
+//! Dynamic tasks accept new instances through `WorkflowEngine::add_mi_instance`
+//! until closed with `WorkflowEngine::close_mi_task`. Once the threshold is
+//! reached, instances still running are cancelled (including their work items)
+//! and the instance outputs are aggregated into case data.
+
+use crate::case::{CaseId, CaseState};
+use crate::data::contract::TaskDataContract;
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{Task, WorkflowSpecId};
+use crate::patterns::multiple_instance::{MultiInstanceSpec, MultiInstanceState};
+use serde_json::Value;
+use tokio::task::JoinSet;
+use tokio::time::Duration;
+
+use super::task::merge_task_result;
+use super::WorkflowEngine;
+
+/// Poll interval for dynamically added instances and human work items
+const MI_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Maximum wait for a human instance work item (100 x 100ms)
+const MI_WORK_ITEM_POLLS: usize = 100;
+
+impl WorkflowEngine {
+    /// Add an instance to a running dynamic multiple instance task (pattern 15)
+    ///
+    /// The instance receives the current case data plus `item` under the task's
+    /// instance variable. Returns the new instance index.
+    pub async fn add_mi_instance(
+        &self,
+        case_id: CaseId,
+        task_id: &str,
+        item: Value,
+    ) -> WorkflowResult<usize> {
+        let case = self.get_case(case_id).await?;
+        let mut state = self
+            .mi_tasks
+            .get_mut(&(case_id, task_id.to_string()))
+            .ok_or_else(|| not_running(case_id, task_id))?;
+        state.add_instance(&case.data, item)
+    }
+
+    /// Stop accepting new instances for a running dynamic multiple instance task
+    ///
+    /// The task completes once all created instances have finished (or the
+    /// threshold is reached).
+    pub fn close_mi_task(&self, case_id: CaseId, task_id: &str) -> WorkflowResult<()> {
+        let mut state = self
+            .mi_tasks
+            .get_mut(&(case_id, task_id.to_string()))
+            .ok_or_else(|| not_running(case_id, task_id))?;
+        state.close();
+        Ok(())
+    }
+
+    /// Get a snapshot of a running multiple instance task
+    pub fn mi_task_state(&self, case_id: CaseId, task_id: &str) -> Option<MultiInstanceState> {
+        self.mi_tasks
+            .get(&(case_id, task_id.to_string()))
+            .map(|state| state.value().clone())
+    }
+}
+
+fn not_running(case_id: CaseId, task_id: &str) -> WorkflowError {
+    WorkflowError::Validation(format!(
+        "No running multiple instance task {} in case {}",
+        task_id, case_id
+    ))
+}
+
+/// Execute a multiple instance task
+pub(super) async fn execute_multi_instance_task(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    spec_id: WorkflowSpecId,
+    task: &Task,
+) -> WorkflowResult<()> {
+    let case = engine.get_case(case_id).await?;
+    let mi_spec = match task.multi_instance {
+        Some(ref mi_spec) => mi_spec.clone(),
+        None => legacy_spec(task, &case.data)?,
+    };
+    let synchronize = mi_spec.synchronize;
+    let state = MultiInstanceState::start(&task.id, mi_spec, &case.data)?;
+    let key = (case_id, task.id.clone());
+    engine.mi_tasks.insert(key.clone(), state);
+
+    if !synchronize {
+        // Pattern 12: instances run on their own, downstream tasks proceed immediately
+        let engine = engine.clone();
+        let task = task.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_instances(&engine, case_id, spec_id, &task).await {
+                tracing::warn!("Unsynchronized MI task {} failed: {}", task.id, e);
+            }
+            engine.mi_tasks.remove(&(case_id, task.id.clone()));
+        });
+        return Ok(());
+    }
+
+    let result = run_instances(engine, case_id, spec_id, task).await;
+    let state = engine.mi_tasks.remove(&key).map(|(_, state)| state);
+    result?;
+    let state = state.ok_or_else(|| not_running(case_id, &task.id))?;
+
+    // Aggregate instance outputs into case data
+    let contract = TaskDataContract::for_task(task)?;
+    let stored = {
+        let store_arc = engine.state_store.read().await;
+        (*store_arc).load_case(&case_id)?
+    };
+    let mut case = match stored {
+        Some(case) => case,
+        None => engine.get_case(case_id).await?,
+    };
+    match state.spec.aggregate_into {
+        Some(ref variable) => {
+            let mut outputs = Vec::new();
+            for output in state.outputs() {
+                if contract.outputs.is_empty() {
+                    outputs.push(output);
+                } else {
+                    outputs.push(Value::Object(contract.map_outputs(&output)?));
+                }
+            }
+            if let Some(case_obj) = case.data.as_object_mut() {
+                case_obj.insert(variable.clone(), Value::Array(outputs));
+            }
+        }
+        None => {
+            for output in state.outputs() {
+                merge_task_result(task, &mut case.data, &output)?;
+            }
+        }
+    }
+    if let Some(case_obj) = case.data.as_object_mut() {
+        case_obj.insert(
+            "mi_completed_count".to_string(),
+            Value::from(state.completed_count()),
+        );
+    }
+
+    {
+        let store_arc = engine.state_store.read().await;
+        (*store_arc).save_case(case_id, &case)?;
+    }
+    // Keep the live case in sync so downstream predicates see the aggregated outputs
+    if let Some(mut live) = engine.cases.get_mut(&case_id) {
+        live.data = case.data.clone();
+    }
+
+    tracing::debug!(
+        "Multiple instance task {} completed {}/{} instances (pattern {})",
+        task.id,
+        state.completed_count(),
+        state.instances.len(),
+        state.spec.pattern_id().0
+    );
+
+    Ok(())
+}
+
+/// Configuration from the legacy `instance_count`/`mi_threshold` case variables
+fn legacy_spec(task: &Task, case_data: &Value) -> WorkflowResult<MultiInstanceSpec> {
+    let read = |key: &str| {
+        case_data.get(key).and_then(|v| {
+            v.as_u64()
+                .map(|n| n as usize)
+                .or_else(|| v.as_str().and_then(|s| s.parse::<usize>().ok()))
+        })
+    };
+    let instance_count = read("instance_count").unwrap_or(1);
+
+    // Guard constraint: max_run_len ≤ 8
+    if instance_count > 8 {
+        return Err(WorkflowError::GuardViolation(format!(
+            "Multiple instance task {} has instance_count {} which exceeds max_run_len 8",
+            task.id, instance_count
+        )));
+    }
+    let threshold = read("mi_threshold");
+    if threshold.is_some_and(|t| t > instance_count) {
+        return Err(WorkflowError::GuardViolation(format!(
+            "MI task {} has threshold {:?} which exceeds instance_count {}",
+            task.id, threshold, instance_count
+        )));
+    }
+
+    Ok(MultiInstanceSpec {
+        minimum: instance_count,
+        maximum: Some(instance_count),
+        threshold,
+        ..MultiInstanceSpec::default()
+    })
+}
+
+/// Run instances until the task completes or can no longer complete
+async fn run_instances(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    spec_id: WorkflowSpecId,
+    task: &Task,
+) -> WorkflowResult<()> {
+    let key = (case_id, task.id.clone());
+    let mut running: JoinSet<(usize, WorkflowResult<Value>)> = JoinSet::new();
+
+    loop {
+        // Launch created instances (initial and dynamically added)
+        let pending = engine
+            .mi_tasks
+            .get_mut(&key)
+            .map(|mut state| state.take_pending())
+            .ok_or_else(|| not_running(case_id, &task.id))?;
+        for (index, input) in pending {
+            let engine = engine.clone();
+            let task = task.clone();
+            running.spawn(async move {
+                let result = run_instance(&engine, case_id, spec_id, &task, index, input).await;
+                (index, result)
+            });
+        }
+
+        let (complete, failed) = engine
+            .mi_tasks
+            .get(&key)
+            .map(|state| (state.is_complete(), state.is_failed()))
+            .ok_or_else(|| not_running(case_id, &task.id))?;
+        if complete {
+            break;
+        }
+        if failed {
+            running.abort_all();
+            let errors: Vec<String> = engine
+                .mi_tasks
+                .get(&key)
+                .map(|state| {
+                    state
+                        .instances
+                        .iter()
+                        .filter_map(|i| i.error.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            cancel_instances(engine, case_id, task).await;
+            return Err(WorkflowError::TaskExecutionFailed(format!(
+                "MI task {} can no longer reach its completion threshold: {}",
+                task.id,
+                errors.join("; ")
+            )));
+        }
+
+        if running.is_empty() {
+            // Open dynamic task: wait for instances to be added or the task to be closed
+            let cancelled = engine
+                .cases
+                .get(&case_id)
+                .is_some_and(|case| case.state == CaseState::Cancelled);
+            if cancelled {
+                cancel_instances(engine, case_id, task).await;
+                return Err(WorkflowError::TaskExecutionFailed(format!(
+                    "Case {} was cancelled while MI task {} was waiting for instances",
+                    case_id, task.id
+                )));
+            }
+            tokio::time::sleep(MI_POLL_INTERVAL).await;
+            continue;
+        }
+
+        tokio::select! {
+            joined = running.join_next() => {
+                if let Some(joined) = joined {
+                    let (index, result) = joined.map_err(|e| {
+                        WorkflowError::TaskExecutionFailed(format!(
+                            "MI task {} instance panicked: {}",
+                            task.id, e
+                        ))
+                    })?;
+                    if let Some(mut state) = engine.mi_tasks.get_mut(&key) {
+                        state.finish_instance(index, result.map_err(|e| e.to_string()));
+                    }
+                }
+            }
+            // Pick up dynamically added instances while others are running
+            _ = tokio::time::sleep(MI_POLL_INTERVAL) => {}
+        }
+    }
+
+    // Threshold reached: cancel the remaining instances (cancelling partial join)
+    // or let them run to completion with their outputs ignored (static partial join)
+    let cancel_remaining = engine
+        .mi_tasks
+        .get(&key)
+        .map(|state| state.spec.cancel_remaining)
+        .unwrap_or(true);
+    if cancel_remaining {
+        running.abort_all();
+        cancel_instances(engine, case_id, task).await;
+    } else {
+        running.detach_all();
+        if let Some(mut state) = engine.mi_tasks.get_mut(&key) {
+            state.close();
+        }
+    }
+
+    Ok(())
+}
+
+/// Cancel unfinished instances and their open work items
+async fn cancel_instances(engine: &WorkflowEngine, case_id: CaseId, task: &Task) {
+    let cancelled = match engine.mi_tasks.get_mut(&(case_id, task.id.clone())) {
+        Some(mut state) => state.cancel_remaining(),
+        None => return,
+    };
+    for index in cancelled {
+        let work_item = engine
+            .work_item_service
+            .find_open_work_item(&case_id.to_string(), &instance_task_id(task, index))
+            .await;
+        if let Some(work_item) = work_item {
+            if let Err(e) = engine.work_item_service.cancel(&work_item.id).await {
+                tracing::warn!("Failed to cancel MI work item {}: {}", work_item.id, e);
+            }
+        }
+        tracing::debug!("Cancelled MI task {} instance {}", task.id, index);
+    }
+}
+
+fn instance_task_id(task: &Task, index: usize) -> String {
+    format!("{}_{}", task.id, index)
+}
+
+/// Execute a single instance and return its output
+async fn run_instance(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    spec_id: WorkflowSpecId,
+    task: &Task,
+    index: usize,
+    input: Value,
+) -> WorkflowResult<Value> {
+    tracing::debug!("Executing MI task {} instance {}", task.id, index);
+
+    if !task.required_roles.is_empty() {
+        // Human task: one work item per instance
+        let contract = TaskDataContract::for_task(task)?;
+        let data = if contract.inputs.is_empty() {
+            input
+        } else {
+            let mut bound = contract.bind_inputs(&input)?;
+            if let (Some(bound_obj), Some(instance_var)) = (
+                bound.as_object_mut(),
+                task.multi_instance
+                    .as_ref()
+                    .map(|mi| mi.instance_variable.clone()),
+            ) {
+                if let Some(item) = input.get(&instance_var) {
+                    bound_obj.insert(instance_var, item.clone());
+                }
+            }
+            bound
+        };
+        let output_contract = if contract.outputs.is_empty() {
+            None
+        } else {
+            Some(contract)
+        };
+        let work_item_id = engine
+            .work_item_service
+            .create_work_item_with_contract(
+                case_id.to_string(),
+                spec_id,
+                instance_task_id(task, index),
+                data,
+                output_contract,
+            )
+            .await?;
+
+        for _ in 0..MI_WORK_ITEM_POLLS {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let work_item = engine
+                .work_item_service
+                .get_work_item(&work_item_id)
+                .await
+                .ok_or_else(|| {
+                    WorkflowError::TaskExecutionFailed(format!(
+                        "Work item {} not found",
+                        work_item_id
+                    ))
+                })?;
+            match work_item.state {
+                crate::services::work_items::WorkItemState::Completed => {
+                    return Ok(work_item.data);
+                }
+                crate::services::work_items::WorkItemState::Cancelled
+                | crate::services::work_items::WorkItemState::Failed => {
+                    let state = format!("{:?}", work_item.state).to_lowercase();
+                    return Err(WorkflowError::TaskExecutionFailed(format!(
+                        "Work item {} for MI instance {} was {}",
+                        work_item_id, index, state
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        return Err(WorkflowError::TaskExecutionFailed(format!(
+            "Work item {} for MI instance {} did not complete within timeout",
+            work_item_id, index
+        )));
+    }
+
+    // Automated task: execute via connector
+    let connector_integration = engine.connector_integration.as_ref().ok_or_else(|| {
+        WorkflowError::TaskExecutionFailed(format!(
+            "Automated MI task instance {} requires connector integration",
+            index
+        ))
+    })?;
+    let connector = connector_integration.lock().await;
+    connector.execute_task(&task.id, input).await.map_err(|e| {
+        WorkflowError::TaskExecutionFailed(format!(
+            "Connector execution failed for MI instance {}: {}",
+            index, e
+        ))
+    })
+}
@@ -0,0 +1,218 @@
+//! Crash recovery of in-flight cases
+//!
+//! Rebuilds every non-terminal case from the event-sourced case history
+//! (see `state::recovery`) and resumes running cases from their last checkpoint.
+
+use crate::case::{Case, CaseId, CaseState};
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::state::recovery::{
+    CaseCheckpoint, CaseMarking, CaseReplay, RecoveredCase, RecoveryOutcome, RecoveryReport,
+};
+use std::collections::HashSet;
+use std::time::Instant;
+
+use super::workflow_execution::resume_workflow;
+use super::WorkflowEngine;
+
+impl WorkflowEngine {
+    /// Persist a checkpoint of a running case (marking, data, work items, timers)
+    ///
+    /// No-op for cases without a marking (not executing).
+    pub(crate) async fn checkpoint_case(&self, case_id: CaseId) -> WorkflowResult<()> {
+        let marking = match self.markings.get(&case_id) {
+            Some(entry) => entry.value().clone(),
+            None => return Ok(()),
+        };
+
+        // Tasks write case data to the state store, so prefer the stored record
+        let stored = {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).load_case(&case_id)?
+        };
+        let case = match stored {
+            Some(case) => case,
+            None => self.get_case(case_id).await?,
+        };
+
+        let case_key = case_id.to_string();
+        let checkpoint = CaseCheckpoint {
+            case,
+            marking,
+            work_items: self.work_item_service.list_case_work_items(&case_key).await,
+            timers: self.timer_service.case_timers(&case_key).await,
+        };
+        self.state_manager.log_checkpoint(checkpoint).await
+    }
+
+    /// Recover in-flight cases after a restart
+    ///
+    /// Scans all persisted cases, skips terminal ones and cases already live in
+    /// this engine, and rebuilds the rest from their case history:
+    /// - Running cases resume from their last checkpoint. Tasks completed after the
+    ///   checkpoint are routed without re-execution; tasks started but not completed
+    ///   are re-executed (human tasks re-attach to their open work item).
+    /// - Created and suspended cases are restored without resuming.
+    ///
+    /// Call once after constructing the engine. Safe to call repeatedly.
+    pub async fn recover_cases(&self) -> WorkflowResult<RecoveryReport> {
+        let start_time = Instant::now();
+        let stored_cases = {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).load_all_cases()?
+        };
+
+        let mut report = RecoveryReport {
+            scanned: stored_cases.len(),
+            ..RecoveryReport::default()
+        };
+
+        for case in stored_cases {
+            if self.cases.contains_key(&case.id) {
+                report.skipped_live += 1;
+                continue;
+            }
+
+            let history = {
+                let store_arc = self.state_store.read().await;
+                (*store_arc).load_case_history(&case.id)?
+            };
+            let replay = CaseReplay::from_events(&history);
+
+            if is_terminal(case.state) || replay.is_terminal() {
+                report.skipped_terminal += 1;
+                continue;
+            }
+
+            let case_id = case.id;
+            let recovered = match self.recover_case(case, replay).await {
+                Ok(recovered) => recovered,
+                Err(e) => {
+                    tracing::warn!("Failed to recover case {}: {}", case_id, e);
+                    RecoveredCase {
+                        case_id,
+                        state: self
+                            .cases
+                            .get(&case_id)
+                            .map(|c| c.state)
+                            .unwrap_or(CaseState::Failed),
+                        outcome: RecoveryOutcome::Failed(e.to_string()),
+                        from_checkpoint: false,
+                        reexecuted_tasks: Vec::new(),
+                        deduplicated_tasks: Vec::new(),
+                        restored_work_items: 0,
+                        restored_timers: 0,
+                    }
+                }
+            };
+            if let RecoveryOutcome::Failed(ref message) = recovered.outcome {
+                let error = WorkflowError::TaskExecutionFailed(message.clone());
+                self.dead_letter_case(case_id, &error).await;
+            }
+            report.cases.push(recovered);
+        }
+
+        report.duration_ms = start_time.elapsed().as_millis() as u64;
+        tracing::info!("{}", report);
+        Ok(report)
+    }
+
+    /// Rebuild a single case and resume it if it was running
+    pub(super) async fn recover_case(
+        &self,
+        stored: Case,
+        replay: CaseReplay,
+    ) -> WorkflowResult<RecoveredCase> {
+        let case_id = stored.id;
+
+        // Make sure the specification is registered (specs are persisted on registration)
+        let spec = match self.specs.get(&stored.spec_id) {
+            Some(spec) => spec.value().clone(),
+            None => {
+                let loaded = {
+                    let store_arc = self.state_store.read().await;
+                    (*store_arc).load_spec(&stored.spec_id)?
+                };
+                let spec = loaded.ok_or_else(|| {
+                    WorkflowError::InvalidSpecification(format!(
+                        "Workflow {} not found for case {}",
+                        stored.spec_id, case_id
+                    ))
+                })?;
+                self.spec_versions.record(&spec).await?;
+                self.specs.insert(spec.id, spec.clone());
+                spec
+            }
+        };
+
+        let from_checkpoint = replay.checkpoint.is_some();
+        let (mut marking, work_items, timers) = match replay.checkpoint {
+            Some(checkpoint) => (checkpoint.marking, checkpoint.work_items, checkpoint.timers),
+            None => (CaseMarking::initial(&spec)?, Vec::new(), Vec::new()),
+        };
+
+        let mut restored_work_items = 0;
+        for work_item in work_items {
+            if self.work_item_service.restore_work_item(work_item).await {
+                restored_work_items += 1;
+            }
+        }
+        let mut restored_timers = 0;
+        for timer in timers {
+            if self.timer_service.restore_timer(timer).await? {
+                restored_timers += 1;
+            }
+        }
+
+        let state = stored.state;
+        self.cases.insert(case_id, stored);
+
+        if state != CaseState::Running {
+            self.markings.insert(case_id, marking);
+            return Ok(RecoveredCase {
+                case_id,
+                state,
+                outcome: RecoveryOutcome::Restored,
+                from_checkpoint,
+                reexecuted_tasks: Vec::new(),
+                deduplicated_tasks: Vec::new(),
+                restored_work_items,
+                restored_timers,
+            });
+        }
+
+        marking.requeue_executing();
+        let mut deduplicated_tasks: Vec<String> =
+            replay.completed_since_checkpoint.iter().cloned().collect();
+        deduplicated_tasks.sort();
+        let already_completed: HashSet<String> = replay.completed_since_checkpoint;
+        let reexecuted_tasks = replay.in_flight;
+
+        let outcome = match resume_workflow(self, case_id, &spec, marking, already_completed).await
+        {
+            Ok(()) => match self.get_case(case_id).await?.state {
+                CaseState::Completed => RecoveryOutcome::Completed,
+                _ => RecoveryOutcome::Resumed,
+            },
+            Err(e) => RecoveryOutcome::Failed(e.to_string()),
+        };
+
+        Ok(RecoveredCase {
+            case_id,
+            state: self.get_case(case_id).await?.state,
+            outcome,
+            from_checkpoint,
+            reexecuted_tasks,
+            deduplicated_tasks,
+            restored_work_items,
+            restored_timers,
+        })
+    }
+}
+
+/// Terminal case states are never recovered
+fn is_terminal(state: CaseState) -> bool {
+    matches!(
+        state,
+        CaseState::Completed | CaseState::Cancelled | CaseState::Failed
+    )
+}
@@ -0,0 +1,570 @@
+//! Retention enforcement and subject erasure
+//!
+//! The retention sweeper applies the policies of a [`RetentionManager`]:
+//! - `Case`: finished cases past their retention period are purged (case record,
+//!   case history, receipts, work items, documents and dead letters) or
+//!   anonymized (personal fields redacted from the case data, its history
+//!   checkpoints and work items; documents deleted), depending on the policy.
+//! - `WorkItem`: finished work items are removed on their own.
+//! - `Document`: documents are deleted on their own.
+//!
+//! A subject-erasure request anonymizes every case of a data subject, finished
+//! or not. Cases held directly or through their subject are skipped and listed
+//! in the report. Receipts are only deleted by purges; every deletion or
+//! redaction leaves a tombstone. Signed reports and tombstones are kept in the
+//! state store.
+
+use crate::case::{Case, CaseId};
+use crate::compliance::{
+    ErasureReason, ErasureRequest, ReportSigner, RetentionAction, RetentionManager,
+    RetentionReport, RetentionReportKind, SignedRetentionReport, Tombstone,
+};
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::services::work_items::WorkItemState;
+use crate::services::DocumentStore;
+use crate::state::manager::StateEvent;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::composite::is_finished;
+use super::WorkflowEngine;
+
+/// Sled tree holding tombstones, keyed by case
+const TOMBSTONE_TREE: &str = "tombstones";
+
+/// Sled tree holding signed retention reports
+const RETENTION_REPORT_TREE: &str = "retention_reports";
+
+/// Resource types governed by retention policies
+const CASE: &str = "Case";
+const WORK_ITEM: &str = "WorkItem";
+const DOCUMENT: &str = "Document";
+
+impl WorkflowEngine {
+    /// Apply the retention policies once, returning the signed report
+    ///
+    /// Documents are only swept when a document store is given. If the sweep
+    /// fails part-way, a report of what was erased so far is still signed and
+    /// recorded before the error is returned.
+    pub async fn sweep_retention(
+        &self,
+        retention: &RetentionManager,
+        documents: Option<&DocumentStore>,
+        signer: &ReportSigner,
+    ) -> WorkflowResult<SignedRetentionReport> {
+        let mut report = RetentionReport::new(RetentionReportKind::Sweep);
+        let outcome = self
+            .apply_retention(retention, documents, &mut report)
+            .await;
+        self.finish_retention_run(report, outcome, signer).await
+    }
+
+    /// Erase the personal data of a data subject from all of their cases
+    ///
+    /// Fails if the case retention policy does not allow right-to-be-forgotten
+    /// requests. Like a sweep, a failed erasure still records a partial report.
+    pub async fn erase_subject(
+        &self,
+        request: &ErasureRequest,
+        retention: &RetentionManager,
+        documents: Option<&DocumentStore>,
+        signer: &ReportSigner,
+    ) -> WorkflowResult<SignedRetentionReport> {
+        if let Some(policy) = retention.policy_for(CASE) {
+            if !policy.gdpr_rtbf {
+                return Err(WorkflowError::Validation(format!(
+                    "Retention policy {} does not allow subject erasure",
+                    policy.name
+                )));
+            }
+        }
+
+        let mut report = RetentionReport::new(RetentionReportKind::SubjectErasure {
+            subject: request.subject.clone(),
+        });
+        let outcome = self
+            .erase_subject_cases(request, retention, documents, &mut report)
+            .await;
+        self.finish_retention_run(report, outcome, signer).await
+    }
+
+    /// Erase expired resources, recording each erasure in `report`
+    async fn apply_retention(
+        &self,
+        retention: &RetentionManager,
+        documents: Option<&DocumentStore>,
+        report: &mut RetentionReport,
+    ) -> WorkflowResult<()> {
+        if let Some(policy) = retention.policy_for(CASE) {
+            for case in self.all_cases().await? {
+                if !is_finished(case.state) {
+                    continue;
+                }
+                let expires_from = case.completed_at.unwrap_or(case.created_at);
+                if retention.should_retain(CASE, expires_from) {
+                    continue;
+                }
+                let subject = retention.subject_of(&case.data);
+                if retention.is_case_held(&case.id, subject.as_deref()) {
+                    report.held.push(case.id);
+                    continue;
+                }
+                match policy.action {
+                    RetentionAction::Purge => self.purge_case(&case, documents, report).await?,
+                    RetentionAction::Anonymize => {
+                        let fields = if policy.personal_fields.is_empty() {
+                            case.data
+                                .as_object()
+                                .map(|data| data.keys().cloned().collect())
+                                .unwrap_or_default()
+                        } else {
+                            policy.personal_fields.clone()
+                        };
+                        self.anonymize_case(
+                            &case,
+                            &fields,
+                            documents,
+                            ErasureReason::Retention,
+                            report,
+                        )
+                        .await?
+                    }
+                }
+            }
+        }
+
+        if retention.policy_for(WORK_ITEM).is_some() {
+            let finished = self
+                .work_item_service
+                .filter_work_items(|item| {
+                    matches!(
+                        item.state,
+                        WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+                    )
+                })
+                .await;
+            for item in finished {
+                let expires_from = item.completed_at.unwrap_or(item.created_at);
+                if retention.should_retain(WORK_ITEM, expires_from) {
+                    continue;
+                }
+                let case_id = CaseId::parse_str(&item.case_id).ok();
+                if let Some(case_id) = case_id {
+                    if self.is_held(retention, case_id, Some(&item.data)).await {
+                        continue;
+                    }
+                }
+                if self
+                    .work_item_service
+                    .remove_work_item(&item.id)
+                    .await
+                    .is_some()
+                {
+                    report.purged(WORK_ITEM, item.id, case_id);
+                }
+            }
+        }
+
+        if let (Some(documents), Some(_)) = (documents, retention.policy_for(DOCUMENT)) {
+            for metadata in documents.list_documents().await {
+                if retention.should_retain(DOCUMENT, metadata.uploaded_at)
+                    || self.is_held(retention, metadata.case_id, None).await
+                {
+                    continue;
+                }
+                purge_document(
+                    documents,
+                    &metadata.id,
+                    metadata.case_id,
+                    ErasureReason::Retention,
+                    report,
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Anonymize every case of the subject, recording each erasure in `report`
+    async fn erase_subject_cases(
+        &self,
+        request: &ErasureRequest,
+        retention: &RetentionManager,
+        documents: Option<&DocumentStore>,
+        report: &mut RetentionReport,
+    ) -> WorkflowResult<()> {
+        let mut fields = request.personal_fields.clone();
+        let subject_field = retention.subject_field().to_string();
+        if !fields.contains(&subject_field) {
+            fields.push(subject_field);
+        }
+
+        for case in self.all_cases().await? {
+            if retention.subject_of(&case.data).as_deref() != Some(request.subject.as_str()) {
+                continue;
+            }
+            if retention.is_case_held(&case.id, Some(&request.subject)) {
+                report.held.push(case.id);
+                continue;
+            }
+            self.anonymize_case(
+                &case,
+                &fields,
+                documents,
+                ErasureReason::SubjectErasure,
+                report,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Sign and record the report of a run, even one that failed part-way
+    ///
+    /// Resources erased before a failure are gone, so their tombstones and
+    /// records must be kept; the report then carries the error, and the error
+    /// is returned once the report is recorded.
+    async fn finish_retention_run(
+        &self,
+        mut report: RetentionReport,
+        outcome: WorkflowResult<()>,
+        signer: &ReportSigner,
+    ) -> WorkflowResult<SignedRetentionReport> {
+        let Err(error) = outcome else {
+            return self.record_retention_report(signer.sign(report)?).await;
+        };
+
+        report.error = Some(error.to_string());
+        let report_id = report.id;
+        let recorded = match signer.sign(report) {
+            Ok(signed) => self.record_retention_report(signed).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = recorded {
+            tracing::error!(
+                "Failed to record partial retention report {}: {}",
+                report_id,
+                e
+            );
+        }
+        Err(error)
+    }
+
+    /// Start a background worker sweeping retention at every `interval`
+    pub fn start_retention_sweeper(
+        &self,
+        retention: Arc<RetentionManager>,
+        documents: Option<Arc<DocumentStore>>,
+        signer: Arc<ReportSigner>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let engine = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match engine
+                    .sweep_retention(&retention, documents.as_deref(), &signer)
+                    .await
+                {
+                    Ok(signed) if !signed.report.records.is_empty() => tracing::info!(
+                        "Retention sweep {}: {} resources erased, {} cases held",
+                        signed.report.id,
+                        signed.report.records.len(),
+                        signed.report.held.len()
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Retention sweep failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Signed retention reports, oldest first
+    pub async fn retention_reports(&self) -> WorkflowResult<Vec<SignedRetentionReport>> {
+        let tree = self
+            .state_store
+            .read()
+            .await
+            .open_tree(RETENTION_REPORT_TREE)?;
+        let mut reports = Vec::new();
+        for result in tree.iter() {
+            let (_, value) = result
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            let signed: SignedRetentionReport =
+                serde_json::from_slice(value.as_ref()).map_err(|e| {
+                    WorkflowError::StatePersistence(format!("Deserialization error: {}", e))
+                })?;
+            reports.push(signed);
+        }
+        reports.sort_by_key(|signed| signed.report.generated_at);
+        Ok(reports)
+    }
+
+    /// Tombstones left by erasures of a case
+    pub async fn tombstones(&self, case_id: CaseId) -> WorkflowResult<Vec<Tombstone>> {
+        let tree = self.state_store.read().await.open_tree(TOMBSTONE_TREE)?;
+        let mut tombstones = Vec::new();
+        for result in tree.scan_prefix(format!("{}:", case_id).as_bytes()) {
+            let (_, value) = result
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            let tombstone: Tombstone = serde_json::from_slice(value.as_ref()).map_err(|e| {
+                WorkflowError::StatePersistence(format!("Deserialization error: {}", e))
+            })?;
+            tombstones.push(tombstone);
+        }
+        tombstones.sort_by_key(|tombstone| tombstone.erased_at);
+        Ok(tombstones)
+    }
+
+    /// Stored and live cases (live copies win)
+    async fn all_cases(&self) -> WorkflowResult<Vec<Case>> {
+        let stored = self.state_store.read().await.load_all_cases()?;
+        let mut cases: HashMap<CaseId, Case> =
+            stored.into_iter().map(|case| (case.id, case)).collect();
+        for live in self.cases.iter() {
+            cases.insert(*live.key(), live.value().clone());
+        }
+        Ok(cases.into_values().collect())
+    }
+
+    /// Check the legal holds of a case, looking up its subject if `data` is not given
+    async fn is_held(
+        &self,
+        retention: &RetentionManager,
+        case_id: CaseId,
+        data: Option<&serde_json::Value>,
+    ) -> bool {
+        let subject = match data.and_then(|data| retention.subject_of(data)) {
+            Some(subject) => Some(subject),
+            None => self
+                .get_case(case_id)
+                .await
+                .ok()
+                .and_then(|case| retention.subject_of(&case.data)),
+        };
+        retention.is_case_held(&case_id, subject.as_deref())
+    }
+
+    /// Delete a case with everything recorded for it
+    async fn purge_case(
+        &self,
+        case: &Case,
+        documents: Option<&DocumentStore>,
+        report: &mut RetentionReport,
+    ) -> WorkflowResult<()> {
+        let case_id = case.id;
+        let reason = ErasureReason::Retention;
+        let case_bytes = serde_json::to_vec(case).map_err(|e| {
+            WorkflowError::Internal(format!("Failed to serialize case {}: {}", case_id, e))
+        })?;
+        {
+            let store_arc = self.state_store.read().await;
+            // Receipts are keyed by `receipt_{case_id}_{spec_id}` (see provenance.rs)
+            for (key, bytes) in (*store_arc).scan_receipts(&format!("receipt_{}_", case_id))? {
+                (*store_arc).remove_receipt(&key)?;
+                report.tombstones.push(Tombstone::deleted(
+                    case_id,
+                    key.clone(),
+                    &bytes,
+                    reason.clone(),
+                ));
+                report.purged("Receipt", key, Some(case_id));
+            }
+            if (*store_arc).delete_case_history(&case_id)? > 0 {
+                report.purged("CaseHistory", case_id.to_string(), Some(case_id));
+            }
+            (*store_arc).delete_case(&case_id)?;
+        }
+        self.state_manager.forget_case(&case_id).await;
+        self.cases.remove(&case_id);
+        self.markings.remove(&case_id);
+        self.mi_tasks.retain(|(id, _), _| *id != case_id);
+        self.case_rdf_stores.write().await.remove(&case_id);
+        report.tombstones.push(Tombstone::deleted(
+            case_id,
+            format!("case:{}", case_id),
+            &case_bytes,
+            reason.clone(),
+        ));
+        report.purged(CASE, case_id.to_string(), Some(case_id));
+
+        let case_key = case_id.to_string();
+        for item in self.work_item_service.list_case_work_items(&case_key).await {
+            if self
+                .work_item_service
+                .remove_work_item(&item.id)
+                .await
+                .is_some()
+            {
+                report.purged(WORK_ITEM, item.id, Some(case_id));
+            }
+        }
+        if let Some(documents) = documents {
+            for metadata in documents.list_case_documents(case_id).await {
+                purge_document(documents, &metadata.id, case_id, reason.clone(), report).await?;
+            }
+        }
+        for entry in self.dead_letter_queue.list()? {
+            if entry.case_id == Some(case_id) {
+                self.dead_letter_queue.remove(entry.id)?;
+                report.purged("DeadLetter", entry.id.to_string(), Some(case_id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Redact personal fields wherever the data of a case is kept
+    ///
+    /// Receipts are kept: they only carry hashes.
+    async fn anonymize_case(
+        &self,
+        case: &Case,
+        fields: &[String],
+        documents: Option<&DocumentStore>,
+        reason: ErasureReason,
+        report: &mut RetentionReport,
+    ) -> WorkflowResult<()> {
+        let case_id = case.id;
+        let mut data = case.data.clone();
+        let redacted = redact(&mut data, fields);
+        if !redacted.is_empty() {
+            self.replace_case_data(case_id, &data).await?;
+            self.state_manager.forget_case(&case_id).await;
+            for field in &redacted {
+                report.tombstones.push(Tombstone::redacted(
+                    case_id,
+                    format!("case:{}", case_id),
+                    field.clone(),
+                    reason.clone(),
+                ));
+            }
+            report.anonymized(CASE, case_id.to_string(), Some(case_id), redacted);
+        }
+
+        // Checkpoints in the case history carry the case data and work items
+        let rewritten = {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).rewrite_case_history(&case_id, |event| match event {
+                StateEvent::CaseCheckpointed { checkpoint, .. } => {
+                    let mut changed = !redact(&mut checkpoint.case.data, fields).is_empty();
+                    for item in checkpoint.work_items.iter_mut() {
+                        changed |= !redact(&mut item.data, fields).is_empty();
+                    }
+                    changed
+                }
+                _ => false,
+            })?
+        };
+        if rewritten > 0 {
+            report.anonymized(
+                "CaseHistory",
+                case_id.to_string(),
+                Some(case_id),
+                fields.to_vec(),
+            );
+        }
+
+        let case_key = case_id.to_string();
+        for item in self.work_item_service.list_case_work_items(&case_key).await {
+            let redacted = self
+                .work_item_service
+                .redact_work_item_data(&item.id, fields)
+                .await;
+            if redacted.is_empty() {
+                continue;
+            }
+            for field in &redacted {
+                report.tombstones.push(Tombstone::redacted(
+                    case_id,
+                    format!("work_item:{}", item.id),
+                    field.clone(),
+                    reason.clone(),
+                ));
+            }
+            report.anonymized(WORK_ITEM, item.id, Some(case_id), redacted);
+        }
+
+        for entry in self.dead_letter_queue.list()? {
+            if entry.case_id != Some(case_id) {
+                continue;
+            }
+            let mut payload = entry.operation_data.clone();
+            let redacted = payload
+                .get_mut("data")
+                .map(|data| redact(data, fields))
+                .unwrap_or_default();
+            if !redacted.is_empty() {
+                self.dead_letter_queue.edit_payload(entry.id, payload)?;
+                report.anonymized("DeadLetter", entry.id.to_string(), Some(case_id), redacted);
+            }
+        }
+
+        // Documents are opaque and cannot be redacted field by field
+        if let Some(documents) = documents {
+            for metadata in documents.list_case_documents(case_id).await {
+                purge_document(documents, &metadata.id, case_id, reason.clone(), report).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Persist the tombstones and the signed report
+    async fn record_retention_report(
+        &self,
+        signed: SignedRetentionReport,
+    ) -> WorkflowResult<SignedRetentionReport> {
+        let store = self.state_store.read().await;
+        let tombstones = store.open_tree(TOMBSTONE_TREE)?;
+        for tombstone in &signed.report.tombstones {
+            let key = format!("{}:{}", tombstone.case_id, tombstone.id);
+            tombstones
+                .insert(key.as_bytes(), to_json(tombstone)?)
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+        }
+        store
+            .open_tree(RETENTION_REPORT_TREE)?
+            .insert(signed.report.id.to_string().as_bytes(), to_json(&signed)?)
+            .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+        Ok(signed)
+    }
+}
+
+/// Delete a document, leaving a tombstone with the digest of its content
+async fn purge_document(
+    documents: &DocumentStore,
+    doc_id: &crate::services::DocumentId,
+    case_id: CaseId,
+    reason: ErasureReason,
+    report: &mut RetentionReport,
+) -> WorkflowResult<()> {
+    let content = documents.get_document(doc_id).await.unwrap_or_default();
+    documents.delete_document(doc_id).await?;
+    report.tombstones.push(Tombstone::deleted(
+        case_id,
+        format!("document:{}", doc_id.0),
+        &content,
+        reason,
+    ));
+    report.purged(DOCUMENT, doc_id.0.clone(), Some(case_id));
+    Ok(())
+}
+
+/// Remove fields from JSON object data, returning the fields that were present
+fn redact(data: &mut serde_json::Value, fields: &[String]) -> Vec<String> {
+    match data.as_object_mut() {
+        Some(data) => fields
+            .iter()
+            .filter(|field| data.remove(field.as_str()).is_some())
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> WorkflowResult<Vec<u8>> {
+    serde_json::to_vec(value)
+        .map_err(|e| WorkflowError::StatePersistence(format!("Serialization error: {}", e)))
+}
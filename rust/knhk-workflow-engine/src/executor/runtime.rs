@@ -26,6 +26,7 @@
 //! - Task dispatch is non-blocking
 //! - Telemetry emission is async
 
+use crate::data::expression::evaluate_predicate;
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::executor::loader::{
     ExecutionMode, FlowDefinition, JoinType, SplitType, TaskDefinition, WorkflowDefinition,
@@ -38,65 +39,6 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 use uuid;
 
-/// Evaluate a predicate against workflow data
-///
-/// Supports simple predicates: "variable == value", "variable >= value", "variable <= value"
-fn evaluate_predicate(predicate: &str, data: &HashMap<String, serde_json::Value>) -> bool {
-    let predicate = predicate.trim();
-
-    // Handle "variable >= value" pattern
-    if let Some(ge_pos) = predicate.find(">=") {
-        let left_var = predicate[..ge_pos].trim();
-        let right_var = predicate[ge_pos + 2..].trim();
-
-        let left_value = data.get(left_var).and_then(|v| v.as_f64());
-        let right_value = data.get(right_var).and_then(|v| v.as_f64());
-
-        if let (Some(left), Some(right)) = (left_value, right_value) {
-            return left >= right;
-        }
-        return false;
-    }
-
-    // Handle "variable <= value" pattern
-    if let Some(le_pos) = predicate.find("<=") {
-        let left_var = predicate[..le_pos].trim();
-        let right_var = predicate[le_pos + 2..].trim();
-
-        let left_value = data.get(left_var).and_then(|v| v.as_f64());
-        let right_value = data.get(right_var).and_then(|v| v.as_f64());
-
-        if let (Some(left), Some(right)) = (left_value, right_value) {
-            return left <= right;
-        }
-        return false;
-    }
-
-    // Handle "variable == value" pattern
-    if let Some(eq_pos) = predicate.find("==") {
-        let var_name = predicate[..eq_pos].trim();
-        let expected_value = predicate[eq_pos + 2..].trim();
-
-        if let Some(actual_value) = data.get(var_name) {
-            match expected_value {
-                "true" => return actual_value.as_bool() == Some(true),
-                "false" => return actual_value.as_bool() == Some(false),
-                _ => {
-                    if let Some(actual_str) = actual_value.as_str() {
-                        return actual_str == expected_value.trim_matches('"');
-                    } else if let (Some(actual_num), Ok(expected_num)) =
-                        (actual_value.as_f64(), expected_value.parse::<f64>())
-                    {
-                        return (actual_num - expected_num).abs() < f64::EPSILON;
-                    }
-                }
-            }
-        }
-    }
-
-    false
-}
-
 /// Workflow execution state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionState {
@@ -479,7 +421,7 @@ impl WorkflowRuntime {
                 let mut enabled = false;
                 for flow in &outgoing {
                     if let Some(ref predicate) = flow.predicate {
-                        if evaluate_predicate(predicate, &state.data) {
+                        if evaluate_predicate(predicate, &state.data)? {
                             self.try_enable_task(state, &flow.to);
                             enabled = true;
                             debug!(
@@ -512,7 +454,7 @@ impl WorkflowRuntime {
                 let total_flows = outgoing.len();
                 for flow in &outgoing {
                     let should_enable = if let Some(ref predicate) = flow.predicate {
-                        evaluate_predicate(predicate, &state.data)
+                        evaluate_predicate(predicate, &state.data)?
                     } else {
                         true // No predicate means always enable
                     };
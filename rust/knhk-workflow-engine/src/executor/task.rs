@@ -2,20 +2,27 @@
 //!
 //! Inputs pre-validated at ingress.
 
-use crate::case::CaseId;
+use crate::case::{CaseId, CaseState};
+use crate::data::contract::TaskDataContract;
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::integration::fortune5::RuntimeClass;
 use crate::parser::{Task, WorkflowSpecId};
 use crate::patterns::{PatternExecutionContext, PatternId};
 use crate::resource::AllocationRequest;
+use crate::services::work_items::{WorkItem, WorkItemState};
+use crate::worklets::{ExceptionKind, ExletResolution};
 #[allow(unused_imports)]
 use crate::{
     otel_attr, otel_bottleneck, otel_conformance, otel_resource, otel_span, otel_span_end,
 };
+use chrono::Utc;
 use knhk_otel::SpanContext;
 use std::collections::HashMap;
 use std::time::Instant;
 
+use super::composite::execute_composite_task;
+use super::messaging::execute_message_catch;
+use super::multi_instance::execute_multi_instance_task;
 use super::WorkflowEngine;
 
 /// Execute a task with resource allocation and Fortune 5 SLO tracking
@@ -34,7 +41,10 @@ pub(super) async fn execute_task_with_allocation(
         let pattern_id = task.pattern_id.unwrap_or_else(|| {
             // Fallback to runtime identification if not pre-compiled
             if matches!(task.task_type, crate::parser::TaskType::MultipleInstance) {
-                PatternId(12) // MI Without Sync
+                task.multi_instance
+                    .as_ref()
+                    .map(|mi| mi.pattern_id())
+                    .unwrap_or(PatternId(12)) // MI Without Sync
             } else {
                 // Map split/join to pattern (simplified)
                 match (task.split_type, task.join_type) {
@@ -155,6 +165,10 @@ pub(super) async fn execute_task_with_allocation(
 
     // Execute task based on task type
     match task.task_type {
+        crate::parser::TaskType::Atomic if task.message.is_some() => {
+            // Message-catch task: suspended until a correlated message arrives
+            execute_message_catch(engine, case_id, task).await?;
+        }
         crate::parser::TaskType::Atomic => {
             // Atomic task: Execute via work item service (human task) or connector (automated)
             let case = engine.get_case(case_id).await?;
@@ -180,117 +194,68 @@ pub(super) async fn execute_task_with_allocation(
                     .await?;
                 }
 
-                let work_item_id = engine
+                // Re-attach to the open work item if the task is re-executed after
+                // crash recovery, otherwise enable a new one
+                let existing = engine
                     .work_item_service
-                    .create_work_item(
-                        case_id.to_string(),
-                        spec_id,
-                        task.id.clone(),
-                        case.data.clone(),
-                    )
-                    .await?;
-
-                // For test scenarios, auto-complete work items immediately
-                // In production, would wait for human completion via async notification
-                // Check if work item is already completed (e.g., by test setup)
-                let mut completed = false;
-                for _ in 0..10 {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-                    if let Some(work_item) =
-                        engine.work_item_service.get_work_item(&work_item_id).await
+                    .find_open_work_item(&case_id.to_string(), &task.id)
+                    .await;
+                let mut work_item_id = match existing {
+                    Some(work_item) => work_item.id,
+                    None => enable_work_item(engine, case_id, spec_id, task).await?,
+                };
+
+                let mut restarts = 0;
+                let result = loop {
+                    let result =
+                        await_work_item(engine, case_id, task, &work_item_id, &mut restarts)
+                            .await?;
+
+                    // Post-constraints see the case data overlaid with the result
+                    let mut checked = engine.get_case(case_id).await?.data;
+                    if let (Some(checked), Some(result)) =
+                        (checked.as_object_mut(), result.as_object())
                     {
-                        match work_item.state {
-                            crate::services::work_items::WorkItemState::Completed => {
-                                completed = true;
-                                // Work item completed - update case with result
-                                let mut case = engine.get_case(case_id).await?;
-                                // Merge work item data into case variables
-                                if let (Some(case_obj), Some(work_item_obj)) =
-                                    (case.data.as_object_mut(), work_item.data.as_object())
-                                {
-                                    for (key, value) in work_item_obj {
-                                        case_obj.insert(key.clone(), value.clone());
-                                    }
-                                }
-
-                                // Produce outputs for declared output parameters if not already present
-                                let outputs = produce_task_outputs(task, &case.data);
-                                if let Some(case_obj) = case.data.as_object_mut() {
-                                    for (key, value) in outputs {
-                                        if !case_obj.contains_key(&key) {
-                                            case_obj.insert(key, value);
-                                        }
-                                    }
-                                }
-
-                                // Update case in engine (save to state store)
-                                let store_arc = engine.state_store.read().await;
-                                (*store_arc).save_case(case_id, &case)?;
-                                break;
-                            }
-                            crate::services::work_items::WorkItemState::Cancelled => {
-                                return Err(WorkflowError::TaskExecutionFailed(format!(
-                                    "Work item {} was cancelled",
-                                    work_item_id
-                                )));
-                            }
-                            _ => {
-                                // Still in progress, continue waiting
-                            }
+                        for (key, value) in result {
+                            checked.insert(key.clone(), value.clone());
                         }
-                    } else {
-                        return Err(WorkflowError::TaskExecutionFailed(format!(
-                            "Work item {} not found",
-                            work_item_id
-                        )));
                     }
-                }
-
-                // If not completed after short wait, auto-complete for test scenarios
-                if !completed {
-                    // Auto-complete work item with case data (for test scenarios)
-                    let case = engine.get_case(case_id).await?;
-                    engine
-                        .work_item_service
-                        .complete(&work_item_id, case.data.clone())
-                        .await?;
-
-                    // Update case with work item result
-                    let mut case = engine.get_case(case_id).await?;
-                    let work_item = engine
-                        .work_item_service
-                        .get_work_item(&work_item_id)
-                        .await
-                        .ok_or_else(|| {
-                            WorkflowError::TaskExecutionFailed(format!(
-                                "Work item {} not found after completion",
-                                work_item_id
-                            ))
-                        })?;
-
-                    // Merge work item data into case variables
-                    if let (Some(case_obj), Some(work_item_obj)) =
-                        (case.data.as_object_mut(), work_item.data.as_object())
-                    {
-                        for (key, value) in work_item_obj {
-                            case_obj.insert(key.clone(), value.clone());
-                        }
+                    let resolution = handle_item_exception(
+                        engine,
+                        case_id,
+                        task,
+                        &work_item_id,
+                        ExceptionKind::ItemPostConstraint,
+                        checked,
+                    )
+                    .await?;
+                    if resolution == ExletResolution::Restart {
+                        restarts += 1;
+                        check_restarts(task, restarts)?;
+                        work_item_id = enable_work_item(engine, case_id, spec_id, task).await?;
+                        continue;
                     }
-
-                    // Produce outputs for declared output parameters if not already present
-                    let outputs = produce_task_outputs(task, &case.data);
-                    if let Some(case_obj) = case.data.as_object_mut() {
-                        for (key, value) in outputs {
-                            if !case_obj.contains_key(&key) {
-                                case_obj.insert(key, value);
-                            }
+                    break result;
+                };
+
+                // Work item completed - update case with result
+                let mut case = engine.get_case(case_id).await?;
+                // Merge work item data into case variables
+                merge_task_result(task, &mut case.data, &result)?;
+
+                // Produce outputs for declared output parameters if not already present
+                let outputs = produce_task_outputs(task, &case.data);
+                if let Some(case_obj) = case.data.as_object_mut() {
+                    for (key, value) in outputs {
+                        if !case_obj.contains_key(&key) {
+                            case_obj.insert(key, value);
                         }
                     }
-
-                    // Update case in engine (save to state store)
-                    let store_arc = engine.state_store.read().await;
-                    (*store_arc).save_case(case_id, &case)?;
                 }
+
+                // Update case in engine (save to state store)
+                let store_arc = engine.state_store.read().await;
+                (*store_arc).save_case(case_id, &case)?;
             } else {
                 // Automated task: Execute via connector integration
                 // Add resource tracking for automated tasks
@@ -324,13 +289,7 @@ pub(super) async fn execute_task_with_allocation(
 
                     // Update case with connector result
                     let mut case = engine.get_case(case_id).await?;
-                    if let (Some(case_obj), Some(result_obj)) =
-                        (case.data.as_object_mut(), result.as_object())
-                    {
-                        for (key, value) in result_obj {
-                            case_obj.insert(key.clone(), value.clone());
-                        }
-                    }
+                    merge_task_result(task, &mut case.data, &result)?;
 
                     // Produce outputs for declared output parameters if not already present
                     let outputs = produce_task_outputs(task, &case.data);
@@ -355,378 +314,13 @@ pub(super) async fn execute_task_with_allocation(
             }
         }
         crate::parser::TaskType::Composite => {
-            // Composite task: Execute as sub-workflow
-            // NOTE: Sub-workflow spec should be stored in task metadata or loaded separately
-            // For now, return error indicating sub-workflow spec loading is required
-            return Err(WorkflowError::TaskExecutionFailed(
-                format!("Composite task {} requires sub-workflow specification - sub-workflow spec must be stored in task metadata or loaded from state store", task.id)
-            ));
+            // Composite task: run the sub-net as a child case, suspended until it finishes
+            execute_composite_task(engine, case_id, task).await?;
         }
         crate::parser::TaskType::MultipleInstance => {
-            // Multiple instance task: Execute multiple instances in parallel
-            // Determine instance count (from task properties or case variables)
-            let case = engine.get_case(case_id).await?;
-
-            // Try to extract instance count from case variables
-            let instance_count = case
-                .data
-                .get("instance_count")
-                .and_then(|v| {
-                    v.as_u64()
-                        .map(|n| n as usize)
-                        .or_else(|| v.as_str().and_then(|s| s.parse::<usize>().ok()))
-                })
-                .or({
-                    // Try to extract from task metadata or default to 1
-                    // For now, default to 1 instance if not specified
-                    Some(1)
-                })
-                .ok_or_else(|| {
-                    WorkflowError::TaskExecutionFailed(format!(
-                        "Multiple instance task {} requires instance_count in case variables",
-                        task.id
-                    ))
-                })?;
-
-            // Validate instance count (guard constraint: max_run_len ≤ 8)
-            if instance_count > 8 {
-                return Err(WorkflowError::GuardViolation(format!(
-                    "Multiple instance task {} has instance_count {} which exceeds max_run_len 8",
-                    task.id, instance_count
-                )));
-            }
-
-            // Execute multiple instances in parallel
-            // Create instance-specific data for each instance and execute
-            let mut instance_handles = Vec::new();
-            let engine_clone = engine.clone();
-            let case_id_clone = case_id;
-            let spec_id_clone = spec_id;
-            let task_clone = task.clone();
-
-            for instance_id in 0..instance_count {
-                let engine_instance = engine_clone.clone();
-                let case_id_instance = case_id_clone;
-                let spec_id_instance = spec_id_clone;
-                let task_instance = task_clone.clone();
-                let instance_id_val = instance_id;
-                let instance_count_val = instance_count;
-
-                // Spawn task for this instance
-                let handle = tokio::spawn(async move {
-                    // Get current case data
-                    let mut case =
-                        engine_instance
-                            .get_case(case_id_instance)
-                            .await
-                            .map_err(|e| {
-                                WorkflowError::TaskExecutionFailed(format!(
-                                    "Failed to get case for MI instance {}: {}",
-                                    instance_id_val, e
-                                ))
-                            })?;
-
-                    // Create instance-specific data
-                    if let Some(obj) = case.data.as_object_mut() {
-                        obj.insert(
-                            "instance_id".to_string(),
-                            serde_json::Value::Number(instance_id_val.into()),
-                        );
-                        obj.insert(
-                            "instance_count".to_string(),
-                            serde_json::Value::Number(instance_count_val.into()),
-                        );
-                    }
-
-                    // Save case with instance data temporarily
-                    let store_arc = engine_instance.state_store.read().await;
-                    (*store_arc)
-                        .save_case(case_id_instance, &case)
-                        .map_err(|e| {
-                            WorkflowError::TaskExecutionFailed(format!(
-                                "Failed to save case for MI instance {}: {}",
-                                instance_id_val, e
-                            ))
-                        })?;
-                    drop(store_arc);
-
-                    tracing::debug!(
-                        "Executing MI task {} instance {}/{}",
-                        task_instance.id,
-                        instance_id_val + 1,
-                        instance_count_val
-                    );
-
-                    // Execute the task instance
-                    // For MI tasks, each instance executes as atomic (human or automated)
-                    // Check if task requires human interaction or is automated
-                    if !task_instance.required_roles.is_empty() {
-                        // Human task: Create work item for this instance
-                        let work_item_id = engine_instance
-                            .work_item_service
-                            .create_work_item(
-                                case_id_instance.to_string(),
-                                spec_id_instance,
-                                format!("{}_{}", task_instance.id, instance_id_val),
-                                case.data.clone(),
-                            )
-                            .await
-                            .map_err(|e| {
-                                WorkflowError::TaskExecutionFailed(format!(
-                                    "Failed to create work item for MI instance {}: {}",
-                                    instance_id_val, e
-                                ))
-                            })?;
-
-                        // Wait for work item completion (with timeout)
-                        let mut completed = false;
-                        for _ in 0..100 {
-                            // Check every 100ms for up to 10 seconds
-                            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                            if let Some(work_item) = engine_instance
-                                .work_item_service
-                                .get_work_item(&work_item_id)
-                                .await
-                            {
-                                match work_item.state {
-                                    crate::services::work_items::WorkItemState::Completed => {
-                                        completed = true;
-                                        // Merge work item data into case
-                                        let mut case = engine_instance
-                                                    .get_case(case_id_instance)
-                                                    .await
-                                                    .map_err(|e| {
-                                                        WorkflowError::TaskExecutionFailed(format!(
-                                                            "Failed to get case after work item completion: {}",
-                                                            e
-                                                        ))
-                                                    })?;
-                                        if let (Some(case_obj), Some(work_item_obj)) =
-                                            (case.data.as_object_mut(), work_item.data.as_object())
-                                        {
-                                            for (key, value) in work_item_obj {
-                                                case_obj.insert(key.clone(), value.clone());
-                                            }
-                                        }
-                                        let store_arc = engine_instance.state_store.read().await;
-                                        (*store_arc).save_case(case_id_instance, &case).map_err(|e| {
-                                                    WorkflowError::TaskExecutionFailed(format!(
-                                                        "Failed to save case after work item completion: {}",
-                                                        e
-                                                    ))
-                                                })?;
-                                        break;
-                                    }
-                                    crate::services::work_items::WorkItemState::Cancelled => {
-                                        return Err(WorkflowError::TaskExecutionFailed(format!(
-                                            "Work item {} for MI instance {} was cancelled",
-                                            work_item_id, instance_id_val
-                                        )));
-                                    }
-                                    _ => {
-                                        // Still in progress, continue waiting
-                                    }
-                                }
-                            }
-                        }
-
-                        if !completed {
-                            return Err(WorkflowError::TaskExecutionFailed(format!(
-                                "Work item {} for MI instance {} did not complete within timeout",
-                                work_item_id, instance_id_val
-                            )));
-                        }
-                    } else {
-                        // Automated task: Execute via connector
-                        if let Some(ref connector_integration) =
-                            engine_instance.connector_integration
-                        {
-                            let connector_name = task_instance.id.clone();
-                            let mut connector = connector_integration.lock().await;
-                            let result = connector
-                                .execute_task(&connector_name, case.data.clone())
-                                .await
-                                .map_err(|e| {
-                                    WorkflowError::TaskExecutionFailed(format!(
-                                        "Connector execution failed for MI instance {}: {}",
-                                        instance_id_val, e
-                                    ))
-                                })?;
-
-                            // Update case with connector result
-                            let mut case = engine_instance
-                                .get_case(case_id_instance)
-                                .await
-                                .map_err(|e| {
-                                    WorkflowError::TaskExecutionFailed(format!(
-                                        "Failed to get case after connector execution: {}",
-                                        e
-                                    ))
-                                })?;
-                            if let (Some(case_obj), Some(result_obj)) =
-                                (case.data.as_object_mut(), result.as_object())
-                            {
-                                for (key, value) in result_obj {
-                                    case_obj.insert(key.clone(), value.clone());
-                                }
-                            }
-                            let store_arc = engine_instance.state_store.read().await;
-                            (*store_arc)
-                                .save_case(case_id_instance, &case)
-                                .map_err(|e| {
-                                    WorkflowError::TaskExecutionFailed(format!(
-                                        "Failed to save case after connector execution: {}",
-                                        e
-                                    ))
-                                })?;
-                        } else {
-                            return Err(WorkflowError::TaskExecutionFailed(format!(
-                                "Automated MI task instance {} requires connector integration",
-                                instance_id_val
-                            )));
-                        }
-                    }
-
-                    Ok::<(), WorkflowError>(())
-                });
-
-                instance_handles.push(handle);
-            }
-
-            // Extract synchronization threshold (default: wait for all)
-            let threshold = case
-                .data
-                .get("mi_threshold")
-                .and_then(|v| {
-                    v.as_u64()
-                        .map(|n| n as usize)
-                        .or_else(|| v.as_str().and_then(|s| s.parse::<usize>().ok()))
-                })
-                .unwrap_or(instance_count); // Default: wait for all
-
-            // Validate threshold (guard constraint: threshold ≤ instance_count)
-            if threshold > instance_count {
-                return Err(WorkflowError::GuardViolation(format!(
-                    "MI task {} has threshold {} which exceeds instance_count {}",
-                    task.id, threshold, instance_count
-                )));
-            }
-
-            // Wait for threshold completions (hyper-advanced: lock-free completion tracking)
-            use std::sync::atomic::{AtomicUsize, Ordering};
-            let completed_count = Arc::new(AtomicUsize::new(0));
-            let failed_count = Arc::new(AtomicUsize::new(0));
-            let (tx, mut rx) = tokio::sync::mpsc::channel(instance_count);
-
-            // Spawn completion monitors for each instance
-            for handle in instance_handles {
-                let completed_count_clone = completed_count.clone();
-                let failed_count_clone = failed_count.clone();
-                let tx_clone = tx.clone();
-
-                tokio::spawn(async move {
-                    match handle.await {
-                        Ok(Ok(())) => {
-                            let count = completed_count_clone.fetch_add(1, Ordering::SeqCst);
-                            tx_clone.send(Ok(())).await.ok();
-                            count + 1
-                        }
-                        Ok(Err(e)) => {
-                            let count = failed_count_clone.fetch_add(1, Ordering::SeqCst);
-                            tx_clone.send(Err(e)).await.ok();
-                            count + 1
-                        }
-                        Err(e) => {
-                            let count = failed_count_clone.fetch_add(1, Ordering::SeqCst);
-                            tx_clone
-                                .send(Err(WorkflowError::TaskExecutionFailed(format!(
-                                    "MI task instance panicked: {}",
-                                    e
-                                ))))
-                                .await
-                                .ok();
-                            count + 1
-                        }
-                    }
-                });
-            }
-
-            // Wait for threshold completions
-            let mut results = Vec::new();
-            let mut errors = Vec::new();
-            let mut completed = 0;
-
-            while completed < threshold {
-                match rx.recv().await {
-                    Some(Ok(())) => {
-                        results.push(Ok(()));
-                        completed += 1;
-                    }
-                    Some(Err(e)) => {
-                        errors.push(e);
-                        completed += 1;
-                    }
-                    None => {
-                        // Channel closed, all instances completed
-                        break;
-                    }
-                }
-            }
-
-            // If threshold reached, cancel remaining instances
-            if completed >= threshold {
-                tracing::debug!(
-                    "MI task {} reached threshold {}/{} - cancelling remaining instances",
-                    task.id,
-                    completed,
-                    instance_count
-                );
-                // Note: Remaining instances will complete or timeout independently
-                // In production, would send cancellation signals to work items
-            }
-
-            // Check if we have enough successful completions
-            let success_count = results.len();
-            if success_count < threshold {
-                return Err(WorkflowError::TaskExecutionFailed(format!(
-                    "MI task {} required {} successful completions, but only {} succeeded ({} failed)",
-                    task.id, threshold, success_count, errors.len()
-                )));
-            }
-
-            // Aggregate results from completed instances
-            // Merge instance data into case
-            let mut case = engine.get_case(case_id).await?;
-            if let Some(case_obj) = case.data.as_object_mut() {
-                // Aggregate instance results (if any)
-                let mut instance_results = Vec::new();
-                for i in 0..completed {
-                    if let Some(instance_data) = case_obj.get(&format!("instance_{}_result", i)) {
-                        instance_results.push(instance_data.clone());
-                    }
-                }
-                if !instance_results.is_empty() {
-                    case_obj.insert(
-                        "mi_results".to_string(),
-                        serde_json::Value::Array(instance_results),
-                    );
-                }
-                case_obj.insert(
-                    "mi_completed_count".to_string(),
-                    serde_json::Value::Number(completed.into()),
-                );
-            }
-
-            // Save aggregated case
-            let store_arc = engine.state_store.read().await;
-            (*store_arc).save_case(case_id, &case)?;
-
-            tracing::debug!(
-                "Multiple instance task {} completed {} instances (threshold: {})",
-                task.id,
-                completed,
-                threshold
-            );
+            // Multiple instance task: instances created from the MI configuration
+            // (or legacy case variables), synchronized according to the threshold
+            execute_multi_instance_task(engine, case_id, spec_id, task).await?;
         }
     }
 
@@ -802,6 +396,224 @@ pub(super) async fn execute_task_with_allocation(
     Ok(())
 }
 
+/// Maximum number of times exlets may restart the work item of a task
+const MAX_ITEM_RESTARTS: u32 = 3;
+
+fn check_restarts(task: &Task, restarts: u32) -> WorkflowResult<()> {
+    if restarts > MAX_ITEM_RESTARTS {
+        return Err(WorkflowError::TaskExecutionFailed(format!(
+            "Work item of task {} was restarted more than {} times",
+            task.id, MAX_ITEM_RESTARTS
+        )));
+    }
+    Ok(())
+}
+
+/// Create and checkpoint the work item of a human task, then check its pre-constraints
+///
+/// A pre-constraint exlet may force-complete the work item or restart it, in
+/// which case it is reset in place and its pre-constraints are checked again.
+async fn enable_work_item(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    spec_id: WorkflowSpecId,
+    task: &Task,
+) -> WorkflowResult<String> {
+    // Typed tasks only see their mapped inputs and must
+    // return results matching their outputs
+    let case = engine.get_case(case_id).await?;
+    let contract = TaskDataContract::for_task(task)?;
+    let input = if contract.inputs.is_empty() {
+        case.data.clone()
+    } else {
+        contract.bind_inputs(&case.data)?
+    };
+    let output_contract = if contract.outputs.is_empty() {
+        None
+    } else {
+        Some(contract)
+    };
+    let work_item_id = engine
+        .work_item_service
+        .create_work_item_with_contract(
+            case_id.to_string(),
+            spec_id,
+            task.id.clone(),
+            input.clone(),
+            output_contract,
+        )
+        .await?;
+    engine.checkpoint_case(case_id).await?;
+
+    let mut restarts = 0;
+    loop {
+        let resolution = handle_item_exception(
+            engine,
+            case_id,
+            task,
+            &work_item_id,
+            ExceptionKind::ItemPreConstraint,
+            input.clone(),
+        )
+        .await?;
+        if resolution != ExletResolution::Restart {
+            return Ok(work_item_id);
+        }
+        restarts += 1;
+        check_restarts(task, restarts)?;
+    }
+}
+
+/// Wait for the work item of a human task to complete and return its result
+///
+/// The work item deadline arms a timer of its own, so an overdue work item
+/// raises a timeout exception as soon as its deadline passes rather than
+/// whenever the item happens to be polled. The deadline is re-read on every
+/// poll in case it was set or moved; a timeout is raised once per deadline.
+/// A timeout exlet that restarts the item resets it in place, so waiting
+/// continues on the same work item. Fails when the item is cancelled or failed.
+async fn await_work_item(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    task: &Task,
+    work_item_id: &str,
+    restarts: &mut u32,
+) -> WorkflowResult<serde_json::Value> {
+    let poll_period = std::time::Duration::from_millis(10);
+    let mut poll = tokio::time::interval_at(tokio::time::Instant::now() + poll_period, poll_period);
+    let timeout = tokio::time::sleep(std::time::Duration::ZERO);
+    tokio::pin!(timeout);
+    let mut armed_deadline = None;
+    let mut timeout_raised = false;
+    loop {
+        let deadline = engine.work_item_service.get_deadline(work_item_id).await?;
+        if deadline != armed_deadline {
+            armed_deadline = deadline;
+            timeout_raised = false;
+            if let Some(deadline) = deadline {
+                let remaining = (deadline - Utc::now()).to_std().unwrap_or_default();
+                timeout
+                    .as_mut()
+                    .reset(tokio::time::Instant::now() + remaining);
+            }
+        }
+
+        tokio::select! {
+            _ = &mut timeout, if armed_deadline.is_some() && !timeout_raised => {
+                timeout_raised = true;
+                let work_item = fetch_work_item(engine, work_item_id).await?;
+                if matches!(
+                    work_item.state,
+                    WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+                ) {
+                    continue;
+                }
+                let resolution = handle_item_exception(
+                    engine,
+                    case_id,
+                    task,
+                    work_item_id,
+                    ExceptionKind::ItemTimeout,
+                    work_item.data,
+                )
+                .await?;
+                if resolution == ExletResolution::Restart {
+                    *restarts += 1;
+                    check_restarts(task, *restarts)?;
+                }
+            }
+            _ = poll.tick() => {
+                let work_item = fetch_work_item(engine, work_item_id).await?;
+                match work_item.state {
+                    WorkItemState::Completed => return Ok(work_item.data),
+                    WorkItemState::Cancelled | WorkItemState::Failed => {
+                        let state = format!("{:?}", work_item.state).to_lowercase();
+                        return Err(WorkflowError::TaskExecutionFailed(format!(
+                            "Work item {} was {}",
+                            work_item_id, state
+                        )));
+                    }
+                    _ => {
+                        // Still open (or suspended), continue waiting
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn fetch_work_item(engine: &WorkflowEngine, work_item_id: &str) -> WorkflowResult<WorkItem> {
+    engine
+        .work_item_service
+        .get_work_item(work_item_id)
+        .await
+        .ok_or_else(|| {
+            WorkflowError::TaskExecutionFailed(format!("Work item {} not found", work_item_id))
+        })
+}
+
+/// Raise a work item exception and return how the task proceeds
+///
+/// The task fails when the exlet fails or cancels the work item, or leaves the
+/// case suspended, cancelled or failed.
+async fn handle_item_exception(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    task: &Task,
+    work_item_id: &str,
+    kind: ExceptionKind,
+    data: serde_json::Value,
+) -> WorkflowResult<ExletResolution> {
+    let report = match engine
+        .raise_exception(case_id, Some(&task.id), Some(work_item_id), kind, data)
+        .await?
+    {
+        Some(report) => report,
+        None => return Ok(ExletResolution::Proceed),
+    };
+
+    let state = engine.get_case(case_id).await?.state;
+    if state != CaseState::Running {
+        return Err(WorkflowError::TaskExecutionFailed(format!(
+            "Case {} is {} after exlet '{}' handled {} of task {}",
+            case_id, state, report.exlet, kind, task.id
+        )));
+    }
+    match report.resolution {
+        ExletResolution::Failed | ExletResolution::Cancelled => {
+            Err(WorkflowError::TaskExecutionFailed(format!(
+                "Work item {} of task {} was {:?} by exlet '{}' on {}",
+                work_item_id, task.id, report.resolution, report.exlet, kind
+            )))
+        }
+        resolution => Ok(resolution),
+    }
+}
+
+/// Merge a task result into case data
+///
+/// Tasks with declared output parameters assign only their mapped outputs
+/// (validated against the parameter types); untyped tasks merge the whole result.
+pub(super) fn merge_task_result(
+    task: &Task,
+    case_data: &mut serde_json::Value,
+    result: &serde_json::Value,
+) -> WorkflowResult<()> {
+    if !task.output_parameters.is_empty() {
+        let updates = TaskDataContract::for_task(task)?.map_outputs(result)?;
+        if let Some(case_obj) = case_data.as_object_mut() {
+            case_obj.extend(updates);
+        }
+    } else if let (Some(case_obj), Some(result_obj)) =
+        (case_data.as_object_mut(), result.as_object())
+    {
+        for (key, value) in result_obj {
+            case_obj.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(())
+}
+
 /// Produce task outputs based on declared output parameters
 /// Follows van der Aalst's formal YAWL semantics: tasks produce exactly what they declare
 fn produce_task_outputs(
@@ -0,0 +1,274 @@
+//! Specification versions and live case migration
+//!
+//! Versions of a spec family (specs sharing an IRI) are registered side by side
+//! under their own spec IDs. New cases can be pinned to the latest version,
+//! running cases stay on the spec they started with until they are explicitly
+//! migrated. A case can be migrated when its marking is compatible with the
+//! target version:
+//! - Every node holding a token (or executing) exists in the target
+//! - Running multiple instance tasks and open work items have their task in the target
+//! - The case data validates against the target's case variables
+//!
+//! A running case continues on the target version from its next routing step.
+
+use crate::case::{CaseId, CaseState};
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{WorkflowSpec, WorkflowSpecId};
+use crate::services::work_items::WorkItemState;
+use crate::snapshots::SpecVersion;
+use serde::{Deserialize, Serialize};
+
+use super::cancellation::belongs_to_task;
+use super::composite::is_finished;
+use super::WorkflowEngine;
+
+/// Compatibility of a case with a target spec version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Case ID
+    pub case_id: CaseId,
+    /// Spec the case runs on
+    pub from_spec_id: WorkflowSpecId,
+    /// Version of the spec the case runs on
+    pub from_version: Option<String>,
+    /// Target spec
+    pub to_spec_id: WorkflowSpecId,
+    /// Version of the target spec
+    pub to_version: Option<String>,
+    /// Reasons the case cannot be migrated (empty when compatible)
+    pub issues: Vec<String>,
+    /// Whether the case was migrated (false for a dry run)
+    pub migrated: bool,
+}
+
+impl MigrationReport {
+    /// Check whether the case can be migrated
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl WorkflowEngine {
+    /// List the registered versions of a spec family, oldest first
+    pub async fn workflow_versions(&self, iri: &str) -> Vec<SpecVersion> {
+        self.spec_versions.versions(iri).await
+    }
+
+    /// Create a case on the latest registered version of a spec family
+    pub async fn create_case_latest(
+        &self,
+        iri: &str,
+        data: serde_json::Value,
+    ) -> WorkflowResult<CaseId> {
+        let latest = self.spec_versions.latest(iri).await.ok_or_else(|| {
+            WorkflowError::InvalidSpecification(format!("No version of {} is registered", iri))
+        })?;
+        self.create_case(latest.spec_id, data).await
+    }
+
+    /// Check whether a case can be migrated to a target spec (dry run)
+    pub async fn check_case_migration(
+        &self,
+        case_id: CaseId,
+        target_spec_id: WorkflowSpecId,
+    ) -> WorkflowResult<MigrationReport> {
+        let case = self.get_case(case_id).await?;
+        let source = self.get_workflow(case.spec_id).await?;
+        let target = self.get_workflow(target_spec_id).await?;
+        let mut issues = Vec::new();
+
+        if case.spec_id == target.id {
+            issues.push(format!("Case already runs on spec {}", target.id));
+        }
+        if source.iri.is_none() || source.iri != target.iri {
+            issues.push(format!(
+                "Spec {} is not a version of {}",
+                target.id,
+                source.iri.as_deref().unwrap_or(&source.name)
+            ));
+        }
+        if is_finished(case.state) {
+            issues.push(format!("Case is {}", case.state));
+        }
+
+        // Every marked node must exist in the target net
+        let marking = self.markings.get(&case_id).map(|m| m.value().clone());
+        match marking {
+            Some(marking) => {
+                for node_id in marking.marked_nodes() {
+                    if !target.tasks.contains_key(&node_id)
+                        && !target.conditions.contains_key(&node_id)
+                    {
+                        issues.push(format!(
+                            "Node {} holds a token but does not exist in the target version",
+                            node_id
+                        ));
+                    }
+                }
+            }
+            None if !matches!(case.state, CaseState::Created) && !is_finished(case.state) => {
+                issues.push("Case has no marking to migrate".to_string());
+            }
+            None => {}
+        }
+
+        let mut mi_tasks: Vec<String> = self
+            .mi_tasks
+            .iter()
+            .filter(|entry| entry.key().0 == case_id)
+            .map(|entry| entry.key().1.clone())
+            .collect();
+        mi_tasks.sort();
+        for task_id in mi_tasks {
+            let still_mi = target
+                .tasks
+                .get(&task_id)
+                .is_some_and(|task| task.multi_instance.is_some());
+            if !still_mi {
+                issues.push(format!(
+                    "Multiple instance task {} is running but is not a multiple instance task in the target version",
+                    task_id
+                ));
+            }
+        }
+
+        let work_items = self
+            .work_item_service
+            .list_case_work_items(&case_id.to_string())
+            .await;
+        for work_item in work_items {
+            if matches!(
+                work_item.state,
+                WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+            ) {
+                continue;
+            }
+            let has_task = target
+                .tasks
+                .keys()
+                .any(|task_id| belongs_to_task(&work_item.task_id, task_id));
+            if !has_task {
+                issues.push(format!(
+                    "Work item {} of task {} has no task in the target version",
+                    work_item.id, work_item.task_id
+                ));
+            }
+        }
+
+        let schema = target.data_schema();
+        let mut data = self.stored_case_data(case_id).await?;
+        schema.apply_defaults(&mut data);
+        if let Err(violations) = schema.validate(&data) {
+            issues.extend(violations.iter().map(|v| format!("Case data {}", v)));
+        }
+
+        Ok(MigrationReport {
+            case_id,
+            from_spec_id: source.id,
+            from_version: source.version,
+            to_spec_id: target.id,
+            to_version: target.version,
+            issues,
+            migrated: false,
+        })
+    }
+
+    /// Migrate cases to another version of their specification
+    ///
+    /// Each case is checked first (see [`WorkflowEngine::check_case_migration`]);
+    /// compatible cases are moved to the target spec, incompatible ones are left
+    /// on their version and reported with their issues.
+    pub async fn migrate_cases(
+        &self,
+        case_ids: &[CaseId],
+        target_spec_id: WorkflowSpecId,
+    ) -> WorkflowResult<Vec<MigrationReport>> {
+        let target = self.get_workflow(target_spec_id).await?;
+        self.specs
+            .entry(target.id)
+            .or_insert_with(|| target.clone());
+
+        let mut reports = Vec::with_capacity(case_ids.len());
+        for &case_id in case_ids {
+            let mut report = self.check_case_migration(case_id, target_spec_id).await?;
+            if report.is_compatible() {
+                self.apply_migration(case_id, &target).await?;
+                report.migrated = true;
+                tracing::info!(
+                    "Migrated case {} from spec {} to spec {}",
+                    case_id,
+                    report.from_spec_id,
+                    report.to_spec_id
+                );
+            }
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Record a spec being registered as a version of its family
+    ///
+    /// Running cases stay on the spec they started with, so a spec ID with
+    /// unfinished cases can only be re-registered unchanged.
+    pub(super) async fn record_spec_version(&self, spec: &WorkflowSpec) -> WorkflowResult<()> {
+        let changed = self.specs.get(&spec.id).is_some_and(|registered| {
+            serde_json::to_value(registered.value()).ok() != serde_json::to_value(spec).ok()
+        });
+        if changed {
+            let unfinished = self
+                .cases
+                .iter()
+                .filter(|case| case.spec_id == spec.id && !is_finished(case.state))
+                .count();
+            if unfinished > 0 {
+                return Err(WorkflowError::Validation(format!(
+                    "Spec {} has {} unfinished case(s); register the change as a new version",
+                    spec.id, unfinished
+                )));
+            }
+        }
+        self.spec_versions.record(spec).await.map(|_| ())
+    }
+
+    /// Move a compatible case to the target spec
+    async fn apply_migration(&self, case_id: CaseId, target: &WorkflowSpec) -> WorkflowResult<()> {
+        let mut case = self.get_case(case_id).await?;
+        let from_spec_id = case.spec_id;
+        case.spec_id = target.id;
+        case.data = self.stored_case_data(case_id).await?;
+        target.data_schema().apply_defaults(&mut case.data);
+
+        {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).save_case(case_id, &case)?;
+        }
+        self.state_manager.save_case(&case).await?;
+        if let Some(mut live) = self.cases.get_mut(&case_id) {
+            live.spec_id = target.id;
+            live.data = case.data.clone();
+        }
+        if let Some(mut marking) = self.markings.get_mut(&case_id) {
+            marking.migrate(target);
+        }
+
+        self.state_manager
+            .log_case_migrated(case_id, from_spec_id, target.id)
+            .await?;
+        self.checkpoint_case(case_id).await
+    }
+
+    /// Case data as last written by its tasks
+    pub(super) async fn stored_case_data(
+        &self,
+        case_id: CaseId,
+    ) -> WorkflowResult<serde_json::Value> {
+        let stored = {
+            let store_arc = self.state_store.read().await;
+            (*store_arc).load_case(&case_id)?
+        };
+        match stored {
+            Some(case) => Ok(case.data),
+            None => Ok(self.get_case(case_id).await?.data),
+        }
+    }
+}
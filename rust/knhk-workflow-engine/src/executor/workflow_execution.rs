@@ -4,11 +4,15 @@
 //! Inputs pre-validated at ingress.
 
 use crate::case::{CaseId, CaseState};
+use crate::data::expression::evaluate_predicate;
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::parser::{Flow, JoinType, SplitType, Task, TaskType, WorkflowSpec};
 use crate::patterns::{PatternExecutionContext, PatternId};
-use std::collections::{HashMap, HashSet, VecDeque};
+use crate::state::recovery::CaseMarking;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
+use super::cancellation::apply_cancellation;
 use super::task::execute_task_with_allocation;
 use super::WorkflowEngine;
 
@@ -57,68 +61,6 @@ pub(crate) fn identify_task_pattern(task: &Task) -> PatternId {
         })
 }
 
-/// Evaluate a predicate against case data
-/// Supports: "variable == value", "variable >= value", "variable <= value" formats
-fn evaluate_predicate(predicate: &str, case_data: &serde_json::Value) -> bool {
-    let predicate = predicate.trim();
-
-    // Handle "variable >= value" pattern (e.g., "balance >= withdrawalAmount")
-    if let Some(ge_pos) = predicate.find(">=") {
-        let left_var = predicate[..ge_pos].trim();
-        let right_var = predicate[ge_pos + 2..].trim();
-
-        let left_value = case_data.get(left_var).and_then(|v| v.as_f64());
-        let right_value = case_data.get(right_var).and_then(|v| v.as_f64());
-
-        if let (Some(left), Some(right)) = (left_value, right_value) {
-            return left >= right;
-        }
-        return false;
-    }
-
-    // Handle "variable <= value" pattern
-    if let Some(le_pos) = predicate.find("<=") {
-        let left_var = predicate[..le_pos].trim();
-        let right_var = predicate[le_pos + 2..].trim();
-
-        let left_value = case_data.get(left_var).and_then(|v| v.as_f64());
-        let right_value = case_data.get(right_var).and_then(|v| v.as_f64());
-
-        if let (Some(left), Some(right)) = (left_value, right_value) {
-            return left <= right;
-        }
-        return false;
-    }
-
-    // Handle "variable == value" pattern
-    if let Some(eq_pos) = predicate.find("==") {
-        let var_name = predicate[..eq_pos].trim();
-        let expected_value = predicate[eq_pos + 2..].trim();
-
-        if let Some(actual_value) = case_data.get(var_name) {
-            match expected_value {
-                "true" => actual_value.as_bool() == Some(true),
-                "false" => actual_value.as_bool() == Some(false),
-                _ => {
-                    if let Some(actual_str) = actual_value.as_str() {
-                        actual_str == expected_value.trim_matches('"')
-                    } else if let (Some(actual_num), Ok(expected_num)) =
-                        (actual_value.as_f64(), expected_value.parse::<f64>())
-                    {
-                        (actual_num - expected_num).abs() < f64::EPSILON
-                    } else {
-                        false
-                    }
-                }
-            }
-        } else {
-            false
-        }
-    } else {
-        false
-    }
-}
-
 /// Execute a workflow from start to end condition
 pub(super) fn execute_workflow<'a>(
     engine: &'a WorkflowEngine,
@@ -126,57 +68,41 @@ pub(super) fn execute_workflow<'a>(
     spec: &'a WorkflowSpec,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = WorkflowResult<()>> + Send + 'a>> {
     Box::pin(async move {
-        let start_condition_id = spec
-            .start_condition
-            .as_ref()
-            .ok_or_else(|| WorkflowError::InvalidSpecification("No start condition".into()))?;
-
-        let end_condition_id = spec
-            .end_condition
-            .as_ref()
-            .ok_or_else(|| WorkflowError::InvalidSpecification("No end condition".into()))?;
-
-        // Track visited nodes to prevent infinite loops
-        let mut visited = HashSet::new();
-        // Queue of nodes to process (BFS traversal)
-        let mut queue: VecDeque<String> = VecDeque::new();
-        queue.push_back(start_condition_id.clone());
-
-        // Track how many incoming flows each task has received
-        // For AND join: task needs all incoming flows
-        // For XOR join: task needs only one incoming flow
-        // For OR join: task needs all active incoming flows (all that were enabled by OR-split)
-        let mut task_incoming_count: HashMap<String, usize> = HashMap::new();
-        for (task_id, task) in &spec.tasks {
-            let required_count = if task.incoming_flows.is_empty() {
-                0 // Task with no incoming flows (starts from start condition) is ready immediately
-            } else if matches!(task.join_type, crate::parser::JoinType::And) {
-                task.incoming_flows.len() // AND join needs all
-            } else if matches!(task.join_type, crate::parser::JoinType::Or) {
-                // OR join: needs all active branches (will be calculated dynamically)
-                // For now, we'll track which branches are active and wait for all of them
-                task.incoming_flows.len() // Will be adjusted based on active branches
-            } else {
-                1 // XOR join needs at least one
-            };
-            task_incoming_count.insert(task_id.clone(), required_count);
-        }
-
-        // Track active branches for OR joins (which branches were enabled by OR-splits)
-        let mut or_join_active_branches: HashMap<String, HashSet<String>> = HashMap::new();
+        let marking = CaseMarking::initial(spec)?;
+        resume_workflow(engine, case_id, spec, marking, HashSet::new()).await
+    })
+}
 
-        // Track how many incoming flows each task has actually received
-        let mut task_received_count: HashMap<String, usize> = HashMap::new();
-        for task_id in spec.tasks.keys() {
-            task_received_count.insert(task_id.clone(), 0);
+/// Execute a workflow from a (possibly recovered) marking until the end condition
+///
+/// Tasks in `already_completed` finished before a crash but were not yet routed:
+/// their tokens are routed without executing the task again. If the case is
+/// migrated to another spec version while running, execution continues on the
+/// target version from the next routing step.
+pub(super) fn resume_workflow<'a>(
+    engine: &'a WorkflowEngine,
+    case_id: CaseId,
+    spec: &'a WorkflowSpec,
+    mut marking: CaseMarking,
+    mut already_completed: HashSet<String>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = WorkflowResult<()>> + Send + 'a>> {
+    Box::pin(async move {
+        let mut spec = Cow::Borrowed(spec);
+        if spec.end_condition.is_none() {
+            return Err(WorkflowError::InvalidSpecification(
+                "No end condition".into(),
+            ));
         }
 
-        // Track completed tasks
-        let mut completed_tasks = HashSet::new();
+        // Checkpoint the starting marking so a crash before the first task can be recovered
+        engine.markings.insert(case_id, marking.clone());
+        engine.checkpoint_case(case_id).await?;
+
+        while let Some(node_id) = marking.pending.pop_front() {
+            follow_migration(engine, case_id, &mut spec, &mut marking);
 
-        while let Some(node_id) = queue.pop_front() {
             // Check if we've reached the end condition
-            if node_id == *end_condition_id {
+            if spec.end_condition.as_deref() == Some(node_id.as_str()) {
                 // Mark case as completed
                 let mut case_ref = engine
                     .cases
@@ -194,33 +120,47 @@ pub(super) fn execute_workflow<'a>(
                 // Save to state manager
                 engine.state_manager.save_case(&case_clone).await?;
 
+                engine.markings.remove(&case_id);
                 return Ok(());
             }
 
             // Skip if already visited (for conditions)
-            if !visited.insert(node_id.clone()) {
+            if !marking.visited.insert(node_id.clone()) {
                 continue;
             }
 
             // Check if this is a task or condition
-            if let Some(task) = spec.tasks.get(&node_id) {
-                // Check if task is ready to execute
-                if !completed_tasks.contains(&node_id) {
-                    let required = task_incoming_count.get(&node_id).copied().unwrap_or(0);
-                    let received = task_received_count.get(&node_id).copied().unwrap_or(0);
-
-                    if required > 0 && received < required {
-                        // Task not ready yet - needs more incoming flows
-                        continue;
-                    }
-                } else {
-                    // Task already completed - skip
+            if spec.tasks.contains_key(&node_id) {
+                // Skip completed tasks and tasks not ready yet (need more incoming flows)
+                if marking.completed_tasks.contains(&node_id) || !marking.is_enabled(&node_id) {
                     continue;
                 }
 
-                // Execute the task (actual work)
-                execute_task_with_allocation(engine, case_id, spec.id, task).await?;
-                completed_tasks.insert(node_id.clone());
+                // Execute the task (actual work), unless it already completed before a crash
+                marking.executing.insert(node_id.clone());
+                engine.markings.insert(case_id, marking.clone());
+                if already_completed.remove(&node_id) {
+                    tracing::debug!(
+                        "Task {} of case {} completed before recovery, routing without re-execution",
+                        node_id,
+                        case_id
+                    );
+                } else {
+                    let task = &spec.tasks[&node_id];
+                    execute_task_with_allocation(engine, case_id, spec.id, task).await?;
+                }
+                marking.executing.remove(&node_id);
+                marking.completed_tasks.insert(node_id.clone());
+
+                // Route with the version the case runs on now (migration
+                // keeps the tasks holding a token)
+                follow_migration(engine, case_id, &mut spec, &mut marking);
+                let task = spec.tasks.get(&node_id).ok_or_else(|| {
+                    WorkflowError::Internal(format!(
+                        "Task {} missing after migration of case {}",
+                        node_id, case_id
+                    ))
+                })?;
 
                 // Van der Aalst Pattern-Based Execution:
                 // Use pre-compiled pattern ID (TRIZ Principle 10: Prior Action)
@@ -268,7 +208,7 @@ pub(super) fn execute_workflow<'a>(
                 let mut enabled_flows = Vec::new();
                 for flow in &outgoing_flows {
                     let flow_enabled = if let Some(predicate) = &flow.predicate {
-                        evaluate_predicate(predicate, case_data)
+                        evaluate_predicate(predicate, case_data)?
                     } else {
                         true // No predicate - flow is always enabled
                     };
@@ -313,52 +253,38 @@ pub(super) fn execute_workflow<'a>(
                     }
                 };
 
-                // Process enabled flows
-                for flow in flows_to_take {
-                    let target_id = &flow.to;
-
-                    // If target is a task, increment received count
-                    if let Some(target_task) = spec.tasks.get(target_id) {
-                        // For OR joins, track which branches are active
-                        if matches!(target_task.join_type, crate::parser::JoinType::Or) {
-                            let active = or_join_active_branches
-                                .entry(target_id.clone())
-                                .or_default();
-                            active.insert(node_id.clone()); // Mark this branch as active
-
-                            // Update required count for OR join: need all active branches
-                            let required = active.len();
-                            task_incoming_count.insert(target_id.clone(), required);
-                        }
-
-                        let received = task_received_count.entry(target_id.clone()).or_insert(0);
-                        *received += 1;
-
-                        let required = task_incoming_count.get(target_id).copied().unwrap_or(0);
-                        // Add to queue if ready (received enough incoming flows)
-                        if *received >= required {
-                            queue.push_back(target_id.clone());
-                        }
-                    } else {
-                        // Target is a condition - add to queue
-                        queue.push_back(target_id.clone());
+                // Apply the cancellation region (and activities cancelled by patterns 19-25)
+                // before producing output tokens
+                let mut region = task.cancellation.clone().unwrap_or_default();
+                for activity_id in &pattern_result.cancel_activities {
+                    if spec.tasks.contains_key(activity_id) && !region.targets.contains(activity_id)
+                    {
+                        tracing::debug!(
+                            "Pattern {} requested cancellation of activity {}",
+                            pattern_id.0,
+                            activity_id
+                        );
+                        region.targets.push(activity_id.clone());
                     }
                 }
-
-                // Handle cancellation patterns (19-25) if pattern result indicates cancellation
-                if !pattern_result.cancel_activities.is_empty() {
-                    for activity_id in &pattern_result.cancel_activities {
-                        // Mark activity as cancelled
-                        if let Some(_cancelled_task) = spec.tasks.get(activity_id) {
-                            // In production, would cancel the task and clean up resources
-                            tracing::debug!(
-                                "Pattern {} requested cancellation of activity {}",
-                                pattern_id.0,
-                                activity_id
-                            );
-                        }
+                if task.cancellation.is_some() || !region.targets.is_empty() {
+                    let case_cancelled =
+                        apply_cancellation(engine, case_id, &node_id, &region, &mut marking)
+                            .await?;
+                    if case_cancelled {
+                        engine.markings.remove(&case_id);
+                        return Ok(());
                     }
                 }
+
+                // Process enabled flows
+                for flow in flows_to_take {
+                    marking.produce(&spec, &node_id, &flow.to);
+                }
+
+                // Checkpoint marking after routing (durable point for crash recovery)
+                engine.markings.insert(case_id, marking.clone());
+                engine.checkpoint_case(case_id).await?;
             } else if let Some(_condition) = spec.conditions.get(&node_id) {
                 // Condition - find outgoing flows
                 let outgoing_flows: Vec<&Flow> =
@@ -370,47 +296,19 @@ pub(super) fn execute_workflow<'a>(
 
                 for flow in &outgoing_flows {
                     let flow_enabled = if let Some(predicate) = &flow.predicate {
-                        evaluate_predicate(predicate, case_data)
+                        evaluate_predicate(predicate, case_data)?
                     } else {
                         true // No predicate - flow is always enabled
                     };
 
                     if flow_enabled {
-                        let target_id = &flow.to;
-
-                        // If target is a task, increment received count
-                        if let Some(target_task) = spec.tasks.get(target_id) {
-                            // For OR joins, track which branches are active
-                            if matches!(target_task.join_type, crate::parser::JoinType::Or) {
-                                let active = or_join_active_branches
-                                    .entry(target_id.clone())
-                                    .or_default();
-                                active.insert(node_id.clone()); // Mark this branch as active
-
-                                // Update required count for OR join: need all active branches
-                                let required = active.len();
-                                task_incoming_count.insert(target_id.clone(), required);
-                            }
-
-                            let received =
-                                task_received_count.entry(target_id.clone()).or_insert(0);
-                            *received += 1;
-
-                            let required = task_incoming_count.get(target_id).copied().unwrap_or(0);
-                            // Add to queue if ready (received enough incoming flows)
-                            if *received >= required {
-                                queue.push_back(target_id.clone());
-                            }
-                        } else {
-                            // Target is a condition - add to queue
-                            queue.push_back(target_id.clone());
-                        }
+                        marking.produce(&spec, &node_id, &flow.to);
                     }
                 }
             }
 
             // Safety: prevent infinite loops
-            if visited.len() > 1000 {
+            if marking.visited.len() > 1000 {
                 return Err(WorkflowError::Internal(
                     "Workflow execution exceeded maximum iterations (possible cycle)".to_string(),
                 ));
@@ -419,12 +317,32 @@ pub(super) fn execute_workflow<'a>(
 
         // If we didn't reach the end condition, the workflow didn't complete
         // This could be due to predicates blocking all paths
-        let case = engine.get_case(case_id).await?;
-        if case.state != CaseState::Completed {
-            // Workflow didn't complete - leave in Running state
-            // (could be waiting for external event, milestone, etc.)
-        }
+        // Workflow didn't complete - leave in Running state
+        // (could be waiting for external event, milestone, etc.)
+        engine.markings.insert(case_id, marking);
 
         Ok(())
     })
 }
+
+/// Switch to the spec version a running case was migrated to
+fn follow_migration(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    spec: &mut Cow<'_, WorkflowSpec>,
+    marking: &mut CaseMarking,
+) {
+    let migrated_to = match engine.cases.get(&case_id) {
+        Some(case) if case.spec_id != spec.id => case.spec_id,
+        _ => return,
+    };
+    if let Some(target) = engine.specs.get(&migrated_to) {
+        tracing::debug!(
+            "Case {} continues on migrated spec {}",
+            case_id,
+            migrated_to
+        );
+        marking.migrate(target.value());
+        *spec = Cow::Owned(target.value().clone());
+    }
+}
@@ -6,6 +6,7 @@
 //! Patterns are pre-compiled at registration time to avoid runtime overhead,
 //! enabling ≤8 tick hot path execution.
 
+use crate::data::contract::validate_spec_data_model;
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::parser::{JoinType, SplitType, Task, TaskType, WorkflowSpec};
 use crate::patterns::PatternId;
@@ -16,11 +17,16 @@ use knhk_otel::SpanStatus;
 use std::time::Instant;
 
 use super::engine::WorkflowEngine;
+use super::messaging::validate_message_catches;
 use super::workflow_execution::identify_task_pattern;
 
 impl WorkflowEngine {
     /// Register a workflow specification with deadlock validation and Fortune 5 checks
-    pub async fn register_workflow(&self, spec: WorkflowSpec) -> WorkflowResult<()> {
+    ///
+    /// Sub-nets defined with the spec are registered first; composite tasks
+    /// must decompose to a registered sub-net. Specs with an IRI and a version
+    /// are registered side by side with the other versions of their family.
+    pub async fn register_workflow(&self, mut spec: WorkflowSpec) -> WorkflowResult<()> {
         let start_time = Instant::now();
 
         // Start OTEL span for workflow registration
@@ -58,9 +64,23 @@ impl WorkflowEngine {
             }
         }
 
-        // Validate for deadlocks before registration
+        // Register sub-nets first so composite tasks can be resolved
+        let mut subnet_result = Ok(());
+        for subnet in std::mem::take(&mut spec.subnets) {
+            subnet_result = Box::pin(self.register_workflow(subnet)).await;
+            if subnet_result.is_err() {
+                break;
+            }
+        }
+
+        // Validate for deadlocks, type-check the data model and resolve
+        // sub-nets before registration
         let detector = DeadlockDetector;
-        let validation_result = detector.validate(&spec);
+        let validation_result = subnet_result
+            .and_then(|_| detector.validate(&spec))
+            .and_then(|_| validate_spec_data_model(&spec))
+            .and_then(|_| self.validate_decompositions(&spec))
+            .and_then(|_| validate_message_catches(&spec));
 
         if let Err(e) = validation_result {
             if let (Some(ref otel), Some(ref span)) =
@@ -101,6 +121,22 @@ impl WorkflowEngine {
         // This eliminates runtime pattern identification overhead, enabling ≤8 tick hot path
         let mut spec = compile_patterns(spec);
 
+        // Record the version; running cases keep the spec they started with
+        if let Err(e) = self.record_spec_version(&spec).await {
+            if let (Some(ref otel), Some(ref span)) =
+                (self.otel_integration.as_ref(), span_ctx.as_ref())
+            {
+                otel_span_end!(
+                    otel,
+                    span_ctx,
+                    success: false,
+                    start_time: start_time
+                )
+                .await?;
+            }
+            return Err(e);
+        }
+
         let spec_clone = spec.clone();
         self.specs.insert(spec.id, spec);
 
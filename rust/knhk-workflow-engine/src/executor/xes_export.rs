@@ -161,6 +161,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         // Register workflow
@@ -198,6 +202,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         engine.register_workflow(spec.clone()).await.unwrap();
@@ -249,6 +257,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         engine.register_workflow(spec.clone()).await.unwrap();
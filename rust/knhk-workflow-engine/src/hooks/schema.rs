@@ -372,6 +372,10 @@ wf:Task a rdf:Class .
                         required_roles: Vec::new(),
                         required_capabilities: Vec::new(),
                         exception_worklet: None,
+                        multi_instance: None,
+                        cancellation: None,
+                        decomposition: None,
+                        message: None,
                     },
                 );
                 tasks.insert(
@@ -395,6 +399,10 @@ wf:Task a rdf:Class .
                         required_roles: Vec::new(),
                         required_capabilities: Vec::new(),
                         exception_worklet: None,
+                        multi_instance: None,
+                        cancellation: None,
+                        decomposition: None,
+                        message: None,
                     },
                 );
                 tasks
@@ -404,6 +412,10 @@ wf:Task a rdf:Class .
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         let result = registry
@@ -181,6 +181,10 @@ fn parse_rdf_to_spec(rdf: &str) -> WorkflowResult<WorkflowSpec> {
         start_condition: None,
         end_condition: None,
         source_turtle: Some(rdf.to_string()),
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     })
 }
 
@@ -68,9 +68,11 @@ impl LegacyFacade {
         }
 
         // Check for blocking capabilities
-        if task.required_capabilities.iter().any(|cap| {
-            cap.contains("io") || cap.contains("network") || cap.contains("database")
-        }) {
+        if task
+            .required_capabilities
+            .iter()
+            .any(|cap| cap.contains("io") || cap.contains("network") || cap.contains("database"))
+        {
             return false;
         }
 
@@ -132,7 +134,7 @@ mod tests {
     #[test]
     fn test_promoteable_check() {
         let facade = LegacyFacade::new();
-        
+
         let mut spec = crate::parser::WorkflowSpec {
             id: crate::parser::WorkflowSpecId::new(),
             name: "Test Workflow".to_string(),
@@ -160,6 +162,10 @@ mod tests {
             required_roles: Vec::new(),
             required_capabilities: Vec::new(),
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         };
 
         spec.tasks.insert("task1".to_string(), task);
@@ -170,7 +176,7 @@ mod tests {
     #[test]
     fn test_non_promoteable_check() {
         let facade = LegacyFacade::new();
-        
+
         let mut spec = crate::parser::WorkflowSpec {
             id: crate::parser::WorkflowSpecId::new(),
             name: "Test Workflow".to_string(),
@@ -198,6 +204,10 @@ mod tests {
             required_roles: Vec::new(),
             required_capabilities: Vec::new(),
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         };
 
         spec.tasks.insert("task1".to_string(), task);
@@ -205,4 +215,3 @@ mod tests {
         assert!(!facade.promoteable(&spec));
     }
 }
-
@@ -134,6 +134,8 @@ pub mod resource;
 pub mod security;
 pub mod self_validation;
 pub mod services;
+/// Discrete-event what-if simulation
+pub mod simulation;
 pub mod state;
 pub mod templates;
 pub mod testing;
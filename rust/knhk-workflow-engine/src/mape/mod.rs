@@ -283,7 +283,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_mape_k_engine_creation() {
-        let receipt_store = Arc::new(ReceiptStore::in_memory());
+        let receipt_store = Arc::new(ReceiptStore::in_memory().unwrap());
         let snapshot_versioning = Arc::new(SnapshotVersioning::new("./test_snapshots"));
         let hook_engine = Arc::new(HookEngine::new());
         let invariant_checker = Arc::new(InvariantChecker::new());
@@ -84,7 +84,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_monitor_collects_observations() {
-        let receipt_store = Arc::new(ReceiptStore::in_memory());
+        let receipt_store = Arc::new(ReceiptStore::in_memory().unwrap());
         let knowledge = Arc::new(RwLock::new(KnowledgeBase::new()));
         let monitor = MonitorPhase::new(receipt_store.clone(), knowledge);
 
@@ -51,7 +51,7 @@ impl SelfExecutingOrchestrator {
     /// Create a new self-executing orchestrator
     pub fn new(snapshot_dir: &str, receipt_dir: &str) -> WorkflowResult<Self> {
         // Create receipt store
-        let receipt_store = Arc::new(ReceiptStore::new());
+        let receipt_store = Arc::new(ReceiptStore::new()?);
 
         // Create snapshot versioning
         let snapshot_versioning = Arc::new(SnapshotVersioning::new());
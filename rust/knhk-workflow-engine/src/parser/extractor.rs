@@ -13,6 +13,10 @@ use oxigraph::store::Store;
 use std::collections::HashMap;
 
 /// Extract workflow specification from RDF store
+///
+/// Documents may define several specifications: the root is the first one no
+/// composite task decomposes to, the others are returned as its `subnets` with
+/// composite tasks linked to them by spec ID.
 #[cfg(feature = "rdf")]
 pub fn extract_workflow_spec(store: &Store) -> WorkflowResult<WorkflowSpec> {
     // YAWL namespace prefixes
@@ -25,13 +29,14 @@ pub fn extract_workflow_spec(store: &Store) -> WorkflowResult<WorkflowSpec> {
         "PREFIX yawl: <{}>\n\
          PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>\n\
          PREFIX rdfs: <{}>\n\
-         SELECT ?spec ?name WHERE {{\n\
+         SELECT ?spec ?name ?version WHERE {{\n\
            {{ ?spec rdf:type yawl:Specification . }}\n\
            UNION\n\
            {{ ?spec rdf:type yawl:WorkflowSpecification . }}\n\
            OPTIONAL {{ ?spec rdfs:label ?name }}\n\
            OPTIONAL {{ ?spec yawl:specName ?name }}\n\
-         }} LIMIT 1",
+           OPTIONAL {{ ?spec yawl:versionNumber ?version }}\n\
+         }}",
         yawl_ns, rdfs
     );
 
@@ -43,57 +48,159 @@ pub fn extract_workflow_spec(store: &Store) -> WorkflowResult<WorkflowSpec> {
         .execute()
         .map_err(|e| WorkflowError::Parse(format!("SPARQL query failed: {}", e)))?;
 
-    let spec_id = WorkflowSpecId::new();
-    let mut spec_name = "Parsed Workflow".to_string();
-    let mut spec_iri: Option<String> = None;
-
-    // Extract workflow spec IRI and name
+    // Extract workflow spec IRIs, names and versions (in document order)
+    let mut headers: Vec<SpecHeader> = Vec::new();
     if let oxigraph::sparql::QueryResults::Solutions(solutions) = query_results {
         for solution in solutions {
             let solution = solution.map_err(|e| {
                 WorkflowError::Parse(format!("Failed to process query solution: {:?}", e))
             })?;
 
-            if let Some(spec_term) = solution.get("spec") {
-                spec_iri = Some(spec_term.to_string());
-            }
+            let spec_iri = match solution.get("spec") {
+                Some(spec_term) => spec_term.to_string(),
+                None => continue,
+            };
+            let index = match headers.iter().position(|h| h.iri == spec_iri) {
+                Some(index) => index,
+                None => {
+                    headers.push(SpecHeader {
+                        iri: spec_iri,
+                        name: None,
+                        version: None,
+                    });
+                    headers.len() - 1
+                }
+            };
             if let Some(oxigraph::model::Term::Literal(lit)) = solution.get("name") {
-                spec_name = lit.value().to_string();
+                headers[index].name = Some(lit.value().to_string());
+            }
+            if let Some(oxigraph::model::Term::Literal(lit)) = solution.get("version") {
+                headers[index].version = Some(lit.value().to_string());
+            }
+        }
+    }
+
+    if headers.len() <= 1 {
+        return extract_net(store, yawl_ns, headers.pop().as_ref());
+    }
+
+    // Root: the first specification no composite task decomposes to
+    let referenced = find_decomposition_targets(store, yawl_ns)?;
+    let root_index = headers
+        .iter()
+        .position(|h| !referenced.contains(clean_iri(&h.iri)))
+        .unwrap_or(0);
+
+    let mut nets = headers
+        .iter()
+        .map(|header| extract_net(store, yawl_ns, Some(header)))
+        .collect::<WorkflowResult<Vec<_>>>()?;
+
+    // Link composite tasks to the sub-nets defined in this document
+    let versions: Vec<(String, Option<String>, WorkflowSpecId)> = nets
+        .iter()
+        .filter_map(|net| Some((net.iri.clone()?, net.version.clone(), net.id)))
+        .collect();
+    for net in &mut nets {
+        for task in net.tasks.values_mut() {
+            if let Some(ref mut decomposition) = task.decomposition {
+                let local = versions.iter().find(|(iri, version, _)| {
+                    decomposition.iri.as_ref() == Some(iri)
+                        && (decomposition.version.is_none() || decomposition.version == *version)
+                });
+                if let Some((_, _, spec_id)) = local {
+                    decomposition.spec_id = Some(*spec_id);
+                }
             }
         }
     }
 
+    let mut root = nets.remove(root_index);
+    root.subnets = nets;
+    Ok(root)
+}
+
+/// Specification header (IRI, name and version) found in the RDF store
+struct SpecHeader {
+    iri: String,
+    name: Option<String>,
+    version: Option<String>,
+}
+
+/// Strip the angle brackets of an IRI term
+fn clean_iri(iri: &str) -> &str {
+    iri.trim().trim_start_matches('<').trim_end_matches('>')
+}
+
+/// Extract a single net (tasks, conditions and flows) of a specification
+fn extract_net(
+    store: &Store,
+    yawl_ns: &str,
+    header: Option<&SpecHeader>,
+) -> WorkflowResult<WorkflowSpec> {
+    let spec_iri = header.map(|h| h.iri.as_str());
+
     // Extract tasks
-    let mut tasks = extract_tasks(store, yawl_ns, spec_iri.as_deref())?;
+    let mut tasks = extract_tasks(store, yawl_ns, spec_iri)?;
 
     // Extract conditions
-    let mut conditions = extract_conditions(store, yawl_ns, spec_iri.as_deref())?;
+    let mut conditions = extract_conditions(store, yawl_ns, spec_iri)?;
 
     // Extract flows
-    let flows = extract_flows(
-        store,
-        yawl_ns,
-        spec_iri.as_deref(),
-        &mut tasks,
-        &mut conditions,
-    )?;
+    let flows = extract_flows(store, yawl_ns, spec_iri, &mut tasks, &mut conditions)?;
 
     // Find start and end conditions
-    let start_condition = find_start_condition(store, yawl_ns, spec_iri.as_deref())?;
-    let end_condition = find_end_condition(store, yawl_ns, spec_iri.as_deref())?;
+    let start_condition = find_start_condition(store, yawl_ns, spec_iri)?;
+    let end_condition = find_end_condition(store, yawl_ns, spec_iri)?;
 
     Ok(WorkflowSpec {
-        id: spec_id,
-        name: spec_name,
+        id: WorkflowSpecId::new(),
+        name: header
+            .and_then(|h| h.name.clone())
+            .unwrap_or_else(|| "Parsed Workflow".to_string()),
         tasks,
         conditions,
         flows,
         start_condition,
         end_condition,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: spec_iri.map(|iri| clean_iri(iri).to_string()),
+        version: header.and_then(|h| h.version.clone()),
+        subnets: Vec::new(),
     })
 }
 
+/// Find the IRIs of all nets composite tasks decompose to
+fn find_decomposition_targets(
+    store: &Store,
+    yawl_ns: &str,
+) -> WorkflowResult<std::collections::HashSet<String>> {
+    let query = format!(
+        "PREFIX yawl: <{}>\n\
+         SELECT DISTINCT ?net WHERE {{ ?task yawl:hasDecomposesTo ?net }}",
+        yawl_ns
+    );
+
+    #[allow(deprecated)]
+    let query_results = store
+        .query(&query)
+        .map_err(|e| WorkflowError::Parse(format!("Failed to query decompositions: {:?}", e)))?;
+
+    let mut targets = std::collections::HashSet::new();
+    if let oxigraph::sparql::QueryResults::Solutions(solutions) = query_results {
+        for solution in solutions {
+            let solution = solution.map_err(|e| {
+                WorkflowError::Parse(format!("Failed to process decomposition solution: {:?}", e))
+            })?;
+            if let Some(oxigraph::model::Term::NamedNode(net)) = solution.get("net") {
+                targets.insert(net.as_str().to_string());
+            }
+        }
+    }
+    Ok(targets)
+}
+
 /// Extract tasks from RDF store
 pub fn extract_tasks(
     store: &Store,
@@ -281,6 +388,26 @@ pub fn extract_tasks(
             let input_parameters = extract_task_parameters(store, yawl_ns, &task_id, true)?;
             let output_parameters = extract_task_parameters(store, yawl_ns, &task_id, false)?;
 
+            // Extract multiple instance configuration
+            let multi_instance = if task_type == TaskType::MultipleInstance {
+                Some(extract_multi_instance(store, yawl_ns, &task_id)?)
+            } else {
+                None
+            };
+
+            // Extract cancellation region
+            let cancellation = extract_cancellation(store, yawl_ns, &task_id)?;
+
+            // Extract the sub-net reference of composite tasks
+            let decomposition = if task_type == TaskType::Composite {
+                extract_decomposition(store, yawl_ns, &task_id)?
+            } else {
+                None
+            };
+
+            // Extract the message the task waits for
+            let message = extract_message_catch(store, yawl_ns, &task_id)?;
+
             let task = Task {
                 id: task_id.clone(),
                 name: task_name,
@@ -301,6 +428,10 @@ pub fn extract_tasks(
                 required_roles: Vec::new(),
                 required_capabilities: Vec::new(),
                 exception_worklet: None,
+                multi_instance,
+                cancellation,
+                decomposition,
+                message,
             };
 
             tasks.insert(task_id, task);
@@ -311,6 +442,10 @@ pub fn extract_tasks(
 }
 
 /// Extract task parameters (input or output) from RDF store
+///
+/// Besides `yawl:paramName`, `yawl:paramType`, `yawl:mapping` and `yawl:required`,
+/// reads the form annotations `rdfs:label`, `rdfs:comment`, `yawl:allowedValue`
+/// (repeatable), `yawl:displayOrder` and `yawl:widget`.
 fn extract_task_parameters(
     store: &Store,
     yawl_ns: &str,
@@ -333,10 +468,18 @@ fn extract_task_parameters(
     let query = format!(
         "PREFIX yawl: <{}>\n\
          PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>\n\
-         SELECT ?paramName ?paramType WHERE {{\n\
+         PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>\n\
+         SELECT ?param ?paramName ?paramType ?mapping ?required ?label ?comment ?allowed ?order ?widget WHERE {{\n\
            <{}> <{}> ?param .\n\
            ?param yawl:paramName ?paramName .\n\
            OPTIONAL {{ ?param yawl:paramType ?paramType }}\n\
+           OPTIONAL {{ ?param yawl:mapping ?mapping }}\n\
+           OPTIONAL {{ ?param yawl:required ?required }}\n\
+           OPTIONAL {{ ?param rdfs:label ?label }}\n\
+           OPTIONAL {{ ?param rdfs:comment ?comment }}\n\
+           OPTIONAL {{ ?param yawl:allowedValue ?allowed }}\n\
+           OPTIONAL {{ ?param yawl:displayOrder ?order }}\n\
+           OPTIONAL {{ ?param yawl:widget ?widget }}\n\
          }}",
         yawl_ns, task_id_clean, param_property_iri
     );
@@ -346,7 +489,19 @@ fn extract_task_parameters(
         .query(&query)
         .map_err(|e| WorkflowError::Parse(format!("Failed to query task parameters: {:?}", e)))?;
 
-    let mut parameters = Vec::new();
+    let literal = |solution: &oxigraph::sparql::QuerySolution, name: &str| {
+        solution.get(name).and_then(|t| {
+            if let oxigraph::model::Term::Literal(lit) = t {
+                Some(lit.value().to_string())
+            } else {
+                None
+            }
+        })
+    };
+
+    // One solution per allowed value, so parameters are grouped by their node
+    let mut parameters: Vec<crate::parser::types::TaskParameter> = Vec::new();
+    let mut index_by_node: HashMap<String, usize> = HashMap::new();
 
     if let oxigraph::sparql::QueryResults::Solutions(solutions) = query_results {
         for solution in solutions {
@@ -360,27 +515,358 @@ fn extract_task_parameters(
                 } else {
                     param_name_term.to_string()
                 };
+                let node = solution
+                    .get("param")
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| param_name.clone());
+
+                let index = match index_by_node.get(&node) {
+                    Some(&index) => index,
+                    None => {
+                        let param_type =
+                            literal(&solution, "paramType").unwrap_or_else(|| "string".to_string());
+
+                        let mapping = literal(&solution, "mapping");
+
+                        let required = literal(&solution, "required")
+                            .map(|v| v == "true" || v == "1")
+                            .unwrap_or(false);
+
+                        let order = match literal(&solution, "order") {
+                            Some(order) => Some(order.trim().parse::<i64>().map_err(|_| {
+                                WorkflowError::Parse(format!(
+                                    "Task {} parameter '{}': invalid yawl:displayOrder '{}'",
+                                    task_id_clean, param_name, order
+                                ))
+                            })?),
+                            None => None,
+                        };
+
+                        parameters.push(crate::parser::types::TaskParameter {
+                            name: param_name,
+                            param_type,
+                            mapping,
+                            required,
+                            annotations: crate::parser::types::FieldAnnotations {
+                                label: literal(&solution, "label"),
+                                description: literal(&solution, "comment"),
+                                allowed_values: Vec::new(),
+                                order,
+                                widget: literal(&solution, "widget"),
+                            },
+                        });
+                        index_by_node.insert(node, parameters.len() - 1);
+                        parameters.len() - 1
+                    }
+                };
 
-                let param_type = solution
-                    .get("paramType")
-                    .and_then(|t| {
-                        if let oxigraph::model::Term::Literal(lit) = t {
-                            Some(lit.value().to_string())
-                        } else {
-                            None
-                        }
+                if let Some(oxigraph::model::Term::Literal(lit)) = solution.get("allowed") {
+                    let value = literal_to_json(lit);
+                    let allowed = &mut parameters[index].annotations.allowed_values;
+                    if !allowed.contains(&value) {
+                        allowed.push(value);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(parameters)
+}
+
+/// Convert a typed RDF literal to JSON (numbers and booleans keep their type)
+fn literal_to_json(lit: &oxigraph::model::Literal) -> serde_json::Value {
+    let value = lit.value();
+    let datatype = lit.datatype().as_str();
+    let xsd = |local: &str| datatype == format!("http://www.w3.org/2001/XMLSchema#{}", local);
+    if xsd("boolean") {
+        if let Ok(b) = value.parse::<bool>() {
+            return serde_json::Value::Bool(b);
+        }
+    } else if xsd("integer") || xsd("int") || xsd("long") {
+        if let Ok(n) = value.parse::<i64>() {
+            return serde_json::Value::from(n);
+        }
+    } else if xsd("decimal") || xsd("double") || xsd("float") {
+        if let Some(n) = value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+        {
+            return serde_json::Value::Number(n);
+        }
+    }
+    serde_json::Value::String(value.to_string())
+}
+
+/// Extract the multiple instance configuration of a task
+///
+/// Reads `yawl:minimum`, `yawl:maximum`, `yawl:threshold`, `yawl:creationMode`
+/// (`static`/`dynamic`), `yawl:miDataInput` (collection expression),
+/// `yawl:miInstanceVariable`, `yawl:miDataOutput` (aggregate variable),
+/// `yawl:synchronize` and `yawl:cancelRemaining`.
+fn extract_multi_instance(
+    store: &Store,
+    yawl_ns: &str,
+    task_id: &str,
+) -> WorkflowResult<crate::patterns::multiple_instance::MultiInstanceSpec> {
+    use crate::patterns::multiple_instance::{MiCreationMode, MultiInstanceSpec};
+
+    let task_id_clean = task_id.trim().trim_start_matches('<').trim_end_matches('>');
+
+    let query = format!(
+        "PREFIX yawl: <{}>\n\
+         SELECT ?minimum ?maximum ?threshold ?creationMode ?dataInput ?instanceVariable \
+                ?dataOutput ?synchronize ?cancelRemaining WHERE {{\n\
+           OPTIONAL {{ <{}> yawl:minimum ?minimum }}\n\
+           OPTIONAL {{ <{}> yawl:maximum ?maximum }}\n\
+           OPTIONAL {{ <{}> yawl:threshold ?threshold }}\n\
+           OPTIONAL {{ <{}> yawl:creationMode ?creationMode }}\n\
+           OPTIONAL {{ <{}> yawl:miDataInput ?dataInput }}\n\
+           OPTIONAL {{ <{}> yawl:miInstanceVariable ?instanceVariable }}\n\
+           OPTIONAL {{ <{}> yawl:miDataOutput ?dataOutput }}\n\
+           OPTIONAL {{ <{}> yawl:synchronize ?synchronize }}\n\
+           OPTIONAL {{ <{}> yawl:cancelRemaining ?cancelRemaining }}\n\
+         }}",
+        yawl_ns,
+        task_id_clean,
+        task_id_clean,
+        task_id_clean,
+        task_id_clean,
+        task_id_clean,
+        task_id_clean,
+        task_id_clean,
+        task_id_clean,
+        task_id_clean
+    );
+
+    #[allow(deprecated)]
+    let query_results = store.query(&query).map_err(|e| {
+        WorkflowError::Parse(format!("Failed to query multiple instance task: {:?}", e))
+    })?;
+
+    let mut spec = MultiInstanceSpec::default();
+
+    if let oxigraph::sparql::QueryResults::Solutions(mut solutions) = query_results {
+        if let Some(solution) = solutions.next() {
+            let solution = solution.map_err(|e| {
+                WorkflowError::Parse(format!("Failed to process MI solution: {:?}", e))
+            })?;
+
+            let value = |name: &str| -> Option<String> {
+                match solution.get(name) {
+                    Some(oxigraph::model::Term::Literal(lit)) => Some(lit.value().to_string()),
+                    Some(oxigraph::model::Term::NamedNode(node)) => Some(node.as_str().to_string()),
+                    _ => None,
+                }
+            };
+            let number = |name: &str| -> WorkflowResult<Option<usize>> {
+                value(name)
+                    .map(|v| {
+                        v.parse::<usize>().map_err(|_| {
+                            WorkflowError::Parse(format!(
+                                "Task {} has invalid yawl:{} '{}'",
+                                task_id_clean, name, v
+                            ))
+                        })
                     })
-                    .unwrap_or_else(|| "string".to_string());
+                    .transpose()
+            };
 
-                parameters.push(crate::parser::types::TaskParameter {
-                    name: param_name,
-                    param_type,
-                });
+            if let Some(minimum) = number("minimum")? {
+                spec.minimum = minimum;
+            }
+            spec.maximum = number("maximum")?;
+            spec.threshold = number("threshold")?;
+            if let Some(mode) = value("creationMode") {
+                spec.creation_mode = if mode.to_lowercase().ends_with("dynamic") {
+                    MiCreationMode::Dynamic
+                } else {
+                    MiCreationMode::Static
+                };
+            }
+            spec.collection = value("dataInput");
+            if let Some(instance_variable) = value("instanceVariable") {
+                spec.instance_variable = instance_variable;
+            }
+            spec.aggregate_into = value("dataOutput");
+            if let Some(synchronize) = value("synchronize") {
+                spec.synchronize = synchronize != "false";
+            }
+            if let Some(cancel_remaining) = value("cancelRemaining") {
+                spec.cancel_remaining = cancel_remaining != "false";
             }
         }
     }
 
-    Ok(parameters)
+    spec.validate()
+        .map_err(|e| WorkflowError::Parse(format!("Task {}: {}", task_id_clean, e)))?;
+    Ok(spec)
+}
+
+/// Extract the sub-net a composite task decomposes to
+///
+/// Reads `yawl:hasDecomposesTo` and the optional `yawl:decomposesToVersion`
+/// pinning the sub-net version.
+fn extract_decomposition(
+    store: &Store,
+    yawl_ns: &str,
+    task_id: &str,
+) -> WorkflowResult<Option<crate::parser::types::SubNetRef>> {
+    use crate::parser::types::SubNetRef;
+
+    let query = format!(
+        "PREFIX yawl: <{}>\n\
+         SELECT ?net ?version WHERE {{\n\
+           <{}> yawl:hasDecomposesTo ?net .\n\
+           OPTIONAL {{ <{}> yawl:decomposesToVersion ?version }}\n\
+         }} LIMIT 1",
+        yawl_ns,
+        clean_iri(task_id),
+        clean_iri(task_id)
+    );
+
+    #[allow(deprecated)]
+    let query_results = store
+        .query(&query)
+        .map_err(|e| WorkflowError::Parse(format!("Failed to query decomposition: {:?}", e)))?;
+
+    if let oxigraph::sparql::QueryResults::Solutions(solutions) = query_results {
+        for solution in solutions {
+            let solution = solution.map_err(|e| {
+                WorkflowError::Parse(format!("Failed to process decomposition solution: {:?}", e))
+            })?;
+            if let Some(oxigraph::model::Term::NamedNode(net)) = solution.get("net") {
+                let version = match solution.get("version") {
+                    Some(oxigraph::model::Term::Literal(lit)) => Some(lit.value()),
+                    _ => None,
+                };
+                return Ok(Some(SubNetRef::iri(net.as_str(), version)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Extract the message a task waits for
+///
+/// Reads `yawl:catchesMessage`, the optional `yawl:startsCase` flag and the
+/// `yawl:correlation` entries (`yawl:correlationKey` plus the `yawl:mapping`
+/// expression over the case data).
+fn extract_message_catch(
+    store: &Store,
+    yawl_ns: &str,
+    task_id: &str,
+) -> WorkflowResult<Option<crate::parser::types::MessageCatch>> {
+    use crate::parser::types::MessageCatch;
+
+    let query = format!(
+        "PREFIX yawl: <{}>\n\
+         SELECT ?message ?starts ?key ?mapping WHERE {{\n\
+           <{}> yawl:catchesMessage ?message .\n\
+           OPTIONAL {{ <{}> yawl:startsCase ?starts }}\n\
+           OPTIONAL {{\n\
+             <{}> yawl:correlation ?c .\n\
+             ?c yawl:correlationKey ?key ;\n\
+                yawl:mapping ?mapping .\n\
+           }}\n\
+         }}",
+        yawl_ns,
+        clean_iri(task_id),
+        clean_iri(task_id),
+        clean_iri(task_id)
+    );
+
+    #[allow(deprecated)]
+    let query_results = store
+        .query(&query)
+        .map_err(|e| WorkflowError::Parse(format!("Failed to query message catch: {:?}", e)))?;
+
+    let mut catch: Option<MessageCatch> = None;
+
+    if let oxigraph::sparql::QueryResults::Solutions(solutions) = query_results {
+        for solution in solutions {
+            let solution = solution.map_err(|e| {
+                WorkflowError::Parse(format!("Failed to process message catch solution: {:?}", e))
+            })?;
+            let Some(oxigraph::model::Term::Literal(message)) = solution.get("message") else {
+                continue;
+            };
+            let entry = catch.get_or_insert_with(|| MessageCatch::new(message.value()));
+            if let Some(oxigraph::model::Term::Literal(starts)) = solution.get("starts") {
+                entry.starts_case |= starts.value().parse::<bool>().unwrap_or(false);
+            }
+            if let (
+                Some(oxigraph::model::Term::Literal(key)),
+                Some(oxigraph::model::Term::Literal(mapping)),
+            ) = (solution.get("key"), solution.get("mapping"))
+            {
+                entry
+                    .correlation
+                    .insert(key.value().to_string(), mapping.value().to_string());
+            }
+        }
+    }
+
+    Ok(catch)
+}
+
+/// Extract the cancellation region of a task
+///
+/// Reads `yawl:CancelScope` (`yawl:CancelRegion`/`yawl:CancelTask` or
+/// `yawl:CancelCase`) and `yawl:CancellationTarget` (tasks and conditions).
+fn extract_cancellation(
+    store: &Store,
+    yawl_ns: &str,
+    task_id: &str,
+) -> WorkflowResult<Option<crate::parser::types::CancellationRegion>> {
+    use crate::parser::types::{CancelScope, CancellationRegion};
+
+    let task_id_clean = task_id.trim().trim_start_matches('<').trim_end_matches('>');
+
+    let query = format!(
+        "PREFIX yawl: <{}>\n\
+         SELECT ?scope ?target WHERE {{\n\
+           {{ <{}> yawl:CancelScope ?scope }}\n\
+           UNION\n\
+           {{ <{}> yawl:CancellationTarget ?target }}\n\
+         }}",
+        yawl_ns, task_id_clean, task_id_clean
+    );
+
+    #[allow(deprecated)]
+    let query_results = store.query(&query).map_err(|e| {
+        WorkflowError::Parse(format!("Failed to query cancellation region: {:?}", e))
+    })?;
+
+    let mut region: Option<CancellationRegion> = None;
+
+    if let oxigraph::sparql::QueryResults::Solutions(solutions) = query_results {
+        for solution in solutions {
+            let solution = solution.map_err(|e| {
+                WorkflowError::Parse(format!("Failed to process cancellation solution: {:?}", e))
+            })?;
+            let region = region.get_or_insert_with(CancellationRegion::default);
+
+            if let Some(oxigraph::model::Term::NamedNode(scope)) = solution.get("scope") {
+                if scope.as_str().ends_with("CancelCase") {
+                    region.scope = CancelScope::Case;
+                }
+            }
+            if let Some(target) = solution.get("target") {
+                let target = target.to_string();
+                if !region.targets.contains(&target) {
+                    region.targets.push(target);
+                }
+            }
+        }
+    }
+
+    if let Some(ref mut region) = region {
+        region.targets.sort();
+    }
+    Ok(region)
 }
 
 /// Extract conditions from RDF store
@@ -469,7 +955,7 @@ pub fn extract_conditions(
 pub fn extract_flows(
     store: &Store,
     yawl_ns: &str,
-    _spec_iri: Option<&str>,
+    spec_iri: Option<&str>,
     tasks: &mut HashMap<String, Task>,
     conditions: &mut HashMap<String, Condition>,
 ) -> WorkflowResult<Vec<crate::parser::types::Flow>> {
@@ -508,6 +994,12 @@ pub fn extract_flows(
                 let from_id = from_term.to_string();
                 let to_id = to_term.to_string();
 
+                // Skip flows of other nets in the same document
+                let in_net = |id: &str| tasks.contains_key(id) || conditions.contains_key(id);
+                if spec_iri.is_some() && !in_net(&from_id) && !in_net(&to_id) {
+                    continue;
+                }
+
                 // Extract predicate if present
                 let predicate = solution.get("predicate").map(|p| {
                     let pred_str = p.to_string();
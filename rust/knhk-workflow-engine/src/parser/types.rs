@@ -81,8 +81,160 @@ pub enum TaskType {
 pub struct TaskParameter {
     /// Parameter name
     pub name: String,
-    /// Parameter type (e.g., "string", "boolean", "decimal")
+    /// Parameter type (e.g., "string", "boolean", "decimal", "xsd:dateTime")
     pub param_type: String,
+    /// Mapping expression (inputs: over case data, outputs: over the task result)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<String>,
+    /// Whether a value must be present
+    #[serde(default)]
+    pub required: bool,
+    /// Presentation and value constraints for generated work item forms
+    #[serde(default, skip_serializing_if = "FieldAnnotations::is_empty")]
+    pub annotations: FieldAnnotations,
+}
+
+/// Form annotations of a task parameter (rdfs:label, rdfs:comment,
+/// yawl:allowedValue, yawl:displayOrder, yawl:widget)
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FieldAnnotations {
+    /// Human-readable label (defaults to the parameter name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Help text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Enumerated values; when non-empty, values outside the list are rejected
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<serde_json::Value>,
+    /// Position of the field in the form (unordered fields follow, by name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<i64>,
+    /// UI widget hint (e.g. "textarea", "radio", "date")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widget: Option<String>,
+}
+
+impl FieldAnnotations {
+    /// Check whether no annotation is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Scope of a task's cancellation (from yawl:CancelScope)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CancelScope {
+    /// Remove tokens from the tasks and conditions of the region (Patterns 19, 25)
+    #[default]
+    Region,
+    /// Cancel the whole case (Pattern 20)
+    Case,
+}
+
+/// Cancellation region (YAWL cancellation set) of a task
+///
+/// Applied atomically when the owning task completes, before its output tokens
+/// are produced: tokens are removed from the target conditions and tasks, and
+/// work items of the target tasks are withdrawn.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CancellationRegion {
+    /// Cancellation scope
+    #[serde(default)]
+    pub scope: CancelScope,
+    /// Cancelled tasks and conditions (from yawl:CancellationTarget)
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+impl CancellationRegion {
+    /// Region removing tokens from the given tasks and conditions
+    pub fn region(targets: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            scope: CancelScope::Region,
+            targets: targets.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Cancel the whole case
+    pub fn case() -> Self {
+        Self {
+            scope: CancelScope::Case,
+            targets: Vec::new(),
+        }
+    }
+}
+
+/// Reference from a composite task to the sub-net it decomposes to
+///
+/// Resolved at registration: by spec ID if set, otherwise by IRI among the
+/// registered specs, picking the pinned version or the latest one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SubNetRef {
+    /// Spec ID of the sub-net (set for sub-nets in the same document)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_id: Option<WorkflowSpecId>,
+    /// Sub-net IRI (from yawl:hasDecomposesTo)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iri: Option<String>,
+    /// Pinned sub-net version (latest registered version if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl SubNetRef {
+    /// Reference a registered spec by ID
+    pub fn spec(spec_id: WorkflowSpecId) -> Self {
+        Self {
+            spec_id: Some(spec_id),
+            ..Self::default()
+        }
+    }
+
+    /// Reference a spec by IRI, optionally pinned to a version
+    pub fn iri(iri: impl Into<String>, version: Option<&str>) -> Self {
+        Self {
+            spec_id: None,
+            iri: Some(iri.into()),
+            version: version.map(str::to_string),
+        }
+    }
+}
+
+/// Message a task waits for (intermediate message catch or message start)
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MessageCatch {
+    /// Message name (e.g. "payment-received")
+    pub message: String,
+    /// Correlation keys mapped to expressions over the case data
+    /// (e.g. `orderId` → `order.id`)
+    #[serde(default)]
+    pub correlation: std::collections::BTreeMap<String, String>,
+    /// Publishing the message instantiates a new case (message start)
+    #[serde(default)]
+    pub starts_case: bool,
+}
+
+impl MessageCatch {
+    /// Catch a message by name
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Correlate on `key`, whose value is read from the case data at `expression`
+    pub fn correlate(mut self, key: impl Into<String>, expression: impl Into<String>) -> Self {
+        self.correlation.insert(key.into(), expression.into());
+        self
+    }
+
+    /// Turn the catch into a message start
+    pub fn starting_case(mut self) -> Self {
+        self.starts_case = true;
+        self
+    }
 }
 
 /// Workflow task
@@ -124,6 +276,18 @@ pub struct Task {
     pub required_capabilities: Vec<String>,
     /// Worklet ID for exception handling (optional)
     pub exception_worklet: Option<crate::worklets::WorkletId>,
+    /// Multiple instance configuration (for `TaskType::MultipleInstance`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multi_instance: Option<crate::patterns::multiple_instance::MultiInstanceSpec>,
+    /// Cancellation region applied when the task completes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancellation: Option<CancellationRegion>,
+    /// Sub-net launched as a child case (for `TaskType::Composite`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decomposition: Option<SubNetRef>,
+    /// Message the task waits for (atomic tasks only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<MessageCatch>,
     /// Pre-compiled pattern ID (TRIZ Principle 10: Prior Action)
     ///
     /// Pattern identification is computed at registration time to avoid
@@ -178,9 +342,26 @@ pub struct WorkflowSpec {
     /// Source RDF/Turtle (for runtime RDF queries)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_turtle: Option<String>,
+    /// Net-level case variables (empty for untyped nets)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<crate::data::schema::VariableDecl>,
+    /// Specification IRI (from the source document)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iri: Option<String>,
+    /// Specification version (from yawl:versionNumber)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Sub-nets defined in the same document (registered with the spec)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subnets: Vec<WorkflowSpec>,
 }
 
 impl WorkflowSpec {
+    /// Case data schema built from the declared net variables
+    pub fn data_schema(&self) -> crate::data::schema::CaseDataSchema {
+        crate::data::schema::CaseDataSchema::from_variables(&self.variables)
+    }
+
     /// Serialize workflow to Turtle format with YAWL ontology annotations
     ///
     /// Converts the WorkflowSpec to RDF/Turtle format using the YAWL ontology.
@@ -1,8 +1,23 @@
 //! Multiple Instance Patterns (12-15)
+//!
+//! Besides the stateless pattern executors, this module holds the runtime model
+//! used by the engine for `TaskType::MultipleInstance` tasks:
+//! - [`MultiInstanceSpec`]: minimum/maximum/threshold, static vs dynamic creation,
+//!   data splitting from a case-data collection and output aggregation
+//! - [`MultiInstanceState`]: instance bookkeeping for one running MI task
+//!
+//! The partial-join variants of the MI patterns (static, cancelling and dynamic
+//! partial join; patterns 34-36 in the Russell et al. catalogue, registered under
+//! other IDs in this registry) are expressed through `threshold` and
+//! `cancel_remaining`.
 
+use crate::data::expression::Expr;
+use crate::error::{WorkflowError, WorkflowResult};
 use crate::patterns::{
     PatternExecutionContext, PatternExecutionResult, PatternExecutor, PatternId,
 };
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Pattern 12: Multiple Instances Without Synchronization
 pub struct MultipleInstanceWithoutSyncPattern;
@@ -129,3 +144,464 @@ pub fn create_pattern_14() -> (PatternId, Box<dyn PatternExecutor>) {
 pub fn create_pattern_15() -> (PatternId, Box<dyn PatternExecutor>) {
     (PatternId(15), Box::new(MultipleInstanceDynamicPattern))
 }
+
+/// Instance creation mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MiCreationMode {
+    /// All instances are created when the task starts
+    #[default]
+    Static,
+    /// Instances can be added while the task is running
+    Dynamic,
+}
+
+/// Multiple instance task configuration (YAWL `minimum`/`maximum`/`threshold`/`creationMode`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiInstanceSpec {
+    /// Minimum number of instances
+    #[serde(default = "default_minimum")]
+    pub minimum: usize,
+    /// Maximum number of instances (unbounded if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<usize>,
+    /// Completed instances after which the task completes (all instances if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<usize>,
+    /// Static or dynamic instance creation
+    #[serde(default)]
+    pub creation_mode: MiCreationMode,
+    /// Expression over case data yielding the collection to split, one instance per item
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    /// Name under which each instance receives its collection item
+    #[serde(default = "default_instance_variable")]
+    pub instance_variable: String,
+    /// Case variable receiving the instance outputs as an array (merged into case data if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate_into: Option<String>,
+    /// Whether downstream tasks wait for the instances (`false`: pattern 12)
+    #[serde(default = "default_true")]
+    pub synchronize: bool,
+    /// Cancel instances still running once the threshold is reached
+    #[serde(default = "default_true")]
+    pub cancel_remaining: bool,
+}
+
+fn default_minimum() -> usize {
+    1
+}
+
+fn default_instance_variable() -> String {
+    "item".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for MultiInstanceSpec {
+    fn default() -> Self {
+        Self {
+            minimum: default_minimum(),
+            maximum: None,
+            threshold: None,
+            creation_mode: MiCreationMode::Static,
+            collection: None,
+            instance_variable: default_instance_variable(),
+            aggregate_into: None,
+            synchronize: true,
+            cancel_remaining: true,
+        }
+    }
+}
+
+impl MultiInstanceSpec {
+    /// Check the configuration for consistency
+    pub fn validate(&self) -> WorkflowResult<()> {
+        if let Some(maximum) = self.maximum {
+            if maximum < self.minimum {
+                return Err(WorkflowError::Validation(format!(
+                    "MI maximum {} is below minimum {}",
+                    maximum, self.minimum
+                )));
+            }
+        }
+        if let (Some(threshold), Some(maximum)) = (self.threshold, self.maximum) {
+            if threshold > maximum {
+                return Err(WorkflowError::Validation(format!(
+                    "MI threshold {} exceeds maximum {}",
+                    threshold, maximum
+                )));
+            }
+        }
+        if let Some(ref collection) = self.collection {
+            Expr::parse(collection)?;
+        }
+        Ok(())
+    }
+
+    /// Workflow pattern implemented by this configuration
+    pub fn pattern_id(&self) -> PatternId {
+        match (self.synchronize, self.creation_mode, &self.collection) {
+            (false, _, _) => PatternId(12),
+            (true, MiCreationMode::Dynamic, _) => PatternId(15),
+            (true, MiCreationMode::Static, Some(_)) => PatternId(14),
+            (true, MiCreationMode::Static, None) => PatternId(13),
+        }
+    }
+}
+
+/// Instance lifecycle state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MiInstanceState {
+    /// Created, not yet started by the engine
+    Pending,
+    /// Executing
+    Running,
+    /// Completed with output
+    Completed,
+    /// Failed
+    Failed,
+    /// Cancelled after the threshold was reached
+    Cancelled,
+}
+
+/// A single instance of a multiple instance task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MiInstance {
+    /// Instance index (creation order)
+    pub index: usize,
+    /// Instance input data
+    pub input: Value,
+    /// State
+    pub state: MiInstanceState,
+    /// Output data (when completed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    /// Failure reason (when failed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl MiInstance {
+    fn is_finished(&self) -> bool {
+        matches!(
+            self.state,
+            MiInstanceState::Completed | MiInstanceState::Failed | MiInstanceState::Cancelled
+        )
+    }
+}
+
+/// Runtime state of a multiple instance task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiInstanceState {
+    /// Task ID
+    pub task_id: String,
+    /// Configuration
+    pub spec: MultiInstanceSpec,
+    /// Instances in creation order
+    pub instances: Vec<MiInstance>,
+    /// No further instances can be added (always true for static creation)
+    pub closed: bool,
+}
+
+impl MultiInstanceState {
+    /// Create the initial instances from case data
+    ///
+    /// With a `collection`, one instance is created per item; otherwise `minimum`
+    /// instances are created. Each instance input is the case data extended with
+    /// the item (under `instance_variable`), `instance_id` and `instance_count`.
+    pub fn start(
+        task_id: &str,
+        spec: MultiInstanceSpec,
+        case_data: &Value,
+    ) -> WorkflowResult<Self> {
+        spec.validate()?;
+
+        let items: Vec<Value> = match spec.collection {
+            Some(ref collection) => match Expr::parse(collection)?.evaluate(case_data)? {
+                Value::Array(items) => items,
+                Value::Null => Vec::new(),
+                other => {
+                    return Err(WorkflowError::TaskExecutionFailed(format!(
+                        "MI task {} collection '{}' is not an array: {}",
+                        task_id, collection, other
+                    )))
+                }
+            },
+            None => vec![Value::Null; spec.minimum],
+        };
+
+        if items.len() < spec.minimum {
+            return Err(WorkflowError::TaskExecutionFailed(format!(
+                "MI task {} requires at least {} instances, collection has {}",
+                task_id,
+                spec.minimum,
+                items.len()
+            )));
+        }
+        if let Some(maximum) = spec.maximum {
+            if items.len() > maximum {
+                return Err(WorkflowError::TaskExecutionFailed(format!(
+                    "MI task {} allows at most {} instances, collection has {}",
+                    task_id,
+                    maximum,
+                    items.len()
+                )));
+            }
+        }
+
+        let count = items.len();
+        let mut state = Self {
+            task_id: task_id.to_string(),
+            closed: spec.creation_mode == MiCreationMode::Static,
+            spec,
+            instances: Vec::with_capacity(count),
+        };
+        for (index, item) in items.into_iter().enumerate() {
+            let input = state.instance_input(case_data, item, index, count);
+            state.instances.push(MiInstance {
+                index,
+                input,
+                state: MiInstanceState::Pending,
+                output: None,
+                error: None,
+            });
+        }
+        Ok(state)
+    }
+
+    fn instance_input(&self, case_data: &Value, item: Value, index: usize, count: usize) -> Value {
+        let mut input = match case_data {
+            Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        if !item.is_null() {
+            input.insert(self.spec.instance_variable.clone(), item);
+        }
+        input.insert("instance_id".to_string(), Value::from(index));
+        input.insert("instance_count".to_string(), Value::from(count));
+        Value::Object(input)
+    }
+
+    /// Add an instance while the task is running (dynamic creation only)
+    ///
+    /// Returns the new instance index.
+    pub fn add_instance(&mut self, case_data: &Value, item: Value) -> WorkflowResult<usize> {
+        if self.spec.creation_mode != MiCreationMode::Dynamic {
+            return Err(WorkflowError::Validation(format!(
+                "MI task {} uses static instance creation",
+                self.task_id
+            )));
+        }
+        if self.closed || self.is_complete() {
+            return Err(WorkflowError::Validation(format!(
+                "MI task {} no longer accepts instances",
+                self.task_id
+            )));
+        }
+        if let Some(maximum) = self.spec.maximum {
+            if self.instances.len() >= maximum {
+                return Err(WorkflowError::Validation(format!(
+                    "MI task {} reached its maximum of {} instances",
+                    self.task_id, maximum
+                )));
+            }
+        }
+
+        let index = self.instances.len();
+        let input = self.instance_input(case_data, item, index, index + 1);
+        self.instances.push(MiInstance {
+            index,
+            input,
+            state: MiInstanceState::Pending,
+            output: None,
+            error: None,
+        });
+        Ok(index)
+    }
+
+    /// Stop accepting new instances
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Instances not yet handed to the engine (marked running)
+    pub fn take_pending(&mut self) -> Vec<(usize, Value)> {
+        self.instances
+            .iter_mut()
+            .filter(|i| i.state == MiInstanceState::Pending)
+            .map(|i| {
+                i.state = MiInstanceState::Running;
+                (i.index, i.input.clone())
+            })
+            .collect()
+    }
+
+    /// Record a finished instance
+    pub fn finish_instance(&mut self, index: usize, result: Result<Value, String>) {
+        if let Some(instance) = self.instances.get_mut(index) {
+            if instance.is_finished() {
+                return;
+            }
+            match result {
+                Ok(output) => {
+                    instance.state = MiInstanceState::Completed;
+                    instance.output = Some(output);
+                }
+                Err(error) => {
+                    instance.state = MiInstanceState::Failed;
+                    instance.error = Some(error);
+                }
+            }
+        }
+    }
+
+    /// Number of completed instances
+    pub fn completed_count(&self) -> usize {
+        self.instances
+            .iter()
+            .filter(|i| i.state == MiInstanceState::Completed)
+            .count()
+    }
+
+    /// Completed instances required for the task to complete
+    pub fn required_completions(&self) -> usize {
+        match self.spec.threshold {
+            Some(threshold) => threshold,
+            None => self.instances.len().max(self.spec.minimum),
+        }
+    }
+
+    /// Check whether the task can complete (threshold reached or all instances finished)
+    pub fn is_complete(&self) -> bool {
+        if self.spec.threshold.is_some() && self.completed_count() >= self.required_completions() {
+            return true;
+        }
+        self.closed
+            && self.instances.iter().all(MiInstance::is_finished)
+            && self.completed_count() >= self.required_completions()
+    }
+
+    /// Check whether the task can no longer complete
+    pub fn is_failed(&self) -> bool {
+        if !self.closed {
+            return false;
+        }
+        let achievable = self
+            .instances
+            .iter()
+            .filter(|i| i.state != MiInstanceState::Failed && i.state != MiInstanceState::Cancelled)
+            .count();
+        achievable < self.required_completions()
+    }
+
+    /// Cancel instances that have not finished; returns their indexes
+    pub fn cancel_remaining(&mut self) -> Vec<usize> {
+        self.closed = true;
+        let mut cancelled = Vec::new();
+        for instance in &mut self.instances {
+            if !instance.is_finished() {
+                instance.state = MiInstanceState::Cancelled;
+                cancelled.push(instance.index);
+            }
+        }
+        cancelled
+    }
+
+    /// Outputs of completed instances, in instance order
+    pub fn outputs(&self) -> Vec<Value> {
+        self.instances
+            .iter()
+            .filter(|i| i.state == MiInstanceState::Completed)
+            .filter_map(|i| i.output.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn collection_spec() -> MultiInstanceSpec {
+        MultiInstanceSpec {
+            collection: Some("order.lines".to_string()),
+            instance_variable: "line".to_string(),
+            ..MultiInstanceSpec::default()
+        }
+    }
+
+    #[test]
+    fn test_start_splits_collection() {
+        let data = json!({"order": {"lines": [{"sku": "a"}, {"sku": "b"}]}});
+        let mut state = MultiInstanceState::start("pick", collection_spec(), &data).unwrap();
+
+        assert_eq!(state.instances.len(), 2);
+        assert_eq!(state.instances[1].input["line"], json!({"sku": "b"}));
+        assert_eq!(state.spec.pattern_id(), PatternId(14));
+
+        let pending = state.take_pending();
+        assert_eq!(pending.len(), 2);
+        state.finish_instance(0, Ok(json!({"picked": true})));
+        assert!(!state.is_complete());
+        state.finish_instance(1, Ok(json!({"picked": false})));
+        assert!(state.is_complete());
+        assert_eq!(state.outputs().len(), 2);
+    }
+
+    #[test]
+    fn test_threshold_completes_and_cancels_remaining() {
+        let spec = MultiInstanceSpec {
+            minimum: 3,
+            threshold: Some(2),
+            ..MultiInstanceSpec::default()
+        };
+        let mut state = MultiInstanceState::start("review", spec, &json!({})).unwrap();
+        state.take_pending();
+        state.finish_instance(2, Ok(json!({"vote": "yes"})));
+        state.finish_instance(0, Ok(json!({"vote": "no"})));
+
+        assert!(state.is_complete());
+        assert_eq!(state.cancel_remaining(), vec![1]);
+        assert_eq!(
+            state.outputs(),
+            vec![json!({"vote": "no"}), json!({"vote": "yes"})]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_creation_until_closed() {
+        let spec = MultiInstanceSpec {
+            creation_mode: MiCreationMode::Dynamic,
+            minimum: 1,
+            maximum: Some(2),
+            ..MultiInstanceSpec::default()
+        };
+        let mut state = MultiInstanceState::start("quote", spec, &json!({})).unwrap();
+        state.take_pending();
+        state.finish_instance(0, Ok(json!({})));
+        assert!(
+            !state.is_complete(),
+            "Open dynamic task waits for more instances"
+        );
+
+        assert_eq!(state.add_instance(&json!({}), json!("extra")).unwrap(), 1);
+        assert!(state.add_instance(&json!({}), json!("too many")).is_err());
+        state.take_pending();
+        state.finish_instance(1, Ok(json!({})));
+        state.close();
+        assert!(state.is_complete());
+    }
+
+    #[test]
+    fn test_collection_must_respect_bounds() {
+        let spec = MultiInstanceSpec {
+            minimum: 3,
+            ..collection_spec()
+        };
+        let data = json!({"order": {"lines": [1, 2]}});
+        assert!(MultiInstanceState::start("pick", spec, &data).is_err());
+    }
+}
@@ -123,10 +123,7 @@ impl RdfWorkflowParser {
                 ?task <{}label> ?name .
                 ?task <{}type> ?type .
             }}",
-            workflow_id,
-            YAWL_NS,
-            RDFS_NS,
-            YAWL_NS
+            workflow_id, YAWL_NS, RDFS_NS, YAWL_NS
         );
 
         // Use SparqlEvaluator (oxigraph 0.5 best practices)
@@ -143,8 +140,11 @@ impl RdfWorkflowParser {
                 binding.get("name"),
                 binding.get("type"),
             ) {
-                if let (Term::NamedNode(task_node), Term::Literal(name_lit), Term::Literal(type_lit)) =
-                    (task_term, name_term, type_term)
+                if let (
+                    Term::NamedNode(task_node),
+                    Term::Literal(name_lit),
+                    Term::Literal(type_lit),
+                ) = (task_term, name_term, type_term)
                 {
                     let task_id = task_node.as_str().to_string();
                     let name = name_lit.value().to_string();
@@ -187,6 +187,10 @@ impl RdfWorkflowParser {
                             required_roles: Vec::new(),
                             required_capabilities: Vec::new(),
                             exception_worklet: None,
+                            multi_instance: None,
+                            cancellation: None,
+                            decomposition: None,
+                            message: None,
                         },
                     );
                 }
@@ -417,18 +421,12 @@ impl RdfWorkflowParser {
 
     /// Extract start condition
     fn extract_start_condition(&self, workflow_id: &str) -> WorkflowResult<Option<String>> {
-        Ok(self.extract_string_property(
-            workflow_id,
-            &format!("{}hasStartCondition", YAWL_NS),
-        ))
+        Ok(self.extract_string_property(workflow_id, &format!("{}hasStartCondition", YAWL_NS)))
     }
 
     /// Extract end condition
     fn extract_end_condition(&self, workflow_id: &str) -> WorkflowResult<Option<String>> {
-        Ok(self.extract_string_property(
-            workflow_id,
-            &format!("{}hasEndCondition", YAWL_NS),
-        ))
+        Ok(self.extract_string_property(workflow_id, &format!("{}hasEndCondition", YAWL_NS)))
     }
 
     /// Extract conditions
@@ -449,9 +447,14 @@ impl RdfWorkflowParser {
             .and_then(|q| q.on_store(&self.store).execute())
         {
             for binding in results {
-                if let (Some(cond_term), Some(name_term)) = (binding.get("condition"), binding.get("name")) {
-                    if let (Term::NamedNode(cond_node), Term::Literal(name_lit)) = (cond_term, name_term) {
-                        conditions.insert(cond_node.as_str().to_string(), name_lit.value().to_string());
+                if let (Some(cond_term), Some(name_term)) =
+                    (binding.get("condition"), binding.get("name"))
+                {
+                    if let (Term::NamedNode(cond_node), Term::Literal(name_lit)) =
+                        (cond_term, name_term)
+                    {
+                        conditions
+                            .insert(cond_node.as_str().to_string(), name_lit.value().to_string());
                     }
                 }
             }
@@ -460,4 +463,3 @@ impl RdfWorkflowParser {
         Ok(conditions)
     }
 }
-
@@ -10,6 +10,7 @@
 //! **KNHK Extensions (NICE TO HAVE):**
 //! - pattern:id - YAWL pattern identifier
 //! - org:resource - Resource assignment
+//! - subcase:parent / subcase:child - Parent and sub-case of a composite task
 
 use crate::case::CaseId;
 use crate::state::StateEvent;
@@ -97,6 +98,8 @@ impl XesExporter {
                 ));
             }
 
+            xml.push_str(&Self::case_link_attributes(&event));
+
             xml.push_str("    </event>\n");
         }
 
@@ -177,6 +180,8 @@ impl XesExporter {
                     ));
                 }
 
+                xml.push_str(&Self::case_link_attributes(&event));
+
                 xml.push_str("    </event>\n");
             }
 
@@ -200,6 +205,8 @@ impl XesExporter {
                 timestamp,
                 resource: Some("System".to_string()),
                 pattern_id: None,
+                parent_case: None,
+                sub_case: None,
             }),
             StateEvent::CaseStateChanged {
                 old_state,
@@ -218,6 +225,8 @@ impl XesExporter {
                 timestamp,
                 resource: Some("System".to_string()),
                 pattern_id: None,
+                parent_case: None,
+                sub_case: None,
             }),
             StateEvent::TaskStarted {
                 task_name,
@@ -230,6 +239,8 @@ impl XesExporter {
                 timestamp,
                 resource: Some("System".to_string()),
                 pattern_id: None,
+                parent_case: None,
+                sub_case: None,
             }),
             StateEvent::TaskCompleted {
                 task_name,
@@ -243,11 +254,72 @@ impl XesExporter {
                 timestamp,
                 resource: Some("System".to_string()),
                 pattern_id: None,
+                parent_case: None,
+                sub_case: None,
+            }),
+            StateEvent::SubCaseStarted {
+                task_id,
+                child_case_id,
+                timestamp,
+                case_id,
+                child_spec_id: _,
+            } => Some(WorkflowEvent {
+                activity_name: format!("subcase_{}", task_id),
+                lifecycle: "start".to_string(),
+                timestamp,
+                resource: Some("System".to_string()),
+                pattern_id: None,
+                parent_case: Some(case_id.to_string()),
+                sub_case: Some(child_case_id.to_string()),
+            }),
+            StateEvent::SubCaseFinished {
+                task_id,
+                child_case_id,
+                child_state,
+                timestamp,
+                case_id,
+            } => Some(WorkflowEvent {
+                activity_name: format!("subcase_{}", task_id),
+                lifecycle: if child_state == "completed" {
+                    "complete".to_string()
+                } else {
+                    "cancel".to_string()
+                },
+                timestamp,
+                resource: Some("System".to_string()),
+                pattern_id: None,
+                parent_case: Some(case_id.to_string()),
+                sub_case: Some(child_case_id.to_string()),
             }),
             StateEvent::SpecRegistered { .. } => None, // Skip spec registration events
+            StateEvent::CaseCheckpointed { .. } => None, // Skip recovery checkpoints
+            StateEvent::RegionCancelled { .. } => None, // Cancelled tasks never complete
+            StateEvent::CaseMigrated { .. } => None,   // Version changes are not activities
+            StateEvent::ExceptionRaised { .. } => None, // Exception handling is not an activity
+            StateEvent::ExletStep { .. } => None,
         }
     }
 
+    /// Parent/sub-case link attributes of an event (composite tasks)
+    fn case_link_attributes(event: &WorkflowEvent) -> String {
+        let mut xml = String::new();
+        if let Some(ref parent_case) = event.parent_case {
+            xml.push_str(&format!(
+                r#"      <string key="subcase:parent" value="{}"/>
+"#,
+                Self::escape_xml(parent_case)
+            ));
+        }
+        if let Some(ref sub_case) = event.sub_case {
+            xml.push_str(&format!(
+                r#"      <string key="subcase:child" value="{}"/>
+"#,
+                Self::escape_xml(sub_case)
+            ));
+        }
+        xml
+    }
+
     /// Escape XML special characters
     fn escape_xml(s: &str) -> String {
         s.replace('&', "&amp;")
@@ -273,6 +345,10 @@ pub struct WorkflowEvent {
     pub resource: Option<String>,
     /// KNHK pattern ID (custom extension)
     pub pattern_id: Option<u32>,
+    /// Parent case of a composite task's sub-case (custom extension)
+    pub parent_case: Option<String>,
+    /// Sub-case launched by a composite task (custom extension)
+    pub sub_case: Option<String>,
 }
 
 #[cfg(test)]
@@ -289,6 +365,8 @@ mod tests {
                 timestamp: Utc::now(),
                 resource: Some("user1".to_string()),
                 pattern_id: Some(1),
+                parent_case: None,
+                sub_case: None,
             },
             WorkflowEvent {
                 activity_name: "task_a".to_string(),
@@ -296,6 +374,8 @@ mod tests {
                 timestamp: Utc::now(),
                 resource: Some("user1".to_string()),
                 pattern_id: Some(1),
+                parent_case: None,
+                sub_case: None,
             },
         ];
 
@@ -322,6 +402,8 @@ mod tests {
             timestamp: Utc::now(),
             resource: Some("user<1>".to_string()),
             pattern_id: None,
+            parent_case: None,
+            sub_case: None,
         }];
 
         let xes = XesExporter::export_case_log(&case_id, events);
@@ -343,6 +425,8 @@ mod tests {
             timestamp: Utc::now(),
             resource: None,
             pattern_id: None,
+            parent_case: None,
+            sub_case: None,
         }];
 
         let events2 = vec![WorkflowEvent {
@@ -351,6 +435,8 @@ mod tests {
             timestamp: Utc::now(),
             resource: None,
             pattern_id: None,
+            parent_case: None,
+            sub_case: None,
         }];
 
         let xes = XesExporter::export_multiple_cases(vec![(case1, events1), (case2, events2)]);
@@ -0,0 +1,199 @@
+//! Receipt Segments and Chains
+//!
+//! Sealing batches the receipt log into Merkle segments whose roots are
+//! anchored in the lockchain. A receipt chain exports the receipts of one
+//! case with an inclusion proof per sealed receipt, verifiable without access
+//! to the store.
+
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::receipts::receipt_generator::Receipt;
+use chrono::{DateTime, Utc};
+use knhk_lockchain::{LockchainStorage, MerkleProof, MerkleTree, QuorumManager};
+use serde::{Deserialize, Serialize};
+
+/// Sealed batch of consecutive receipts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptSegment {
+    /// Segment index (lockchain cycle when anchored)
+    pub index: u64,
+    /// Sequence number of the first receipt
+    pub first_seq: u64,
+    /// Sequence number of the last receipt
+    pub last_seq: u64,
+    /// Receipt IDs, in leaf order
+    pub receipt_ids: Vec<String>,
+    /// Hex Merkle root over the receipt signatures
+    pub root: String,
+    /// Seal timestamp
+    pub sealed_at: DateTime<Utc>,
+    /// Whether the root was anchored in the lockchain
+    pub anchored: bool,
+}
+
+/// Proof that a receipt is a leaf of a sealed segment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// Segment index
+    pub segment: u64,
+    /// Leaf position in the segment
+    pub leaf_index: usize,
+    /// Hex leaf hash (the receipt signature)
+    pub leaf_hash: String,
+    /// Hex sibling hashes from leaf to root
+    pub siblings: Vec<String>,
+    /// Hex segment root
+    pub root: String,
+}
+
+impl InclusionProof {
+    /// Build a proof from a lockchain Merkle proof
+    pub(crate) fn from_merkle(segment: u64, proof: &MerkleProof) -> Self {
+        Self {
+            segment,
+            leaf_index: proof.leaf_index,
+            leaf_hash: hex::encode(proof.leaf_hash),
+            siblings: proof.proof_hashes.iter().map(hex::encode).collect(),
+            root: hex::encode(proof.root),
+        }
+    }
+
+    /// Verify that `receipt` is the proven leaf and the path leads to the root
+    pub fn verify(&self, receipt: &Receipt) -> bool {
+        if !receipt.verify_signature() || receipt.signature != self.leaf_hash {
+            return false;
+        }
+        let (leaf_hash, root) = match (decode_hash(&self.leaf_hash), decode_hash(&self.root)) {
+            (Some(leaf_hash), Some(root)) => (leaf_hash, root),
+            _ => return false,
+        };
+        let proof_hashes: Option<Vec<[u8; 32]>> =
+            self.siblings.iter().map(|h| decode_hash(h)).collect();
+        match proof_hashes {
+            Some(proof_hashes) => MerkleProof {
+                leaf_index: self.leaf_index,
+                leaf_hash,
+                proof_hashes,
+                root,
+            }
+            .verify(),
+            None => false,
+        }
+    }
+}
+
+/// Receipt with its inclusion proof (None until its segment is sealed)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainedReceipt {
+    /// The receipt
+    pub receipt: Receipt,
+    /// Inclusion proof in a sealed segment
+    pub proof: Option<InclusionProof>,
+}
+
+/// Receipts of one case, oldest first, with the segments proving them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptChain {
+    /// Case ID
+    pub case_id: String,
+    /// Receipts of the case
+    pub receipts: Vec<ChainedReceipt>,
+    /// Segments referenced by the proofs
+    pub segments: Vec<ReceiptSegment>,
+}
+
+impl ReceiptChain {
+    /// Verify every receipt signature and every inclusion proof
+    ///
+    /// Anchors are checked separately with [`LockchainAnchor::verify_segment`].
+    pub fn verify(&self) -> WorkflowResult<()> {
+        for chained in &self.receipts {
+            let receipt = &chained.receipt;
+            if receipt.case_id.as_deref() != Some(self.case_id.as_str())
+                || !receipt.verify_signature()
+            {
+                return Err(WorkflowError::Crypto(format!(
+                    "Receipt {} is not a valid receipt of case {}",
+                    receipt.receipt_id, self.case_id
+                )));
+            }
+            let proof = match chained.proof {
+                Some(ref proof) => proof,
+                None => continue,
+            };
+            let segment_root = self
+                .segments
+                .iter()
+                .find(|segment| segment.index == proof.segment)
+                .map(|segment| segment.root.as_str());
+            if segment_root != Some(proof.root.as_str()) || !proof.verify(receipt) {
+                return Err(WorkflowError::Crypto(format!(
+                    "Inclusion proof of receipt {} in segment {} failed",
+                    receipt.receipt_id, proof.segment
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lockchain namespace holding receipt segment roots
+const RECEIPT_ANCHOR_NAMESPACE: &str = "receipt-segments";
+
+/// Lockchain anchoring of segment roots (segment index used as cycle)
+///
+/// Roots are kept in their own lockchain namespace so that segment numbers
+/// never collide with the beat cycles other producers persist.
+pub struct LockchainAnchor {
+    storage: LockchainStorage,
+    quorum: QuorumManager,
+}
+
+impl LockchainAnchor {
+    /// Create an anchor over lockchain storage, reaching consensus through `quorum`
+    pub fn new(storage: LockchainStorage, quorum: QuorumManager) -> WorkflowResult<Self> {
+        let storage = storage
+            .with_namespace(RECEIPT_ANCHOR_NAMESPACE)
+            .map_err(|e| {
+                WorkflowError::ExternalSystem(format!("Lockchain storage error: {}", e))
+            })?;
+        Ok(Self { storage, quorum })
+    }
+
+    /// Anchor a segment root
+    pub fn anchor(&self, segment: u64, root: [u8; 32]) -> WorkflowResult<()> {
+        let proof = self.quorum.achieve_consensus(root, segment).map_err(|e| {
+            WorkflowError::ExternalSystem(format!("Lockchain consensus failed: {}", e))
+        })?;
+        self.storage
+            .persist_root(segment, root, proof)
+            .map_err(|e| WorkflowError::ExternalSystem(format!("Lockchain storage error: {}", e)))
+    }
+
+    /// Check that the lockchain holds the root of a segment
+    pub fn verify_segment(&self, segment: &ReceiptSegment) -> WorkflowResult<bool> {
+        let entry = self.storage.get_root(segment.index).map_err(|e| {
+            WorkflowError::ExternalSystem(format!("Lockchain storage error: {}", e))
+        })?;
+        Ok(entry.is_some_and(|entry| hex::encode(entry.root) == segment.root))
+    }
+}
+
+/// Merkle tree over the signatures of receipts, in order
+pub(crate) fn segment_tree(receipts: &[Receipt]) -> WorkflowResult<MerkleTree> {
+    let mut tree = MerkleTree::new();
+    for receipt in receipts {
+        let leaf = decode_hash(&receipt.signature).ok_or_else(|| {
+            WorkflowError::ReceiptGenerationFailed(format!(
+                "Receipt {} has a malformed signature",
+                receipt.receipt_id
+            ))
+        })?;
+        tree.add_leaf(leaf);
+    }
+    tree.compute_root();
+    Ok(tree)
+}
+
+fn decode_hash(hex_hash: &str) -> Option<[u8; 32]> {
+    hex::decode(hex_hash).ok()?.try_into().ok()
+}
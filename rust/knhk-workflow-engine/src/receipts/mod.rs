@@ -3,8 +3,10 @@
 //! Cryptographic receipt generation and storage for all hook executions.
 //! Provides immutable audit trail with query capabilities.
 
+pub mod chain;
 pub mod receipt_generator;
 pub mod receipt_store;
 
+pub use chain::{ChainedReceipt, InclusionProof, LockchainAnchor, ReceiptChain, ReceiptSegment};
 pub use receipt_generator::{Receipt, ReceiptGenerator};
-pub use receipt_store::{ReceiptQuery, ReceiptStats, ReceiptStore};
+pub use receipt_store::{ReceiptPage, ReceiptQuery, ReceiptScan, ReceiptStats, ReceiptStore};
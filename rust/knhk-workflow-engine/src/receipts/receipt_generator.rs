@@ -28,6 +28,12 @@ pub struct Receipt {
     pub ticks_used: u32,
     /// Timestamp (milliseconds since epoch)
     pub timestamp_ms: u64,
+    /// Case the receipt was issued for (if any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_id: Option<String>,
+    /// Workflow specification the receipt was issued for (if any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
     /// Receipt signature (cryptographic hash of entire receipt)
     pub signature: String,
 }
@@ -60,6 +66,8 @@ impl Receipt {
             guards_failed,
             ticks_used,
             timestamp_ms,
+            case_id: None,
+            workflow_id: None,
             signature: String::new(), // Will be computed
         };
 
@@ -68,6 +76,14 @@ impl Receipt {
         receipt
     }
 
+    /// Attribute the receipt to a case of a workflow (re-signs the receipt)
+    pub fn for_case(mut self, case_id: impl Into<String>, workflow_id: impl Into<String>) -> Self {
+        self.case_id = Some(case_id.into());
+        self.workflow_id = Some(workflow_id.into());
+        self.signature = self.compute_signature();
+        self
+    }
+
     /// Generate unique receipt ID
     fn generate_receipt_id() -> String {
         use uuid::Uuid;
@@ -102,6 +118,16 @@ impl Receipt {
             hasher.update(guard.as_bytes());
         }
 
+        // Case attribution is only covered when present (keeps older receipts valid)
+        if let Some(ref case_id) = self.case_id {
+            hasher.update(b"case:");
+            hasher.update(case_id.as_bytes());
+        }
+        if let Some(ref workflow_id) = self.workflow_id {
+            hasher.update(b"workflow:");
+            hasher.update(workflow_id.as_bytes());
+        }
+
         let result = hasher.finalize();
         hex::encode(result)
     }
@@ -245,6 +271,13 @@ mod tests {
         let mut tampered = receipt.clone();
         tampered.ticks_used = 999;
         assert!(!tampered.verify_signature());
+
+        // Case attribution is covered by the signature
+        let attributed = receipt.for_case("case-1", "workflow-1");
+        assert!(attributed.verify_signature());
+        let mut reattributed = attributed.clone();
+        reattributed.case_id = Some("case-2".to_string());
+        assert!(!reattributed.verify_signature());
     }
 
     #[test]
@@ -1,20 +1,48 @@
 //! Receipt Store
 //!
-//! Immutable log storage for execution receipts.
+//! Immutable log storage for execution receipts, persisted in sled.
 //! Provides query API for receipt retrieval and audit trails.
+//!
+//! # Keyspace
+//!
+//! All records live in one sled tree, under key prefixes:
+//! - `r:{seq}`: receipt log (append-only, `seq` is a big-endian counter)
+//! - `id:{receipt_id}`: sequence number of a receipt
+//! - `t:{ts}{seq}`, `s:{sigma}\0{ts}{seq}`, `c:{case}\0{ts}{seq}`,
+//!   `w:{workflow}\0{ts}{seq}`: secondary indexes ordered by time
+//! - `seg:{index}`, `in:{seq}`: sealed segments and segment membership
+//! - `meta:*`: counters
 
 use crate::error::{WorkflowError, WorkflowResult};
+use crate::receipts::chain::{
+    segment_tree, ChainedReceipt, InclusionProof, LockchainAnchor, ReceiptChain, ReceiptSegment,
+};
 use crate::receipts::receipt_generator::Receipt;
 use serde::{Deserialize, Serialize};
+use sled::transaction::{ConflictableTransactionError, TransactionError};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Number of receipts stored
+const SEQ_KEY: &[u8] = b"meta:seq";
+/// Last receipt sealed into a segment
+const SEALED_KEY: &[u8] = b"meta:sealed";
+/// Number of segments sealed
+const SEGMENTS_KEY: &[u8] = b"meta:segments";
+/// Segments below this index are anchored
+const ANCHORED_KEY: &[u8] = b"meta:anchored";
 
 /// Receipt query filter
 #[derive(Debug, Clone, Default)]
 pub struct ReceiptQuery {
     /// Filter by sigma ID
     pub sigma_id: Option<String>,
+    /// Filter by case ID
+    pub case_id: Option<String>,
+    /// Filter by workflow ID
+    pub workflow_id: Option<String>,
     /// Filter by minimum timestamp
     pub min_timestamp_ms: Option<u64>,
     /// Filter by maximum timestamp
@@ -25,6 +53,45 @@ pub struct ReceiptQuery {
     pub limit: Option<usize>,
 }
 
+impl ReceiptQuery {
+    fn matches(&self, receipt: &Receipt) -> bool {
+        let field_matches = |filter: &Option<String>, value: Option<&str>| {
+            filter.as_deref().is_none_or(|filter| Some(filter) == value)
+        };
+        field_matches(&self.sigma_id, Some(&receipt.sigma_id))
+            && field_matches(&self.case_id, receipt.case_id.as_deref())
+            && field_matches(&self.workflow_id, receipt.workflow_id.as_deref())
+            && self
+                .only_valid
+                .is_none_or(|only_valid| only_valid == receipt.is_valid())
+    }
+
+    /// Index key range covering the query (most selective index first)
+    fn index_range(&self) -> (Vec<u8>, Vec<u8>) {
+        let mut prefix = match (&self.case_id, &self.workflow_id, &self.sigma_id) {
+            (Some(case_id), _, _) => index_prefix(b"c:", case_id),
+            (None, Some(workflow_id), _) => index_prefix(b"w:", workflow_id),
+            (None, None, Some(sigma_id)) => index_prefix(b"s:", sigma_id),
+            (None, None, None) => b"t:".to_vec(),
+        };
+        let mut end = prefix.clone();
+        prefix.extend_from_slice(&self.min_timestamp_ms.unwrap_or(0).to_be_bytes());
+        prefix.extend_from_slice(&[0u8; 8]);
+        end.extend_from_slice(&self.max_timestamp_ms.unwrap_or(u64::MAX).to_be_bytes());
+        end.extend_from_slice(&[0xffu8; 8]);
+        (prefix, end)
+    }
+}
+
+/// One page of a paginated query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptPage {
+    /// Receipts, oldest first
+    pub receipts: Vec<Receipt>,
+    /// Cursor of the next page (None on the last page)
+    pub next_cursor: Option<String>,
+}
+
 /// Receipt statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReceiptStats {
@@ -40,28 +107,101 @@ pub struct ReceiptStats {
     pub avg_ticks: f64,
 }
 
+/// Streaming iterator over the receipts matching a query
+///
+/// Walks the index lazily; receipts are loaded one at a time.
+pub struct ReceiptScan {
+    tree: sled::Tree,
+    keys: Box<dyn Iterator<Item = sled::Result<(sled::IVec, sled::IVec)>> + Send>,
+    query: ReceiptQuery,
+    remaining: Option<usize>,
+    last_key: Option<Vec<u8>>,
+}
+
+impl Iterator for ReceiptScan {
+    type Item = WorkflowResult<Receipt>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        loop {
+            let (key, _) = match self.keys.next()? {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(db_error(e))),
+            };
+            let seq = match key.len().checked_sub(8) {
+                Some(start) => key[start..].to_vec(),
+                None => continue,
+            };
+            let receipt = match self.tree.get(receipt_key_bytes(&seq)) {
+                Ok(Some(value)) => match decode_receipt(&value) {
+                    Ok(receipt) => receipt,
+                    Err(e) => return Some(Err(e)),
+                },
+                Ok(None) => continue,
+                Err(e) => return Some(Err(db_error(e))),
+            };
+            self.last_key = Some(key.to_vec());
+            if self.query.matches(&receipt) {
+                if let Some(ref mut remaining) = self.remaining {
+                    *remaining -= 1;
+                }
+                return Some(Ok(receipt));
+            }
+        }
+    }
+}
+
 /// Receipt store (immutable log)
 pub struct ReceiptStore {
-    /// Receipt log (append-only)
-    log: Arc<RwLock<Vec<Receipt>>>,
-    /// Receipt index by ID
-    index_by_id: Arc<RwLock<HashMap<String, usize>>>,
-    /// Receipt index by sigma ID
-    index_by_sigma: Arc<RwLock<HashMap<String, Vec<usize>>>>,
+    /// Receipt log, indexes and segments
+    tree: sled::Tree,
+    /// Serializes sealing
+    sealing: Mutex<()>,
 }
 
 impl ReceiptStore {
-    /// Create new receipt store
-    pub fn new() -> Self {
+    /// Create new in-memory receipt store
+    pub fn new() -> WorkflowResult<Self> {
+        Self::in_memory()
+    }
+
+    /// Create a receipt store backed by a temporary database (removed on drop)
+    pub fn in_memory() -> WorkflowResult<Self> {
+        let tree = sled::Config::new()
+            .temporary(true)
+            .open()
+            .and_then(|db| db.open_tree("receipts"))
+            .map_err(|e| {
+                WorkflowError::StatePersistence(format!(
+                    "Failed to open temporary receipt store: {:?}",
+                    e
+                ))
+            })?;
+        Ok(Self::from_tree(tree))
+    }
+
+    /// Open a persistent receipt store
+    pub fn open<P: AsRef<Path>>(path: P) -> WorkflowResult<Self> {
+        let tree = sled::open(path)
+            .and_then(|db| db.open_tree("receipts"))
+            .map_err(|e| {
+                WorkflowError::StatePersistence(format!("Failed to open receipt store: {:?}", e))
+            })?;
+        Ok(Self::from_tree(tree))
+    }
+
+    /// Create a receipt store over a sled tree (e.g. from `StateStore::open_tree`)
+    pub fn from_tree(tree: sled::Tree) -> Self {
         Self {
-            log: Arc::new(RwLock::new(Vec::new())),
-            index_by_id: Arc::new(RwLock::new(HashMap::new())),
-            index_by_sigma: Arc::new(RwLock::new(HashMap::new())),
+            tree,
+            sealing: Mutex::new(()),
         }
     }
 
     /// Store a receipt (append-only)
-    pub async fn store(&self, receipt: Receipt) -> WorkflowResult<()> {
+    pub fn store(&self, receipt: Receipt) -> WorkflowResult<()> {
         // Verify receipt signature before storing
         if !receipt.verify_signature() {
             return Err(WorkflowError::ReceiptGenerationFailed(
@@ -69,147 +209,465 @@ impl ReceiptStore {
             ));
         }
 
-        let mut log = self.log.write().await;
-        let mut index_by_id = self.index_by_id.write().await;
-        let mut index_by_sigma = self.index_by_sigma.write().await;
-
-        // Check for duplicate
-        if index_by_id.contains_key(&receipt.receipt_id) {
-            return Err(WorkflowError::Validation(format!(
-                "Receipt {} already exists",
-                receipt.receipt_id
-            )));
+        let value = serde_json::to_vec(&receipt)
+            .map_err(|e| WorkflowError::StatePersistence(format!("Serialization error: {}", e)))?;
+        let id_key = prefixed(b"id:", receipt.receipt_id.as_bytes());
+        let ts = receipt.timestamp_ms.to_be_bytes();
+        let mut index_prefixes = vec![b"t:".to_vec(), index_prefix(b"s:", &receipt.sigma_id)];
+        if let Some(ref case_id) = receipt.case_id {
+            index_prefixes.push(index_prefix(b"c:", case_id));
+        }
+        if let Some(ref workflow_id) = receipt.workflow_id {
+            index_prefixes.push(index_prefix(b"w:", workflow_id));
         }
 
-        // Append to log
-        let index = log.len();
-        log.push(receipt.clone());
-
-        // Update indices
-        index_by_id.insert(receipt.receipt_id.clone(), index);
-        index_by_sigma
-            .entry(receipt.sigma_id.clone())
-            .or_insert_with(Vec::new)
-            .push(index);
-
-        Ok(())
+        let result = self.tree.transaction(|tx| {
+            // Check for duplicate
+            if tx.get(&id_key)?.is_some() {
+                return Err(ConflictableTransactionError::Abort(
+                    WorkflowError::Validation(format!(
+                        "Receipt {} already exists",
+                        receipt.receipt_id
+                    )),
+                ));
+            }
+
+            let seq = (read_u64(tx.get(SEQ_KEY)?.as_deref()) + 1).to_be_bytes();
+            tx.insert(SEQ_KEY, seq.to_vec())?;
+            tx.insert(receipt_key_bytes(&seq), value.clone())?;
+            tx.insert(id_key.clone(), seq.to_vec())?;
+
+            // Update indices
+            for prefix in &index_prefixes {
+                let mut key = prefix.clone();
+                key.extend_from_slice(&ts);
+                key.extend_from_slice(&seq);
+                tx.insert(key, Vec::new())?;
+            }
+            Ok(())
+        });
+        result.map_err(transaction_error)
     }
 
     /// Get receipt by ID
-    pub async fn get_by_id(&self, receipt_id: &str) -> Option<Receipt> {
-        let log = self.log.read().await;
-        let index = self.index_by_id.read().await;
-
-        index.get(receipt_id).and_then(|&idx| log.get(idx).cloned())
+    pub fn get_by_id(&self, receipt_id: &str) -> WorkflowResult<Option<Receipt>> {
+        match self.seq_of(receipt_id)? {
+            Some(seq) => self.load(seq),
+            None => Ok(None),
+        }
     }
 
-    /// Get receipts by sigma ID
-    pub async fn get_by_sigma(&self, sigma_id: &str) -> Vec<Receipt> {
-        let log = self.log.read().await;
-        let index = self.index_by_sigma.read().await;
+    /// Get receipts by sigma ID (oldest first)
+    pub fn get_by_sigma(&self, sigma_id: &str) -> WorkflowResult<Vec<Receipt>> {
+        self.scan(ReceiptQuery {
+            sigma_id: Some(sigma_id.to_string()),
+            ..Default::default()
+        })
+        .collect()
+    }
 
-        index
-            .get(sigma_id)
-            .map(|indices| {
-                indices
-                    .iter()
-                    .filter_map(|&idx| log.get(idx).cloned())
-                    .collect()
-            })
-            .unwrap_or_default()
+    /// Get receipts of a case (oldest first)
+    pub fn get_by_case(&self, case_id: &str) -> WorkflowResult<Vec<Receipt>> {
+        self.scan(ReceiptQuery {
+            case_id: Some(case_id.to_string()),
+            ..Default::default()
+        })
+        .collect()
     }
 
-    /// Query receipts
-    pub async fn query(&self, query: ReceiptQuery) -> Vec<Receipt> {
-        let log = self.log.read().await;
+    /// Stream the receipts matching a query, oldest first
+    pub fn scan(&self, query: ReceiptQuery) -> ReceiptScan {
+        let (start, end) = query.index_range();
+        self.scan_range(query, Bound::Included(start), Bound::Included(end), false)
+    }
 
-        let mut results: Vec<Receipt> = log
-            .iter()
-            .filter(|r| {
-                // Filter by sigma ID
-                if let Some(ref sigma_id) = query.sigma_id {
-                    if &r.sigma_id != sigma_id {
-                        return false;
-                    }
-                }
+    /// Query receipts (newest first)
+    pub fn query(&self, query: ReceiptQuery) -> WorkflowResult<Vec<Receipt>> {
+        let (start, end) = query.index_range();
+        self.scan_range(query, Bound::Included(start), Bound::Included(end), true)
+            .collect()
+    }
 
-                // Filter by timestamp range
-                if let Some(min_ts) = query.min_timestamp_ms {
-                    if r.timestamp_ms < min_ts {
-                        return false;
-                    }
-                }
-                if let Some(max_ts) = query.max_timestamp_ms {
-                    if r.timestamp_ms > max_ts {
-                        return false;
-                    }
-                }
+    /// Most recent receipts (newest first)
+    pub fn query_recent(&self, limit: usize) -> WorkflowResult<Vec<Receipt>> {
+        self.query(ReceiptQuery {
+            limit: Some(limit),
+            ..Default::default()
+        })
+    }
 
-                // Filter by validity
-                if let Some(only_valid) = query.only_valid {
-                    if only_valid && !r.is_valid() {
-                        return false;
-                    }
-                    if !only_valid && r.is_valid() {
-                        return false;
-                    }
+    /// Query one page of receipts, oldest first
+    ///
+    /// Pass the `next_cursor` of a page to get the following one. Pages are
+    /// ordered by receipt timestamp, and a cursor marks a position in that
+    /// order: receipts appended later with a newer timestamp show up on later
+    /// pages, but one appended with a timestamp before the cursor sorts behind
+    /// it and is not returned by the remaining pages.
+    pub fn query_page(
+        &self,
+        query: ReceiptQuery,
+        cursor: Option<&str>,
+        page_size: usize,
+    ) -> WorkflowResult<ReceiptPage> {
+        let (start, end) = query.index_range();
+        let start = match cursor {
+            Some(cursor) => {
+                let key = hex::decode(cursor).map_err(|e| {
+                    WorkflowError::Validation(format!("Invalid receipt cursor: {}", e))
+                })?;
+                if key < start || key > end {
+                    return Err(WorkflowError::Validation(
+                        "Receipt cursor does not belong to this query".to_string(),
+                    ));
                 }
+                Bound::Excluded(key)
+            }
+            None => Bound::Included(start),
+        };
 
-                true
-            })
-            .cloned()
-            .collect();
-
-        // Sort by timestamp (newest first)
-        results.sort_by(|a, b| b.timestamp_ms.cmp(&a.timestamp_ms));
-
-        // Apply limit
-        if let Some(limit) = query.limit {
-            results.truncate(limit);
-        }
-
-        results
+        let query = ReceiptQuery {
+            limit: Some(page_size),
+            ..query
+        };
+        let mut scan = self.scan_range(query, start, Bound::Included(end.clone()), false);
+        let receipts = scan.by_ref().collect::<WorkflowResult<Vec<_>>>()?;
+        // Only hand out a cursor if a full page was returned and the index goes on
+        let next_cursor = match scan.last_key {
+            Some(last_key) if receipts.len() == page_size => {
+                let more = self
+                    .tree
+                    .range((Bound::Excluded(last_key.clone()), Bound::Included(end)))
+                    .next()
+                    .is_some();
+                more.then(|| hex::encode(last_key))
+            }
+            _ => None,
+        };
+        Ok(ReceiptPage {
+            receipts,
+            next_cursor,
+        })
     }
 
     /// Get receipt statistics
-    pub async fn get_stats(&self) -> ReceiptStats {
-        let log = self.log.read().await;
-
-        let total_receipts = log.len();
-        let valid_receipts = log.iter().filter(|r| r.is_valid()).count();
-        let invalid_receipts = total_receipts - valid_receipts;
-        let total_ticks: u64 = log.iter().map(|r| r.ticks_used as u64).sum();
+    pub fn get_stats(&self) -> WorkflowResult<ReceiptStats> {
+        let mut total_receipts = 0;
+        let mut valid_receipts = 0;
+        let mut total_ticks: u64 = 0;
+        for receipt in self.scan(ReceiptQuery::default()) {
+            let receipt = receipt?;
+            total_receipts += 1;
+            if receipt.is_valid() {
+                valid_receipts += 1;
+            }
+            total_ticks += receipt.ticks_used as u64;
+        }
         let avg_ticks = if total_receipts > 0 {
             total_ticks as f64 / total_receipts as f64
         } else {
             0.0
         };
 
-        ReceiptStats {
+        Ok(ReceiptStats {
             total_receipts,
             valid_receipts,
-            invalid_receipts,
+            invalid_receipts: total_receipts - valid_receipts,
             total_ticks,
             avg_ticks,
-        }
+        })
     }
 
     /// Get total receipts count
-    pub async fn count(&self) -> usize {
-        let log = self.log.read().await;
-        log.len()
+    pub fn count(&self) -> usize {
+        self.counter(SEQ_KEY).unwrap_or(0) as usize
+    }
+
+    /// Get all receipts (oldest first)
+    pub fn get_all(&self) -> WorkflowResult<Vec<Receipt>> {
+        self.scan(ReceiptQuery::default()).collect()
     }
 
-    /// Get all receipts
-    pub async fn get_all(&self) -> Vec<Receipt> {
-        let log = self.log.read().await;
-        log.clone()
+    /// Seal unsealed receipts into Merkle-batched segments of `segment_size`
+    ///
+    /// Receipts stay in the log; a segment records the Merkle root and the
+    /// membership of a batch. Only full segments are sealed; the remainder
+    /// waits for the next call. Each segment is committed before its root is
+    /// anchored, so the lockchain never holds the root of a segment the store
+    /// lost. With an anchor, every sealed segment not yet anchored is anchored,
+    /// including those left over when anchoring failed earlier. Returns the
+    /// segments sealed by this call.
+    pub fn seal(
+        &self,
+        segment_size: usize,
+        anchor: Option<&LockchainAnchor>,
+    ) -> WorkflowResult<Vec<ReceiptSegment>> {
+        if segment_size == 0 {
+            return Err(WorkflowError::Validation(
+                "Segment size must be positive".to_string(),
+            ));
+        }
+        let _guard = self
+            .sealing
+            .lock()
+            .map_err(|_| WorkflowError::Internal("Receipt sealing lock poisoned".to_string()))?;
+
+        let sealed = self.counter(SEALED_KEY)?;
+        let mut next_index = self.counter(SEGMENTS_KEY)?;
+        let mut pending = Vec::new();
+        let start = receipt_key_bytes(&(sealed + 1).to_be_bytes());
+        for entry in self
+            .tree
+            .range(start..=receipt_key_bytes(&u64::MAX.to_be_bytes()))
+        {
+            let (key, value) = entry.map_err(db_error)?;
+            pending.push((read_u64(Some(&key[2..])), decode_receipt(&value)?));
+        }
+
+        let mut segments = Vec::new();
+        for batch in pending.chunks_exact(segment_size) {
+            let receipts: Vec<Receipt> = batch.iter().map(|(_, r)| r.clone()).collect();
+            let root = segment_tree(&receipts)?.root();
+            let segment = ReceiptSegment {
+                index: next_index,
+                first_seq: batch[0].0,
+                last_seq: batch[batch.len() - 1].0,
+                receipt_ids: receipts.iter().map(|r| r.receipt_id.clone()).collect(),
+                root: hex::encode(root),
+                sealed_at: chrono::Utc::now(),
+                anchored: false,
+            };
+            let value = encode_segment(&segment)?;
+
+            let index = segment.index.to_be_bytes();
+            let result = self.tree.transaction(|tx| {
+                tx.insert(prefixed(b"seg:", &index), value.clone())?;
+                for (seq, _) in batch {
+                    tx.insert(prefixed(b"in:", &seq.to_be_bytes()), index.to_vec())?;
+                }
+                tx.insert(SEALED_KEY, segment.last_seq.to_be_bytes().to_vec())?;
+                tx.insert(SEGMENTS_KEY, (segment.index + 1).to_be_bytes().to_vec())?;
+                Ok::<(), ConflictableTransactionError<WorkflowError>>(())
+            });
+            result.map_err(transaction_error)?;
+
+            next_index += 1;
+            segments.push(segment);
+        }
+
+        if let Some(anchor) = anchor {
+            self.anchor_segments(anchor)?;
+            for segment in &mut segments {
+                segment.anchored = true;
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Anchor the roots of sealed segments not yet anchored, oldest first
+    ///
+    /// A root the lockchain already holds (anchored by a run that failed
+    /// before recording it) is not anchored again. Caller must hold `sealing`.
+    fn anchor_segments(&self, anchor: &LockchainAnchor) -> WorkflowResult<()> {
+        let total = self.counter(SEGMENTS_KEY)?;
+        for index in self.counter(ANCHORED_KEY)?..total {
+            let mut segment = self.get_segment(index)?.ok_or_else(|| {
+                WorkflowError::StatePersistence(format!("Receipt segment {} is missing", index))
+            })?;
+            if !segment.anchored {
+                if !anchor.verify_segment(&segment)? {
+                    let root = hex::decode(&segment.root)
+                        .ok()
+                        .and_then(|root| <[u8; 32]>::try_from(root).ok())
+                        .ok_or_else(|| {
+                            WorkflowError::StatePersistence(format!(
+                                "Receipt segment {} has a malformed root",
+                                index
+                            ))
+                        })?;
+                    anchor.anchor(index, root)?;
+                }
+                segment.anchored = true;
+                self.tree
+                    .insert(
+                        prefixed(b"seg:", &index.to_be_bytes()),
+                        encode_segment(&segment)?,
+                    )
+                    .map_err(db_error)?;
+            }
+            self.tree
+                .insert(ANCHORED_KEY, (index + 1).to_be_bytes().to_vec())
+                .map_err(db_error)?;
+        }
+        Ok(())
+    }
+
+    /// Get a sealed segment
+    pub fn get_segment(&self, index: u64) -> WorkflowResult<Option<ReceiptSegment>> {
+        match self
+            .tree
+            .get(prefixed(b"seg:", &index.to_be_bytes()))
+            .map_err(db_error)?
+        {
+            Some(value) => serde_json::from_slice(&value).map(Some).map_err(|e| {
+                WorkflowError::StatePersistence(format!("Deserialization error: {}", e))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Export the receipt chain of a case with inclusion proofs
+    pub fn export_case_chain(&self, case_id: &str) -> WorkflowResult<ReceiptChain> {
+        let mut trees: HashMap<u64, (ReceiptSegment, knhk_lockchain::MerkleTree)> = HashMap::new();
+        let mut receipts = Vec::new();
+        for receipt in self.get_by_case(case_id)? {
+            let proof = match self.segment_of(&receipt.receipt_id)? {
+                Some(index) => {
+                    if !trees.contains_key(&index) {
+                        let segment = self.get_segment(index)?.ok_or_else(|| {
+                            WorkflowError::StatePersistence(format!(
+                                "Receipt segment {} is missing",
+                                index
+                            ))
+                        })?;
+                        let members = segment
+                            .receipt_ids
+                            .iter()
+                            .map(|id| {
+                                self.get_by_id(id)?.ok_or_else(|| {
+                                    WorkflowError::StatePersistence(format!(
+                                        "Receipt {} of segment {} is missing",
+                                        id, index
+                                    ))
+                                })
+                            })
+                            .collect::<WorkflowResult<Vec<_>>>()?;
+                        trees.insert(index, (segment, segment_tree(&members)?));
+                    }
+                    let (segment, tree) = &trees[&index];
+                    let leaf_index = segment
+                        .receipt_ids
+                        .iter()
+                        .position(|id| *id == receipt.receipt_id)
+                        .ok_or_else(|| {
+                            WorkflowError::StatePersistence(format!(
+                                "Receipt {} is not listed in segment {}",
+                                receipt.receipt_id, index
+                            ))
+                        })?;
+                    let proof = tree.generate_proof(leaf_index).map_err(|e| {
+                        WorkflowError::Internal(format!("Merkle proof generation failed: {}", e))
+                    })?;
+                    Some(InclusionProof::from_merkle(index, &proof))
+                }
+                None => None,
+            };
+            receipts.push(ChainedReceipt { receipt, proof });
+        }
+
+        let mut segments: Vec<ReceiptSegment> =
+            trees.into_values().map(|(segment, _)| segment).collect();
+        segments.sort_by_key(|segment| segment.index);
+        Ok(ReceiptChain {
+            case_id: case_id.to_string(),
+            receipts,
+            segments,
+        })
+    }
+
+    fn scan_range(
+        &self,
+        query: ReceiptQuery,
+        start: Bound<Vec<u8>>,
+        end: Bound<Vec<u8>>,
+        newest_first: bool,
+    ) -> ReceiptScan {
+        let range = self.tree.range((start, end));
+        let keys: Box<dyn Iterator<Item = sled::Result<(sled::IVec, sled::IVec)>> + Send> =
+            if newest_first {
+                Box::new(range.rev())
+            } else {
+                Box::new(range)
+            };
+        ReceiptScan {
+            tree: self.tree.clone(),
+            keys,
+            remaining: query.limit,
+            query,
+            last_key: None,
+        }
+    }
+
+    fn seq_of(&self, receipt_id: &str) -> WorkflowResult<Option<[u8; 8]>> {
+        let seq = self
+            .tree
+            .get(prefixed(b"id:", receipt_id.as_bytes()))
+            .map_err(db_error)?;
+        Ok(seq.map(|seq| read_u64(Some(&seq)).to_be_bytes()))
+    }
+
+    fn load(&self, seq: [u8; 8]) -> WorkflowResult<Option<Receipt>> {
+        match self.tree.get(receipt_key_bytes(&seq)).map_err(db_error)? {
+            Some(value) => decode_receipt(&value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn segment_of(&self, receipt_id: &str) -> WorkflowResult<Option<u64>> {
+        let seq = match self.seq_of(receipt_id)? {
+            Some(seq) => seq,
+            None => return Ok(None),
+        };
+        let index = self.tree.get(prefixed(b"in:", &seq)).map_err(db_error)?;
+        Ok(index.map(|index| read_u64(Some(&index))))
+    }
+
+    fn counter(&self, key: &[u8]) -> WorkflowResult<u64> {
+        let value = self.tree.get(key).map_err(db_error)?;
+        Ok(read_u64(value.as_deref()))
     }
 }
 
-impl Default for ReceiptStore {
-    fn default() -> Self {
-        Self::new()
+fn prefixed(prefix: &[u8], bytes: &[u8]) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(bytes);
+    key
+}
+
+fn index_prefix(prefix: &[u8], value: &str) -> Vec<u8> {
+    let mut key = prefixed(prefix, value.as_bytes());
+    key.push(0);
+    key
+}
+
+fn receipt_key_bytes(seq: &[u8]) -> Vec<u8> {
+    prefixed(b"r:", seq)
+}
+
+fn read_u64(bytes: Option<&[u8]>) -> u64 {
+    bytes
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_be_bytes)
+        .unwrap_or(0)
+}
+
+fn decode_receipt(value: &[u8]) -> WorkflowResult<Receipt> {
+    serde_json::from_slice(value)
+        .map_err(|e| WorkflowError::StatePersistence(format!("Deserialization error: {}", e)))
+}
+
+fn encode_segment(segment: &ReceiptSegment) -> WorkflowResult<Vec<u8>> {
+    serde_json::to_vec(segment)
+        .map_err(|e| WorkflowError::StatePersistence(format!("Serialization error: {}", e)))
+}
+
+fn db_error(e: sled::Error) -> WorkflowError {
+    WorkflowError::StatePersistence(format!("Database error: {:?}", e))
+}
+
+fn transaction_error(e: TransactionError<WorkflowError>) -> WorkflowError {
+    match e {
+        TransactionError::Abort(e) => e,
+        TransactionError::Storage(e) => db_error(e),
     }
 }
 
@@ -217,10 +675,21 @@ impl Default for ReceiptStore {
 mod tests {
     use super::*;
     use crate::receipts::receipt_generator::ReceiptGenerator;
+    use knhk_lockchain::{LockchainStorage, PeerId, QuorumManager};
+    use tempfile::TempDir;
+
+    fn case_receipt(generator: &ReceiptGenerator, case_id: &str, ticks: u32) -> Receipt {
+        let o_in = serde_json::json!({"case": case_id, "ticks": ticks});
+        let a_out = serde_json::json!({"output": ticks});
+        generator
+            .generate_receipt("sigma-1".to_string(), &o_in, &a_out, vec![], vec![], ticks)
+            .expect("Generation failed")
+            .for_case(case_id, "workflow-1")
+    }
 
-    #[tokio::test]
-    async fn test_receipt_store() {
-        let store = ReceiptStore::new();
+    #[test]
+    fn test_receipt_store() {
+        let store = ReceiptStore::new().unwrap();
         let generator = ReceiptGenerator::new();
 
         let o_in = serde_json::json!({"input": 1});
@@ -230,16 +699,16 @@ mod tests {
             .generate_receipt("sigma-1".to_string(), &o_in, &a_out, vec![], vec![], 5)
             .expect("Generation failed");
 
-        store.store(receipt.clone()).await.expect("Store failed");
+        store.store(receipt.clone()).expect("Store failed");
 
-        let retrieved = store.get_by_id(&receipt.receipt_id).await;
+        let retrieved = store.get_by_id(&receipt.receipt_id).expect("Get failed");
         assert!(retrieved.is_some());
         assert_eq!(retrieved.unwrap().receipt_id, receipt.receipt_id);
     }
 
-    #[tokio::test]
-    async fn test_receipt_query() {
-        let store = ReceiptStore::new();
+    #[test]
+    fn test_receipt_query() {
+        let store = ReceiptStore::new().unwrap();
         let generator = ReceiptGenerator::new();
 
         // Store multiple receipts
@@ -258,7 +727,7 @@ mod tests {
                 )
                 .expect("Generation failed");
 
-            store.store(receipt).await.expect("Store failed");
+            store.store(receipt).expect("Store failed");
         }
 
         // Query by sigma ID
@@ -267,13 +736,14 @@ mod tests {
             ..Default::default()
         };
 
-        let results = store.query(query).await;
+        let results = store.query(query).expect("Query failed");
         assert_eq!(results.len(), 5);
+        assert_eq!(store.query_recent(2).expect("Query failed").len(), 2);
     }
 
-    #[tokio::test]
-    async fn test_receipt_stats() {
-        let store = ReceiptStore::new();
+    #[test]
+    fn test_receipt_stats() {
+        let store = ReceiptStore::new().unwrap();
         let generator = ReceiptGenerator::new();
 
         let o_in = serde_json::json!({});
@@ -283,7 +753,7 @@ mod tests {
         let receipt1 = generator
             .generate_receipt("sigma-1".to_string(), &o_in, &a_out, vec![], vec![], 10)
             .expect("Generation failed");
-        store.store(receipt1).await.expect("Store failed");
+        store.store(receipt1).expect("Store failed");
 
         // Invalid receipt
         let receipt2 = generator
@@ -296,9 +766,9 @@ mod tests {
                 5,
             )
             .expect("Generation failed");
-        store.store(receipt2).await.expect("Store failed");
+        store.store(receipt2).expect("Store failed");
 
-        let stats = store.get_stats().await;
+        let stats = store.get_stats().expect("Stats failed");
         assert_eq!(stats.total_receipts, 2);
         assert_eq!(stats.valid_receipts, 1);
         assert_eq!(stats.invalid_receipts, 1);
@@ -306,9 +776,9 @@ mod tests {
         assert_eq!(stats.avg_ticks, 7.5);
     }
 
-    #[tokio::test]
-    async fn test_duplicate_prevention() {
-        let store = ReceiptStore::new();
+    #[test]
+    fn test_duplicate_prevention() {
+        let store = ReceiptStore::new().unwrap();
         let generator = ReceiptGenerator::new();
 
         let o_in = serde_json::json!({});
@@ -318,12 +788,157 @@ mod tests {
             .generate_receipt("sigma-1".to_string(), &o_in, &a_out, vec![], vec![], 1)
             .expect("Generation failed");
 
-        store
-            .store(receipt.clone())
-            .await
-            .expect("First store failed");
-        let result = store.store(receipt).await;
+        store.store(receipt.clone()).expect("First store failed");
+        let result = store.store(receipt);
 
         assert!(result.is_err(), "Should reject duplicate receipt");
+        assert_eq!(store.count(), 1);
+    }
+
+    #[test]
+    fn test_receipts_survive_reopen_with_indexes() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = ReceiptGenerator::new();
+        let receipt = case_receipt(&generator, "case-1", 3);
+        {
+            let store = ReceiptStore::open(temp_dir.path()).unwrap();
+            store.store(receipt.clone()).unwrap();
+            store.store(case_receipt(&generator, "case-2", 4)).unwrap();
+        }
+
+        let store = ReceiptStore::open(temp_dir.path()).unwrap();
+        assert_eq!(store.count(), 2);
+        assert_eq!(store.get_by_case("case-1").unwrap(), vec![receipt.clone()]);
+        let by_workflow = store
+            .query(ReceiptQuery {
+                workflow_id: Some("workflow-1".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(by_workflow.len(), 2);
+        let before = store
+            .query(ReceiptQuery {
+                max_timestamp_ms: Some(receipt.timestamp_ms.saturating_sub(1)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(before.is_empty());
+    }
+
+    #[test]
+    fn test_paginated_range_query() {
+        let store = ReceiptStore::new().unwrap();
+        let generator = ReceiptGenerator::new();
+        let mut stored = Vec::new();
+        for ticks in 0..5 {
+            let receipt = case_receipt(&generator, "case-1", ticks);
+            stored.push(receipt.receipt_id.clone());
+            store.store(receipt).unwrap();
+        }
+        store.store(case_receipt(&generator, "case-2", 9)).unwrap();
+
+        let query = ReceiptQuery {
+            case_id: Some("case-1".to_string()),
+            ..Default::default()
+        };
+        let mut seen = Vec::new();
+        let mut cursor: Option<String> = None;
+        let mut pages = 0;
+        loop {
+            let page = store
+                .query_page(query.clone(), cursor.as_deref(), 2)
+                .unwrap();
+            pages += 1;
+            seen.extend(page.receipts.into_iter().map(|r| r.receipt_id));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(seen, stored);
+        assert!(store
+            .query_page(ReceiptQuery::default(), Some("zz"), 2)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sealing_anchors_segments_and_exports_provable_chain() {
+        let lockchain_dir = TempDir::new().unwrap();
+        let anchor = LockchainAnchor::new(
+            LockchainStorage::new(&lockchain_dir.path().to_string_lossy()).unwrap(),
+            QuorumManager::new(
+                vec![PeerId("peer-1".to_string())],
+                2,
+                PeerId("self".to_string()),
+            ),
+        )
+        .unwrap();
+        let store = ReceiptStore::new().unwrap();
+        let generator = ReceiptGenerator::new();
+        for ticks in 0..5 {
+            let case_id = if ticks % 2 == 0 { "case-1" } else { "case-2" };
+            store
+                .store(case_receipt(&generator, case_id, ticks))
+                .unwrap();
+        }
+
+        // Two full segments are sealed, the fifth receipt waits
+        let segments = store.seal(2, Some(&anchor)).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert!(segments.iter().all(|s| s.anchored));
+        assert!(store.seal(2, Some(&anchor)).unwrap().is_empty());
+        for segment in &segments {
+            assert!(anchor.verify_segment(segment).unwrap());
+        }
+
+        let chain = store.export_case_chain("case-1").unwrap();
+        assert_eq!(chain.receipts.len(), 3);
+        assert!(chain.receipts[..2].iter().all(|r| r.proof.is_some()));
+        assert!(chain.receipts[2].proof.is_none());
+        chain.verify().unwrap();
+
+        // A tampered receipt no longer matches its proof
+        let mut tampered = chain.clone();
+        tampered.receipts[0].receipt.ticks_used = 99;
+        assert!(tampered.verify().is_err());
+        let mut forged = chain;
+        forged.segments[0].root = hex::encode([0u8; 32]);
+        assert!(forged.verify().is_err());
+    }
+
+    #[test]
+    fn test_sealing_without_anchor_is_anchored_later() {
+        let lockchain_dir = TempDir::new().unwrap();
+        let anchor = LockchainAnchor::new(
+            LockchainStorage::new(&lockchain_dir.path().to_string_lossy()).unwrap(),
+            QuorumManager::new(
+                vec![PeerId("peer-1".to_string())],
+                2,
+                PeerId("self".to_string()),
+            ),
+        )
+        .unwrap();
+        let store = ReceiptStore::new().unwrap();
+        let generator = ReceiptGenerator::new();
+        for ticks in 0..4 {
+            store
+                .store(case_receipt(&generator, "case-1", ticks))
+                .unwrap();
+        }
+
+        // Segments are committed even when nothing anchors them yet
+        let sealed = store.seal(2, None).unwrap();
+        assert_eq!(sealed.len(), 2);
+        assert!(sealed.iter().all(|s| !s.anchored));
+        assert!(!anchor.verify_segment(&sealed[0]).unwrap());
+
+        // A later run anchors them without sealing anything new
+        assert!(store.seal(2, Some(&anchor)).unwrap().is_empty());
+        for index in 0..2 {
+            let segment = store.get_segment(index).unwrap().unwrap();
+            assert!(segment.anchored);
+            assert!(anchor.verify_segment(&segment).unwrap());
+        }
     }
 }
@@ -244,6 +244,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         let task = crate::parser::Task {
@@ -265,6 +269,10 @@ mod tests {
             input_parameters: Vec::new(),
             output_parameters: Vec::new(),
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         };
 
         spec.tasks.insert("task1".to_string(), task);
@@ -290,6 +298,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         // Add tasks with different tick budgets
@@ -312,6 +324,10 @@ mod tests {
             input_parameters: Vec::new(),
             output_parameters: Vec::new(),
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         };
 
         let task2 = crate::parser::Task {
@@ -333,6 +349,10 @@ mod tests {
             input_parameters: Vec::new(),
             output_parameters: Vec::new(),
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         };
 
         spec.tasks.insert("task1".to_string(), task1);
@@ -1,16 +1,37 @@
 #![allow(clippy::unwrap_used)] // Supporting infrastructure - unwrap() acceptable for now
 #![allow(clippy::unwrap_used)] // Supporting infrastructure - unwrap() acceptable for now
 //! Dead letter queue for failed workflow operations
+//!
+//! Entries can be kept in memory or persisted in a sled tree (see
+//! [`DeadLetterQueue::persistent`]) so they survive restarts. Due entries are
+//! redriven through a [`DeadLetterHandler`] with the backoff of a
+//! [`RetryConfig`]; entries failing with a non-retryable error or exhausting
+//! their attempts are quarantined until an operator requeues or purges them.
 
 use crate::case::CaseId;
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::parser::WorkflowSpecId;
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use uuid::Uuid;
 
+use super::retry::{RetryConfig, RetryPolicy};
+
+/// Status of a dead letter entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DlqEntryStatus {
+    /// Waiting for (automatic) redrive
+    #[default]
+    Pending,
+    /// Poison message: not redriven until requeued
+    Quarantined,
+}
+
 /// Dead letter queue entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DLQEntry {
@@ -32,12 +53,51 @@ pub struct DLQEntry {
     pub retry_count: u32,
     /// Next retry timestamp
     pub next_retry_at: Option<DateTime<Utc>>,
+    /// Entry status
+    #[serde(default)]
+    pub status: DlqEntryStatus,
+    /// Why the entry was quarantined
+    #[serde(default)]
+    pub quarantine_reason: Option<String>,
+}
+
+impl DLQEntry {
+    /// Check whether the entry is due for automatic redrive
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == DlqEntryStatus::Pending
+            && self
+                .next_retry_at
+                .map(|retry_at| retry_at <= now)
+                .unwrap_or(true)
+    }
+}
+
+/// Replays the operation of a dead letter entry
+#[async_trait]
+pub trait DeadLetterHandler: Send + Sync {
+    /// Redrive the failed operation; an error keeps the entry in the queue
+    async fn redrive(&self, entry: &DLQEntry) -> WorkflowResult<()>;
+}
+
+/// Outcome of a redrive pass
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedriveSummary {
+    /// Entries redriven successfully (removed from the queue)
+    pub succeeded: usize,
+    /// Entries that failed and were rescheduled
+    pub rescheduled: usize,
+    /// Entries that failed and were quarantined
+    pub quarantined: usize,
 }
 
 /// Dead letter queue for failed operations
 pub struct DeadLetterQueue {
     entries: Arc<Mutex<VecDeque<DLQEntry>>>,
     max_size: usize,
+    /// Durable copy of the entries (keyed by entry ID)
+    tree: Option<sled::Tree>,
+    /// Entries currently being redriven
+    in_flight: Mutex<HashSet<Uuid>>,
 }
 
 impl DeadLetterQueue {
@@ -46,9 +106,75 @@ impl DeadLetterQueue {
         Self {
             entries: Arc::new(Mutex::new(VecDeque::new())),
             max_size,
+            tree: None,
+            in_flight: Mutex::new(HashSet::new()),
         }
     }
 
+    /// Create a dead letter queue persisted in a sled tree
+    ///
+    /// Entries already in the tree are loaded, oldest first.
+    pub fn persistent(tree: sled::Tree, max_size: usize) -> WorkflowResult<Self> {
+        let mut loaded = Vec::new();
+        for result in tree.iter() {
+            let (_, value) = result
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            let entry: DLQEntry = serde_json::from_slice(value.as_ref()).map_err(|e| {
+                WorkflowError::StatePersistence(format!("Deserialization error: {}", e))
+            })?;
+            loaded.push(entry);
+        }
+        loaded.sort_by_key(|entry| entry.created_at);
+
+        let dlq = Self {
+            tree: Some(tree),
+            ..Self::new(max_size)
+        };
+        *dlq.lock()? = loaded.into();
+        Ok(dlq)
+    }
+
+    fn lock(&self) -> WorkflowResult<std::sync::MutexGuard<'_, VecDeque<DLQEntry>>> {
+        self.entries
+            .lock()
+            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))
+    }
+
+    fn persist(&self, entry: &DLQEntry) -> WorkflowResult<()> {
+        if let Some(ref tree) = self.tree {
+            let value = serde_json::to_vec(entry).map_err(|e| {
+                WorkflowError::StatePersistence(format!("Serialization error: {}", e))
+            })?;
+            tree.insert(entry.id.as_bytes(), value)
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn unpersist(&self, id: Uuid) -> WorkflowResult<()> {
+        if let Some(ref tree) = self.tree {
+            tree.remove(id.as_bytes())
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Apply a change to an entry and persist it
+    fn update<F>(&self, id: Uuid, change: F) -> WorkflowResult<DLQEntry>
+    where
+        F: FnOnce(&mut DLQEntry),
+    {
+        let mut entries = self.lock()?;
+        let entry = entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or_else(|| dead_letter_not_found(id))?;
+        change(entry);
+        let updated = entry.clone();
+        self.persist(&updated)?;
+        Ok(updated)
+    }
+
     /// Add an entry to the DLQ
     pub fn add(
         &self,
@@ -57,14 +183,13 @@ impl DeadLetterQueue {
         error: WorkflowError,
         operation_data: serde_json::Value,
     ) -> WorkflowResult<Uuid> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        let mut entries = self.lock()?;
 
         // Remove oldest entries if at capacity
         while entries.len() >= self.max_size {
-            entries.pop_front();
+            if let Some(evicted) = entries.pop_front() {
+                self.unpersist(evicted.id)?;
+            }
         }
 
         let entry = DLQEntry {
@@ -77,9 +202,12 @@ impl DeadLetterQueue {
             created_at: Utc::now(),
             retry_count: 0,
             next_retry_at: None,
+            status: DlqEntryStatus::Pending,
+            quarantine_reason: None,
         };
 
         let id = entry.id;
+        self.persist(&entry)?;
         entries.push_back(entry);
 
         Ok(id)
@@ -87,21 +215,12 @@ impl DeadLetterQueue {
 
     /// Get entries ready for retry
     pub fn get_retryable_entries(&self) -> WorkflowResult<Vec<DLQEntry>> {
-        let entries = self
-            .entries
-            .lock()
-            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        let entries = self.lock()?;
 
         let now = Utc::now();
         let retryable: Vec<DLQEntry> = entries
             .iter()
-            .filter(|entry| {
-                entry.retry_count < 3
-                    && entry
-                        .next_retry_at
-                        .map(|retry_at| retry_at <= now)
-                        .unwrap_or(true)
-            })
+            .filter(|entry| entry.is_due(now))
             .cloned()
             .collect();
 
@@ -110,31 +229,28 @@ impl DeadLetterQueue {
 
     /// Remove an entry from the DLQ
     pub fn remove(&self, id: Uuid) -> WorkflowResult<()> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        let mut entries = self.lock()?;
 
         entries.retain(|entry| entry.id != id);
-        Ok(())
+        self.unpersist(id)
+    }
+
+    /// Get an entry
+    pub fn get(&self, id: Uuid) -> WorkflowResult<Option<DLQEntry>> {
+        let entries = self.lock()?;
+        Ok(entries.iter().find(|e| e.id == id).cloned())
     }
 
     /// Get all entries
     pub fn list(&self) -> WorkflowResult<Vec<DLQEntry>> {
-        let entries = self
-            .entries
-            .lock()
-            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        let entries = self.lock()?;
 
         Ok(entries.iter().cloned().collect())
     }
 
     /// Get entry count
     pub fn len(&self) -> WorkflowResult<usize> {
-        let entries = self
-            .entries
-            .lock()
-            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        let entries = self.lock()?;
 
         Ok(entries.len())
     }
@@ -146,18 +262,211 @@ impl DeadLetterQueue {
 
     /// Update retry count and next retry time
     pub fn update_retry(&self, id: Uuid, next_retry_at: DateTime<Utc>) -> WorkflowResult<()> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        let mut entries = self.lock()?;
 
         if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
             entry.retry_count += 1;
             entry.next_retry_at = Some(next_retry_at);
+            let updated = entry.clone();
+            self.persist(&updated)?;
         }
 
         Ok(())
     }
+
+    /// Replace the operation data of an entry (e.g. to fix a poison payload)
+    pub fn edit_payload(
+        &self,
+        id: Uuid,
+        operation_data: serde_json::Value,
+    ) -> WorkflowResult<DLQEntry> {
+        self.update(id, |entry| entry.operation_data = operation_data)
+    }
+
+    /// Make an entry due immediately with a fresh retry budget
+    ///
+    /// Releases quarantined entries.
+    pub fn requeue(&self, id: Uuid) -> WorkflowResult<DLQEntry> {
+        self.update(id, |entry| {
+            entry.status = DlqEntryStatus::Pending;
+            entry.quarantine_reason = None;
+            entry.retry_count = 0;
+            entry.next_retry_at = None;
+        })
+    }
+
+    /// Quarantine an entry so it is no longer redriven automatically
+    pub fn quarantine(&self, id: Uuid, reason: impl Into<String>) -> WorkflowResult<DLQEntry> {
+        let reason = reason.into();
+        self.update(id, |entry| {
+            entry.status = DlqEntryStatus::Quarantined;
+            entry.quarantine_reason = Some(reason);
+            entry.next_retry_at = None;
+        })
+    }
+
+    /// Remove all entries, or only those with the given status
+    ///
+    /// Returns the number of purged entries.
+    pub fn purge(&self, status: Option<DlqEntryStatus>) -> WorkflowResult<usize> {
+        let mut entries = self.lock()?;
+        let mut purged = Vec::new();
+        entries.retain(|entry| {
+            let matches = status.map(|s| entry.status == s).unwrap_or(true);
+            if matches {
+                purged.push(entry.id);
+            }
+            !matches
+        });
+        for id in &purged {
+            self.unpersist(*id)?;
+        }
+        Ok(purged.len())
+    }
+
+    /// Record a failed redrive attempt
+    ///
+    /// The entry is quarantined when the error is not retryable under `policy`
+    /// or `config.max_retries` attempts are exhausted; otherwise it is
+    /// rescheduled with exponential backoff. Returns the updated entry.
+    pub fn record_failure(
+        &self,
+        id: Uuid,
+        error: &WorkflowError,
+        config: &RetryConfig,
+        policy: &dyn RetryPolicy,
+    ) -> WorkflowResult<DLQEntry> {
+        let retryable = policy.should_retry(error);
+        self.update(id, |entry| {
+            entry.retry_count += 1;
+            entry.error = error.to_string();
+            entry.error_type = format!("{:?}", error);
+            if !retryable {
+                entry.status = DlqEntryStatus::Quarantined;
+                entry.quarantine_reason = Some(format!("Non-retryable error: {}", error));
+                entry.next_retry_at = None;
+            } else if entry.retry_count >= config.max_retries {
+                entry.status = DlqEntryStatus::Quarantined;
+                entry.quarantine_reason = Some(format!(
+                    "Redrive failed {} times (max {})",
+                    entry.retry_count, config.max_retries
+                ));
+                entry.next_retry_at = None;
+            } else {
+                let delay = config.delay_for_attempt(entry.retry_count - 1);
+                entry.next_retry_at =
+                    Some(Utc::now() + chrono::Duration::milliseconds(delay.as_millis() as i64));
+            }
+        })
+    }
+
+    /// Redrive an entry now, regardless of its schedule or status
+    ///
+    /// Removes the entry on success; on failure the entry is kept unchanged
+    /// and the error is returned.
+    pub async fn redrive(&self, id: Uuid, handler: &dyn DeadLetterHandler) -> WorkflowResult<()> {
+        let entry = self.get(id)?.ok_or_else(|| dead_letter_not_found(id))?;
+        if !self.claim(id)? {
+            return Err(WorkflowError::Validation(format!(
+                "Dead letter entry {} is already being redriven",
+                id
+            )));
+        }
+        let result = handler.redrive(&entry).await;
+        self.release(id)?;
+        result?;
+        self.remove(id)
+    }
+
+    /// Redrive all due entries once
+    pub async fn redrive_due(
+        &self,
+        handler: &dyn DeadLetterHandler,
+        config: &RetryConfig,
+        policy: &dyn RetryPolicy,
+    ) -> WorkflowResult<RedriveSummary> {
+        let mut summary = RedriveSummary::default();
+        for entry in self.get_retryable_entries()? {
+            if !self.claim(entry.id)? {
+                continue;
+            }
+            let result = handler.redrive(&entry).await;
+            self.release(entry.id)?;
+            match result {
+                Ok(()) => {
+                    self.remove(entry.id)?;
+                    summary.succeeded += 1;
+                }
+                Err(e) => {
+                    // The entry may have been purged while it was redriven
+                    if self.get(entry.id)?.is_none() {
+                        continue;
+                    }
+                    let updated = self.record_failure(entry.id, &e, config, policy)?;
+                    if updated.status == DlqEntryStatus::Quarantined {
+                        tracing::warn!(
+                            "Quarantined dead letter entry {}: {}",
+                            entry.id,
+                            updated.quarantine_reason.as_deref().unwrap_or_default()
+                        );
+                        summary.quarantined += 1;
+                    } else {
+                        summary.rescheduled += 1;
+                    }
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Spawn a background worker redriving due entries every `interval`
+    pub fn spawn_redrive_worker(
+        self: Arc<Self>,
+        handler: Arc<dyn DeadLetterHandler>,
+        config: RetryConfig,
+        policy: Arc<dyn RetryPolicy + Send + Sync>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self
+                    .redrive_due(handler.as_ref(), &config, policy.as_ref())
+                    .await
+                {
+                    Ok(summary) if summary != RedriveSummary::default() => {
+                        tracing::info!(
+                            "Dead letter redrive: {} succeeded, {} rescheduled, {} quarantined",
+                            summary.succeeded,
+                            summary.rescheduled,
+                            summary.quarantined
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Dead letter redrive failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Mark an entry as being redriven; false if it already is
+    fn claim(&self, id: Uuid) -> WorkflowResult<bool> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        Ok(in_flight.insert(id))
+    }
+
+    fn release(&self, id: Uuid) -> WorkflowResult<()> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .map_err(|e| WorkflowError::Internal(format!("Failed to acquire DLQ lock: {}", e)))?;
+        in_flight.remove(&id);
+        Ok(())
+    }
 }
 
 impl Default for DeadLetterQueue {
@@ -166,9 +475,50 @@ impl Default for DeadLetterQueue {
     }
 }
 
+fn dead_letter_not_found(id: Uuid) -> WorkflowError {
+    WorkflowError::Validation(format!("Dead letter entry {} not found", id))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::resilience::retry::DefaultRetryPolicy;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails with the given error until `failures` attempts were made
+    struct FlakyHandler {
+        failures: u32,
+        error: WorkflowError,
+        attempts: AtomicU32,
+    }
+
+    #[async_trait]
+    impl DeadLetterHandler for FlakyHandler {
+        async fn redrive(&self, _entry: &DLQEntry) -> WorkflowResult<()> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err(self.error.clone())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn flaky(failures: u32, error: WorkflowError) -> FlakyHandler {
+        FlakyHandler {
+            failures,
+            error,
+            attempts: AtomicU32::new(0),
+        }
+    }
+
+    fn no_backoff() -> RetryConfig {
+        RetryConfig {
+            max_retries: 3,
+            initial_delay_ms: 0,
+            jitter: 0.0,
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn test_dlq_add_entry() {
@@ -197,4 +547,92 @@ mod tests {
         let entries = dlq.list().unwrap();
         assert!(entries.iter().any(|e| e.id == id3));
     }
+
+    #[test]
+    fn test_dlq_survives_reopen() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = sled::open(temp_dir.path()).unwrap();
+        let dlq = DeadLetterQueue::persistent(db.open_tree("dlq").unwrap(), 100).unwrap();
+        let kept = dlq
+            .add(
+                None,
+                None,
+                WorkflowError::Timeout,
+                serde_json::json!({"n": 1}),
+            )
+            .unwrap();
+        let removed = dlq
+            .add(
+                None,
+                None,
+                WorkflowError::Timeout,
+                serde_json::json!({"n": 2}),
+            )
+            .unwrap();
+        dlq.edit_payload(kept, serde_json::json!({"n": 3})).unwrap();
+        dlq.remove(removed).unwrap();
+        drop(dlq);
+
+        let reopened = DeadLetterQueue::persistent(db.open_tree("dlq").unwrap(), 100).unwrap();
+        let entries = reopened.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, kept);
+        assert_eq!(entries[0].operation_data, serde_json::json!({"n": 3}));
+    }
+
+    #[tokio::test]
+    async fn test_redrive_reschedules_then_succeeds() {
+        let dlq = DeadLetterQueue::new(100);
+        let id = dlq
+            .add(None, None, WorkflowError::Timeout, serde_json::json!({}))
+            .unwrap();
+        let handler = flaky(1, WorkflowError::Timeout);
+
+        let first = dlq
+            .redrive_due(&handler, &no_backoff(), &DefaultRetryPolicy)
+            .await
+            .unwrap();
+        assert_eq!(first.rescheduled, 1);
+        assert_eq!(dlq.get(id).unwrap().unwrap().retry_count, 1);
+
+        let second = dlq
+            .redrive_due(&handler, &no_backoff(), &DefaultRetryPolicy)
+            .await
+            .unwrap();
+        assert_eq!(second.succeeded, 1);
+        assert!(dlq.is_empty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_poison_entries_are_quarantined() {
+        let dlq = DeadLetterQueue::new(100);
+        let poison = dlq
+            .add(None, None, WorkflowError::Timeout, serde_json::json!({}))
+            .unwrap();
+        let invalid = flaky(u32::MAX, WorkflowError::Validation("bad".to_string()));
+
+        let summary = dlq
+            .redrive_due(&invalid, &no_backoff(), &DefaultRetryPolicy)
+            .await
+            .unwrap();
+        assert_eq!(summary.quarantined, 1);
+        let entry = dlq.get(poison).unwrap().unwrap();
+        assert_eq!(entry.status, DlqEntryStatus::Quarantined);
+        assert!(dlq.get_retryable_entries().unwrap().is_empty());
+
+        // Requeued entries get a fresh budget; exhausting it quarantines again
+        dlq.requeue(poison).unwrap();
+        let timeouts = flaky(u32::MAX, WorkflowError::Timeout);
+        for _ in 0..3 {
+            dlq.redrive_due(&timeouts, &no_backoff(), &DefaultRetryPolicy)
+                .await
+                .unwrap();
+        }
+        let entry = dlq.get(poison).unwrap().unwrap();
+        assert_eq!(entry.status, DlqEntryStatus::Quarantined);
+        assert_eq!(entry.retry_count, 3);
+
+        assert_eq!(dlq.purge(Some(DlqEntryStatus::Quarantined)).unwrap(), 1);
+        assert!(dlq.is_empty().unwrap());
+    }
 }
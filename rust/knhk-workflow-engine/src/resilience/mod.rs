@@ -12,9 +12,9 @@ pub mod timeout;
 pub mod yawl_exception;
 
 pub use circuit_breaker::CircuitBreaker;
-pub use dlq::DeadLetterQueue;
+pub use dlq::{DLQEntry, DeadLetterHandler, DeadLetterQueue, DlqEntryStatus, RedriveSummary};
 pub use rate_limit::{KeyedRateLimiter, RateLimitConfig, RateLimiter};
-pub use retry::{RetryConfig, RetryPolicy};
+pub use retry::{DefaultRetryPolicy, RetryConfig, RetryPolicy};
 pub use timeout::{PathType, TimeoutConfig, TimeoutManager};
 pub use yawl_exception::{
     CompensationHandler, ExceptionAnalytics, ExceptionCategory, ExceptionHandler,
@@ -17,6 +17,11 @@ pub struct RetryConfig {
     pub max_delay_ms: u64,
     /// Exponential backoff multiplier
     pub multiplier: f64,
+    /// Random jitter as a fraction of the delay (0.0 = none, 0.5 = ±50%)
+    ///
+    /// Off by default so delays stay deterministic; enable it where many
+    /// retries may fire together, e.g. dead letter redrive.
+    pub jitter: f64,
 }
 
 impl Default for RetryConfig {
@@ -26,10 +31,31 @@ impl Default for RetryConfig {
             initial_delay_ms: 100,
             max_delay_ms: 5000,
             multiplier: 2.0,
+            jitter: 0.0,
         }
     }
 }
 
+impl RetryConfig {
+    /// Set the random jitter (fraction of the delay)
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay before retry attempt `attempt` (0-based), with jitter applied
+    ///
+    /// The exponential delay is capped at `max_delay_ms` before jitter, so
+    /// concurrent retries at the cap are still spread out.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let exponential = self.initial_delay_ms as f64 * self.multiplier.powi(attempt as i32);
+        let capped = exponential.min(self.max_delay_ms as f64);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (fastrand::f64() * 2.0 - 1.0);
+        Duration::from_millis((capped * factor).max(0.0) as u64)
+    }
+}
+
 /// Retry policy trait
 pub trait RetryPolicy {
     /// Check if an error should be retried
@@ -61,7 +87,6 @@ where
     F: FnMut() -> Fut,
     Fut: std::future::Future<Output = WorkflowResult<T>>,
 {
-    let mut delay = Duration::from_millis(config.initial_delay_ms);
     let mut last_error: Option<WorkflowError> = None;
 
     for attempt in 0..=config.max_retries {
@@ -110,14 +135,8 @@ where
 
                 // Check if we should retry
                 if attempt < config.max_retries && policy.should_retry(&e) {
-                    // Wait before retrying
-                    sleep(delay).await;
-
-                    // Calculate next delay with exponential backoff
-                    delay = Duration::from_millis(
-                        ((delay.as_millis() as f64 * config.multiplier) as u64)
-                            .min(config.max_delay_ms),
-                    );
+                    // Wait before retrying (exponential backoff with jitter)
+                    sleep(config.delay_for_attempt(attempt)).await;
                 } else {
                     // Don't retry: either max retries reached or non-retryable error
                     break;
@@ -187,4 +206,32 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_delay_for_attempt_backs_off_within_jitter() {
+        let config = RetryConfig {
+            initial_delay_ms: 100,
+            max_delay_ms: 1000,
+            multiplier: 2.0,
+            jitter: 0.2,
+            ..Default::default()
+        };
+
+        for _ in 0..50 {
+            let first = config.delay_for_attempt(0).as_millis();
+            let third = config.delay_for_attempt(2).as_millis();
+            let capped = config.delay_for_attempt(10).as_millis();
+            assert!((80..=120).contains(&first), "{}", first);
+            assert!((320..=480).contains(&third), "{}", third);
+            assert!((800..=1200).contains(&capped), "{}", capped);
+        }
+    }
+
+    #[test]
+    fn test_default_delays_have_no_jitter() {
+        let config = RetryConfig::default();
+        assert_eq!(config.delay_for_attempt(0), Duration::from_millis(100));
+        assert_eq!(config.delay_for_attempt(3), Duration::from_millis(800));
+        assert_eq!(config.delay_for_attempt(10), Duration::from_millis(5000));
+    }
 }
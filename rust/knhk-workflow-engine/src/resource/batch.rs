@@ -0,0 +1,1126 @@
+//! Cost-optimal batch allocation
+//!
+//! Instead of choosing a resource greedily per work item, the batch allocator
+//! periodically assigns all unallocated work items at once by solving a
+//! min-cost assignment problem:
+//!
+//! - Each resource offers one slot per free unit of capacity (capacity minus
+//!   current workload). Slot `k` starts after the resource's current workload
+//!   and the `k` batch items queued before it, each taking the resource's
+//!   average completion time.
+//! - Assigning an item to a slot costs its weighted lateness against the
+//!   deadline plus the resource cost from the [`CostService`] rate.
+//! - The solver maximizes the number of assigned items first, then minimizes
+//!   the total cost (min-cost max-flow over items and slots).
+//! - Separation of duties excludes resources that performed a conflicting
+//!   task in the same case; conflicting items of one batch that land on the
+//!   same resource are resolved by re-solving without that assignment.
+//!
+//! Every assignment carries an explanation: its lateness and cost terms and
+//! why each other eligible resource was not chosen.
+
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::resource::compliance::SeparationOfDuties;
+use crate::resource::three_phase::{OfferCriteria, ResourceMetadata, ThreePhaseAllocator, UserId};
+use crate::resource::ResourceId;
+use crate::resourcing::org_model::OrgModel;
+use crate::services::work_items::{WorkItemService, WorkItemState};
+use crate::services::CostService;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Scale of objective values in the integer flow network
+const COST_SCALE: f64 = 1_000.0;
+
+/// Batch allocation parameters
+#[derive(Debug, Clone)]
+pub struct BatchAllocationConfig {
+    /// Maximum active work items per resource (including its current workload)
+    pub default_capacity: u32,
+    /// Capacity overrides by user ID
+    pub capacities: HashMap<UserId, u32>,
+    /// Objective weight of one hour of lateness (multiplied by the item weight)
+    pub lateness_weight: f64,
+    /// Objective weight of one currency unit of resource cost
+    pub cost_weight: f64,
+    /// Duration of a work item for resources without completion history
+    pub default_duration: Duration,
+    /// Task pairs that must not be performed by the same resource within a case
+    pub separation_of_duties: SeparationOfDuties,
+    /// Number of alternatives listed per assignment
+    pub max_alternatives: usize,
+}
+
+impl Default for BatchAllocationConfig {
+    fn default() -> Self {
+        Self {
+            default_capacity: 5,
+            capacities: HashMap::new(),
+            lateness_weight: 100.0,
+            cost_weight: 1.0,
+            default_duration: Duration::hours(1),
+            separation_of_duties: SeparationOfDuties::new(),
+            max_alternatives: 3,
+        }
+    }
+}
+
+impl BatchAllocationConfig {
+    /// Capacity of a resource
+    pub fn capacity(&self, user_id: &UserId) -> u32 {
+        self.capacities
+            .get(user_id)
+            .copied()
+            .unwrap_or(self.default_capacity)
+    }
+
+    fn conflicting_tasks<'a>(&'a self, task_id: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.separation_of_duties
+            .conflicting_tasks
+            .iter()
+            .filter_map(move |(a, b)| {
+                if a == task_id {
+                    Some(b.as_str())
+                } else if b == task_id {
+                    Some(a.as_str())
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+/// Work item to allocate
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchWorkItem {
+    /// Work item ID
+    pub work_item_id: String,
+    /// Case ID
+    pub case_id: String,
+    /// Task ID
+    pub task_id: String,
+    /// Eligible users (offer phase result)
+    pub eligible: Vec<UserId>,
+    /// Deadline (no lateness term without one)
+    pub deadline: Option<DateTime<Utc>>,
+    /// Lateness weight (e.g. derived from the priority)
+    pub weight: f64,
+    /// Expected duration (resource average completion time if unset)
+    pub estimated_duration: Option<Duration>,
+}
+
+/// Work (task, user) already performed or held in a case
+pub type CaseHistory = HashMap<String, HashSet<(String, UserId)>>;
+
+/// Why an eligible resource was not chosen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum AlternativeReason {
+    /// The resource would have cost more
+    HigherObjective {
+        /// Objective difference to the chosen assignment
+        delta: f64,
+    },
+    /// The resource's free slots went to items that gained more from them
+    NeededElsewhere {
+        /// Objective difference to the chosen assignment
+        delta: f64,
+    },
+    /// The resource has no free capacity
+    AtCapacity,
+    /// The resource performs a conflicting task of the same case
+    SeparationOfDuties {
+        /// Conflicting task
+        conflicting_task: String,
+    },
+}
+
+/// Eligible resource that was not chosen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alternative {
+    /// User ID
+    pub user_id: UserId,
+    /// Objective at the resource's next free slot (if it could take the item)
+    pub objective: Option<f64>,
+    /// Why it was not chosen
+    pub reason: AlternativeReason,
+}
+
+/// Allocation of one work item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchAssignment {
+    /// Work item ID
+    pub work_item_id: String,
+    /// Task ID
+    pub task_id: String,
+    /// Allocated user
+    pub user_id: UserId,
+    /// Position among the items allocated to the user in this batch (0-based)
+    pub queue_position: u32,
+    /// Expected start
+    pub expected_start: DateTime<Utc>,
+    /// Expected completion
+    pub expected_completion: DateTime<Utc>,
+    /// Expected lateness in hours (0 if on time or without deadline)
+    pub lateness_hours: f64,
+    /// Resource cost
+    pub cost: f64,
+    /// Objective value (weighted lateness plus weighted cost)
+    pub objective: f64,
+    /// Other eligible resources, best first
+    pub alternatives: Vec<Alternative>,
+    /// Human-readable explanation
+    pub explanation: String,
+}
+
+/// Why a work item was not allocated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnassignedReason {
+    /// No eligible resource is registered
+    NoEligibleResource,
+    /// All eligible resources perform a conflicting task of the same case
+    SeparationOfDuties,
+    /// All eligible resources are at capacity
+    CapacityExhausted,
+}
+
+/// Work item left unallocated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnassignedItem {
+    /// Work item ID
+    pub work_item_id: String,
+    /// Reason
+    pub reason: UnassignedReason,
+}
+
+/// Result of a batch allocation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchAllocationPlan {
+    /// Planning time
+    pub planned_at: DateTime<Utc>,
+    /// Assignments in work item order
+    pub assignments: Vec<BatchAssignment>,
+    /// Items left unallocated
+    pub unassigned: Vec<UnassignedItem>,
+    /// Total objective
+    pub total_objective: f64,
+    /// Total resource cost
+    pub total_cost: f64,
+    /// Total expected lateness in hours
+    pub total_lateness_hours: f64,
+}
+
+impl BatchAllocationPlan {
+    /// Assignment of a work item
+    pub fn assignment(&self, work_item_id: &str) -> Option<&BatchAssignment> {
+        self.assignments
+            .iter()
+            .find(|a| a.work_item_id == work_item_id)
+    }
+}
+
+/// Batch allocator over the resources of a three-phase allocator
+pub struct BatchAllocator {
+    allocator: Arc<ThreePhaseAllocator>,
+    config: BatchAllocationConfig,
+    last_plan: RwLock<Option<BatchAllocationPlan>>,
+}
+
+impl BatchAllocator {
+    /// Create a batch allocator with default parameters
+    pub fn new(allocator: Arc<ThreePhaseAllocator>) -> Self {
+        Self::with_config(allocator, BatchAllocationConfig::default())
+    }
+
+    /// Create a batch allocator with custom parameters
+    pub fn with_config(allocator: Arc<ThreePhaseAllocator>, config: BatchAllocationConfig) -> Self {
+        Self {
+            allocator,
+            config,
+            last_plan: RwLock::new(None),
+        }
+    }
+
+    /// Allocation parameters
+    pub fn config(&self) -> &BatchAllocationConfig {
+        &self.config
+    }
+
+    /// Most recent plan of [`run`](Self::run)
+    pub async fn last_plan(&self) -> Option<BatchAllocationPlan> {
+        self.last_plan.read().await.clone()
+    }
+
+    /// Compute the optimal assignment of a batch without applying it
+    pub async fn plan(
+        &self,
+        items: &[BatchWorkItem],
+        rates: &HashMap<UserId, f64>,
+        history: &CaseHistory,
+        at: DateTime<Utc>,
+    ) -> WorkflowResult<BatchAllocationPlan> {
+        let resources: BTreeMap<UserId, ResourceMetadata> = self
+            .allocator
+            .list_resources()
+            .await
+            .into_iter()
+            .map(|r| (r.user_id.clone(), r))
+            .collect();
+        Solver::new(&self.config, items, &resources, rates, history, at).solve()
+    }
+
+    /// Collect the unallocated work items of the work item service
+    ///
+    /// Eligible users come from the item's `offered_to` list or from the offer
+    /// criteria of its task; items with neither are left to other allocation.
+    /// Deadlines and priorities are read from the item data (`deadline`,
+    /// `priority` with 100 as weight 1).
+    pub async fn collect(
+        &self,
+        work_items: &WorkItemService,
+        criteria: &HashMap<String, OfferCriteria>,
+    ) -> WorkflowResult<Vec<BatchWorkItem>> {
+        let mut pending = work_items
+            .get_work_items_by_state(WorkItemState::Created)
+            .await;
+        pending.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+        let mut batch = Vec::with_capacity(pending.len());
+        for item in pending {
+            let offered: Vec<UserId> = item
+                .data
+                .get("offered_to")
+                .and_then(|v| v.as_array())
+                .map(|users| {
+                    users
+                        .iter()
+                        .filter_map(|u| u.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let eligible = if !offered.is_empty() {
+                offered
+            } else if let Some(criteria) = criteria.get(&item.task_id) {
+                let mut eligible = self.allocator.offer_phase(criteria).await?.eligible_users;
+                eligible.sort();
+                eligible
+            } else {
+                continue;
+            };
+            let deadline = work_items.get_deadline(&item.id).await?;
+            let priority = work_items.get_priority(&item.id).await?;
+            batch.push(BatchWorkItem {
+                work_item_id: item.id,
+                case_id: item.case_id,
+                task_id: item.task_id,
+                eligible,
+                deadline,
+                weight: f64::from(priority) / 100.0,
+                estimated_duration: None,
+            });
+        }
+        Ok(batch)
+    }
+
+    /// Tasks and users of the active or finished work items of the batch's cases
+    pub async fn case_history(
+        work_items: &WorkItemService,
+        items: &[BatchWorkItem],
+    ) -> CaseHistory {
+        let mut history = CaseHistory::new();
+        for case_id in items.iter().map(|i| &i.case_id).collect::<HashSet<_>>() {
+            let performed = history.entry(case_id.clone()).or_default();
+            for item in work_items.list_case_work_items(case_id).await {
+                if item.state == WorkItemState::Cancelled {
+                    continue;
+                }
+                if let Some(user) = item.assigned_resource_id {
+                    performed.insert((item.task_id, user));
+                }
+            }
+        }
+        history
+    }
+
+    /// Hourly rates of the registered resources from the cost service
+    pub async fn rates(&self, costs: &CostService) -> HashMap<UserId, f64> {
+        let mut rates = HashMap::new();
+        for resource in self.allocator.list_resources().await {
+            if let Some(rate) = costs
+                .get_resource_rate(&cost_resource_id(&resource.user_id))
+                .await
+            {
+                rates.insert(resource.user_id, rate);
+            }
+        }
+        rates
+    }
+
+    /// Plan the unallocated work items and assign them
+    pub async fn run(
+        &self,
+        work_items: &WorkItemService,
+        costs: &CostService,
+        criteria: &HashMap<String, OfferCriteria>,
+        at: DateTime<Utc>,
+    ) -> WorkflowResult<BatchAllocationPlan> {
+        let items = self.collect(work_items, criteria).await?;
+        let history = Self::case_history(work_items, &items).await;
+        let rates = self.rates(costs).await;
+        let plan = self.plan(&items, &rates, &history, at).await?;
+
+        for assignment in &plan.assignments {
+            work_items
+                .assign(&assignment.work_item_id, assignment.user_id.clone())
+                .await?;
+            if let Some(resource) = self.allocator.get_resource(&assignment.user_id).await {
+                self.allocator
+                    .update_workload(&assignment.user_id, resource.current_workload + 1)
+                    .await;
+                self.allocator
+                    .update_queue_length(&assignment.user_id, resource.queue_length + 1)
+                    .await;
+            }
+        }
+        tracing::debug!(
+            "Batch allocation assigned {} work items ({} unassigned, objective {:.2})",
+            plan.assignments.len(),
+            plan.unassigned.len(),
+            plan.total_objective
+        );
+        *self.last_plan.write().await = Some(plan.clone());
+        Ok(plan)
+    }
+
+    /// Start a background worker running the batch allocation at every `interval`
+    pub fn start(
+        self: Arc<Self>,
+        work_items: Arc<WorkItemService>,
+        costs: Arc<CostService>,
+        criteria: HashMap<String, OfferCriteria>,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run(&work_items, &costs, &criteria, Utc::now()).await {
+                    tracing::warn!("Batch allocation failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// Cost service ID of a user (UUID user IDs as is, otherwise the org model participant ID)
+pub fn cost_resource_id(user_id: &UserId) -> ResourceId {
+    match uuid::Uuid::parse_str(user_id) {
+        Ok(uuid) => ResourceId(uuid),
+        Err(_) => OrgModel::resource_id(user_id),
+    }
+}
+
+/// Candidate slot of an item
+#[derive(Debug, Clone, Copy)]
+struct SlotTerms {
+    start: DateTime<Utc>,
+    completion: DateTime<Utc>,
+    lateness_hours: f64,
+    cost: f64,
+    objective: f64,
+}
+
+/// Assignment problem of one batch
+struct Solver<'a> {
+    config: &'a BatchAllocationConfig,
+    items: &'a [BatchWorkItem],
+    resources: &'a BTreeMap<UserId, ResourceMetadata>,
+    rates: &'a HashMap<UserId, f64>,
+    at: DateTime<Utc>,
+    /// Free slots per resource
+    free: BTreeMap<UserId, u32>,
+    /// Excluded (item, user) pairs with the conflicting task
+    excluded: HashMap<(usize, UserId), String>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(
+        config: &'a BatchAllocationConfig,
+        items: &'a [BatchWorkItem],
+        resources: &'a BTreeMap<UserId, ResourceMetadata>,
+        rates: &'a HashMap<UserId, f64>,
+        history: &CaseHistory,
+        at: DateTime<Utc>,
+    ) -> Self {
+        let free = resources
+            .iter()
+            .map(|(user, r)| {
+                let free = config.capacity(user).saturating_sub(r.current_workload);
+                (user.clone(), free.min(items.len() as u32))
+            })
+            .collect();
+
+        // Separation of duties against work already held in the case
+        let mut excluded = HashMap::new();
+        for (index, item) in items.iter().enumerate() {
+            let Some(performed) = history.get(&item.case_id) else {
+                continue;
+            };
+            for conflicting in config.conflicting_tasks(&item.task_id) {
+                for (task, user) in performed {
+                    if task == conflicting {
+                        excluded.insert((index, user.clone()), conflicting.to_string());
+                    }
+                }
+            }
+        }
+
+        Self {
+            config,
+            items,
+            resources,
+            rates,
+            at,
+            free,
+            excluded,
+        }
+    }
+
+    fn duration(&self, item: &BatchWorkItem, resource: &ResourceMetadata) -> Duration {
+        item.estimated_duration.unwrap_or_else(|| {
+            if resource.avg_completion_time > 0.0 {
+                Duration::milliseconds(resource.avg_completion_time.round() as i64)
+            } else {
+                self.config.default_duration
+            }
+        })
+    }
+
+    fn slot_duration(&self, resource: &ResourceMetadata) -> Duration {
+        if resource.avg_completion_time > 0.0 {
+            Duration::milliseconds(resource.avg_completion_time.round() as i64)
+        } else {
+            self.config.default_duration
+        }
+    }
+
+    /// Terms of placing an item in slot `k` of a resource
+    fn terms(&self, item: &BatchWorkItem, user: &UserId, k: u32) -> Option<SlotTerms> {
+        let resource = self.resources.get(user)?;
+        let slot = self.slot_duration(resource);
+        let start = self.at + slot * (resource.current_workload + k) as i32;
+        let duration = self.duration(item, resource);
+        let completion = start + duration;
+        let lateness_hours = item
+            .deadline
+            .map(|deadline| hours(completion - deadline).max(0.0))
+            .unwrap_or(0.0);
+        let cost = self.rates.get(user).copied().unwrap_or(0.0) * hours(duration);
+        let objective = self.config.lateness_weight * item.weight.max(0.0) * lateness_hours
+            + self.config.cost_weight * cost;
+        Some(SlotTerms {
+            start,
+            completion,
+            lateness_hours,
+            cost,
+            objective,
+        })
+    }
+
+    fn candidates(&self, index: usize) -> Vec<&'a UserId> {
+        let mut users: Vec<&UserId> = self.items[index]
+            .eligible
+            .iter()
+            .filter_map(|user| self.resources.get_key_value(user).map(|(k, _)| k))
+            .collect();
+        users.sort();
+        users.dedup();
+        users
+    }
+
+    fn solve(mut self) -> WorkflowResult<BatchAllocationPlan> {
+        // Re-solve until no two conflicting items of a case share a resource
+        let mut placement;
+        let mut rounds = 0usize;
+        loop {
+            placement = self.assign()?;
+            let conflicts = self.in_batch_conflicts(&placement);
+            if conflicts.is_empty() {
+                break;
+            }
+            rounds += 1;
+            if rounds > self.items.len() * self.resources.len().max(1) {
+                return Err(WorkflowError::Internal(
+                    "Batch allocation did not resolve separation-of-duties conflicts".to_string(),
+                ));
+            }
+            for (index, user, task) in conflicts {
+                self.excluded.insert((index, user), task);
+            }
+        }
+        Ok(self.plan(&placement))
+    }
+
+    /// Min-cost max-flow assignment of items to slots: item index → (user, slot)
+    fn assign(&self) -> WorkflowResult<HashMap<usize, (UserId, u32)>> {
+        let n = self.items.len();
+        let slots: Vec<(UserId, u32)> = self
+            .free
+            .iter()
+            .flat_map(|(user, free)| (0..*free).map(move |k| (user.clone(), k)))
+            .collect();
+        let source = 0;
+        let sink = 1;
+        let item_node = |i: usize| 2 + i;
+        let slot_node = |s: usize| 2 + n + s;
+        let slot_index: HashMap<(&UserId, u32), usize> = slots
+            .iter()
+            .enumerate()
+            .map(|(s, (user, k))| ((user, *k), s))
+            .collect();
+
+        let mut graph = FlowGraph::new(2 + n + slots.len());
+        let mut item_edges: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for (i, item) in self.items.iter().enumerate() {
+            graph.add_edge(source, item_node(i), 1, 0);
+            for user in self.candidates(i) {
+                if self.excluded.contains_key(&(i, user.clone())) {
+                    continue;
+                }
+                for k in 0..self.free.get(user).copied().unwrap_or(0) {
+                    let Some(terms) = self.terms(item, user, k) else {
+                        continue;
+                    };
+                    let s = slot_index[&(user, k)];
+                    let cost = (terms.objective * COST_SCALE).round() as i64;
+                    let edge = graph.add_edge(item_node(i), slot_node(s), 1, cost);
+                    item_edges[i].push((edge, s));
+                }
+            }
+        }
+        for s in 0..slots.len() {
+            graph.add_edge(slot_node(s), sink, 1, 0);
+        }
+        graph.min_cost_max_flow(source, sink)?;
+
+        let mut placement = HashMap::new();
+        for (i, edges) in item_edges.iter().enumerate() {
+            for &(edge, s) in edges {
+                if graph.adj[item_node(i)][edge].cap == 0 {
+                    placement.insert(i, slots[s].clone());
+                }
+            }
+        }
+
+        // Earlier slots of a resource go first, so positions are contiguous
+        let mut by_user: BTreeMap<UserId, Vec<(u32, usize)>> = BTreeMap::new();
+        for (i, (user, k)) in &placement {
+            by_user.entry(user.clone()).or_default().push((*k, *i));
+        }
+        for (user, mut placed) in by_user {
+            placed.sort();
+            for (position, (_, i)) in placed.into_iter().enumerate() {
+                placement.insert(i, (user.clone(), position as u32));
+            }
+        }
+        Ok(placement)
+    }
+
+    /// Conflicting items of a case on the same resource (the later item is moved)
+    fn in_batch_conflicts(
+        &self,
+        placement: &HashMap<usize, (UserId, u32)>,
+    ) -> Vec<(usize, UserId, String)> {
+        let mut conflicts = Vec::new();
+        for (i, (user_i, _)) in placement {
+            for (j, (user_j, _)) in placement {
+                if i >= j || user_i != user_j {
+                    continue;
+                }
+                let (a, b) = (&self.items[*i], &self.items[*j]);
+                if a.case_id != b.case_id {
+                    continue;
+                }
+                if self
+                    .config
+                    .conflicting_tasks(&a.task_id)
+                    .any(|task| task == b.task_id)
+                {
+                    // Keep the heavier item (ties: the earlier one) on the resource
+                    let moved = if b.weight > a.weight { *i } else { *j };
+                    let kept = if moved == *i { b } else { a };
+                    conflicts.push((moved, user_i.clone(), kept.task_id.clone()));
+                }
+            }
+        }
+        conflicts.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        conflicts.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+        conflicts
+    }
+
+    fn plan(&self, placement: &HashMap<usize, (UserId, u32)>) -> BatchAllocationPlan {
+        let mut used: BTreeMap<&UserId, u32> = BTreeMap::new();
+        for (user, _) in placement.values() {
+            *used.entry(user).or_default() += 1;
+        }
+
+        let mut assignments = Vec::new();
+        let mut unassigned = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            let candidates = self.candidates(i);
+            let Some((user, position)) = placement.get(&i) else {
+                let reason = if candidates.is_empty() {
+                    UnassignedReason::NoEligibleResource
+                } else if candidates
+                    .iter()
+                    .all(|u| self.excluded.contains_key(&(i, (*u).clone())))
+                {
+                    UnassignedReason::SeparationOfDuties
+                } else {
+                    UnassignedReason::CapacityExhausted
+                };
+                unassigned.push(UnassignedItem {
+                    work_item_id: item.work_item_id.clone(),
+                    reason,
+                });
+                continue;
+            };
+            let Some(terms) = self.terms(item, user, *position) else {
+                continue;
+            };
+
+            let mut alternatives: Vec<Alternative> = candidates
+                .iter()
+                .filter(|u| **u != user)
+                .map(|other| {
+                    if let Some(task) = self.excluded.get(&(i, (*other).clone())) {
+                        return Alternative {
+                            user_id: (*other).clone(),
+                            objective: None,
+                            reason: AlternativeReason::SeparationOfDuties {
+                                conflicting_task: task.clone(),
+                            },
+                        };
+                    }
+                    let free = self.free.get(*other).copied().unwrap_or(0);
+                    if free == 0 {
+                        return Alternative {
+                            user_id: (*other).clone(),
+                            objective: None,
+                            reason: AlternativeReason::AtCapacity,
+                        };
+                    }
+                    let taken = used.get(other).copied().unwrap_or(0);
+                    let next = taken.min(free - 1);
+                    let objective = self.terms(item, other, next).map(|t| t.objective);
+                    let delta = objective.map(|o| o - terms.objective).unwrap_or(0.0);
+                    let reason = if taken >= free || delta <= 0.0 {
+                        AlternativeReason::NeededElsewhere { delta }
+                    } else {
+                        AlternativeReason::HigherObjective { delta }
+                    };
+                    Alternative {
+                        user_id: (*other).clone(),
+                        objective,
+                        reason,
+                    }
+                })
+                .collect();
+            alternatives.sort_by(|a, b| {
+                let key = |alt: &Alternative| alt.objective.unwrap_or(f64::INFINITY);
+                key(a)
+                    .partial_cmp(&key(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.user_id.cmp(&b.user_id))
+            });
+            alternatives.truncate(self.config.max_alternatives);
+
+            let explanation = explain(item, user, *position, &terms, alternatives.first());
+            assignments.push(BatchAssignment {
+                work_item_id: item.work_item_id.clone(),
+                task_id: item.task_id.clone(),
+                user_id: user.clone(),
+                queue_position: *position,
+                expected_start: terms.start,
+                expected_completion: terms.completion,
+                lateness_hours: terms.lateness_hours,
+                cost: terms.cost,
+                objective: terms.objective,
+                alternatives,
+                explanation,
+            });
+        }
+
+        BatchAllocationPlan {
+            planned_at: self.at,
+            total_objective: assignments.iter().map(|a| a.objective).sum(),
+            total_cost: assignments.iter().map(|a| a.cost).sum(),
+            total_lateness_hours: assignments.iter().map(|a| a.lateness_hours).sum(),
+            assignments,
+            unassigned,
+        }
+    }
+}
+
+fn hours(duration: Duration) -> f64 {
+    duration.num_milliseconds() as f64 / 3_600_000.0
+}
+
+fn explain(
+    item: &BatchWorkItem,
+    user: &UserId,
+    position: u32,
+    terms: &SlotTerms,
+    best_alternative: Option<&Alternative>,
+) -> String {
+    let timing = match item.deadline {
+        Some(deadline) if terms.lateness_hours > 0.0 => format!(
+            "{:.2}h late against deadline {}",
+            terms.lateness_hours,
+            deadline.to_rfc3339()
+        ),
+        Some(deadline) => format!("on time for deadline {}", deadline.to_rfc3339()),
+        None => "no deadline".to_string(),
+    };
+    let mut explanation = format!(
+        "Assigned to {} at queue position {}: expected completion {} ({}), cost {:.2}, objective {:.2}",
+        user,
+        position,
+        terms.completion.to_rfc3339(),
+        timing,
+        terms.cost,
+        terms.objective
+    );
+    if let Some(alternative) = best_alternative {
+        let reason = match alternative.reason {
+            AlternativeReason::HigherObjective { delta } => format!("objective +{:.2}", delta),
+            AlternativeReason::NeededElsewhere { delta } => format!(
+                "its slots serve other items better (objective {:+.2} here)",
+                delta
+            ),
+            AlternativeReason::AtCapacity => "at capacity".to_string(),
+            AlternativeReason::SeparationOfDuties {
+                ref conflicting_task,
+            } => format!("performs conflicting task {}", conflicting_task),
+        };
+        explanation.push_str(&format!("; next best {}: {}", alternative.user_id, reason));
+    }
+    explanation
+}
+
+/// Residual graph edge
+#[derive(Debug, Clone)]
+struct FlowEdge {
+    to: usize,
+    rev: usize,
+    cap: i64,
+    cost: i64,
+}
+
+/// Min-cost max-flow by successive shortest paths (Bellman-Ford queue)
+struct FlowGraph {
+    adj: Vec<Vec<FlowEdge>>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        Self {
+            adj: vec![Vec::new(); nodes],
+        }
+    }
+
+    /// Add an edge, returning its index in the adjacency list of `from`
+    fn add_edge(&mut self, from: usize, to: usize, cap: i64, cost: i64) -> usize {
+        let forward = self.adj[from].len();
+        let backward = self.adj[to].len() + usize::from(from == to);
+        self.adj[from].push(FlowEdge {
+            to,
+            rev: backward,
+            cap,
+            cost,
+        });
+        self.adj[to].push(FlowEdge {
+            to: from,
+            rev: forward,
+            cap: 0,
+            cost: -cost,
+        });
+        forward
+    }
+
+    fn min_cost_max_flow(&mut self, source: usize, sink: usize) -> WorkflowResult<(i64, i64)> {
+        let nodes = self.adj.len();
+        let (mut flow, mut cost) = (0i64, 0i64);
+        loop {
+            let mut dist = vec![i64::MAX; nodes];
+            let mut in_queue = vec![false; nodes];
+            let mut relaxations = vec![0usize; nodes];
+            let mut previous: Vec<Option<(usize, usize)>> = vec![None; nodes];
+            let mut queue = VecDeque::from([source]);
+            dist[source] = 0;
+            while let Some(u) = queue.pop_front() {
+                in_queue[u] = false;
+                for (e, edge) in self.adj[u].iter().enumerate() {
+                    if edge.cap > 0 && dist[u] + edge.cost < dist[edge.to] {
+                        dist[edge.to] = dist[u] + edge.cost;
+                        previous[edge.to] = Some((u, e));
+                        if !in_queue[edge.to] {
+                            relaxations[edge.to] += 1;
+                            if relaxations[edge.to] > nodes {
+                                return Err(WorkflowError::Internal(
+                                    "Negative cycle in batch allocation network".to_string(),
+                                ));
+                            }
+                            in_queue[edge.to] = true;
+                            queue.push_back(edge.to);
+                        }
+                    }
+                }
+            }
+            if dist[sink] == i64::MAX {
+                return Ok((flow, cost));
+            }
+
+            // Augment along the shortest path
+            let mut push = i64::MAX;
+            let mut node = sink;
+            while let Some((u, e)) = previous[node] {
+                push = push.min(self.adj[u][e].cap);
+                node = u;
+            }
+            let mut node = sink;
+            while let Some((u, e)) = previous[node] {
+                self.adj[u][e].cap -= push;
+                let rev = self.adj[u][e].rev;
+                self.adj[node][rev].cap += push;
+                node = u;
+            }
+            flow += push;
+            cost += push * dist[sink];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(user: &str, workload: u32, avg_ms: f64) -> ResourceMetadata {
+        ResourceMetadata {
+            user_id: user.to_string(),
+            roles: vec!["clerk".to_string()],
+            capabilities: Vec::new(),
+            position_level: 1,
+            department: None,
+            org_groups: Vec::new(),
+            current_workload: workload,
+            avg_completion_time: avg_ms,
+            queue_length: workload,
+        }
+    }
+
+    fn item(id: &str, case: &str, task: &str, deadline: Option<DateTime<Utc>>) -> BatchWorkItem {
+        BatchWorkItem {
+            work_item_id: id.to_string(),
+            case_id: case.to_string(),
+            task_id: task.to_string(),
+            eligible: vec!["alice".to_string(), "bob".to_string()],
+            deadline,
+            weight: 1.0,
+            estimated_duration: None,
+        }
+    }
+
+    async fn allocator(
+        resources: Vec<ResourceMetadata>,
+        config: BatchAllocationConfig,
+    ) -> BatchAllocator {
+        let three_phase = Arc::new(ThreePhaseAllocator::new());
+        for r in resources {
+            three_phase.register_resource(r).await;
+        }
+        BatchAllocator::with_config(three_phase, config)
+    }
+
+    const HOUR_MS: f64 = 3_600_000.0;
+
+    #[tokio::test]
+    async fn test_deadlines_beat_greedy_cost() {
+        let at = Utc::now();
+        // alice is cheap but busy for 2 hours, bob is expensive and idle
+        let batch = allocator(
+            vec![resource("alice", 2, HOUR_MS), resource("bob", 0, HOUR_MS)],
+            BatchAllocationConfig::default(),
+        )
+        .await;
+        let rates = HashMap::from([("alice".to_string(), 10.0), ("bob".to_string(), 50.0)]);
+        let items = vec![
+            item("urgent", "c1", "review", Some(at + Duration::hours(1))),
+            item("relaxed", "c2", "review", Some(at + Duration::hours(8))),
+        ];
+
+        let plan = batch
+            .plan(&items, &rates, &CaseHistory::new(), at)
+            .await
+            .unwrap();
+        let urgent = plan.assignment("urgent").unwrap();
+        assert_eq!(urgent.user_id, "bob", "Only bob finishes within the hour");
+        assert_eq!(urgent.lateness_hours, 0.0);
+        let relaxed = plan.assignment("relaxed").unwrap();
+        assert_eq!(relaxed.user_id, "alice", "Cheaper resource when on time");
+        assert_eq!(relaxed.cost, 10.0);
+        assert!(matches!(
+            relaxed.alternatives[0].reason,
+            AlternativeReason::HigherObjective { delta } if (delta - 40.0).abs() < 1e-6
+        ));
+        assert!(urgent.explanation.contains("on time"));
+        assert_eq!(plan.total_cost, 60.0);
+    }
+
+    #[tokio::test]
+    async fn test_capacity_limits_and_queue_positions() {
+        let at = Utc::now();
+        let mut config = BatchAllocationConfig::default();
+        config.capacities.insert("alice".to_string(), 2);
+        config.capacities.insert("bob".to_string(), 1);
+        let batch = allocator(
+            vec![resource("alice", 0, HOUR_MS), resource("bob", 1, HOUR_MS)],
+            config,
+        )
+        .await;
+        let items = vec![
+            item("late", "c1", "review", Some(at + Duration::hours(2))),
+            item("early", "c2", "review", Some(at + Duration::hours(1))),
+            // Already overdue: late in any slot, so it loses the second slot
+            item("extra", "c3", "review", Some(at - Duration::hours(1))),
+        ];
+
+        let plan = batch
+            .plan(&items, &HashMap::new(), &CaseHistory::new(), at)
+            .await
+            .unwrap();
+        assert_eq!(plan.assignments.len(), 2);
+        assert_eq!(plan.assignment("early").unwrap().queue_position, 0);
+        assert_eq!(plan.assignment("late").unwrap().queue_position, 1);
+        assert_eq!(plan.total_lateness_hours, 0.0);
+        assert_eq!(
+            plan.unassigned,
+            vec![UnassignedItem {
+                work_item_id: "extra".to_string(),
+                reason: UnassignedReason::CapacityExhausted,
+            }]
+        );
+        let bob = &plan.assignment("early").unwrap().alternatives[0];
+        assert_eq!(bob.reason, AlternativeReason::AtCapacity);
+    }
+
+    #[tokio::test]
+    async fn test_separation_of_duties() {
+        let at = Utc::now();
+        let mut config = BatchAllocationConfig::default();
+        config
+            .separation_of_duties
+            .add_conflicting_tasks("request".to_string(), "approve".to_string());
+        config
+            .separation_of_duties
+            .add_conflicting_tasks("approve".to_string(), "audit".to_string());
+        let batch = allocator(
+            vec![resource("alice", 0, HOUR_MS), resource("bob", 0, HOUR_MS)],
+            config,
+        )
+        .await;
+        let rates = HashMap::from([("alice".to_string(), 10.0), ("bob".to_string(), 20.0)]);
+
+        // alice requested in case c1 and cannot approve it
+        let history = CaseHistory::from([(
+            "c1".to_string(),
+            HashSet::from([("request".to_string(), "alice".to_string())]),
+        )]);
+        let items = vec![item("approve-c1", "c1", "approve", None)];
+        let plan = batch.plan(&items, &rates, &history, at).await.unwrap();
+        let approval = plan.assignment("approve-c1").unwrap();
+        assert_eq!(approval.user_id, "bob");
+        assert_eq!(
+            approval.alternatives[0].reason,
+            AlternativeReason::SeparationOfDuties {
+                conflicting_task: "request".to_string()
+            }
+        );
+
+        // Approval and audit of one case in the same batch go to different users
+        let items = vec![
+            item("approve-c2", "c2", "approve", None),
+            item("audit-c2", "c2", "audit", None),
+        ];
+        let plan = batch
+            .plan(&items, &rates, &CaseHistory::new(), at)
+            .await
+            .unwrap();
+        assert_ne!(
+            plan.assignment("approve-c2").unwrap().user_id,
+            plan.assignment("audit-c2").unwrap().user_id
+        );
+
+        // Nobody left for the approval if only alice is eligible
+        let mut only_alice = item("approve-c1", "c1", "approve", None);
+        only_alice.eligible = vec!["alice".to_string()];
+        let plan = batch
+            .plan(&[only_alice], &rates, &history, at)
+            .await
+            .unwrap();
+        assert_eq!(
+            plan.unassigned[0].reason,
+            UnassignedReason::SeparationOfDuties
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_assigns_offered_work_items() {
+        let batch = allocator(
+            vec![resource("alice", 0, HOUR_MS), resource("bob", 0, HOUR_MS)],
+            BatchAllocationConfig::default(),
+        )
+        .await;
+        let work_items = WorkItemService::new();
+        let spec_id = crate::parser::WorkflowSpecId::new();
+        let id = work_items
+            .create_work_item(
+                "case-1".to_string(),
+                spec_id,
+                "review".to_string(),
+                serde_json::json!({"offered_to": ["alice", "bob"]}),
+            )
+            .await
+            .unwrap();
+        let costs = CostService::default();
+        costs
+            .set_resource_rate(cost_resource_id(&"alice".to_string()), 80.0)
+            .await;
+        costs
+            .set_resource_rate(cost_resource_id(&"bob".to_string()), 30.0)
+            .await;
+
+        let plan = batch
+            .run(&work_items, &costs, &HashMap::new(), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(plan.assignments[0].user_id, "bob");
+        let work_item = work_items.get_work_item(&id).await.unwrap();
+        assert_eq!(work_item.state, WorkItemState::Assigned);
+        assert_eq!(work_item.assigned_resource_id.as_deref(), Some("bob"));
+        assert_eq!(
+            batch
+                .allocator
+                .get_resource(&"bob".to_string())
+                .await
+                .unwrap()
+                .current_workload,
+            1
+        );
+        assert_eq!(batch.last_plan().await, Some(plan));
+    }
+}
@@ -6,8 +6,10 @@
 //!
 //! - `yawl_resource.rs`: YAWL 3-phase allocation system with TRIZ enhancements
 //! - `compliance.rs`: Compliance constraints (SOD, 4-eyes) with hyper-advanced patterns
+//! - `batch.rs`: Cost-optimal batch allocation of offered work items
 
 pub mod allocation;
+pub mod batch;
 pub mod compliance;
 mod pool;
 pub mod query;
@@ -18,6 +20,10 @@ pub use allocation::{
     AllocationPolicy, AllocationRequest, AllocationResult, Capability, Resource, ResourceAllocator,
     ResourceId, Role,
 };
+pub use batch::{
+    Alternative, AlternativeReason, BatchAllocationConfig, BatchAllocationPlan, BatchAllocator,
+    BatchAssignment, BatchWorkItem, UnassignedItem, UnassignedReason,
+};
 pub use compliance::{
     ApproverHistory, ComplianceManager, ConstraintType, ConstraintViolation, FourEyes,
     ResourceHistory, SeparationOfDuties,
@@ -20,6 +20,7 @@
 //!    - Shortest queue
 //!    - Least busy
 //!    - Fastest completion history
+//!    - Cost-optimal batch allocation of all offered items (`resource::batch`)
 //!
 //! 3. **Phase 3: Start** - Determine when to start
 //!    - User-initiated
@@ -196,6 +197,24 @@ impl ThreePhaseAllocator {
         resources.insert(metadata.user_id.clone(), metadata);
     }
 
+    /// Get a registered resource
+    pub async fn get_resource(&self, user_id: &UserId) -> Option<ResourceMetadata> {
+        self.resources.read().await.get(user_id).cloned()
+    }
+
+    /// List the registered resources, ordered by user ID
+    pub async fn list_resources(&self) -> Vec<ResourceMetadata> {
+        let mut resources: Vec<ResourceMetadata> =
+            self.resources.read().await.values().cloned().collect();
+        resources.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        resources
+    }
+
+    /// Unregister a resource (e.g. while absent)
+    pub async fn unregister_resource(&self, user_id: &UserId) -> Option<ResourceMetadata> {
+        self.resources.write().await.remove(user_id)
+    }
+
     /// Phase 1: Offer - Select eligible participants
     pub async fn offer_phase(&self, criteria: &OfferCriteria) -> WorkflowResult<OfferPhase> {
         let resources = self.resources.read().await;
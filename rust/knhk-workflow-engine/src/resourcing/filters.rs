@@ -7,8 +7,10 @@
 
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::resource::allocation::types::{Capability, Resource, ResourceId, Role};
+use crate::resourcing::org_model::OrgModel;
 use crate::resourcing::three_phase::AllocationContext;
 use std::sync::Arc;
+use tokio::sync::RwLock;
 
 /// Filter result
 #[derive(Debug, Clone)]
@@ -159,6 +161,88 @@ impl ResourceFilter for AvailabilityFilter {
     }
 }
 
+/// Supervisor-of filter - Direct supervisors of a participant (e.g. the case starter)
+pub struct SupervisorOfFilter {
+    model: Arc<RwLock<OrgModel>>,
+    subordinate: String,
+}
+
+impl SupervisorOfFilter {
+    pub fn new(model: Arc<RwLock<OrgModel>>, subordinate: String) -> Self {
+        Self { model, subordinate }
+    }
+}
+
+impl ResourceFilter for SupervisorOfFilter {
+    fn filter(
+        &self,
+        resource: &Resource,
+        _context: &AllocationContext,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = WorkflowResult<FilterResult>> + Send>>
+    {
+        let model = self.model.clone();
+        let subordinate = self.subordinate.clone();
+        let resource_id = resource.id;
+
+        Box::pin(async move {
+            let model = model.read().await;
+            let passed = model
+                .participant_for(resource_id)
+                .is_some_and(|p| model.is_supervisor_of(&p.id, &subordinate));
+
+            Ok(FilterResult {
+                passed,
+                reason: if passed {
+                    None
+                } else {
+                    Some(format!("Not a supervisor of {}", subordinate))
+                },
+            })
+        })
+    }
+}
+
+/// Same-unit filter - Participants sharing an org unit with a participant
+pub struct SameUnitFilter {
+    model: Arc<RwLock<OrgModel>>,
+    participant: String,
+}
+
+impl SameUnitFilter {
+    pub fn new(model: Arc<RwLock<OrgModel>>, participant: String) -> Self {
+        Self { model, participant }
+    }
+}
+
+impl ResourceFilter for SameUnitFilter {
+    fn filter(
+        &self,
+        resource: &Resource,
+        _context: &AllocationContext,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = WorkflowResult<FilterResult>> + Send>>
+    {
+        let model = self.model.clone();
+        let participant = self.participant.clone();
+        let resource_id = resource.id;
+
+        Box::pin(async move {
+            let model = model.read().await;
+            let passed = model
+                .participant_for(resource_id)
+                .is_some_and(|p| model.same_unit(&p.id, &participant));
+
+            Ok(FilterResult {
+                passed,
+                reason: if passed {
+                    None
+                } else {
+                    Some(format!("Not in the same unit as {}", participant))
+                },
+            })
+        })
+    }
+}
+
 /// Composite filter (TRIZ Principle 40: Composite Materials)
 ///
 /// Combines multiple filters with AND/OR logic
@@ -252,4 +336,65 @@ mod tests {
         let result = filter.filter(&resource, &context).await.unwrap();
         assert!(result.passed);
     }
+
+    #[tokio::test]
+    async fn test_org_model_filters() {
+        let model = OrgModel::from_csv(
+            "kind,id,unit,reports_to,positions\n\
+             unit,claims,,,\n\
+             unit,sales,,,\n\
+             position,lead,claims,,\n\
+             position,clerk,claims,lead,\n\
+             position,rep,sales,,\n\
+             person,lena,,,lead\n\
+             person,ann,,,clerk\n\
+             person,sam,,,rep\n",
+        )
+        .unwrap();
+        let resources = model.to_resources(chrono::Utc::now());
+        let model = Arc::new(RwLock::new(model));
+        let resource = |id: &str| {
+            let resource_id = OrgModel::resource_id(id);
+            resources.iter().find(|r| r.id == resource_id).unwrap()
+        };
+        let context = AllocationContext {
+            task_id: "approve".to_string(),
+            case_id: "case1".to_string(),
+            required_capabilities: vec![],
+            required_roles: vec![],
+            workload_constraints: std::collections::HashMap::new(),
+        };
+
+        let supervisor = SupervisorOfFilter::new(model.clone(), "ann".to_string());
+        assert!(
+            supervisor
+                .filter(resource("lena"), &context)
+                .await
+                .unwrap()
+                .passed
+        );
+        assert!(
+            !supervisor
+                .filter(resource("sam"), &context)
+                .await
+                .unwrap()
+                .passed
+        );
+
+        let same_unit = SameUnitFilter::new(model, "ann".to_string());
+        assert!(
+            same_unit
+                .filter(resource("lena"), &context)
+                .await
+                .unwrap()
+                .passed
+        );
+        assert!(
+            !same_unit
+                .filter(resource("sam"), &context)
+                .await
+                .unwrap()
+                .passed
+        );
+    }
 }
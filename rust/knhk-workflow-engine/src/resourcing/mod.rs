@@ -9,6 +9,7 @@
 pub mod allocation;
 pub mod constraints;
 pub mod filters;
+pub mod org_model;
 pub mod resource_pool_impl;
 pub mod three_phase;
 
@@ -16,6 +17,10 @@ pub use allocation::{AllocationPolicy, AllocationResult, ResourceAllocator};
 pub use constraints::{
     Constraint, ConstraintResult, ConstraintType, FourEyesPrinciple, SeparationOfDuties,
 };
-pub use filters::{FilterResult, FilterType, ResourceFilter};
+pub use filters::{FilterResult, FilterType, ResourceFilter, SameUnitFilter, SupervisorOfFilter};
+pub use org_model::{
+    Absence, Delegation, OrgModel, OrgResourcePool, OrgUnit, Participant, ParticipantKind,
+    Position, Reroute,
+};
 pub use resource_pool_impl::ResourcePoolWrapper;
 pub use three_phase::{AllocationPhase, ResourcePool as ResourcePoolTrait, ThreePhaseAllocator};
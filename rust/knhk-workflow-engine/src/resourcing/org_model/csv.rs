@@ -0,0 +1,183 @@
+//! CSV import
+//!
+//! One row per entity, with a header row naming the columns (order and
+//! unknown columns are irrelevant, missing ones are empty):
+//!
+//! | column | used by | meaning |
+//! |---|---|---|
+//! | `kind` | all | `unit`, `position`, `person` or `resource` (non-human) |
+//! | `id`, `name` | all | identifier and display name (name defaults to id) |
+//! | `parent` | unit | enclosing unit |
+//! | `unit` | position, person, resource | owning unit / direct membership |
+//! | `reports_to` | position | superior position |
+//! | `positions` | person, resource | positions held |
+//! | `roles` | position, person, resource | roles |
+//! | `capabilities` | person, resource | `name` or `name:level` |
+//! | `deputy` | person, resource | standing deputy |
+//!
+//! List columns are separated by `;`. Fields follow RFC 4180 quoting.
+
+use super::{parse_capability, OrgModel, OrgUnit, Participant, ParticipantKind, Position};
+use crate::error::{WorkflowError, WorkflowResult};
+use std::collections::HashMap;
+
+impl OrgModel {
+    /// Import an org model from CSV
+    pub fn from_csv(input: &str) -> WorkflowResult<Self> {
+        let mut rows = parse_rows(input)?.into_iter();
+        let header: HashMap<String, usize> = rows
+            .next()
+            .ok_or_else(|| WorkflowError::Validation("Empty org model CSV".to_string()))?
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| (name.trim().to_ascii_lowercase(), i))
+            .collect();
+        for required in ["kind", "id"] {
+            if !header.contains_key(required) {
+                return Err(WorkflowError::Validation(format!(
+                    "Org model CSV lacks a '{}' column",
+                    required
+                )));
+            }
+        }
+
+        let mut model = OrgModel::new();
+        for (index, row) in rows.enumerate() {
+            if row.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+            let field = |name: &str| -> Option<String> {
+                header
+                    .get(name)
+                    .and_then(|i| row.get(*i))
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+            };
+            let list = |name: &str| -> Vec<String> {
+                field(name)
+                    .map(|v| {
+                        v.split(';')
+                            .map(str::trim)
+                            .filter(|v| !v.is_empty())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            let line = index + 2;
+            let id = field("id").ok_or_else(|| {
+                WorkflowError::Validation(format!("Org model CSV line {}: missing id", line))
+            })?;
+            let name = field("name").unwrap_or_else(|| id.clone());
+            let kind = field("kind").unwrap_or_default().to_ascii_lowercase();
+            match kind.as_str() {
+                "unit" => model.add_unit(OrgUnit {
+                    id,
+                    name,
+                    parent: field("parent"),
+                })?,
+                "position" => model.add_position(Position {
+                    id,
+                    name,
+                    unit: field("unit"),
+                    reports_to: field("reports_to"),
+                    roles: list("roles"),
+                })?,
+                "person" | "resource" => model.add_participant(Participant {
+                    id,
+                    name,
+                    kind: if kind == "person" {
+                        ParticipantKind::Human
+                    } else {
+                        ParticipantKind::NonHuman
+                    },
+                    positions: list("positions"),
+                    unit: field("unit"),
+                    roles: list("roles"),
+                    capabilities: list("capabilities")
+                        .iter()
+                        .map(|c| parse_capability(c))
+                        .collect::<WorkflowResult<_>>()?,
+                    deputy: field("deputy"),
+                })?,
+                other => {
+                    return Err(WorkflowError::Validation(format!(
+                        "Org model CSV line {}: unknown kind '{}'",
+                        line, other
+                    )))
+                }
+            }
+        }
+        model.validate()?;
+        Ok(model)
+    }
+}
+
+/// Split RFC 4180 CSV into rows of fields
+fn parse_rows(input: &str) -> WorkflowResult<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(WorkflowError::Validation(
+            "Unterminated quoted field in org model CSV".to_string(),
+        ));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted_fields() {
+        let rows = parse_rows("a,\"b,c\",\"say \"\"hi\"\"\"\r\nd,,\"multi\nline\"").unwrap();
+        assert_eq!(rows[0], vec!["a", "b,c", "say \"hi\""]);
+        assert_eq!(rows[1], vec!["d", "", "multi\nline"]);
+        assert!(parse_rows("\"open").is_err());
+    }
+
+    #[test]
+    fn test_import_reports_unknown_references() {
+        let csv = "kind,id,name,unit\nposition,clerk,Clerk,nowhere\n";
+        let err = OrgModel::from_csv(csv).unwrap_err();
+        assert!(err.to_string().contains("nowhere"));
+
+        let csv = "kind,id,capabilities\nperson,ann,\"typing:80;law\"\n";
+        let model = OrgModel::from_csv(csv).unwrap();
+        let ann = model.participant("ann").unwrap();
+        assert_eq!(ann.name, "ann");
+        assert_eq!(ann.capabilities[0].level, 80);
+        assert_eq!(ann.capabilities[1].level, 100);
+    }
+}
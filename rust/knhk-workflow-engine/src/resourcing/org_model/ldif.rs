@@ -0,0 +1,445 @@
+//! LDIF import
+//!
+//! Maps a directory export (RFC 2849 content records) onto the org model:
+//! - `organizationalUnit` → org unit (`ou`), nested by DN
+//! - `organizationalRole` → position (`cn`) in the nearest unit of its DN;
+//!   `roleOccupant` names the holders
+//! - `person` / `organizationalPerson` / `inetOrgPerson` → person (`uid`,
+//!   else `cn`) named by `displayName` or `cn`, member of the nearest unit of
+//!   its DN
+//! - `device` → non-human resource (`cn`)
+//! - `groupOfNames` / `groupOfUniqueNames` → role (`cn`) granted to each
+//!   `member` / `uniqueMember`
+//!
+//! Reporting lines come from `manager`: positions held by a person without
+//! an explicit superior report to the manager's first position. People who
+//! occupy no `organizationalRole` get a personal position (named by `title`)
+//! so that their reporting line can be represented. The extension attributes
+//! `knhkCapability` (`name` or `name:level`) and `knhkDeputy` (DN) carry
+//! capabilities and standing deputies.
+
+use super::{parse_capability, OrgModel, OrgUnit, Participant, Position};
+use crate::error::{WorkflowError, WorkflowResult};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::HashMap;
+
+/// One LDIF record: DN plus lower-cased attribute names with their values
+struct Entry {
+    dn: Vec<(String, String)>,
+    attributes: HashMap<String, Vec<String>>,
+}
+
+impl Entry {
+    fn values(&self, name: &str) -> &[String] {
+        self.attributes.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn first(&self, name: &str) -> Option<&str> {
+        self.values(name).first().map(String::as_str)
+    }
+
+    fn has_class(&self, classes: &[&str]) -> bool {
+        self.values("objectclass")
+            .iter()
+            .any(|c| classes.iter().any(|k| c.eq_ignore_ascii_case(k)))
+    }
+
+    /// Value of the leading RDN
+    fn rdn_value(&self) -> Option<&str> {
+        self.dn.first().map(|(_, v)| v.as_str())
+    }
+
+    /// Nearest enclosing `ou` (excluding the entry's own RDN)
+    fn parent_unit(&self) -> Option<String> {
+        self.dn
+            .iter()
+            .skip(1)
+            .find(|(attr, _)| attr == "ou")
+            .map(|(_, v)| v.clone())
+    }
+}
+
+impl OrgModel {
+    /// Import an org model from an LDIF directory export
+    pub fn from_ldif(input: &str) -> WorkflowResult<Self> {
+        let entries = parse_entries(input)?;
+        let mut model = OrgModel::new();
+        // DN (normalized) → participant ID
+        let mut participants_by_dn = HashMap::new();
+        // DN (normalized) → position ID
+        let mut positions_by_dn = HashMap::new();
+
+        for entry in &entries {
+            let dn = normalize_dn(&entry.dn);
+            if entry.has_class(&["organizationalUnit"]) {
+                let id = entry
+                    .first("ou")
+                    .or(entry.rdn_value())
+                    .unwrap_or_default()
+                    .to_string();
+                model.add_unit(OrgUnit {
+                    name: entry.first("description").unwrap_or(&id).to_string(),
+                    id,
+                    parent: entry.parent_unit(),
+                })?;
+            } else if entry.has_class(&["organizationalRole"]) {
+                let id = entry.first("cn").or(entry.rdn_value()).unwrap_or_default();
+                model.add_position(Position {
+                    id: id.to_string(),
+                    name: entry.first("description").unwrap_or(id).to_string(),
+                    unit: entry.parent_unit(),
+                    reports_to: None,
+                    roles: Vec::new(),
+                })?;
+                positions_by_dn.insert(dn, id.to_string());
+            } else if entry.has_class(&["person", "organizationalPerson", "inetOrgPerson"]) {
+                let id = entry
+                    .first("uid")
+                    .or(entry.first("cn"))
+                    .or(entry.rdn_value())
+                    .unwrap_or_default();
+                let name = entry
+                    .first("displayname")
+                    .or(entry.first("cn"))
+                    .unwrap_or(id);
+                let mut participant = Participant::human(id, name);
+                participant.unit = entry.parent_unit();
+                participant.capabilities = entry
+                    .values("knhkcapability")
+                    .iter()
+                    .map(|c| parse_capability(c))
+                    .collect::<WorkflowResult<_>>()?;
+                model.add_participant(participant)?;
+                participants_by_dn.insert(dn, id.to_string());
+            } else if entry.has_class(&["device"]) {
+                let id = entry.first("cn").or(entry.rdn_value()).unwrap_or_default();
+                let mut participant =
+                    Participant::non_human(id, entry.first("description").unwrap_or(id));
+                participant.unit = entry.parent_unit();
+                participant.capabilities = entry
+                    .values("knhkcapability")
+                    .iter()
+                    .map(|c| parse_capability(c))
+                    .collect::<WorkflowResult<_>>()?;
+                model.add_participant(participant)?;
+                participants_by_dn.insert(dn, id.to_string());
+            }
+        }
+
+        let resolve = |value: &str, what: &str| -> WorkflowResult<String> {
+            participants_by_dn
+                .get(&normalize_dn(&parse_dn(value)?))
+                .cloned()
+                .ok_or_else(|| {
+                    WorkflowError::Validation(format!("Unknown {} '{}' in LDIF", what, value))
+                })
+        };
+
+        // Occupants, group roles and deputies
+        for entry in &entries {
+            if entry.has_class(&["organizationalRole"]) {
+                let position = &positions_by_dn[&normalize_dn(&entry.dn)];
+                for occupant in entry.values("roleoccupant") {
+                    let id = resolve(occupant, "role occupant")?;
+                    if let Some(p) = model.participants.get_mut(&id) {
+                        p.positions.push(position.clone());
+                    }
+                }
+            } else if entry.has_class(&["groupOfNames", "groupOfUniqueNames"]) {
+                let role = entry.first("cn").or(entry.rdn_value()).unwrap_or_default();
+                for member in entry
+                    .values("member")
+                    .iter()
+                    .chain(entry.values("uniquemember"))
+                {
+                    // Groups may contain non-participant entries (e.g. nested groups)
+                    if let Ok(id) = resolve(member, "group member") {
+                        if let Some(p) = model.participants.get_mut(&id) {
+                            p.roles.push(role.to_string());
+                        }
+                    }
+                }
+            } else if let Some(id) = participants_by_dn.get(&normalize_dn(&entry.dn)) {
+                if let Some(deputy) = entry.first("knhkdeputy") {
+                    let deputy = resolve(deputy, "deputy")?;
+                    if let Some(p) = model.participants.get_mut(id) {
+                        p.deputy = Some(deputy);
+                    }
+                }
+            }
+        }
+
+        // Personal positions for people outside any organizationalRole
+        for entry in &entries {
+            let Some(id) = participants_by_dn.get(&normalize_dn(&entry.dn)) else {
+                continue;
+            };
+            let needs_position =
+                entry.has_class(&["person", "organizationalPerson", "inetOrgPerson"])
+                    && model.participants[id].positions.is_empty();
+            if needs_position {
+                let position_id = format!("{}#position", id);
+                model.add_position(Position {
+                    id: position_id.clone(),
+                    name: entry.first("title").unwrap_or(id).to_string(),
+                    unit: entry.parent_unit(),
+                    reports_to: None,
+                    roles: Vec::new(),
+                })?;
+                if let Some(p) = model.participants.get_mut(id) {
+                    p.positions.push(position_id);
+                    p.unit = None;
+                }
+            }
+        }
+
+        // Reporting lines from `manager`
+        for entry in &entries {
+            let (Some(id), Some(manager)) = (
+                participants_by_dn.get(&normalize_dn(&entry.dn)),
+                entry.first("manager"),
+            ) else {
+                continue;
+            };
+            let manager = resolve(manager, "manager")?;
+            let Some(boss) = model.participants[&manager].positions.first().cloned() else {
+                continue;
+            };
+            for position in model.participants[id].positions.clone() {
+                if let Some(p) = model.positions.get_mut(&position) {
+                    if p.reports_to.is_none() && p.id != boss {
+                        p.reports_to = Some(boss.clone());
+                    }
+                }
+            }
+        }
+
+        model.validate()?;
+        Ok(model)
+    }
+}
+
+/// Split LDIF into entries (comments dropped, folded lines joined, base64 decoded)
+fn parse_entries(input: &str) -> WorkflowResult<Vec<Entry>> {
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_comment = false;
+    for raw in input.lines() {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(continuation) = raw.strip_prefix(' ') {
+            if !in_comment {
+                if let Some(last) = lines.last_mut() {
+                    last.push_str(continuation);
+                }
+            }
+            continue;
+        }
+        in_comment = raw.starts_with('#');
+        if in_comment {
+            continue;
+        }
+        if raw.trim().is_empty() {
+            if !lines.is_empty() {
+                records.push(std::mem::take(&mut lines));
+            }
+            continue;
+        }
+        lines.push(raw.to_string());
+    }
+    if !lines.is_empty() {
+        records.push(lines);
+    }
+
+    let mut entries = Vec::new();
+    for record in records {
+        let mut dn = None;
+        let mut attributes: HashMap<String, Vec<String>> = HashMap::new();
+        for line in record {
+            let (name, value) = parse_line(&line)?;
+            match name.as_str() {
+                "version" if dn.is_none() => {}
+                "dn" => dn = Some(parse_dn(&value)?),
+                "changetype" => {
+                    return Err(WorkflowError::Validation(
+                        "LDIF change records are not supported".to_string(),
+                    ))
+                }
+                _ => attributes.entry(name).or_default().push(value),
+            }
+        }
+        // A leading `version:` line forms its own record when followed by a blank line
+        if let Some(dn) = dn {
+            entries.push(Entry { dn, attributes });
+        } else if !attributes.is_empty() {
+            return Err(WorkflowError::Validation(
+                "LDIF record without dn".to_string(),
+            ));
+        }
+    }
+    Ok(entries)
+}
+
+/// Parse `name: value`, `name:: base64` (attribute options are dropped)
+fn parse_line(line: &str) -> WorkflowResult<(String, String)> {
+    let (name, rest) = line
+        .split_once(':')
+        .ok_or_else(|| WorkflowError::Validation(format!("Invalid LDIF line '{}'", line)))?;
+    let name = name
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let value = if let Some(encoded) = rest.strip_prefix(':') {
+        let bytes = STANDARD.decode(encoded.trim()).map_err(|e| {
+            WorkflowError::Validation(format!("Invalid base64 value of '{}': {}", name, e))
+        })?;
+        String::from_utf8(bytes)
+            .map_err(|_| WorkflowError::Validation(format!("Value of '{}' is not UTF-8", name)))?
+    } else if rest.starts_with('<') {
+        return Err(WorkflowError::Validation(format!(
+            "URL values are not supported (attribute '{}')",
+            name
+        )));
+    } else {
+        rest.trim_start().to_string()
+    };
+    Ok((name, value))
+}
+
+/// Split a DN into `(attribute, value)` RDNs, innermost first
+///
+/// Multi-valued RDNs keep only their first component.
+fn parse_dn(dn: &str) -> WorkflowResult<Vec<(String, String)>> {
+    let mut rdns = Vec::new();
+    let mut current = String::new();
+    let mut chars = dn.chars();
+    let mut components = Vec::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.push('\\');
+                    current.push(escaped);
+                }
+            }
+            ',' => components.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    components.push(current);
+    for component in components {
+        let component = component.split('+').next().unwrap_or_default();
+        if component.trim().is_empty() {
+            continue;
+        }
+        let (attr, value) = component
+            .split_once('=')
+            .ok_or_else(|| WorkflowError::Validation(format!("Invalid DN '{}'", dn)))?;
+        rdns.push((attr.trim().to_ascii_lowercase(), unescape(value.trim())));
+    }
+    Ok(rdns)
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                out.push(escaped);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Case-insensitive DN key
+fn normalize_dn(dn: &[(String, String)]) -> String {
+    dn.iter()
+        .map(|(attr, value)| format!("{}={}", attr, value.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTORY: &str = "\
+version: 1
+
+# Organization
+dn: ou=hq,dc=example,dc=com
+objectClass: organizationalUnit
+ou: hq
+
+dn: ou=claims,ou=hq,dc=example,dc=com
+objectClass: organizationalUnit
+ou: claims
+description: Claims Department
+
+dn: cn=claims-lead,ou=claims,ou=hq,dc=example,dc=com
+objectClass: organizationalRole
+cn: claims-lead
+roleOccupant: uid=lena,ou=claims,ou=hq,dc=example,dc=com
+
+dn: uid=lena,ou=claims,ou=hq,dc=example,dc=com
+objectClass: inetOrgPerson
+uid: lena
+cn: Lena Lead
+manager: uid=carol,ou=hq,dc=example,dc=com
+
+dn: uid=carol,ou=hq,dc=example,dc=com
+objectClass: inetOrgPerson
+uid: carol
+cn: Carol
+title: CEO
+
+dn: uid=ann,ou=claims,ou=hq,dc=example,dc=com
+objectClass: inetOrgPerson
+uid: ann
+cn:: QW5uIEzDs3Bleg==
+manager: uid=lena,ou=claims,
+ ou=hq,dc=example,dc=com
+knhkCapability: typing:70
+knhkDeputy: UID=Lena,OU=Claims,OU=HQ,DC=example,DC=com
+
+dn: cn=scanner,ou=claims,ou=hq,dc=example,dc=com
+objectClass: device
+cn: scanner
+
+dn: cn=approvers,dc=example,dc=com
+objectClass: groupOfNames
+cn: approvers
+member: uid=lena,ou=claims,ou=hq,dc=example,dc=com
+member: cn=nested,dc=example,dc=com
+";
+
+    #[test]
+    fn test_import_directory() {
+        let model = OrgModel::from_ldif(DIRECTORY).unwrap();
+        assert_eq!(model.unit("claims").unwrap().parent.as_deref(), Some("hq"));
+        assert_eq!(model.unit("claims").unwrap().name, "Claims Department");
+
+        let ann = model.participant("ann").unwrap();
+        assert_eq!(ann.name, "Ann López");
+        assert_eq!(ann.capabilities[0].level, 70);
+        assert_eq!(ann.deputy.as_deref(), Some("lena"));
+
+        assert!(model.is_supervisor_of("lena", "ann"));
+        assert!(model.is_supervisor_of("carol", "lena"));
+        assert_eq!(model.position("carol#position").unwrap().name, "CEO");
+        assert!(model.roles_of("lena").contains("approvers"));
+        assert!(model.same_unit("ann", "scanner"));
+    }
+
+    #[test]
+    fn test_rejects_change_records() {
+        let ldif = "dn: uid=ann,dc=example,dc=com\nchangetype: delete\n";
+        assert!(OrgModel::from_ldif(ldif).is_err());
+    }
+}
@@ -0,0 +1,1126 @@
+//! Organizational model
+//!
+//! Org units, positions with reporting lines, roles, capabilities and
+//! participants (people and non-human resources), together with the
+//! time-dependent parts of resourcing: absences and delegations.
+//!
+//! Models are imported from CSV ([`OrgModel::from_csv`]), LDIF
+//! ([`OrgModel::from_ldif`]) or Turtle using the W3C Organization Ontology
+//! ([`OrgModel::from_turtle`], `rdf` feature) and feed both resourcing layers:
+//! - [`OrgResourcePool`] is a [`ResourcePool`] for
+//!   [`resourcing::ThreePhaseAllocator`](super::ThreePhaseAllocator) whose
+//!   offers skip absent participants
+//! - [`OrgModel::sync_three_phase`] registers present participants with
+//!   [`resource::ThreePhaseAllocator`](crate::resource::ThreePhaseAllocator)
+//!
+//! Work items queued for an absent participant are rerouted to a stand-in
+//! ([`OrgModel::stand_in_for`]): an active delegation first, then the
+//! standing deputy, then the supervisor. [`OrgResourcePool::add_absence`] and
+//! [`OrgResourcePool::add_delegation`] reroute and resync right away;
+//! [`OrgResourcePool::refresh`] does the same for absences that start later.
+
+mod csv;
+mod ldif;
+#[cfg(feature = "rdf")]
+mod turtle;
+
+use super::three_phase::ResourcePool;
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::resource::allocation::types::{Capability, Resource, ResourceId, Role};
+use crate::resource::three_phase::{ResourceMetadata, ThreePhaseAllocator};
+use crate::services::work_items::{WorkItem, WorkItemService, WorkItemState};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Organizational unit (department, team, ...)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrgUnit {
+    /// Unit identifier
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Enclosing unit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+/// Position (post) in the reporting hierarchy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    /// Position identifier
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Unit the position belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Position this one reports to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reports_to: Option<String>,
+    /// Roles granted to holders of the position
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// Kind of participant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParticipantKind {
+    /// Person
+    #[default]
+    Human,
+    /// Machine, system or other non-human resource
+    NonHuman,
+}
+
+/// Participant (person or non-human resource)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Participant {
+    /// Participant identifier (also the user ID towards allocators and work items)
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Human or non-human
+    #[serde(default)]
+    pub kind: ParticipantKind,
+    /// Positions held
+    #[serde(default)]
+    pub positions: Vec<String>,
+    /// Unit membership outside of positions (typical for non-human resources)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Roles held directly (in addition to those of the positions)
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Capabilities
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    /// Standing deputy while absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deputy: Option<String>,
+}
+
+impl Participant {
+    /// Person with no positions, roles or capabilities yet
+    pub fn human(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            kind: ParticipantKind::Human,
+            positions: Vec::new(),
+            unit: None,
+            roles: Vec::new(),
+            capabilities: Vec::new(),
+            deputy: None,
+        }
+    }
+
+    /// Non-human resource with no roles or capabilities yet
+    pub fn non_human(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            kind: ParticipantKind::NonHuman,
+            ..Self::human(id, name)
+        }
+    }
+}
+
+/// Period in which a participant is unavailable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Absence {
+    /// Absent participant
+    pub participant: String,
+    /// Start (inclusive)
+    pub from: DateTime<Utc>,
+    /// End (exclusive)
+    pub until: DateTime<Utc>,
+    /// Reason (vacation, sick leave, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Absence {
+    /// Whether the absence covers `at`
+    pub fn covers(&self, at: DateTime<Utc>) -> bool {
+        self.from <= at && at < self.until
+    }
+}
+
+/// Delegation of a participant's work to another participant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delegation {
+    /// Participant whose work is delegated
+    pub delegator: String,
+    /// Participant receiving the work
+    pub delegate: String,
+    /// Start (inclusive)
+    pub from: DateTime<Utc>,
+    /// End (exclusive, open-ended if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    /// Tasks covered by the delegation (all tasks if empty)
+    #[serde(default)]
+    pub tasks: Vec<String>,
+}
+
+impl Delegation {
+    /// Whether the delegation covers `task_id` at `at`
+    ///
+    /// A delegation restricted to specific tasks never applies when the task
+    /// is unknown (`None`).
+    pub fn applies(&self, task_id: Option<&str>, at: DateTime<Utc>) -> bool {
+        let active = self.from <= at && !self.until.is_some_and(|until| until <= at);
+        let task_matches = self.tasks.is_empty()
+            || task_id.is_some_and(|task_id| self.tasks.iter().any(|t| t == task_id));
+        active && task_matches
+    }
+}
+
+/// Rerouting decision for a work item queued for an absent participant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reroute {
+    /// Work item ID
+    pub work_item_id: String,
+    /// Absent assignee
+    pub from: String,
+    /// Stand-in (`None` if nobody is available)
+    pub to: Option<String>,
+}
+
+/// Organizational model
+#[derive(Debug, Clone, Default)]
+pub struct OrgModel {
+    units: BTreeMap<String, OrgUnit>,
+    positions: BTreeMap<String, Position>,
+    participants: BTreeMap<String, Participant>,
+    by_resource: HashMap<ResourceId, String>,
+    absences: Vec<Absence>,
+    delegations: Vec<Delegation>,
+}
+
+impl OrgModel {
+    /// Create an empty model
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stable resource ID of a participant
+    ///
+    /// Derived from the participant ID, so re-importing a model yields the
+    /// same resource IDs.
+    pub fn resource_id(participant_id: &str) -> ResourceId {
+        ResourceId(Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            format!("urn:knhk:participant:{}", participant_id).as_bytes(),
+        ))
+    }
+
+    /// Add an org unit
+    pub fn add_unit(&mut self, unit: OrgUnit) -> WorkflowResult<()> {
+        if self.units.contains_key(&unit.id) {
+            return Err(duplicate("org unit", &unit.id));
+        }
+        self.units.insert(unit.id.clone(), unit);
+        Ok(())
+    }
+
+    /// Add a position
+    pub fn add_position(&mut self, position: Position) -> WorkflowResult<()> {
+        if self.positions.contains_key(&position.id) {
+            return Err(duplicate("position", &position.id));
+        }
+        self.positions.insert(position.id.clone(), position);
+        Ok(())
+    }
+
+    /// Add a participant
+    pub fn add_participant(&mut self, participant: Participant) -> WorkflowResult<()> {
+        if self.participants.contains_key(&participant.id) {
+            return Err(duplicate("participant", &participant.id));
+        }
+        self.by_resource
+            .insert(Self::resource_id(&participant.id), participant.id.clone());
+        self.participants
+            .insert(participant.id.clone(), participant);
+        Ok(())
+    }
+
+    /// Record an absence period
+    pub fn add_absence(&mut self, absence: Absence) -> WorkflowResult<()> {
+        self.require_participant(&absence.participant)?;
+        if absence.from >= absence.until {
+            return Err(WorkflowError::Validation(format!(
+                "Absence of '{}' ends before it starts",
+                absence.participant
+            )));
+        }
+        self.absences.push(absence);
+        Ok(())
+    }
+
+    /// Record a delegation
+    pub fn add_delegation(&mut self, delegation: Delegation) -> WorkflowResult<()> {
+        self.require_participant(&delegation.delegator)?;
+        self.require_participant(&delegation.delegate)?;
+        if delegation.delegator == delegation.delegate {
+            return Err(WorkflowError::Validation(format!(
+                "Participant '{}' cannot delegate to themselves",
+                delegation.delegator
+            )));
+        }
+        if delegation
+            .until
+            .is_some_and(|until| until <= delegation.from)
+        {
+            return Err(WorkflowError::Validation(format!(
+                "Delegation from '{}' to '{}' ends before it starts",
+                delegation.delegator, delegation.delegate
+            )));
+        }
+        self.delegations.push(delegation);
+        Ok(())
+    }
+
+    /// Check that all references resolve and that units and reporting lines
+    /// are free of cycles
+    pub fn validate(&self) -> WorkflowResult<()> {
+        for unit in self.units.values() {
+            if let Some(parent) = &unit.parent {
+                self.require_unit(parent, &format!("org unit '{}'", unit.id))?;
+            }
+            let mut seen = HashSet::new();
+            let mut current = Some(unit);
+            while let Some(u) = current {
+                if !seen.insert(&u.id) {
+                    return Err(WorkflowError::Validation(format!(
+                        "Org unit '{}' is its own ancestor",
+                        unit.id
+                    )));
+                }
+                current = u.parent.as_ref().and_then(|p| self.units.get(p));
+            }
+        }
+        for position in self.positions.values() {
+            let owner = format!("position '{}'", position.id);
+            if let Some(unit) = &position.unit {
+                self.require_unit(unit, &owner)?;
+            }
+            if let Some(boss) = &position.reports_to {
+                self.require_position(boss, &owner)?;
+            }
+            let mut seen = HashSet::new();
+            let mut current = Some(position);
+            while let Some(p) = current {
+                if !seen.insert(&p.id) {
+                    return Err(WorkflowError::Validation(format!(
+                        "Position '{}' reports to itself",
+                        position.id
+                    )));
+                }
+                current = p.reports_to.as_ref().and_then(|r| self.positions.get(r));
+            }
+        }
+        for participant in self.participants.values() {
+            let owner = format!("participant '{}'", participant.id);
+            for position in &participant.positions {
+                self.require_position(position, &owner)?;
+            }
+            if let Some(unit) = &participant.unit {
+                self.require_unit(unit, &owner)?;
+            }
+            if let Some(deputy) = &participant.deputy {
+                if deputy == &participant.id || !self.participants.contains_key(deputy) {
+                    return Err(WorkflowError::Validation(format!(
+                        "Unknown deputy '{}' of {}",
+                        deputy, owner
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Org unit by ID
+    pub fn unit(&self, id: &str) -> Option<&OrgUnit> {
+        self.units.get(id)
+    }
+
+    /// Position by ID
+    pub fn position(&self, id: &str) -> Option<&Position> {
+        self.positions.get(id)
+    }
+
+    /// Participant by ID
+    pub fn participant(&self, id: &str) -> Option<&Participant> {
+        self.participants.get(id)
+    }
+
+    /// Participant behind a resource ID
+    pub fn participant_for(&self, resource_id: ResourceId) -> Option<&Participant> {
+        self.by_resource
+            .get(&resource_id)
+            .and_then(|id| self.participants.get(id))
+    }
+
+    /// All org units
+    pub fn units(&self) -> impl Iterator<Item = &OrgUnit> {
+        self.units.values()
+    }
+
+    /// All positions
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.positions.values()
+    }
+
+    /// All participants
+    pub fn participants(&self) -> impl Iterator<Item = &Participant> {
+        self.participants.values()
+    }
+
+    /// Recorded absences
+    pub fn absences(&self) -> &[Absence] {
+        &self.absences
+    }
+
+    /// Recorded delegations
+    pub fn delegations(&self) -> &[Delegation] {
+        &self.delegations
+    }
+
+    /// Roles of a participant, including those of the positions held
+    pub fn roles_of(&self, participant_id: &str) -> BTreeSet<&str> {
+        let Some(participant) = self.participants.get(participant_id) else {
+            return BTreeSet::new();
+        };
+        participant
+            .roles
+            .iter()
+            .chain(
+                participant
+                    .positions
+                    .iter()
+                    .filter_map(|p| self.positions.get(p))
+                    .flat_map(|p| p.roles.iter()),
+            )
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Units a participant belongs to directly (through positions or membership)
+    pub fn units_of(&self, participant_id: &str) -> BTreeSet<&str> {
+        let Some(participant) = self.participants.get(participant_id) else {
+            return BTreeSet::new();
+        };
+        participant
+            .positions
+            .iter()
+            .filter_map(|p| self.positions.get(p))
+            .filter_map(|p| p.unit.as_deref())
+            .chain(participant.unit.as_deref())
+            .collect()
+    }
+
+    /// Participants holding a position
+    pub fn holders(&self, position_id: &str) -> Vec<&Participant> {
+        self.participants
+            .values()
+            .filter(|p| p.positions.iter().any(|pos| pos == position_id))
+            .collect()
+    }
+
+    /// Direct supervisors: holders of the positions the participant's positions report to
+    pub fn supervisors_of(&self, participant_id: &str) -> Vec<&Participant> {
+        let Some(participant) = self.participants.get(participant_id) else {
+            return Vec::new();
+        };
+        let mut supervisors: Vec<&Participant> = Vec::new();
+        for boss in participant
+            .positions
+            .iter()
+            .filter_map(|p| self.positions.get(p))
+            .filter_map(|p| p.reports_to.as_deref())
+        {
+            for holder in self.holders(boss) {
+                if holder.id != participant.id && !supervisors.iter().any(|s| s.id == holder.id) {
+                    supervisors.push(holder);
+                }
+            }
+        }
+        supervisors
+    }
+
+    /// Whether `supervisor` is a direct supervisor of `subordinate`
+    pub fn is_supervisor_of(&self, supervisor: &str, subordinate: &str) -> bool {
+        self.supervisors_of(subordinate)
+            .iter()
+            .any(|s| s.id == supervisor)
+    }
+
+    /// Whether two participants share a unit
+    pub fn same_unit(&self, a: &str, b: &str) -> bool {
+        let units = self.units_of(a);
+        self.units_of(b).iter().any(|u| units.contains(u))
+    }
+
+    /// Whether a participant is absent at `at`
+    pub fn is_absent(&self, participant_id: &str, at: DateTime<Utc>) -> bool {
+        self.absences
+            .iter()
+            .any(|a| a.participant == participant_id && a.covers(at))
+    }
+
+    /// Present participant taking over `participant_id`'s work on `task_id`
+    ///
+    /// Candidates are tried in order: active delegations, the standing
+    /// deputy, then the direct supervisors. Absent candidates are skipped in
+    /// favour of their own stand-ins.
+    pub fn stand_in_for(
+        &self,
+        participant_id: &str,
+        task_id: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> Option<&Participant> {
+        let mut visited: HashSet<&str> = HashSet::from([participant_id]);
+        let mut queue: VecDeque<&str> = VecDeque::from([participant_id]);
+        while let Some(current) = queue.pop_front() {
+            for candidate in self.stand_in_candidates(current, task_id, at) {
+                if !visited.insert(candidate) {
+                    continue;
+                }
+                if !self.is_absent(candidate, at) {
+                    return self.participants.get(candidate);
+                }
+                queue.push_back(candidate);
+            }
+        }
+        None
+    }
+
+    fn stand_in_candidates<'a>(
+        &'a self,
+        participant_id: &str,
+        task_id: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> Vec<&'a str> {
+        let delegates = self
+            .delegations
+            .iter()
+            .filter(|d| d.delegator == participant_id && d.applies(task_id, at))
+            .map(|d| d.delegate.as_str());
+        let deputy = self
+            .participants
+            .get(participant_id)
+            .and_then(|p| p.deputy.as_deref());
+        let supervisors = self
+            .supervisors_of(participant_id)
+            .into_iter()
+            .map(|p| p.id.as_str());
+        delegates.chain(deputy).chain(supervisors).collect()
+    }
+
+    /// Reroute decisions for work items of participants absent at `at`
+    ///
+    /// Covers items allocated to an absent participant (assigned or claimed)
+    /// and open offers that include one; an offer yields one decision per
+    /// absent participant it was made to.
+    pub fn plan_reroutes(&self, work_items: &[WorkItem], at: DateTime<Utc>) -> Vec<Reroute> {
+        let mut reroutes = Vec::new();
+        for item in work_items {
+            let holders = match item.state {
+                WorkItemState::Assigned if !offered_to(item).is_empty() => offered_to(item),
+                WorkItemState::Assigned | WorkItemState::Claimed => {
+                    item.assigned_resource_id.iter().cloned().collect()
+                }
+                _ => continue,
+            };
+            for holder in holders {
+                if !self.participants.contains_key(&holder) || !self.is_absent(&holder, at) {
+                    continue;
+                }
+                reroutes.push(Reroute {
+                    work_item_id: item.id.clone(),
+                    to: self
+                        .stand_in_for(&holder, Some(&item.task_id), at)
+                        .map(|p| p.id.clone()),
+                    from: holder,
+                });
+            }
+        }
+        reroutes
+    }
+
+    /// Move queued work items of absent participants to their stand-ins
+    ///
+    /// Allocated items are delegated. Offers are made again with each absent
+    /// participant replaced by their stand-in; an offer left with nobody goes
+    /// back to `Created`. Allocated items without an available stand-in stay
+    /// where they are; they are still reported with `to: None`.
+    pub async fn reroute_work_items(
+        &self,
+        service: &WorkItemService,
+        at: DateTime<Utc>,
+    ) -> WorkflowResult<Vec<Reroute>> {
+        let queued = service
+            .get_work_items_by_states(vec![WorkItemState::Assigned, WorkItemState::Claimed])
+            .await;
+        let reroutes = self.plan_reroutes(&queued, at);
+        for item in &queued {
+            let item_reroutes: Vec<&Reroute> = reroutes
+                .iter()
+                .filter(|r| r.work_item_id == item.id)
+                .collect();
+            if item_reroutes.is_empty() {
+                continue;
+            }
+            let offered = offered_to(item);
+            if item.state == WorkItemState::Assigned && !offered.is_empty() {
+                let mut resources: Vec<String> = Vec::new();
+                for resource in offered {
+                    let resource = match item_reroutes.iter().find(|r| r.from == resource) {
+                        Some(reroute) => reroute.to.clone(),
+                        None => Some(resource),
+                    };
+                    if let Some(resource) = resource.filter(|r| !resources.contains(r)) {
+                        resources.push(resource);
+                    }
+                }
+                service.reoffer_work_item(&item.id, resources).await?;
+            } else {
+                for reroute in item_reroutes {
+                    if let Some(to) = &reroute.to {
+                        service
+                            .delegate_work_item(&item.id, &reroute.from, to.clone())
+                            .await?;
+                    }
+                }
+            }
+        }
+        Ok(reroutes)
+    }
+
+    /// Resources for all participants (`available` is false while absent)
+    pub fn to_resources(&self, at: DateTime<Utc>) -> Vec<Resource> {
+        self.participants
+            .values()
+            .map(|participant| Resource {
+                id: Self::resource_id(&participant.id),
+                name: participant.name.clone(),
+                roles: self
+                    .roles_of(&participant.id)
+                    .into_iter()
+                    .map(|role| Role {
+                        id: role.to_string(),
+                        name: role.to_string(),
+                        capabilities: Vec::new(),
+                    })
+                    .collect(),
+                capabilities: participant.capabilities.clone(),
+                workload: 0,
+                queue_length: 0,
+                available: !self.is_absent(&participant.id, at),
+            })
+            .collect()
+    }
+
+    /// Allocator metadata of a participant
+    ///
+    /// The position level counts the reporting levels below the participant's
+    /// most senior position (0 for positions nobody reports to). The
+    /// department is the first direct unit; org groups are all direct units
+    /// and their ancestors.
+    pub fn resource_metadata(&self, participant_id: &str) -> Option<ResourceMetadata> {
+        let participant = self.participants.get(participant_id)?;
+        let units = self.units_of(participant_id);
+        let mut org_groups = BTreeSet::new();
+        for unit in &units {
+            let mut current = self.units.get(*unit);
+            while let Some(u) = current {
+                if !org_groups.insert(u.id.clone()) {
+                    break;
+                }
+                current = u.parent.as_ref().and_then(|p| self.units.get(p));
+            }
+        }
+        Some(ResourceMetadata {
+            user_id: participant.id.clone(),
+            roles: self
+                .roles_of(participant_id)
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            capabilities: participant
+                .capabilities
+                .iter()
+                .map(|c| c.name.clone())
+                .collect(),
+            position_level: participant
+                .positions
+                .iter()
+                .map(|p| self.position_level(p))
+                .max()
+                .unwrap_or(0),
+            department: units.iter().next().map(|u| u.to_string()),
+            org_groups: org_groups.into_iter().collect(),
+            current_workload: 0,
+            avg_completion_time: 0.0,
+            queue_length: 0,
+        })
+    }
+
+    fn position_level(&self, position_id: &str) -> u32 {
+        // Reporting lines are acyclic after `validate`; the depth bound keeps
+        // unvalidated models from recursing forever.
+        fn level(model: &OrgModel, position_id: &str, depth: usize) -> u32 {
+            if depth > model.positions.len() {
+                return 0;
+            }
+            model
+                .positions
+                .values()
+                .filter(|p| p.reports_to.as_deref() == Some(position_id))
+                .map(|p| level(model, &p.id, depth + 1) + 1)
+                .max()
+                .unwrap_or(0)
+        }
+        level(self, position_id, 0)
+    }
+
+    /// Register participants present at `at` with a 3-phase allocator and
+    /// unregister absent ones
+    ///
+    /// Already registered participants keep their workload, queue length and
+    /// completion history.
+    pub async fn sync_three_phase(&self, allocator: &ThreePhaseAllocator, at: DateTime<Utc>) {
+        for participant in self.participants.values() {
+            if self.is_absent(&participant.id, at) {
+                allocator.unregister_resource(&participant.id).await;
+                continue;
+            }
+            let Some(mut metadata) = self.resource_metadata(&participant.id) else {
+                continue;
+            };
+            if let Some(existing) = allocator.get_resource(&participant.id).await {
+                metadata.current_workload = existing.current_workload;
+                metadata.avg_completion_time = existing.avg_completion_time;
+                metadata.queue_length = existing.queue_length;
+            }
+            allocator.register_resource(metadata).await;
+        }
+    }
+
+    fn require_participant(&self, id: &str) -> WorkflowResult<()> {
+        if self.participants.contains_key(id) {
+            Ok(())
+        } else {
+            Err(WorkflowError::Validation(format!(
+                "Unknown participant '{}'",
+                id
+            )))
+        }
+    }
+
+    fn require_unit(&self, id: &str, owner: &str) -> WorkflowResult<()> {
+        if self.units.contains_key(id) {
+            Ok(())
+        } else {
+            Err(WorkflowError::Validation(format!(
+                "Unknown org unit '{}' referenced by {}",
+                id, owner
+            )))
+        }
+    }
+
+    fn require_position(&self, id: &str, owner: &str) -> WorkflowResult<()> {
+        if self.positions.contains_key(id) {
+            Ok(())
+        } else {
+            Err(WorkflowError::Validation(format!(
+                "Unknown position '{}' referenced by {}",
+                id, owner
+            )))
+        }
+    }
+}
+
+/// Resources an open offer was made to (`offered_to` in the item data)
+fn offered_to(item: &WorkItem) -> Vec<String> {
+    item.data
+        .get("offered_to")
+        .and_then(|offered| offered.as_array())
+        .map(|offered| {
+            offered
+                .iter()
+                .filter_map(|r| r.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn duplicate(what: &str, id: &str) -> WorkflowError {
+    WorkflowError::Validation(format!("Duplicate {} '{}'", what, id))
+}
+
+/// Parse a capability written as `name` or `name:level` (level 0-100, defaults to 100)
+fn parse_capability(value: &str) -> WorkflowResult<Capability> {
+    let (name, level) = match value.rsplit_once(':') {
+        Some((name, level)) if level.trim().bytes().all(|b| b.is_ascii_digit()) => {
+            let level = level.trim().parse::<u8>().ok().filter(|l| *l <= 100);
+            let level = level.ok_or_else(|| {
+                WorkflowError::Validation(format!("Invalid capability level in '{}'", value))
+            })?;
+            (name.trim(), level)
+        }
+        _ => (value.trim(), 100),
+    };
+    Ok(Capability {
+        id: name.to_string(),
+        name: name.to_string(),
+        level,
+    })
+}
+
+/// Resource pool over a shared org model for
+/// [`resourcing::ThreePhaseAllocator`](super::ThreePhaseAllocator)
+///
+/// Participants absent at offer time are left out of the pool. Absences and
+/// delegations recorded through the pool also reroute queued work items and
+/// resync the resource-layer allocator, when those are attached.
+pub struct OrgResourcePool {
+    model: Arc<RwLock<OrgModel>>,
+    work_items: Option<Arc<WorkItemService>>,
+    allocator: Option<Arc<ThreePhaseAllocator>>,
+}
+
+impl OrgResourcePool {
+    /// Create new pool
+    pub fn new(model: Arc<RwLock<OrgModel>>) -> Self {
+        Self {
+            model,
+            work_items: None,
+            allocator: None,
+        }
+    }
+
+    /// Reroute the work items of `service` when participants become absent
+    pub fn with_work_items(mut self, service: Arc<WorkItemService>) -> Self {
+        self.work_items = Some(service);
+        self
+    }
+
+    /// Keep the registrations of a resource-layer allocator in step with absences
+    pub fn with_allocator(mut self, allocator: Arc<ThreePhaseAllocator>) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+
+    /// Get underlying model
+    pub fn model(&self) -> &Arc<RwLock<OrgModel>> {
+        &self.model
+    }
+
+    /// Record an absence, then reroute and resync for the current time
+    pub async fn add_absence(&self, absence: Absence) -> WorkflowResult<Vec<Reroute>> {
+        self.model.write().await.add_absence(absence)?;
+        self.refresh(Utc::now()).await
+    }
+
+    /// Record a delegation, then reroute and resync for the current time
+    pub async fn add_delegation(&self, delegation: Delegation) -> WorkflowResult<Vec<Reroute>> {
+        self.model.write().await.add_delegation(delegation)?;
+        self.refresh(Utc::now()).await
+    }
+
+    /// Apply the calendar at `at`
+    ///
+    /// Reroutes work queued for participants absent at `at` and re-registers
+    /// the present ones with the allocator. Absences recorded ahead of time
+    /// take effect the first time this runs inside them, so call it on a
+    /// schedule (e.g. from the timer service).
+    pub async fn refresh(&self, at: DateTime<Utc>) -> WorkflowResult<Vec<Reroute>> {
+        let model = self.model.read().await;
+        let reroutes = match &self.work_items {
+            Some(service) => model.reroute_work_items(service, at).await?,
+            None => Vec::new(),
+        };
+        if let Some(allocator) = &self.allocator {
+            model.sync_three_phase(allocator, at).await;
+        }
+        Ok(reroutes)
+    }
+}
+
+#[async_trait::async_trait]
+impl ResourcePool for OrgResourcePool {
+    async fn get_all_resources(&self) -> Vec<Resource> {
+        let model = self.model.read().await;
+        model
+            .to_resources(Utc::now())
+            .into_iter()
+            .filter(|r| r.available)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::WorkflowSpecId;
+    use chrono::Duration;
+
+    const ORG: &str = "\
+kind,id,name,parent,unit,reports_to,positions,roles,capabilities,deputy
+unit,hq,Headquarters,,,,,,,
+unit,claims,Claims,hq,,,,,,
+position,ceo,Chief Executive,,hq,,,executive,,
+position,lead,Claims Lead,,claims,ceo,,approver,,
+position,clerk,Claims Clerk,,claims,lead,,clerk,,
+person,carol,Carol,,,,ceo,,,
+person,lena,Lena,,,,lead,,law:80,
+person,ann,Ann,,,,clerk,,typing,bob
+person,bob,Bob,,,,clerk,,typing,
+resource,ocr,OCR Engine,,claims,,,scanner,ocr,
+";
+
+    fn model() -> OrgModel {
+        OrgModel::from_csv(ORG).unwrap()
+    }
+
+    fn work_item(id: &str, task_id: &str, assignee: &str) -> WorkItem {
+        WorkItem {
+            id: id.to_string(),
+            case_id: "case-1".to_string(),
+            spec_id: WorkflowSpecId::new(),
+            task_id: task_id.to_string(),
+            state: WorkItemState::Assigned,
+            assigned_resource_id: Some(assignee.to_string()),
+            created_at: Utc::now(),
+            completed_at: None,
+            data: serde_json::json!({}),
+            output_contract: None,
+            suspended_from: None,
+        }
+    }
+
+    #[test]
+    fn test_reporting_lines_and_units() {
+        let model = model();
+        assert!(model.is_supervisor_of("lena", "ann"));
+        assert!(model.is_supervisor_of("carol", "lena"));
+        assert!(!model.is_supervisor_of("carol", "ann"));
+        assert!(model.same_unit("ann", "ocr"));
+        assert!(!model.same_unit("ann", "carol"));
+        assert_eq!(
+            model.roles_of("ann").into_iter().collect::<Vec<_>>(),
+            vec!["clerk"]
+        );
+
+        let metadata = model.resource_metadata("carol").unwrap();
+        assert_eq!(metadata.position_level, 2);
+        assert_eq!(metadata.department.as_deref(), Some("hq"));
+        let metadata = model.resource_metadata("ann").unwrap();
+        assert_eq!(metadata.org_groups, vec!["claims", "hq"]);
+    }
+
+    #[test]
+    fn test_validate_rejects_reporting_cycle() {
+        let mut model = model();
+        model
+            .add_position(Position {
+                id: "a".to_string(),
+                name: "A".to_string(),
+                unit: None,
+                reports_to: Some("b".to_string()),
+                roles: vec![],
+            })
+            .unwrap();
+        model
+            .add_position(Position {
+                id: "b".to_string(),
+                name: "B".to_string(),
+                unit: None,
+                reports_to: Some("a".to_string()),
+                roles: vec![],
+            })
+            .unwrap();
+        assert!(model.validate().is_err());
+    }
+
+    #[test]
+    fn test_stand_in_order() {
+        let mut model = model();
+        let now = Utc::now();
+        let absent = |who: &str| Absence {
+            participant: who.to_string(),
+            from: now - Duration::days(1),
+            until: now + Duration::days(1),
+            reason: None,
+        };
+        model.add_absence(absent("ann")).unwrap();
+
+        // Standing deputy
+        assert_eq!(model.stand_in_for("ann", None, now).unwrap().id, "bob");
+
+        // Task-specific delegation wins over the deputy
+        model
+            .add_delegation(Delegation {
+                delegator: "ann".to_string(),
+                delegate: "ocr".to_string(),
+                from: now - Duration::hours(1),
+                until: None,
+                tasks: vec!["scan".to_string()],
+            })
+            .unwrap();
+        assert_eq!(
+            model.stand_in_for("ann", Some("scan"), now).unwrap().id,
+            "ocr"
+        );
+        assert_eq!(
+            model.stand_in_for("ann", Some("review"), now).unwrap().id,
+            "bob"
+        );
+
+        // Absent deputy falls through to the supervisor
+        model.add_absence(absent("bob")).unwrap();
+        assert_eq!(
+            model.stand_in_for("ann", Some("review"), now).unwrap().id,
+            "lena"
+        );
+        assert!(model
+            .stand_in_for("ann", None, now + Duration::days(2))
+            .is_some());
+    }
+
+    #[test]
+    fn test_plan_reroutes() {
+        let mut model = model();
+        let now = Utc::now();
+        model
+            .add_absence(Absence {
+                participant: "ann".to_string(),
+                from: now - Duration::hours(1),
+                until: now + Duration::hours(1),
+                reason: Some("vacation".to_string()),
+            })
+            .unwrap();
+
+        let mut done = work_item("wi-3", "review", "ann");
+        done.state = WorkItemState::Completed;
+        let items = vec![
+            work_item("wi-1", "review", "ann"),
+            work_item("wi-2", "review", "bob"),
+            done,
+        ];
+        let reroutes = model.plan_reroutes(&items, now);
+        assert_eq!(
+            reroutes,
+            vec![Reroute {
+                work_item_id: "wi-1".to_string(),
+                from: "ann".to_string(),
+                to: Some("bob".to_string()),
+            }]
+        );
+        assert!(model
+            .plan_reroutes(&items, now + Duration::hours(2))
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pool_excludes_absent_participants() {
+        let mut model = model();
+        let now = Utc::now();
+        model
+            .add_absence(Absence {
+                participant: "bob".to_string(),
+                from: now - Duration::hours(1),
+                until: now + Duration::hours(1),
+                reason: None,
+            })
+            .unwrap();
+        let resources = model.to_resources(now);
+        assert_eq!(resources.len(), 5);
+        let bob = OrgModel::resource_id("bob");
+        assert!(!resources.iter().find(|r| r.id == bob).unwrap().available);
+        assert_eq!(model.participant_for(bob).unwrap().name, "Bob");
+
+        let pool = OrgResourcePool::new(Arc::new(RwLock::new(model)));
+        let offered = pool.get_all_resources().await;
+        assert_eq!(offered.len(), 4);
+        assert!(offered.iter().all(|r| r.id != bob));
+    }
+
+    #[tokio::test]
+    async fn test_pool_absence_reroutes_queued_items() {
+        let service = Arc::new(WorkItemService::new());
+        let spec_id = WorkflowSpecId::new();
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            ids.push(
+                service
+                    .create_work_item(
+                        "case-1".to_string(),
+                        spec_id.clone(),
+                        "review".to_string(),
+                        serde_json::json!({}),
+                    )
+                    .await
+                    .unwrap(),
+            );
+        }
+        let (allocated, offered) = (&ids[0], &ids[1]);
+        service.assign(allocated, "ann".to_string()).await.unwrap();
+        service
+            .reoffer_work_item(offered, vec!["ann".to_string(), "lena".to_string()])
+            .await
+            .unwrap();
+
+        let allocator = Arc::new(ThreePhaseAllocator::new());
+        let pool = OrgResourcePool::new(Arc::new(RwLock::new(model())))
+            .with_work_items(service.clone())
+            .with_allocator(allocator.clone());
+        let now = Utc::now();
+        let reroutes = pool
+            .add_absence(Absence {
+                participant: "ann".to_string(),
+                from: now - Duration::hours(1),
+                until: now + Duration::hours(1),
+                reason: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(reroutes.len(), 2);
+        assert!(reroutes.iter().all(|r| r.to.as_deref() == Some("bob")));
+
+        let item = service.get_work_item(allocated).await.unwrap();
+        assert_eq!(item.assigned_resource_id.as_deref(), Some("bob"));
+        let item = service.get_work_item(offered).await.unwrap();
+        assert_eq!(item.state, WorkItemState::Assigned);
+        assert_eq!(item.data["offered_to"], serde_json::json!(["bob", "lena"]));
+
+        assert!(allocator.get_resource("ann").await.is_none());
+        assert!(allocator.get_resource("bob").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sync_three_phase() {
+        let mut model = model();
+        let now = Utc::now();
+        let allocator = ThreePhaseAllocator::new();
+        model.sync_three_phase(&allocator, now).await;
+        assert!(allocator.get_resource("ann").await.is_some());
+
+        model
+            .add_absence(Absence {
+                participant: "ann".to_string(),
+                from: now - Duration::hours(1),
+                until: now + Duration::hours(1),
+                reason: None,
+            })
+            .unwrap();
+        model.sync_three_phase(&allocator, now).await;
+        assert!(allocator.get_resource("ann").await.is_none());
+        assert!(allocator.get_resource("bob").await.is_some());
+    }
+}
@@ -0,0 +1,236 @@
+//! Turtle import (W3C Organization Ontology)
+//!
+//! | RDF | org model |
+//! |---|---|
+//! | `org:OrganizationalUnit`, nested with `org:unitOf` / `org:subOrganizationOf` | org unit |
+//! | `org:Post` with `org:postIn`, `org:reportsTo`, `org:role` | position |
+//! | `org:heldBy` / `org:holds` | position holders |
+//! | `foaf:Person` | person |
+//! | `knhk:NonHumanResource` | non-human resource |
+//! | `org:role` on a participant | direct role |
+//! | `knhk:capability` (`"name"` or `"name:level"`), `knhk:deputy`, `knhk:unit` | capabilities, deputy, unit membership |
+//!
+//! Identifiers are the full IRIs; names come from `foaf:name`,
+//! `skos:prefLabel` or `rdfs:label` (roles are named by their label or the
+//! local part of their IRI). `knhk:` is `http://knhk.org/org#`.
+
+use super::{parse_capability, OrgModel, OrgUnit, Participant, Position};
+use crate::error::{WorkflowError, WorkflowResult};
+use oxigraph::io::RdfFormat;
+use oxigraph::model::Term;
+use oxigraph::sparql::{QueryResults, SparqlEvaluator};
+use oxigraph::store::Store;
+use std::collections::HashMap;
+
+const PREFIXES: &str = "\
+PREFIX org: <http://www.w3.org/ns/org#>
+PREFIX foaf: <http://xmlns.com/foaf/0.1/>
+PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+PREFIX skos: <http://www.w3.org/2004/02/skos/core#>
+PREFIX knhk: <http://knhk.org/org#>
+";
+
+impl OrgModel {
+    /// Import an org model from Turtle
+    pub fn from_turtle(turtle: &str) -> WorkflowResult<Self> {
+        let store = Store::new()
+            .map_err(|e| WorkflowError::Internal(format!("Failed to create store: {:?}", e)))?;
+        store
+            .load_from_reader(RdfFormat::Turtle, turtle.as_bytes())
+            .map_err(|e| {
+                WorkflowError::InvalidSpecification(format!("Invalid org model Turtle: {}", e))
+            })?;
+
+        let mut model = OrgModel::new();
+        for row in select(
+            &store,
+            "SELECT ?u (SAMPLE(?l) AS ?label) (SAMPLE(?p) AS ?parent) WHERE {
+                ?u a org:OrganizationalUnit .
+                OPTIONAL { ?u rdfs:label|skos:prefLabel ?l }
+                OPTIONAL { ?u org:unitOf|org:subOrganizationOf ?p . ?p a org:OrganizationalUnit }
+            } GROUP BY ?u",
+        )? {
+            let id = row["u"].clone();
+            model.add_unit(OrgUnit {
+                name: row.get("label").cloned().unwrap_or_else(|| id.clone()),
+                parent: row.get("parent").cloned(),
+                id,
+            })?;
+        }
+
+        for row in select(
+            &store,
+            "SELECT ?p (SAMPLE(?l) AS ?label) (SAMPLE(?u) AS ?unit) (SAMPLE(?b) AS ?boss) WHERE {
+                ?p a org:Post .
+                OPTIONAL { ?p rdfs:label|skos:prefLabel ?l }
+                OPTIONAL { ?p org:postIn ?u }
+                OPTIONAL { ?p org:reportsTo ?b }
+            } GROUP BY ?p",
+        )? {
+            let id = row["p"].clone();
+            model.add_position(Position {
+                name: row.get("label").cloned().unwrap_or_else(|| id.clone()),
+                unit: row.get("unit").cloned(),
+                reports_to: row.get("boss").cloned(),
+                roles: Vec::new(),
+                id,
+            })?;
+        }
+
+        for row in select(
+            &store,
+            "SELECT ?x (SAMPLE(?n) AS ?name) (SAMPLE(?u) AS ?unit) (SAMPLE(?d) AS ?deputy)
+                    (SAMPLE(?nonHuman) AS ?machine) WHERE {
+                { ?x a foaf:Person } UNION { ?x a knhk:NonHumanResource . BIND(true AS ?nonHuman) }
+                OPTIONAL { ?x foaf:name|skos:prefLabel|rdfs:label ?n }
+                OPTIONAL { ?x knhk:unit ?u }
+                OPTIONAL { ?x knhk:deputy ?d }
+            } GROUP BY ?x",
+        )? {
+            let id = row["x"].clone();
+            let name = row.get("name").cloned().unwrap_or_else(|| id.clone());
+            let mut participant = if row.contains_key("machine") {
+                Participant::non_human(id, name)
+            } else {
+                Participant::human(id, name)
+            };
+            participant.unit = row.get("unit").cloned();
+            participant.deputy = row.get("deputy").cloned();
+            model.add_participant(participant)?;
+        }
+
+        for row in select(
+            &store,
+            "SELECT DISTINCT ?x ?p WHERE { { ?p org:heldBy ?x } UNION { ?x org:holds ?p } }",
+        )? {
+            if let Some(p) = model.participants.get_mut(&row["x"]) {
+                p.positions.push(row["p"].clone());
+            }
+        }
+
+        for row in select(
+            &store,
+            "SELECT ?s ?r (SAMPLE(?l) AS ?label) WHERE {
+                ?s org:role ?r .
+                OPTIONAL { ?r rdfs:label|skos:prefLabel ?l }
+            } GROUP BY ?s ?r",
+        )? {
+            let role = row
+                .get("label")
+                .cloned()
+                .unwrap_or_else(|| local_name(&row["r"]));
+            if let Some(p) = model.positions.get_mut(&row["s"]) {
+                p.roles.push(role);
+            } else if let Some(p) = model.participants.get_mut(&row["s"]) {
+                p.roles.push(role);
+            }
+        }
+
+        for row in select(&store, "SELECT ?x ?c WHERE { ?x knhk:capability ?c }")? {
+            let capability = parse_capability(&row["c"])?;
+            if let Some(p) = model.participants.get_mut(&row["x"]) {
+                p.capabilities.push(capability);
+            }
+        }
+
+        for participant in model.participants.values_mut() {
+            participant.positions.sort();
+            participant.roles.sort();
+        }
+        model.validate()?;
+        Ok(model)
+    }
+}
+
+/// Run a SELECT query and return each solution as variable → value
+fn select(store: &Store, query: &str) -> WorkflowResult<Vec<HashMap<String, String>>> {
+    let results = SparqlEvaluator::new()
+        .parse_query(&format!("{}{}", PREFIXES, query))
+        .map_err(|e| WorkflowError::Internal(format!("Failed to parse SPARQL query: {:?}", e)))?
+        .on_store(store)
+        .execute()
+        .map_err(|e| WorkflowError::Internal(format!("SPARQL query failed: {:?}", e)))?;
+    let mut rows = Vec::new();
+    if let QueryResults::Solutions(solutions) = results {
+        for solution in solutions {
+            let solution = solution
+                .map_err(|e| WorkflowError::Internal(format!("SPARQL query failed: {:?}", e)))?;
+            rows.push(
+                solution
+                    .iter()
+                    .map(|(variable, term)| (variable.as_str().to_string(), term_value(term)))
+                    .collect(),
+            );
+        }
+    }
+    Ok(rows)
+}
+
+fn term_value(term: &Term) -> String {
+    match term {
+        Term::NamedNode(node) => node.as_str().to_string(),
+        Term::BlankNode(node) => format!("_:{}", node.as_str()),
+        Term::Literal(literal) => literal.value().to_string(),
+        #[allow(unreachable_patterns)]
+        _ => term.to_string(),
+    }
+}
+
+fn local_name(iri: &str) -> String {
+    iri.rsplit(['#', '/', ':'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(iri)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resourcing::org_model::ParticipantKind;
+
+    const ORG: &str = r#"
+@prefix org: <http://www.w3.org/ns/org#> .
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix knhk: <http://knhk.org/org#> .
+@prefix ex: <http://example.org/> .
+
+ex:hq a org:OrganizationalUnit ; rdfs:label "Headquarters" .
+ex:claims a org:OrganizationalUnit ; org:unitOf ex:hq .
+
+ex:lead a org:Post ; rdfs:label "Claims Lead" ; org:postIn ex:claims ;
+    org:role [ rdfs:label "approver" ] .
+ex:clerk a org:Post ; org:postIn ex:claims ; org:reportsTo ex:lead ;
+    org:role ex:Clerk .
+
+ex:lena a foaf:Person ; foaf:name "Lena" ; org:holds ex:lead .
+ex:ann a foaf:Person ; foaf:name "Ann" ; knhk:capability "typing:70" ;
+    knhk:deputy ex:lena .
+ex:clerk org:heldBy ex:ann .
+ex:ocr a knhk:NonHumanResource ; knhk:unit ex:claims ; knhk:capability "ocr" .
+"#;
+
+    #[test]
+    fn test_import_org_ontology() {
+        let model = OrgModel::from_turtle(ORG).unwrap();
+        let ann = "http://example.org/ann";
+        let lena = "http://example.org/lena";
+
+        let claims = model.unit("http://example.org/claims").unwrap();
+        assert_eq!(claims.parent.as_deref(), Some("http://example.org/hq"));
+        assert!(model.is_supervisor_of(lena, ann));
+        assert!(model.same_unit(ann, "http://example.org/ocr"));
+        assert!(model.roles_of(lena).contains("approver"));
+        assert!(model.roles_of(ann).contains("Clerk"));
+
+        let participant = model.participant(ann).unwrap();
+        assert_eq!(participant.name, "Ann");
+        assert_eq!(participant.deputy.as_deref(), Some(lena));
+        assert_eq!(participant.capabilities[0].level, 70);
+        assert_eq!(
+            model.participant("http://example.org/ocr").unwrap().kind,
+            ParticipantKind::NonHuman
+        );
+    }
+}
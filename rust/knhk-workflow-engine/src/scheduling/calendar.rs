@@ -7,7 +7,7 @@
 
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::resource::allocation::types::ResourceId;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -49,7 +49,7 @@ pub struct ResourceCalendar {
 }
 
 /// Working hours pattern
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct WorkingHours {
     /// Start hour (0-23)
     pub start_hour: u8,
@@ -69,6 +69,34 @@ impl Default for WorkingHours {
     }
 }
 
+impl WorkingHours {
+    /// Whether `time` falls inside the working hours pattern
+    pub fn is_working_time(&self, time: DateTime<Utc>) -> bool {
+        let hour = time.hour();
+        let weekday = time.weekday().num_days_from_sunday() as u8;
+
+        hour >= u32::from(self.start_hour)
+            && hour < u32::from(self.end_hour)
+            && self.working_days.contains(&weekday)
+    }
+
+    /// Earliest working time at or after `from` (`None` if the pattern has no working time)
+    pub fn next_working_time(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.is_working_time(from) {
+            return Some(from);
+        }
+        let mut hour = from.duration_trunc(Duration::hours(1)).ok()?;
+        // One week covers every weekday/hour combination
+        for _ in 0..(7 * 24) {
+            hour = hour + Duration::hours(1);
+            if self.is_working_time(hour) {
+                return Some(hour);
+            }
+        }
+        None
+    }
+}
+
 impl ResourceCalendar {
     /// Create a new resource calendar
     pub fn new(resource_id: ResourceId) -> Self {
@@ -97,12 +125,7 @@ impl ResourceCalendar {
         }
 
         // Check working hours pattern
-        let hour = time.hour();
-        let weekday = time.weekday().num_days_from_sunday() as u8;
-
-        hour >= working_hours.start_hour
-            && hour < working_hours.end_hour
-            && working_hours.working_days.contains(&weekday)
+        working_hours.is_working_time(time)
     }
 
     /// Add calendar entry
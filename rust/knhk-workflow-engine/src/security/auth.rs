@@ -4,6 +4,8 @@ use crate::error::{WorkflowError, WorkflowResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::jwt::JwtValidator;
+
 /// Principal (user/service) identity
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Principal {
@@ -16,6 +18,24 @@ pub struct Principal {
     pub attributes: HashMap<String, String>,
 }
 
+impl Principal {
+    /// Roles of the principal (`role` and the comma-separated `roles` attribute)
+    pub fn roles(&self) -> Vec<&str> {
+        let mut roles: Vec<&str> = self
+            .attributes
+            .get("roles")
+            .map(|roles| roles.split(',').map(str::trim).collect())
+            .unwrap_or_default();
+        if let Some(role) = self.attributes.get("role") {
+            if !roles.contains(&role.as_str()) {
+                roles.push(role);
+            }
+        }
+        roles.retain(|role| !role.is_empty());
+        roles
+    }
+}
+
 /// Principal type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PrincipalType {
@@ -63,6 +83,10 @@ pub enum Action {
     CancelCase,
     /// Execute pattern
     ExecutePattern,
+    /// Inspect, edit, redrive and purge dead letter entries
+    ManageDeadLetters,
+    /// Publish messages to message-catch and message-start tasks
+    PublishMessage,
 }
 
 /// Authentication and authorization manager
@@ -71,6 +95,8 @@ pub struct AuthManager {
     principals: HashMap<String, Principal>,
     /// Authorization policies
     policies: Vec<AuthPolicy>,
+    /// Bearer-token validator
+    jwt_validator: Option<JwtValidator>,
 }
 
 impl AuthManager {
@@ -79,95 +105,45 @@ impl AuthManager {
         Self {
             principals: HashMap::new(),
             policies: Vec::new(),
+            jwt_validator: None,
         }
     }
 
-    /// Authenticate a principal
-    ///
-    /// Supports SPIFFE/SPIRE authentication by validating SPIFFE ID format
-    /// and extracting principal information from the SPIFFE ID.
+    /// Authenticate a bearer token
     ///
-    /// The token parameter can be:
-    /// - A SPIFFE ID (spiffe://trust-domain/path) - validated and used directly
-    /// - A certificate or certificate path - would require SPIFFE Workload API client
-    ///
-    /// For now, implements SPIFFE ID validation and extraction.
-    /// Full SPIFFE/SPIRE integration with Workload API would require additional dependencies.
+    /// Tokens must be JWTs signed by a key of the configured JWKS (RS256, ES256
+    /// or EdDSA) with the expected issuer and audience and an unexpired `exp`.
+    /// Attributes of a registered principal with the token subject (e.g. locally
+    /// assigned roles) are added to the authenticated principal. Without a JWT
+    /// validator every token is rejected.
     pub fn authenticate(&self, token: &str) -> WorkflowResult<Principal> {
-        // Check if token is a SPIFFE ID
-        if token.starts_with("spiffe://") {
-            return self.authenticate_spiffe_id(token);
-        }
-
-        // Check if token is a registered principal ID
-        if let Some(principal) = self.principals.get(token) {
-            return Ok(principal.clone());
-        }
-
-        // Token format not recognized
-        Err(WorkflowError::Validation(format!(
-            "Invalid authentication token format. Expected SPIFFE ID (spiffe://trust-domain/path) or registered principal ID, got: '{}'",
-            token
-        )))
-    }
-
-    /// Authenticate using SPIFFE ID
-    ///
-    /// Validates SPIFFE ID format and extracts principal information.
-    fn authenticate_spiffe_id(&self, spiffe_id: &str) -> WorkflowResult<Principal> {
-        // Validate SPIFFE ID format: spiffe://trust-domain/path
-        if !spiffe_id.starts_with("spiffe://") || spiffe_id.len() <= 10 {
-            return Err(WorkflowError::Validation(format!(
-                "Invalid SPIFFE ID format: '{}'. Expected format: spiffe://trust-domain/path",
-                spiffe_id
-            )));
-        }
-
-        // Extract trust domain and path
-        let without_prefix = &spiffe_id[9..]; // Remove "spiffe://"
-        let (trust_domain, path) = if let Some(slash_pos) = without_prefix.find('/') {
-            (
-                &without_prefix[..slash_pos],
-                &without_prefix[slash_pos + 1..],
+        let validator = self.jwt_validator.as_ref().ok_or_else(|| {
+            WorkflowError::Validation(
+                "Bearer-token authentication is not configured (no JWT validator)".to_string(),
             )
-        } else {
-            (without_prefix, "")
-        };
-
-        if trust_domain.is_empty() {
-            return Err(WorkflowError::Validation(format!(
-                "Invalid SPIFFE ID: trust domain cannot be empty in '{}'",
-                spiffe_id
-            )));
-        }
-
-        // Extract attributes from SPIFFE ID path
-        let mut attributes = HashMap::new();
-        attributes.insert("trust_domain".to_string(), trust_domain.to_string());
-        if !path.is_empty() {
-            attributes.insert("spiffe_path".to_string(), path.to_string());
-            // Extract service name from path (last component)
-            if let Some(service_name) = path.split('/').next_back() {
-                if !service_name.is_empty() {
-                    attributes.insert("service".to_string(), service_name.to_string());
+        })?;
+        let mut principal = validator.validate(token)?;
+
+        if let Some(registered) = self.principals.get(&principal.id) {
+            let mut roles: Vec<String> = principal.roles().iter().map(|r| r.to_string()).collect();
+            for role in registered.roles() {
+                if !roles.iter().any(|r| r == role) {
+                    roles.push(role.to_string());
                 }
             }
+            for (name, value) in &registered.attributes {
+                principal
+                    .attributes
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+            if !roles.is_empty() {
+                principal
+                    .attributes
+                    .insert("roles".to_string(), roles.join(","));
+            }
         }
-
-        // Determine principal type from SPIFFE ID path
-        let principal_type = if path.starts_with("user/") || path.contains("/user/") {
-            PrincipalType::User
-        } else if path.starts_with("system/") || path.contains("/system/") {
-            PrincipalType::System
-        } else {
-            PrincipalType::Service // Default for SPIFFE IDs
-        };
-
-        Ok(Principal {
-            id: spiffe_id.to_string(),
-            principal_type,
-            attributes,
-        })
+        Ok(principal)
     }
 
     /// Authorize an action
@@ -183,10 +159,9 @@ impl AuthManager {
                 // Check if principal matches
                 if policy.allowed_principals.contains(&principal.id)
                     || principal
-                        .attributes
-                        .get("role")
-                        .map(|role| policy.allowed_roles.contains(role))
-                        .unwrap_or(false)
+                        .roles()
+                        .iter()
+                        .any(|role| policy.allowed_roles.iter().any(|allowed| allowed == role))
                 {
                     // Check resource pattern
                     if policy.resource_patterns.is_empty()
@@ -213,9 +188,22 @@ impl AuthManager {
     }
 
     /// Register a principal
+    ///
+    /// Registered principals are not credentials: their attributes are added
+    /// to authenticated tokens with the same subject.
     pub fn register_principal(&mut self, principal: Principal) {
         self.principals.insert(principal.id.clone(), principal);
     }
+
+    /// Set the validator for JWT bearer tokens
+    pub fn set_jwt_validator(&mut self, validator: JwtValidator) {
+        self.jwt_validator = Some(validator);
+    }
+
+    /// Validator for JWT bearer tokens, if configured
+    pub fn jwt_validator(&self) -> Option<&JwtValidator> {
+        self.jwt_validator.as_ref()
+    }
 }
 
 impl Default for AuthManager {
@@ -224,6 +212,66 @@ impl Default for AuthManager {
     }
 }
 
+/// Build a principal from a SPIFFE ID (e.g. the subject of a JWT-SVID)
+///
+/// Extracts the trust domain, path and service name as attributes and derives
+/// the principal type from the path.
+pub fn principal_from_spiffe_id(spiffe_id: &str) -> WorkflowResult<Principal> {
+    // Validate SPIFFE ID format: spiffe://trust-domain/path
+    if !spiffe_id.starts_with("spiffe://") || spiffe_id.len() <= 10 {
+        return Err(WorkflowError::Validation(format!(
+            "Invalid SPIFFE ID format: '{}'. Expected format: spiffe://trust-domain/path",
+            spiffe_id
+        )));
+    }
+
+    // Extract trust domain and path
+    let without_prefix = &spiffe_id[9..]; // Remove "spiffe://"
+    let (trust_domain, path) = if let Some(slash_pos) = without_prefix.find('/') {
+        (
+            &without_prefix[..slash_pos],
+            &without_prefix[slash_pos + 1..],
+        )
+    } else {
+        (without_prefix, "")
+    };
+
+    if trust_domain.is_empty() {
+        return Err(WorkflowError::Validation(format!(
+            "Invalid SPIFFE ID: trust domain cannot be empty in '{}'",
+            spiffe_id
+        )));
+    }
+
+    // Extract attributes from SPIFFE ID path
+    let mut attributes = HashMap::new();
+    attributes.insert("trust_domain".to_string(), trust_domain.to_string());
+    if !path.is_empty() {
+        attributes.insert("spiffe_path".to_string(), path.to_string());
+        // Extract service name from path (last component)
+        if let Some(service_name) = path.split('/').next_back() {
+            if !service_name.is_empty() {
+                attributes.insert("service".to_string(), service_name.to_string());
+            }
+        }
+    }
+
+    // Determine principal type from SPIFFE ID path
+    let principal_type = if path.starts_with("user/") || path.contains("/user/") {
+        PrincipalType::User
+    } else if path.starts_with("system/") || path.contains("/system/") {
+        PrincipalType::System
+    } else {
+        PrincipalType::Service // Default for SPIFFE IDs
+    };
+
+    Ok(Principal {
+        id: spiffe_id.to_string(),
+        principal_type,
+        attributes,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,13 +310,10 @@ mod tests {
     }
 
     #[test]
-    fn test_authenticate_spiffe_id() {
-        let manager = AuthManager::new();
-
+    fn test_principal_from_spiffe_id() {
         // Valid SPIFFE ID
-        let principal = manager
-            .authenticate("spiffe://example.com/workflow-engine")
-            .expect("should authenticate SPIFFE ID");
+        let principal = principal_from_spiffe_id("spiffe://example.com/workflow-engine")
+            .expect("should parse SPIFFE ID");
         assert_eq!(principal.id, "spiffe://example.com/workflow-engine");
         assert_eq!(principal.principal_type, PrincipalType::Service);
         assert_eq!(
@@ -281,25 +326,23 @@ mod tests {
         );
 
         // SPIFFE ID with user path
-        let principal = manager
-            .authenticate("spiffe://example.com/user/admin")
-            .expect("should authenticate SPIFFE ID");
+        let principal =
+            principal_from_spiffe_id("spiffe://example.com/user/admin").expect("should parse");
         assert_eq!(principal.principal_type, PrincipalType::User);
 
         // SPIFFE ID with system path
-        let principal = manager
-            .authenticate("spiffe://example.com/system/knhk")
-            .expect("should authenticate SPIFFE ID");
+        let principal =
+            principal_from_spiffe_id("spiffe://example.com/system/knhk").expect("should parse");
         assert_eq!(principal.principal_type, PrincipalType::System);
 
         // Invalid SPIFFE ID
-        assert!(manager.authenticate("invalid").is_err());
-        assert!(manager.authenticate("spiffe://").is_err());
-        assert!(manager.authenticate("spiffe://example.com").is_ok()); // Valid, empty path
+        assert!(principal_from_spiffe_id("invalid").is_err());
+        assert!(principal_from_spiffe_id("spiffe://").is_err());
+        assert!(principal_from_spiffe_id("spiffe://example.com").is_ok()); // Valid, empty path
     }
 
     #[test]
-    fn test_authenticate_registered_principal() {
+    fn test_identifiers_are_not_tokens() {
         let mut manager = AuthManager::new();
         let principal = Principal {
             id: "test-service".to_string(),
@@ -307,11 +350,12 @@ mod tests {
             attributes: HashMap::new(),
         };
 
-        manager.register_principal(principal.clone());
+        manager.register_principal(principal);
 
-        let authenticated = manager
-            .authenticate("test-service")
-            .expect("should authenticate registered principal");
-        assert_eq!(authenticated.id, "test-service");
+        // Neither a SPIFFE ID nor a registered principal ID authenticates
+        assert!(manager
+            .authenticate("spiffe://example.com/workflow-engine")
+            .is_err());
+        assert!(manager.authenticate("test-service").is_err());
     }
 }
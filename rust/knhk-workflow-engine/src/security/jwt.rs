@@ -0,0 +1,672 @@
+//! JWT bearer-token validation
+//!
+//! Validates signed JWTs (RS256, ES256, EdDSA) against the keys of a local JWKS
+//! file. The key set is cached in memory; `spawn_key_refresh` re-reads the file
+//! on an interval so keys can be rotated by replacing it, and a file that is
+//! missing or invalid during rotation keeps the last good keys in use. Issuer,
+//! audience, expiry and not-before are checked; configured claim values are
+//! mapped to engine roles.
+
+use crate::error::{WorkflowError, WorkflowResult};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use parking_lot::RwLock;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::auth::{principal_from_spiffe_id, Principal, PrincipalType};
+
+/// Default clock skew allowed for `exp` and `nbf`
+const DEFAULT_LEEWAY: Duration = Duration::from_secs(60);
+
+/// Signature algorithm of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    /// RSASSA-PKCS1-v1_5 with SHA-256
+    RS256,
+    /// ECDSA P-256 with SHA-256
+    ES256,
+    /// Ed25519
+    EdDSA,
+}
+
+impl JwtAlgorithm {
+    fn from_header(alg: &str) -> WorkflowResult<Self> {
+        match alg {
+            "RS256" => Ok(Self::RS256),
+            "ES256" => Ok(Self::ES256),
+            "EdDSA" => Ok(Self::EdDSA),
+            other => Err(WorkflowError::Validation(format!(
+                "Unsupported token algorithm '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Maps a token claim value to an engine role
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimRoleMapping {
+    /// Claim path (dot-separated for nested claims, e.g. `realm_access.roles`)
+    pub claim: String,
+    /// Claim value (or array element) to match
+    pub value: String,
+    /// Engine role granted on a match
+    pub role: String,
+}
+
+impl ClaimRoleMapping {
+    /// Create a claim-to-role mapping
+    pub fn new(
+        claim: impl Into<String>,
+        value: impl Into<String>,
+        role: impl Into<String>,
+    ) -> Self {
+        Self {
+            claim: claim.into(),
+            value: value.into(),
+            role: role.into(),
+        }
+    }
+}
+
+/// JWT validation settings
+#[derive(Debug, Clone)]
+pub struct JwtConfig {
+    /// Expected `iss` claim
+    pub issuer: String,
+    /// Audience the `aud` claim must contain
+    pub audience: String,
+    /// Local JWKS file
+    pub jwks_path: PathBuf,
+    /// Allowed clock skew for `exp` and `nbf`
+    pub leeway: Duration,
+    /// Claim-to-role mappings
+    pub role_mappings: Vec<ClaimRoleMapping>,
+}
+
+impl JwtConfig {
+    /// Create settings for an issuer, audience and JWKS file
+    pub fn new(
+        issuer: impl Into<String>,
+        audience: impl Into<String>,
+        jwks_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            issuer: issuer.into(),
+            audience: audience.into(),
+            jwks_path: jwks_path.into(),
+            leeway: DEFAULT_LEEWAY,
+            role_mappings: Vec::new(),
+        }
+    }
+
+    /// Set the allowed clock skew
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Add a claim-to-role mapping
+    pub fn with_role_mapping(mut self, mapping: ClaimRoleMapping) -> Self {
+        self.role_mappings.push(mapping);
+        self
+    }
+}
+
+/// Public key of the JWKS
+#[derive(Debug, Clone)]
+enum VerifyingKey {
+    Rsa { n: Vec<u8>, e: Vec<u8> },
+    EcP256 { point: Vec<u8> },
+    Ed25519 { x: Vec<u8> },
+}
+
+impl VerifyingKey {
+    fn algorithm(&self) -> JwtAlgorithm {
+        match self {
+            Self::Rsa { .. } => JwtAlgorithm::RS256,
+            Self::EcP256 { .. } => JwtAlgorithm::ES256,
+            Self::Ed25519 { .. } => JwtAlgorithm::EdDSA,
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Rsa { n, e } => RsaPublicKeyComponents { n, e }
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, signature)
+                .is_ok(),
+            Self::EcP256 { point } => {
+                UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point)
+                    .verify(message, signature)
+                    .is_ok()
+            }
+            Self::Ed25519 { x } => UnparsedPublicKey::new(&signature::ED25519, x)
+                .verify(message, signature)
+                .is_ok(),
+        }
+    }
+}
+
+/// JSON Web Key (the members used for signature verification)
+#[derive(Debug, Deserialize)]
+struct Jwk {
+    kty: String,
+    #[serde(default)]
+    kid: Option<String>,
+    #[serde(default, rename = "use")]
+    key_use: Option<String>,
+    #[serde(default)]
+    crv: Option<String>,
+    #[serde(default)]
+    n: Option<String>,
+    #[serde(default)]
+    e: Option<String>,
+    #[serde(default)]
+    x: Option<String>,
+    #[serde(default)]
+    y: Option<String>,
+}
+
+impl Jwk {
+    fn verifying_key(&self) -> WorkflowResult<Option<VerifyingKey>> {
+        if self.key_use.as_deref().is_some_and(|u| u != "sig") {
+            return Ok(None);
+        }
+        let member = |value: &Option<String>, name: &str| {
+            let value = value.as_deref().ok_or_else(|| {
+                WorkflowError::Validation(format!("JWK {} key is missing '{}'", self.kty, name))
+            })?;
+            decode_segment(value)
+        };
+        let key = match (self.kty.as_str(), self.crv.as_deref()) {
+            ("RSA", _) => VerifyingKey::Rsa {
+                n: member(&self.n, "n")?,
+                e: member(&self.e, "e")?,
+            },
+            ("EC", Some("P-256")) => {
+                let mut point = vec![0x04];
+                point.extend(member(&self.x, "x")?);
+                point.extend(member(&self.y, "y")?);
+                VerifyingKey::EcP256 { point }
+            }
+            ("OKP", Some("Ed25519")) => VerifyingKey::Ed25519 {
+                x: member(&self.x, "x")?,
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(key))
+    }
+}
+
+/// JSON Web Key Set file
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+/// Loaded key set and the content it was read from
+struct KeySet {
+    keys: HashMap<String, VerifyingKey>,
+    /// Hash of the JWKS file content
+    fingerprint: u64,
+}
+
+impl KeySet {
+    /// Read and parse a JWKS file
+    fn load(path: &Path) -> WorkflowResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            WorkflowError::Io(format!("Failed to read JWKS {}: {}", path.display(), e))
+        })?;
+        let jwks: JwkSet = serde_json::from_str(&content)
+            .map_err(|e| WorkflowError::Parse(format!("Invalid JWKS {}: {}", path.display(), e)))?;
+        let mut keys = HashMap::new();
+        for jwk in &jwks.keys {
+            if let Some(key) = jwk.verifying_key()? {
+                keys.insert(jwk.kid.clone().unwrap_or_default(), key);
+            }
+        }
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Ok(Self {
+            keys,
+            fingerprint: hasher.finish(),
+        })
+    }
+}
+
+/// JWT validator backed by a local JWKS file
+pub struct JwtValidator {
+    config: JwtConfig,
+    key_set: Arc<RwLock<KeySet>>,
+}
+
+impl JwtValidator {
+    /// Create a validator and load the key set
+    ///
+    /// Fails if the JWKS file cannot be read, so a server never starts
+    /// without keys.
+    pub fn new(config: JwtConfig) -> WorkflowResult<Self> {
+        let key_set = KeySet::load(&config.jwks_path)?;
+        tracing::debug!(
+            "Loaded {} signing keys from {}",
+            key_set.keys.len(),
+            config.jwks_path.display()
+        );
+        Ok(Self {
+            config,
+            key_set: Arc::new(RwLock::new(key_set)),
+        })
+    }
+
+    /// Validation settings
+    pub fn config(&self) -> &JwtConfig {
+        &self.config
+    }
+
+    /// Validate a token and build the authenticated principal
+    pub fn validate(&self, token: &str) -> WorkflowResult<Principal> {
+        let mut segments = token.split('.');
+        let (header, payload, signature) = match (
+            segments.next(),
+            segments.next(),
+            segments.next(),
+            segments.next(),
+        ) {
+            (Some(h), Some(p), Some(s), None) => (h, p, s),
+            _ => {
+                return Err(WorkflowError::Validation(
+                    "Token is not a JWT (header.payload.signature)".to_string(),
+                ))
+            }
+        };
+
+        let header: Value = decode_json(header, "header")?;
+        let algorithm = JwtAlgorithm::from_header(header["alg"].as_str().unwrap_or("none"))?;
+        let key = self.key(header["kid"].as_str())?;
+        if key.algorithm() != algorithm {
+            return Err(WorkflowError::Validation(format!(
+                "Token algorithm {:?} does not match its key ({:?})",
+                algorithm,
+                key.algorithm()
+            )));
+        }
+        let signed = &token[..token.len() - signature.len() - 1];
+        if !key.verify(signed.as_bytes(), &decode_segment(signature)?) {
+            return Err(WorkflowError::Validation(
+                "Invalid token signature".to_string(),
+            ));
+        }
+
+        let claims: Value = decode_json(payload, "payload")?;
+        self.check_claims(&claims)?;
+        self.principal(&claims)
+    }
+
+    /// Check issuer, audience, expiry and not-before
+    fn check_claims(&self, claims: &Value) -> WorkflowResult<()> {
+        if claims["iss"].as_str() != Some(self.config.issuer.as_str()) {
+            return Err(WorkflowError::Validation(format!(
+                "Token issuer {} is not trusted",
+                claims["iss"]
+            )));
+        }
+        let audience_ok = match &claims["aud"] {
+            Value::String(aud) => *aud == self.config.audience,
+            Value::Array(auds) => auds
+                .iter()
+                .any(|aud| aud.as_str() == Some(self.config.audience.as_str())),
+            _ => false,
+        };
+        if !audience_ok {
+            return Err(WorkflowError::Validation(format!(
+                "Token is not intended for audience {}",
+                self.config.audience
+            )));
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let leeway = self.config.leeway.as_secs() as i64;
+        let exp = claims["exp"]
+            .as_i64()
+            .ok_or_else(|| WorkflowError::Validation("Token has no expiry (exp)".to_string()))?;
+        if exp + leeway < now {
+            return Err(WorkflowError::Validation("Token has expired".to_string()));
+        }
+        if let Some(nbf) = claims["nbf"].as_i64() {
+            if nbf - leeway > now {
+                return Err(WorkflowError::Validation(
+                    "Token is not valid yet".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the principal of validated claims
+    ///
+    /// SPIFFE subjects (JWT-SVIDs) keep the attributes of their SPIFFE ID;
+    /// client-credentials tokens (`azp` or `client_id` equal to `sub`) are services.
+    fn principal(&self, claims: &Value) -> WorkflowResult<Principal> {
+        let subject = claims["sub"]
+            .as_str()
+            .filter(|sub| !sub.is_empty())
+            .ok_or_else(|| WorkflowError::Validation("Token has no subject (sub)".to_string()))?;
+
+        let mut principal = if subject.starts_with("spiffe://") {
+            principal_from_spiffe_id(subject)?
+        } else {
+            let client = claims["azp"].as_str().or(claims["client_id"].as_str());
+            Principal {
+                id: subject.to_string(),
+                principal_type: if client == Some(subject) {
+                    PrincipalType::Service
+                } else {
+                    PrincipalType::User
+                },
+                attributes: HashMap::new(),
+            }
+        };
+        principal
+            .attributes
+            .insert("issuer".to_string(), self.config.issuer.clone());
+
+        let roles: BTreeSet<&str> = self
+            .config
+            .role_mappings
+            .iter()
+            .filter(|mapping| claim_contains(claims, &mapping.claim, &mapping.value))
+            .map(|mapping| mapping.role.as_str())
+            .collect();
+        if let Some(role) = roles.iter().next() {
+            principal
+                .attributes
+                .insert("role".to_string(), role.to_string());
+            let roles: Vec<&str> = roles.into_iter().collect();
+            principal
+                .attributes
+                .insert("roles".to_string(), roles.join(","));
+        }
+        Ok(principal)
+    }
+
+    /// Key for a key ID from the cached key set
+    fn key(&self, kid: Option<&str>) -> WorkflowResult<VerifyingKey> {
+        let key_set = self.key_set.read();
+        let key = match kid {
+            Some(kid) => key_set.keys.get(kid),
+            None if key_set.keys.len() == 1 => key_set.keys.values().next(),
+            None => None,
+        };
+        key.cloned().ok_or_else(|| {
+            WorkflowError::Validation(format!(
+                "No signing key {} in {}",
+                kid.unwrap_or("(token has no kid)"),
+                self.config.jwks_path.display()
+            ))
+        })
+    }
+
+    /// Re-read the JWKS file, replacing the cached keys if it changed
+    ///
+    /// Returns whether the keys changed. On error the previous keys stay in
+    /// use. This blocks on file I/O; servers use `spawn_key_refresh`.
+    pub fn reload_keys(&self) -> WorkflowResult<bool> {
+        let key_set = KeySet::load(&self.config.jwks_path)?;
+        Ok(replace_key_set(
+            &self.key_set,
+            key_set,
+            &self.config.jwks_path,
+        ))
+    }
+
+    /// Re-read the JWKS file every `interval` on the blocking thread pool
+    ///
+    /// Failed reloads are logged and keep the last good keys.
+    pub fn spawn_key_refresh(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let key_set = self.key_set.clone();
+        let path = self.config.jwks_path.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately; the keys were loaded in `new`
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let load_path = path.clone();
+                match tokio::task::spawn_blocking(move || KeySet::load(&load_path)).await {
+                    Ok(Ok(loaded)) => {
+                        replace_key_set(&key_set, loaded, &path);
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!(error = %e, "JWKS reload failed, keeping previous keys")
+                    }
+                    Err(e) => tracing::warn!(error = %e, "JWKS reload task failed"),
+                }
+            }
+        })
+    }
+}
+
+/// Swap in a newly loaded key set if its content differs
+fn replace_key_set(current: &RwLock<KeySet>, loaded: KeySet, path: &Path) -> bool {
+    if current.read().fingerprint == loaded.fingerprint {
+        return false;
+    }
+    tracing::info!(
+        "Reloaded {} signing keys from {}",
+        loaded.keys.len(),
+        path.display()
+    );
+    *current.write() = loaded;
+    true
+}
+
+/// Decode a base64url token segment
+fn decode_segment(segment: &str) -> WorkflowResult<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|e| WorkflowError::Validation(format!("Invalid base64url in token: {}", e)))
+}
+
+/// Decode a base64url JSON token segment
+fn decode_json(segment: &str, part: &str) -> WorkflowResult<Value> {
+    serde_json::from_slice(&decode_segment(segment)?)
+        .map_err(|e| WorkflowError::Validation(format!("Invalid token {}: {}", part, e)))
+}
+
+/// Check whether a claim (string or array, dot-separated path) contains a value
+fn claim_contains(claims: &Value, path: &str, expected: &str) -> bool {
+    let claim = path
+        .split('.')
+        .try_fold(claims, |value, name| value.get(name));
+    match claim {
+        Some(Value::String(value)) => value == expected,
+        Some(Value::Array(values)) => values.iter().any(|v| v.as_str() == Some(expected)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, Ed25519KeyPair, KeyPair};
+    use tempfile::TempDir;
+
+    const ISSUER: &str = "https://idp.example.com";
+    const AUDIENCE: &str = "knhk-workflow-engine";
+
+    fn b64(bytes: &[u8]) -> String {
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    fn ed25519_key() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    fn sign_ed25519(key: &Ed25519KeyPair, kid: &str, claims: &Value) -> String {
+        let header = serde_json::json!({"alg": "EdDSA", "typ": "JWT", "kid": kid});
+        let signed = format!(
+            "{}.{}",
+            b64(header.to_string().as_bytes()),
+            b64(claims.to_string().as_bytes())
+        );
+        format!("{}.{}", signed, b64(key.sign(signed.as_bytes()).as_ref()))
+    }
+
+    fn write_jwks(dir: &TempDir, keys: Value) -> PathBuf {
+        let path = dir.path().join("jwks.json");
+        std::fs::write(&path, serde_json::json!({ "keys": keys }).to_string()).unwrap();
+        path
+    }
+
+    fn ed25519_jwk(key: &Ed25519KeyPair, kid: &str) -> Value {
+        serde_json::json!({
+            "kty": "OKP", "crv": "Ed25519", "use": "sig", "kid": kid,
+            "x": b64(key.public_key().as_ref()),
+        })
+    }
+
+    fn claims(sub: &str, exp_offset: i64) -> Value {
+        serde_json::json!({
+            "iss": ISSUER,
+            "aud": [AUDIENCE, "other"],
+            "sub": sub,
+            "exp": chrono::Utc::now().timestamp() + exp_offset,
+            "groups": ["ops", "finance"],
+        })
+    }
+
+    #[test]
+    fn test_validates_eddsa_token_and_maps_roles() {
+        let dir = TempDir::new().unwrap();
+        let key = ed25519_key();
+        let path = write_jwks(&dir, serde_json::json!([ed25519_jwk(&key, "k1")]));
+        let config = JwtConfig::new(ISSUER, AUDIENCE, path)
+            .with_role_mapping(ClaimRoleMapping::new("groups", "finance", "approver"));
+        let validator = JwtValidator::new(config).unwrap();
+
+        let principal = validator
+            .validate(&sign_ed25519(&key, "k1", &claims("alice", 300)))
+            .unwrap();
+
+        assert_eq!(principal.id, "alice");
+        assert_eq!(principal.principal_type, PrincipalType::User);
+        assert_eq!(
+            principal.attributes.get("role"),
+            Some(&"approver".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validates_es256_token() {
+        let dir = TempDir::new().unwrap();
+        let rng = SystemRandom::new();
+        let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(alg, &rng).unwrap();
+        let key = EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+        let point = key.public_key().as_ref();
+        let path = write_jwks(
+            &dir,
+            serde_json::json!([{
+                "kty": "EC", "crv": "P-256", "kid": "ec1",
+                "x": b64(&point[1..33]), "y": b64(&point[33..65]),
+            }]),
+        );
+        let validator = JwtValidator::new(JwtConfig::new(ISSUER, AUDIENCE, path)).unwrap();
+        let header = serde_json::json!({"alg": "ES256", "kid": "ec1"});
+        let signed = format!(
+            "{}.{}",
+            b64(header.to_string().as_bytes()),
+            b64(claims("spiffe://example.com/billing", 300)
+                .to_string()
+                .as_bytes())
+        );
+        let token = format!(
+            "{}.{}",
+            signed,
+            b64(key.sign(&rng, signed.as_bytes()).unwrap().as_ref())
+        );
+
+        let principal = validator.validate(&token).unwrap();
+
+        assert_eq!(principal.principal_type, PrincipalType::Service);
+        assert_eq!(
+            principal.attributes.get("trust_domain"),
+            Some(&"example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rejects_forged_expired_and_foreign_tokens() {
+        let dir = TempDir::new().unwrap();
+        let key = ed25519_key();
+        let path = write_jwks(&dir, serde_json::json!([ed25519_jwk(&key, "k1")]));
+        let validator = JwtValidator::new(JwtConfig::new(ISSUER, AUDIENCE, path)).unwrap();
+
+        let forged = sign_ed25519(&ed25519_key(), "k1", &claims("alice", 300));
+        assert!(validator.validate(&forged).is_err(), "Unknown signer");
+        let expired = sign_ed25519(&key, "k1", &claims("alice", -3600));
+        assert!(validator.validate(&expired).is_err(), "Expired");
+        let mut foreign = claims("alice", 300);
+        foreign["aud"] = serde_json::json!("another-service");
+        assert!(
+            validator
+                .validate(&sign_ed25519(&key, "k1", &foreign))
+                .is_err(),
+            "Wrong audience"
+        );
+        let unsigned = format!(
+            "{}.{}.",
+            b64(br#"{"alg":"none"}"#),
+            b64(claims("alice", 300).to_string().as_bytes())
+        );
+        assert!(validator.validate(&unsigned).is_err(), "alg none");
+    }
+
+    #[test]
+    fn test_rotated_key_set_is_reloaded() {
+        let dir = TempDir::new().unwrap();
+        let old_key = ed25519_key();
+        let path = write_jwks(&dir, serde_json::json!([ed25519_jwk(&old_key, "2024")]));
+        let validator = JwtValidator::new(JwtConfig::new(ISSUER, AUDIENCE, &path)).unwrap();
+        let new_key = ed25519_key();
+        let token = sign_ed25519(&new_key, "2025", &claims("alice", 300));
+        assert!(validator.validate(&token).is_err());
+
+        write_jwks(
+            &dir,
+            serde_json::json!([ed25519_jwk(&old_key, "2024"), ed25519_jwk(&new_key, "2025")]),
+        );
+        assert!(validator.reload_keys().unwrap());
+        assert!(!validator.reload_keys().unwrap(), "Unchanged content");
+
+        assert_eq!(validator.validate(&token).unwrap().id, "alice");
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_last_good_keys() {
+        let dir = TempDir::new().unwrap();
+        let key = ed25519_key();
+        let path = write_jwks(&dir, serde_json::json!([ed25519_jwk(&key, "k1")]));
+        let validator = JwtValidator::new(JwtConfig::new(ISSUER, AUDIENCE, &path)).unwrap();
+        let token = sign_ed25519(&key, "k1", &claims("alice", 300));
+
+        // Mid-rotation: the file is briefly missing, then half-written
+        std::fs::remove_file(&path).unwrap();
+        assert!(validator.reload_keys().is_err());
+        assert_eq!(validator.validate(&token).unwrap().id, "alice");
+        std::fs::write(&path, "{\"keys\": [").unwrap();
+        assert!(validator.reload_keys().is_err());
+        assert_eq!(validator.validate(&token).unwrap().id, "alice");
+    }
+}
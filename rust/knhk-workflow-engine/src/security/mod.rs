@@ -1,16 +1,18 @@
 //! Security module
 //!
-//! Provides security features including authentication, authorization,
-//! input validation, and audit logging.
+//! Provides security features including authentication (JWT bearer tokens),
+//! authorization, input validation, and audit logging.
 
 mod audit;
 mod auth;
 mod guards;
+mod jwt;
 mod secrets;
 mod validation;
 
 pub use audit::*;
 pub use auth::*;
 pub use guards::*;
+pub use jwt::*;
 pub use secrets::*;
 pub use validation::*;
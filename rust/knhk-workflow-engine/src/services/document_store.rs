@@ -192,6 +192,12 @@ impl DocumentStore {
             .unwrap_or_default()
     }
 
+    /// List all documents
+    pub async fn list_documents(&self) -> Vec<DocumentMetadata> {
+        let docs = self.documents.read().await;
+        docs.values().cloned().collect()
+    }
+
     /// Delete a document
     pub async fn delete_document(&self, doc_id: &DocumentId) -> WorkflowResult<()> {
         let mut docs = self.documents.write().await;
@@ -201,10 +207,10 @@ impl DocumentStore {
 
         // Delete file
         let file_path = self.storage_root.join(format!("{}.bin", doc_id.0));
-        tokio::fs::remove_file(&file_path).await.map_err(|e| {
-            warn!("Failed to delete document file: {}", e);
+        if let Err(e) = tokio::fs::remove_file(&file_path).await {
             // Continue even if file deletion fails
-        })?;
+            warn!("Failed to delete document file: {}", e);
+        }
 
         // Remove from case index
         let mut case_docs = self.case_documents.write().await;
@@ -244,5 +250,9 @@ mod tests {
 
         let metadata = store.get_metadata(&doc_id).await.unwrap();
         assert_eq!(metadata.name, "test.txt");
+
+        store.delete_document(&doc_id).await.unwrap();
+        assert!(store.list_case_documents(case_id).await.is_empty());
+        assert!(store.get_document(&doc_id).await.is_err());
     }
 }
@@ -0,0 +1,463 @@
+//! Message correlation service
+//!
+//! Handles:
+//! - Message-catch tasks waiting for a message with matching correlation keys
+//! - Buffering of messages that arrive before the case is waiting for them
+//! - Hand-over of the message that instantiated a case (message start)
+//!
+//! A message matches a waiting catch when it has the same name and carries
+//! every correlation key of the catch with an equal value (additional keys are
+//! ignored). Correlation values are compared as text: strings by content,
+//! other JSON values by their JSON rendering.
+//!
+//! The service is in-memory: waiting catches are re-registered when a
+//! recovered case re-executes its catch task, buffered messages are lost on
+//! restart.
+
+use crate::case::CaseId;
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::WorkflowSpecId;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use tokio::sync::{oneshot, Mutex};
+
+/// Message published to running cases
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorrelatedMessage {
+    /// Message ID
+    pub id: String,
+    /// Message name (e.g. "payment-received")
+    pub name: String,
+    /// Correlation key values
+    #[serde(default)]
+    pub correlation: BTreeMap<String, serde_json::Value>,
+    /// Message payload (merged into the case data of the receiving task)
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    /// Publication time
+    pub published_at: DateTime<Utc>,
+    /// End of buffering if nobody is waiting (service default if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CorrelatedMessage {
+    /// Create a message without correlation keys
+    pub fn new(name: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.into(),
+            correlation: BTreeMap::new(),
+            payload,
+            published_at: Utc::now(),
+            expires_at: None,
+        }
+    }
+
+    /// Add a correlation key
+    pub fn with_key(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.correlation.insert(key.into(), value.into());
+        self
+    }
+
+    /// Buffer the message for at most `ttl` while nobody is waiting for it
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.expires_at = Some(self.published_at + ttl);
+        self
+    }
+
+    /// Whether the message satisfies a catch's correlation values
+    pub fn matches(&self, name: &str, correlation: &BTreeMap<String, String>) -> bool {
+        self.name == name
+            && correlation.iter().all(|(key, value)| {
+                self.correlation
+                    .get(key)
+                    .is_some_and(|v| correlation_value(v) == *value)
+            })
+    }
+}
+
+/// Text form of a correlation value
+pub fn correlation_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Outcome of publishing a message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum MessageDelivery {
+    /// Delivered to a waiting message-catch task
+    Delivered {
+        /// Receiving case
+        case_id: CaseId,
+        /// Receiving task
+        task_id: String,
+    },
+    /// Instantiated new cases through message-start tasks
+    Started {
+        /// Started cases
+        case_ids: Vec<CaseId>,
+        /// Their specifications
+        spec_ids: Vec<WorkflowSpecId>,
+    },
+    /// Buffered until a matching catch subscribes
+    Buffered {
+        /// End of buffering
+        expires_at: DateTime<Utc>,
+    },
+}
+
+/// Message-catch task waiting for a message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaitingCatch {
+    /// Waiting case
+    pub case_id: CaseId,
+    /// Waiting task
+    pub task_id: String,
+    /// Expected message name
+    pub message: String,
+    /// Expected correlation values
+    pub correlation: BTreeMap<String, String>,
+    /// Time the task started waiting
+    pub since: DateTime<Utc>,
+}
+
+/// Result of subscribing a message-catch task
+#[derive(Debug)]
+pub enum MessageWait {
+    /// A buffered message matched immediately
+    Ready(CorrelatedMessage),
+    /// Waiting; the sender is dropped if the subscription is cancelled
+    Waiting(oneshot::Receiver<CorrelatedMessage>),
+}
+
+/// Buffering limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageBufferConfig {
+    /// Buffering time of messages without their own expiry
+    pub default_ttl: Duration,
+    /// Maximum buffered messages (the oldest are dropped first)
+    pub max_buffered: usize,
+}
+
+impl Default for MessageBufferConfig {
+    fn default() -> Self {
+        Self {
+            default_ttl: Duration::hours(24),
+            max_buffered: 10_000,
+        }
+    }
+}
+
+struct Subscription {
+    catch: WaitingCatch,
+    sender: oneshot::Sender<CorrelatedMessage>,
+}
+
+#[derive(Default)]
+struct CorrelatorState {
+    /// Waiting catches, oldest first
+    subscriptions: Vec<Subscription>,
+    /// Unmatched messages, oldest first
+    buffer: VecDeque<CorrelatedMessage>,
+    /// Messages that started a case, by case and message-start task
+    reserved: HashMap<(CaseId, String), CorrelatedMessage>,
+}
+
+/// Message correlation service
+pub struct MessageCorrelator {
+    config: MessageBufferConfig,
+    state: Mutex<CorrelatorState>,
+}
+
+impl MessageCorrelator {
+    /// Create a correlator with default buffering limits
+    pub fn new() -> Self {
+        Self::with_config(MessageBufferConfig::default())
+    }
+
+    /// Create a correlator with custom buffering limits
+    pub fn with_config(config: MessageBufferConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CorrelatorState::default()),
+        }
+    }
+
+    /// Wait for a message, consuming the oldest matching buffered message if any
+    pub async fn subscribe(
+        &self,
+        case_id: CaseId,
+        task_id: String,
+        message: String,
+        correlation: BTreeMap<String, String>,
+    ) -> MessageWait {
+        let mut state = self.state.lock().await;
+        let now = Utc::now();
+        state
+            .buffer
+            .retain(|m| !m.expires_at.is_some_and(|expires_at| expires_at <= now));
+        if let Some(index) = state
+            .buffer
+            .iter()
+            .position(|m| m.matches(&message, &correlation))
+        {
+            if let Some(buffered) = state.buffer.remove(index) {
+                return MessageWait::Ready(buffered);
+            }
+        }
+
+        // A re-executed task replaces its previous subscription
+        state
+            .subscriptions
+            .retain(|s| !(s.catch.case_id == case_id && s.catch.task_id == task_id));
+        let (sender, receiver) = oneshot::channel();
+        state.subscriptions.push(Subscription {
+            catch: WaitingCatch {
+                case_id,
+                task_id,
+                message,
+                correlation,
+                since: now,
+            },
+            sender,
+        });
+        MessageWait::Waiting(receiver)
+    }
+
+    /// Deliver a message to the oldest matching waiting catch
+    pub async fn deliver(&self, message: CorrelatedMessage) -> Option<(CaseId, String)> {
+        let mut state = self.state.lock().await;
+        Self::deliver_locked(&mut state, message).map(|catch| (catch.case_id, catch.task_id))
+    }
+
+    /// Deliver a message to a waiting catch, or buffer it
+    pub async fn deliver_or_buffer(
+        &self,
+        message: CorrelatedMessage,
+    ) -> WorkflowResult<MessageDelivery> {
+        let mut state = self.state.lock().await;
+        let expires_at = message
+            .expires_at
+            .unwrap_or(message.published_at + self.config.default_ttl);
+        if expires_at <= Utc::now() {
+            return Err(WorkflowError::Validation(format!(
+                "Message {} expired before it could be delivered",
+                message.id
+            )));
+        }
+        let mut message = message;
+        message.expires_at = Some(expires_at);
+
+        let pending = match Self::try_deliver(&mut state, message) {
+            Ok(catch) => {
+                return Ok(MessageDelivery::Delivered {
+                    case_id: catch.case_id,
+                    task_id: catch.task_id,
+                })
+            }
+            Err(message) => message,
+        };
+        if self.config.max_buffered == 0 {
+            return Err(WorkflowError::ResourceUnavailable(
+                "Message buffering is disabled".to_string(),
+            ));
+        }
+        while state.buffer.len() >= self.config.max_buffered {
+            if let Some(dropped) = state.buffer.pop_front() {
+                tracing::warn!(
+                    "Message buffer full, dropping message {} ({})",
+                    dropped.id,
+                    dropped.name
+                );
+            }
+        }
+        state.buffer.push_back(pending);
+        Ok(MessageDelivery::Buffered { expires_at })
+    }
+
+    /// Hand the message that started a case to its message-start task
+    pub async fn reserve(&self, case_id: CaseId, task_id: String, message: CorrelatedMessage) {
+        let mut state = self.state.lock().await;
+        state.reserved.insert((case_id, task_id), message);
+    }
+
+    /// Take the message reserved for a message-start task
+    pub async fn take_reserved(&self, case_id: CaseId, task_id: &str) -> Option<CorrelatedMessage> {
+        let mut state = self.state.lock().await;
+        state.reserved.remove(&(case_id, task_id.to_string()))
+    }
+
+    /// Drop the subscriptions and reservations of a case (e.g. when cancelled)
+    pub async fn cancel_case(&self, case_id: CaseId) -> usize {
+        let mut state = self.state.lock().await;
+        let before = state.subscriptions.len();
+        state.subscriptions.retain(|s| s.catch.case_id != case_id);
+        state.reserved.retain(|(id, _), _| *id != case_id);
+        before - state.subscriptions.len()
+    }
+
+    /// Catches currently waiting, oldest first
+    pub async fn waiting(&self) -> Vec<WaitingCatch> {
+        let state = self.state.lock().await;
+        state
+            .subscriptions
+            .iter()
+            .map(|s| s.catch.clone())
+            .collect()
+    }
+
+    /// Buffered messages that have not expired, oldest first
+    pub async fn buffered(&self) -> Vec<CorrelatedMessage> {
+        let mut state = self.state.lock().await;
+        let now = Utc::now();
+        state
+            .buffer
+            .retain(|m| !m.expires_at.is_some_and(|expires_at| expires_at <= now));
+        state.buffer.iter().cloned().collect()
+    }
+
+    fn deliver_locked(
+        state: &mut CorrelatorState,
+        message: CorrelatedMessage,
+    ) -> Option<WaitingCatch> {
+        Self::try_deliver(state, message).ok()
+    }
+
+    /// Hand the message to the oldest live matching subscription, or give it back
+    fn try_deliver(
+        state: &mut CorrelatorState,
+        mut message: CorrelatedMessage,
+    ) -> Result<WaitingCatch, CorrelatedMessage> {
+        // Subscriptions whose task stopped waiting are dropped on the way
+        state.subscriptions.retain(|s| !s.sender.is_closed());
+        while let Some(index) = state
+            .subscriptions
+            .iter()
+            .position(|s| message.matches(&s.catch.message, &s.catch.correlation))
+        {
+            let subscription = state.subscriptions.remove(index);
+            match subscription.sender.send(message) {
+                Ok(()) => return Ok(subscription.catch),
+                Err(returned) => message = returned,
+            }
+        }
+        Err(message)
+    }
+}
+
+impl Default for MessageCorrelator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_deliver_to_waiting_catch() {
+        let correlator = MessageCorrelator::new();
+        let case_id = CaseId::new();
+        let wait = correlator
+            .subscribe(
+                case_id,
+                "wait_payment".to_string(),
+                "payment".to_string(),
+                keys(&[("orderId", "42")]),
+            )
+            .await;
+        let MessageWait::Waiting(receiver) = wait else {
+            panic!("nothing buffered yet");
+        };
+
+        // Wrong correlation value is buffered, matching one is delivered
+        let other = CorrelatedMessage::new("payment", serde_json::json!({})).with_key("orderId", 7);
+        assert!(matches!(
+            correlator.deliver_or_buffer(other).await.unwrap(),
+            MessageDelivery::Buffered { .. }
+        ));
+        let message = CorrelatedMessage::new("payment", serde_json::json!({"amount": 10}))
+            .with_key("orderId", 42)
+            .with_key("currency", "EUR");
+        assert_eq!(
+            correlator.deliver_or_buffer(message).await.unwrap(),
+            MessageDelivery::Delivered {
+                case_id,
+                task_id: "wait_payment".to_string()
+            }
+        );
+        assert_eq!(receiver.await.unwrap().payload["amount"], 10);
+        assert!(correlator.waiting().await.is_empty());
+        assert_eq!(correlator.buffered().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_buffered_message_consumed_on_subscribe() {
+        let correlator = MessageCorrelator::new();
+        let message = CorrelatedMessage::new("shipped", serde_json::json!({"carrier": "DHL"}))
+            .with_key("orderId", "A-1");
+        correlator.deliver_or_buffer(message).await.unwrap();
+
+        let wait = correlator
+            .subscribe(
+                CaseId::new(),
+                "wait_shipping".to_string(),
+                "shipped".to_string(),
+                keys(&[("orderId", "A-1")]),
+            )
+            .await;
+        assert!(matches!(wait, MessageWait::Ready(ref m) if m.payload["carrier"] == "DHL"));
+        assert!(correlator.buffered().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_buffer_expiry_and_capacity() {
+        let correlator = MessageCorrelator::with_config(MessageBufferConfig {
+            default_ttl: Duration::hours(1),
+            max_buffered: 2,
+        });
+        let expired =
+            CorrelatedMessage::new("late", serde_json::json!({})).with_ttl(Duration::seconds(-1));
+        assert!(correlator.deliver_or_buffer(expired).await.is_err());
+
+        for i in 0..3 {
+            let message = CorrelatedMessage::new("ping", serde_json::json!({})).with_key("n", i);
+            correlator.deliver_or_buffer(message).await.unwrap();
+        }
+        let buffered = correlator.buffered().await;
+        assert_eq!(buffered.len(), 2);
+        assert_eq!(buffered[0].correlation["n"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_case_drops_subscription() {
+        let correlator = MessageCorrelator::new();
+        let case_id = CaseId::new();
+        let wait = correlator
+            .subscribe(
+                case_id,
+                "wait".to_string(),
+                "payment".to_string(),
+                BTreeMap::new(),
+            )
+            .await;
+        assert_eq!(correlator.cancel_case(case_id).await, 1);
+        let MessageWait::Waiting(receiver) = wait else {
+            panic!("nothing buffered yet");
+        };
+        assert!(receiver.await.is_err());
+    }
+}
@@ -5,11 +5,13 @@
 //! - Event sidecar for external event handling (pattern 16)
 //! - Admission gate for case validation
 //! - Work item service for human task management
+//! - Message correlation for message-catch and message-start tasks
 
 pub mod admission;
 pub mod cost;
 pub mod document_store;
 pub mod event_sidecar;
+pub mod messages;
 pub mod timer;
 pub mod work_items;
 
@@ -17,7 +19,11 @@ pub use admission::AdmissionGate;
 pub use cost::{ActivityCost, CaseCostSummary, CostCategory, CostService};
 pub use document_store::{DocumentId, DocumentMetadata, DocumentStore};
 pub use event_sidecar::EventSidecar;
-pub use timer::TimerFired;
+pub use messages::{
+    CorrelatedMessage, MessageBufferConfig, MessageCorrelator, MessageDelivery, MessageWait,
+    WaitingCatch,
+};
+pub use timer::{PendingTimer, TimerFired};
 pub use work_items::WorkItemService;
 
 // TimerService is generic over Timebase, so we export a type alias for common use
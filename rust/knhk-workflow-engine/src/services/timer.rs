@@ -70,6 +70,58 @@ struct TimerEntry {
     active: bool,
 }
 
+/// Snapshot of a pending timer (captured in case checkpoints for crash recovery)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingTimer {
+    /// Timer ID
+    pub id: String,
+    /// Pattern ID (30 for transient, 31 for persistent)
+    pub pattern_id: u32,
+    /// Case ID
+    pub case_id: String,
+    /// Workflow ID
+    pub workflow_id: String,
+    /// Timer key
+    pub key: String,
+    /// Timer kind
+    pub kind: TimerKind,
+    /// Due time
+    pub due_at: DateTime<Utc>,
+    /// Recurrence rule (for persistent timers)
+    pub rrule: Option<String>,
+}
+
+impl From<&TimerEntry> for PendingTimer {
+    fn from(entry: &TimerEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            pattern_id: entry.pattern_id.0,
+            case_id: entry.case_id.clone(),
+            workflow_id: entry.workflow_id.clone(),
+            key: entry.key.clone(),
+            kind: entry.kind,
+            due_at: entry.due_at,
+            rrule: entry.rrule.clone(),
+        }
+    }
+}
+
+impl From<PendingTimer> for TimerEntry {
+    fn from(timer: PendingTimer) -> Self {
+        Self {
+            id: timer.id,
+            pattern_id: PatternId(timer.pattern_id),
+            case_id: timer.case_id,
+            workflow_id: timer.workflow_id,
+            key: timer.key,
+            kind: timer.kind,
+            due_at: timer.due_at,
+            rrule: timer.rrule,
+            active: true,
+        }
+    }
+}
+
 /// Timer service with Timebase integration and durability
 pub struct TimerService<T: Timebase> {
     /// Timebase for time operations
@@ -212,6 +264,43 @@ impl<T: Timebase + 'static> TimerService<T> {
         Ok(())
     }
 
+    /// Get pending (active) timers for a case
+    pub async fn case_timers(&self, case_id: &str) -> Vec<PendingTimer> {
+        let timers = self.timers.read().await;
+        let mut pending: Vec<PendingTimer> = timers
+            .values()
+            .filter(|entry| entry.active && entry.case_id == case_id)
+            .map(PendingTimer::from)
+            .collect();
+        pending.sort_by(|a, b| a.due_at.cmp(&b.due_at).then_with(|| a.id.cmp(&b.id)));
+        pending
+    }
+
+    /// Restore a pending timer captured in a case checkpoint
+    ///
+    /// Idempotent: restoring a timer that is already registered is a no-op.
+    /// Timers that became due while the process was down fire on the next tick.
+    pub async fn restore_timer(&self, timer: PendingTimer) -> WorkflowResult<bool> {
+        // Keep newly issued timer IDs clear of restored ones
+        if let Some(n) = timer
+            .id
+            .strip_prefix("timer:")
+            .and_then(|n| n.parse::<u64>().ok())
+        {
+            let mut id = self.next_timer_id.lock().await;
+            if *id < n {
+                *id = n;
+            }
+        }
+
+        let mut timers = self.timers.write().await;
+        if timers.contains_key(&timer.id) {
+            return Ok(false);
+        }
+        timers.insert(timer.id.clone(), TimerEntry::from(timer));
+        Ok(true)
+    }
+
     /// Recover timers from durable storage on startup
     ///
     /// Loads all active timers from state store and restores them to the timer service.
@@ -5,6 +5,8 @@
 //! - Work item queue
 //! - Task assignment and claiming
 
+use crate::data::contract::TaskDataContract;
+use crate::data::schema::violations_error;
 use crate::error::{WorkflowError, WorkflowResult};
 use crate::parser::WorkflowSpecId;
 use chrono::{DateTime, Utc};
@@ -29,6 +31,10 @@ pub enum WorkItemState {
     Completed,
     /// Cancelled
     Cancelled,
+    /// Failed by exception handling
+    Failed,
+    /// Suspended by exception handling (cannot be completed until continued)
+    Suspended,
 }
 
 /// Work item
@@ -52,6 +58,25 @@ pub struct WorkItem {
     pub completed_at: Option<DateTime<Utc>>,
     /// Work item data
     pub data: serde_json::Value,
+    /// Typed output contract of the task (results are validated on completion)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_contract: Option<TaskDataContract>,
+    /// State to return to when a suspended work item is continued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspended_from: Option<WorkItemState>,
+}
+
+/// Reject a work item result that violates the task's output contract
+fn validate_result(item: &WorkItem, result: &serde_json::Value) -> WorkflowResult<()> {
+    match item.output_contract {
+        Some(ref contract) => contract.validate_result(result).map_err(|violations| {
+            violations_error(
+                &format!("Invalid result for work item {}", item.id),
+                &violations,
+            )
+        }),
+        None => Ok(()),
+    }
 }
 
 /// Work item service
@@ -78,6 +103,19 @@ impl WorkItemService {
         spec_id: WorkflowSpecId,
         task_id: String,
         data: serde_json::Value,
+    ) -> WorkflowResult<String> {
+        self.create_work_item_with_contract(case_id, spec_id, task_id, data, None)
+            .await
+    }
+
+    /// Create a work item whose result must satisfy the task's output contract
+    pub async fn create_work_item_with_contract(
+        &self,
+        case_id: String,
+        spec_id: WorkflowSpecId,
+        task_id: String,
+        data: serde_json::Value,
+        output_contract: Option<TaskDataContract>,
     ) -> WorkflowResult<String> {
         let work_item_id = Uuid::new_v4().to_string();
         let work_item = WorkItem {
@@ -90,6 +128,8 @@ impl WorkItemService {
             created_at: Utc::now(),
             completed_at: None,
             data,
+            output_contract,
+            suspended_from: None,
         };
 
         let mut items = self.work_items.write().await;
@@ -104,6 +144,73 @@ impl WorkItemService {
         Ok(work_item_id)
     }
 
+    /// Restore a work item captured in a case checkpoint
+    ///
+    /// Idempotent: restoring a work item that is already known is a no-op.
+    pub async fn restore_work_item(&self, work_item: WorkItem) -> bool {
+        let mut items = self.work_items.write().await;
+        if items.contains_key(&work_item.id) {
+            return false;
+        }
+        let work_item_id = work_item.id.clone();
+        let case_id = work_item.case_id.clone();
+        items.insert(work_item_id.clone(), work_item);
+        drop(items);
+
+        let mut case_items = self.case_items.write().await;
+        case_items.entry(case_id).or_default().push(work_item_id);
+        true
+    }
+
+    /// Remove a work item (retention enforcement only)
+    pub async fn remove_work_item(&self, work_item_id: &str) -> Option<WorkItem> {
+        let removed = self.work_items.write().await.remove(work_item_id)?;
+        let mut case_items = self.case_items.write().await;
+        if let Some(item_ids) = case_items.get_mut(&removed.case_id) {
+            item_ids.retain(|id| id != work_item_id);
+            if item_ids.is_empty() {
+                case_items.remove(&removed.case_id);
+            }
+        }
+        Some(removed)
+    }
+
+    /// Remove the fields of a work item's data, returning the fields that were present
+    pub async fn redact_work_item_data(
+        &self,
+        work_item_id: &str,
+        fields: &[String],
+    ) -> Vec<String> {
+        let mut items = self.work_items.write().await;
+        let data = match items
+            .get_mut(work_item_id)
+            .and_then(|item| item.data.as_object_mut())
+        {
+            Some(data) => data,
+            None => return Vec::new(),
+        };
+        fields
+            .iter()
+            .filter(|field| data.remove(field.as_str()).is_some())
+            .cloned()
+            .collect()
+    }
+
+    /// Find the open (not completed, cancelled or failed) work item of a case task
+    ///
+    /// Used to re-attach to an existing work item instead of creating a duplicate
+    /// when a task is re-executed after crash recovery.
+    pub async fn find_open_work_item(&self, case_id: &str, task_id: &str) -> Option<WorkItem> {
+        let items = self.list_case_work_items(case_id).await;
+        items.into_iter().find(|item| {
+            item.task_id == task_id
+                && !matches!(
+                    item.state,
+                    WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+                )
+        })
+    }
+
     /// Get work item by ID
     pub async fn get_work_item(&self, work_item_id: &str) -> Option<WorkItem> {
         let items = self.work_items.read().await;
@@ -178,12 +285,22 @@ impl WorkItemService {
     ) -> WorkflowResult<()> {
         let mut items = self.work_items.write().await;
         if let Some(item) = items.get_mut(work_item_id) {
-            if item.state == WorkItemState::Completed || item.state == WorkItemState::Cancelled {
+            if matches!(
+                item.state,
+                WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+            ) {
                 return Err(WorkflowError::Validation(format!(
-                    "Work item {} is already completed or cancelled",
+                    "Work item {} is already completed, cancelled or failed",
                     work_item_id
                 )));
             }
+            if item.state == WorkItemState::Suspended {
+                return Err(WorkflowError::InvalidStateTransition {
+                    from: "suspended".to_string(),
+                    to: "completed".to_string(),
+                });
+            }
+            validate_result(item, &result)?;
             item.state = WorkItemState::Completed;
             item.completed_at = Some(Utc::now());
             item.data = result;
@@ -202,9 +319,9 @@ impl WorkItemService {
     pub async fn cancel(&self, work_item_id: &str) -> WorkflowResult<()> {
         let mut items = self.work_items.write().await;
         if let Some(item) = items.get_mut(work_item_id) {
-            if item.state == WorkItemState::Completed {
+            if matches!(item.state, WorkItemState::Completed | WorkItemState::Failed) {
                 return Err(WorkflowError::Validation(format!(
-                    "Work item {} is already completed",
+                    "Work item {} is already completed or failed",
                     work_item_id
                 )));
             }
@@ -224,6 +341,117 @@ impl WorkItemService {
         }
     }
 
+    /// Suspend a work item for exception handling
+    ///
+    /// The item keeps its data and allocation but cannot be completed until
+    /// [`unsuspend`](Self::unsuspend) is called.
+    pub async fn suspend(&self, work_item_id: &str) -> WorkflowResult<()> {
+        let mut items = self.work_items.write().await;
+        let item = items.get_mut(work_item_id).ok_or_else(|| {
+            WorkflowError::ResourceUnavailable(format!("Work item {} not found", work_item_id))
+        })?;
+        match item.state {
+            WorkItemState::Completed
+            | WorkItemState::Cancelled
+            | WorkItemState::Failed
+            | WorkItemState::Suspended => Err(WorkflowError::InvalidStateTransition {
+                from: format!("{:?}", item.state).to_lowercase(),
+                to: "suspended".to_string(),
+            }),
+            state => {
+                item.suspended_from = Some(state);
+                item.state = WorkItemState::Suspended;
+                Ok(())
+            }
+        }
+    }
+
+    /// Continue a work item suspended by [`suspend`](Self::suspend)
+    pub async fn unsuspend(&self, work_item_id: &str) -> WorkflowResult<()> {
+        let mut items = self.work_items.write().await;
+        let item = items.get_mut(work_item_id).ok_or_else(|| {
+            WorkflowError::ResourceUnavailable(format!("Work item {} not found", work_item_id))
+        })?;
+        if item.state != WorkItemState::Suspended {
+            return Err(WorkflowError::InvalidStateTransition {
+                from: format!("{:?}", item.state).to_lowercase(),
+                to: "resumed".to_string(),
+            });
+        }
+        item.state = item.suspended_from.take().unwrap_or(WorkItemState::Created);
+        Ok(())
+    }
+
+    /// Complete a work item with its current data, skipping output validation
+    ///
+    /// Used by exlets to force a task through after an exception.
+    pub async fn force_complete(&self, work_item_id: &str) -> WorkflowResult<()> {
+        let mut items = self.work_items.write().await;
+        let item = items.get_mut(work_item_id).ok_or_else(|| {
+            WorkflowError::ResourceUnavailable(format!("Work item {} not found", work_item_id))
+        })?;
+        if matches!(
+            item.state,
+            WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+        ) {
+            return Err(WorkflowError::Validation(format!(
+                "Work item {} is already completed, cancelled or failed",
+                work_item_id
+            )));
+        }
+        item.state = WorkItemState::Completed;
+        item.suspended_from = None;
+        item.completed_at = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Fail an open work item
+    ///
+    /// Used by exlets; the task waiting on the item fails with it.
+    pub async fn fail(&self, work_item_id: &str) -> WorkflowResult<()> {
+        let mut items = self.work_items.write().await;
+        let item = items.get_mut(work_item_id).ok_or_else(|| {
+            WorkflowError::ResourceUnavailable(format!("Work item {} not found", work_item_id))
+        })?;
+        if matches!(
+            item.state,
+            WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+        ) {
+            return Err(WorkflowError::InvalidStateTransition {
+                from: format!("{:?}", item.state).to_lowercase(),
+                to: "failed".to_string(),
+            });
+        }
+        item.state = WorkItemState::Failed;
+        item.suspended_from = None;
+        item.completed_at = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Reset an open work item so it is offered again from scratch
+    ///
+    /// Used by exlets to restart an item: its allocation is dropped and it
+    /// returns to `Created`, keeping its id and data.
+    pub async fn restart(&self, work_item_id: &str) -> WorkflowResult<()> {
+        let mut items = self.work_items.write().await;
+        let item = items.get_mut(work_item_id).ok_or_else(|| {
+            WorkflowError::ResourceUnavailable(format!("Work item {} not found", work_item_id))
+        })?;
+        if matches!(
+            item.state,
+            WorkItemState::Completed | WorkItemState::Cancelled | WorkItemState::Failed
+        ) {
+            return Err(WorkflowError::InvalidStateTransition {
+                from: format!("{:?}", item.state).to_lowercase(),
+                to: "created".to_string(),
+            });
+        }
+        item.state = WorkItemState::Created;
+        item.assigned_resource_id = None;
+        item.suspended_from = None;
+        Ok(())
+    }
+
     /// Get inbox for a resource (work items assigned to or available for the resource)
     pub async fn get_inbox(&self, resource_id: &str) -> WorkflowResult<Vec<WorkItem>> {
         let items = self.work_items.read().await;
@@ -1935,6 +2163,8 @@ impl WorkItemService {
                 )));
             }
 
+            validate_result(item, &data)?;
+
             // Complete the work item
             item.state = WorkItemState::Completed;
             item.completed_at = Some(Utc::now());
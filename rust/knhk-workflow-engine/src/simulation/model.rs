@@ -0,0 +1,390 @@
+//! Simulation model: duration distributions, resource pools and run configuration
+
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::WorkflowSpec;
+use crate::scheduling::WorkingHours;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Probability distribution of a simulated duration (seconds)
+///
+/// Samples are clamped at zero, so e.g. a normal distribution never yields a
+/// negative task duration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Distribution {
+    /// Always the same value
+    Constant {
+        /// Value in seconds
+        value: f64,
+    },
+    /// Uniform over `[min, max]`
+    Uniform {
+        /// Lower bound in seconds
+        min: f64,
+        /// Upper bound in seconds
+        max: f64,
+    },
+    /// Exponential (memoryless, e.g. Poisson arrivals)
+    Exponential {
+        /// Mean in seconds
+        mean: f64,
+    },
+    /// Normal distribution
+    Normal {
+        /// Mean in seconds
+        mean: f64,
+        /// Standard deviation in seconds
+        std_dev: f64,
+    },
+    /// Log-normal distribution, parameterized by its own mean and standard deviation
+    LogNormal {
+        /// Mean in seconds
+        mean: f64,
+        /// Standard deviation in seconds
+        std_dev: f64,
+    },
+    /// Triangular distribution (three-point estimate)
+    Triangular {
+        /// Minimum in seconds
+        min: f64,
+        /// Most likely value in seconds
+        mode: f64,
+        /// Maximum in seconds
+        max: f64,
+    },
+}
+
+impl Distribution {
+    /// Constant distribution
+    pub fn constant(value: f64) -> Self {
+        Self::Constant { value }
+    }
+
+    /// Exponential distribution with the given mean
+    pub fn exponential(mean: f64) -> Self {
+        Self::Exponential { mean }
+    }
+
+    /// Draw a sample (seconds, never negative)
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let value = match *self {
+            Self::Constant { value } => value,
+            Self::Uniform { min, max } => min + (max - min) * rng.gen::<f64>(),
+            Self::Exponential { mean } => -mean * (1.0 - rng.gen::<f64>()).ln(),
+            Self::Normal { mean, std_dev } => mean + std_dev * standard_normal(rng),
+            Self::LogNormal { mean, std_dev } => {
+                let sigma2 = (1.0 + (std_dev * std_dev) / (mean * mean)).ln();
+                let mu = mean.ln() - sigma2 / 2.0;
+                (mu + sigma2.sqrt() * standard_normal(rng)).exp()
+            }
+            Self::Triangular { min, mode, max } => {
+                let u = rng.gen::<f64>();
+                let split = if max > min {
+                    (mode - min) / (max - min)
+                } else {
+                    0.0
+                };
+                if u < split {
+                    min + (u * (max - min) * (mode - min)).sqrt()
+                } else {
+                    max - ((1.0 - u) * (max - min) * (max - mode)).sqrt()
+                }
+            }
+        };
+        value.max(0.0)
+    }
+
+    /// Expected value (before clamping)
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Self::Constant { value } => value,
+            Self::Uniform { min, max } => (min + max) / 2.0,
+            Self::Exponential { mean }
+            | Self::Normal { mean, .. }
+            | Self::LogNormal { mean, .. } => mean,
+            Self::Triangular { min, mode, max } => (min + mode + max) / 3.0,
+        }
+    }
+
+    fn validate(&self, what: &str) -> WorkflowResult<()> {
+        let valid = match *self {
+            Self::Constant { value } => value.is_finite() && value >= 0.0,
+            Self::Uniform { min, max } => min.is_finite() && max.is_finite() && min <= max,
+            Self::Exponential { mean } => mean.is_finite() && mean > 0.0,
+            Self::Normal { mean, std_dev } => {
+                mean.is_finite() && std_dev.is_finite() && std_dev >= 0.0
+            }
+            Self::LogNormal { mean, std_dev } => {
+                mean.is_finite() && mean > 0.0 && std_dev.is_finite() && std_dev >= 0.0
+            }
+            Self::Triangular { min, mode, max } => {
+                min.is_finite() && max.is_finite() && min <= mode && mode <= max
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(WorkflowError::Validation(format!(
+                "Invalid {} distribution: {:?}",
+                what, self
+            )))
+        }
+    }
+}
+
+/// Box-Muller standard normal sample
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Pool of interchangeable resources (e.g. "clerks", "approvers")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePool {
+    /// Pool name
+    pub name: String,
+    /// Number of resources in the pool
+    pub capacity: usize,
+    /// Working hours; work items only start inside them (always available if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<WorkingHours>,
+}
+
+impl ResourcePool {
+    /// Always-available pool
+    pub fn new(name: impl Into<String>, capacity: usize) -> Self {
+        Self {
+            name: name.into(),
+            capacity,
+            calendar: None,
+        }
+    }
+
+    /// Restrict the pool to working hours
+    pub fn with_calendar(mut self, calendar: WorkingHours) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+}
+
+/// Simulation parameters of one task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskProfile {
+    /// Service time distribution
+    pub duration: Distribution,
+    /// Resource pool executing the task (unconstrained if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+}
+
+/// What-if scenario for [`Simulator`](super::Simulator)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    /// Number of synthetic cases to run
+    pub cases: usize,
+    /// Time between case arrivals
+    pub inter_arrival: Distribution,
+    /// Service time of tasks without a profile
+    pub default_duration: Distribution,
+    /// Per-task profiles, keyed by task ID
+    #[serde(default)]
+    pub tasks: HashMap<String, TaskProfile>,
+    /// Branch weights, keyed by source node then target node
+    ///
+    /// XOR splits and deferred choices pick one branch in proportion to the
+    /// weights (unlisted branches weigh 1.0). For OR splits each weight is the
+    /// independent probability of taking that branch.
+    #[serde(default)]
+    pub branch_weights: HashMap<String, HashMap<String, f64>>,
+    /// Resource pools
+    #[serde(default)]
+    pub pools: Vec<ResourcePool>,
+    /// Cycle time target in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla_seconds: Option<f64>,
+    /// Simulated wall-clock time of the first arrival
+    pub start: DateTime<Utc>,
+    /// Random seed (runs with the same seed are identical)
+    pub seed: u64,
+    /// Node firings (tasks and conditions) after which a case is abandoned
+    /// (guards against livelock in cyclic nets)
+    pub max_steps_per_case: usize,
+    /// Record the simulated event log (needed for XES export)
+    pub record_log: bool,
+}
+
+impl SimulationConfig {
+    /// Scenario with `cases` arrivals every 10 minutes (exponential) and
+    /// one-minute tasks
+    pub fn new(cases: usize) -> Self {
+        Self {
+            cases,
+            inter_arrival: Distribution::exponential(600.0),
+            default_duration: Distribution::constant(60.0),
+            tasks: HashMap::new(),
+            branch_weights: HashMap::new(),
+            pools: Vec::new(),
+            sla_seconds: None,
+            start: Utc::now(),
+            seed: 0,
+            max_steps_per_case: 1000,
+            record_log: true,
+        }
+    }
+
+    /// Set the inter-arrival time distribution
+    pub fn with_inter_arrival(mut self, inter_arrival: Distribution) -> Self {
+        self.inter_arrival = inter_arrival;
+        self
+    }
+
+    /// Set the service time of tasks without a profile
+    pub fn with_default_duration(mut self, duration: Distribution) -> Self {
+        self.default_duration = duration;
+        self
+    }
+
+    /// Set a task's service time distribution
+    pub fn with_task_duration(
+        mut self,
+        task_id: impl Into<String>,
+        duration: Distribution,
+    ) -> Self {
+        let profile = self.profile_mut(task_id.into());
+        profile.duration = duration;
+        self
+    }
+
+    /// Assign a task to a resource pool
+    pub fn with_task_pool(mut self, task_id: impl Into<String>, pool: impl Into<String>) -> Self {
+        let profile = self.profile_mut(task_id.into());
+        profile.pool = Some(pool.into());
+        self
+    }
+
+    /// Set the weight (probability) of the branch `from → to`
+    pub fn with_branch_weight(
+        mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        weight: f64,
+    ) -> Self {
+        self.branch_weights
+            .entry(from.into())
+            .or_default()
+            .insert(to.into(), weight);
+        self
+    }
+
+    /// Add a resource pool
+    pub fn with_pool(mut self, pool: ResourcePool) -> Self {
+        self.pools.push(pool);
+        self
+    }
+
+    /// Set the cycle time target
+    pub fn with_sla(mut self, sla: std::time::Duration) -> Self {
+        self.sla_seconds = Some(sla.as_secs_f64());
+        self
+    }
+
+    /// Set the time of the first arrival
+    pub fn with_start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Set the random seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Enable or disable event log recording
+    pub fn with_log(mut self, record_log: bool) -> Self {
+        self.record_log = record_log;
+        self
+    }
+
+    fn profile_mut(&mut self, task_id: String) -> &mut TaskProfile {
+        let default_duration = self.default_duration.clone();
+        self.tasks.entry(task_id).or_insert_with(|| TaskProfile {
+            duration: default_duration,
+            pool: None,
+        })
+    }
+
+    /// Check the scenario against the specification it will run on
+    pub fn validate(&self, spec: &WorkflowSpec) -> WorkflowResult<()> {
+        self.inter_arrival.validate("inter-arrival")?;
+        self.default_duration.validate("default duration")?;
+        if self.max_steps_per_case == 0 {
+            return Err(WorkflowError::Validation(
+                "max_steps_per_case must be positive".to_string(),
+            ));
+        }
+        for pool in &self.pools {
+            if pool.capacity == 0 {
+                return Err(WorkflowError::Validation(format!(
+                    "Resource pool '{}' has no capacity",
+                    pool.name
+                )));
+            }
+            if let Some(calendar) = &pool.calendar {
+                if calendar.next_working_time(self.start).is_none() {
+                    return Err(WorkflowError::Validation(format!(
+                        "Calendar of resource pool '{}' has no working time",
+                        pool.name
+                    )));
+                }
+            }
+        }
+        for (task_id, profile) in &self.tasks {
+            if !spec.tasks.contains_key(task_id) {
+                return Err(WorkflowError::Validation(format!(
+                    "Task profile for unknown task '{}'",
+                    task_id
+                )));
+            }
+            profile
+                .duration
+                .validate(&format!("duration of task '{}'", task_id))?;
+            if let Some(pool) = &profile.pool {
+                if !self.pools.iter().any(|p| &p.name == pool) {
+                    return Err(WorkflowError::Validation(format!(
+                        "Task '{}' uses unknown resource pool '{}'",
+                        task_id, pool
+                    )));
+                }
+            }
+        }
+        for (from, targets) in &self.branch_weights {
+            for (to, weight) in targets {
+                if !weight.is_finite() || *weight < 0.0 {
+                    return Err(WorkflowError::Validation(format!(
+                        "Branch weight {} → {} must be non-negative",
+                        from, to
+                    )));
+                }
+                if !spec.flows.iter().any(|f| &f.from == from && &f.to == to) {
+                    return Err(WorkflowError::Validation(format!(
+                        "Branch weight for unknown flow {} → {}",
+                        from, to
+                    )));
+                }
+            }
+        }
+        if let Some(sla) = self.sla_seconds {
+            if !sla.is_finite() || sla <= 0.0 {
+                return Err(WorkflowError::Validation(
+                    "SLA must be a positive duration".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! Simulation results
+
+use crate::case::CaseId;
+use crate::process_mining::{WorkflowEvent, XesExporter};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Cycle time distribution of completed cases (seconds)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CycleTimeStats {
+    /// Number of completed cases
+    pub count: usize,
+    /// Mean cycle time
+    pub mean: f64,
+    /// Shortest cycle time
+    pub min: f64,
+    /// Longest cycle time
+    pub max: f64,
+    /// Median
+    pub p50: f64,
+    /// 90th percentile
+    pub p90: f64,
+    /// 95th percentile
+    pub p95: f64,
+    /// 99th percentile
+    pub p99: f64,
+}
+
+impl CycleTimeStats {
+    /// Summarize cycle time samples (nearest-rank percentiles)
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        Self {
+            count: sorted.len(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+        }
+    }
+}
+
+/// Load on one resource pool
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PoolStats {
+    /// Resources in the pool
+    pub capacity: usize,
+    /// Busy time over available time (0.0 - 1.0)
+    pub utilization: f64,
+    /// Total busy resource-seconds
+    pub busy_seconds: f64,
+    /// Resource-seconds inside the pool calendar over the simulated horizon
+    pub available_seconds: f64,
+    /// Longest queue of waiting work items
+    pub max_queue_length: usize,
+    /// Time-weighted mean queue length
+    pub mean_queue_length: f64,
+    /// Mean time a work item waited for a resource
+    pub mean_wait_seconds: f64,
+}
+
+/// Executions of one task
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TaskStats {
+    /// Number of executions
+    pub executions: usize,
+    /// Mean service time
+    pub mean_duration_seconds: f64,
+    /// Mean time between enablement and start
+    pub mean_wait_seconds: f64,
+}
+
+/// Result of a simulation run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SimulationReport {
+    /// Cases that arrived
+    pub cases_started: usize,
+    /// Cases that reached the end condition
+    pub cases_completed: usize,
+    /// Cases that stalled (no route to the end condition or step limit reached)
+    pub cases_incomplete: usize,
+    /// Simulated time from the first arrival to the last event (seconds)
+    pub horizon_seconds: f64,
+    /// Cycle time distribution of completed cases
+    pub cycle_time: CycleTimeStats,
+    /// Share of completed cases whose cycle time exceeded the SLA
+    pub sla_breach_rate: Option<f64>,
+    /// Per-pool statistics
+    pub pools: BTreeMap<String, PoolStats>,
+    /// Per-task statistics
+    pub tasks: BTreeMap<String, TaskStats>,
+    /// Simulated event log (empty unless recording is enabled)
+    #[serde(skip)]
+    pub log: Vec<(CaseId, Vec<WorkflowEvent>)>,
+}
+
+impl SimulationReport {
+    /// Export the simulated event log as XES
+    pub fn to_xes(&self) -> String {
+        XesExporter::export_multiple_cases(self.log.clone())
+    }
+}
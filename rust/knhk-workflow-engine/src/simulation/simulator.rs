@@ -0,0 +1,839 @@
+//! Discrete-event simulator
+//!
+//! Cases flow through the net as tokens on simulated time: a task is enabled
+//! when its join is satisfied, waits for a free resource of its pool (inside
+//! the pool calendar), runs for a sampled service time and then routes tokens
+//! according to its split. Only the control flow is simulated; predicates and
+//! case data are replaced by the configured branch weights.
+
+use super::model::SimulationConfig;
+use super::report::{CycleTimeStats, PoolStats, SimulationReport, TaskStats};
+use crate::case::CaseId;
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{JoinType, SplitType, WorkflowSpec};
+use crate::process_mining::WorkflowEvent;
+use crate::scheduling::WorkingHours;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
+use uuid::Uuid;
+
+/// Runs what-if scenarios of a workflow specification
+pub struct Simulator {
+    spec: WorkflowSpec,
+    config: SimulationConfig,
+    /// Flow targets per node, in specification order
+    outgoing: HashMap<String, Vec<String>>,
+    /// Number of incoming flows per task
+    incoming: HashMap<String, usize>,
+}
+
+impl Simulator {
+    /// Create a simulator for `spec` under the given scenario
+    pub fn new(spec: WorkflowSpec, config: SimulationConfig) -> WorkflowResult<Self> {
+        if spec.start_condition.is_none() || spec.end_condition.is_none() {
+            return Err(WorkflowError::InvalidSpecification(
+                "Simulation requires start and end conditions".into(),
+            ));
+        }
+        config.validate(&spec)?;
+
+        let mut outgoing: HashMap<String, Vec<String>> = HashMap::new();
+        let mut incoming: HashMap<String, usize> = HashMap::new();
+        for flow in &spec.flows {
+            outgoing
+                .entry(flow.from.clone())
+                .or_default()
+                .push(flow.to.clone());
+            *incoming.entry(flow.to.clone()).or_default() += 1;
+        }
+
+        Ok(Self {
+            spec,
+            config,
+            outgoing,
+            incoming,
+        })
+    }
+
+    /// Scenario being simulated
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    /// Run all cases to completion and summarize the run
+    pub fn run(&self) -> SimulationReport {
+        let mut run = Run::new(self);
+        run.execute();
+        run.report()
+    }
+}
+
+/// Scheduled simulation event
+struct Event {
+    time: f64,
+    seq: u64,
+    kind: EventKind,
+}
+
+enum EventKind {
+    /// Next case arrives
+    Arrival,
+    /// A task finished; `slot` is the (pool, resource) that executed it
+    Complete {
+        case: usize,
+        task: String,
+        slot: Option<(usize, usize)>,
+    },
+    /// A pool calendar opens
+    PoolOpens(usize),
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Event {}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Event {
+    /// Reversed so that the max-heap pops the earliest event first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .time
+            .total_cmp(&self.time)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+struct CaseRun {
+    id: CaseId,
+    arrival: f64,
+    /// Tokens received per task since it last fired
+    received: HashMap<String, usize>,
+    /// Branches taken by open OR-splits (innermost last)
+    or_branches: Vec<usize>,
+    steps: usize,
+    completed: bool,
+    abandoned: bool,
+    events: Vec<WorkflowEvent>,
+}
+
+impl CaseRun {
+    fn is_done(&self) -> bool {
+        self.completed || self.abandoned
+    }
+}
+
+struct WorkItem {
+    case: usize,
+    task: String,
+    enabled_at: f64,
+}
+
+struct PoolRun {
+    name: String,
+    capacity: usize,
+    calendar: Option<WorkingHours>,
+    free: Vec<usize>,
+    queue: VecDeque<WorkItem>,
+    busy_seconds: f64,
+    queue_area: f64,
+    last_change: f64,
+    max_queue: usize,
+    wait_total: f64,
+    started: usize,
+    opening_scheduled: bool,
+}
+
+impl PoolRun {
+    /// Accumulate the time-weighted queue length up to `now`
+    fn observe_queue(&mut self, now: f64) {
+        self.queue_area += self.queue.len() as f64 * (now - self.last_change);
+        self.last_change = now;
+    }
+}
+
+#[derive(Default)]
+struct TaskAccumulator {
+    executions: usize,
+    duration_total: f64,
+    wait_total: f64,
+}
+
+/// State of one simulation run
+struct Run<'a> {
+    sim: &'a Simulator,
+    rng: StdRng,
+    now: f64,
+    seq: u64,
+    events: BinaryHeap<Event>,
+    cases: Vec<CaseRun>,
+    pools: Vec<PoolRun>,
+    pool_index: HashMap<String, usize>,
+    tasks: BTreeMap<String, TaskAccumulator>,
+    cycle_times: Vec<f64>,
+}
+
+impl<'a> Run<'a> {
+    fn new(sim: &'a Simulator) -> Self {
+        let pools: Vec<PoolRun> = sim
+            .config
+            .pools
+            .iter()
+            .map(|pool| PoolRun {
+                name: pool.name.clone(),
+                capacity: pool.capacity,
+                calendar: pool.calendar.clone(),
+                free: (0..pool.capacity).rev().collect(),
+                queue: VecDeque::new(),
+                busy_seconds: 0.0,
+                queue_area: 0.0,
+                last_change: 0.0,
+                max_queue: 0,
+                wait_total: 0.0,
+                started: 0,
+                opening_scheduled: false,
+            })
+            .collect();
+        let pool_index = pools
+            .iter()
+            .enumerate()
+            .map(|(index, pool)| (pool.name.clone(), index))
+            .collect();
+        Self {
+            sim,
+            rng: StdRng::seed_from_u64(sim.config.seed),
+            now: 0.0,
+            seq: 0,
+            events: BinaryHeap::new(),
+            cases: Vec::with_capacity(sim.config.cases),
+            pools,
+            pool_index,
+            tasks: BTreeMap::new(),
+            cycle_times: Vec::new(),
+        }
+    }
+
+    fn schedule(&mut self, time: f64, kind: EventKind) {
+        self.seq += 1;
+        self.events.push(Event {
+            time,
+            seq: self.seq,
+            kind,
+        });
+    }
+
+    /// Wall-clock time of a simulated instant
+    fn at(&self, seconds: f64) -> DateTime<Utc> {
+        self.sim.config.start + Duration::microseconds((seconds * 1_000_000.0).round() as i64)
+    }
+
+    fn execute(&mut self) {
+        if self.sim.config.cases > 0 {
+            self.schedule(0.0, EventKind::Arrival);
+        }
+        while let Some(event) = self.events.pop() {
+            self.now = event.time;
+            match event.kind {
+                EventKind::Arrival => self.arrive(),
+                EventKind::Complete { case, task, slot } => self.complete(case, &task, slot),
+                EventKind::PoolOpens(pool) => {
+                    self.pools[pool].opening_scheduled = false;
+                    self.dispatch(pool);
+                }
+            }
+        }
+    }
+
+    fn arrive(&mut self) {
+        let index = self.cases.len();
+        let id = CaseId(Uuid::from_u128(
+            (u128::from(self.sim.config.seed) << 64) | index as u128,
+        ));
+        self.cases.push(CaseRun {
+            id,
+            arrival: self.now,
+            received: HashMap::new(),
+            or_branches: Vec::new(),
+            steps: 0,
+            completed: false,
+            abandoned: false,
+            events: Vec::new(),
+        });
+        if self.cases.len() < self.sim.config.cases {
+            let gap = self.sim.config.inter_arrival.sample(&mut self.rng);
+            self.schedule(self.now + gap, EventKind::Arrival);
+        }
+        if let Some(start) = &self.sim.spec.start_condition {
+            self.token(index, start);
+        }
+    }
+
+    /// Deliver a token to a task or condition
+    fn token(&mut self, case: usize, node: &str) {
+        if self.cases[case].is_done() {
+            return;
+        }
+        let Some(task) = self.sim.spec.tasks.get(node) else {
+            if self.sim.spec.end_condition.as_deref() == Some(node) {
+                let run = &mut self.cases[case];
+                run.completed = true;
+                self.cycle_times.push(self.now - run.arrival);
+            } else if self.step(case) {
+                self.route(case, node, SplitType::Xor);
+            }
+            return;
+        };
+
+        let inputs = self.sim.incoming.get(node).copied().unwrap_or(0).max(1);
+        let run = &mut self.cases[case];
+        let received = run.received.entry(node.to_string()).or_insert(0);
+        *received += 1;
+        let fire = match task.join_type {
+            JoinType::Xor => {
+                *received = 0;
+                true
+            }
+            JoinType::And => {
+                let fire = *received >= inputs;
+                if fire {
+                    *received = 0;
+                }
+                fire
+            }
+            JoinType::Or => {
+                let active = run.or_branches.last().copied().unwrap_or(1).max(1);
+                let fire = *received >= active;
+                if fire {
+                    *received = 0;
+                    run.or_branches.pop();
+                }
+                fire
+            }
+            JoinType::Discriminator { quorum } => {
+                // Fire on the quorum-th token, swallow the rest of the round
+                let fire = *received == quorum.clamp(1, inputs);
+                if *received >= inputs {
+                    *received = 0;
+                }
+                fire
+            }
+        };
+        if fire && self.step(case) {
+            self.enable(case, node);
+        }
+    }
+
+    /// Count a node firing; abandons the case past the step limit
+    fn step(&mut self, case: usize) -> bool {
+        let run = &mut self.cases[case];
+        run.steps += 1;
+        if run.steps > self.sim.config.max_steps_per_case {
+            run.abandoned = true;
+        }
+        !run.abandoned
+    }
+
+    fn enable(&mut self, case: usize, task: &str) {
+        let pool = self
+            .sim
+            .config
+            .tasks
+            .get(task)
+            .and_then(|profile| profile.pool.as_ref())
+            .and_then(|pool| self.pool_index.get(pool).copied());
+        match pool {
+            Some(pool) => {
+                let now = self.now;
+                let run = &mut self.pools[pool];
+                run.observe_queue(now);
+                run.queue.push_back(WorkItem {
+                    case,
+                    task: task.to_string(),
+                    enabled_at: now,
+                });
+                run.max_queue = run.max_queue.max(run.queue.len());
+                self.dispatch(pool);
+            }
+            None => self.start(case, task, None, self.now),
+        }
+    }
+
+    /// Start queued work items while the pool has free resources and is open
+    fn dispatch(&mut self, pool: usize) {
+        let now = self.now;
+        let time = self.at(now);
+        loop {
+            let run = &mut self.pools[pool];
+            if run.queue.is_empty() || run.free.is_empty() {
+                return;
+            }
+            if let Some(calendar) = &run.calendar {
+                if !calendar.is_working_time(time) {
+                    if !run.opening_scheduled {
+                        if let Some(opens) = calendar.next_working_time(time) {
+                            run.opening_scheduled = true;
+                            let delay =
+                                (opens - time).num_microseconds().unwrap_or(0) as f64 / 1_000_000.0;
+                            self.schedule(now + delay, EventKind::PoolOpens(pool));
+                        }
+                    }
+                    return;
+                }
+            }
+            run.observe_queue(now);
+            let Some(item) = run.queue.pop_front() else {
+                return;
+            };
+            if self.cases[item.case].is_done() {
+                continue;
+            }
+            let Some(unit) = run.free.pop() else {
+                return;
+            };
+            run.wait_total += now - item.enabled_at;
+            run.started += 1;
+            self.start(item.case, &item.task, Some((pool, unit)), item.enabled_at);
+        }
+    }
+
+    fn start(&mut self, case: usize, task: &str, slot: Option<(usize, usize)>, enabled_at: f64) {
+        let duration = self
+            .sim
+            .config
+            .tasks
+            .get(task)
+            .map(|profile| &profile.duration)
+            .unwrap_or(&self.sim.config.default_duration)
+            .sample(&mut self.rng);
+
+        let stats = self.tasks.entry(task.to_string()).or_default();
+        stats.executions += 1;
+        stats.duration_total += duration;
+        stats.wait_total += self.now - enabled_at;
+
+        let resource = slot.map(|(pool, unit)| {
+            self.pools[pool].busy_seconds += duration;
+            format!("{}-{}", self.pools[pool].name, unit + 1)
+        });
+        self.log(case, task, "start", resource);
+        self.schedule(
+            self.now + duration,
+            EventKind::Complete {
+                case,
+                task: task.to_string(),
+                slot,
+            },
+        );
+    }
+
+    fn complete(&mut self, case: usize, task: &str, slot: Option<(usize, usize)>) {
+        if let Some((pool, unit)) = slot {
+            self.pools[pool].free.push(unit);
+        }
+        if !self.cases[case].is_done() {
+            let resource =
+                slot.map(|(pool, unit)| format!("{}-{}", self.pools[pool].name, unit + 1));
+            self.log(case, task, "complete", resource);
+            let split = self
+                .sim
+                .spec
+                .tasks
+                .get(task)
+                .map(|t| t.split_type)
+                .unwrap_or(SplitType::And);
+            self.route(case, task, split);
+        }
+        if let Some((pool, _)) = slot {
+            self.dispatch(pool);
+        }
+    }
+
+    /// Produce tokens on the outgoing flows of `node`
+    fn route(&mut self, case: usize, node: &str, split: SplitType) {
+        let Some(targets) = self.sim.outgoing.get(node) else {
+            return;
+        };
+        let weight = |target: &str| {
+            self.sim
+                .config
+                .branch_weights
+                .get(node)
+                .and_then(|weights| weights.get(target))
+                .copied()
+                .unwrap_or(1.0)
+        };
+        let weights: Vec<f64> = targets.iter().map(|target| weight(target)).collect();
+        let chosen: Vec<usize> = match split {
+            SplitType::And => (0..targets.len()).collect(),
+            SplitType::Xor => vec![self.choose(&weights)],
+            SplitType::Or => {
+                let mut taken: Vec<usize> = (0..targets.len())
+                    .filter(|&i| self.rng.gen::<f64>() < weights[i].min(1.0))
+                    .collect();
+                if taken.is_empty() {
+                    taken.push(self.choose(&weights));
+                }
+                self.cases[case].or_branches.push(taken.len());
+                taken
+            }
+        };
+        for index in chosen {
+            self.token(case, &targets[index]);
+        }
+    }
+
+    /// Pick one branch in proportion to its weight (uniformly if all are zero)
+    fn choose(&mut self, weights: &[f64]) -> usize {
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return self.rng.gen_range(0..weights.len());
+        }
+        let mut draw = self.rng.gen::<f64>() * total;
+        for (index, weight) in weights.iter().enumerate() {
+            if draw < *weight {
+                return index;
+            }
+            draw -= weight;
+        }
+        weights.len() - 1
+    }
+
+    fn log(&mut self, case: usize, task: &str, lifecycle: &str, resource: Option<String>) {
+        if !self.sim.config.record_log {
+            return;
+        }
+        let activity_name = self
+            .sim
+            .spec
+            .tasks
+            .get(task)
+            .map(|t| t.name.clone())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| task.to_string());
+        let timestamp = self.at(self.now);
+        self.cases[case].events.push(WorkflowEvent {
+            activity_name,
+            lifecycle: lifecycle.to_string(),
+            timestamp,
+            resource,
+            pattern_id: None,
+            parent_case: None,
+            sub_case: None,
+        });
+    }
+
+    fn report(mut self) -> SimulationReport {
+        let horizon = self.now;
+        let mut pools = BTreeMap::new();
+        for index in 0..self.pools.len() {
+            let open_seconds = match &self.pools[index].calendar {
+                Some(calendar) => self.working_seconds(calendar, horizon),
+                None => horizon,
+            };
+            let run = &mut self.pools[index];
+            run.observe_queue(horizon);
+            let available_seconds = open_seconds * run.capacity as f64;
+            pools.insert(
+                run.name.clone(),
+                PoolStats {
+                    capacity: run.capacity,
+                    utilization: ratio(run.busy_seconds, available_seconds),
+                    busy_seconds: run.busy_seconds,
+                    available_seconds,
+                    max_queue_length: run.max_queue,
+                    mean_queue_length: ratio(run.queue_area, horizon),
+                    mean_wait_seconds: ratio(run.wait_total, run.started as f64),
+                },
+            );
+        }
+
+        let tasks = self
+            .tasks
+            .iter()
+            .map(|(task, stats)| {
+                (
+                    task.clone(),
+                    TaskStats {
+                        executions: stats.executions,
+                        mean_duration_seconds: ratio(stats.duration_total, stats.executions as f64),
+                        mean_wait_seconds: ratio(stats.wait_total, stats.executions as f64),
+                    },
+                )
+            })
+            .collect();
+
+        let cases_completed = self.cycle_times.len();
+        let sla_breach_rate = self.sim.config.sla_seconds.map(|sla| {
+            let breaches = self.cycle_times.iter().filter(|t| **t > sla).count();
+            ratio(breaches as f64, cases_completed as f64)
+        });
+
+        SimulationReport {
+            cases_started: self.cases.len(),
+            cases_completed,
+            cases_incomplete: self.cases.len() - cases_completed,
+            horizon_seconds: horizon,
+            cycle_time: CycleTimeStats::from_samples(&self.cycle_times),
+            sla_breach_rate,
+            pools,
+            tasks,
+            log: if self.sim.config.record_log {
+                self.cases
+                    .into_iter()
+                    .map(|case| (case.id, case.events))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    /// Seconds inside `calendar` between the first arrival and `horizon`
+    fn working_seconds(&self, calendar: &WorkingHours, horizon: f64) -> f64 {
+        let end = self.at(horizon);
+        let mut cursor = self.sim.config.start;
+        let mut total = Duration::zero();
+        while cursor < end {
+            let next_hour = cursor
+                .duration_trunc(Duration::hours(1))
+                .map(|hour| hour + Duration::hours(1))
+                .unwrap_or(end);
+            let slot_end = next_hour.min(end);
+            if calendar.is_working_time(cursor) {
+                total = total + (slot_end - cursor);
+            }
+            cursor = slot_end;
+        }
+        total.num_microseconds().unwrap_or(0) as f64 / 1_000_000.0
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{Distribution, ResourcePool};
+    use crate::testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder};
+    use chrono::TimeZone;
+
+    fn monday_morning() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap()
+    }
+
+    /// start → a → b → end
+    fn sequence() -> WorkflowSpec {
+        WorkflowSpecBuilder::new("sequence")
+            .add_task(TaskBuilder::new("a", "Receive").build())
+            .add_task(TaskBuilder::new("b", "Approve").build())
+            .add_flow("start", "a")
+            .add_flow("a", "b")
+            .add_flow("b", "end")
+            .with_start_condition("start")
+            .with_end_condition("end")
+            .build()
+    }
+
+    /// start → triage (XOR) → {fast, slow} → end
+    fn choice() -> WorkflowSpec {
+        WorkflowSpecBuilder::new("choice")
+            .add_task(
+                TaskBuilder::new("triage", "Triage")
+                    .with_split_type(SplitType::Xor)
+                    .build(),
+            )
+            .add_task(TaskBuilder::new("fast", "Fast track").build())
+            .add_task(TaskBuilder::new("slow", "Full review").build())
+            .add_flow("start", "triage")
+            .add_flow("triage", "fast")
+            .add_flow("triage", "slow")
+            .add_flow("fast", "end")
+            .add_flow("slow", "end")
+            .with_start_condition("start")
+            .with_end_condition("end")
+            .build()
+    }
+
+    #[test]
+    fn test_constant_sequence_cycle_time() {
+        let config = SimulationConfig::new(50)
+            .with_inter_arrival(Distribution::constant(1000.0))
+            .with_task_duration("a", Distribution::constant(60.0))
+            .with_task_duration("b", Distribution::constant(90.0))
+            .with_start(monday_morning());
+        let report = Simulator::new(sequence(), config).unwrap().run();
+
+        assert_eq!(report.cases_started, 50);
+        assert_eq!(report.cases_completed, 50);
+        assert_eq!(report.cases_incomplete, 0);
+        assert_eq!(report.cycle_time.p50, 150.0);
+        assert_eq!(report.cycle_time.p99, 150.0);
+        assert_eq!(report.tasks["a"].executions, 50);
+        assert_eq!(report.tasks["b"].mean_duration_seconds, 90.0);
+    }
+
+    #[test]
+    fn test_branch_weights_drive_xor_choice() {
+        let config = SimulationConfig::new(2000)
+            .with_branch_weight("triage", "fast", 4.0)
+            .with_branch_weight("triage", "slow", 1.0)
+            .with_seed(7)
+            .with_log(false);
+        let report = Simulator::new(choice(), config).unwrap().run();
+
+        assert_eq!(report.cases_completed, 2000);
+        let fast = report.tasks["fast"].executions as f64;
+        assert!((1500.0..1700.0).contains(&fast), "fast executions {}", fast);
+        assert_eq!(
+            report.tasks["fast"].executions + report.tasks["slow"].executions,
+            2000
+        );
+        assert!(report.log.is_empty());
+    }
+
+    #[test]
+    fn test_and_join_waits_for_slowest_branch() {
+        let spec = WorkflowSpecBuilder::new("parallel")
+            .add_task(TaskBuilder::new("split", "Split").build())
+            .add_task(TaskBuilder::new("left", "Left").build())
+            .add_task(TaskBuilder::new("right", "Right").build())
+            .add_task(TaskBuilder::new("join", "Join").build())
+            .add_flow("start", "split")
+            .add_flow("split", "left")
+            .add_flow("split", "right")
+            .add_flow("left", "join")
+            .add_flow("right", "join")
+            .add_flow("join", "end")
+            .with_start_condition("start")
+            .with_end_condition("end")
+            .build();
+        let config = SimulationConfig::new(10)
+            .with_default_duration(Distribution::constant(0.0))
+            .with_task_duration("left", Distribution::constant(60.0))
+            .with_task_duration("right", Distribution::constant(120.0));
+        let report = Simulator::new(spec, config).unwrap().run();
+
+        assert_eq!(report.cases_completed, 10);
+        assert_eq!(report.cycle_time.max, 120.0);
+        assert_eq!(report.tasks["join"].executions, 10);
+    }
+
+    #[test]
+    fn test_pool_capacity_creates_queues_and_sla_breaches() {
+        let scenario = |capacity: usize| {
+            SimulationConfig::new(100)
+                .with_inter_arrival(Distribution::constant(30.0))
+                .with_default_duration(Distribution::constant(0.0))
+                .with_task_duration("a", Distribution::constant(60.0))
+                .with_task_pool("a", "clerks")
+                .with_pool(ResourcePool::new("clerks", capacity))
+                .with_sla(std::time::Duration::from_secs(300))
+        };
+
+        let overloaded = Simulator::new(sequence(), scenario(1)).unwrap().run();
+        let clerks = &overloaded.pools["clerks"];
+        assert!(clerks.utilization > 0.99, "{:?}", clerks);
+        assert!(clerks.max_queue_length > 10);
+        assert!(clerks.mean_wait_seconds > 0.0);
+        assert!(overloaded.sla_breach_rate.unwrap() > 0.5);
+
+        let staffed = Simulator::new(sequence(), scenario(3)).unwrap().run();
+        let clerks = &staffed.pools["clerks"];
+        assert_eq!(clerks.max_queue_length, 1);
+        assert_eq!(clerks.mean_wait_seconds, 0.0);
+        assert!((clerks.utilization - 0.67).abs() < 0.02, "{:?}", clerks);
+        assert_eq!(staffed.sla_breach_rate, Some(0.0));
+        assert_eq!(staffed.cycle_time.max, 60.0);
+    }
+
+    #[test]
+    fn test_pool_calendar_defers_work_to_next_opening() {
+        // Monday 16:30; the second case finds the only clerk busy until 17:30
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 16, 30, 0).unwrap();
+        let config = SimulationConfig::new(2)
+            .with_inter_arrival(Distribution::constant(1.0))
+            .with_default_duration(Distribution::constant(0.0))
+            .with_task_duration("a", Distribution::constant(3600.0))
+            .with_task_pool("a", "clerks")
+            .with_pool(ResourcePool::new("clerks", 1).with_calendar(WorkingHours::default()))
+            .with_start(start);
+        let report = Simulator::new(sequence(), config).unwrap().run();
+
+        assert_eq!(report.cases_completed, 2);
+        // Tuesday 09:00 + 1h, measured from Monday 16:30:01
+        assert_eq!(report.cycle_time.max, 17.5 * 3600.0 - 1.0);
+        let resumed = &report.log[1].1[0];
+        assert_eq!(
+            resumed.timestamp,
+            Utc.with_ymd_and_hms(2024, 1, 2, 9, 0, 0).unwrap()
+        );
+        assert_eq!(resumed.resource.as_deref(), Some("clerks-1"));
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_run_and_exports_xes() {
+        let scenario = || {
+            SimulationConfig::new(20)
+                .with_task_duration(
+                    "a",
+                    Distribution::Triangular {
+                        min: 30.0,
+                        mode: 60.0,
+                        max: 300.0,
+                    },
+                )
+                .with_task_duration(
+                    "b",
+                    Distribution::LogNormal {
+                        mean: 120.0,
+                        std_dev: 60.0,
+                    },
+                )
+                .with_start(monday_morning())
+                .with_seed(42)
+        };
+        let first = Simulator::new(sequence(), scenario()).unwrap().run();
+        let second = Simulator::new(sequence(), scenario()).unwrap().run();
+
+        assert_eq!(first.cycle_time, second.cycle_time);
+        let xes = first.to_xes();
+        assert_eq!(xes, second.to_xes());
+        assert_eq!(xes.matches("<trace>").count(), 20);
+        assert!(xes.contains(r#"<string key="concept:name" value="Approve"/>"#));
+    }
+
+    #[test]
+    fn test_invalid_scenarios_are_rejected() {
+        let unknown_pool = SimulationConfig::new(1).with_task_pool("a", "nobody");
+        assert!(Simulator::new(sequence(), unknown_pool).is_err());
+
+        let unknown_flow = SimulationConfig::new(1).with_branch_weight("a", "end", 1.0);
+        assert!(Simulator::new(sequence(), unknown_flow).is_err());
+
+        let closed = SimulationConfig::new(1).with_pool(
+            ResourcePool::new("night", 1).with_calendar(WorkingHours {
+                start_hour: 9,
+                end_hour: 17,
+                working_days: vec![],
+            }),
+        );
+        assert!(Simulator::new(sequence(), closed).is_err());
+    }
+}
@@ -1,9 +1,12 @@
 //! Snapshot Management System
 //!
 //! Σ versioning with SHA3 hashing, atomic pointer updates, and rollback mechanism.
+//! Workflow specification versions are recorded as Σ snapshots per spec family.
 
 pub mod sigma_versioning;
+pub mod spec_versions;
 
 pub use sigma_versioning::{
     Snapshot, SnapshotId, SnapshotManifest, SnapshotMetadata, SnapshotVersioning,
 };
+pub use spec_versions::{compare_versions, SpecVersion, SpecVersionRegistry};
@@ -0,0 +1,228 @@
+//! Workflow specification versions
+//!
+//! Specs sharing an IRI form a family whose versions are registered side by
+//! side. Every registered version is kept as a Σ snapshot of the spec, chained
+//! to the previously registered version, so each version carries a content
+//! hash and its lineage.
+
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{WorkflowSpec, WorkflowSpecId};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use super::sigma_versioning::{Snapshot, SnapshotId, SnapshotVersioning};
+
+/// Registered version of a workflow specification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecVersion {
+    /// Spec family IRI
+    pub iri: String,
+    /// Version string (e.g. "1.2.0")
+    pub version: String,
+    /// Spec ID registered for this version
+    pub spec_id: WorkflowSpecId,
+    /// Σ snapshot of the registered spec
+    pub snapshot_id: SnapshotId,
+    /// Snapshot of the version registered before this one
+    pub parent_snapshot_id: Option<SnapshotId>,
+}
+
+/// Versions of one spec family
+#[derive(Default)]
+struct SpecFamily {
+    snapshots: SnapshotVersioning,
+    versions: Vec<SpecVersion>,
+}
+
+/// Registry of spec versions by family IRI
+#[derive(Default)]
+pub struct SpecVersionRegistry {
+    families: RwLock<HashMap<String, SpecFamily>>,
+}
+
+impl SpecVersionRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a registered spec as a version of its family
+    ///
+    /// Unversioned specs (no IRI or no version) are ignored. A version can only
+    /// be registered under one spec ID; re-registering the same spec ID records
+    /// a new snapshot if the spec changed.
+    pub async fn record(&self, spec: &WorkflowSpec) -> WorkflowResult<Option<SpecVersion>> {
+        let (iri, version) = match (spec.iri.as_ref(), spec.version.as_ref()) {
+            (Some(iri), Some(version)) => (iri, version),
+            _ => return Ok(None),
+        };
+        let content = serde_json::to_value(spec).map_err(|e| {
+            WorkflowError::Internal(format!("Failed to serialize spec {}: {}", spec.id, e))
+        })?;
+
+        let mut families = self.families.write().await;
+        let family = families.entry(iri.clone()).or_default();
+        let existing = family.versions.iter().position(|v| &v.version == version);
+        if let Some(index) = existing {
+            let registered = &family.versions[index];
+            if registered.spec_id != spec.id {
+                return Err(WorkflowError::Validation(format!(
+                    "Version {} of {} is already registered as spec {}",
+                    version, iri, registered.spec_id
+                )));
+            }
+            if Snapshot::new(content.clone(), None, 0).id == registered.snapshot_id {
+                return Ok(Some(registered.clone()));
+            }
+        }
+
+        // The empty root snapshot of the family is not a version
+        let parent_snapshot_id = family
+            .snapshots
+            .get_current_snapshot()
+            .await
+            .filter(|snapshot| snapshot.metadata.version > 0)
+            .map(|snapshot| snapshot.id);
+        let snapshot_id = family.snapshots.create_snapshot(content).await?;
+        let spec_version = SpecVersion {
+            iri: iri.clone(),
+            version: version.clone(),
+            spec_id: spec.id,
+            snapshot_id,
+            parent_snapshot_id,
+        };
+        match existing {
+            Some(index) => family.versions[index] = spec_version.clone(),
+            None => family.versions.push(spec_version.clone()),
+        }
+        Ok(Some(spec_version))
+    }
+
+    /// List the versions of a spec family, oldest first
+    pub async fn versions(&self, iri: &str) -> Vec<SpecVersion> {
+        let families = self.families.read().await;
+        let mut versions = families
+            .get(iri)
+            .map(|family| family.versions.clone())
+            .unwrap_or_default();
+        versions.sort_by(|a, b| compare_versions(Some(&a.version), Some(&b.version)));
+        versions
+    }
+
+    /// Latest version of a spec family
+    pub async fn latest(&self, iri: &str) -> Option<SpecVersion> {
+        self.versions(iri).await.pop()
+    }
+
+    /// Registered version of a spec ID
+    pub async fn version_of(&self, spec_id: WorkflowSpecId) -> Option<SpecVersion> {
+        let families = self.families.read().await;
+        families
+            .values()
+            .flat_map(|family| family.versions.iter())
+            .find(|v| v.spec_id == spec_id)
+            .cloned()
+    }
+
+    /// Σ snapshot of a registered version
+    pub async fn snapshot(&self, version: &SpecVersion) -> Option<Snapshot> {
+        let families = self.families.read().await;
+        let family = families.get(&version.iri)?;
+        family.snapshots.get_snapshot(&version.snapshot_id).await
+    }
+}
+
+/// Order versions by their dot-separated numeric components
+///
+/// Unversioned specs sort before versioned ones; non-numeric components fall
+/// back to string comparison.
+pub fn compare_versions(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let mut a_parts = a.split('.');
+            let mut b_parts = b.split('.');
+            loop {
+                match (a_parts.next(), b_parts.next()) {
+                    (Some(x), Some(y)) => {
+                        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                            (Ok(x), Ok(y)) => x.cmp(&y),
+                            _ => x.cmp(y),
+                        };
+                        if ordering != Ordering::Equal {
+                            return ordering;
+                        }
+                    }
+                    (Some(_), None) => return Ordering::Greater,
+                    (None, Some(_)) => return Ordering::Less,
+                    (None, None) => return Ordering::Equal,
+                }
+            }
+        }
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versioned(version: &str) -> WorkflowSpec {
+        WorkflowSpec {
+            id: WorkflowSpecId::new(),
+            name: "order".to_string(),
+            tasks: HashMap::new(),
+            conditions: HashMap::new(),
+            flows: Vec::new(),
+            start_condition: None,
+            end_condition: None,
+            source_turtle: None,
+            variables: Vec::new(),
+            iri: Some("http://example.org/order".to_string()),
+            version: Some(version.to_string()),
+            subnets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare_versions_is_numeric() {
+        assert_eq!(
+            compare_versions(Some("1.10.0"), Some("1.9.2")),
+            Ordering::Greater
+        );
+        assert_eq!(compare_versions(Some("2.0"), Some("2.0.1")), Ordering::Less);
+        assert_eq!(compare_versions(Some("1.0"), Some("1.0")), Ordering::Equal);
+        assert_eq!(compare_versions(None, Some("0.1")), Ordering::Less);
+    }
+
+    #[tokio::test]
+    async fn test_versions_are_chained_snapshots() {
+        let registry = SpecVersionRegistry::new();
+        let v2 = versioned("2.0");
+        let v10 = versioned("10.0");
+
+        let first = registry.record(&v2).await.unwrap().unwrap();
+        let second = registry.record(&v10).await.unwrap().unwrap();
+
+        assert_eq!(first.parent_snapshot_id, None);
+        assert_eq!(second.parent_snapshot_id, Some(first.snapshot_id.clone()));
+        let latest = registry.latest("http://example.org/order").await.unwrap();
+        assert_eq!(latest.spec_id, v10.id);
+        let snapshot = registry.snapshot(&first).await.unwrap();
+        assert!(snapshot.verify_integrity());
+    }
+
+    #[tokio::test]
+    async fn test_version_is_bound_to_one_spec() {
+        let registry = SpecVersionRegistry::new();
+        let spec = versioned("1.0");
+        registry.record(&spec).await.unwrap();
+
+        assert_eq!(
+            registry.record(&spec).await.unwrap().unwrap().spec_id,
+            spec.id
+        );
+        assert!(registry.record(&versioned("1.0")).await.is_err());
+    }
+}
@@ -5,6 +5,7 @@
 use crate::case::{Case, CaseId};
 use crate::error::WorkflowResult;
 use crate::parser::{WorkflowSpec, WorkflowSpecId};
+use crate::state::recovery::CaseCheckpoint;
 #[cfg(feature = "storage")]
 use crate::state::store::StateStore;
 use std::collections::HashMap;
@@ -60,6 +61,73 @@ pub enum StateEvent {
         duration_ms: u64,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+    /// Cancellation region applied on completion of a task
+    RegionCancelled {
+        case_id: CaseId,
+        /// Task owning the cancellation region
+        task_id: String,
+        /// Tasks and conditions whose tokens were removed
+        cancelled: Vec<String>,
+        /// Work items withdrawn
+        withdrawn_work_items: Vec<String>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Sub-case launched by a composite task (recorded in both case histories)
+    SubCaseStarted {
+        /// Parent case
+        case_id: CaseId,
+        /// Composite task
+        task_id: String,
+        child_case_id: CaseId,
+        child_spec_id: WorkflowSpecId,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Sub-case of a composite task finished (recorded in both case histories)
+    SubCaseFinished {
+        /// Parent case
+        case_id: CaseId,
+        /// Composite task
+        task_id: String,
+        child_case_id: CaseId,
+        /// Final state of the sub-case
+        child_state: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Case migrated to another version of its specification
+    CaseMigrated {
+        case_id: CaseId,
+        from_spec_id: WorkflowSpecId,
+        to_spec_id: WorkflowSpecId,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Case checkpointed (marking, data, work items, timers) for crash recovery
+    CaseCheckpointed {
+        case_id: CaseId,
+        checkpoint: Box<CaseCheckpoint>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Exception raised for a case or work item
+    ExceptionRaised {
+        case_id: CaseId,
+        task_id: Option<String>,
+        work_item_id: Option<String>,
+        /// Exception kind (`item_pre_constraint`, `item_timeout`, ...)
+        exception: String,
+        /// Exception rule whose exlet handles the exception (`None` if unhandled)
+        rule_id: Option<String>,
+        exlet: Option<String>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Exlet step executed while handling an exception
+    ExletStep {
+        case_id: CaseId,
+        task_id: Option<String>,
+        exlet: String,
+        step: String,
+        success: bool,
+        detail: Option<String>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
 }
 
 impl StateEvent {
@@ -71,6 +139,13 @@ impl StateEvent {
             StateEvent::CaseStateChanged { case_id, .. } => Some(*case_id),
             StateEvent::TaskStarted { case_id, .. } => Some(*case_id),
             StateEvent::TaskCompleted { case_id, .. } => Some(*case_id),
+            StateEvent::RegionCancelled { case_id, .. } => Some(*case_id),
+            StateEvent::SubCaseStarted { case_id, .. } => Some(*case_id),
+            StateEvent::SubCaseFinished { case_id, .. } => Some(*case_id),
+            StateEvent::CaseMigrated { case_id, .. } => Some(*case_id),
+            StateEvent::CaseCheckpointed { case_id, .. } => Some(*case_id),
+            StateEvent::ExceptionRaised { case_id, .. } => Some(*case_id),
+            StateEvent::ExletStep { case_id, .. } => Some(*case_id),
         }
     }
 
@@ -82,6 +157,13 @@ impl StateEvent {
             StateEvent::CaseStateChanged { timestamp, .. } => *timestamp,
             StateEvent::TaskStarted { timestamp, .. } => *timestamp,
             StateEvent::TaskCompleted { timestamp, .. } => *timestamp,
+            StateEvent::RegionCancelled { timestamp, .. } => *timestamp,
+            StateEvent::SubCaseStarted { timestamp, .. } => *timestamp,
+            StateEvent::SubCaseFinished { timestamp, .. } => *timestamp,
+            StateEvent::CaseMigrated { timestamp, .. } => *timestamp,
+            StateEvent::CaseCheckpointed { timestamp, .. } => *timestamp,
+            StateEvent::ExceptionRaised { timestamp, .. } => *timestamp,
+            StateEvent::ExletStep { timestamp, .. } => *timestamp,
         }
     }
 }
@@ -312,6 +394,184 @@ impl StateManager {
         Ok(())
     }
 
+    /// Log cancellation region event
+    pub async fn log_region_cancelled(
+        &self,
+        case_id: CaseId,
+        task_id: String,
+        cancelled: Vec<String>,
+        withdrawn_work_items: Vec<String>,
+    ) -> WorkflowResult<()> {
+        let event = StateEvent::RegionCancelled {
+            case_id,
+            task_id,
+            cancelled,
+            withdrawn_work_items,
+            timestamp: chrono::Utc::now(),
+        };
+        {
+            let mut log = self.event_log.write().await;
+            log.push(event.clone());
+        }
+        // Persist event to store (for audit trail)
+        self.store.save_case_history_event(&case_id, &event)?;
+        Ok(())
+    }
+
+    /// Log exception raised event
+    pub async fn log_exception_raised(
+        &self,
+        case_id: CaseId,
+        task_id: Option<String>,
+        work_item_id: Option<String>,
+        exception: String,
+        rule: Option<(String, String)>,
+    ) -> WorkflowResult<()> {
+        let (rule_id, exlet) = rule.unzip();
+        let event = StateEvent::ExceptionRaised {
+            case_id,
+            task_id,
+            work_item_id,
+            exception,
+            rule_id,
+            exlet,
+            timestamp: chrono::Utc::now(),
+        };
+        {
+            let mut log = self.event_log.write().await;
+            log.push(event.clone());
+        }
+        // Persist event to store (for audit trail)
+        self.store.save_case_history_event(&case_id, &event)?;
+        Ok(())
+    }
+
+    /// Log exlet step event
+    pub async fn log_exlet_step(
+        &self,
+        case_id: CaseId,
+        task_id: Option<String>,
+        exlet: String,
+        step: String,
+        success: bool,
+        detail: Option<String>,
+    ) -> WorkflowResult<()> {
+        let event = StateEvent::ExletStep {
+            case_id,
+            task_id,
+            exlet,
+            step,
+            success,
+            detail,
+            timestamp: chrono::Utc::now(),
+        };
+        {
+            let mut log = self.event_log.write().await;
+            log.push(event.clone());
+        }
+        // Persist event to store (for audit trail)
+        self.store.save_case_history_event(&case_id, &event)?;
+        Ok(())
+    }
+
+    /// Log sub-case started event (in the parent and the sub-case history)
+    pub async fn log_subcase_started(
+        &self,
+        case_id: CaseId,
+        task_id: String,
+        child_case_id: CaseId,
+        child_spec_id: WorkflowSpecId,
+    ) -> WorkflowResult<()> {
+        let event = StateEvent::SubCaseStarted {
+            case_id,
+            task_id,
+            child_case_id,
+            child_spec_id,
+            timestamp: chrono::Utc::now(),
+        };
+        self.log_subcase_event(case_id, child_case_id, event).await
+    }
+
+    /// Log sub-case finished event (in the parent and the sub-case history)
+    pub async fn log_subcase_finished(
+        &self,
+        case_id: CaseId,
+        task_id: String,
+        child_case_id: CaseId,
+        child_state: String,
+    ) -> WorkflowResult<()> {
+        let event = StateEvent::SubCaseFinished {
+            case_id,
+            task_id,
+            child_case_id,
+            child_state,
+            timestamp: chrono::Utc::now(),
+        };
+        self.log_subcase_event(case_id, child_case_id, event).await
+    }
+
+    /// Log case migration event
+    pub async fn log_case_migrated(
+        &self,
+        case_id: CaseId,
+        from_spec_id: WorkflowSpecId,
+        to_spec_id: WorkflowSpecId,
+    ) -> WorkflowResult<()> {
+        let event = StateEvent::CaseMigrated {
+            case_id,
+            from_spec_id,
+            to_spec_id,
+            timestamp: chrono::Utc::now(),
+        };
+        {
+            let mut log = self.event_log.write().await;
+            log.push(event.clone());
+        }
+        // Persist event to store (for audit trail)
+        self.store.save_case_history_event(&case_id, &event)?;
+        Ok(())
+    }
+
+    async fn log_subcase_event(
+        &self,
+        case_id: CaseId,
+        child_case_id: CaseId,
+        event: StateEvent,
+    ) -> WorkflowResult<()> {
+        {
+            let mut log = self.event_log.write().await;
+            log.push(event.clone());
+        }
+        // Persist event to both histories so each case links to the other
+        self.store.save_case_history_event(&case_id, &event)?;
+        self.store.save_case_history_event(&child_case_id, &event)?;
+        Ok(())
+    }
+
+    /// Log case checkpoint event
+    ///
+    /// Checkpoints are only persisted to the case history (not kept in the
+    /// in-memory event log) since they carry the full case snapshot. Each new
+    /// checkpoint replaces the previous one of the case.
+    pub async fn log_checkpoint(&self, checkpoint: CaseCheckpoint) -> WorkflowResult<()> {
+        let case_id = checkpoint.case.id;
+        let event = StateEvent::CaseCheckpointed {
+            case_id,
+            checkpoint: Box::new(checkpoint),
+            timestamp: chrono::Utc::now(),
+        };
+        self.store.save_case_checkpoint(&case_id, &event)
+    }
+
+    /// Drop a case from the cache and its events from the in-memory event log
+    ///
+    /// Used when case data is erased, so no stale copy is served afterwards.
+    pub async fn forget_case(&self, case_id: &CaseId) {
+        self.case_cache.write().await.remove(case_id);
+        let mut log = self.event_log.write().await;
+        log.retain(|event| event.case_id() != Some(*case_id));
+    }
+
     /// Clear cache (for testing/debugging)
     pub async fn clear_cache(&self) {
         let mut spec_cache = self.spec_cache.write().await;
@@ -4,11 +4,16 @@
 //! - State manager with event sourcing
 //! - State caching
 //! - State snapshots
+//! - Crash recovery (case checkpoints and history replay)
 
 pub mod manager;
+pub mod recovery;
 #[cfg(feature = "storage")]
 mod store;
 
 pub use manager::{StateEvent, StateManager};
+pub use recovery::{
+    CaseCheckpoint, CaseMarking, CaseReplay, RecoveredCase, RecoveryOutcome, RecoveryReport,
+};
 #[cfg(feature = "storage")]
 pub use store::StateStore;
@@ -0,0 +1,489 @@
+//! Crash recovery - case checkpoints and event-log replay
+//!
+//! Running cases are checkpointed into the case history after every task:
+//! - Token marking of the workflow net (pending nodes, join counters, executing tasks)
+//! - Case data
+//! - Work items and pending timers of the case
+//!
+//! On startup the engine folds each case history into a [`CaseReplay`] and resumes
+//! every non-terminal case from its last checkpoint. Tasks that completed after the
+//! checkpoint are routed without re-execution; tasks that were started but not
+//! completed are re-executed.
+
+use crate::case::{Case, CaseId, CaseState};
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::{JoinType, Task, WorkflowSpec};
+use crate::services::timer::PendingTimer;
+use crate::services::work_items::WorkItem;
+use crate::state::manager::StateEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Token marking of a running case
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CaseMarking {
+    /// Nodes (tasks or conditions) holding a token, in firing order
+    pub pending: VecDeque<String>,
+    /// Nodes already fired
+    pub visited: HashSet<String>,
+    /// Tasks started but not yet routed
+    pub executing: HashSet<String>,
+    /// Completed tasks
+    pub completed_tasks: HashSet<String>,
+    /// Incoming tokens required per task (join semantics)
+    pub required_tokens: HashMap<String, usize>,
+    /// Incoming tokens received per task
+    pub received_tokens: HashMap<String, usize>,
+    /// Active branches per OR-join task
+    pub or_join_branches: HashMap<String, HashSet<String>>,
+}
+
+impl CaseMarking {
+    /// Initial marking: a single token in the start condition
+    pub fn initial(spec: &WorkflowSpec) -> WorkflowResult<Self> {
+        let start_condition_id = spec
+            .start_condition
+            .as_ref()
+            .ok_or_else(|| WorkflowError::InvalidSpecification("No start condition".into()))?;
+
+        let mut marking = Self::default();
+        marking.pending.push_back(start_condition_id.clone());
+
+        for (task_id, task) in &spec.tasks {
+            marking
+                .required_tokens
+                .insert(task_id.clone(), join_requirement(task));
+            marking.received_tokens.insert(task_id.clone(), 0);
+        }
+
+        Ok(marking)
+    }
+
+    /// Nodes holding a token, waiting on a join or executing (sorted)
+    pub fn marked_nodes(&self) -> Vec<String> {
+        let mut nodes: Vec<String> = self
+            .pending
+            .iter()
+            .chain(self.executing.iter())
+            .cloned()
+            .collect();
+        let waiting = self
+            .received_tokens
+            .iter()
+            .filter(|(_, received)| **received > 0)
+            .map(|(task_id, _)| task_id)
+            .chain(self.or_join_branches.keys())
+            .filter(|task_id| !self.completed_tasks.contains(*task_id));
+        nodes.extend(waiting.cloned());
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+
+    /// Carry the marking over to another version of the net
+    ///
+    /// Join requirements are recomputed from the target net; tasks keep their
+    /// received tokens and active OR-join branches, tasks only present in the
+    /// target start without tokens. Tasks that become enabled under the new
+    /// join requirements are queued.
+    pub fn migrate(&mut self, spec: &WorkflowSpec) {
+        self.required_tokens
+            .retain(|task_id, _| spec.tasks.contains_key(task_id));
+        self.received_tokens
+            .retain(|task_id, _| spec.tasks.contains_key(task_id));
+        self.or_join_branches
+            .retain(|task_id, _| spec.tasks.contains_key(task_id));
+
+        let mut task_ids: Vec<&String> = spec.tasks.keys().collect();
+        task_ids.sort();
+        for task_id in task_ids {
+            let task = &spec.tasks[task_id];
+            let required = match self.or_join_branches.get(task_id) {
+                Some(active) if matches!(task.join_type, JoinType::Or) => active.len(),
+                _ => join_requirement(task),
+            };
+            self.required_tokens.insert(task_id.clone(), required);
+            let received = *self.received_tokens.entry(task_id.clone()).or_insert(0);
+
+            let idle = !self.completed_tasks.contains(task_id)
+                && !self.executing.contains(task_id)
+                && !self.pending.contains(task_id);
+            if idle && received > 0 && received >= required {
+                self.pending.push_back(task_id.clone());
+            }
+        }
+    }
+
+    /// Check whether a task has received enough tokens to fire
+    pub fn is_enabled(&self, task_id: &str) -> bool {
+        let required = self.required_tokens.get(task_id).copied().unwrap_or(0);
+        let received = self.received_tokens.get(task_id).copied().unwrap_or(0);
+        required == 0 || received >= required
+    }
+
+    /// Produce a token on `target` from node `from`
+    pub fn produce(&mut self, spec: &WorkflowSpec, from: &str, target: &str) {
+        if let Some(target_task) = spec.tasks.get(target) {
+            // For OR joins, track which branches are active
+            if matches!(target_task.join_type, JoinType::Or) {
+                let active = self.or_join_branches.entry(target.to_string()).or_default();
+                active.insert(from.to_string());
+                let required = active.len();
+                self.required_tokens.insert(target.to_string(), required);
+            }
+
+            let received = self.received_tokens.entry(target.to_string()).or_insert(0);
+            *received += 1;
+
+            let required = self.required_tokens.get(target).copied().unwrap_or(0);
+            if *received >= required {
+                self.pending.push_back(target.to_string());
+            }
+        } else {
+            // Target is a condition
+            self.pending.push_back(target.to_string());
+        }
+    }
+
+    /// Remove the tokens held by a cancellation region
+    ///
+    /// Clears pending tokens, received join tokens and active OR-join branches of
+    /// the target tasks and conditions. Returns the targets that held a token or
+    /// were executing (sorted).
+    pub fn cancel_region(&mut self, targets: &[String]) -> Vec<String> {
+        let mut cancelled = Vec::new();
+        for target in targets {
+            let queued = self.pending.len();
+            self.pending.retain(|node| node != target);
+            let mut held_token = self.pending.len() != queued;
+
+            if let Some(received) = self.received_tokens.get_mut(target) {
+                held_token |= *received > 0;
+                *received = 0;
+            }
+            self.or_join_branches.remove(target);
+            held_token |= self.executing.remove(target);
+
+            if held_token {
+                cancelled.push(target.clone());
+            }
+        }
+        cancelled.sort();
+        cancelled.dedup();
+        cancelled
+    }
+
+    /// Put tasks that were executing at checkpoint time back in front of the queue
+    ///
+    /// Returns the re-enabled task IDs (sorted).
+    pub fn requeue_executing(&mut self) -> Vec<String> {
+        let mut tasks: Vec<String> = self.executing.drain().collect();
+        tasks.sort();
+        for task_id in tasks.iter().rev() {
+            self.visited.remove(task_id);
+            if !self.pending.contains(task_id) {
+                self.pending.push_front(task_id.clone());
+            }
+        }
+        tasks
+    }
+}
+
+/// Incoming tokens a task needs to fire
+///
+/// AND join needs all incoming flows, XOR join needs one, OR join needs all
+/// active branches (adjusted as branches are enabled).
+fn join_requirement(task: &Task) -> usize {
+    if task.incoming_flows.is_empty() {
+        0
+    } else if matches!(task.join_type, JoinType::And | JoinType::Or) {
+        task.incoming_flows.len()
+    } else {
+        1
+    }
+}
+
+/// Durable snapshot of a running case (persisted as a case history event)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseCheckpoint {
+    /// Case (state and data)
+    pub case: Case,
+    /// Token marking
+    pub marking: CaseMarking,
+    /// Work items of the case
+    pub work_items: Vec<WorkItem>,
+    /// Pending timers of the case
+    pub timers: Vec<PendingTimer>,
+}
+
+/// Result of folding a case history
+#[derive(Debug, Clone, Default)]
+pub struct CaseReplay {
+    /// Number of events replayed
+    pub events: usize,
+    /// Last case state recorded in the history
+    pub last_state: Option<String>,
+    /// Last checkpoint
+    pub checkpoint: Option<CaseCheckpoint>,
+    /// Tasks started (or executing at checkpoint time) but never completed
+    pub in_flight: Vec<String>,
+    /// Tasks completed after the last checkpoint (must not be re-executed)
+    pub completed_since_checkpoint: HashSet<String>,
+}
+
+impl CaseReplay {
+    /// Fold case history events (sorted by timestamp)
+    pub fn from_events(events: &[StateEvent]) -> Self {
+        let mut replay = Self {
+            events: events.len(),
+            ..Self::default()
+        };
+        let mut started: Vec<String> = Vec::new();
+
+        for event in events {
+            match event {
+                StateEvent::CaseCheckpointed { checkpoint, .. } => {
+                    started = checkpoint.marking.executing.iter().cloned().collect();
+                    started.sort();
+                    replay.completed_since_checkpoint.clear();
+                    replay.checkpoint = Some((**checkpoint).clone());
+                }
+                StateEvent::TaskStarted { task_id, .. } => {
+                    if !started.contains(task_id) {
+                        started.push(task_id.clone());
+                    }
+                }
+                StateEvent::TaskCompleted { task_id, .. } => {
+                    started.retain(|t| t != task_id);
+                    replay.completed_since_checkpoint.insert(task_id.clone());
+                }
+                StateEvent::RegionCancelled { cancelled, .. } => {
+                    // Cancelled tasks must not be re-executed on recovery
+                    started.retain(|t| !cancelled.contains(t));
+                }
+                StateEvent::CaseStateChanged { new_state, .. } => {
+                    replay.last_state = Some(new_state.clone());
+                }
+                StateEvent::CaseCreated { .. }
+                | StateEvent::SpecRegistered { .. }
+                | StateEvent::SubCaseStarted { .. }
+                | StateEvent::SubCaseFinished { .. }
+                | StateEvent::CaseMigrated { .. }
+                | StateEvent::ExceptionRaised { .. }
+                | StateEvent::ExletStep { .. } => {}
+            }
+        }
+
+        replay.in_flight = started;
+        replay
+    }
+
+    /// Check whether the history ends in a terminal case state
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.last_state.as_deref(),
+            Some("completed") | Some("cancelled") | Some("failed")
+        )
+    }
+}
+
+/// Outcome of recovering a single case
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecoveryOutcome {
+    /// Case restored into the engine (not running, nothing to resume)
+    Restored,
+    /// Case resumed and still running (waiting on external input)
+    Resumed,
+    /// Case resumed and ran to completion
+    Completed,
+    /// Recovery or resumption failed
+    Failed(String),
+}
+
+/// Recovery details for a single case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveredCase {
+    /// Case ID
+    pub case_id: CaseId,
+    /// Case state after recovery
+    pub state: CaseState,
+    /// Outcome
+    pub outcome: RecoveryOutcome,
+    /// Whether the case was rebuilt from a checkpoint (otherwise from the initial marking)
+    pub from_checkpoint: bool,
+    /// Tasks started but not completed before the crash (re-executed)
+    pub reexecuted_tasks: Vec<String>,
+    /// Tasks completed before the crash but not yet routed (routed without re-execution)
+    pub deduplicated_tasks: Vec<String>,
+    /// Work items restored
+    pub restored_work_items: usize,
+    /// Timers restored
+    pub restored_timers: usize,
+}
+
+/// Recovery report returned by `WorkflowEngine::recover_cases`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    /// Persisted cases scanned
+    pub scanned: usize,
+    /// Cases skipped because they reached a terminal state
+    pub skipped_terminal: usize,
+    /// Cases skipped because they are already live in the engine
+    pub skipped_live: usize,
+    /// Recovered cases
+    pub cases: Vec<RecoveredCase>,
+    /// Recovery duration (milliseconds)
+    pub duration_ms: u64,
+}
+
+impl RecoveryReport {
+    /// Number of cases recovered without error
+    pub fn recovered_count(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|c| !matches!(c.outcome, RecoveryOutcome::Failed(_)))
+            .count()
+    }
+
+    /// Number of cases that failed to recover
+    pub fn failed_count(&self) -> usize {
+        self.cases.len() - self.recovered_count()
+    }
+
+    /// Get recovery details for a case
+    pub fn case(&self, case_id: CaseId) -> Option<&RecoveredCase> {
+        self.cases.iter().find(|c| c.case_id == case_id)
+    }
+}
+
+impl std::fmt::Display for RecoveryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reexecuted: usize = self.cases.iter().map(|c| c.reexecuted_tasks.len()).sum();
+        let deduplicated: usize = self.cases.iter().map(|c| c.deduplicated_tasks.len()).sum();
+        write!(
+            f,
+            "recovery: {} scanned, {} recovered, {} failed, {} terminal, {} live; \
+             {} tasks re-executed, {} deduplicated ({} ms)",
+            self.scanned,
+            self.recovered_count(),
+            self.failed_count(),
+            self.skipped_terminal,
+            self.skipped_live,
+            reexecuted,
+            deduplicated,
+            self.duration_ms
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::WorkflowSpecId;
+
+    fn checkpoint_event(case: &Case, marking: CaseMarking) -> StateEvent {
+        StateEvent::CaseCheckpointed {
+            case_id: case.id,
+            checkpoint: Box::new(CaseCheckpoint {
+                case: case.clone(),
+                marking,
+                work_items: Vec::new(),
+                timers: Vec::new(),
+            }),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    fn task_event(case_id: CaseId, task_id: &str, completed: bool) -> StateEvent {
+        if completed {
+            StateEvent::TaskCompleted {
+                case_id,
+                task_id: task_id.to_string(),
+                task_name: task_id.to_string(),
+                duration_ms: 1,
+                timestamp: chrono::Utc::now(),
+            }
+        } else {
+            StateEvent::TaskStarted {
+                case_id,
+                task_id: task_id.to_string(),
+                task_name: task_id.to_string(),
+                timestamp: chrono::Utc::now(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_replay_tracks_in_flight_and_completed_since_checkpoint() {
+        let case = Case::new(WorkflowSpecId::new(), serde_json::json!({}));
+        let events = vec![
+            task_event(case.id, "a", false),
+            task_event(case.id, "a", true),
+            checkpoint_event(&case, CaseMarking::default()),
+            task_event(case.id, "b", false),
+            task_event(case.id, "b", true),
+            task_event(case.id, "c", false),
+        ];
+
+        let replay = CaseReplay::from_events(&events);
+
+        assert!(replay.checkpoint.is_some());
+        assert_eq!(replay.in_flight, vec!["c".to_string()]);
+        assert!(replay.completed_since_checkpoint.contains("b"));
+        assert!(
+            !replay.completed_since_checkpoint.contains("a"),
+            "Completions before the checkpoint are part of the marking"
+        );
+        assert!(!replay.is_terminal());
+    }
+
+    #[test]
+    fn test_replay_carries_executing_tasks_from_checkpoint() {
+        let case = Case::new(WorkflowSpecId::new(), serde_json::json!({}));
+        let mut marking = CaseMarking::default();
+        marking.executing.insert("approve".to_string());
+        marking.visited.insert("approve".to_string());
+
+        let replay = CaseReplay::from_events(&[checkpoint_event(&case, marking)]);
+        assert_eq!(replay.in_flight, vec!["approve".to_string()]);
+
+        let mut marking = replay.checkpoint.map(|c| c.marking).unwrap_or_default();
+        let requeued = marking.requeue_executing();
+        assert_eq!(requeued, vec!["approve".to_string()]);
+        assert_eq!(marking.pending.front().map(String::as_str), Some("approve"));
+        assert!(!marking.visited.contains("approve"));
+        assert!(marking.executing.is_empty());
+    }
+
+    #[test]
+    fn test_cancel_region_removes_tokens() {
+        let mut marking = CaseMarking::default();
+        marking
+            .pending
+            .extend(["c_wait".to_string(), "c_other".to_string()]);
+        marking.required_tokens.insert("join".to_string(), 2);
+        marking.received_tokens.insert("join".to_string(), 1);
+        marking
+            .or_join_branches
+            .insert("join".to_string(), ["a".to_string()].into_iter().collect());
+
+        let cancelled =
+            marking.cancel_region(&["join".to_string(), "c_wait".to_string(), "idle".to_string()]);
+
+        assert_eq!(cancelled, vec!["c_wait".to_string(), "join".to_string()]);
+        assert_eq!(marking.pending, VecDeque::from(vec!["c_other".to_string()]));
+        assert_eq!(marking.received_tokens.get("join"), Some(&0));
+        assert!(marking.or_join_branches.is_empty());
+        let replay = CaseReplay::from_events(&[
+            task_event(CaseId::new(), "join", false),
+            StateEvent::RegionCancelled {
+                case_id: CaseId::new(),
+                task_id: "timeout".to_string(),
+                cancelled,
+                withdrawn_work_items: Vec::new(),
+                timestamp: chrono::Utc::now(),
+            },
+        ]);
+        assert!(replay.in_flight.is_empty());
+    }
+}
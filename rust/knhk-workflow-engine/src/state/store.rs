@@ -32,6 +32,13 @@ impl StateStore {
         })
     }
 
+    /// Open a named tree in the database (for subsystems keeping their own keyspace)
+    pub fn open_tree(&self, name: &str) -> WorkflowResult<sled::Tree> {
+        self.db.open_tree(name).map_err(|e| {
+            WorkflowError::StatePersistence(format!("Failed to open tree {}: {:?}", name, e))
+        })
+    }
+
     /// Save a workflow specification (with cache)
     pub fn save_spec(&self, spec: &WorkflowSpec) -> WorkflowResult<()> {
         // Update cache first (hot path, lock-free DashMap operation)
@@ -133,6 +140,29 @@ impl StateStore {
         Ok(())
     }
 
+    /// Scan receipts whose key starts with `prefix`, returning their full keys and bytes
+    pub fn scan_receipts(&self, prefix: &str) -> WorkflowResult<Vec<(String, Vec<u8>)>> {
+        let prefix = format!("receipt:{}", prefix);
+        let mut receipts = Vec::new();
+        for result in self.db.scan_prefix(prefix.as_bytes()) {
+            let (key, value) = result
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            receipts.push((
+                String::from_utf8_lossy(key.as_ref()).into_owned(),
+                value.to_vec(),
+            ));
+        }
+        Ok(receipts)
+    }
+
+    /// Remove a receipt by its full key (retention enforcement only)
+    pub fn remove_receipt(&self, key: &str) -> WorkflowResult<()> {
+        self.db
+            .remove(key.as_bytes())
+            .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+        Ok(())
+    }
+
     /// List all cases for a workflow specification
     pub fn list_cases(
         &self,
@@ -157,6 +187,33 @@ impl StateStore {
         Ok(cases)
     }
 
+    /// Load all persisted cases (for crash recovery)
+    pub fn load_all_cases(&self) -> WorkflowResult<Vec<Case>> {
+        let mut cases = Vec::new();
+
+        for result in self.db.scan_prefix(b"case:") {
+            let (_, value) = result
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+
+            let case: Case = serde_json::from_slice(value.as_ref()).map_err(|e| {
+                WorkflowError::StatePersistence(format!("Deserialization error: {}", e))
+            })?;
+            cases.push(case);
+        }
+
+        Ok(cases)
+    }
+
+    /// Delete a case (cache and sled)
+    pub fn delete_case(&self, case_id: &crate::case::CaseId) -> WorkflowResult<()> {
+        self.cache.remove_case(case_id);
+        let key = format!("case:{}", case_id);
+        self.db
+            .remove(key.as_bytes())
+            .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+        Ok(())
+    }
+
     /// Delete a workflow specification
     pub fn delete_spec(&self, spec_id: &crate::parser::WorkflowSpecId) -> WorkflowResult<()> {
         let key = format!("spec:{}", spec_id);
@@ -202,11 +259,7 @@ impl StateStore {
         event: &crate::state::manager::StateEvent,
     ) -> WorkflowResult<()> {
         // Persist event to sled (append-only log)
-        let key = format!(
-            "case_history:{}:{}",
-            case_id,
-            chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
-        );
+        let key = case_history_key(case_id);
         let value = serde_json::to_vec(event)
             .map_err(|e| WorkflowError::StatePersistence(format!("Serialization error: {}", e)))?;
         self.db
@@ -215,6 +268,33 @@ impl StateStore {
         Ok(())
     }
 
+    /// Save a checkpoint event to the case history, replacing the previous one
+    ///
+    /// Recovery only needs the latest checkpoint, so each case keeps a single
+    /// checkpoint event instead of one per step. The key of that event is kept
+    /// under `case_checkpoint:{case_id}`; swapping it is atomic.
+    pub fn save_case_checkpoint(
+        &self,
+        case_id: &crate::case::CaseId,
+        event: &crate::state::manager::StateEvent,
+    ) -> WorkflowResult<()> {
+        let key = case_history_key(case_id);
+        let index_key = format!("case_checkpoint:{}", case_id);
+        let value = serde_json::to_vec(event)
+            .map_err(|e| WorkflowError::StatePersistence(format!("Serialization error: {}", e)))?;
+        self.db
+            .transaction(|tx| {
+                if let Some(previous) = tx.insert(index_key.as_bytes(), key.as_bytes())? {
+                    tx.remove(previous)?;
+                }
+                tx.insert(key.as_bytes(), value.as_slice())?;
+                Ok(())
+            })
+            .map_err(|e: sled::transaction::TransactionError<()>| {
+                WorkflowError::StatePersistence(format!("Database error: {:?}", e))
+            })
+    }
+
     /// Load case history events
     pub fn load_case_history(
         &self,
@@ -239,4 +319,66 @@ impl StateStore {
 
         Ok(events)
     }
+
+    /// Delete the case history events of a case, returning how many were deleted
+    pub fn delete_case_history(&self, case_id: &crate::case::CaseId) -> WorkflowResult<usize> {
+        let prefix = format!("case_history:{}:", case_id);
+        let mut deleted = 0;
+        for result in self.db.scan_prefix(prefix.as_bytes()) {
+            let (key, _) = result
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            self.db
+                .remove(key)
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            deleted += 1;
+        }
+        self.db
+            .remove(format!("case_checkpoint:{}", case_id).as_bytes())
+            .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+        Ok(deleted)
+    }
+
+    /// Rewrite the case history events of a case in place
+    ///
+    /// `rewrite` returns whether it changed the event; changed events are stored
+    /// back under their original key. Returns how many events were rewritten.
+    pub fn rewrite_case_history<F>(
+        &self,
+        case_id: &crate::case::CaseId,
+        mut rewrite: F,
+    ) -> WorkflowResult<usize>
+    where
+        F: FnMut(&mut crate::state::manager::StateEvent) -> bool,
+    {
+        let prefix = format!("case_history:{}:", case_id);
+        let mut rewritten = 0;
+        for result in self.db.scan_prefix(prefix.as_bytes()) {
+            let (key, value) = result
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            let mut event: crate::state::manager::StateEvent =
+                serde_json::from_slice(value.as_ref()).map_err(|e| {
+                    WorkflowError::StatePersistence(format!("Deserialization error: {}", e))
+                })?;
+            if !rewrite(&mut event) {
+                continue;
+            }
+            let value = serde_json::to_vec(&event).map_err(|e| {
+                WorkflowError::StatePersistence(format!("Serialization error: {}", e))
+            })?;
+            self.db
+                .insert(key, value)
+                .map_err(|e| WorkflowError::StatePersistence(format!("Database error: {:?}", e)))?;
+            rewritten += 1;
+        }
+        Ok(rewritten)
+    }
+}
+
+/// Key of a new case history event, ordered by time
+fn case_history_key(case_id: &crate::case::CaseId) -> String {
+    format!(
+        "case_history:{}:{}",
+        case_id,
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+    )
 }
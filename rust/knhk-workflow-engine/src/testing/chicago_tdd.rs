@@ -15,6 +15,7 @@ use crate::patterns::{
     PatternExecutionContext, PatternExecutionResult, PatternId, PatternRegistry,
 };
 use crate::resource::{Capability, Resource, ResourceId, Role};
+use crate::services::work_items::WorkItemState;
 #[cfg(feature = "storage")]
 use crate::state::StateStore;
 use crate::worklets::{Worklet, WorkletId, WorkletMetadata};
@@ -79,9 +80,11 @@ impl WorkflowTestFixture {
     }
 
     /// Execute a case and return final state
+    ///
+    /// Work items of human tasks are completed as they are offered.
     pub async fn execute_case(&self, case_id: CaseId) -> WorkflowResult<Case> {
         self.engine.start_case(case_id).await?;
-        self.engine.execute_case(case_id).await?;
+        complete_work_items_while(&self.engine, self.engine.execute_case(case_id)).await?;
         self.engine.get_case(case_id).await
     }
 
@@ -336,6 +339,45 @@ impl Default for WorkflowTestFixture {
     }
 }
 
+/// Drive `run` while standing in for the resources of human tasks
+///
+/// Every open work item is completed with its case data as soon as it is
+/// offered, so cases with human tasks run to completion in tests.
+pub async fn complete_work_items_while<F: std::future::Future>(
+    engine: &WorkflowEngine,
+    run: F,
+) -> F::Output {
+    tokio::pin!(run);
+    let mut poll = tokio::time::interval(std::time::Duration::from_millis(5));
+    loop {
+        tokio::select! {
+            output = &mut run => return output,
+            _ = poll.tick() => complete_open_work_items(engine).await,
+        }
+    }
+}
+
+async fn complete_open_work_items(engine: &WorkflowEngine) {
+    let service = engine.work_item_service();
+    let open = service
+        .get_work_items_by_states(vec![
+            WorkItemState::Created,
+            WorkItemState::Assigned,
+            WorkItemState::Claimed,
+            WorkItemState::InProgress,
+        ])
+        .await;
+    for item in open {
+        let case = match CaseId::parse_str(&item.case_id) {
+            Ok(case_id) => engine.get_case(case_id).await.ok(),
+            Err(_) => None,
+        };
+        let result = case.map_or(item.data, |case| case.data);
+        // The item may have moved on since it was listed
+        let _ = service.complete(&item.id, result).await;
+    }
+}
+
 // ============================================================================
 // Pattern Test Helpers
 // ============================================================================
@@ -448,6 +490,10 @@ impl WorkflowSpecBuilder {
                 start_condition: None,
                 end_condition: None,
                 source_turtle: None,
+                variables: Vec::new(),
+                iri: None,
+                version: None,
+                subnets: Vec::new(),
             },
         }
     }
@@ -577,6 +623,25 @@ impl WorkflowSpecBuilder {
         self
     }
 
+    /// Declare a net-level case variable
+    pub fn add_variable(mut self, variable: crate::data::schema::VariableDecl) -> Self {
+        self.spec.variables.push(variable);
+        self
+    }
+
+    /// Set the specification IRI and version (for sub-net resolution)
+    pub fn with_version(mut self, iri: impl Into<String>, version: impl Into<String>) -> Self {
+        self.spec.iri = Some(iri.into());
+        self.spec.version = Some(version.into());
+        self
+    }
+
+    /// Add a sub-net registered together with the specification
+    pub fn add_subnet(mut self, subnet: WorkflowSpec) -> Self {
+        self.spec.subnets.push(subnet);
+        self
+    }
+
     /// Build the workflow specification
     pub fn build(self) -> WorkflowSpec {
         self.spec
@@ -612,6 +677,10 @@ impl TaskBuilder {
                 required_roles: vec![],
                 required_capabilities: vec![],
                 exception_worklet: None,
+                multi_instance: None,
+                cancellation: None,
+                decomposition: None,
+                message: None,
             },
         }
     }
@@ -658,6 +727,53 @@ impl TaskBuilder {
         self
     }
 
+    /// Make this a multiple instance task with the given configuration
+    pub fn with_multi_instance(
+        mut self,
+        multi_instance: crate::patterns::multiple_instance::MultiInstanceSpec,
+    ) -> Self {
+        self.task.task_type = TaskType::MultipleInstance;
+        self.task.multi_instance = Some(multi_instance);
+        self
+    }
+
+    /// Set the cancellation region applied when the task completes
+    pub fn with_cancellation(mut self, cancellation: crate::parser::CancellationRegion) -> Self {
+        self.task.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Make the task a composite task launching the given sub-net
+    pub fn with_decomposition(mut self, decomposition: crate::parser::SubNetRef) -> Self {
+        self.task.task_type = TaskType::Composite;
+        self.task.decomposition = Some(decomposition);
+        self
+    }
+
+    /// Make the task wait for a correlated message
+    pub fn with_message(mut self, message: crate::parser::MessageCatch) -> Self {
+        self.task.message = Some(message);
+        self
+    }
+
+    /// Add required role (makes the task a human task)
+    pub fn add_required_role(mut self, role: impl Into<String>) -> Self {
+        self.task.required_roles.push(role.into());
+        self
+    }
+
+    /// Add input parameter
+    pub fn add_input_parameter(mut self, parameter: crate::parser::TaskParameter) -> Self {
+        self.task.input_parameters.push(parameter);
+        self
+    }
+
+    /// Add output parameter
+    pub fn add_output_parameter(mut self, parameter: crate::parser::TaskParameter) -> Self {
+        self.task.output_parameters.push(parameter);
+        self
+    }
+
     /// Build the task
     pub fn build(self) -> Task {
         self.task
@@ -773,6 +889,10 @@ pub fn create_test_worklet(name: impl Into<String>, exception_types: Vec<String>
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         },
         rules: vec![],
     }
@@ -11,14 +11,14 @@ pub mod property;
 
 pub use chicago_tdd::{
     assert_pattern_failure, assert_pattern_has_next_state, assert_pattern_has_variable,
-    assert_pattern_success, assert_pattern_variable_equals, create_loop_workflow,
-    create_mi_workflow, create_parallel_split_workflow, create_sequential_workflow,
-    create_sequential_workflow_with_flows, create_simple_sequential_workflow,
-    create_test_capability, create_test_context, create_test_context_for_workflow,
-    create_test_context_with_vars, create_test_registry, create_test_resource, create_test_role,
-    create_test_worklet, create_xor_split_workflow, ConditionBuilder, IntegrationTestHelper,
-    PerformanceTestHelper, TaskBuilder, WorkflowPropertyTester, WorkflowSpecBuilder,
-    WorkflowTestFixture,
+    assert_pattern_success, assert_pattern_variable_equals, complete_work_items_while,
+    create_loop_workflow, create_mi_workflow, create_parallel_split_workflow,
+    create_sequential_workflow, create_sequential_workflow_with_flows,
+    create_simple_sequential_workflow, create_test_capability, create_test_context,
+    create_test_context_for_workflow, create_test_context_with_vars, create_test_registry,
+    create_test_resource, create_test_role, create_test_worklet, create_xor_split_workflow,
+    ConditionBuilder, IntegrationTestHelper, PerformanceTestHelper, TaskBuilder,
+    WorkflowPropertyTester, WorkflowSpecBuilder, WorkflowTestFixture,
 };
 // TestDataBuilder is now in chicago-tdd-tools - import directly:
 // use chicago_tdd_tools::builders::TestDataBuilder;
@@ -90,6 +90,10 @@ impl PropertyTestGenerator {
                 required_roles: vec![],
                 required_capabilities: vec![],
                 exception_worklet: None,
+                multi_instance: None,
+                cancellation: None,
+                decomposition: None,
+                message: None,
             };
             tasks.insert(task_id, task);
         }
@@ -106,6 +110,10 @@ impl PropertyTestGenerator {
             start_condition: Some("condition:start".to_string()),
             end_condition: Some("condition:end".to_string()),
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         }
     }
 
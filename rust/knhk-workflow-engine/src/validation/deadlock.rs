@@ -4,7 +4,7 @@
 //! Uses Petri net analysis to detect potential deadlocks at design-time.
 
 use crate::error::{WorkflowError, WorkflowResult};
-use crate::parser::WorkflowSpec;
+use crate::parser::{CancelScope, WorkflowSpec};
 use crate::patterns::RegisterAllExt;
 use std::collections::{HashMap, HashSet, VecDeque};
 
@@ -17,6 +17,8 @@ pub struct DeadlockDetectionResult {
     pub deadlock_locations: Vec<String>,
     /// Deadlock cycles (if any)
     pub cycles: Vec<Vec<String>>,
+    /// Cancellation targets that are not tasks or conditions of the net
+    pub invalid_cancellation_targets: Vec<String>,
     /// Warnings
     pub warnings: Vec<String>,
 }
@@ -65,10 +67,14 @@ impl DeadlockDetector {
             warnings.push(format!("Dead-end tasks detected: {:?}", dead_ends));
         }
 
+        // Check cancellation regions (WS-S3: cancellation targets must exist)
+        let invalid_cancellation_targets = self.find_invalid_cancellation_targets(spec);
+
         DeadlockDetectionResult {
             has_deadlock,
             deadlock_locations,
             cycles,
+            invalid_cancellation_targets,
             warnings,
         }
     }
@@ -202,14 +208,42 @@ impl DeadlockDetector {
     }
 
     /// Find dead-end tasks (tasks without outgoing flows)
+    ///
+    /// Tasks cancelling the whole case terminate it and are not dead ends.
     fn find_dead_ends(&self, spec: &WorkflowSpec) -> Vec<String> {
         spec.tasks
             .iter()
             .filter(|(_, task)| task.outgoing_flows.is_empty())
+            .filter(|(_, task)| {
+                !task
+                    .cancellation
+                    .as_ref()
+                    .is_some_and(|region| region.scope == CancelScope::Case)
+            })
             .map(|(task_id, _)| task_id.clone())
             .collect()
     }
 
+    /// Find cancellation targets that are neither tasks nor conditions of the net
+    fn find_invalid_cancellation_targets(&self, spec: &WorkflowSpec) -> Vec<String> {
+        let mut invalid: Vec<String> = spec
+            .tasks
+            .iter()
+            .filter_map(|(task_id, task)| task.cancellation.as_ref().map(|r| (task_id, r)))
+            .flat_map(|(task_id, region)| {
+                region
+                    .targets
+                    .iter()
+                    .filter(|target| {
+                        !spec.tasks.contains_key(*target) && !spec.conditions.contains_key(*target)
+                    })
+                    .map(move |target| format!("{} -> {}", task_id, target))
+            })
+            .collect();
+        invalid.sort();
+        invalid
+    }
+
     /// Convert Petri net node to string
     fn node_to_string(&self, node: &PetriNetNode) -> String {
         match node {
@@ -229,6 +263,13 @@ impl DeadlockDetector {
             )));
         }
 
+        if !result.invalid_cancellation_targets.is_empty() {
+            return Err(WorkflowError::Validation(format!(
+                "Cancellation targets do not exist: {:?}",
+                result.invalid_cancellation_targets
+            )));
+        }
+
         // Log warnings but don't fail validation
         if !result.warnings.is_empty() {
             // In production, would log these warnings
@@ -255,6 +296,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         let result = detector.detect_deadlocks(&spec);
@@ -273,6 +318,10 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         // Create a simple cycle: task1 -> condition1 -> task1
@@ -295,6 +344,10 @@ mod tests {
             input_parameters: Vec::new(),
             output_parameters: Vec::new(),
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         };
 
         let condition1 = crate::parser::Condition {
@@ -311,4 +364,68 @@ mod tests {
         // Should detect cycle
         assert!(result.has_deadlock || !result.cycles.is_empty());
     }
+
+    #[test]
+    fn test_cancellation_targets_must_exist() {
+        let detector = DeadlockDetector;
+        let mut spec = WorkflowSpec {
+            id: crate::parser::WorkflowSpecId::new(),
+            name: "Cancelling Workflow".to_string(),
+            tasks: HashMap::new(),
+            conditions: HashMap::new(),
+            flows: Vec::new(),
+            start_condition: None,
+            end_condition: None,
+            source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
+        };
+
+        let timeout = Task {
+            id: "timeout".to_string(),
+            name: "Timeout".to_string(),
+            task_type: crate::parser::TaskType::Atomic,
+            split_type: crate::parser::SplitType::And,
+            join_type: crate::parser::JoinType::And,
+            max_ticks: None,
+            priority: None,
+            use_simd: false,
+            input_conditions: Vec::new(),
+            output_conditions: Vec::new(),
+            outgoing_flows: Vec::new(),
+            incoming_flows: Vec::new(),
+            allocation_policy: None,
+            required_roles: Vec::new(),
+            required_capabilities: Vec::new(),
+            input_parameters: Vec::new(),
+            output_parameters: Vec::new(),
+            exception_worklet: None,
+            multi_instance: None,
+            cancellation: Some(crate::parser::CancellationRegion::region(["approve"])),
+            decomposition: None,
+            message: None,
+            pattern_id: None,
+        };
+        spec.tasks.insert("timeout".to_string(), timeout.clone());
+
+        let result = detector.detect_deadlocks(&spec);
+        assert_eq!(
+            result.invalid_cancellation_targets,
+            vec!["timeout -> approve"]
+        );
+        assert!(detector.validate(&spec).is_err());
+
+        let approve = Task {
+            id: "approve".to_string(),
+            name: "Approve".to_string(),
+            cancellation: Some(crate::parser::CancellationRegion::case()),
+            ..timeout
+        };
+        spec.tasks.insert("approve".to_string(), approve);
+        let result = detector.detect_deadlocks(&spec);
+        assert!(result.invalid_cancellation_targets.is_empty());
+        assert!(detector.validate(&spec).is_ok());
+    }
 }
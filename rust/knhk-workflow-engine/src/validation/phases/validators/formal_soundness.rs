@@ -5,6 +5,8 @@
 //! 2. Proper Completion: Workflow reaches proper end state
 //! 3. No Dead Tasks: No unreachable tasks
 //!
+//! Tasks cancelling the whole case are treated as completing the workflow.
+//!
 //! Based on van der Aalst's workflow verification theory.
 
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -13,6 +15,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use crate::error::{WorkflowError, WorkflowResult};
+use crate::parser::CancelScope;
 use crate::patterns::PatternId;
 use crate::validation::phases::core::{
     Phase, PhaseContext, PhaseMetadata, PhaseResult, PhaseStatus,
@@ -182,12 +185,21 @@ fn build_task_graph(spec: &WorkflowSpec) -> HashMap<String, Vec<String>> {
 
     // Add tasks from workflow patterns
     for (task_id, task) in &spec.tasks {
-        let successors = task
+        let mut successors = task
             .outgoing_flows
             .iter()
             .map(|s| s.to_string())
             .collect::<Vec<_>>();
 
+        // Cancelling the case terminates it: the task completes the workflow
+        if task
+            .cancellation
+            .as_ref()
+            .is_some_and(|region| region.scope == CancelScope::Case)
+        {
+            successors.push("__end__".to_string());
+        }
+
         graph.insert(task_id.clone(), successors);
     }
 
@@ -28,6 +28,13 @@
 //! - `VR-S010`: Output condition must not have outgoing flows
 //! - `VR-S011`: XOR split flows should have predicates for routing
 //! - `VR-S012`: OR join vicious circle warning (requires runtime analysis)
+//!
+//! # Domain Shapes
+//!
+//! Teams can attach their own shapes graph with [`ShaclValidator::with_shapes`].
+//! Domain shapes are evaluated by the generic SHACL Core engine in `knhk-shacl`
+//! alongside the soundness rules; each result is reported with the IRI of its
+//! source shape as the rule ID.
 
 #[cfg(feature = "rdf")]
 use oxigraph::io::RdfFormat;
@@ -113,6 +120,8 @@ impl ShaclValidationReport {
 pub struct ShaclValidator {
     /// Store containing SHACL shape definitions
     shapes_store: Store,
+    /// User-supplied domain shapes, validated in addition to the soundness rules
+    domain_shapes: Option<knhk_shacl::ShaclEngine>,
 }
 
 impl ShaclValidator {
@@ -130,9 +139,32 @@ impl ShaclValidator {
 
         Ok(Self {
             shapes_store: store,
+            domain_shapes: None,
         })
     }
 
+    /// Load a domain shapes graph (Turtle) validated alongside the soundness rules
+    pub fn with_shapes(mut self, shapes_turtle: &str) -> Result<Self, String> {
+        let engine = knhk_shacl::ShaclEngine::from_turtle(shapes_turtle)
+            .map_err(|e| format!("Failed to load domain shapes: {}", e))?;
+        self.domain_shapes = Some(engine);
+        Ok(self)
+    }
+
+    /// Validate a workflow against the domain shapes only, returning the
+    /// W3C validation report (conforming when no domain shapes are loaded)
+    pub fn validate_shapes(
+        &self,
+        workflow_turtle: &str,
+    ) -> Result<knhk_shacl::ValidationReport, String> {
+        match &self.domain_shapes {
+            Some(engine) => engine
+                .validate_turtle(workflow_turtle)
+                .map_err(|e| format!("Domain shape validation failed: {}", e)),
+            None => Ok(knhk_shacl::ValidationReport::new(Vec::new())),
+        }
+    }
+
     /// Validate workflow against all soundness SHACL shapes
     ///
     /// This executes all 12 soundness validation rules and returns a comprehensive
@@ -189,6 +221,14 @@ impl ShaclValidator {
         // VR-S012: OR join vicious circle warning
         violations.extend(self.validate_rule_s012(&data_store)?);
 
+        // Domain shapes supplied with the workflow spec
+        if let Some(engine) = &self.domain_shapes {
+            let report = engine
+                .validate_store(&data_store)
+                .map_err(|e| format!("Domain shape validation failed: {}", e))?;
+            violations.extend(report.results.into_iter().map(domain_violation));
+        }
+
         // Build report
         if violations.is_empty() {
             Ok(ShaclValidationReport::conforming())
@@ -522,6 +562,28 @@ impl ShaclValidator {
     }
 }
 
+/// Convert a domain shape result into a soundness-report violation
+fn domain_violation(result: knhk_shacl::ValidationResult) -> ShaclViolation {
+    let rule_id = match &result.source_shape {
+        oxigraph::model::Term::NamedNode(shape) => shape.as_str().to_string(),
+        shape => shape.to_string(),
+    };
+    let severity = match result.severity {
+        knhk_shacl::Severity::Violation => ValidationSeverity::Violation,
+        knhk_shacl::Severity::Warning => ValidationSeverity::Warning,
+        knhk_shacl::Severity::Info => ValidationSeverity::Info,
+    };
+    let message = result
+        .message
+        .unwrap_or_else(|| result.source_constraint_component.as_str().to_string());
+    ShaclViolation {
+        rule_id,
+        severity,
+        focus_node: result.focus_node.to_string(),
+        message,
+    }
+}
+
 impl Default for ShaclValidator {
     fn default() -> Self {
         Self::new().unwrap_or_else(|e| panic!("Failed to create default SHACL validator: {:?}", e))
@@ -794,4 +856,56 @@ mod tests {
             "Should have VR-S010 violations"
         );
     }
+
+    #[test]
+    fn test_domain_shapes_are_reported_with_soundness_rules() {
+        let shapes = r#"
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+            @prefix yawl: <http://www.yawlfoundation.org/yawlschema#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+            <http://example.org/shapes/TaskLabel> a sh:NodeShape ;
+                sh:targetClass yawl:Task ;
+                sh:severity sh:Warning ;
+                sh:message "Tasks must have a label" ;
+                sh:property [ sh:path rdfs:label ; sh:minCount 1 ] .
+        "#;
+        let workflow = r#"
+            @prefix yawl: <http://www.yawlfoundation.org/yawlschema#> .
+
+            <http://example.org/workflow1> a yawl:Specification ;
+                yawl:hasInputCondition <http://example.org/input1> ;
+                yawl:hasOutputCondition <http://example.org/output1> ;
+                yawl:hasTask <http://example.org/task1> .
+
+            <http://example.org/input1> a yawl:InputCondition ;
+                yawl:flowsInto <http://example.org/flow1> .
+
+            <http://example.org/flow1> a yawl:FlowsInto ;
+                yawl:nextElementRef <http://example.org/task1> .
+
+            <http://example.org/task1> a yawl:Task ;
+                yawl:flowsInto <http://example.org/flow2> .
+
+            <http://example.org/flow2> a yawl:FlowsInto ;
+                yawl:nextElementRef <http://example.org/output1> .
+
+            <http://example.org/output1> a yawl:OutputCondition .
+        "#;
+
+        let validator = ShaclValidator::new().unwrap().with_shapes(shapes).unwrap();
+        let report = validator.validate_soundness(workflow).unwrap();
+
+        assert!(!report.conforms);
+        assert_eq!(report.violations.len(), 1);
+        let violation = &report.violations[0];
+        assert_eq!(violation.rule_id, "http://example.org/shapes/TaskLabel");
+        assert_eq!(violation.severity, ValidationSeverity::Warning);
+        assert_eq!(violation.focus_node, "<http://example.org/task1>");
+        assert_eq!(violation.message, "Tasks must have a label");
+
+        let w3c = validator.validate_shapes(workflow).unwrap();
+        assert!(!w3c.conforms);
+        assert!(w3c.to_turtle().contains("sh:MinCountConstraintComponent"));
+    }
 }
@@ -2,9 +2,10 @@
 //! Workflow visualization module
 //!
 //! Generates visual diagrams from workflow specifications using GraphViz/DOT format.
+//! Cancellation regions are drawn as dashed edges from the cancelling task.
 
 use crate::error::{WorkflowError, WorkflowResult};
-use crate::parser::WorkflowSpec;
+use crate::parser::{CancelScope, WorkflowSpec};
 use std::collections::HashMap;
 
 /// Workflow visualizer
@@ -139,6 +140,29 @@ impl WorkflowVisualizer {
             }
         }
 
+        // Add cancellation regions (dashed edges from the cancelling task)
+        for (task_id, task) in &spec.tasks {
+            let Some(ref region) = task.cancellation else {
+                continue;
+            };
+            match region.scope {
+                CancelScope::Region => {
+                    for target in &region.targets {
+                        dot.push_str(&format!(
+                            "    \"{}\" -> \"{}\" [style=dashed, color=\"#B22222\", arrowhead=tee, constraint=false];\n",
+                            task_id, target
+                        ));
+                    }
+                }
+                CancelScope::Case => {
+                    dot.push_str(&format!(
+                        "    \"{}\" -> end [style=dashed, color=\"#B22222\", arrowhead=tee, label=\"cancel case\", constraint=false];\n",
+                        task_id
+                    ));
+                }
+            }
+        }
+
         // Add edges to end
         if let Some(ref end_id) = spec.end_condition {
             if let Some(end_condition) = spec.conditions.get(end_id) {
@@ -251,9 +275,37 @@ mod tests {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         let dot = visualizer.generate_dot(&spec).unwrap();
         assert!(dot.contains("digraph workflow"));
     }
+
+    #[test]
+    fn test_generate_dot_draws_cancellation_regions() {
+        use crate::parser::CancellationRegion;
+        use crate::testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder};
+
+        let spec = WorkflowSpecBuilder::new("Cancelling Workflow")
+            .add_task(TaskBuilder::new("ship", "Ship").build())
+            .add_task(
+                TaskBuilder::new("timeout", "Timeout")
+                    .with_cancellation(CancellationRegion::region(["ship"]))
+                    .build(),
+            )
+            .add_task(
+                TaskBuilder::new("abort", "Abort")
+                    .with_cancellation(CancellationRegion::case())
+                    .build(),
+            )
+            .build();
+
+        let dot = WorkflowVisualizer::new().generate_dot(&spec).unwrap();
+        assert!(dot.contains("\"timeout\" -> \"ship\" [style=dashed"));
+        assert!(dot.contains("\"abort\" -> end [style=dashed"));
+    }
 }
@@ -0,0 +1,302 @@
+//! Exlets - exception-handling processes
+//!
+//! Based on: org.yawlfoundation.yawl.worklet.exception
+//!
+//! An exlet is the conclusion of an exception rule: a sequence of handling
+//! primitives executed by the engine when an exception is raised for a case or
+//! work item. Exception rule trees are kept per exception kind, either for a
+//! single task or for the whole case, and are evaluated with the usual RDR
+//! selection (the most specific matching rule carrying an exlet wins).
+//!
+//! Typical exlets:
+//! - `[SuspendCase, Compensate("refund"), ContinueCase]`
+//! - `[SuspendItem, Compensate("collect-missing-data"), RestartItem]`
+//! - `[CancelCase]`
+
+use crate::error::{WorkflowError, WorkflowResult};
+use serde::{Deserialize, Serialize};
+
+/// Kind of exception an exlet handles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExceptionKind {
+    /// Work item data violates a pre-constraint (checked before the item is offered)
+    ItemPreConstraint,
+    /// Work item result violates a post-constraint (checked on completion)
+    ItemPostConstraint,
+    /// Work item deadline passed before completion
+    ItemTimeout,
+    /// Exception raised from outside the engine (`resilience::YawlException`)
+    External,
+}
+
+impl ExceptionKind {
+    /// Name used as `exception_type` in RDR conditions
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ItemPreConstraint => "item_pre_constraint",
+            Self::ItemPostConstraint => "item_post_constraint",
+            Self::ItemTimeout => "item_timeout",
+            Self::External => "external",
+        }
+    }
+
+    /// Whether the exception is a constraint check
+    ///
+    /// Constraint exceptions are only raised when a rule of the constraint
+    /// tree matches; timeouts and external exceptions are always raised.
+    pub fn is_constraint(&self) -> bool {
+        matches!(self, Self::ItemPreConstraint | Self::ItemPostConstraint)
+    }
+}
+
+impl std::fmt::Display for ExceptionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Exlet handling primitive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ExletPrimitive {
+    /// Suspend the work item
+    SuspendItem,
+    /// Suspend the case
+    SuspendCase,
+    /// Continue a suspended work item
+    ContinueItem,
+    /// Continue a suspended case
+    ContinueCase,
+    /// Run a compensating worklet (by worklet name or ID) as a case, to completion
+    Compensate {
+        /// Worklet name or ID
+        worklet: String,
+    },
+    /// Reset the work item in place and offer the task again
+    RestartItem,
+    /// Complete the work item with its current data, skipping output validation
+    ForceCompleteItem,
+    /// Fail the work item (the task fails and the case is dead-lettered)
+    FailItem,
+    /// Fail the case
+    FailCase,
+    /// Cancel the work item
+    CancelItem,
+    /// Cancel the case
+    CancelCase,
+}
+
+impl ExletPrimitive {
+    /// Whether the primitive decides the fate of the work item
+    pub fn resolves_item(&self) -> bool {
+        matches!(
+            self,
+            Self::RestartItem
+                | Self::ForceCompleteItem
+                | Self::FailItem
+                | Self::FailCase
+                | Self::CancelItem
+                | Self::CancelCase
+        )
+    }
+
+    /// Whether the primitive ends the case
+    pub fn ends_case(&self) -> bool {
+        matches!(self, Self::FailCase | Self::CancelCase)
+    }
+}
+
+impl std::fmt::Display for ExletPrimitive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SuspendItem => f.write_str("suspend_item"),
+            Self::SuspendCase => f.write_str("suspend_case"),
+            Self::ContinueItem => f.write_str("continue_item"),
+            Self::ContinueCase => f.write_str("continue_case"),
+            Self::Compensate { worklet } => write!(f, "compensate({})", worklet),
+            Self::RestartItem => f.write_str("restart_item"),
+            Self::ForceCompleteItem => f.write_str("force_complete_item"),
+            Self::FailItem => f.write_str("fail_item"),
+            Self::FailCase => f.write_str("fail_case"),
+            Self::CancelItem => f.write_str("cancel_item"),
+            Self::CancelCase => f.write_str("cancel_case"),
+        }
+    }
+}
+
+/// Exception-handling process (conclusion of an exception rule)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exlet {
+    /// Exlet name
+    pub name: String,
+    /// Primitives, executed in order
+    pub steps: Vec<ExletPrimitive>,
+}
+
+impl Exlet {
+    /// Create an empty exlet
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Append a primitive
+    pub fn then(mut self, step: ExletPrimitive) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Check the exlet is executable
+    ///
+    /// An exlet needs at least one step, names its compensating worklets, and
+    /// decides the fate of the work item at most once, as its last step.
+    pub fn validate(&self) -> WorkflowResult<()> {
+        if self.steps.is_empty() {
+            return Err(WorkflowError::Validation(format!(
+                "Exlet '{}' has no steps",
+                self.name
+            )));
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            if let ExletPrimitive::Compensate { worklet } = step {
+                if worklet.trim().is_empty() {
+                    return Err(WorkflowError::Validation(format!(
+                        "Exlet '{}' step {} compensates with an unnamed worklet",
+                        self.name,
+                        index + 1
+                    )));
+                }
+            }
+            if step.resolves_item() && index + 1 != self.steps.len() {
+                return Err(WorkflowError::Validation(format!(
+                    "Exlet '{}' step {} ({}) must be the last step",
+                    self.name,
+                    index + 1,
+                    step
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// How the work item is resolved once all steps ran
+    pub fn resolution(&self) -> ExletResolution {
+        match self.steps.last() {
+            Some(ExletPrimitive::RestartItem) => ExletResolution::Restart,
+            Some(ExletPrimitive::ForceCompleteItem) => ExletResolution::ForceComplete,
+            Some(ExletPrimitive::FailItem) | Some(ExletPrimitive::FailCase) => {
+                ExletResolution::Failed
+            }
+            Some(ExletPrimitive::CancelItem) | Some(ExletPrimitive::CancelCase) => {
+                ExletResolution::Cancelled
+            }
+            _ => ExletResolution::Proceed,
+        }
+    }
+}
+
+/// Effect of an exlet on the work item or task that raised the exception
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExletResolution {
+    /// Carry on as if no exception had been raised
+    Proceed,
+    /// Offer the task again: an open work item is reset, a completed one replaced
+    Restart,
+    /// Complete the task with the current work item data
+    ForceComplete,
+    /// The task failed
+    Failed,
+    /// The work item (or case) was cancelled
+    Cancelled,
+}
+
+/// Executed exlet step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExletStepRecord {
+    /// Primitive
+    pub step: ExletPrimitive,
+    /// Whether the step succeeded
+    pub success: bool,
+    /// Step detail (compensation case, failure reason, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Outcome of handling an exception with an exlet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExletReport {
+    /// Exception kind
+    pub kind: ExceptionKind,
+    /// Rule whose conclusion was executed
+    pub rule_id: String,
+    /// Executed exlet
+    pub exlet: String,
+    /// Executed steps (stops at the first failed step)
+    pub steps: Vec<ExletStepRecord>,
+    /// Effect on the work item or task
+    pub resolution: ExletResolution,
+}
+
+impl ExletReport {
+    /// Whether every step succeeded
+    pub fn succeeded(&self) -> bool {
+        self.steps.iter().all(|s| s.success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exlet_validation_and_resolution() {
+        let compensate = Exlet::new("refund")
+            .then(ExletPrimitive::SuspendCase)
+            .then(ExletPrimitive::Compensate {
+                worklet: "refund".to_string(),
+            })
+            .then(ExletPrimitive::ContinueCase);
+        assert!(compensate.validate().is_ok());
+        assert_eq!(compensate.resolution(), ExletResolution::Proceed);
+
+        let restart = Exlet::new("retry")
+            .then(ExletPrimitive::SuspendItem)
+            .then(ExletPrimitive::RestartItem);
+        assert!(restart.validate().is_ok());
+        assert_eq!(restart.resolution(), ExletResolution::Restart);
+
+        assert!(Exlet::new("empty").validate().is_err());
+        assert!(Exlet::new("early")
+            .then(ExletPrimitive::CancelCase)
+            .then(ExletPrimitive::ContinueCase)
+            .validate()
+            .is_err());
+        assert!(Exlet::new("unnamed")
+            .then(ExletPrimitive::Compensate {
+                worklet: " ".to_string()
+            })
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_primitive_serialization() {
+        let step = ExletPrimitive::Compensate {
+            worklet: "refund".to_string(),
+        };
+        let json = serde_json::to_value(&step).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"action": "compensate", "worklet": "refund"})
+        );
+        assert_eq!(
+            serde_json::from_value::<ExletPrimitive>(serde_json::json!({"action": "fail_item"}))
+                .unwrap(),
+            ExletPrimitive::FailItem
+        );
+    }
+}
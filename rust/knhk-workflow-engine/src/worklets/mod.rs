@@ -11,8 +11,12 @@
 //! - TRIZ Principle 19: Periodic Action - Periodic repository sync
 /// WorkletExecutionBackend implementation for WorkflowEngine
 pub mod backend_impl;
+/// Exlets: exception-handling processes concluded by exception rules
+pub mod exlet;
 /// Ripple Down Rules (RDR) engine for worklet selection
 pub mod rdr;
+/// RDR rule learning from cornerstone cases and rule tree persistence
+pub mod rdr_learning;
 /// YAWL worklet service implementation
 pub mod yawl_worklet;
 
@@ -24,7 +28,14 @@ use crate::error::{WorkflowError, WorkflowResult};
 use crate::parser::WorkflowSpec;
 use crate::patterns::{PatternExecutionContext, PatternExecutionResult, PatternId};
 use async_trait::async_trait;
+pub use exlet::{
+    ExceptionKind, Exlet, ExletPrimitive, ExletReport, ExletResolution, ExletStepRecord,
+};
 pub use rdr::{ExceptionContext, RDREngine, RDRRule, RDRSelectionPlan};
+pub use rdr_learning::{
+    AttributeDifference, CandidateCondition, RefinementProposal, RuleTreeDocument, RuleTreeFile,
+    RuleTreeStore,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -110,6 +121,8 @@ pub struct WorkletRepository {
     tag_index: Arc<RwLock<HashMap<String, Vec<WorkletId>>>>,
     /// RDR engine for rule-based selection (TRIZ Principle 24: Intermediary)
     rdr_engine: Arc<RwLock<RDREngine>>,
+    /// Exception rule trees by exception kind and task (`None` = case-level)
+    exception_rules: Arc<RwLock<HashMap<(ExceptionKind, Option<String>), RDREngine>>>,
     /// Last reconciliation time (TRIZ Principle 19: Periodic Action)
     last_reconciliation: Arc<RwLock<Option<chrono::DateTime<chrono::Utc>>>>,
 }
@@ -122,6 +135,7 @@ impl WorkletRepository {
             exception_index: Arc::new(RwLock::new(HashMap::new())),
             tag_index: Arc::new(RwLock::new(HashMap::new())),
             rdr_engine: Arc::new(RwLock::new(RDREngine::new())),
+            exception_rules: Arc::new(RwLock::new(HashMap::new())),
             last_reconciliation: Arc::new(RwLock::new(None)),
         }
     }
@@ -200,6 +214,170 @@ impl WorkletRepository {
         Ok(None)
     }
 
+    /// Add a rule to the exception rule tree of a task, or of the case when
+    /// `task_id` is `None`
+    ///
+    /// Rules concluding with an exlet are executed by the engine when an
+    /// exception of that kind is raised.
+    pub async fn add_exception_rule(
+        &self,
+        kind: ExceptionKind,
+        task_id: Option<&str>,
+        rule: RDRRule,
+    ) -> WorkflowResult<()> {
+        let mut trees = self.exception_rules.write().await;
+        trees
+            .entry((kind, task_id.map(str::to_string)))
+            .or_default()
+            .add_rule(rule)
+    }
+
+    /// Select the exlet handling an exception
+    ///
+    /// The rule tree of the task is consulted first, then the case-level tree.
+    /// Returns the concluding rule ID and its exlet.
+    pub async fn select_exlet(
+        &self,
+        kind: ExceptionKind,
+        context: &ExceptionContext,
+    ) -> WorkflowResult<Option<(String, Exlet)>> {
+        let trees = self.exception_rules.read().await;
+        let mut keys = Vec::with_capacity(2);
+        if !context.task_id.is_empty() {
+            keys.push((kind, Some(context.task_id.clone())));
+        }
+        keys.push((kind, None));
+        for key in keys {
+            let Some(tree) = trees.get(&key) else {
+                continue;
+            };
+            let plan = tree.select_worklet(context)?;
+            if let Some(rule) = plan.exlet_rule() {
+                if let Some(ref exlet) = rule.exlet {
+                    return Ok(Some((rule.rule_id.clone(), exlet.clone())));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Propose a refinement for a case whose selection the operator rejected
+    ///
+    /// `exception` names the exception rule tree of the case's task (the
+    /// case-level tree without a task); `None` refines the worklet selection
+    /// tree.
+    pub async fn reject_selection(
+        &self,
+        exception: Option<ExceptionKind>,
+        context: &ExceptionContext,
+    ) -> WorkflowResult<RefinementProposal> {
+        match exception {
+            Some(kind) => {
+                let trees = self.exception_rules.read().await;
+                match trees.get(&exception_tree_key(kind, context)) {
+                    Some(tree) => tree.propose_refinement(context),
+                    None => RDREngine::new().propose_refinement(context),
+                }
+            }
+            None => self.rdr_engine.read().await.propose_refinement(context),
+        }
+    }
+
+    /// Add the rule chosen by the operator for a rejected selection
+    ///
+    /// Fails if the condition does not hold for the case or changes the
+    /// conclusion for a stored cornerstone case.
+    pub async fn refine_rule(
+        &self,
+        exception: Option<ExceptionKind>,
+        context: &ExceptionContext,
+        condition: &str,
+        worklet_id: String,
+        exlet: Option<Exlet>,
+    ) -> WorkflowResult<RDRRule> {
+        match exception {
+            Some(kind) => {
+                let mut trees = self.exception_rules.write().await;
+                trees
+                    .entry(exception_tree_key(kind, context))
+                    .or_default()
+                    .refine(context, condition, worklet_id, exlet)
+            }
+            None => self
+                .rdr_engine
+                .write()
+                .await
+                .refine(context, condition, worklet_id, exlet),
+        }
+    }
+
+    /// Save all rule trees, one file per task
+    ///
+    /// The worklet selection tree is saved with the case-level trees. Returns
+    /// the number of files written.
+    pub async fn save_rule_trees(&self, store: &RuleTreeStore) -> WorkflowResult<usize> {
+        let mut by_task: HashMap<Option<String>, Vec<RuleTreeDocument>> = HashMap::new();
+        by_task
+            .entry(None)
+            .or_default()
+            .push(RuleTreeDocument::from_engine(None, &*self.rdr_engine.read().await));
+        for ((kind, task_id), tree) in self.exception_rules.read().await.iter() {
+            by_task
+                .entry(task_id.clone())
+                .or_default()
+                .push(RuleTreeDocument::from_engine(Some(*kind), tree));
+        }
+
+        let files = by_task.len();
+        for (task_id, mut trees) in by_task {
+            trees.sort_by_key(|tree| tree.exception.map(|kind| kind.as_str()));
+            store.save(task_id.as_deref(), trees)?;
+        }
+        Ok(files)
+    }
+
+    /// Load the rule trees saved with [`save_rule_trees`](Self::save_rule_trees)
+    ///
+    /// Loaded trees replace the trees in the repository. Returns the number of
+    /// trees loaded.
+    pub async fn load_rule_trees(&self, store: &RuleTreeStore) -> WorkflowResult<usize> {
+        let files = store.load_all()?;
+        let mut loaded = 0;
+        for file in files {
+            for tree in &file.trees {
+                let engine = tree.to_engine()?;
+                match tree.exception {
+                    Some(kind) => {
+                        self.exception_rules
+                            .write()
+                            .await
+                            .insert((kind, file.task_id.clone()), engine);
+                    }
+                    None if file.task_id.is_none() => {
+                        *self.rdr_engine.write().await = engine;
+                    }
+                    None => {
+                        return Err(WorkflowError::Parse(format!(
+                            "Worklet selection rules of task {:?} are not supported",
+                            file.task_id
+                        )));
+                    }
+                }
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Find a worklet by name or ID
+    pub async fn find_by_name(&self, name: &str) -> Option<WorkletId> {
+        let worklets = self.worklets.read().await;
+        worklets
+            .iter()
+            .find(|(id, worklet)| worklet.metadata.name == name || id.0.to_string() == name)
+            .map(|(id, _)| *id)
+    }
+
     /// Register a worklet
     pub async fn register(&self, worklet: Worklet) -> WorkflowResult<()> {
         let mut worklets = self.worklets.write().await;
@@ -427,6 +605,15 @@ impl Default for WorkletRepository {
     }
 }
 
+/// Exception rule tree of the context's task, or the case-level tree without a task
+fn exception_tree_key(
+    kind: ExceptionKind,
+    context: &ExceptionContext,
+) -> (ExceptionKind, Option<String>) {
+    let task_id = (!context.task_id.is_empty()).then(|| context.task_id.clone());
+    (kind, task_id)
+}
+
 /// Worklet executor
 /// Trait for worklet execution backend - breaks circular dependency
 ///
@@ -562,6 +749,10 @@ mod tests {
                 start_condition: None,
                 end_condition: None,
                 source_turtle: None,
+                variables: Vec::new(),
+                iri: None,
+                version: None,
+                subnets: Vec::new(),
             },
             rules: vec![WorkletRule {
                 id: "rule1".to_string(),
@@ -602,6 +793,10 @@ mod tests {
                 start_condition: None,
                 end_condition: None,
                 source_turtle: None,
+                variables: Vec::new(),
+                iri: None,
+                version: None,
+                subnets: Vec::new(),
             },
             rules: vec![WorkletRule {
                 id: "rule1".to_string(),
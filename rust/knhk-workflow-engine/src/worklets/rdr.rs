@@ -13,7 +13,9 @@
 //! - Cornerstone cases (representative examples)
 //! - Rule conflict resolution
 
+use crate::data::expression::evaluate_predicate;
 use crate::error::{WorkflowError, WorkflowResult};
+use crate::worklets::exlet::Exlet;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -21,7 +23,7 @@ use std::collections::HashMap;
 pub type WorkletId = String;
 
 /// Exception context for rule evaluation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExceptionContext {
     /// Exception type
     pub exception_type: String,
@@ -35,8 +37,30 @@ pub struct ExceptionContext {
     pub metadata: HashMap<String, String>,
 }
 
+impl ExceptionContext {
+    /// Variables visible to rule conditions
+    pub fn scope(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut scope = self.data.as_object().cloned().unwrap_or_default();
+        let context = [
+            ("exception_type", &self.exception_type),
+            ("task_id", &self.task_id),
+            ("case_id", &self.case_id),
+        ];
+        for (name, value) in context.into_iter().chain(
+            self.metadata
+                .iter()
+                .map(|(name, value)| (name.as_str(), value)),
+        ) {
+            scope
+                .entry(name.to_string())
+                .or_insert_with(|| serde_json::Value::String(value.clone()));
+        }
+        scope
+    }
+}
+
 /// RDR Rule
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RDRRule {
     /// Rule ID
     pub rule_id: String,
@@ -50,6 +74,9 @@ pub struct RDRRule {
     pub cornerstone_cases: Vec<ExceptionContext>,
     /// Rule priority (higher = more specific)
     pub priority: u32,
+    /// Exception-handling process concluded by the rule (exception rule trees)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exlet: Option<Exlet>,
 }
 
 /// RDR Rule Tree Node (TRIZ Principle 24: Intermediary)
@@ -76,11 +103,19 @@ pub struct RDRSelectionPlan {
     pub confidence: f64,
 }
 
+impl RDRSelectionPlan {
+    /// Most specific matched rule concluding with an exlet
+    pub fn exlet_rule(&self) -> Option<&RDRRule> {
+        self.matched_rules.iter().find(|r| r.exlet.is_some())
+    }
+}
+
 /// RDR Engine for worklet selection
 ///
 /// TRIZ Principle 24: Intermediary
 /// - Uses RDRSelectionPlan intermediate representation
 /// - Pre-computes rule matches for performance
+#[derive(Debug, Clone)]
 pub struct RDREngine {
     /// Root rule node
     root: RDRNode,
@@ -100,6 +135,7 @@ impl RDREngine {
                     parent_rule_id: None,
                     cornerstone_cases: vec![],
                     priority: 0,
+                    exlet: None,
                 },
                 children: vec![],
             },
@@ -107,49 +143,58 @@ impl RDREngine {
         }
     }
 
+    /// Rebuild an RDR tree from rules listed parents first
+    pub fn from_rules(rules: impl IntoIterator<Item = RDRRule>) -> WorkflowResult<Self> {
+        let mut engine = Self::new();
+        for rule in rules {
+            engine.add_rule(rule)?;
+        }
+        Ok(engine)
+    }
+
     /// Add a rule to the RDR tree
     pub fn add_rule(&mut self, rule: RDRRule) -> WorkflowResult<()> {
-        // Store rule
-        self.rules_by_id.insert(rule.rule_id.clone(), rule.clone());
+        if let Some(ref exlet) = rule.exlet {
+            exlet.validate()?;
+        }
+        if rule.rule_id == self.root.rule.rule_id || self.rules_by_id.contains_key(&rule.rule_id) {
+            return Err(WorkflowError::Validation(format!(
+                "Rule {} already exists",
+                rule.rule_id
+            )));
+        }
 
         // Insert into tree
+        let node = RDRNode {
+            rule: rule.clone(),
+            children: vec![],
+        };
         if let Some(parent_id) = &rule.parent_rule_id {
-            self.insert_rule_under_parent(&mut self.root, rule, parent_id)?;
+            Self::find_node_mut(&mut self.root, parent_id)
+                .ok_or_else(|| {
+                    WorkflowError::Internal(format!("Parent rule {} not found", parent_id))
+                })?
+                .children
+                .push(node);
         } else {
             // Add as child of root
-            self.root.children.push(RDRNode {
-                rule,
-                children: vec![],
-            });
+            self.root.children.push(node);
         }
 
+        // Store rule
+        self.rules_by_id.insert(rule.rule_id.clone(), rule);
+
         Ok(())
     }
 
-    /// Insert rule under parent (recursive)
-    fn insert_rule_under_parent(
-        &mut self,
-        node: &mut RDRNode,
-        rule: RDRRule,
-        parent_id: &str,
-    ) -> WorkflowResult<()> {
-        if node.rule.rule_id == *parent_id {
-            node.children.push(RDRNode {
-                rule,
-                children: vec![],
-            });
-            Ok(())
-        } else {
-            for child in &mut node.children {
-                if let Ok(()) = self.insert_rule_under_parent(child, rule.clone(), parent_id) {
-                    return Ok(());
-                }
-            }
-            Err(WorkflowError::Internal(format!(
-                "Parent rule {} not found",
-                parent_id
-            )))
+    /// Find a rule node (recursive)
+    fn find_node_mut<'a>(node: &'a mut RDRNode, rule_id: &str) -> Option<&'a mut RDRNode> {
+        if node.rule.rule_id == rule_id {
+            return Some(node);
         }
+        node.children
+            .iter_mut()
+            .find_map(|child| Self::find_node_mut(child, rule_id))
     }
 
     /// Select worklet using RDR (TRIZ Principle 24: Intermediary)
@@ -201,20 +246,25 @@ impl RDREngine {
         Ok(())
     }
 
-    /// Evaluate condition (simplified implementation)
+    /// Evaluate condition
     ///
-    /// In production, would use a proper expression evaluator
+    /// Conditions are data expressions over the exception data, with
+    /// `exception_type`, `task_id`, `case_id` and the metadata entries in scope
+    /// (data fields take precedence). Conditions that do not parse fall back to
+    /// matching `exception_type`/`task_id` by string.
     fn evaluate_condition(
         &self,
         condition: &str,
         context: &ExceptionContext,
     ) -> WorkflowResult<bool> {
-        // Simple condition evaluation
-        // In production, use proper expression evaluator (e.g., evalexpr, rhai)
         if condition == "true" {
             return Ok(true);
         }
 
+        if let Ok(matches) = evaluate_predicate(condition, &context.scope()) {
+            return Ok(matches);
+        }
+
         // Basic string matching for demonstration
         let condition_lower = condition.to_lowercase();
         if condition_lower.contains("exception_type") {
@@ -256,6 +306,24 @@ impl RDREngine {
     pub fn get_all_rules(&self) -> Vec<&RDRRule> {
         self.rules_by_id.values().collect()
     }
+
+    /// Root rule (always matches, concludes with the default worklet)
+    pub fn root_rule(&self) -> &RDRRule {
+        &self.root.rule
+    }
+
+    /// All rules except the root, each listed before its children
+    pub fn rules_in_tree_order(&self) -> Vec<&RDRRule> {
+        fn collect<'a>(node: &'a RDRNode, rules: &mut Vec<&'a RDRRule>) {
+            for child in &node.children {
+                rules.push(&child.rule);
+                collect(child, rules);
+            }
+        }
+        let mut rules = Vec::with_capacity(self.rules_by_id.len());
+        collect(&self.root, &mut rules);
+        rules
+    }
 }
 
 impl Default for RDREngine {
@@ -285,6 +353,7 @@ mod tests {
             parent_rule_id: None,
             cornerstone_cases: vec![],
             priority: 10,
+            exlet: None,
         };
 
         let result = engine.add_rule(rule);
@@ -303,6 +372,7 @@ mod tests {
             parent_rule_id: None,
             cornerstone_cases: vec![],
             priority: 10,
+            exlet: None,
         };
         engine.add_rule(rule).unwrap();
 
@@ -318,4 +388,53 @@ mod tests {
         let plan = engine.select_worklet(&context).unwrap();
         assert_eq!(plan.selected_worklet, Some("timeout-handler".to_string()));
     }
+
+    #[test]
+    fn test_exlet_selected_from_data_condition() {
+        use crate::worklets::exlet::ExletPrimitive;
+
+        let mut engine = RDREngine::new();
+        let refund = Exlet::new("refund")
+            .then(ExletPrimitive::SuspendCase)
+            .then(ExletPrimitive::Compensate {
+                worklet: "refund".to_string(),
+            })
+            .then(ExletPrimitive::ContinueCase);
+        engine
+            .add_rule(RDRRule {
+                rule_id: "large".to_string(),
+                condition: "exception_type == 'item_post_constraint' && amount > 1000".to_string(),
+                worklet_id: "refund".to_string(),
+                parent_rule_id: None,
+                cornerstone_cases: vec![],
+                priority: 10,
+                exlet: Some(refund.clone()),
+            })
+            .unwrap();
+
+        let mut context = ExceptionContext {
+            exception_type: "item_post_constraint".to_string(),
+            task_id: "pay".to_string(),
+            case_id: "case1".to_string(),
+            data: serde_json::json!({"amount": 5000}),
+            metadata: HashMap::new(),
+        };
+        let plan = engine.select_worklet(&context).unwrap();
+        assert_eq!(plan.exlet_rule().unwrap().exlet, Some(refund));
+
+        context.data = serde_json::json!({"amount": 10});
+        let plan = engine.select_worklet(&context).unwrap();
+        assert!(plan.exlet_rule().is_none());
+
+        let invalid = RDRRule {
+            rule_id: "invalid".to_string(),
+            condition: "true".to_string(),
+            worklet_id: "none".to_string(),
+            parent_rule_id: None,
+            cornerstone_cases: vec![],
+            priority: 1,
+            exlet: Some(Exlet::new("empty")),
+        };
+        assert!(engine.add_rule(invalid).is_err());
+    }
 }
@@ -0,0 +1,580 @@
+//! RDR rule learning from cornerstone cases
+//!
+//! Based on: org.yawlfoundation.yawl.worklet.rdr
+//!
+//! When an operator rejects the conclusion selected for a case, the rule tree is
+//! refined the ripple-down way: a new rule is added as an exception (child) of
+//! the rule that concluded.
+//! 1. The difference list compares the case with the concluding rule's
+//!    cornerstone case
+//! 2. Candidate conditions are proposed from the differences; each holds for the
+//!    case and not for the cornerstone
+//! 3. Candidates are validated against every stored cornerstone case: a rule
+//!    that would change the conclusion of another cornerstone is rejected
+//! 4. The accepted rule keeps the case as its cornerstone
+//!
+//! Rule trees are persisted per task with [`RuleTreeStore`], one JSON file per
+//! task in a versioned format.
+
+use crate::data::expression::evaluate_predicate;
+use crate::error::{WorkflowError, WorkflowResult};
+use crate::worklets::exlet::{ExceptionKind, Exlet};
+use crate::worklets::rdr::{ExceptionContext, RDREngine, RDRRule};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Context attributes never used in learned conditions
+const IGNORED_ATTRIBUTES: &[&str] = &["case_id"];
+
+/// Attribute whose value differs between a case and a cornerstone case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDifference {
+    /// Attribute name
+    pub attribute: String,
+    /// Value in the case
+    pub case_value: Value,
+    /// Value in the cornerstone case (`None` if absent)
+    pub cornerstone_value: Option<Value>,
+}
+
+/// Condition proposed for a refinement rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateCondition {
+    /// Condition expression
+    pub condition: String,
+    /// Cornerstone cases (by case ID) whose conclusion the rule would change
+    pub misclassified: Vec<String>,
+}
+
+impl CandidateCondition {
+    /// Whether the rule keeps the conclusion of every stored cornerstone case
+    pub fn is_valid(&self) -> bool {
+        self.misclassified.is_empty()
+    }
+}
+
+/// Refinement proposed when the selection for a case is rejected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefinementProposal {
+    /// Rule whose conclusion was rejected (parent of the new rule)
+    pub parent_rule_id: String,
+    /// Rejected conclusion (exlet or worklet)
+    pub rejected_conclusion: String,
+    /// Differences between the case and the parent rule's cornerstone case
+    pub differences: Vec<AttributeDifference>,
+    /// Candidate conditions, most general first
+    pub candidates: Vec<CandidateCondition>,
+}
+
+impl RefinementProposal {
+    /// Candidates that misclassify no cornerstone case
+    pub fn valid_candidates(&self) -> impl Iterator<Item = &CandidateCondition> {
+        self.candidates.iter().filter(|c| c.is_valid())
+    }
+}
+
+impl RDREngine {
+    /// Rule concluding for a case: the most specific matching rule
+    pub fn concluding_rule(&self, context: &ExceptionContext) -> WorkflowResult<RDRRule> {
+        let plan = self.select_worklet(context)?;
+        Ok(plan
+            .matched_rules
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| self.root_rule().clone()))
+    }
+
+    /// Cornerstone cases of all rules
+    pub fn cornerstone_cases(&self) -> Vec<&ExceptionContext> {
+        self.rules_in_tree_order()
+            .into_iter()
+            .flat_map(|rule| rule.cornerstone_cases.iter())
+            .collect()
+    }
+
+    /// Propose conditions for a rule correcting the conclusion for a case
+    pub fn propose_refinement(
+        &self,
+        context: &ExceptionContext,
+    ) -> WorkflowResult<RefinementProposal> {
+        let parent = self.concluding_rule(context)?;
+        let cornerstone = parent.cornerstone_cases.first();
+        let differences = difference_list(context, cornerstone);
+
+        // Single-attribute conditions, then all differences together
+        let mut conditions: Vec<String> = Vec::new();
+        let mut most_specific: Vec<String> = Vec::new();
+        for difference in &differences {
+            let proposed = attribute_conditions(difference);
+            if let Some(last) = proposed.last() {
+                most_specific.push(last.clone());
+            }
+            conditions.extend(proposed);
+        }
+        if most_specific.len() > 1 {
+            conditions.push(most_specific.join(" && "));
+        }
+
+        let case_scope = context.scope();
+        let mut candidates = Vec::new();
+        for condition in conditions {
+            let distinguishes = evaluate_predicate(&condition, &case_scope).unwrap_or(false)
+                && !cornerstone
+                    .is_some_and(|c| evaluate_predicate(&condition, &c.scope()).unwrap_or(true));
+            if !distinguishes
+                || candidates
+                    .iter()
+                    .any(|c: &CandidateCondition| c.condition == condition)
+            {
+                continue;
+            }
+            let rule = self.refinement_rule(&parent, context, &condition, String::new(), None);
+            candidates.push(CandidateCondition {
+                misclassified: self.misclassified_cornerstones(&rule)?,
+                condition,
+            });
+        }
+
+        Ok(RefinementProposal {
+            rejected_conclusion: conclusion_name(&parent),
+            parent_rule_id: parent.rule_id,
+            differences,
+            candidates,
+        })
+    }
+
+    /// Cornerstone cases (by case ID) whose conclusion a new rule would change
+    pub fn misclassified_cornerstones(&self, rule: &RDRRule) -> WorkflowResult<Vec<String>> {
+        let mut trial = self.clone();
+        trial.add_rule(rule.clone())?;
+        let mut misclassified = Vec::new();
+        for cornerstone in self.cornerstone_cases() {
+            if self.concluding_rule(cornerstone)?.rule_id
+                != trial.concluding_rule(cornerstone)?.rule_id
+            {
+                misclassified.push(cornerstone.case_id.clone());
+            }
+        }
+        Ok(misclassified)
+    }
+
+    /// Add a rule correcting the conclusion for a case
+    ///
+    /// The rule refines the rule currently concluding for the case, keeps the
+    /// case as its cornerstone and must not change the conclusion of any stored
+    /// cornerstone case.
+    pub fn refine(
+        &mut self,
+        context: &ExceptionContext,
+        condition: &str,
+        worklet_id: String,
+        exlet: Option<Exlet>,
+    ) -> WorkflowResult<RDRRule> {
+        let holds = evaluate_predicate(condition, &context.scope()).map_err(|e| {
+            WorkflowError::Validation(format!("Invalid rule condition '{}': {}", condition, e))
+        })?;
+        if !holds {
+            return Err(WorkflowError::Validation(format!(
+                "Rule condition '{}' does not hold for case {}",
+                condition, context.case_id
+            )));
+        }
+
+        let parent = self.concluding_rule(context)?;
+        let rule = self.refinement_rule(&parent, context, condition, worklet_id, exlet);
+        let misclassified = self.misclassified_cornerstones(&rule)?;
+        if !misclassified.is_empty() {
+            return Err(WorkflowError::ConstraintViolation(format!(
+                "Rule condition '{}' changes the conclusion for cornerstone cases {}",
+                condition,
+                misclassified.join(", ")
+            )));
+        }
+
+        let mut refined = self.clone();
+        refined.add_rule(rule.clone())?;
+        if refined.concluding_rule(context)?.rule_id != rule.rule_id {
+            return Err(WorkflowError::ConstraintViolation(format!(
+                "Rule condition '{}' is overridden by a more specific rule for case {}",
+                condition, context.case_id
+            )));
+        }
+        *self = refined;
+        Ok(rule)
+    }
+
+    /// Build the rule refining `parent` for a case
+    fn refinement_rule(
+        &self,
+        parent: &RDRRule,
+        context: &ExceptionContext,
+        condition: &str,
+        worklet_id: String,
+        exlet: Option<Exlet>,
+    ) -> RDRRule {
+        let mut number = self.get_all_rules().len() + 1;
+        while self.get_rule(&format!("rule{}", number)).is_some() {
+            number += 1;
+        }
+        RDRRule {
+            rule_id: format!("rule{}", number),
+            condition: condition.to_string(),
+            worklet_id,
+            parent_rule_id: Some(parent.rule_id.clone()),
+            cornerstone_cases: vec![context.clone()],
+            priority: parent.priority + 1,
+            exlet,
+        }
+    }
+}
+
+/// Compare a case with a cornerstone case (all attributes differ without one)
+pub fn difference_list(
+    context: &ExceptionContext,
+    cornerstone: Option<&ExceptionContext>,
+) -> Vec<AttributeDifference> {
+    let cornerstone_scope = cornerstone.map(|c| c.scope()).unwrap_or_default();
+    let mut differences: Vec<AttributeDifference> = context
+        .scope()
+        .into_iter()
+        .filter(|(attribute, _)| !IGNORED_ATTRIBUTES.contains(&attribute.as_str()))
+        .filter(|(attribute, value)| cornerstone_scope.get(attribute) != Some(value))
+        .map(|(attribute, case_value)| AttributeDifference {
+            cornerstone_value: cornerstone_scope.get(&attribute).cloned(),
+            attribute,
+            case_value,
+        })
+        .collect();
+    differences.sort_by(|a, b| a.attribute.cmp(&b.attribute));
+    differences
+}
+
+/// Conditions on one attribute, most general first
+fn attribute_conditions(difference: &AttributeDifference) -> Vec<String> {
+    let attribute = &difference.attribute;
+    if !is_identifier(attribute) {
+        return Vec::new();
+    }
+    match (&difference.case_value, &difference.cornerstone_value) {
+        (Value::Number(value), Some(Value::Number(cornerstone))) => {
+            let above = value.as_f64() > cornerstone.as_f64();
+            let (general, specific) = if above { (">", ">=") } else { ("<", "<=") };
+            vec![
+                format!("{} {} {}", attribute, general, cornerstone),
+                format!("{} {} {}", attribute, specific, value),
+            ]
+        }
+        (value, _) => literal(value)
+            .map(|literal| vec![format!("{} == {}", attribute, literal)])
+            .unwrap_or_default(),
+    }
+}
+
+/// Expression literal of a scalar value
+fn literal(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::String(s) if !s.contains('\'') => Some(format!("'{}'", s)),
+        Value::String(s) if !s.contains('"') => Some(format!("\"{}\"", s)),
+        _ => None,
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn conclusion_name(rule: &RDRRule) -> String {
+    match rule.exlet {
+        Some(ref exlet) => exlet.name.clone(),
+        None => rule.worklet_id.clone(),
+    }
+}
+
+/// Rule tree file format identifier
+pub const RULE_TREE_FORMAT: &str = "knhk-rdr-rules";
+
+/// Current rule tree file format version
+pub const RULE_TREE_FORMAT_VERSION: u32 = 1;
+
+/// Rule tree for worklet selection or one exception kind
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleTreeDocument {
+    /// Exception kind (`None` for the worklet selection tree)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exception: Option<ExceptionKind>,
+    /// Rules, each listed before its children
+    pub rules: Vec<RDRRule>,
+}
+
+impl RuleTreeDocument {
+    /// Capture an RDR tree
+    pub fn from_engine(exception: Option<ExceptionKind>, engine: &RDREngine) -> Self {
+        Self {
+            exception,
+            rules: engine.rules_in_tree_order().into_iter().cloned().collect(),
+        }
+    }
+
+    /// Rebuild the RDR tree
+    pub fn to_engine(&self) -> WorkflowResult<RDREngine> {
+        RDREngine::from_rules(self.rules.iter().cloned())
+    }
+}
+
+/// Rule trees of a task as stored on disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleTreeFile {
+    /// Format identifier ([`RULE_TREE_FORMAT`])
+    pub format: String,
+    /// Format version
+    pub version: u32,
+    /// Task ID (`None` for case-level trees)
+    pub task_id: Option<String>,
+    /// Time the trees were saved
+    pub saved_at: DateTime<Utc>,
+    /// Rule trees
+    pub trees: Vec<RuleTreeDocument>,
+}
+
+/// Directory of rule tree files, one per task
+#[derive(Debug, Clone)]
+pub struct RuleTreeStore {
+    dir: PathBuf,
+}
+
+impl RuleTreeStore {
+    /// Open a rule tree directory, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> WorkflowResult<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// File holding the rule trees of a task (or the case-level trees)
+    pub fn path_for(&self, task_id: Option<&str>) -> PathBuf {
+        match task_id {
+            Some(task_id) => self
+                .dir
+                .join(format!("task.{}.rules.json", encode(task_id))),
+            None => self.dir.join("case.rules.json"),
+        }
+    }
+
+    /// Save the rule trees of a task, replacing the previous file
+    pub fn save(
+        &self,
+        task_id: Option<&str>,
+        trees: Vec<RuleTreeDocument>,
+    ) -> WorkflowResult<PathBuf> {
+        let file = RuleTreeFile {
+            format: RULE_TREE_FORMAT.to_string(),
+            version: RULE_TREE_FORMAT_VERSION,
+            task_id: task_id.map(str::to_string),
+            saved_at: Utc::now(),
+            trees,
+        };
+        let content = serde_json::to_string_pretty(&file).map_err(|e| {
+            WorkflowError::Internal(format!("Failed to serialize rule trees: {}", e))
+        })?;
+
+        // Write next to the target and rename, so readers never see a partial file
+        let path = self.path_for(task_id);
+        let partial = path.with_extension("json.partial");
+        std::fs::write(&partial, content)?;
+        std::fs::rename(&partial, &path)?;
+        Ok(path)
+    }
+
+    /// Load the rule trees of a task
+    pub fn load(&self, task_id: Option<&str>) -> WorkflowResult<Option<RuleTreeFile>> {
+        let path = self.path_for(task_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_rule_tree_file(&path).map(Some)
+    }
+
+    /// Load the rule trees of all tasks
+    pub fn load_all(&self) -> WorkflowResult<Vec<RuleTreeFile>> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(".rules.json"))
+            })
+            .collect();
+        paths.sort();
+        paths.iter().map(|path| read_rule_tree_file(path)).collect()
+    }
+}
+
+/// Read a rule tree file, rejecting other formats and newer versions
+fn read_rule_tree_file(path: &Path) -> WorkflowResult<RuleTreeFile> {
+    let content = std::fs::read_to_string(path)?;
+    let value: Value = serde_json::from_str(&content).map_err(|e| {
+        WorkflowError::Parse(format!("Invalid rule tree file {}: {}", path.display(), e))
+    })?;
+    if value.get("format").and_then(Value::as_str) != Some(RULE_TREE_FORMAT) {
+        return Err(WorkflowError::Parse(format!(
+            "{} is not a rule tree file",
+            path.display()
+        )));
+    }
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version == 0 || version > u64::from(RULE_TREE_FORMAT_VERSION) {
+        return Err(WorkflowError::Parse(format!(
+            "Unsupported rule tree file version {} in {} (supported: 1 to {})",
+            version,
+            path.display(),
+            RULE_TREE_FORMAT_VERSION
+        )));
+    }
+    serde_json::from_value(value).map_err(|e| {
+        WorkflowError::Parse(format!("Invalid rule tree file {}: {}", path.display(), e))
+    })
+}
+
+/// File-name-safe encoding of a task ID
+fn encode(task_id: &str) -> String {
+    let mut encoded = String::with_capacity(task_id.len());
+    for byte in task_id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worklets::exlet::ExletPrimitive;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn case(case_id: &str, data: Value) -> ExceptionContext {
+        ExceptionContext {
+            exception_type: "item_post_constraint".to_string(),
+            task_id: "review".to_string(),
+            case_id: case_id.to_string(),
+            data,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// `large` (amount > 1000) concludes `manual-review` for two cornerstones
+    fn engine() -> RDREngine {
+        RDREngine::from_rules([RDRRule {
+            rule_id: "large".to_string(),
+            condition: "amount > 1000".to_string(),
+            worklet_id: "manual-review".to_string(),
+            parent_rule_id: None,
+            cornerstone_cases: vec![
+                case("cs-5000", json!({"amount": 5000})),
+                case("cs-9000", json!({"amount": 9000, "vip": false})),
+            ],
+            priority: 10,
+            exlet: None,
+        }])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_refinement_validated_against_cornerstones() {
+        let mut engine = engine();
+        let rejected = case("case-1", json!({"amount": 8000, "vip": true}));
+
+        let proposal = engine.propose_refinement(&rejected).unwrap();
+        assert_eq!(proposal.parent_rule_id, "large");
+        assert_eq!(proposal.rejected_conclusion, "manual-review");
+        let attributes: Vec<_> = proposal
+            .differences
+            .iter()
+            .map(|d| d.attribute.as_str())
+            .collect();
+        assert_eq!(attributes, vec!["amount", "vip"]);
+
+        let verdicts: HashMap<_, _> = proposal
+            .candidates
+            .iter()
+            .map(|c| (c.condition.as_str(), c.is_valid()))
+            .collect();
+        assert_eq!(verdicts.get("amount > 5000"), Some(&false));
+        assert_eq!(verdicts.get("vip == true"), Some(&true));
+        assert_eq!(verdicts.get("amount >= 8000 && vip == true"), Some(&true));
+
+        assert!(matches!(
+            engine.refine(&rejected, "amount > 5000", "vip-review".to_string(), None),
+            Err(WorkflowError::ConstraintViolation(_))
+        ));
+        assert!(engine
+            .refine(&rejected, "vip == false", "vip-review".to_string(), None)
+            .is_err());
+        let rule = engine
+            .refine(&rejected, "vip == true", "vip-review".to_string(), None)
+            .unwrap();
+
+        assert_eq!(rule.parent_rule_id.as_deref(), Some("large"));
+        assert_eq!(
+            engine.select_worklet(&rejected).unwrap().selected_worklet,
+            Some("vip-review".to_string())
+        );
+        for cornerstone in engine.cornerstone_cases().into_iter().take(2) {
+            assert_eq!(
+                engine.concluding_rule(cornerstone).unwrap().rule_id,
+                "large"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rule_tree_store_round_trip_and_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RuleTreeStore::new(dir.path()).unwrap();
+        let mut engine = engine();
+        engine
+            .refine(
+                &case("case-1", json!({"amount": 8000, "vip": true})),
+                "vip == true",
+                "vip-review".to_string(),
+                Some(Exlet::new("vip-review").then(ExletPrimitive::SuspendItem)),
+            )
+            .unwrap();
+
+        let task_id = "http://example.org/claim#review";
+        let path = store
+            .save(
+                Some(task_id),
+                vec![RuleTreeDocument::from_engine(
+                    Some(ExceptionKind::ItemPostConstraint),
+                    &engine,
+                )],
+            )
+            .unwrap();
+        let loaded = store.load(Some(task_id)).unwrap().unwrap();
+        assert_eq!(loaded.task_id.as_deref(), Some(task_id));
+        assert_eq!(loaded.version, RULE_TREE_FORMAT_VERSION);
+        let restored = loaded.trees[0].to_engine().unwrap();
+        assert_eq!(
+            RuleTreeDocument::from_engine(None, &restored).rules,
+            RuleTreeDocument::from_engine(None, &engine).rules
+        );
+        assert!(store.load(None).unwrap().is_none());
+
+        let mut future: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        future["version"] = json!(RULE_TREE_FORMAT_VERSION + 1);
+        std::fs::write(&path, future.to_string()).unwrap();
+        assert!(store.load_all().is_err());
+    }
+}
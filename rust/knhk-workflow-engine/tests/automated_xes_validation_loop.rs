@@ -48,6 +48,10 @@ fn create_test_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -73,6 +77,10 @@ fn create_test_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -105,6 +113,10 @@ fn create_test_workflow() -> WorkflowSpec {
         conditions: HashMap::new(),
         flows,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     }
 }
 
@@ -0,0 +1,153 @@
+//! Integration tests for cancellation regions (YAWL cancellation sets)
+
+use knhk_workflow_engine::{
+    case::CaseState,
+    executor::WorkflowEngine,
+    parser::{CancellationRegion, WorkflowSpec},
+    services::work_items::WorkItemState,
+    state::{StateEvent, StateStore},
+    testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder},
+    CaseId,
+};
+use tempfile::TempDir;
+
+/// Race: start → (timeout | ship) → done, where timeout cancels ship
+fn create_race_workflow() -> WorkflowSpec {
+    WorkflowSpecBuilder::new("cancel_region")
+        .add_task(
+            TaskBuilder::new("timeout", "Timeout")
+                .with_cancellation(CancellationRegion::region(["ship"]))
+                .build(),
+        )
+        .add_task(TaskBuilder::new("ship", "Ship").build())
+        .with_auto_conditions("timeout", "done")
+        .add_flow("condition:timeout", "timeout")
+        .add_flow("condition:timeout", "ship")
+        .add_flow("timeout", "condition:done")
+        .add_flow("ship", "condition:done")
+        .build()
+}
+
+/// Sequence: start → reject (cancels case) → archive → done
+fn create_cancel_case_workflow() -> WorkflowSpec {
+    WorkflowSpecBuilder::new("cancel_case")
+        .add_task(
+            TaskBuilder::new("reject", "Reject")
+                .with_cancellation(CancellationRegion::case())
+                .build(),
+        )
+        .add_task(TaskBuilder::new("archive", "Archive").build())
+        .with_auto_conditions("reject", "done")
+        .add_flow("condition:reject", "reject")
+        .add_flow("reject", "archive")
+        .add_flow("archive", "condition:done")
+        .build()
+}
+
+async fn history(engine: &WorkflowEngine, case_id: CaseId) -> Vec<StateEvent> {
+    let store = engine.state_store().read().await.clone();
+    store.load_case_history(&case_id).unwrap()
+}
+
+fn started(history: &[StateEvent], task: &str) -> bool {
+    history
+        .iter()
+        .any(|e| matches!(e, StateEvent::TaskStarted { task_id, .. } if task_id == task))
+}
+
+#[tokio::test]
+async fn test_region_removes_tokens_and_withdraws_work_items() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_race_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({}))
+        .await
+        .unwrap();
+    // Open work item of a ship instance (e.g. offered to a warehouse clerk)
+    let work_item_id = engine
+        .work_item_service()
+        .create_work_item(
+            case_id.to_string(),
+            spec.id,
+            "ship_0".to_string(),
+            serde_json::json!({}),
+        )
+        .await
+        .unwrap();
+
+    engine.execute_case(case_id).await.unwrap();
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    let events = history(&engine, case_id).await;
+    assert!(!started(&events, "ship"), "Cancelled task must not run");
+    let region_event = events.iter().find_map(|e| match e {
+        StateEvent::RegionCancelled {
+            task_id,
+            cancelled,
+            withdrawn_work_items,
+            ..
+        } => Some((
+            task_id.clone(),
+            cancelled.clone(),
+            withdrawn_work_items.clone(),
+        )),
+        _ => None,
+    });
+    assert_eq!(
+        region_event,
+        Some((
+            "timeout".to_string(),
+            vec!["ship".to_string()],
+            vec![work_item_id.clone()]
+        ))
+    );
+    let work_item = engine
+        .work_item_service()
+        .get_work_item(&work_item_id)
+        .await
+        .unwrap();
+    assert_eq!(work_item.state, WorkItemState::Cancelled);
+}
+
+#[tokio::test]
+async fn test_cancel_case_scope_stops_the_case() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_cancel_case_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    engine.execute_case(case_id).await.unwrap();
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Cancelled);
+    let events = history(&engine, case_id).await;
+    assert!(started(&events, "reject"));
+    assert!(!started(&events, "archive"));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, StateEvent::RegionCancelled { task_id, .. } if task_id == "reject")));
+}
+
+#[tokio::test]
+async fn test_register_rejects_unknown_cancellation_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let mut spec = create_race_workflow();
+    if let Some(task) = spec.tasks.get_mut("timeout") {
+        task.cancellation = Some(CancellationRegion::region(["ship", "pack"]));
+    }
+
+    let result = engine.register_workflow(spec).await;
+
+    assert!(
+        result.is_err(),
+        "Unknown cancellation target must be rejected"
+    );
+}
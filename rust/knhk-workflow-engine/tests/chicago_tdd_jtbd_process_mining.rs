@@ -132,6 +132,10 @@ fn create_sequential_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -157,6 +161,10 @@ fn create_sequential_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -182,6 +190,10 @@ fn create_sequential_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -220,6 +232,10 @@ fn create_sequential_workflow() -> WorkflowSpec {
         conditions: HashMap::new(),
         flows,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     }
 }
 
@@ -52,6 +52,10 @@ fn create_test_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -77,6 +81,10 @@ fn create_test_workflow() -> WorkflowSpec {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
         },
     );
 
@@ -109,6 +117,10 @@ fn create_test_workflow() -> WorkflowSpec {
         conditions: HashMap::new(),
         flows,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     }
 }
 
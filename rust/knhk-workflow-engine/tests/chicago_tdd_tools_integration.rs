@@ -27,6 +27,7 @@ use knhk_workflow_engine::parser::{
     Condition, JoinType, SplitType, Task, TaskType, WorkflowSpec, WorkflowSpecId,
 };
 use knhk_workflow_engine::state::StateStore;
+use knhk_workflow_engine::testing::chicago_tdd::complete_work_items_while;
 use knhk_workflow_engine::WorkflowEngine;
 use std::collections::HashMap;
 
@@ -50,6 +51,10 @@ chicago_async_test!(test_workflow_registration_and_retrieval, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     let task = Task {
@@ -69,6 +74,10 @@ chicago_async_test!(test_workflow_registration_and_retrieval, {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
         input_parameters: vec![],
         output_parameters: vec![],
     };
@@ -112,6 +121,10 @@ chicago_async_test!(test_case_creation_and_state_transitions, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     // Create start condition
@@ -155,6 +168,10 @@ chicago_async_test!(test_case_creation_and_state_transitions, {
         required_roles: vec!["test_role".to_string()],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
         input_parameters: vec![],
         output_parameters: vec![],
     };
@@ -184,7 +201,7 @@ chicago_async_test!(test_case_creation_and_state_transitions, {
     assert_eq_enhanced!(&case.state, &CaseState::Running);
 
     // Act: Execute case
-    engine.execute_case(case_id).await?;
+    complete_work_items_while(&engine, engine.execute_case(case_id)).await?;
 
     // Assert: Case completes (may be Completed or still Running depending on implementation)
     let case = engine.get_case(case_id).await?;
@@ -213,6 +230,10 @@ chicago_async_test!(test_case_execution_with_multiple_tasks, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     // Create start condition
@@ -266,6 +287,10 @@ chicago_async_test!(test_case_execution_with_multiple_tasks, {
             required_roles: vec!["test_role".to_string()],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
             input_parameters: vec![],
             output_parameters: vec![],
         };
@@ -284,7 +309,7 @@ chicago_async_test!(test_case_execution_with_multiple_tasks, {
     // Act: Create and execute case
     let case_id = engine.create_case(spec.id, test_data).await?;
     engine.start_case(case_id).await?;
-    engine.execute_case(case_id).await?;
+    complete_work_items_while(&engine, engine.execute_case(case_id)).await?;
 
     // Assert: Case executed successfully
     let case = engine.get_case(case_id).await?;
@@ -317,6 +342,10 @@ chicago_async_test!(test_error_handling_invalid_workflow, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     // Act: Try to register empty workflow
@@ -411,6 +440,10 @@ chicago_async_test!(test_case_cancellation, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     let task = Task {
@@ -430,6 +463,10 @@ chicago_async_test!(test_case_cancellation, {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
         input_parameters: vec![],
         output_parameters: vec![],
     };
@@ -485,6 +522,10 @@ chicago_async_test!(test_multiple_workflows_and_cases, {
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         let task = Task {
@@ -504,6 +545,10 @@ chicago_async_test!(test_multiple_workflows_and_cases, {
             required_roles: vec![],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
             input_parameters: vec![],
             output_parameters: vec![],
         };
@@ -576,6 +621,10 @@ chicago_async_test!(test_state_persistence, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     let task = Task {
@@ -595,6 +644,10 @@ chicago_async_test!(test_state_persistence, {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
         input_parameters: vec![],
         output_parameters: vec![],
     };
@@ -643,6 +696,10 @@ chicago_async_test!(test_admission_gate_integration, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     let task = Task {
@@ -662,6 +719,10 @@ chicago_async_test!(test_admission_gate_integration, {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
         input_parameters: vec![],
         output_parameters: vec![],
     };
@@ -725,6 +786,10 @@ chicago_async_test!(test_complete_workflow_lifecycle, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     // Create start condition
@@ -777,6 +842,10 @@ chicago_async_test!(test_complete_workflow_lifecycle, {
             required_roles: vec!["test_role".to_string()],
             required_capabilities: vec![],
             exception_worklet: None,
+            multi_instance: None,
+            cancellation: None,
+            decomposition: None,
+            message: None,
             input_parameters: vec![],
             output_parameters: vec![],
         };
@@ -816,7 +885,7 @@ chicago_async_test!(test_complete_workflow_lifecycle, {
     assert_eq_msg!(&case.state, &CaseState::Running, "Case should be running");
 
     // Act: Execute case
-    engine.execute_case(case_id).await?;
+    complete_work_items_while(&engine, engine.execute_case(case_id)).await?;
 
     // Assert: Case execution completed
     let case = engine.get_case(case_id).await?;
@@ -847,6 +916,10 @@ chicago_async_test!(test_create_case_rejects_max_run_len_violation, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     let task = Task {
@@ -866,6 +939,10 @@ chicago_async_test!(test_create_case_rejects_max_run_len_violation, {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
         input_parameters: vec![],
         output_parameters: vec![],
     };
@@ -930,6 +1007,10 @@ chicago_async_test!(test_create_case_accepts_valid_max_run_len, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
 
     let task = Task {
@@ -949,6 +1030,10 @@ chicago_async_test!(test_create_case_accepts_valid_max_run_len, {
         required_roles: vec![],
         required_capabilities: vec![],
         exception_worklet: None,
+        multi_instance: None,
+        cancellation: None,
+        decomposition: None,
+        message: None,
         input_parameters: vec![],
         output_parameters: vec![],
     };
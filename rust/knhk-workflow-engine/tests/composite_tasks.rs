@@ -0,0 +1,339 @@
+//! Integration tests for composite tasks running versioned sub-nets as child cases
+
+use knhk_workflow_engine::{
+    case::CaseState,
+    executor::WorkflowEngine,
+    parser::{CancellationRegion, SubNetRef, WorkflowParser, WorkflowSpec},
+    patterns::multiple_instance::{MiCreationMode, MultiInstanceSpec},
+    services::work_items::WorkItemState,
+    state::{StateEvent, StateStore},
+    testing::chicago_tdd::{complete_work_items_while, TaskBuilder, WorkflowSpecBuilder},
+    CaseId,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+const REVIEW_IRI: &str = "http://example.org/review";
+
+/// Sub-net: start → check (human) → done
+fn create_review_subnet(version: &str, task_id: &str) -> WorkflowSpec {
+    WorkflowSpecBuilder::new(format!("review {}", version))
+        .with_version(REVIEW_IRI, version)
+        .add_task(
+            TaskBuilder::new(task_id, "Check")
+                .add_required_role("reviewer")
+                .build(),
+        )
+        .with_auto_conditions(task_id, "reviewed")
+        .add_flow(format!("condition:{}", task_id), task_id)
+        .add_flow(task_id, "condition:reviewed")
+        .build()
+}
+
+/// Parent: start → review (composite) → done
+fn create_parent_workflow(subnet: SubNetRef) -> WorkflowSpec {
+    WorkflowSpecBuilder::new("order")
+        .add_task(
+            TaskBuilder::new("review", "Review Order")
+                .with_decomposition(subnet)
+                .build(),
+        )
+        .with_auto_conditions("review", "done")
+        .add_flow("condition:review", "review")
+        .add_flow("review", "condition:done")
+        .build()
+}
+
+/// Wait until the composite task of the parent case launched its sub-case
+async fn wait_for_sub_case(engine: &WorkflowEngine, case_id: CaseId) -> CaseId {
+    for _ in 0..200 {
+        if let Some(child_id) = engine.sub_cases(case_id).first() {
+            return *child_id;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected a sub-case of case {}", case_id);
+}
+
+async fn history(engine: &WorkflowEngine, case_id: CaseId) -> Vec<StateEvent> {
+    let store = engine.state_store().read().await.clone();
+    store.load_case_history(&case_id).unwrap()
+}
+
+#[tokio::test]
+async fn test_composite_task_runs_latest_subnet_as_child_case() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path()).unwrap(),
+    ));
+    let review_v1 = create_review_subnet("1.9.0", "check");
+    let review_v2 = create_review_subnet("1.10.0", "check");
+    engine.register_workflow(review_v1).await.unwrap();
+    engine.register_workflow(review_v2.clone()).await.unwrap();
+    let parent = create_parent_workflow(SubNetRef::iri(REVIEW_IRI, None));
+    engine.register_workflow(parent.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(parent.id, serde_json::json!({"order": 42}))
+        .await
+        .unwrap();
+
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+    let child_id = wait_for_sub_case(&engine, case_id).await;
+    // The reviewer approves in the sub-case
+    let mut work_item = None;
+    for _ in 0..50 {
+        work_item = engine
+            .work_item_service()
+            .find_open_work_item(&child_id.to_string(), "check")
+            .await;
+        if work_item.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    engine
+        .work_item_service()
+        .complete(
+            &work_item.unwrap().id,
+            serde_json::json!({"order": 42, "approved": true}),
+        )
+        .await
+        .unwrap();
+    runner.await.unwrap().unwrap();
+
+    let child = engine.get_case(child_id).await.unwrap();
+    assert_eq!(child.spec_id, review_v2.id, "Latest sub-net version runs");
+    assert_eq!(child.state, CaseState::Completed);
+    assert_eq!(child.data["order"], 42, "Sub-case receives the task inputs");
+    assert_eq!(child.parent.as_ref().unwrap().case_id, case_id);
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(
+        case.data["approved"], true,
+        "Sub-case outputs are mapped back"
+    );
+
+    for events in [
+        history(&engine, case_id).await,
+        history(&engine, child_id).await,
+    ] {
+        assert!(events.iter().any(|e| matches!(
+            e,
+            StateEvent::SubCaseFinished { child_case_id, child_state, .. }
+                if *child_case_id == child_id && child_state == "completed"
+        )));
+    }
+    let parent_xes = engine.export_case_to_xes(case_id).await.unwrap();
+    assert!(parent_xes.contains(&format!(
+        "<string key=\"subcase:child\" value=\"{}\"/>",
+        child_id
+    )));
+    let child_xes = engine.export_case_to_xes(child_id).await.unwrap();
+    assert!(child_xes.contains(&format!(
+        "<string key=\"subcase:parent\" value=\"{}\"/>",
+        case_id
+    )));
+}
+
+#[tokio::test]
+async fn test_composite_task_runs_pinned_subnet_version() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let review_v1 = create_review_subnet("1.0.0", "check");
+    let review_v2 = create_review_subnet("2.0.0", "check");
+    engine.register_workflow(review_v1.clone()).await.unwrap();
+    engine.register_workflow(review_v2).await.unwrap();
+    let parent = create_parent_workflow(SubNetRef::iri(REVIEW_IRI, Some("1.0.0")));
+    engine.register_workflow(parent.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(parent.id, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    complete_work_items_while(&engine, engine.execute_case(case_id))
+        .await
+        .unwrap();
+
+    let sub_cases = engine.sub_cases(case_id);
+    assert_eq!(sub_cases.len(), 1);
+    let child = engine.get_case(sub_cases[0]).await.unwrap();
+    assert_eq!(child.spec_id, review_v1.id);
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Completed
+    );
+}
+
+#[tokio::test]
+async fn test_cancelled_sub_case_cancels_parent() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let subnet = WorkflowSpecBuilder::new("reject")
+        .add_task(
+            TaskBuilder::new("reject", "Reject")
+                .add_required_role("reviewer")
+                .with_cancellation(CancellationRegion::case())
+                .build(),
+        )
+        .with_auto_conditions("reject", "rejected")
+        .add_flow("condition:reject", "reject")
+        .add_flow("reject", "condition:rejected")
+        .build();
+    let parent = WorkflowSpecBuilder::new("order")
+        .add_subnet(subnet.clone())
+        .add_task(
+            TaskBuilder::new("review", "Review Order")
+                .with_decomposition(SubNetRef::spec(subnet.id))
+                .build(),
+        )
+        .with_auto_conditions("review", "done")
+        .add_flow("condition:review", "review")
+        .add_flow("review", "condition:done")
+        .build();
+    engine.register_workflow(parent.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(parent.id, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    let result = complete_work_items_while(&engine, engine.execute_case(case_id)).await;
+
+    assert!(result.is_err(), "Composite task fails with its sub-case");
+    let child_id = engine.sub_cases(case_id)[0];
+    assert_eq!(
+        engine.get_case(child_id).await.unwrap().state,
+        CaseState::Cancelled
+    );
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Cancelled
+    );
+}
+
+#[tokio::test]
+async fn test_cancelling_parent_cancels_running_sub_case() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path()).unwrap(),
+    ));
+    // Sub-net waiting on an open dynamic MI task until instances are added
+    let subnet = WorkflowSpecBuilder::new("collect quotes")
+        .add_task(
+            TaskBuilder::new("quote", "Collect Quote")
+                .with_multi_instance(MultiInstanceSpec {
+                    creation_mode: MiCreationMode::Dynamic,
+                    minimum: 0,
+                    ..MultiInstanceSpec::default()
+                })
+                .add_required_role("buyer")
+                .build(),
+        )
+        .with_auto_conditions("quote", "quoted")
+        .add_flow("condition:quote", "quote")
+        .add_flow("quote", "condition:quoted")
+        .build();
+    engine.register_workflow(subnet.clone()).await.unwrap();
+    let parent = create_parent_workflow(SubNetRef::spec(subnet.id));
+    engine.register_workflow(parent.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(parent.id, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+    let child_id = wait_for_sub_case(&engine, case_id).await;
+    for _ in 0..200 {
+        if engine.mi_task_state(child_id, "quote").is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    engine.cancel_case(case_id).await.unwrap();
+
+    assert!(runner.await.unwrap().is_err());
+    assert_eq!(
+        engine.get_case(child_id).await.unwrap().state,
+        CaseState::Cancelled
+    );
+    assert!(engine
+        .work_item_service()
+        .list_case_work_items(&child_id.to_string())
+        .await
+        .iter()
+        .all(|item| item.state != WorkItemState::Created));
+}
+
+#[tokio::test]
+async fn test_register_rejects_unknown_subnet() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    engine
+        .register_workflow(create_review_subnet("1.0.0", "check"))
+        .await
+        .unwrap();
+
+    let result = engine
+        .register_workflow(create_parent_workflow(SubNetRef::iri(
+            REVIEW_IRI,
+            Some("3.0.0"),
+        )))
+        .await;
+
+    assert!(result.is_err(), "Unregistered sub-net version is rejected");
+}
+
+#[tokio::test]
+async fn test_parse_subnet_from_same_document() {
+    let turtle = r#"
+        @prefix yawl: <http://bitflow.ai/ontology/yawl/v2#> .
+
+        <http://example.org/order> a yawl:Specification ;
+            yawl:specName "Order" ;
+            yawl:hasInputCondition <http://example.org/order/start> ;
+            yawl:hasTask <http://example.org/order/review> ;
+            yawl:hasOutputCondition <http://example.org/order/end> .
+
+        <http://example.org/order/review> a yawl:CompositeTask ;
+            yawl:taskName "Review Order" ;
+            yawl:hasDecomposesTo <http://example.org/review> .
+
+        <http://example.org/review> a yawl:Specification ;
+            yawl:specName "Review" ;
+            yawl:versionNumber "1.2.0" ;
+            yawl:hasInputCondition <http://example.org/review/start> ;
+            yawl:hasTask <http://example.org/review/check> ;
+            yawl:hasOutputCondition <http://example.org/review/end> .
+
+        <http://example.org/review/check> a yawl:AtomicTask ;
+            yawl:taskName "Check" .
+    "#;
+
+    let mut parser = WorkflowParser::new().unwrap();
+    let spec = parser.parse_turtle(turtle).unwrap();
+
+    assert_eq!(spec.name, "Order");
+    assert_eq!(spec.subnets.len(), 1);
+    let subnet = &spec.subnets[0];
+    assert_eq!(subnet.name, "Review");
+    assert_eq!(subnet.iri.as_deref(), Some(REVIEW_IRI));
+    assert_eq!(subnet.version.as_deref(), Some("1.2.0"));
+    assert_eq!(subnet.tasks.len(), 1);
+    let review = &spec.tasks["<http://example.org/order/review>"];
+    assert_eq!(
+        review.decomposition.as_ref().and_then(|d| d.spec_id),
+        Some(subnet.id)
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let subnet_id = subnet.id;
+    engine.register_workflow(spec).await.unwrap();
+    assert!(engine.get_workflow(subnet_id).await.is_ok());
+}
@@ -0,0 +1,202 @@
+//! Integration tests for crash recovery of in-flight cases
+//!
+//! Simulates a process restart by dropping the engine's in-memory cases and
+//! rebuilding them from the event-sourced case history.
+
+use knhk_workflow_engine::{
+    case::CaseState,
+    executor::WorkflowEngine,
+    parser::WorkflowSpec,
+    state::{CaseCheckpoint, CaseMarking, RecoveryOutcome, StateEvent, StateStore},
+    testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder},
+    CaseId,
+};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Sequence workflow: start → a → b → end
+fn create_sequence_workflow() -> WorkflowSpec {
+    WorkflowSpecBuilder::new("recovery_sequence")
+        .add_task(TaskBuilder::new("a", "Task A").build())
+        .add_task(TaskBuilder::new("b", "Task B").build())
+        .with_auto_conditions("a", "b")
+        .add_flow("condition:a", "a")
+        .add_flow("a", "b")
+        .add_flow("b", "condition:b")
+        .build()
+}
+
+async fn running_case(engine: &WorkflowEngine, spec: &WorkflowSpec) -> (CaseId, Arc<StateStore>) {
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({"order_id": 42}))
+        .await
+        .unwrap();
+    engine.start_case(case_id).await.unwrap();
+
+    // Checkpoint the initial marking, as the executor does before the first task
+    let store = engine.state_store().read().await.clone();
+    let case = engine.get_case(case_id).await.unwrap();
+    let checkpoint = CaseCheckpoint {
+        case,
+        marking: CaseMarking::initial(spec).unwrap(),
+        work_items: Vec::new(),
+        timers: Vec::new(),
+    };
+    store
+        .save_case_history_event(
+            &case_id,
+            &StateEvent::CaseCheckpointed {
+                case_id,
+                checkpoint: Box::new(checkpoint),
+                timestamp: chrono::Utc::now(),
+            },
+        )
+        .unwrap();
+
+    (case_id, store)
+}
+
+fn task_started(case_id: CaseId, task_id: &str) -> StateEvent {
+    StateEvent::TaskStarted {
+        case_id,
+        task_id: task_id.to_string(),
+        task_name: task_id.to_string(),
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+fn started_count(history: &[StateEvent], task: &str) -> usize {
+    history
+        .iter()
+        .filter(|e| matches!(e, StateEvent::TaskStarted { task_id, .. } if task_id == task))
+        .count()
+}
+
+#[tokio::test]
+async fn test_recovery_reexecutes_started_task() {
+    // Arrange: crash while task "a" is executing
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_sequence_workflow();
+    let (case_id, store) = running_case(&engine, &spec).await;
+    store
+        .save_case_history_event(&case_id, &task_started(case_id, "a"))
+        .unwrap();
+    engine.cases().clear();
+
+    // Act
+    let report = engine.recover_cases().await.unwrap();
+
+    // Assert
+    let recovered = report.case(case_id).unwrap();
+    assert_eq!(recovered.outcome, RecoveryOutcome::Completed);
+    assert!(recovered.from_checkpoint);
+    assert_eq!(recovered.reexecuted_tasks, vec!["a".to_string()]);
+    assert!(recovered.deduplicated_tasks.is_empty());
+    assert_eq!(report.failed_count(), 0);
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.data["order_id"], 42);
+}
+
+#[tokio::test]
+async fn test_recovery_does_not_reexecute_completed_task() {
+    // Arrange: crash after task "a" completed but before its tokens were routed
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_sequence_workflow();
+    let (case_id, store) = running_case(&engine, &spec).await;
+    store
+        .save_case_history_event(&case_id, &task_started(case_id, "a"))
+        .unwrap();
+    store
+        .save_case_history_event(
+            &case_id,
+            &StateEvent::TaskCompleted {
+                case_id,
+                task_id: "a".to_string(),
+                task_name: "a".to_string(),
+                duration_ms: 5,
+                timestamp: chrono::Utc::now(),
+            },
+        )
+        .unwrap();
+    engine.cases().clear();
+
+    // Act
+    let report = engine.recover_cases().await.unwrap();
+
+    // Assert: "a" is routed exactly once, "b" runs after recovery
+    let recovered = report.case(case_id).unwrap();
+    assert_eq!(recovered.outcome, RecoveryOutcome::Completed);
+    assert_eq!(recovered.deduplicated_tasks, vec!["a".to_string()]);
+    assert!(recovered.reexecuted_tasks.is_empty());
+
+    let history = store.load_case_history(&case_id).unwrap();
+    assert_eq!(started_count(&history, "a"), 1);
+    assert_eq!(started_count(&history, "b"), 1);
+}
+
+#[tokio::test]
+async fn test_recovery_skips_terminal_and_live_cases() {
+    // Arrange
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_sequence_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+
+    let cancelled = engine
+        .create_case(spec.id, serde_json::json!({}))
+        .await
+        .unwrap();
+    engine.cancel_case(cancelled).await.unwrap();
+    let live = engine
+        .create_case(spec.id, serde_json::json!({}))
+        .await
+        .unwrap();
+    engine.cases().remove(&cancelled);
+
+    // Act
+    let report = engine.recover_cases().await.unwrap();
+
+    // Assert
+    assert_eq!(report.scanned, 2);
+    assert_eq!(report.skipped_terminal, 1);
+    assert_eq!(report.skipped_live, 1);
+    assert!(report.cases.is_empty());
+    assert!(engine.get_case(cancelled).await.is_err());
+    assert!(engine.get_case(live).await.is_ok());
+
+    // Recovery is idempotent
+    let again = engine.recover_cases().await.unwrap();
+    assert!(again.cases.is_empty());
+}
+
+#[tokio::test]
+async fn test_case_history_keeps_only_latest_checkpoint() {
+    // Arrange
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_sequence_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    // Act: the executor checkpoints before every step
+    engine.execute_case(case_id).await.unwrap();
+
+    // Assert: the step events remain, but only one checkpoint
+    let store = engine.state_store().read().await.clone();
+    let history = store.load_case_history(&case_id).unwrap();
+    let checkpoints = history
+        .iter()
+        .filter(|event| matches!(event, StateEvent::CaseCheckpointed { .. }))
+        .count();
+    assert_eq!(checkpoints, 1);
+    assert_eq!(started_count(&history, "a"), 1);
+    assert_eq!(started_count(&history, "b"), 1);
+}
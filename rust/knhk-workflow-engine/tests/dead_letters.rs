@@ -0,0 +1,138 @@
+//! Integration tests for dead-lettered case executions and their redrive
+
+use knhk_workflow_engine::{
+    case::CaseState,
+    executor::WorkflowEngine,
+    parser::{TaskParameter, WorkflowSpec},
+    resilience::{DLQEntry, DefaultRetryPolicy, DlqEntryStatus, RetryConfig},
+    testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder},
+    CaseId, StateStore,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// start → ship (human, requires an `address` input) → shipped
+fn create_shipping_workflow() -> WorkflowSpec {
+    WorkflowSpecBuilder::new("dead_letter_shipping")
+        .add_task(
+            TaskBuilder::new("ship", "Ship Order")
+                .add_input_parameter(TaskParameter {
+                    name: "address".to_string(),
+                    param_type: "xsd:string".to_string(),
+                    mapping: None,
+                    required: true,
+                    annotations: Default::default(),
+                })
+                .add_required_role("clerk")
+                .build(),
+        )
+        .with_auto_conditions("ship", "shipped")
+        .add_flow("condition:ship", "ship")
+        .add_flow("ship", "condition:shipped")
+        .build()
+}
+
+/// Register the workflow and run a case that fails for lack of an address
+async fn failed_case(engine: &WorkflowEngine) -> (CaseId, DLQEntry) {
+    let spec = create_shipping_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({"order_id": 7}))
+        .await
+        .unwrap();
+    assert!(engine.execute_case(case_id).await.is_err());
+
+    let entries = engine.dead_letter_queue().list().unwrap();
+    assert_eq!(entries.len(), 1, "Failed execution is dead-lettered once");
+    (case_id, entries[0].clone())
+}
+
+fn with_address(entry: &DLQEntry) -> serde_json::Value {
+    let mut payload = entry.operation_data.clone();
+    payload["data"]["address"] = serde_json::json!("1 Main St");
+    payload
+}
+
+#[tokio::test]
+async fn test_poison_case_is_quarantined_then_redriven_with_fixed_payload() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let (case_id, entry) = failed_case(&engine).await;
+    assert_eq!(entry.case_id, Some(case_id));
+    assert_eq!(entry.status, DlqEntryStatus::Pending);
+    assert_eq!(entry.operation_data["data"]["order_id"], 7);
+
+    // The worker redrives the entry; the missing input is not retryable
+    let worker = engine.start_dead_letter_redrive(
+        RetryConfig::default(),
+        Arc::new(DefaultRetryPolicy),
+        Duration::from_millis(10),
+    );
+    let mut quarantined = None;
+    for _ in 0..200 {
+        let current = engine.dead_letter_queue().get(entry.id).unwrap().unwrap();
+        if current.status == DlqEntryStatus::Quarantined {
+            quarantined = Some(current);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    worker.abort();
+    let quarantined = quarantined.expect("poison entry is quarantined");
+    assert_eq!(quarantined.retry_count, 1);
+    assert!(quarantined.quarantine_reason.is_some());
+
+    // An operator fixes the payload and redrives the entry
+    engine
+        .dead_letter_queue()
+        .edit_payload(entry.id, with_address(&quarantined))
+        .unwrap();
+    engine.redrive_dead_letter(entry.id).await.unwrap();
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.data["address"], "1 Main St");
+    assert!(engine.dead_letter_queue().is_empty().unwrap());
+}
+
+#[tokio::test]
+async fn test_redrive_after_restart_rebuilds_case_from_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let (case_id, entry) = failed_case(&engine).await;
+
+    // Simulate a restart: the case is no longer live, the entry is persisted
+    engine.cases().clear();
+    engine
+        .dead_letter_queue()
+        .edit_payload(entry.id, with_address(&entry))
+        .unwrap();
+    engine.redrive_dead_letter(entry.id).await.unwrap();
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.data["order_id"], 7);
+    assert!(engine.dead_letter_queue().is_empty().unwrap());
+}
+
+#[tokio::test]
+async fn test_failed_redrive_keeps_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let (case_id, entry) = failed_case(&engine).await;
+
+    assert!(engine.redrive_dead_letter(entry.id).await.is_err());
+
+    let kept = engine.dead_letter_queue().get(entry.id).unwrap().unwrap();
+    assert_eq!(kept.case_id, Some(case_id));
+    assert_eq!(
+        engine.dead_letter_queue().list().unwrap().len(),
+        1,
+        "Redrive does not dead-letter the case again"
+    );
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Running
+    );
+}
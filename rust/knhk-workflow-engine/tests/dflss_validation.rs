@@ -568,6 +568,10 @@ chicago_async_test!(test_create_case_enforces_max_batch_size_at_ingress, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
     let spec_id = spec.id.clone();
     engine.register_workflow(spec).await?;
@@ -614,6 +618,10 @@ chicago_async_test!(test_create_case_accepts_valid_batch_size_at_ingress, {
         start_condition: None,
         end_condition: None,
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     };
     let spec_id = spec.id.clone();
     engine.register_workflow(spec).await?;
@@ -669,6 +677,10 @@ chicago_async_test!(
                 required_roles: vec![],
                 required_capabilities: vec![],
                 exception_worklet: None,
+                multi_instance: None,
+                cancellation: None,
+                decomposition: None,
+                message: None,
                 input_parameters: vec![],
                 output_parameters: vec![],
             };
@@ -684,6 +696,10 @@ chicago_async_test!(
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         // Act: Attempt to register workflow with >8 tasks
@@ -740,6 +756,10 @@ chicago_async_test!(
             start_condition: None,
             end_condition: None,
             source_turtle: None,
+            variables: Vec::new(),
+            iri: None,
+            version: None,
+            subnets: Vec::new(),
         };
 
         // Act: Attempt to register workflow with >8 flows
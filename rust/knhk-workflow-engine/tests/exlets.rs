@@ -0,0 +1,441 @@
+//! Integration tests for exlets: exception-handling processes run by the engine
+//!
+//! Work item pre/post-constraint and timeout rule trees, compensating worklets,
+//! external exceptions and the exlet steps recorded in case history.
+
+use knhk_workflow_engine::{
+    api::{models::requests::GetCaseHistoryRequest, service::CaseService},
+    case::CaseState,
+    executor::WorkflowEngine,
+    parser::WorkflowSpec,
+    resilience::{ExceptionCategory, ExceptionSeverity, YawlException},
+    services::work_items::{WorkItem, WorkItemState},
+    testing::chicago_tdd::{complete_work_items_while, TaskBuilder, WorkflowSpecBuilder},
+    worklets::{
+        ExceptionKind, Exlet, ExletPrimitive, ExletResolution, RDRRule, Worklet, WorkletId,
+        WorkletMetadata,
+    },
+    CaseId, StateStore,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// start → `task_id` (human) → done
+fn create_single_task_workflow(name: &str, task_id: &str, role: &str) -> WorkflowSpec {
+    WorkflowSpecBuilder::new(name)
+        .add_task(
+            TaskBuilder::new(task_id, task_id)
+                .add_required_role(role)
+                .build(),
+        )
+        .with_auto_conditions(task_id, "done")
+        .add_flow(&format!("condition:{}", task_id), task_id)
+        .add_flow(task_id, "condition:done")
+        .build()
+}
+
+fn exception_rule(rule_id: &str, condition: &str, exlet: Exlet) -> RDRRule {
+    RDRRule {
+        rule_id: rule_id.to_string(),
+        condition: condition.to_string(),
+        worklet_id: exlet.name.clone(),
+        parent_rule_id: None,
+        cornerstone_cases: vec![],
+        priority: 10,
+        exlet: Some(exlet),
+    }
+}
+
+fn create_engine(temp_dir: &TempDir) -> Arc<WorkflowEngine> {
+    Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path()).unwrap(),
+    ))
+}
+
+/// Wait until the case offers a work item for `task_id`
+async fn open_work_item(engine: &Arc<WorkflowEngine>, case_id: CaseId, task_id: &str) -> WorkItem {
+    for _ in 0..500 {
+        if let Some(item) = engine
+            .work_item_service()
+            .find_open_work_item(&case_id.to_string(), task_id)
+            .await
+        {
+            return item;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("Task {} of case {} offered no work item", task_id, case_id);
+}
+
+/// Raise an external exception naming the task of a case
+async fn raise_for_task(engine: &Arc<WorkflowEngine>, case_id: CaseId, task_id: &str) {
+    let mut exception = YawlException::new(
+        "Order amended by customer".to_string(),
+        ExceptionCategory::ExternalData,
+        ExceptionSeverity::Medium,
+    );
+    exception.case_id = Some(case_id.to_string());
+    exception.task_id = Some(task_id.to_string());
+    engine
+        .raise_external_exception(&exception)
+        .await
+        .unwrap()
+        .expect("exlet selected");
+}
+
+/// Register an external exception exlet with the single item step `step`
+async fn add_external_item_exlet(engine: &Arc<WorkflowEngine>, step: ExletPrimitive) {
+    engine
+        .worklet_repository()
+        .add_exception_rule(
+            ExceptionKind::External,
+            None,
+            exception_rule(
+                "amended",
+                "category == 'ExternalData'",
+                Exlet::new("amend").then(step),
+            ),
+        )
+        .await
+        .unwrap();
+}
+
+async fn history(
+    engine: &Arc<WorkflowEngine>,
+    case_id: CaseId,
+) -> Vec<(String, serde_json::Value)> {
+    CaseService::new(engine.clone())
+        .get_case_history(GetCaseHistoryRequest { case_id })
+        .await
+        .unwrap()
+        .entries
+        .into_iter()
+        .filter(|e| e.event_type == "exception_raised" || e.event_type == "exlet_step")
+        .map(|e| (e.event_type, e.data))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_post_constraint_exlet_runs_compensating_worklet() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = create_engine(&temp_dir);
+    let spec = create_single_task_workflow("claim", "review", "adjuster");
+    engine.register_workflow(spec.clone()).await.unwrap();
+
+    engine
+        .worklet_repository()
+        .register(Worklet {
+            metadata: WorkletMetadata {
+                id: WorkletId::new(),
+                name: "refund".to_string(),
+                description: "Refund the claimant".to_string(),
+                version: "1.0.0".to_string(),
+                exception_types: vec![],
+                required_context: vec![],
+                pattern_ids: vec![],
+                tags: vec![],
+            },
+            workflow_spec: create_single_task_workflow("refund_worklet", "refund", "clerk"),
+            rules: vec![],
+        })
+        .await
+        .unwrap();
+    let exlet = Exlet::new("refund-large-claims")
+        .then(ExletPrimitive::SuspendCase)
+        .then(ExletPrimitive::Compensate {
+            worklet: "refund".to_string(),
+        })
+        .then(ExletPrimitive::ContinueCase);
+    engine
+        .worklet_repository()
+        .add_exception_rule(
+            ExceptionKind::ItemPostConstraint,
+            Some("review"),
+            exception_rule("large-claim", "amount > 1000", exlet),
+        )
+        .await
+        .unwrap();
+
+    let small = engine
+        .create_case(spec.id, json!({"amount": 200}))
+        .await
+        .unwrap();
+    complete_work_items_while(&engine, engine.execute_case(small))
+        .await
+        .unwrap();
+    assert!(history(&engine, small).await.is_empty());
+
+    let large = engine
+        .create_case(spec.id, json!({"amount": 5000}))
+        .await
+        .unwrap();
+    complete_work_items_while(&engine, engine.execute_case(large))
+        .await
+        .unwrap();
+    assert_eq!(
+        engine.get_case(large).await.unwrap().state,
+        CaseState::Completed
+    );
+
+    let events = history(&engine, large).await;
+    assert_eq!(events.len(), 4, "{:?}", events);
+    assert_eq!(events[0].0, "exception_raised");
+    assert_eq!(events[0].1["exception"], json!("item_post_constraint"));
+    assert_eq!(events[0].1["rule_id"], json!("large-claim"));
+    let steps: Vec<_> = events[1..]
+        .iter()
+        .map(|(_, data)| data["step"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        steps,
+        vec!["suspend_case", "compensate(refund)", "continue_case"]
+    );
+    assert!(events[1..]
+        .iter()
+        .all(|(_, data)| data["success"] == json!(true)));
+}
+
+#[tokio::test]
+async fn test_pre_constraint_exlet_fails_case() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = create_engine(&temp_dir);
+    let spec = create_single_task_workflow("payment", "pay", "clerk");
+    engine.register_workflow(spec.clone()).await.unwrap();
+    engine
+        .worklet_repository()
+        .add_exception_rule(
+            ExceptionKind::ItemPreConstraint,
+            None,
+            exception_rule(
+                "negative-amount",
+                "amount < 0",
+                Exlet::new("reject").then(ExletPrimitive::FailCase),
+            ),
+        )
+        .await
+        .unwrap();
+
+    let case_id = engine
+        .create_case(spec.id, json!({"amount": -5}))
+        .await
+        .unwrap();
+    let result = engine.execute_case(case_id).await;
+
+    assert!(result.is_err());
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Failed
+    );
+    assert!(
+        engine.dead_letter_queue().list().unwrap().is_empty(),
+        "A case failed by an exlet is finished, not dead-lettered"
+    );
+    let events = history(&engine, case_id).await;
+    assert_eq!(events.last().unwrap().1["step"], json!("fail_case"));
+}
+
+#[tokio::test]
+async fn test_timeout_exlet_force_completes_overdue_item() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = create_engine(&temp_dir);
+    let spec = create_single_task_workflow("approval", "approve", "manager");
+    engine.register_workflow(spec.clone()).await.unwrap();
+    engine
+        .worklet_repository()
+        .add_exception_rule(
+            ExceptionKind::ItemTimeout,
+            Some("approve"),
+            exception_rule(
+                "overdue",
+                "exception_type == 'item_timeout'",
+                Exlet::new("escalate").then(ExletPrimitive::ForceCompleteItem),
+            ),
+        )
+        .await
+        .unwrap();
+
+    // Nobody works the item: only the deadline lets the task finish
+    let deadline = chrono::Utc::now() + chrono::Duration::milliseconds(300);
+    let case_id = engine
+        .create_case(spec.id, json!({"deadline": deadline.to_rfc3339()}))
+        .await
+        .unwrap();
+    let started = Instant::now();
+    engine.execute_case(case_id).await.unwrap();
+
+    assert!(
+        started.elapsed() >= Duration::from_millis(250),
+        "Timeout raised before the deadline after {:?}",
+        started.elapsed()
+    );
+    let events = history(&engine, case_id).await;
+    assert_eq!(events[0].1["exception"], json!("item_timeout"));
+    assert_eq!(events[1].1["step"], json!("force_complete_item"));
+    assert_eq!(events[1].1["success"], json!(true));
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Completed
+    );
+}
+
+#[tokio::test]
+async fn test_external_exception_suspends_case() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = create_engine(&temp_dir);
+    let spec = create_single_task_workflow("shipping", "ship", "clerk");
+    engine.register_workflow(spec.clone()).await.unwrap();
+    engine
+        .worklet_repository()
+        .add_exception_rule(
+            ExceptionKind::External,
+            None,
+            exception_rule(
+                "carrier-down",
+                "category == 'ExternalData' && severity == 'High'",
+                Exlet::new("hold").then(ExletPrimitive::SuspendCase),
+            ),
+        )
+        .await
+        .unwrap();
+    let case_id = engine.create_case(spec.id, json!({})).await.unwrap();
+    engine.start_case(case_id).await.unwrap();
+
+    let mut minor = YawlException::new(
+        "Carrier feed delayed".to_string(),
+        ExceptionCategory::ExternalData,
+        ExceptionSeverity::Low,
+    );
+    minor.case_id = Some(case_id.to_string());
+    let unhandled = engine.raise_external_exception(&minor).await.unwrap();
+
+    let mut outage = YawlException::new(
+        "Carrier API unavailable".to_string(),
+        ExceptionCategory::ExternalData,
+        ExceptionSeverity::High,
+    );
+    outage.case_id = Some(case_id.to_string());
+    let report = engine
+        .raise_external_exception(&outage)
+        .await
+        .unwrap()
+        .expect("exlet selected");
+
+    assert!(unhandled.is_none());
+    assert_eq!(report.rule_id, "carrier-down");
+    assert_eq!(report.resolution, ExletResolution::Proceed);
+    assert!(report.succeeded());
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Suspended
+    );
+    let events = history(&engine, case_id).await;
+    assert_eq!(
+        events.len(),
+        3,
+        "Unhandled external exceptions are recorded"
+    );
+    assert_eq!(events[0].1["exlet"], json!(null));
+}
+
+#[tokio::test]
+async fn test_external_exception_restarts_item() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = create_engine(&temp_dir);
+    let spec = create_single_task_workflow("orders", "pick", "picker");
+    engine.register_workflow(spec.clone()).await.unwrap();
+    add_external_item_exlet(&engine, ExletPrimitive::RestartItem).await;
+    let case_id = engine.create_case(spec.id, json!({})).await.unwrap();
+    engine.start_case(case_id).await.unwrap();
+    let execution = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+
+    let item = open_work_item(&engine, case_id, "pick").await;
+    let work_items = engine.work_item_service();
+    work_items
+        .assign(&item.id, "alice".to_string())
+        .await
+        .unwrap();
+    work_items.claim(&item.id, "alice").await.unwrap();
+    raise_for_task(&engine, case_id, "pick").await;
+
+    let restarted = engine
+        .work_item_service()
+        .get_work_item(&item.id)
+        .await
+        .unwrap();
+    assert_eq!(restarted.state, WorkItemState::Created);
+    assert_eq!(restarted.assigned_resource_id, None);
+    engine
+        .work_item_service()
+        .complete(&item.id, json!({"picked": true}))
+        .await
+        .unwrap();
+    execution.await.unwrap().unwrap();
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.data["picked"], json!(true));
+}
+
+#[tokio::test]
+async fn test_external_exception_fails_item() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = create_engine(&temp_dir);
+    let spec = create_single_task_workflow("orders", "pick", "picker");
+    engine.register_workflow(spec.clone()).await.unwrap();
+    add_external_item_exlet(&engine, ExletPrimitive::FailItem).await;
+    let case_id = engine.create_case(spec.id, json!({})).await.unwrap();
+    engine.start_case(case_id).await.unwrap();
+    let execution = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+
+    let item = open_work_item(&engine, case_id, "pick").await;
+    raise_for_task(&engine, case_id, "pick").await;
+
+    let error = execution.await.unwrap().unwrap_err();
+    assert!(error.to_string().contains("was failed"), "{}", error);
+    assert_eq!(
+        engine
+            .work_item_service()
+            .get_work_item(&item.id)
+            .await
+            .unwrap()
+            .state,
+        WorkItemState::Failed
+    );
+}
+
+#[tokio::test]
+async fn test_external_exception_cancels_item() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = create_engine(&temp_dir);
+    let spec = create_single_task_workflow("orders", "pick", "picker");
+    engine.register_workflow(spec.clone()).await.unwrap();
+    add_external_item_exlet(&engine, ExletPrimitive::CancelItem).await;
+    let case_id = engine.create_case(spec.id, json!({})).await.unwrap();
+    engine.start_case(case_id).await.unwrap();
+    let execution = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+
+    let item = open_work_item(&engine, case_id, "pick").await;
+    raise_for_task(&engine, case_id, "pick").await;
+
+    let error = execution.await.unwrap().unwrap_err();
+    assert!(error.to_string().contains("was cancelled"), "{}", error);
+    assert_eq!(
+        engine
+            .work_item_service()
+            .get_work_item(&item.id)
+            .await
+            .unwrap()
+            .state,
+        WorkItemState::Cancelled
+    );
+}
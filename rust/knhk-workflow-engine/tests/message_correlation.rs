@@ -0,0 +1,221 @@
+//! Integration tests for message-catch and message-start tasks
+
+use knhk_workflow_engine::{
+    case::CaseState,
+    executor::WorkflowEngine,
+    parser::{MessageCatch, WorkflowSpec},
+    services::{CorrelatedMessage, MessageDelivery},
+    state::StateStore,
+    testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder},
+    CaseId,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Order: start → wait_payment (catches payment by order ID) → done
+fn create_payment_workflow() -> WorkflowSpec {
+    WorkflowSpecBuilder::new("order_payment")
+        .add_task(
+            TaskBuilder::new("wait_payment", "Wait for Payment")
+                .with_message(MessageCatch::new("payment").correlate("orderId", "order.id"))
+                .build(),
+        )
+        .with_auto_conditions("wait_payment", "done")
+        .add_flow("condition:wait_payment", "wait_payment")
+        .add_flow("wait_payment", "condition:done")
+        .build()
+}
+
+/// Order: start → receive_order (message start) → wait_shipping → done
+fn create_message_started_workflow() -> WorkflowSpec {
+    WorkflowSpecBuilder::new("order_shipping")
+        .add_task(
+            TaskBuilder::new("receive_order", "Receive Order")
+                .with_message(MessageCatch::new("order-placed").starting_case())
+                .build(),
+        )
+        .add_task(
+            TaskBuilder::new("wait_shipping", "Wait for Shipping")
+                .with_message(MessageCatch::new("shipped").correlate("orderId", "orderId"))
+                .build(),
+        )
+        .with_auto_conditions("receive_order", "done")
+        .add_flow("condition:receive_order", "receive_order")
+        .add_flow("receive_order", "wait_shipping")
+        .add_flow("wait_shipping", "condition:done")
+        .build()
+}
+
+fn new_engine(temp_dir: &TempDir) -> Arc<WorkflowEngine> {
+    Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path()).unwrap(),
+    ))
+}
+
+/// Wait until a task of the case waits for a message
+async fn wait_for_catch(engine: &WorkflowEngine, case_id: CaseId, task_id: &str) {
+    for _ in 0..200 {
+        let waiting = engine.message_correlator().waiting().await;
+        if waiting
+            .iter()
+            .any(|w| w.case_id == case_id && w.task_id == task_id)
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected task {} of case {} to wait", task_id, case_id);
+}
+
+async fn wait_for_state(engine: &WorkflowEngine, case_id: CaseId, state: CaseState) {
+    for _ in 0..200 {
+        if engine.get_case(case_id).await.unwrap().state == state {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected case {} to reach {:?}", case_id, state);
+}
+
+#[tokio::test]
+async fn test_message_catch_waits_for_correlated_message() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = new_engine(&temp_dir);
+    let spec = create_payment_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({"order": {"id": 42}}))
+        .await
+        .unwrap();
+
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+    wait_for_catch(&engine, case_id, "wait_payment").await;
+
+    // A payment for another order is buffered, the matching one is delivered
+    let other = CorrelatedMessage::new("payment", serde_json::json!({"paid": false}))
+        .with_key("orderId", 7);
+    assert!(matches!(
+        engine.publish_message(other).await.unwrap(),
+        MessageDelivery::Buffered { .. }
+    ));
+    let payment = CorrelatedMessage::new("payment", serde_json::json!({"paid": true}))
+        .with_key("orderId", "42");
+    assert_eq!(
+        engine.publish_message(payment).await.unwrap(),
+        MessageDelivery::Delivered {
+            case_id,
+            task_id: "wait_payment".to_string()
+        }
+    );
+    runner.await.unwrap().unwrap();
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.data["paid"], true, "Message payload is merged");
+    assert_eq!(engine.message_correlator().buffered().await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_early_message_is_buffered_until_case_waits() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = new_engine(&temp_dir);
+    let spec = create_payment_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+
+    let payment = CorrelatedMessage::new("payment", serde_json::json!({"amount": 99}))
+        .with_key("orderId", 42);
+    assert!(matches!(
+        engine.publish_message(payment).await.unwrap(),
+        MessageDelivery::Buffered { .. }
+    ));
+
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({"order": {"id": 42}}))
+        .await
+        .unwrap();
+    engine.execute_case(case_id).await.unwrap();
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.data["amount"], 99);
+    assert!(engine.message_correlator().buffered().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_message_start_instantiates_case() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = new_engine(&temp_dir);
+    let spec = create_message_started_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+
+    let order = CorrelatedMessage::new("order-placed", serde_json::json!({"orderId": "A-1"}));
+    let case_id = match engine.publish_message(order).await.unwrap() {
+        MessageDelivery::Started { case_ids, spec_ids } => {
+            assert_eq!(spec_ids, vec![spec.id]);
+            assert_eq!(case_ids.len(), 1);
+            case_ids[0]
+        }
+        other => panic!("expected a started case, got {:?}", other),
+    };
+    wait_for_catch(&engine, case_id, "wait_shipping").await;
+
+    let shipped = CorrelatedMessage::new("shipped", serde_json::json!({"carrier": "DHL"}))
+        .with_key("orderId", "A-1");
+    engine.publish_message(shipped).await.unwrap();
+    wait_for_state(&engine, case_id, CaseState::Completed).await;
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.data["orderId"], "A-1");
+    assert_eq!(case.data["carrier"], "DHL");
+}
+
+#[tokio::test]
+async fn test_cancelled_case_stops_waiting() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = new_engine(&temp_dir);
+    let spec = create_payment_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(spec.id, serde_json::json!({"order": {"id": 1}}))
+        .await
+        .unwrap();
+
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+    wait_for_catch(&engine, case_id, "wait_payment").await;
+    engine.cancel_case(case_id).await.unwrap();
+
+    assert!(runner.await.unwrap().is_err());
+    assert!(engine.message_correlator().waiting().await.is_empty());
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Cancelled
+    );
+}
+
+#[tokio::test]
+async fn test_message_start_must_follow_start_condition() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = new_engine(&temp_dir);
+    let spec = WorkflowSpecBuilder::new("misplaced_start")
+        .add_task(TaskBuilder::new("prepare", "Prepare").build())
+        .add_task(
+            TaskBuilder::new("receive", "Receive")
+                .with_message(MessageCatch::new("order-placed").starting_case())
+                .build(),
+        )
+        .with_auto_conditions("prepare", "done")
+        .add_flow("condition:prepare", "prepare")
+        .add_flow("prepare", "receive")
+        .add_flow("receive", "condition:done")
+        .build();
+
+    let err = engine.register_workflow(spec).await.unwrap_err();
+    assert!(err.to_string().contains("start condition"));
+}
@@ -0,0 +1,188 @@
+//! Integration tests for multiple instance tasks (patterns 12-15, MI partial joins)
+
+use knhk_workflow_engine::{
+    executor::WorkflowEngine,
+    parser::WorkflowSpec,
+    patterns::multiple_instance::{MiCreationMode, MultiInstanceSpec},
+    services::work_items::WorkItemState,
+    testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder},
+    CaseId, StateStore,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// start → pick (multiple instance, human) → end
+fn create_mi_workflow(multi_instance: MultiInstanceSpec) -> WorkflowSpec {
+    WorkflowSpecBuilder::new("mi_picking")
+        .add_task(
+            TaskBuilder::new("pick", "Pick Order Line")
+                .with_multi_instance(multi_instance)
+                .add_required_role("picker")
+                .build(),
+        )
+        .with_auto_conditions("pick", "packed")
+        .add_flow("condition:pick", "pick")
+        .add_flow("pick", "condition:packed")
+        .build()
+}
+
+async fn start_case(
+    temp_dir: &TempDir,
+    multi_instance: MultiInstanceSpec,
+    data: serde_json::Value,
+) -> (Arc<WorkflowEngine>, CaseId) {
+    let engine = Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path()).unwrap(),
+    ));
+    let spec = create_mi_workflow(multi_instance);
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let case_id = engine.create_case(spec.id, data).await.unwrap();
+    (engine, case_id)
+}
+
+/// Wait until `count` open work items exist for the case
+async fn open_work_items(
+    engine: &WorkflowEngine,
+    case_id: CaseId,
+    count: usize,
+) -> Vec<knhk_workflow_engine::services::work_items::WorkItem> {
+    for _ in 0..200 {
+        let items: Vec<_> = engine
+            .work_item_service()
+            .list_case_work_items(&case_id.to_string())
+            .await
+            .into_iter()
+            .filter(|item| item.state == WorkItemState::Created)
+            .collect();
+        if items.len() >= count {
+            return items;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected {} open work items", count);
+}
+
+async fn stored_data(engine: &WorkflowEngine, case_id: CaseId) -> serde_json::Value {
+    let store = engine.state_store().read().await.clone();
+    store.load_case(&case_id).unwrap().unwrap().data
+}
+
+#[tokio::test]
+async fn test_instances_split_from_collection_and_aggregated() {
+    let temp_dir = TempDir::new().unwrap();
+    let multi_instance = MultiInstanceSpec {
+        collection: Some("order.lines".to_string()),
+        instance_variable: "line".to_string(),
+        aggregate_into: Some("picked".to_string()),
+        ..MultiInstanceSpec::default()
+    };
+    let (engine, case_id) = start_case(
+        &temp_dir,
+        multi_instance,
+        serde_json::json!({"order": {"lines": [{"sku": "A"}, {"sku": "B"}, {"sku": "C"}]}}),
+    )
+    .await;
+
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+
+    let items = open_work_items(&engine, case_id, 3).await;
+    for item in &items {
+        let sku = item.data["line"]["sku"].clone();
+        engine
+            .work_item_service()
+            .complete(&item.id, serde_json::json!({"sku": sku, "qty": 1}))
+            .await
+            .unwrap();
+    }
+    runner.await.unwrap().unwrap();
+
+    let data = stored_data(&engine, case_id).await;
+    let picked = data["picked"].as_array().unwrap();
+    let skus: Vec<_> = picked.iter().map(|p| p["sku"].clone()).collect();
+    assert_eq!(skus, vec!["A", "B", "C"], "Outputs keep instance order");
+    assert_eq!(data["mi_completed_count"], 3);
+}
+
+#[tokio::test]
+async fn test_threshold_cancels_remaining_instances() {
+    let temp_dir = TempDir::new().unwrap();
+    let multi_instance = MultiInstanceSpec {
+        minimum: 3,
+        threshold: Some(2),
+        aggregate_into: Some("votes".to_string()),
+        ..MultiInstanceSpec::default()
+    };
+    let (engine, case_id) = start_case(&temp_dir, multi_instance, serde_json::json!({})).await;
+
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+
+    let items = open_work_items(&engine, case_id, 3).await;
+    for item in items.iter().take(2) {
+        engine
+            .work_item_service()
+            .complete(&item.id, serde_json::json!({"vote": "yes"}))
+            .await
+            .unwrap();
+    }
+    runner.await.unwrap().unwrap();
+
+    let remaining = engine
+        .work_item_service()
+        .get_work_item(&items[2].id)
+        .await
+        .unwrap();
+    assert_eq!(remaining.state, WorkItemState::Cancelled);
+    let data = stored_data(&engine, case_id).await;
+    assert_eq!(data["votes"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_dynamic_instances_added_while_running() {
+    let temp_dir = TempDir::new().unwrap();
+    let multi_instance = MultiInstanceSpec {
+        creation_mode: MiCreationMode::Dynamic,
+        minimum: 1,
+        maximum: Some(3),
+        aggregate_into: Some("quotes".to_string()),
+        ..MultiInstanceSpec::default()
+    };
+    let (engine, case_id) = start_case(&temp_dir, multi_instance, serde_json::json!({})).await;
+
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+
+    open_work_items(&engine, case_id, 1).await;
+    let index = engine
+        .add_mi_instance(case_id, "pick", serde_json::json!("supplier-b"))
+        .await
+        .unwrap();
+    assert_eq!(index, 1);
+    engine.close_mi_task(case_id, "pick").unwrap();
+    assert!(engine
+        .add_mi_instance(case_id, "pick", serde_json::json!("late"))
+        .await
+        .is_err());
+
+    let items = open_work_items(&engine, case_id, 2).await;
+    for item in &items {
+        engine
+            .work_item_service()
+            .complete(&item.id, serde_json::json!({"price": 10}))
+            .await
+            .unwrap();
+    }
+    runner.await.unwrap().unwrap();
+
+    let data = stored_data(&engine, case_id).await;
+    assert_eq!(data["quotes"].as_array().unwrap().len(), 2);
+    assert!(engine.mi_task_state(case_id, "pick").is_none());
+}
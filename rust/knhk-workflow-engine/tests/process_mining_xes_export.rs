@@ -22,6 +22,10 @@ fn create_test_workflow() -> WorkflowSpec {
         conditions: std::collections::HashMap::new(),
         flows: Vec::new(),
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     }
 }
 
@@ -0,0 +1,111 @@
+//! Integration tests for RDR rule learning in the worklet repository
+//!
+//! Rejected selections refined into new rules validated against cornerstone
+//! cases, and rule trees persisted per task.
+
+use knhk_workflow_engine::worklets::{
+    ExceptionContext, ExceptionKind, Exlet, ExletPrimitive, RDRRule, RuleTreeStore,
+    WorkletRepository,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn post_constraint(case_id: &str, data: serde_json::Value) -> ExceptionContext {
+    ExceptionContext {
+        exception_type: ExceptionKind::ItemPostConstraint.as_str().to_string(),
+        task_id: "review".to_string(),
+        case_id: case_id.to_string(),
+        data,
+        metadata: HashMap::new(),
+    }
+}
+
+/// Repository whose `review` post-constraint tree suspends large claims
+async fn repository() -> WorkletRepository {
+    let repository = WorkletRepository::new();
+    repository
+        .add_exception_rule(
+            ExceptionKind::ItemPostConstraint,
+            Some("review"),
+            RDRRule {
+                rule_id: "large".to_string(),
+                condition: "amount > 1000".to_string(),
+                worklet_id: "hold".to_string(),
+                parent_rule_id: None,
+                cornerstone_cases: vec![
+                    post_constraint("cs-1", json!({"amount": 5000, "region": "eu"})),
+                    post_constraint("cs-2", json!({"amount": 7000, "region": "us"})),
+                ],
+                priority: 10,
+                exlet: Some(Exlet::new("hold").then(ExletPrimitive::SuspendItem)),
+            },
+        )
+        .await
+        .unwrap();
+    repository
+}
+
+#[tokio::test]
+async fn test_rejected_selection_refined_and_persisted_per_task() {
+    let repository = repository().await;
+    let rejected = post_constraint("case-9", json!({"amount": 6000, "region": "apac"}));
+    let kind = Some(ExceptionKind::ItemPostConstraint);
+
+    let proposal = repository.reject_selection(kind, &rejected).await.unwrap();
+    assert_eq!(proposal.parent_rule_id, "large");
+    assert_eq!(proposal.rejected_conclusion, "hold");
+    let candidate = |condition: &str| {
+        proposal
+            .candidates
+            .iter()
+            .find(|c| c.condition == condition)
+            .unwrap_or_else(|| panic!("{} not proposed: {:?}", condition, proposal))
+    };
+    assert_eq!(candidate("amount > 5000").misclassified, vec!["cs-2"]);
+    assert!(candidate("region == 'apac'").is_valid());
+
+    let invalid = repository
+        .refine_rule(
+            kind,
+            &rejected,
+            "amount > 5000",
+            "cancel".to_string(),
+            Some(Exlet::new("cancel").then(ExletPrimitive::CancelItem)),
+        )
+        .await;
+    assert!(invalid.is_err(), "cs-2 would be cancelled instead of held");
+    repository
+        .refine_rule(
+            kind,
+            &rejected,
+            "region == 'apac'",
+            "cancel".to_string(),
+            Some(Exlet::new("cancel").then(ExletPrimitive::CancelItem)),
+        )
+        .await
+        .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let store = RuleTreeStore::new(temp_dir.path()).unwrap();
+    assert_eq!(repository.save_rule_trees(&store).await.unwrap(), 2);
+    assert!(store.path_for(Some("review")).exists());
+    assert!(store.path_for(None).exists());
+
+    let restored = WorkletRepository::new();
+    assert_eq!(restored.load_rule_trees(&store).await.unwrap(), 2);
+    for (context, exlet) in [
+        (&rejected, "cancel"),
+        (
+            &post_constraint("case-10", json!({"amount": 6000, "region": "eu"})),
+            "hold",
+        ),
+    ] {
+        let (_, selected) = restored
+            .select_exlet(ExceptionKind::ItemPostConstraint, context)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(selected.name, exlet);
+    }
+}
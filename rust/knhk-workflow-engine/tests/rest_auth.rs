@@ -0,0 +1,161 @@
+//! Integration tests for bearer-token authentication of the REST API
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use knhk_workflow_engine::{
+    api::rest::RestApiServer,
+    executor::WorkflowEngine,
+    security::{Action, AuthManager, AuthPolicy, ClaimRoleMapping, JwtConfig, JwtValidator},
+    CaseId, StateStore,
+};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+const ISSUER: &str = "https://idp.example.com";
+const AUDIENCE: &str = "knhk-workflow-engine";
+
+fn b64(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn sign(key: &Ed25519KeyPair, groups: &[&str]) -> String {
+    let header = serde_json::json!({"alg": "EdDSA", "typ": "JWT", "kid": "k1"});
+    let claims = serde_json::json!({
+        "iss": ISSUER,
+        "aud": AUDIENCE,
+        "sub": "alice",
+        "exp": chrono::Utc::now().timestamp() + 300,
+        "groups": groups,
+    });
+    let signed = format!(
+        "{}.{}",
+        b64(header.to_string().as_bytes()),
+        b64(claims.to_string().as_bytes())
+    );
+    format!("{}.{}", signed, b64(key.sign(signed.as_bytes()).as_ref()))
+}
+
+/// Serve the REST API with auth: the `auditors` group may read cases
+async fn serve(temp_dir: &TempDir, key: &Ed25519KeyPair) -> String {
+    let jwks_path = temp_dir.path().join("jwks.json");
+    let jwks = serde_json::json!({"keys": [{
+        "kty": "OKP", "crv": "Ed25519", "kid": "k1",
+        "x": b64(key.public_key().as_ref()),
+    }]});
+    std::fs::write(&jwks_path, jwks.to_string()).unwrap();
+
+    let mut auth = AuthManager::new();
+    auth.set_jwt_validator(
+        JwtValidator::new(
+            JwtConfig::new(ISSUER, AUDIENCE, jwks_path)
+                .with_role_mapping(ClaimRoleMapping::new("groups", "auditors", "auditor")),
+        )
+        .unwrap(),
+    );
+    auth.add_policy(AuthPolicy {
+        name: "auditors read cases".to_string(),
+        allowed_principals: vec![],
+        allowed_roles: vec!["auditor".to_string()],
+        resource_patterns: vec![],
+        actions: vec![Action::ReadCase],
+    });
+
+    let engine = Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path().join("state")).unwrap(),
+    ));
+    let router = RestApiServer::new(engine)
+        .with_auth(Arc::new(auth))
+        .router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    format!("http://{}", address)
+}
+
+#[tokio::test]
+async fn test_routes_require_valid_token_and_permission() {
+    let temp_dir = TempDir::new().unwrap();
+    let key_pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let key = Ed25519KeyPair::from_pkcs8(key_pkcs8.as_ref()).unwrap();
+    let base_url = serve(&temp_dir, &key).await;
+    let client = reqwest::Client::new();
+    let case_url = format!("{}/cases/{}", base_url, CaseId::new());
+
+    let health = client
+        .get(format!("{}/health", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert!(health.status().is_success(), "Health stays public");
+
+    let anonymous = client.get(&case_url).send().await.unwrap();
+    assert_eq!(anonymous.status(), 401);
+    let impersonation = client
+        .get(&case_url)
+        .bearer_auth("spiffe://example.com/workflow-engine")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(impersonation.status(), 401, "SPIFFE IDs are not tokens");
+    let forged_key_pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let forged_key = Ed25519KeyPair::from_pkcs8(forged_key_pkcs8.as_ref()).unwrap();
+    let forged = client
+        .get(&case_url)
+        .bearer_auth(sign(&forged_key, &["auditors"]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(forged.status(), 401);
+
+    let not_permitted = client
+        .get(&case_url)
+        .bearer_auth(sign(&key, &["sales"]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(not_permitted.status(), 403);
+    let auditor = client
+        .get(&case_url)
+        .bearer_auth(sign(&key, &["auditors"]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        auditor.status(),
+        404,
+        "Authorized request reaches the handler"
+    );
+}
+
+#[tokio::test]
+async fn test_dead_letter_routes_require_auth() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path().join("open")).unwrap(),
+    ));
+    let router = RestApiServer::new(engine).router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    let client = reqwest::Client::new();
+
+    let purge = client
+        .delete(format!("http://{}/dlq", address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(purge.status(), 404, "Not served without auth");
+
+    let key_pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let key = Ed25519KeyPair::from_pkcs8(key_pkcs8.as_ref()).unwrap();
+    let base_url = serve(&temp_dir, &key).await;
+    let auditor_purge = client
+        .delete(format!("{}/dlq", base_url))
+        .bearer_auth(sign(&key, &["auditors"]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(auditor_purge.status(), 403);
+}
@@ -0,0 +1,220 @@
+//! Integration tests for retention sweeps and subject erasure
+
+use knhk_workflow_engine::{
+    case::CaseState,
+    compliance::{
+        ErasureOutcome, ErasureRequest, ReportSigner, RetentionAction, RetentionManager,
+        RetentionPolicy, RetentionReportKind,
+    },
+    executor::WorkflowEngine,
+    parser::WorkflowSpec,
+    services::DocumentStore,
+    state::StateEvent,
+    testing::chicago_tdd::{complete_work_items_while, TaskBuilder, WorkflowSpecBuilder},
+    CaseId, StateStore,
+};
+use tempfile::TempDir;
+
+/// start → review (human) → reviewed
+fn create_review_workflow() -> WorkflowSpec {
+    WorkflowSpecBuilder::new("retention_review")
+        .add_task(
+            TaskBuilder::new("review", "Review Order")
+                .add_required_role("clerk")
+                .build(),
+        )
+        .with_auto_conditions("review", "reviewed")
+        .add_flow("condition:review", "review")
+        .add_flow("review", "condition:reviewed")
+        .build()
+}
+
+fn case_policy(action: RetentionAction, gdpr_rtbf: bool) -> RetentionPolicy {
+    RetentionPolicy {
+        name: "cases".to_string(),
+        resource_type: "Case".to_string(),
+        retention_days: 0,
+        legal_hold: false,
+        gdpr_rtbf,
+        action,
+        personal_fields: vec!["email".to_string()],
+    }
+}
+
+async fn completed_case(engine: &WorkflowEngine, spec: &WorkflowSpec, customer: &str) -> CaseId {
+    let case_id = engine
+        .create_case(
+            spec.id,
+            serde_json::json!({"customer_id": customer, "email": "a@example.com", "order_id": 7}),
+        )
+        .await
+        .unwrap();
+    complete_work_items_while(engine, engine.execute_case(case_id))
+        .await
+        .unwrap();
+    assert_eq!(
+        engine.get_case(case_id).await.unwrap().state,
+        CaseState::Completed
+    );
+    case_id
+}
+
+#[tokio::test]
+async fn test_sweep_purges_expired_cases_and_respects_holds() {
+    let temp_dir = TempDir::new().unwrap();
+    let doc_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let documents = DocumentStore::new(doc_dir.path()).unwrap();
+    let spec = create_review_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+
+    let expired = completed_case(&engine, &spec, "cust-1").await;
+    let held_case = completed_case(&engine, &spec, "cust-2").await;
+    let held_subject = completed_case(&engine, &spec, "cust-3").await;
+
+    let receipt = br#"{"a_hash":1,"mu_hash":1}"#;
+    let receipt_key = format!("receipt_{}_{}", expired, spec.id);
+    engine
+        .state_store()
+        .read()
+        .await
+        .append_receipt(&receipt_key, receipt)
+        .unwrap();
+    let doc_id = documents
+        .store_document(
+            expired,
+            "invoice.pdf".to_string(),
+            "application/pdf".to_string(),
+            b"invoice",
+        )
+        .await
+        .unwrap();
+
+    let mut retention = RetentionManager::new();
+    retention.add_policy(case_policy(RetentionAction::Purge, true));
+    retention.place_case_hold(held_case);
+    retention.place_subject_hold("cust-3".to_string());
+    let signer = ReportSigner::generate().unwrap();
+
+    let signed = engine
+        .sweep_retention(&retention, Some(&documents), &signer)
+        .await
+        .unwrap();
+    signed.verify().unwrap();
+    assert_eq!(signed.public_key, signer.public_key_hex());
+    assert_eq!(signed.report.kind, RetentionReportKind::Sweep);
+
+    // The expired case is gone with everything recorded for it
+    assert!(engine.get_case(expired).await.is_err());
+    let store = engine.state_store().read().await.clone();
+    assert!(store.load_case(&expired).unwrap().is_none());
+    assert!(store.load_case_history(&expired).unwrap().is_empty());
+    assert!(store
+        .scan_receipts(&format!("receipt_{}_", expired))
+        .unwrap()
+        .is_empty());
+    assert!(documents.get_metadata(&doc_id).await.is_none());
+    assert!(signed
+        .report
+        .records
+        .iter()
+        .all(|r| r.case_id == Some(expired) && r.outcome == ErasureOutcome::Purged));
+
+    // Deleted receipts and documents stay verifiable through their tombstones
+    let tombstones = engine.tombstones(expired).await.unwrap();
+    assert!(tombstones
+        .iter()
+        .any(|t| t.resource.starts_with("receipt:") && t.matches(receipt)));
+    assert!(tombstones
+        .iter()
+        .any(|t| t.resource == format!("document:{}", doc_id.0) && t.matches(b"invoice")));
+
+    // Held cases are kept and reported
+    let mut held = signed.report.held.clone();
+    held.sort_by_key(|id| id.to_string());
+    let mut expected = vec![held_case, held_subject];
+    expected.sort_by_key(|id| id.to_string());
+    assert_eq!(held, expected);
+    assert!(engine.get_case(held_case).await.is_ok());
+    assert!(engine.get_case(held_subject).await.is_ok());
+
+    let reports = engine.retention_reports().await.unwrap();
+    assert_eq!(reports, vec![signed]);
+}
+
+#[tokio::test]
+async fn test_subject_erasure_redacts_personal_fields_everywhere() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_review_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+
+    let erased = completed_case(&engine, &spec, "cust-1").await;
+    let other = completed_case(&engine, &spec, "cust-2").await;
+
+    let mut retention = RetentionManager::new();
+    retention.add_policy(case_policy(RetentionAction::Anonymize, true));
+    let signer = ReportSigner::generate().unwrap();
+    let request = ErasureRequest {
+        subject: "cust-1".to_string(),
+        personal_fields: vec!["email".to_string()],
+    };
+
+    let signed = engine
+        .erase_subject(&request, &retention, None, &signer)
+        .await
+        .unwrap();
+    signed.verify().unwrap();
+
+    let case = engine.get_case(erased).await.unwrap();
+    assert_eq!(case.data, serde_json::json!({"order_id": 7}));
+    let history = engine
+        .state_store()
+        .read()
+        .await
+        .load_case_history(&erased)
+        .unwrap();
+    for event in &history {
+        if let StateEvent::CaseCheckpointed { checkpoint, .. } = event {
+            assert!(checkpoint.case.data.get("email").is_none());
+            assert!(checkpoint.case.data.get("customer_id").is_none());
+        }
+    }
+    let tombstones = engine.tombstones(erased).await.unwrap();
+    assert!(tombstones
+        .iter()
+        .any(|t| t.field.as_deref() == Some("email") && t.digest.is_none()));
+
+    // Other subjects are untouched, and a repeated request is a no-op
+    assert_eq!(
+        engine.get_case(other).await.unwrap().data["email"],
+        "a@example.com"
+    );
+    let repeated = engine
+        .erase_subject(&request, &retention, None, &signer)
+        .await
+        .unwrap();
+    assert!(repeated.report.records.is_empty());
+}
+
+#[tokio::test]
+async fn test_subject_erasure_requires_rtbf_policy() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let mut retention = RetentionManager::new();
+    retention.add_policy(case_policy(RetentionAction::Purge, false));
+    let request = ErasureRequest {
+        subject: "cust-1".to_string(),
+        personal_fields: Vec::new(),
+    };
+
+    let result = engine
+        .erase_subject(
+            &request,
+            &retention,
+            None,
+            &ReportSigner::generate().unwrap(),
+        )
+        .await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,248 @@
+//! Integration tests for spec versions and live case migration
+
+use knhk_workflow_engine::{
+    case::CaseState,
+    data::{DataType, VariableDecl},
+    executor::WorkflowEngine,
+    parser::WorkflowSpec,
+    patterns::multiple_instance::{MiCreationMode, MultiInstanceSpec},
+    services::work_items::WorkItemState,
+    state::{StateEvent, StateStore},
+    testing::chicago_tdd::{complete_work_items_while, TaskBuilder, WorkflowSpecBuilder},
+    CaseId,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+const ORDER_IRI: &str = "http://example.org/workflows/order";
+
+/// start → pick (dynamic multiple instance, human) → [inspect →] ship → shipped
+fn create_order_version(version: &str, inspect: bool) -> WorkflowSpec {
+    let builder = WorkflowSpecBuilder::new(format!("order {}", version))
+        .with_version(ORDER_IRI, version)
+        .add_task(
+            TaskBuilder::new("pick", "Pick Order")
+                .with_multi_instance(MultiInstanceSpec {
+                    creation_mode: MiCreationMode::Dynamic,
+                    minimum: 1,
+                    ..MultiInstanceSpec::default()
+                })
+                .add_required_role("picker")
+                .build(),
+        )
+        .add_task(
+            TaskBuilder::new("ship", "Ship Order")
+                .add_required_role("clerk")
+                .build(),
+        )
+        .with_auto_conditions("pick", "shipped")
+        .add_flow("condition:pick", "pick")
+        .add_flow("ship", "condition:shipped");
+    if inspect {
+        builder
+            .add_task(
+                TaskBuilder::new("inspect", "Inspect Order")
+                    .add_required_role("clerk")
+                    .build(),
+            )
+            .add_flow("pick", "inspect")
+            .add_flow("inspect", "ship")
+            .build()
+    } else {
+        builder.add_flow("pick", "ship").build()
+    }
+}
+
+/// start → ship → shipped (no picking step)
+fn create_ship_only_version(version: &str) -> WorkflowSpec {
+    WorkflowSpecBuilder::new(format!("order {}", version))
+        .with_version(ORDER_IRI, version)
+        .add_task(
+            TaskBuilder::new("ship", "Ship Order")
+                .add_required_role("clerk")
+                .build(),
+        )
+        .with_auto_conditions("ship", "shipped")
+        .add_flow("condition:ship", "ship")
+        .add_flow("ship", "condition:shipped")
+        .build()
+}
+
+async fn history(engine: &WorkflowEngine, case_id: CaseId) -> Vec<StateEvent> {
+    let store = engine.state_store().read().await.clone();
+    store.load_case_history(&case_id).unwrap()
+}
+
+fn started(history: &[StateEvent], task: &str) -> bool {
+    history
+        .iter()
+        .any(|e| matches!(e, StateEvent::TaskStarted { task_id, .. } if task_id == task))
+}
+
+/// Wait until the picking instance of the case offered its work item
+async fn wait_for_picking(engine: &WorkflowEngine, case_id: CaseId) -> String {
+    for _ in 0..200 {
+        let open = engine
+            .work_item_service()
+            .list_case_work_items(&case_id.to_string())
+            .await
+            .into_iter()
+            .find(|item| item.state == WorkItemState::Created);
+        if let Some(item) = open {
+            return item.id;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected an open picking work item for case {}", case_id);
+}
+
+#[tokio::test]
+async fn test_new_cases_use_latest_version_running_cases_keep_theirs() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let v1 = create_ship_only_version("1.9.0");
+    let v2 = create_ship_only_version("1.10.0");
+    engine.register_workflow(v1.clone()).await.unwrap();
+    let pinned = engine
+        .create_case_latest(ORDER_IRI, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    engine.register_workflow(v2.clone()).await.unwrap();
+    let latest = engine
+        .create_case_latest(ORDER_IRI, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    let versions: Vec<String> = engine
+        .workflow_versions(ORDER_IRI)
+        .await
+        .into_iter()
+        .map(|v| v.version)
+        .collect();
+    assert_eq!(versions, vec!["1.9.0", "1.10.0"]);
+    assert_eq!(engine.get_case(pinned).await.unwrap().spec_id, v1.id);
+    assert_eq!(engine.get_case(latest).await.unwrap().spec_id, v2.id);
+    complete_work_items_while(&engine, engine.execute_case(pinned))
+        .await
+        .unwrap();
+    let case = engine.get_case(pinned).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.spec_id, v1.id, "Case stays on the version it started");
+}
+
+#[tokio::test]
+async fn test_changed_spec_with_running_cases_must_be_a_new_version() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let spec = create_ship_only_version("1.0.0");
+    engine.register_workflow(spec.clone()).await.unwrap();
+    engine
+        .create_case(spec.id, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    // Re-registering the unchanged spec is a no-op
+    engine.register_workflow(spec.clone()).await.unwrap();
+    let mut changed = spec.clone();
+    changed.name = "order (renamed)".to_string();
+    assert!(
+        engine.register_workflow(changed).await.is_err(),
+        "Running cases keep their spec"
+    );
+    assert!(
+        engine
+            .register_workflow(create_ship_only_version("1.0.0"))
+            .await
+            .is_err(),
+        "A version is registered once"
+    );
+}
+
+#[tokio::test]
+async fn test_running_case_migrates_to_compatible_version() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path()).unwrap(),
+    ));
+    let v1 = create_order_version("1.0.0", false);
+    let v2 = create_order_version("2.0.0", true);
+    let v3 = create_ship_only_version("3.0.0");
+    for spec in [&v1, &v2, &v3] {
+        engine.register_workflow(spec.clone()).await.unwrap();
+    }
+    let case_id = engine
+        .create_case(v1.id, serde_json::json!({}))
+        .await
+        .unwrap();
+    let runner = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.execute_case(case_id).await }
+    });
+    let work_item_id = wait_for_picking(&engine, case_id).await;
+
+    // Dry run: v3 has no picking step, v2 keeps it
+    let incompatible = engine.check_case_migration(case_id, v3.id).await.unwrap();
+    assert!(!incompatible.is_compatible());
+    assert!(incompatible
+        .issues
+        .iter()
+        .any(|issue| issue.contains("pick")));
+    let dry_run = engine.check_case_migration(case_id, v2.id).await.unwrap();
+    assert!(dry_run.is_compatible(), "{:?}", dry_run.issues);
+    assert!(!dry_run.migrated);
+    assert_eq!(engine.get_case(case_id).await.unwrap().spec_id, v1.id);
+
+    let reports = engine.migrate_cases(&[case_id], v2.id).await.unwrap();
+    assert!(reports[0].migrated);
+    assert_eq!(reports[0].from_version.as_deref(), Some("1.0.0"));
+    assert_eq!(reports[0].to_version.as_deref(), Some("2.0.0"));
+
+    engine
+        .work_item_service()
+        .complete(&work_item_id, serde_json::json!({}))
+        .await
+        .unwrap();
+    engine.close_mi_task(case_id, "pick").unwrap();
+    runner.await.unwrap().unwrap();
+
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.state, CaseState::Completed);
+    assert_eq!(case.spec_id, v2.id);
+    let events = history(&engine, case_id).await;
+    assert!(
+        started(&events, "inspect"),
+        "Case continues on the new version"
+    );
+    assert!(events.iter().any(|e| matches!(
+        e,
+        StateEvent::CaseMigrated { from_spec_id, to_spec_id, .. }
+            if *from_spec_id == v1.id && *to_spec_id == v2.id
+    )));
+}
+
+#[tokio::test]
+async fn test_migration_reports_incompatible_case_data() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap());
+    let v1 = create_ship_only_version("1.0.0");
+    let mut v2 = create_ship_only_version("2.0.0");
+    v2.variables
+        .push(VariableDecl::new("carrier", DataType::String).required());
+    engine.register_workflow(v1.clone()).await.unwrap();
+    engine.register_workflow(v2.clone()).await.unwrap();
+    let case_id = engine
+        .create_case(v1.id, serde_json::json!({}))
+        .await
+        .unwrap();
+
+    let reports = engine.migrate_cases(&[case_id], v2.id).await.unwrap();
+
+    assert!(!reports[0].migrated);
+    assert!(reports[0]
+        .issues
+        .iter()
+        .any(|issue| issue.contains("carrier")));
+    assert_eq!(engine.get_case(case_id).await.unwrap().spec_id, v1.id);
+}
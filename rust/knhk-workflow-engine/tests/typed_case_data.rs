@@ -0,0 +1,131 @@
+//! Integration tests for the typed case data model
+//!
+//! Net variables, task input/output contracts and type-checked flow predicates.
+
+use knhk_workflow_engine::{
+    data::{DataType, TaskDataContract, VariableDecl},
+    executor::WorkflowEngine,
+    parser::{TaskParameter, WorkflowSpec},
+    testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder},
+    StateStore, WorkflowSpecId,
+};
+use tempfile::TempDir;
+
+fn parameter(name: &str, param_type: &str, required: bool) -> TaskParameter {
+    TaskParameter {
+        name: name.to_string(),
+        param_type: param_type.to_string(),
+        mapping: None,
+        required,
+        annotations: Default::default(),
+    }
+}
+
+/// Approval workflow: review → (approve | reject) routed on `amount`
+fn create_approval_workflow(predicate: &str) -> WorkflowSpec {
+    WorkflowSpecBuilder::new("typed_approval")
+        .add_variable(VariableDecl::new("amount", DataType::Decimal).required())
+        .add_variable(VariableDecl::new("approved", DataType::Boolean))
+        .add_variable(
+            VariableDecl::new("currency", DataType::String).with_default(serde_json::json!("EUR")),
+        )
+        .add_task(
+            TaskBuilder::new("review", "Review")
+                .add_input_parameter(parameter("amount", "xsd:decimal", true))
+                .add_output_parameter(parameter("approved", "xsd:boolean", true))
+                .build(),
+        )
+        .add_task(TaskBuilder::new("approve", "Approve").build())
+        .add_task(TaskBuilder::new("reject", "Reject").build())
+        .with_auto_conditions("review", "approve")
+        .add_flow("condition:review", "review")
+        .add_flow_with_predicate("review", "approve", predicate)
+        .add_flow_with_predicate("review", "reject", "!approved")
+        .add_flow("approve", "condition:approve")
+        .build()
+}
+
+fn engine(temp_dir: &TempDir) -> WorkflowEngine {
+    WorkflowEngine::new(StateStore::new(temp_dir.path()).unwrap())
+}
+
+#[tokio::test]
+async fn test_register_rejects_ill_typed_predicate() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = engine(&temp_dir);
+
+    let mistyped = engine
+        .register_workflow(create_approval_workflow("approved && amount > \"1000\""))
+        .await;
+    let undeclared = engine
+        .register_workflow(create_approval_workflow("approved && total > 1000"))
+        .await;
+    let valid = engine
+        .register_workflow(create_approval_workflow("approved && amount <= 1000"))
+        .await;
+
+    assert!(
+        mistyped.is_err(),
+        "Ordering decimal against string must be rejected"
+    );
+    assert!(undeclared.is_err(), "Undeclared variables must be rejected");
+    assert!(valid.is_ok());
+}
+
+#[tokio::test]
+async fn test_create_case_validates_data_and_applies_defaults() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = engine(&temp_dir);
+    let spec = create_approval_workflow("approved");
+    let spec_id: WorkflowSpecId = spec.id;
+    engine.register_workflow(spec).await.unwrap();
+
+    let wrong_type = engine
+        .create_case(spec_id, serde_json::json!({"amount": "a lot"}))
+        .await;
+    let missing = engine.create_case(spec_id, serde_json::json!({})).await;
+    let case_id = engine
+        .create_case(spec_id, serde_json::json!({"amount": 250.0}))
+        .await
+        .unwrap();
+
+    assert!(wrong_type.is_err());
+    assert!(missing.is_err());
+    let case = engine.get_case(case_id).await.unwrap();
+    assert_eq!(case.data["currency"], "EUR");
+}
+
+#[tokio::test]
+async fn test_work_item_completion_enforces_output_contract() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = engine(&temp_dir);
+    let spec = create_approval_workflow("approved");
+    let contract = TaskDataContract::for_task(&spec.tasks["review"]).unwrap();
+    let input = contract
+        .bind_inputs(&serde_json::json!({"amount": 250.0, "notes": "internal"}))
+        .unwrap();
+    let work_item_id = engine
+        .work_item_service()
+        .create_work_item_with_contract(
+            "case-1".to_string(),
+            spec.id,
+            "review".to_string(),
+            input.clone(),
+            Some(contract),
+        )
+        .await
+        .unwrap();
+
+    let rejected = engine
+        .work_item_service()
+        .complete(&work_item_id, serde_json::json!({"approved": "yes"}))
+        .await;
+    let accepted = engine
+        .work_item_service()
+        .complete(&work_item_id, serde_json::json!({"approved": true}))
+        .await;
+
+    assert_eq!(input, serde_json::json!({"amount": 250.0}));
+    assert!(rejected.is_err(), "Malformed result must be rejected");
+    assert!(accepted.is_ok());
+}
@@ -71,6 +71,10 @@ fn create_test_workflow() -> WorkflowSpec {
         start_condition: Some("start".to_string()),
         end_condition: Some("end".to_string()),
         source_turtle: None,
+        variables: Vec::new(),
+        iri: None,
+        version: None,
+        subnets: Vec::new(),
     }
 }
 
@@ -0,0 +1,196 @@
+//! Integration tests for work item forms generated from task parameters
+//!
+//! Turtle form annotations, the work item REST service and completion
+//! validation against the generated form's contract.
+
+use knhk_workflow_engine::{
+    api::{models::requests::GetWorkItemRequest, service::WorkItemApiService},
+    data::{TaskDataContract, TaskForm},
+    executor::WorkflowEngine,
+    parser::{FieldAnnotations, TaskParameter, WorkflowParser, WorkflowSpec},
+    testing::chicago_tdd::{TaskBuilder, WorkflowSpecBuilder},
+    StateStore,
+};
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+const CLAIM_TURTLE: &str = r#"
+    @prefix yawl: <http://bitflow.ai/ontology/yawl/v2#> .
+    @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+    <http://example.org/claim> a yawl:Specification ;
+        yawl:specName "Claim" ;
+        yawl:hasInputCondition <http://example.org/claim/start> ;
+        yawl:hasTask <http://example.org/claim/review> ;
+        yawl:hasOutputCondition <http://example.org/claim/end> .
+
+    <http://example.org/claim/review> a yawl:AtomicTask ;
+        yawl:taskName "Review Claim" ;
+        yawl:hasInputParameter [
+            yawl:paramName "amount" ;
+            yawl:paramType "xsd:decimal" ;
+            rdfs:label "Claim amount" ;
+            yawl:displayOrder 1
+        ] ;
+        yawl:hasOutputParameter [
+            yawl:paramName "decision" ;
+            yawl:paramType "xsd:string" ;
+            yawl:required true ;
+            rdfs:label "Decision" ;
+            yawl:allowedValue "approve", "reject" ;
+            yawl:displayOrder 2 ;
+            yawl:widget "radio"
+        ] ;
+        yawl:hasOutputParameter [
+            yawl:paramName "priority" ;
+            yawl:paramType "xsd:integer" ;
+            rdfs:comment "1 is the most urgent" ;
+            yawl:allowedValue 1, 2, 3
+        ] .
+"#;
+
+fn param(name: &str, param_type: &str, required: bool) -> TaskParameter {
+    TaskParameter {
+        name: name.to_string(),
+        param_type: param_type.to_string(),
+        mapping: None,
+        required,
+        annotations: FieldAnnotations::default(),
+    }
+}
+
+/// start → review (human, `amount` in, `decision` out) → reviewed
+fn create_review_workflow() -> WorkflowSpec {
+    let mut decision = param("decision", "xsd:string", true);
+    decision.annotations.allowed_values = vec![json!("approve"), json!("reject")];
+    WorkflowSpecBuilder::new("claim_review")
+        .add_task(
+            TaskBuilder::new("review", "Review Claim")
+                .add_input_parameter(param("amount", "xsd:decimal", true))
+                .add_output_parameter(decision)
+                .add_required_role("adjuster")
+                .build(),
+        )
+        .with_auto_conditions("review", "reviewed")
+        .add_flow("condition:review", "review")
+        .add_flow("review", "condition:reviewed")
+        .build()
+}
+
+#[test]
+fn test_turtle_annotations_generate_form() {
+    let mut parser = WorkflowParser::new().unwrap();
+    let spec = parser.parse_turtle(CLAIM_TURTLE).unwrap();
+    let task = spec
+        .tasks
+        .values()
+        .find(|t| t.name == "Review Claim")
+        .expect("review task");
+
+    let priority = task
+        .output_parameters
+        .iter()
+        .find(|p| p.name == "priority")
+        .unwrap();
+    let mut allowed = priority.annotations.allowed_values.clone();
+    allowed.sort_by_key(|v| v.as_i64());
+    assert_eq!(allowed, vec![json!(1), json!(2), json!(3)]);
+
+    let form = TaskForm::for_task(task).unwrap();
+    let properties = &form.json_schema["properties"];
+    assert_eq!(properties["amount"]["title"], json!("Claim amount"));
+    assert_eq!(properties["amount"]["readOnly"], json!(true));
+    assert_eq!(properties["decision"]["type"], json!("string"));
+    assert_eq!(properties["decision"]["enum"].as_array().unwrap().len(), 2);
+    assert_eq!(properties["priority"]["type"], json!("integer"));
+    assert_eq!(
+        properties["priority"]["description"],
+        json!("1 is the most urgent")
+    );
+    assert_eq!(form.json_schema["required"], json!(["decision"]));
+    assert_eq!(
+        form.ui_schema["ui:order"],
+        json!(["amount", "decision", "priority"])
+    );
+    assert_eq!(form.ui_schema["decision"]["ui:widget"], json!("radio"));
+}
+
+#[test]
+fn test_allowed_values_must_match_parameter_type() {
+    let mut level = param("level", "xsd:integer", false);
+    level.annotations.allowed_values = vec![json!("high")];
+    let spec = WorkflowSpecBuilder::new("bad_enum")
+        .add_task(
+            TaskBuilder::new("rate", "Rate")
+                .add_output_parameter(level)
+                .build(),
+        )
+        .with_auto_conditions("rate", "rated")
+        .add_flow("condition:rate", "rate")
+        .add_flow("rate", "condition:rated")
+        .build();
+
+    assert!(TaskDataContract::for_task(&spec.tasks["rate"]).is_err());
+}
+
+#[tokio::test]
+async fn test_work_item_served_with_form_and_completion_validated() {
+    let temp_dir = TempDir::new().unwrap();
+    let engine = Arc::new(WorkflowEngine::new(
+        StateStore::new(temp_dir.path()).unwrap(),
+    ));
+    let spec = create_review_workflow();
+    engine.register_workflow(spec.clone()).await.unwrap();
+
+    let contract = TaskDataContract::for_task(&spec.tasks["review"]).unwrap();
+    let input = contract.bind_inputs(&json!({"amount": 980.0})).unwrap();
+    let work_item_id = engine
+        .work_item_service()
+        .create_work_item_with_contract(
+            "case-1".to_string(),
+            spec.id,
+            "review".to_string(),
+            input,
+            Some(contract),
+        )
+        .await
+        .unwrap();
+
+    let response = WorkItemApiService::new(engine.clone())
+        .get(GetWorkItemRequest {
+            id: work_item_id.clone(),
+        })
+        .await
+        .unwrap();
+    let form = response.form.expect("typed task has a form");
+    assert_eq!(response.work_item.id, work_item_id);
+    assert_eq!(
+        form.json_schema["properties"]["amount"]["default"],
+        json!(980.0)
+    );
+    assert_eq!(form.editable_fields(), vec!["decision"]);
+
+    let missing = WorkItemApiService::new(engine.clone())
+        .get(GetWorkItemRequest {
+            id: "unknown".to_string(),
+        })
+        .await;
+    assert!(missing.is_err());
+
+    let rejected = engine
+        .work_item_service()
+        .complete(&work_item_id, json!({"decision": "escalate"}))
+        .await;
+    let accepted = engine
+        .work_item_service()
+        .complete(&work_item_id, json!({"decision": "approve"}))
+        .await;
+
+    assert!(
+        rejected.is_err(),
+        "Value outside the enumeration must be rejected"
+    );
+    assert!(accepted.is_ok());
+}